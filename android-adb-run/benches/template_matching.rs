@@ -0,0 +1,57 @@
+// Criterion benchmarks of the patch matching strategies on a synthetic
+// quarter-size screenshot: `cargo bench --bench template_matching`.
+// For real screenshots and patches use `android-adb-run --bench-match <png>`.
+
+use android_adb_run::template_matching::PatchInfo;
+use android_adb_run::template_matching::bench::{BenchSettings, Strategy, run_strategy};
+use criterion::{Criterion, criterion_group, criterion_main};
+use image::{Rgb, RgbImage};
+use std::hint::black_box;
+
+/// Busy enough that most positions fail early, like a real game screen
+fn screenshot() -> RgbImage {
+    RgbImage::from_fn(270, 600, |x, y| {
+        let v = x.wrapping_mul(31) ^ y.wrapping_mul(17);
+        Rgb([v as u8, (v >> 3) as u8, (x + y) as u8])
+    })
+}
+
+fn patches(screenshot: &RgbImage) -> Vec<PatchInfo> {
+    [
+        ("claim", 10, 290, 60, 36),
+        ("menu", 240, 24, 24, 24),
+        ("wave", 136, 335, 130, 40),
+    ]
+    .into_iter()
+    .map(|(label, x, y, width, height)| {
+        let pixels = image::imageops::crop_imm(screenshot, x, y, width, height)
+            .to_image()
+            .into_raw();
+        PatchInfo::new(Some(label.to_string()), x, y, width, height, pixels)
+    })
+    .collect()
+}
+
+fn bench_strategies(c: &mut Criterion) {
+    let screenshot = screenshot();
+    let patches = patches(&screenshot);
+    let settings = BenchSettings {
+        search_margin: 12,
+        ..Default::default()
+    };
+
+    let mut group = c.benchmark_group("patch_matching");
+    group.sample_size(10);
+    for strategy in Strategy::ALL {
+        group.bench_function(strategy.name(), |b| {
+            b.iter(|| {
+                run_strategy(strategy, black_box(&screenshot), &patches, &settings)
+                    .expect("matching failed")
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_strategies);
+criterion_main!(benches);
@@ -1,19 +1,49 @@
+use super::discovery::{device_matches, is_tcp_address};
 use super::error::{AdbError, AdbResult};
 use super::types::AdbClient;
-use super::usb_impl::UsbAdb;
+use super::wireless;
 
 /// AdbBackend is now just a type alias for UsbAdb (direct USB connection)
-pub type AdbBackend = UsbAdb;
+#[cfg(not(feature = "mock"))]
+pub type AdbBackend = super::usb_impl::UsbAdb;
+
+/// Built with `--features mock`: a fake device serving the screenshots in
+/// `ADB_MOCK_DIR`, for running and testing automation without hardware
+#[cfg(feature = "mock")]
+pub type AdbBackend = super::mock::MockAdb;
+
+/// Shown in the GUI settings; switching backends means rebuilding with or without `mock`
+#[cfg(not(feature = "mock"))]
+pub const BACKEND_NAME: &str = "USB";
+
+#[cfg(feature = "mock")]
+pub const BACKEND_NAME: &str = "Mock (ADB_MOCK_DIR)";
 
 impl AdbBackend {
     /// Connect to the first available USB device
     pub async fn connect_first() -> AdbResult<Self> {
+        Self::connect(None).await
+    }
+
+    /// Connect to the device matching `selector` (name, serial or `ip:port`), or the first one
+    pub async fn connect(selector: Option<&str>) -> AdbResult<Self> {
+        if let Some(address) = selector.filter(|s| is_tcp_address(s)) {
+            return wireless::connect_address(address).await;
+        }
         let devices = Self::list_devices().await?;
-        let first = devices
-            .into_iter()
-            .next()
-            .ok_or(AdbError::NoTouchDeviceFound)?;
-        Self::new_with_device(&first.name).await
+        let chosen = match selector {
+            Some(selector) => devices
+                .into_iter()
+                .find(|d| device_matches(d, selector))
+                .ok_or_else(|| AdbError::DeviceNotSelected {
+                    selector: selector.to_string(),
+                })?,
+            None => devices
+                .into_iter()
+                .next()
+                .ok_or(AdbError::NoTouchDeviceFound)?,
+        };
+        Self::new_with_device(chosen.key()).await
     }
 }
 
@@ -0,0 +1,114 @@
+// USB device discovery - enumerates every attached device exposing an ADB interface.
+//
+// adb_client only offers `search_adb_devices()` which stops at the first match,
+// so enumeration is done directly with rusb to support `--device` selection.
+
+use super::error::{AdbError, AdbResult};
+use super::types::Device;
+use rusb::UsbContext;
+use std::time::Duration;
+
+/// ADB interface signature: vendor specific class / ADB subclass / ADB protocol
+const ADB_CLASS: u8 = 0xff;
+const ADB_SUBCLASS: u8 = 0x42;
+const ADB_PROTOCOL: u8 = 0x01;
+
+const STRING_READ_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Parse a `vid:pid` device name (hex) as produced by `list_devices`
+pub fn parse_vid_pid(name: &str) -> Option<(u16, u16)> {
+    let (vid, pid) = name.split_once(':')?;
+    let vid = u16::from_str_radix(vid.trim(), 16).ok()?;
+    let pid = u16::from_str_radix(pid.trim(), 16).ok()?;
+    Some((vid, pid))
+}
+
+/// True for `ip:port` device names (wireless connections)
+pub fn is_tcp_address(name: &str) -> bool {
+    name.parse::<std::net::SocketAddr>().is_ok()
+}
+
+/// True when `selector` refers to `device` by name (`vid:pid`) or USB serial
+pub fn device_matches(device: &Device, selector: &str) -> bool {
+    device.name.eq_ignore_ascii_case(selector) || device.serial.as_deref() == Some(selector)
+}
+
+/// List every USB device exposing an ADB interface (blocking)
+pub fn enumerate_usb_devices() -> AdbResult<Vec<Device>> {
+    let context =
+        rusb::Context::new().map_err(|source| AdbError::UsbEnumerationFailed { source })?;
+    let usb_devices = context
+        .devices()
+        .map_err(|source| AdbError::UsbEnumerationFailed { source })?;
+
+    let mut devices = Vec::new();
+    for usb_device in usb_devices.iter() {
+        let Ok(descriptor) = usb_device.device_descriptor() else {
+            continue;
+        };
+        if !has_adb_interface(&usb_device, &descriptor) {
+            continue;
+        }
+
+        let serial = read_serial(&usb_device, &descriptor);
+        devices.push(Device {
+            name: usb_device_name(&descriptor),
+            transport_id: None,
+            serial,
+        });
+    }
+
+    Ok(devices)
+}
+
+/// Attached ADB device with USB serial `serial` and its `vid:pid` name (blocking)
+pub(super) fn find_usb_device(serial: &str) -> Option<(rusb::Device<rusb::GlobalContext>, String)> {
+    rusb::devices().ok()?.iter().find_map(|usb_device| {
+        let descriptor = usb_device.device_descriptor().ok()?;
+        (has_adb_interface(&usb_device, &descriptor)
+            && read_serial(&usb_device, &descriptor).as_deref() == Some(serial))
+        .then(|| (usb_device, usb_device_name(&descriptor)))
+    })
+}
+
+/// Serial needs an open handle; permission errors simply leave it empty
+pub(super) fn read_serial<T: UsbContext>(
+    usb_device: &rusb::Device<T>,
+    descriptor: &rusb::DeviceDescriptor,
+) -> Option<String> {
+    let handle = usb_device.open().ok()?;
+    let language = handle
+        .read_languages(STRING_READ_TIMEOUT)
+        .ok()?
+        .into_iter()
+        .next()?;
+    handle
+        .read_serial_number_string(language, descriptor, STRING_READ_TIMEOUT)
+        .ok()
+}
+
+/// `vid:pid` in hex, the name USB devices are listed and selected by
+pub(super) fn usb_device_name(descriptor: &rusb::DeviceDescriptor) -> String {
+    format!(
+        "{:04x}:{:04x}",
+        descriptor.vendor_id(),
+        descriptor.product_id()
+    )
+}
+
+pub(super) fn has_adb_interface<T: UsbContext>(
+    device: &rusb::Device<T>,
+    descriptor: &rusb::DeviceDescriptor,
+) -> bool {
+    (0..descriptor.num_configurations()).any(|index| {
+        device.config_descriptor(index).is_ok_and(|config| {
+            config.interfaces().any(|interface| {
+                interface.descriptors().any(|desc| {
+                    desc.class_code() == ADB_CLASS
+                        && desc.sub_class_code() == ADB_SUBCLASS
+                        && desc.protocol_code() == ADB_PROTOCOL
+                })
+            })
+        })
+    })
+}
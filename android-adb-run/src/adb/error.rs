@@ -13,8 +13,44 @@ pub enum AdbError {
         source: adb_client::RustADBError,
     },
 
-    #[error("ADB key not found at {path:?}. Please run 'adb devices' once to generate it.")]
-    KeyNotFound { path: PathBuf },
+    #[error("Failed to enumerate USB devices: {source}")]
+    UsbEnumerationFailed { source: rusb::Error },
+
+    #[error("No device matching '{selector}' found")]
+    DeviceNotSelected { selector: String },
+
+    #[error("Invalid device address, expected <ip>:<port>")]
+    InvalidAddress,
+
+    #[error("Invalid pairing code '{code}', expected the 6 digits shown on the phone")]
+    InvalidPairingCode { code: String },
+
+    #[error("Pairing with {address} failed: {source}")]
+    PairingFailed {
+        address: String,
+        source: adb_client::RustADBError,
+    },
+
+    #[error("Wireless device list {path:?}: {description}")]
+    WirelessRegistryFailed { path: PathBuf, description: String },
+
+    #[error("Mock screenshots {path:?}: {description}")]
+    MockFramesFailed { path: PathBuf, description: String },
+
+    #[error("Session line {line}: {description}")]
+    SessionParseFailed { line: usize, description: String },
+
+    #[error("Session file {path:?}: {source}")]
+    SessionFileFailed {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Could not create the ADB key at {path:?}: {description}")]
+    KeyGenerationFailed { path: PathBuf, description: String },
+
+    #[error("{device} is waiting for USB debugging to be allowed - accept the dialog on the phone")]
+    AuthorizationPending { device: String },
 
     #[error("Failed to determine home directory for ADB key")]
     HomeDirectoryNotFound,
@@ -36,6 +72,13 @@ pub enum AdbError {
         source: adb_client::RustADBError,
     },
 
+    /// The device stopped a slow command; the connection itself is fine
+    #[error("Shell command '{command}' was stopped after {duration:?}")]
+    ShellCommandTimedOut {
+        command: String,
+        duration: std::time::Duration,
+    },
+
     #[error("Operation timed out after {duration:?}: {description}")]
     Timeout {
         duration: std::time::Duration,
@@ -51,15 +94,60 @@ pub enum AdbError {
     #[error("Could not parse screen size from 'wm size' output.")]
     ScreenSizeParseFailed,
 
+    #[error("Could not parse display rotation from 'dumpsys input' output.")]
+    OrientationParseFailed,
+
     #[error("Framebuffer capture failed: {source}")]
     FramebufferCaptureFailed { source: adb_client::RustADBError },
 
     #[error("Failed to convert framebuffer to PNG: {description}")]
     FramebufferToPngFailed { description: String },
 
+    #[error("Failed to decode screenshot: {description}")]
+    FrameDecodeFailed { description: String },
+
+    #[error(
+        "Region {width}x{height} at ({x},{y}) is outside the {screen_width}x{screen_height} screen"
+    )]
+    RegionOutOfBounds {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        screen_width: u32,
+        screen_height: u32,
+    },
+
+    #[error("Invalid gesture: {description}")]
+    InvalidGesture { description: String },
+
     #[error("Failed to convert JPEG to PNG: {description}")]
     JpegToPngFailed { description: String },
 
+    #[error("Installing {path:?} failed: {source}")]
+    InstallFailed {
+        path: PathBuf,
+        source: adb_client::RustADBError,
+    },
+
+    #[error("Transferring {path} failed: {source}")]
+    FileTransferFailed {
+        path: String,
+        source: adb_client::RustADBError,
+    },
+
+    #[error("Local file {path:?}: {source}")]
+    LocalFileFailed {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid package or activity name '{name}'")]
+    InvalidPackageName { name: String },
+
+    #[error("'{command}' failed: {output}")]
+    AppCommandFailed { command: String, output: String },
+
     #[error("Tap coordinates are out of bounds: x={x}, y={y}")]
     TapOutOfBounds { x: u32, y: u32 },
 
@@ -81,30 +169,71 @@ pub enum AdbError {
     ProtocolDesync { description: String },
 }
 
+/// Broad class of an `AdbError`, what callers decide on: reconnect, tell the
+/// user to fix USB access, or just report it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdbErrorKind {
+    /// Device unplugged, offline or left the network
+    Disconnected,
+    /// USB access denied (udev rules / permissions)
+    PermissionDenied,
+    Timeout,
+    /// USB interface claimed by another program (adb server, Android Studio)
+    Busy,
+    /// ADB protocol out of sync (CLSE), the connection must be re-established
+    Protocol,
+    /// The phone has not (yet) accepted this computer's ADB key
+    Unauthorized,
+    Other,
+}
+
 impl AdbError {
-    /// Check if this error indicates a protocol desync that requires reconnection
-    pub fn is_protocol_desync(&self) -> bool {
+    pub fn kind(&self) -> AdbErrorKind {
         match self {
-            AdbError::ShellCommandFailed { source, .. } => {
-                let err_str = source.to_string();
-                err_str.contains("CLSE") || err_str.contains("no write endpoint")
+            AdbError::ProtocolDesync { .. } => AdbErrorKind::Protocol,
+            AdbError::AuthorizationPending { .. } => AdbErrorKind::Unauthorized,
+            AdbError::ChannelClosed | AdbError::DeviceNotSelected { .. } => {
+                AdbErrorKind::Disconnected
             }
-            AdbError::ProtocolDesync { .. } => true,
-            _ => false,
+            AdbError::Timeout { .. }
+            | AdbError::ConnectionTimeout { .. }
+            | AdbError::ConnectionValidationTimeout => AdbErrorKind::Timeout,
+            AdbError::UsbEnumerationFailed { source } => usb_error_kind(source),
+            AdbError::DeviceEnumerationFailed { source }
+            | AdbError::PairingFailed { source, .. }
+            | AdbError::ConnectionFailed { source }
+            | AdbError::ConnectionValidationFailed { source }
+            | AdbError::ShellCommandFailed { source, .. }
+            | AdbError::FramebufferCaptureFailed { source }
+            | AdbError::InstallFailed { source, .. }
+            | AdbError::FileTransferFailed { source, .. } => client_error_kind(source),
+            _ => AdbErrorKind::Other,
         }
     }
 
+    /// The connection is gone or unusable and has to be re-established
+    pub fn needs_reconnect(&self) -> bool {
+        matches!(
+            self.kind(),
+            AdbErrorKind::Disconnected | AdbErrorKind::Protocol | AdbErrorKind::Timeout
+        )
+    }
+
+    /// Check if this error indicates a protocol desync that requires reconnection
+    pub fn is_protocol_desync(&self) -> bool {
+        self.kind() == AdbErrorKind::Protocol
+    }
+
     /// Check if the underlying adb_client error indicates protocol desync
     pub fn from_adb_error_with_desync_check(
         command: String,
         source: adb_client::RustADBError,
     ) -> Self {
-        let err_str = source.to_string();
-        if err_str.contains("CLSE") || err_str.contains("no write endpoint") {
+        if client_error_kind(&source) == AdbErrorKind::Protocol {
             AdbError::ProtocolDesync {
                 description: format!(
                     "Command '{}' failed with protocol error: {}",
-                    command, err_str
+                    command, source
                 ),
             }
         } else {
@@ -114,38 +243,90 @@ impl AdbError {
 
     /// Check if this error indicates USB resource is already in use
     pub fn is_resource_busy(&self) -> bool {
-        let err_str = self.to_string().to_lowercase();
-        err_str.contains("resource busy")
+        self.kind() == AdbErrorKind::Busy
     }
 
     /// Check if this error indicates permission issues
     pub fn is_permission_denied(&self) -> bool {
-        let err_str = self.to_string().to_lowercase();
-        err_str.contains("permission denied")
+        self.kind() == AdbErrorKind::PermissionDenied
     }
 
     /// Check if this error indicates device not found
     pub fn is_device_not_found(&self) -> bool {
-        let err_str = self.to_string().to_lowercase();
-        err_str.contains("no such device")
-            || err_str.contains("device not found")
-            || err_str.contains("not found")
+        self.kind() == AdbErrorKind::Disconnected
     }
 
     /// Get a user-friendly message for common connection errors
     pub fn connection_error_message(&self) -> Option<String> {
-        if self.is_resource_busy() {
-            return Some(
+        match self.kind() {
+            AdbErrorKind::Busy => Some(
                 "USB Already in Use - Close other ADB apps (VS Code, Android Studio, etc.)"
                     .to_string(),
-            );
-        }
-        if self.is_permission_denied() {
-            return Some("Permission Denied - Run: sudo chmod 666 /dev/bus/usb/*/0*".to_string());
+            ),
+            AdbErrorKind::PermissionDenied => {
+                Some("Permission Denied - Run: sudo chmod 666 /dev/bus/usb/*/0*".to_string())
+            }
+            AdbErrorKind::Disconnected => {
+                Some("No Device Found - Reconnect USB cable (unplug and replug)".to_string())
+            }
+            AdbErrorKind::Unauthorized => {
+                Some("Not Authorized - Accept 'Allow USB debugging?' on the phone".to_string())
+            }
+            _ => None,
         }
-        if self.is_device_not_found() {
-            return Some("No Device Found - Reconnect USB cable (unplug and replug)".to_string());
+    }
+}
+
+fn usb_error_kind(error: &rusb::Error) -> AdbErrorKind {
+    match error {
+        rusb::Error::Busy => AdbErrorKind::Busy,
+        rusb::Error::Access => AdbErrorKind::PermissionDenied,
+        rusb::Error::Timeout => AdbErrorKind::Timeout,
+        rusb::Error::NoDevice | rusb::Error::NotFound | rusb::Error::Io | rusb::Error::Pipe => {
+            AdbErrorKind::Disconnected
         }
-        None
+        _ => AdbErrorKind::Other,
+    }
+}
+
+/// adb_client reports USB, TCP and protocol failures as one error type with
+/// the cause in its message, so this is the one place that reads the text
+fn client_error_kind(error: &adb_client::RustADBError) -> AdbErrorKind {
+    message_kind(&error.to_string())
+}
+
+pub(super) fn message_kind(message: &str) -> AdbErrorKind {
+    let message = message.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|n| message.contains(n));
+
+    if has(&["clse", "no write endpoint", "protocol desync"]) {
+        AdbErrorKind::Protocol
+    } else if has(&["resource busy"]) {
+        AdbErrorKind::Busy
+    } else if has(&["permission denied", "access denied"]) {
+        AdbErrorKind::PermissionDenied
+    } else if has(&["unauthorized", "authentication"]) {
+        AdbErrorKind::Unauthorized
+    } else if has(&["timed out", "timeout"]) {
+        AdbErrorKind::Timeout
+    } else if has(&[
+        "device offline",
+        "not found",
+        "no such device",
+        "no devices",
+        "connection refused",
+        "broken pipe",
+        "connection reset",
+        "no route to host",
+        "network is unreachable",
+        "host is down",
+        "closed",
+        "not connected",
+        "usb error",
+        "input/output error",
+    ]) {
+        AdbErrorKind::Disconnected
+    } else {
+        AdbErrorKind::Other
     }
 }
@@ -0,0 +1,225 @@
+// Multi-touch gestures - pinch and multi-finger swipes replayed with `sendevent`.
+//
+// `input` only knows single-pointer gestures, so each frame of a multi-touch
+// gesture is written straight to the touchscreen's event device using the
+// multi-touch slot protocol (type B), all in one `sh -c` so the fingers move
+// together.
+use super::error::{AdbError, AdbResult};
+
+/// Waypoints of one finger in screen pixels; it moves through them in order
+pub type TouchPath = Vec<(u32, u32)>;
+
+/// Most fingers a gesture may use (touch controllers usually track 10 slots)
+pub const MAX_FINGERS: usize = 10;
+
+/// Time between gesture frames; each `sendevent` is its own process, so frames
+/// can't usefully be much shorter than this
+const FRAME_MS: u32 = 50;
+const MAX_FRAMES: u32 = 20;
+
+// linux/input-event-codes.h
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_ABS: u16 = 0x03;
+const SYN_REPORT: u16 = 0x00;
+const BTN_TOUCH: u16 = 0x14a;
+const ABS_MT_SLOT: u16 = 0x2f;
+const ABS_MT_POSITION_X: u16 = 0x35;
+const ABS_MT_POSITION_Y: u16 = 0x36;
+const ABS_MT_TRACKING_ID: u16 = 0x39;
+
+/// Touchscreen event device and the raw range of its position axes
+#[derive(Debug, Clone, PartialEq)]
+pub struct TouchAxes {
+    pub device: String,
+    pub max_x: u32,
+    pub max_y: u32,
+}
+
+impl TouchAxes {
+    /// Raw axis value for a screen pixel (touch panels often report a finer grid)
+    fn scale(&self, (x, y): (u32, u32), (screen_x, screen_y): (u32, u32)) -> (u32, u32) {
+        let scale = |value: u32, max: u32, screen: u32| {
+            (u64::from(value) * u64::from(max + 1) / u64::from(screen.max(1))) as u32
+        };
+        (
+            scale(x, self.max_x, screen_x),
+            scale(y, self.max_y, screen_y),
+        )
+    }
+}
+
+/// Find the multi-touch device in `getevent -p` output and read its
+/// ABS_MT_POSITION_X / _Y maxima
+pub fn parse_touch_axes(output: &str) -> Option<TouchAxes> {
+    let mut device: Option<String> = None;
+    let (mut max_x, mut max_y) = (None, None);
+
+    for line in output.lines() {
+        if line.starts_with("add device") {
+            if let (Some(device), Some(max_x), Some(max_y)) = (&device, max_x, max_y) {
+                return Some(TouchAxes {
+                    device: device.clone(),
+                    max_x,
+                    max_y,
+                });
+            }
+            device = line
+                .find("/dev/input/event")
+                .map(|start| line[start..].trim().to_string());
+            (max_x, max_y) = (None, None);
+            continue;
+        }
+        // "    ABS (0003): 0035  : value 0, min 0, max 1079, fuzz 0, flat 0, resolution 0"
+        let Some((code, rest)) = line.split_once("  :") else {
+            continue;
+        };
+        let code = code.rsplit(' ').next().unwrap_or_default();
+        let max = rest
+            .split(',')
+            .find_map(|field| field.trim().strip_prefix("max "))
+            .and_then(|value| value.trim().parse::<u32>().ok());
+        match code {
+            "0035" => max_x = max,
+            "0036" => max_y = max,
+            _ => {}
+        }
+    }
+
+    Some(TouchAxes {
+        device: device?,
+        max_x: max_x?,
+        max_y: max_y?,
+    })
+}
+
+/// Two fingers on a horizontal line through `(cx, cy)`, moving from
+/// `start_distance` to `end_distance` apart (growing = zoom in)
+pub fn pinch_paths(cx: u32, cy: u32, start_distance: u32, end_distance: u32) -> [TouchPath; 2] {
+    let (start, end) = (start_distance / 2, end_distance / 2);
+    [
+        vec![(cx.saturating_sub(start), cy), (cx.saturating_sub(end), cy)],
+        vec![(cx.saturating_add(start), cy), (cx.saturating_add(end), cy)],
+    ]
+}
+
+/// Point `t` (0.0 - 1.0) of the way along `path`, spreading time evenly over its legs
+fn point_at(path: &[(u32, u32)], t: f32) -> (u32, u32) {
+    let legs = path.len().saturating_sub(1);
+    if legs == 0 {
+        return path[0];
+    }
+    let position = t.clamp(0.0, 1.0) * legs as f32;
+    let leg = (position.floor() as usize).min(legs - 1);
+    let local = position - leg as f32;
+    let ((x1, y1), (x2, y2)) = (path[leg], path[leg + 1]);
+    let lerp = |a: u32, b: u32| (a as f32 + (b as f32 - a as f32) * local).round() as u32;
+    (lerp(x1, x2), lerp(y1, y2))
+}
+
+/// Check a gesture fits the screen before sending anything
+pub fn validate_paths(paths: &[TouchPath], (screen_x, screen_y): (u32, u32)) -> AdbResult<()> {
+    if paths.is_empty() || paths.len() > MAX_FINGERS {
+        return Err(AdbError::InvalidGesture {
+            description: format!("need 1-{} fingers, got {}", MAX_FINGERS, paths.len()),
+        });
+    }
+    if paths.iter().any(Vec::is_empty) {
+        return Err(AdbError::InvalidGesture {
+            description: "every finger needs at least one point".into(),
+        });
+    }
+    match paths
+        .iter()
+        .flatten()
+        .find(|(x, y)| *x > screen_x || *y > screen_y)
+    {
+        Some(&(x, y)) => Err(AdbError::TapOutOfBounds { x, y }),
+        None => Ok(()),
+    }
+}
+
+/// Where every finger is at one step of a gesture, and how long to wait before the next
+struct Frame {
+    points: Vec<(u32, u32)>,
+    pause_ms: u32,
+}
+
+/// Steps that move every finger along its path over about `duration_ms`
+fn move_frames(paths: &[TouchPath], duration_ms: u32) -> Vec<Frame> {
+    let frames = (duration_ms / FRAME_MS).clamp(1, MAX_FRAMES);
+    (0..=frames)
+        .map(|frame| {
+            let t = frame as f32 / frames as f32;
+            Frame {
+                points: paths.iter().map(|path| point_at(path, t)).collect(),
+                pause_ms: if frame < frames {
+                    duration_ms / frames
+                } else {
+                    0
+                },
+            }
+        })
+        .collect()
+}
+
+/// Shell script that presses on the first frame, plays the rest and lifts every finger
+fn frames_script(axes: &TouchAxes, screen: (u32, u32), frames: &[Frame]) -> String {
+    // `e` keeps the command line short enough for older adbd versions
+    let mut script = vec![format!("e() {{ sendevent {} $1 $2 $3; }}", axes.device)];
+    let event = |kind: u16, code: u16, value: i64| format!("e {} {} {}", kind, code, value);
+    let fingers = frames.first().map_or(0, |frame| frame.points.len());
+
+    for (index, frame) in frames.iter().enumerate() {
+        for (slot, &point) in frame.points.iter().enumerate() {
+            let (x, y) = axes.scale(point, screen);
+            script.push(event(EV_ABS, ABS_MT_SLOT, slot as i64));
+            if index == 0 {
+                script.push(event(EV_ABS, ABS_MT_TRACKING_ID, slot as i64 + 1));
+            }
+            script.push(event(EV_ABS, ABS_MT_POSITION_X, i64::from(x)));
+            script.push(event(EV_ABS, ABS_MT_POSITION_Y, i64::from(y)));
+        }
+        if index == 0 {
+            script.push(event(EV_KEY, BTN_TOUCH, 1));
+        }
+        script.push(event(EV_SYN, SYN_REPORT, 0));
+        if frame.pause_ms > 0 {
+            script.push(format!("sleep {:.3}", frame.pause_ms as f32 / 1000.0));
+        }
+    }
+
+    // Lift every finger
+    for slot in 0..fingers {
+        script.push(event(EV_ABS, ABS_MT_SLOT, slot as i64));
+        script.push(event(EV_ABS, ABS_MT_TRACKING_ID, -1));
+    }
+    script.push(event(EV_KEY, BTN_TOUCH, 0));
+    script.push(event(EV_SYN, SYN_REPORT, 0));
+
+    script.join("; ")
+}
+
+/// Shell script that plays `paths` on the touch device over about `duration_ms`
+pub fn sendevent_script(
+    axes: &TouchAxes,
+    screen: (u32, u32),
+    paths: &[TouchPath],
+    duration_ms: u32,
+) -> String {
+    frames_script(axes, screen, &move_frames(paths, duration_ms))
+}
+
+/// Shell script that presses at `from`, holds still for `hold_ms` (long enough
+/// for a game to pick the item up), moves to `to` over `move_ms` and releases
+pub fn drag_script(
+    axes: &TouchAxes,
+    screen: (u32, u32),
+    from: (u32, u32),
+    to: (u32, u32),
+    (hold_ms, move_ms): (u32, u32),
+) -> String {
+    let mut frames = move_frames(&[vec![from, to]], move_ms);
+    frames[0].pause_ms += hold_ms;
+    frames_script(axes, screen, &frames)
+}
@@ -0,0 +1,165 @@
+// USB hot-plug notifications - libusb tells us when an ADB device is plugged
+// in or pulled out, so the GUI and the automation do not wait for the next poll.
+//
+// Platforms without libusb hotplug support (Windows) get no events; callers
+// keep their polling intervals as the fallback.
+
+use super::discovery::{has_adb_interface, read_serial, usb_device_name};
+use rusb::{Hotplug, HotplugBuilder, UsbContext};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, Receiver, Sender, error::RecvError};
+
+/// A USB device came or went, named by `Device::key` like `list_devices`: its
+/// USB serial when it could be read, else `vid:pid`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotplugEvent {
+    DeviceAttached(String),
+    DeviceDetached(String),
+}
+
+static EVENTS: OnceLock<Option<Sender<HotplugEvent>>> = OnceLock::new();
+
+/// Receive hot-plug events, starting the listener on first use; `None` when
+/// this platform or build has no hotplug support
+pub fn subscribe() -> Option<Receiver<HotplugEvent>> {
+    EVENTS
+        .get_or_init(start_listener)
+        .as_ref()
+        .map(Sender::subscribe)
+}
+
+/// Next event; never returns without a receiver, so it can sit in a `select!`
+pub async fn next_event(events: Option<&mut Receiver<HotplugEvent>>) -> HotplugEvent {
+    let Some(events) = events else {
+        return std::future::pending().await;
+    };
+    loop {
+        match events.recv().await {
+            Ok(event) => return event,
+            Err(RecvError::Lagged(missed)) => {
+                log::debug!("USB hotplug: {} events missed", missed);
+            }
+            Err(RecvError::Closed) => return std::future::pending().await,
+        }
+    }
+}
+
+/// Wait up to `timeout` for a device to be plugged in; false when none was
+pub async fn wait_for_attach(
+    events: Option<&mut Receiver<HotplugEvent>>,
+    timeout: Duration,
+) -> bool {
+    let Some(events) = events else {
+        tokio::time::sleep(timeout).await;
+        return false;
+    };
+    tokio::time::timeout(timeout, async {
+        while !matches!(
+            next_event(Some(&mut *events)).await,
+            HotplugEvent::DeviceAttached(_)
+        ) {}
+    })
+    .await
+    .is_ok()
+}
+
+struct Listener {
+    tx: Sender<HotplugEvent>,
+    // Key of every attached device by bus and address, as a detached device
+    // can no longer be opened to read its serial
+    keys: Arc<Mutex<HashMap<(u8, u8), String>>>,
+    // Devices already attached at registration are only recorded, not announced
+    enumerating: Arc<AtomicBool>,
+}
+
+impl<T: UsbContext + 'static> Hotplug<T> for Listener {
+    fn device_arrived(&mut self, device: rusb::Device<T>) {
+        let Ok(descriptor) = device.device_descriptor() else {
+            return;
+        };
+        if !has_adb_interface(&device, &descriptor) {
+            return;
+        }
+        // Reading the serial is device I/O, which libusb callbacks must not do
+        let tx = self.tx.clone();
+        let keys = self.keys.clone();
+        let announce = !self.enumerating.load(Ordering::Relaxed);
+        let spawned = std::thread::Builder::new()
+            .name("usb-hotplug-serial".into())
+            .spawn(move || {
+                let key = read_serial(&device, &descriptor)
+                    .unwrap_or_else(|| usb_device_name(&descriptor));
+                keys.lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .insert((device.bus_number(), device.address()), key.clone());
+                if announce {
+                    log::info!("🔌 USB device attached: {}", key);
+                    let _ = tx.send(HotplugEvent::DeviceAttached(key));
+                }
+            });
+        if let Err(e) = spawned {
+            log::warn!("⚠️ USB device attached but not reported: {}", e);
+        }
+    }
+
+    fn device_left(&mut self, device: rusb::Device<T>) {
+        let Ok(descriptor) = device.device_descriptor() else {
+            return;
+        };
+        let key = self
+            .keys
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&(device.bus_number(), device.address()))
+            .unwrap_or_else(|| usb_device_name(&descriptor));
+        log::debug!("USB device detached: {}", key);
+        let _ = self.tx.send(HotplugEvent::DeviceDetached(key));
+    }
+}
+
+fn start_listener() -> Option<Sender<HotplugEvent>> {
+    // The mock backend has no USB devices to watch
+    if cfg!(feature = "mock") || !rusb::has_hotplug() {
+        log::info!("USB hotplug unavailable, polling for devices");
+        return None;
+    }
+    let context = rusb::Context::new()
+        .map_err(|e| log::warn!("⚠️ USB hotplug disabled: {}", e))
+        .ok()?;
+    let (tx, _) = broadcast::channel(16);
+    // Enumerated so devices plugged in before the listener started have a key
+    // to be detached by; their callbacks run inside `register`
+    let enumerating = Arc::new(AtomicBool::new(true));
+    let registration = HotplugBuilder::new()
+        .enumerate(true)
+        .register(
+            &context,
+            Box::new(Listener {
+                tx: tx.clone(),
+                keys: Arc::default(),
+                enumerating: enumerating.clone(),
+            }),
+        )
+        .map_err(|e| log::warn!("⚠️ USB hotplug disabled: {}", e))
+        .ok()?;
+    enumerating.store(false, Ordering::Relaxed);
+
+    std::thread::Builder::new()
+        .name("usb-hotplug".into())
+        .spawn(move || {
+            // Dropping the registration would unregister the callback
+            let _registration = registration;
+            loop {
+                if let Err(e) = context.handle_events(None) {
+                    log::warn!("⚠️ USB hotplug listener stopped: {}", e);
+                    break;
+                }
+            }
+        })
+        .map_err(|e| log::warn!("⚠️ USB hotplug disabled: {}", e))
+        .ok()?;
+    Some(tx)
+}
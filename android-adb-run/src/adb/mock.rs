@@ -0,0 +1,558 @@
+// Mock device for tests and runs without hardware: serves canned screenshots
+// (in memory, or replayed from a folder of PNGs), records input instead of
+// sending it, and can simulate disconnects and slow captures through its
+// `MockControl`.
+//
+// Built with `--features mock`, `AdbBackend` is this type and every connect
+// replays the screenshots in `ADB_MOCK_DIR`.
+use super::error::{AdbError, AdbResult};
+use super::gesture::TouchPath;
+use super::replay::{REPLAY_LOOP_ENV, ReplayFolder, decode_png, png_files};
+use super::transfer::{ProgressCallback, ProgressSink};
+use super::types::{
+    AdbClient, ConnectionType, DEFAULT_TOUCH_TIMEOUT_SECS, Device, DeviceDetails, Orientation,
+    RgbFrame, ShellOutput, TouchActivityMonitor, TouchActivityState,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Directory of `*.png` screenshots the mock device serves, in file name order
+pub const MOCK_DIR_ENV: &str = "ADB_MOCK_DIR";
+
+/// Input the mock device received, in order
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockAction {
+    Tap {
+        x: u32,
+        y: u32,
+    },
+    Swipe {
+        x1: u32,
+        y1: u32,
+        x2: u32,
+        y2: u32,
+        duration: Option<u32>,
+    },
+    LongPress {
+        x: u32,
+        y: u32,
+        duration_ms: u32,
+    },
+    Drag {
+        from: (u32, u32),
+        to: (u32, u32),
+    },
+    MultiSwipe {
+        paths: Vec<TouchPath>,
+        duration_ms: u32,
+    },
+    Text(String),
+    Key(u32),
+    Install(PathBuf),
+    Uninstall(String),
+    StartActivity(String),
+    ForceStop(String),
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    frames: Vec<RgbFrame>,
+    next_frame: usize,
+    /// Served instead of `frames` when set
+    replay: Option<ReplayFolder>,
+    captures: usize,
+    actions: Vec<MockAction>,
+    disconnected: bool,
+    capture_delay: Duration,
+    foreground: Option<String>,
+    orientation: Orientation,
+    clipboard: String,
+    /// Device files by path, as `push` left them or `set_file` put them
+    files: HashMap<String, Vec<u8>>,
+    /// Output per shell command line; anything else prints nothing
+    shell_output: HashMap<String, String>,
+}
+
+/// Handle for steering a `MockAdb` from a test while automation owns the device
+#[derive(Debug, Clone, Default)]
+pub struct MockControl(Arc<Mutex<MockState>>);
+
+impl MockControl {
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.0.lock().unwrap()
+    }
+
+    /// Input received so far
+    pub fn actions(&self) -> Vec<MockAction> {
+        self.state().actions.clone()
+    }
+
+    /// Input received so far, clearing the record
+    pub fn take_actions(&self) -> Vec<MockAction> {
+        std::mem::take(&mut self.state().actions)
+    }
+
+    /// Screenshots served so far
+    pub fn captures(&self) -> usize {
+        self.state().captures
+    }
+
+    /// While disconnected every call fails the way an unplugged device does
+    pub fn set_disconnected(&self, disconnected: bool) {
+        self.state().disconnected = disconnected;
+    }
+
+    /// Wait this long before returning each screenshot
+    pub fn set_capture_delay(&self, delay: Duration) {
+        self.state().capture_delay = delay;
+    }
+
+    /// Package `is_app_foreground` reports; `start_activity` also sets it
+    pub fn set_foreground(&self, package: Option<&str>) {
+        self.state().foreground = package.map(str::to_string);
+    }
+
+    /// Rotation `orientation` reports, e.g. to test remapped taps
+    pub fn set_orientation(&self, orientation: Orientation) {
+        self.state().orientation = orientation;
+    }
+
+    /// Text on the mock clipboard, as `set_clipboard` leaves it
+    pub fn clipboard(&self) -> String {
+        self.state().clipboard.clone()
+    }
+
+    /// Contents of device file `remote`
+    pub fn file(&self, remote: &str) -> Option<Vec<u8>> {
+        self.state().files.get(remote).cloned()
+    }
+
+    /// Put a file on the mock device for `pull`
+    pub fn set_file(&self, remote: &str, contents: &[u8]) {
+        self.state()
+            .files
+            .insert(remote.to_string(), contents.to_vec());
+    }
+
+    /// What `shell` prints for `command` (arguments joined by spaces),
+    /// e.g. `dumpsys battery` for telemetry
+    pub fn set_shell_output(&self, command: &str, output: &str) {
+        self.state()
+            .shell_output
+            .insert(command.to_string(), output.to_string());
+    }
+
+    /// Serve `frames` from the next capture on
+    pub fn set_frames(&self, frames: Vec<RgbFrame>) {
+        let mut state = self.state();
+        state.frames = frames;
+        state.next_frame = 0;
+        state.replay = None;
+    }
+
+    /// Replayed screenshots served so far and the total, when replaying a folder
+    pub fn replay_position(&self) -> Option<(usize, usize)> {
+        self.state().replay.as_ref().map(ReplayFolder::position)
+    }
+
+    /// Serve the first screenshot again on the next capture
+    pub fn rewind(&self) {
+        let mut state = self.state();
+        state.next_frame = 0;
+        if let Some(replay) = state.replay.as_mut() {
+            replay.rewind();
+        }
+    }
+
+    fn check_connected(&self) -> AdbResult<()> {
+        if self.state().disconnected {
+            Err(AdbError::ChannelClosed)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn record(&self, action: MockAction) -> AdbResult<()> {
+        self.check_connected()?;
+        self.state().actions.push(action);
+        Ok(())
+    }
+
+    async fn next_frame(&self) -> AdbResult<RgbFrame> {
+        let delay = self.state().capture_delay;
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        self.check_connected()?;
+        let replay_file = {
+            let mut state = self.state();
+            state
+                .replay
+                .as_mut()
+                .map(|replay| replay.advance().to_path_buf())
+        };
+        if let Some(path) = replay_file {
+            let frame = tokio::task::spawn_blocking(move || decode_png(&path)).await??;
+            self.state().captures += 1;
+            return Ok(frame);
+        }
+
+        let mut state = self.state();
+        if state.frames.is_empty() {
+            return Err(AdbError::FrameDecodeFailed {
+                description: "mock device has no screenshots".into(),
+            });
+        }
+        let index = state.next_frame % state.frames.len();
+        state.next_frame = index + 1;
+        state.captures += 1;
+        Ok(state.frames[index].clone())
+    }
+}
+
+/// Fake device serving canned screenshots (cycling through them) and recording input
+pub struct MockAdb {
+    device: Device,
+    screen_x: u32,
+    screen_y: u32,
+    control: MockControl,
+    touch_monitor: TouchActivityMonitor,
+}
+
+impl MockAdb {
+    fn with_screen(name: &str, (screen_x, screen_y): (u32, u32)) -> Self {
+        Self {
+            device: Device {
+                name: name.to_string(),
+                transport_id: Some("mock".into()),
+                serial: None,
+            },
+            screen_x,
+            screen_y,
+            control: MockControl::default(),
+            touch_monitor: Arc::new(RwLock::new(TouchActivityState::new(
+                DEFAULT_TOUCH_TIMEOUT_SECS,
+            ))),
+        }
+    }
+
+    /// Device named `name` serving `frames`; the first frame sets the screen size
+    pub fn from_frames(name: &str, frames: Vec<RgbFrame>) -> Self {
+        let screen = frames
+            .first()
+            .map(|frame| (frame.width, frame.height))
+            .unwrap_or((1080, 2400));
+        let mock = Self::with_screen(name, screen);
+        mock.control.set_frames(frames);
+        mock
+    }
+
+    /// Device named `name` replaying the `*.png` files in `dir` one per capture,
+    /// in file name order; the first file sets the screen size. Holds the last
+    /// screenshot at the end unless `looped`.
+    pub fn replay(name: &str, dir: impl Into<PathBuf>, looped: bool) -> AdbResult<Self> {
+        let replay = ReplayFolder::open(dir, looped)?;
+        let first = decode_png(replay.first())?;
+        let mock = Self::with_screen(name, (first.width, first.height));
+        mock.control.state().replay = Some(replay);
+        Ok(mock)
+    }
+
+    /// Handle for steering this device and reading back the input it received
+    pub fn control(&self) -> MockControl {
+        self.control.clone()
+    }
+
+    /// Any `ip:port` connects to the screenshots in `ADB_MOCK_DIR`
+    pub async fn connect_tcp(address: &str) -> AdbResult<Self> {
+        Self::new_with_device(address).await
+    }
+
+    pub async fn enable_tcpip(&self, _port: u16) -> AdbResult<()> {
+        self.control.check_connected()
+    }
+
+    pub async fn details(&self) -> DeviceDetails {
+        DeviceDetails {
+            name: self.device.name.clone(),
+            serial: None,
+            model: Some("Mock".into()),
+            android_version: None,
+            screen_width: self.screen_x,
+            screen_height: self.screen_y,
+            connection: ConnectionType::Usb,
+        }
+    }
+
+    /// Output set with `MockControl::set_shell_output`, empty otherwise
+    pub async fn shell(&self, args: &[&str]) -> AdbResult<String> {
+        self.control.check_connected()?;
+        Ok(self
+            .control
+            .state()
+            .shell_output
+            .get(&args.join(" "))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    pub async fn utc_offset(&self) -> AdbResult<Option<time::UtcOffset>> {
+        Ok(super::usb_impl::parse_utc_offset(
+            &self.shell(&["date", "+%z"]).await?,
+        ))
+    }
+
+    pub async fn set_touch_timeout(&self, timeout: Duration) {
+        self.touch_monitor.write().await.timeout_duration = timeout;
+    }
+}
+
+/// Decode the `*.png` files in `dir` up front, sorted by file name
+pub fn load_frames(dir: &Path) -> AdbResult<Vec<RgbFrame>> {
+    png_files(dir)?
+        .iter()
+        .map(|path| decode_png(path))
+        .collect()
+}
+
+fn mock_dir() -> AdbResult<PathBuf> {
+    std::env::var_os(MOCK_DIR_ENV)
+        .map(PathBuf::from)
+        .ok_or_else(|| AdbError::MockFramesFailed {
+            path: PathBuf::new(),
+            description: format!("set {} to a directory of screenshots", MOCK_DIR_ENV),
+        })
+}
+
+impl AdbClient for MockAdb {
+    async fn list_devices() -> AdbResult<Vec<Device>> {
+        Ok(mock_dir()
+            .map(|dir| Device {
+                name: format!(
+                    "replay:{}",
+                    dir.file_name().unwrap_or_default().to_string_lossy()
+                ),
+                transport_id: Some("mock".into()),
+                serial: None,
+            })
+            .into_iter()
+            .collect())
+    }
+
+    async fn new_with_device(device_name: &str) -> AdbResult<Self> {
+        let looped = std::env::var(REPLAY_LOOP_ENV).is_ok_and(|value| value == "1");
+        Self::replay(device_name, mock_dir()?, looped)
+    }
+
+    async fn screen_capture_bytes(&self) -> AdbResult<Vec<u8>> {
+        self.control.next_frame().await?.to_png()
+    }
+
+    async fn screen_capture_rgb(&self) -> AdbResult<RgbFrame> {
+        self.control.next_frame().await
+    }
+
+    async fn tap(&self, x: u32, y: u32) -> AdbResult<()> {
+        if x >= self.screen_x || y >= self.screen_y {
+            return Err(AdbError::TapOutOfBounds { x, y });
+        }
+        self.control.record(MockAction::Tap { x, y })
+    }
+
+    async fn swipe(
+        &self,
+        x1: u32,
+        y1: u32,
+        x2: u32,
+        y2: u32,
+        duration: Option<u32>,
+    ) -> AdbResult<()> {
+        self.control.record(MockAction::Swipe {
+            x1,
+            y1,
+            x2,
+            y2,
+            duration,
+        })
+    }
+
+    async fn send_text(&self, text: &str) -> AdbResult<()> {
+        self.control.record(MockAction::Text(text.to_string()))
+    }
+
+    async fn key_event(&self, keycode: u32) -> AdbResult<()> {
+        self.control.record(MockAction::Key(keycode))
+    }
+
+    async fn long_press(&self, x: u32, y: u32, duration_ms: u32) -> AdbResult<()> {
+        self.control
+            .record(MockAction::LongPress { x, y, duration_ms })
+    }
+
+    async fn multi_swipe(&self, paths: &[TouchPath], duration_ms: u32) -> AdbResult<()> {
+        self.control.record(MockAction::MultiSwipe {
+            paths: paths.to_vec(),
+            duration_ms,
+        })
+    }
+
+    async fn drag(
+        &self,
+        from: (u32, u32),
+        to: (u32, u32),
+        _hold_ms: u32,
+        _move_ms: u32,
+    ) -> AdbResult<()> {
+        self.control.record(MockAction::Drag { from, to })
+    }
+
+    async fn get_device_ip(&self) -> AdbResult<String> {
+        self.control.check_connected()?;
+        Ok("127.0.0.1".into())
+    }
+
+    async fn install_apk(&self, apk_path: &Path) -> AdbResult<()> {
+        self.control
+            .record(MockAction::Install(apk_path.to_path_buf()))
+    }
+
+    async fn push(
+        &self,
+        local: &Path,
+        remote: &str,
+        progress: Option<ProgressCallback>,
+    ) -> AdbResult<()> {
+        self.control.check_connected()?;
+        let contents = std::fs::read(local).map_err(|source| AdbError::LocalFileFailed {
+            path: local.to_path_buf(),
+            source,
+        })?;
+        ProgressSink::new(progress, Some(contents.len() as u64)).advance(contents.len());
+        self.control.set_file(remote, &contents);
+        Ok(())
+    }
+
+    async fn pull(
+        &self,
+        remote: &str,
+        local: &Path,
+        progress: Option<ProgressCallback>,
+    ) -> AdbResult<()> {
+        self.control.check_connected()?;
+        let contents = self
+            .control
+            .file(remote)
+            .ok_or_else(|| AdbError::AppCommandFailed {
+                command: format!("pull {}", remote),
+                output: "No such file or directory".into(),
+            })?;
+        std::fs::write(local, &contents).map_err(|source| AdbError::LocalFileFailed {
+            path: local.to_path_buf(),
+            source,
+        })?;
+        ProgressSink::new(progress, Some(contents.len() as u64)).advance(contents.len());
+        Ok(())
+    }
+
+    async fn uninstall(&self, package: &str) -> AdbResult<()> {
+        self.control
+            .record(MockAction::Uninstall(package.to_string()))
+    }
+
+    async fn start_activity(&self, component: &str) -> AdbResult<()> {
+        self.control
+            .record(MockAction::StartActivity(component.to_string()))?;
+        let package = component.split('/').next().unwrap_or(component);
+        self.control.set_foreground(Some(package));
+        Ok(())
+    }
+
+    async fn force_stop(&self, package: &str) -> AdbResult<()> {
+        self.control
+            .record(MockAction::ForceStop(package.to_string()))?;
+        let mut state = self.control.state();
+        if state.foreground.as_deref() == Some(package) {
+            state.foreground = None;
+        }
+        Ok(())
+    }
+
+    async fn is_app_foreground(&self, package: &str) -> AdbResult<bool> {
+        self.control.check_connected()?;
+        Ok(self.control.state().foreground.as_deref() == Some(package))
+    }
+
+    async fn orientation(&self) -> AdbResult<Orientation> {
+        self.control.check_connected()?;
+        Ok(self.control.state().orientation)
+    }
+
+    async fn run_shell(&self, args: &[&str], _timeout: Duration) -> AdbResult<ShellOutput> {
+        Ok(ShellOutput {
+            stdout: self.shell(args).await?,
+            ..ShellOutput::default()
+        })
+    }
+
+    async fn get_clipboard(&self) -> AdbResult<String> {
+        self.control.check_connected()?;
+        Ok(self.control.clipboard())
+    }
+
+    async fn set_clipboard(&self, text: &str) -> AdbResult<()> {
+        self.control.check_connected()?;
+        self.control.state().clipboard = text.to_string();
+        Ok(())
+    }
+
+    async fn is_human_touching(&self) -> bool {
+        self.touch_monitor.read().await.is_human_active()
+    }
+
+    async fn get_touch_timeout_remaining(&self) -> Option<u64> {
+        self.touch_monitor.read().await.get_remaining_seconds()
+    }
+
+    async fn clear_touch_activity(&self) -> AdbResult<()> {
+        self.touch_monitor.write().await.clear_touch_activity();
+        Ok(())
+    }
+
+    async fn register_touch_activity(&self) -> AdbResult<()> {
+        self.touch_monitor.write().await.mark_touch_activity();
+        Ok(())
+    }
+
+    async fn start_touch_monitoring(&self) -> AdbResult<()> {
+        self.touch_monitor.write().await.is_monitoring = true;
+        Ok(())
+    }
+
+    async fn stop_touch_monitoring(&self) -> AdbResult<()> {
+        self.touch_monitor.write().await.is_monitoring = false;
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> AdbResult<()> {
+        self.stop_touch_monitoring().await
+    }
+
+    fn screen_dimensions(&self) -> (u32, u32) {
+        (self.screen_x, self.screen_y)
+    }
+
+    fn device_name(&self) -> &str {
+        &self.device.name
+    }
+
+    fn device_key(&self) -> &str {
+        self.device.key()
+    }
+
+    fn transport_id(&self) -> Option<u32> {
+        None
+    }
+}
@@ -3,15 +3,42 @@
 // using direct USB connection (no daemon required).
 
 pub mod backend;
+pub mod discovery;
 pub mod error;
+pub mod gesture;
+pub mod hotplug;
+pub mod mock;
+pub mod notifications;
+pub mod pool;
+pub mod replay;
+pub mod session;
+pub mod telemetry;
+pub mod transfer;
 pub mod types;
+pub mod ui_events;
+pub mod ui_hierarchy;
 pub mod usb_impl;
+pub mod wireless;
 
 #[cfg(test)]
 mod tests;
 
 // Re-export the main types and functions for easy access
-pub use backend::AdbBackend;
-pub use error::{AdbError, AdbResult};
-pub use types::{AdbClient, Device, ImageCapture};
+pub use backend::{AdbBackend, BACKEND_NAME};
+pub use error::{AdbError, AdbErrorKind, AdbResult};
+pub use gesture::TouchPath;
+pub use hotplug::HotplugEvent;
+pub use mock::{MockAction, MockAdb, MockControl};
+pub use notifications::DeviceNotification;
+pub use pool::{DevicePool, SharedBackend};
+pub use session::{Session, SessionAction, SessionEvent, SessionRecorder};
+pub use telemetry::DeviceTelemetry;
+pub use transfer::{ProgressCallback, TransferProgress};
+pub use types::{
+    AdbClient, ConnectionType, Device, DeviceDetails, ImageCapture, Orientation, RgbFrame,
+    ShellOutput,
+};
+pub use ui_events::UiEvent;
+pub use ui_hierarchy::{UiNode, UiSelector};
 pub use usb_impl::UsbAdb;
+pub use wireless::{KnownWirelessDevice, WirelessRegistry};
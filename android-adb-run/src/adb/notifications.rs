@@ -0,0 +1,120 @@
+// Android notifications read from `dumpsys notification`, so automation can
+// react to the game's own "energy refilled" or "reward ready" messages
+use super::backend::AdbBackend;
+use super::error::AdbResult;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// One posted notification; title and text stay `None` when the device redacts them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeviceNotification {
+    /// Android notification key, `user|package|id|tag|uid`
+    pub key: String,
+    pub package: String,
+    pub title: Option<String>,
+    pub text: Option<String>,
+}
+
+impl DeviceNotification {
+    /// Title and text, for logs and rule matching
+    pub fn summary(&self) -> String {
+        match (&self.title, &self.text) {
+            (Some(title), Some(text)) => format!("{}: {}", title, text),
+            (Some(only), None) | (None, Some(only)) => only.clone(),
+            (None, None) => self.key.clone(),
+        }
+    }
+}
+
+/// `name=value` token from a `NotificationRecord(...)` header line
+fn record_field<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    line.split_whitespace()
+        .find_map(|token| token.strip_prefix(name)?.strip_prefix('='))
+        .map(|value| value.trim_end_matches(':'))
+}
+
+/// Value of an `android.title=String (Energy full)` extras line; `None` for
+/// redacted values such as `String [length=11]`
+fn extra_value(value: &str) -> Option<String> {
+    let (_, text) = value.split_once(" (")?;
+    let text = text.strip_suffix(')')?.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Notifications listed in `dumpsys notification --noredact` output, once each
+pub fn parse_notifications(output: &str) -> Vec<DeviceNotification> {
+    let mut notifications: Vec<DeviceNotification> = Vec::new();
+    let mut current: Option<DeviceNotification> = None;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("NotificationRecord(") {
+            notifications.extend(current.take());
+            current = match (record_field(line, "pkg"), record_field(line, "key")) {
+                (Some(package), Some(key)) => Some(DeviceNotification {
+                    key: key.to_string(),
+                    package: package.to_string(),
+                    title: None,
+                    text: None,
+                }),
+                _ => None,
+            };
+        } else if let Some(notification) = current.as_mut() {
+            if let Some(value) = line.strip_prefix("android.title=") {
+                notification.title = extra_value(value);
+            } else if let Some(value) = line.strip_prefix("android.text=") {
+                notification.text = extra_value(value);
+            }
+        }
+    }
+    notifications.extend(current);
+
+    // Records also show up in the snoozed and enqueued lists
+    let mut seen = HashSet::new();
+    notifications.retain(|notification| seen.insert(notification.key.clone()));
+    notifications
+}
+
+/// Remembers what the last poll listed, to tell newly posted (or updated)
+/// notifications apart from ones already handled
+#[derive(Debug, Default)]
+pub struct NotificationWatcher {
+    known: Option<HashMap<String, DeviceNotification>>,
+}
+
+impl NotificationWatcher {
+    /// Notifications in `current` that are new or changed since the last call.
+    /// The first call only takes stock, so old notifications never fire rules.
+    pub fn update(&mut self, current: Vec<DeviceNotification>) -> Vec<DeviceNotification> {
+        let posted = match &self.known {
+            Some(known) => current
+                .iter()
+                .filter(|notification| known.get(&notification.key) != Some(notification))
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        self.known = Some(
+            current
+                .into_iter()
+                .map(|notification| (notification.key.clone(), notification))
+                .collect(),
+        );
+        posted
+    }
+
+    /// Forget everything, e.g. after switching device
+    pub fn reset(&mut self) {
+        self.known = None;
+    }
+}
+
+impl AdbBackend {
+    /// Notifications currently posted on the device
+    pub async fn notifications(&self) -> AdbResult<Vec<DeviceNotification>> {
+        let output = self
+            .shell(&["dumpsys", "notification", "--noredact"])
+            .await?;
+        Ok(parse_notifications(&output))
+    }
+}
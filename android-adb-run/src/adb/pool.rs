@@ -0,0 +1,133 @@
+// Device pool - keeps one shared USB connection per attached device.
+//
+// Every connection still serializes its own USB traffic through its
+// UsbCommand queue; the pool only decides which connection belongs to which
+// device so several phones can stay connected at the same time.
+
+use super::backend::AdbBackend;
+use super::discovery::is_tcp_address;
+use super::error::AdbResult;
+use super::types::{AdbClient, Device};
+use super::wireless;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Shared handle to a connected device (same shape the GUI and FSM already use)
+pub type SharedBackend = Arc<Mutex<AdbBackend>>;
+
+/// Connected devices keyed by `Device::key`: the USB serial, or `vid:pid` when the
+/// serial can't be read, so two phones of the same model get a connection each
+pub struct DevicePool<C: AdbClient = AdbBackend> {
+    clients: Arc<Mutex<HashMap<String, Arc<Mutex<C>>>>>,
+}
+
+impl<C: AdbClient> Clone for DevicePool<C> {
+    fn clone(&self) -> Self {
+        Self {
+            clients: self.clients.clone(),
+        }
+    }
+}
+
+impl<C: AdbClient> Default for DevicePool<C> {
+    fn default() -> Self {
+        Self {
+            clients: Arc::default(),
+        }
+    }
+}
+
+impl DevicePool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// List attached devices and drop pooled USB connections whose device is gone.
+    ///
+    /// Wireless (`ip:port`) connections are not enumerable over USB, so pooled
+    /// ones are appended to the list as-is, followed by remembered wireless
+    /// devices that are not connected yet.
+    pub async fn refresh(&self) -> AdbResult<Vec<Device>> {
+        let mut devices = AdbBackend::list_devices().await?;
+        let (stale, pooled_wireless): (Vec<String>, Vec<String>) = {
+            let clients = self.clients.lock().await;
+            clients
+                .keys()
+                .filter(|key| {
+                    is_tcp_address(key) || !devices.iter().any(|d| d.key() == key.as_str())
+                })
+                .cloned()
+                .partition(|name| !is_tcp_address(name))
+        };
+        for name in stale {
+            log::debug!("Device pool: {} no longer attached", name);
+            self.remove(&name).await;
+        }
+        let remembered = wireless::known_addresses()
+            .into_iter()
+            .filter(|address| !pooled_wireless.contains(address));
+        devices.extend(
+            pooled_wireless
+                .iter()
+                .cloned()
+                .chain(remembered)
+                .map(|name| Device {
+                    name,
+                    transport_id: Some("tcp".into()),
+                    serial: None,
+                }),
+        );
+        Ok(devices)
+    }
+
+    /// Return the pooled connection for `key`, connecting on first use
+    pub async fn connect(&self, key: &str) -> AdbResult<SharedBackend> {
+        if let Some(client) = self.get(key).await {
+            return Ok(client);
+        }
+        let client = if is_tcp_address(key) {
+            wireless::connect_address(key).await?
+        } else {
+            AdbBackend::new_with_device(key).await?
+        };
+        Ok(self.insert(key, client).await)
+    }
+}
+
+impl<C: AdbClient> DevicePool<C> {
+    /// Pool `client` under `key`, replacing what was there
+    pub async fn insert(&self, key: &str, client: C) -> Arc<Mutex<C>> {
+        let client = Arc::new(Mutex::new(client));
+        self.clients
+            .lock()
+            .await
+            .insert(key.to_string(), client.clone());
+        client
+    }
+
+    pub async fn get(&self, key: &str) -> Option<Arc<Mutex<C>>> {
+        self.clients.lock().await.get(key).cloned()
+    }
+
+    /// Remove a device from the pool, shutting it down when nobody else holds it
+    pub async fn remove(&self, key: &str) -> bool {
+        let Some(client) = self.clients.lock().await.remove(key) else {
+            return false;
+        };
+        if Arc::strong_count(&client) == 1 {
+            let mut guard = client.lock().await;
+            if let Err(e) = guard.shutdown().await {
+                log::debug!("Device pool: shutdown of {} failed: {}", key, e);
+            }
+        }
+        true
+    }
+
+    /// Keys of currently connected devices, sorted for stable display
+    pub async fn connected_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.clients.lock().await.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
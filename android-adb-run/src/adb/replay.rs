@@ -0,0 +1,117 @@
+// Screenshot-folder replay: the PNGs of a previously recorded session served one
+// per capture, so automation and the GUI can run offline against real frames
+// while templates, thresholds and scripts are tuned. Files are decoded as they
+// are served, so long recordings don't have to fit in memory.
+use super::error::{AdbError, AdbResult};
+use super::types::RgbFrame;
+use std::path::{Path, PathBuf};
+
+/// Set to `1` to start over after the last screenshot instead of holding it
+pub const REPLAY_LOOP_ENV: &str = "ADB_REPLAY_LOOP";
+
+/// The `*.png` files in `dir`, sorted by file name (capture order for
+/// timestamped names)
+pub fn png_files(dir: &Path) -> AdbResult<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| replay_error(dir, e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+        })
+        .collect();
+    paths.sort();
+    if paths.is_empty() {
+        return Err(replay_error(dir, "no .png screenshots"));
+    }
+    Ok(paths)
+}
+
+pub fn decode_png(path: &Path) -> AdbResult<RgbFrame> {
+    let bytes = std::fs::read(path).map_err(|e| replay_error(path, e))?;
+    RgbFrame::from_png(&bytes).map_err(|e| replay_error(path, e))
+}
+
+fn replay_error(path: &Path, error: impl std::fmt::Display) -> AdbError {
+    AdbError::MockFramesFailed {
+        path: path.to_path_buf(),
+        description: error.to_string(),
+    }
+}
+
+/// Position in a folder of recorded screenshots
+#[derive(Debug, Clone)]
+pub struct ReplayFolder {
+    files: Vec<PathBuf>,
+    /// Index of the next file to serve
+    next: usize,
+    looped: bool,
+}
+
+impl ReplayFolder {
+    pub fn open(dir: impl Into<PathBuf>, looped: bool) -> AdbResult<Self> {
+        let dir = dir.into();
+        let files = png_files(&dir)?;
+        log::info!(
+            "🎞️ Replaying {} screenshots from {}{}",
+            files.len(),
+            dir.display(),
+            if looped { " (looped)" } else { "" }
+        );
+        Ok(Self {
+            files,
+            next: 0,
+            looped,
+        })
+    }
+
+    /// The first screenshot, which sets the replayed screen size
+    pub fn first(&self) -> &Path {
+        &self.files[0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Frames served so far in this pass and the total, for status lines
+    pub fn position(&self) -> (usize, usize) {
+        (self.next, self.files.len())
+    }
+
+    /// File for the next capture. At the end the last file is served again,
+    /// or the first one when looping.
+    pub fn advance(&mut self) -> &Path {
+        if self.next == self.files.len() {
+            if self.looped {
+                self.next = 0;
+            } else {
+                return &self.files[self.files.len() - 1];
+            }
+        }
+        let index = self.next;
+        self.next += 1;
+        log::debug!(
+            "🎞️ Replay screenshot {}/{}: {}",
+            self.next,
+            self.files.len(),
+            self.files[index].display()
+        );
+        if self.next == self.files.len() && !self.looped {
+            log::info!(
+                "🎞️ Replay reached its last screenshot, holding {}",
+                self.files[index].display()
+            );
+        }
+        &self.files[index]
+    }
+
+    /// Start over from the first screenshot
+    pub fn rewind(&mut self) {
+        self.next = 0;
+    }
+}
@@ -0,0 +1,250 @@
+// Session recording - timestamped tap/swipe/screenshot events that can be replayed.
+//
+// Sessions are stored as plain text, one event per line, so recorded game
+// flows can be read and tweaked by hand:
+//
+//     # android-adb-run session
+//     0 tap 540 1200
+//     1500 swipe 540 1800 540 600 300
+//     3200 screenshot step-3.png
+//
+// The first column is the offset in milliseconds from the start of the session.
+
+use super::error::{AdbError, AdbResult};
+use super::types::AdbClient;
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const SESSION_HEADER: &str = "# android-adb-run session";
+
+/// One device operation that can be recorded and replayed
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionAction {
+    Tap {
+        x: u32,
+        y: u32,
+    },
+    Swipe {
+        x1: u32,
+        y1: u32,
+        x2: u32,
+        y2: u32,
+        duration_ms: Option<u32>,
+    },
+    /// Capture the screen, saving it to `path` when one was given
+    Screenshot {
+        path: Option<String>,
+    },
+}
+
+impl SessionAction {
+    /// Parse a command such as `tap 540 1200` (same syntax as the CLI subcommands)
+    pub fn parse(command: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        let number = |name: &str, value: &str| {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid {} value: {}", name, value))
+        };
+        match parts.as_slice() {
+            ["tap", x, y] => Ok(Self::Tap {
+                x: number("x", x)?,
+                y: number("y", y)?,
+            }),
+            ["swipe", x1, y1, x2, y2, rest @ ..] if rest.len() <= 1 => Ok(Self::Swipe {
+                x1: number("x1", x1)?,
+                y1: number("y1", y1)?,
+                x2: number("x2", x2)?,
+                y2: number("y2", y2)?,
+                duration_ms: rest.first().map(|v| number("duration_ms", v)).transpose()?,
+            }),
+            ["screenshot"] => Ok(Self::Screenshot { path: None }),
+            ["screenshot", path] => Ok(Self::Screenshot {
+                path: Some(path.to_string()),
+            }),
+            [] => Err("Empty command".to_string()),
+            _ => Err(format!("Unknown command: {}", command.trim())),
+        }
+    }
+
+    /// Run this action against a connected device
+    pub async fn execute<C: AdbClient>(&self, client: &C) -> AdbResult<()> {
+        match self {
+            Self::Tap { x, y } => client.tap(*x, *y).await,
+            Self::Swipe {
+                x1,
+                y1,
+                x2,
+                y2,
+                duration_ms,
+            } => client.swipe(*x1, *y1, *x2, *y2, *duration_ms).await,
+            Self::Screenshot { path } => {
+                let capture = client.screen_capture().await?;
+                if let Some(path) = path {
+                    write_file(path, &capture.bytes).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for SessionAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tap { x, y } => write!(f, "tap {} {}", x, y),
+            Self::Swipe {
+                x1,
+                y1,
+                x2,
+                y2,
+                duration_ms,
+            } => {
+                write!(f, "swipe {} {} {} {}", x1, y1, x2, y2)?;
+                if let Some(ms) = duration_ms {
+                    write!(f, " {}", ms)?;
+                }
+                Ok(())
+            }
+            Self::Screenshot { path: Some(path) } => write!(f, "screenshot {}", path),
+            Self::Screenshot { path: None } => write!(f, "screenshot"),
+        }
+    }
+}
+
+/// An action together with its offset from the start of the session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionEvent {
+    pub at_ms: u64,
+    pub action: SessionAction,
+}
+
+/// A recorded sequence of device actions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Session {
+    pub events: Vec<SessionEvent>,
+}
+
+impl Session {
+    /// Parse the text format; blank lines and `#` comments are ignored
+    pub fn parse(text: &str) -> AdbResult<Self> {
+        let mut events = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parse_error = |description: String| AdbError::SessionParseFailed {
+                line: index + 1,
+                description,
+            };
+            let (at_ms, command) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| parse_error("Expected '<ms> <command>'".to_string()))?;
+            let at_ms = at_ms
+                .parse::<u64>()
+                .map_err(|_| parse_error(format!("Invalid timestamp: {}", at_ms)))?;
+            let action = SessionAction::parse(command).map_err(parse_error)?;
+            events.push(SessionEvent { at_ms, action });
+        }
+        Ok(Self { events })
+    }
+
+    pub async fn load(path: impl AsRef<Path>) -> AdbResult<Self> {
+        let path = path.as_ref();
+        let text = tokio::fs::read_to_string(path).await.map_err(|source| {
+            AdbError::SessionFileFailed {
+                path: path.to_path_buf(),
+                source,
+            }
+        })?;
+        Self::parse(&text)
+    }
+
+    pub async fn save(&self, path: impl AsRef<Path>) -> AdbResult<()> {
+        write_file(path, self.to_string().as_bytes()).await
+    }
+
+    /// Total length of the session
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.events.last().map_or(0, |e| e.at_ms))
+    }
+
+    /// Re-execute every event against `client`, keeping the recorded timing.
+    ///
+    /// `on_event` is called just before each event runs (e.g. for progress output).
+    pub async fn replay<C: AdbClient>(
+        &self,
+        client: &C,
+        mut on_event: impl FnMut(&SessionEvent),
+    ) -> AdbResult<()> {
+        let start = Instant::now();
+        for event in &self.events {
+            let due = Duration::from_millis(event.at_ms);
+            if let Some(wait) = due.checked_sub(start.elapsed()) {
+                tokio::time::sleep(wait).await;
+            }
+            on_event(event);
+            event.action.execute(client).await?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", SESSION_HEADER)?;
+        for event in &self.events {
+            writeln!(f, "{} {}", event.at_ms, event.action)?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects actions with their offset from when recording started
+#[derive(Debug)]
+pub struct SessionRecorder {
+    started: Instant,
+    session: Session,
+}
+
+impl Default for SessionRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionRecorder {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            session: Session::default(),
+        }
+    }
+
+    /// Append `action`, timestamped now
+    pub fn record(&mut self, action: SessionAction) -> &SessionEvent {
+        let at_ms = self.started.elapsed().as_millis() as u64;
+        self.session.events.push(SessionEvent { at_ms, action });
+        self.session.events.last().expect("event was just pushed")
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    pub fn finish(self) -> Session {
+        self.session
+    }
+}
+
+async fn write_file(path: impl AsRef<Path>, bytes: &[u8]) -> AdbResult<()> {
+    let path = path.as_ref();
+    tokio::fs::write(path, bytes)
+        .await
+        .map_err(|source| AdbError::SessionFileFailed {
+            path: path.to_path_buf(),
+            source,
+        })
+}
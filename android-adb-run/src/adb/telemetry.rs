@@ -0,0 +1,122 @@
+// Device health telemetry - battery, thermal status and memory read over the shell
+use super::backend::AdbBackend;
+use super::error::AdbResult;
+use serde::Serialize;
+use std::time::Duration;
+
+/// How often the automation samples device health
+pub const TELEMETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Android `PowerManager.THERMAL_STATUS_*` names, indexed by status
+const THERMAL_STATUS_NAMES: [&str; 7] = [
+    "none",
+    "light",
+    "moderate",
+    "severe",
+    "critical",
+    "emergency",
+    "shutdown",
+];
+
+/// One health sample; fields stay `None` when the device does not report them
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DeviceTelemetry {
+    /// Battery charge, 0 - 100 %
+    pub battery_level: Option<u8>,
+    /// Plugged into AC, USB, wireless or dock power
+    pub charging: Option<bool>,
+    /// Battery temperature in °C
+    pub battery_temp_c: Option<f32>,
+    /// `PowerManager.THERMAL_STATUS_*` (0 = none ... 6 = shutdown)
+    pub thermal_status: Option<u8>,
+    pub mem_available_mb: Option<u64>,
+    pub mem_total_mb: Option<u64>,
+}
+
+impl DeviceTelemetry {
+    /// `none`, `light`, ... `shutdown`
+    pub fn thermal_label(&self) -> Option<&'static str> {
+        self.thermal_status
+            .and_then(|status| THERMAL_STATUS_NAMES.get(status as usize).copied())
+    }
+
+    /// Share of memory still available, 0.0 - 1.0
+    pub fn mem_available_ratio(&self) -> Option<f32> {
+        match (self.mem_available_mb, self.mem_total_mb) {
+            (Some(available), Some(total)) if total > 0 => Some(available as f32 / total as f32),
+            _ => None,
+        }
+    }
+}
+
+/// Fill battery fields from `dumpsys battery` output
+pub fn parse_battery(output: &str, telemetry: &mut DeviceTelemetry) {
+    let mut powered = None;
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "level" => telemetry.battery_level = value.parse::<u8>().ok().map(|l| l.min(100)),
+            // Tenths of a degree Celsius
+            "temperature" => {
+                telemetry.battery_temp_c = value.parse::<i32>().ok().map(|t| t as f32 / 10.0)
+            }
+            "AC powered" | "USB powered" | "Wireless powered" | "Dock powered" => {
+                let on = value == "true";
+                powered = Some(powered.unwrap_or(false) || on);
+            }
+            _ => {}
+        }
+    }
+    telemetry.charging = powered;
+}
+
+/// `Thermal Status: N` from `dumpsys thermalservice` output
+pub fn parse_thermal_status(output: &str) -> Option<u8> {
+    output.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("Thermal Status:")?
+            .trim()
+            .parse()
+            .ok()
+    })
+}
+
+/// Fill memory fields from `/proc/meminfo` (values in kB)
+pub fn parse_meminfo(output: &str, telemetry: &mut DeviceTelemetry) {
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let kb = value
+            .split_whitespace()
+            .next()
+            .and_then(|v| v.parse::<u64>().ok());
+        match key.trim() {
+            "MemTotal" => telemetry.mem_total_mb = kb.map(|kb| kb / 1024),
+            "MemAvailable" => telemetry.mem_available_mb = kb.map(|kb| kb / 1024),
+            _ => {}
+        }
+    }
+}
+
+impl AdbBackend {
+    /// Sample battery, thermal status and memory. Only the battery read is
+    /// required; thermal and memory stay `None` where the shell refuses them.
+    pub async fn telemetry(&self) -> AdbResult<DeviceTelemetry> {
+        let mut telemetry = DeviceTelemetry::default();
+        parse_battery(&self.shell(&["dumpsys", "battery"]).await?, &mut telemetry);
+
+        match self.shell(&["dumpsys", "thermalservice"]).await {
+            Ok(output) => telemetry.thermal_status = parse_thermal_status(&output),
+            Err(e) => log::debug!("Thermal status unavailable: {}", e),
+        }
+        match self.shell(&["cat", "/proc/meminfo"]).await {
+            Ok(output) => parse_meminfo(&output, &mut telemetry),
+            Err(e) => log::debug!("Memory info unavailable: {}", e),
+        }
+        Ok(telemetry)
+    }
+}
@@ -322,7 +322,13 @@ mod hardware_access_tests {
                     UsbCommand::Tap { .. } => processed += 1,
                     UsbCommand::Swipe { .. } => processed += 1,
                     UsbCommand::Screenshot { .. } => {}
+                    UsbCommand::ScreenshotRgb { .. } => {}
+                    UsbCommand::ScreenshotRegion { .. } => {}
+                    UsbCommand::Shell { .. } => {}
                     UsbCommand::CheckTouchEvent { .. } => {}
+                    UsbCommand::Install { .. } => {}
+                    UsbCommand::Push { .. } => {}
+                    UsbCommand::Pull { .. } => {}
                 }
             }
             processed
@@ -867,4 +873,802 @@ mod cross_platform_tests {
             "Timeout should not be desync"
         );
     }
+
+    #[test]
+    fn test_error_kind_drives_reconnect() {
+        use super::super::error::{AdbError, AdbErrorKind, message_kind};
+
+        // CLSE during a command is a protocol desync, the root cause of the
+        // GUI hung state bug; it needs a fresh connection
+        let desync = AdbError::ProtocolDesync {
+            description: "Command 'input tap' failed with protocol error: wrong command CLSE"
+                .into(),
+        };
+        assert_eq!(desync.kind(), AdbErrorKind::Protocol);
+        assert!(desync.needs_reconnect());
+        assert!(AdbError::ChannelClosed.needs_reconnect());
+        assert!(!AdbError::TapOutOfBounds { x: 1, y: 2 }.needs_reconnect());
+        assert_eq!(
+            AdbError::UsbEnumerationFailed {
+                source: rusb::Error::Busy
+            }
+            .kind(),
+            AdbErrorKind::Busy
+        );
+
+        // adb_client only describes its failures in text
+        for message in [
+            "device offline",
+            "device not found",
+            "no devices",
+            "connection refused",
+            "broken pipe",
+            "connection reset",
+        ] {
+            assert_eq!(
+                message_kind(message),
+                AdbErrorKind::Disconnected,
+                "{}",
+                message
+            );
+        }
+        assert_eq!(message_kind("no write endpoint"), AdbErrorKind::Protocol);
+        assert_eq!(
+            message_kind("LIBUSB_ERROR_ACCESS: Permission denied"),
+            AdbErrorKind::PermissionDenied
+        );
+        assert_eq!(message_kind("Resource busy"), AdbErrorKind::Busy);
+        assert_eq!(message_kind("unexpected reply OKAY"), AdbErrorKind::Other);
+
+        // Waiting for the phone's "Allow USB debugging?" dialog is not a lost device
+        assert_eq!(
+            message_kind("device unauthorized"),
+            AdbErrorKind::Unauthorized
+        );
+        let pending = AdbError::AuthorizationPending {
+            device: "18d1:4ee7".into(),
+        };
+        assert_eq!(pending.kind(), AdbErrorKind::Unauthorized);
+        assert!(!pending.needs_reconnect());
+        assert!(pending.connection_error_message().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_hotplug_wait_for_attach() {
+        use super::super::hotplug::{HotplugEvent, wait_for_attach};
+        use std::time::Duration;
+
+        let (tx, mut rx) = tokio::sync::broadcast::channel(4);
+        tx.send(HotplugEvent::DeviceDetached("18d1:4ee7".into()))
+            .unwrap();
+        tx.send(HotplugEvent::DeviceAttached("18d1:4ee7".into()))
+            .unwrap();
+        assert!(wait_for_attach(Some(&mut rx), Duration::from_millis(100)).await);
+
+        // Only a detach, or no hotplug support at all: wait out the timeout
+        tx.send(HotplugEvent::DeviceDetached("18d1:4ee7".into()))
+            .unwrap();
+        assert!(!wait_for_attach(Some(&mut rx), Duration::from_millis(20)).await);
+        assert!(!wait_for_attach(None, Duration::from_millis(20)).await);
+    }
+
+    #[test]
+    fn test_device_selector_matching() {
+        use super::super::discovery::{device_matches, parse_vid_pid};
+        use super::super::types::Device;
+
+        assert_eq!(parse_vid_pid("18d1:4ee7"), Some((0x18d1, 0x4ee7)));
+        assert_eq!(parse_vid_pid("not-a-device"), None);
+
+        let device = Device {
+            name: "18d1:4ee7".into(),
+            transport_id: None,
+            serial: Some("R58M123ABC".into()),
+        };
+        assert!(device_matches(&device, "18D1:4EE7"));
+        assert!(device_matches(&device, "R58M123ABC"));
+        assert!(!device_matches(&device, "R58M999"));
+    }
+
+    #[tokio::test]
+    async fn test_pool_keeps_identical_models_apart() {
+        use super::super::mock::MockAdb;
+        use super::super::pool::DevicePool;
+        use super::super::types::{AdbClient, Device};
+
+        let usb = |serial: Option<&str>| Device {
+            name: "18d1:4ee7".into(),
+            transport_id: None,
+            serial: serial.map(str::to_string),
+        };
+        let (first, second) = (usb(Some("R58M123ABC")), usb(Some("R58M456DEF")));
+        assert_eq!(first.key(), "R58M123ABC");
+        assert_eq!(usb(None).key(), "18d1:4ee7");
+
+        let pool = DevicePool::<MockAdb>::default();
+        pool.insert(first.key(), MockAdb::from_frames("phone-a", Vec::new()))
+            .await;
+        pool.insert(second.key(), MockAdb::from_frames("phone-b", Vec::new()))
+            .await;
+        assert_eq!(
+            pool.connected_names().await,
+            vec!["R58M123ABC", "R58M456DEF"]
+        );
+        let a = pool.get(first.key()).await.unwrap();
+        assert_eq!(a.lock().await.device_name(), "phone-a");
+        assert!(pool.get("18d1:4ee7").await.is_none());
+
+        assert!(pool.remove(first.key()).await);
+        assert!(!pool.remove(first.key()).await);
+        assert!(pool.get(first.key()).await.is_none());
+        let b = pool.get(second.key()).await.unwrap();
+        assert_eq!(b.lock().await.device_name(), "phone-b");
+    }
+
+    #[test]
+    fn test_parse_wlan_ip() {
+        use super::super::usb_impl::parse_wlan_ip;
+
+        let output = "3: wlan0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500\n    inet 192.168.1.50/24 brd 192.168.1.255 scope global wlan0\n";
+        assert_eq!(parse_wlan_ip(output), Some("192.168.1.50".to_string()));
+        assert_eq!(parse_wlan_ip("Device \"wlan0\" does not exist."), None);
+    }
+
+    #[test]
+    fn test_wireless_registry_and_pairing_code() {
+        use super::super::wireless::{WirelessRegistry, parse_pairing_code};
+
+        let mut registry = WirelessRegistry::default();
+        registry.remember("192.168.1.50:5555", Some("Pixel 7".into()));
+        registry.remember("192.168.1.60:5555", None);
+        registry.remember("192.168.1.50:5555", None);
+        assert_eq!(
+            registry.addresses(),
+            vec!["192.168.1.50:5555", "192.168.1.60:5555"]
+        );
+        // Reconnecting keeps the name learned earlier
+        assert_eq!(registry.devices[0].name.as_deref(), Some("Pixel 7"));
+
+        let text = toml::to_string_pretty(&registry).unwrap();
+        assert_eq!(toml::from_str::<WirelessRegistry>(&text).unwrap(), registry);
+        assert!(registry.forget("192.168.1.60:5555").is_some());
+        assert_eq!(registry.devices.len(), 1);
+
+        assert_eq!(parse_pairing_code(" 482915 ").unwrap(), "482915");
+        assert!(parse_pairing_code("48291").is_err());
+        assert!(parse_pairing_code("48a915").is_err());
+    }
+
+    #[test]
+    fn test_escape_input_text() {
+        use super::super::usb_impl::escape_input_text;
+
+        assert_eq!(escape_input_text("user@example.com"), "user@example.com");
+        assert_eq!(escape_input_text("hello world"), "hello%sworld");
+        assert_eq!(escape_input_text("p&ss'w$rd"), "p\\&ss\\'w\\$rd");
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        use super::super::error::AdbError;
+        use super::super::session::{Session, SessionAction};
+
+        let text =
+            "# comment\n0 tap 540 1200\n\n1500 swipe 1 2 3 4 300\n3200 screenshot step.png\n";
+        let session = Session::parse(text).unwrap();
+        assert_eq!(session.events.len(), 3);
+        assert_eq!(
+            session.events[0].action,
+            SessionAction::Tap { x: 540, y: 1200 }
+        );
+        assert_eq!(session.duration(), Duration::from_millis(3200));
+        assert_eq!(Session::parse(&session.to_string()).unwrap(), session);
+
+        assert!(matches!(
+            Session::parse("0 tap 1 2\n10 jump 3"),
+            Err(AdbError::SessionParseFailed { line: 2, .. })
+        ));
+        assert!(SessionAction::parse("swipe 1 2 3").is_err());
+    }
+
+    #[test]
+    fn test_parse_device_telemetry() {
+        use super::super::telemetry::{
+            DeviceTelemetry, parse_battery, parse_meminfo, parse_thermal_status,
+        };
+
+        let mut telemetry = DeviceTelemetry::default();
+        parse_battery(
+            "Current Battery Service state:\n  AC powered: false\n  USB powered: true\n  \
+             Wireless powered: false\n  status: 2\n  level: 87\n  scale: 100\n  temperature: 356\n",
+            &mut telemetry,
+        );
+        assert_eq!(telemetry.battery_level, Some(87));
+        assert_eq!(telemetry.charging, Some(true));
+        assert_eq!(telemetry.battery_temp_c, Some(35.6));
+
+        telemetry.thermal_status =
+            parse_thermal_status("IsStatusOverride: false\nThermal Status: 2\n");
+        assert_eq!(telemetry.thermal_label(), Some("moderate"));
+        assert_eq!(parse_thermal_status("Permission Denial"), None);
+
+        parse_meminfo(
+            "MemTotal:        5843124 kB\nMemFree:          204800 kB\nMemAvailable:    2921562 kB\n",
+            &mut telemetry,
+        );
+        assert_eq!(telemetry.mem_total_mb, Some(5706));
+        assert_eq!(telemetry.mem_available_mb, Some(2853));
+        assert!((telemetry.mem_available_ratio().unwrap() - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_notifications_and_watch_for_new_ones() {
+        use super::super::notifications::{NotificationWatcher, parse_notifications};
+
+        let dumpsys = "  Notification List:\n    \
+             NotificationRecord(0x0c3b5e2a: pkg=com.example.game user=UserHandle{0} id=7 tag=null \
+             importance=3 key=0|com.example.game|7|null|10234: Notification(channel=energy))\n      \
+             uid=10234 userId=0\n      extras={\n        \
+             android.title=String (Energy refilled)\n        \
+             android.text=String (Your energy is full. Time to play!)\n      }\n    \
+             NotificationRecord(0x1d2e3f40: pkg=com.android.systemui user=UserHandle{0} id=1 tag=null \
+             importance=2 key=0|com.android.systemui|1|null|10012: Notification(channel=usb))\n      \
+             extras={\n        android.title=String [length=13]\n      }\n  \
+             Snoozed notifications:\n    \
+             NotificationRecord(0x0c3b5e2a: pkg=com.example.game user=UserHandle{0} id=7 tag=null \
+             importance=3 key=0|com.example.game|7|null|10234: Notification(channel=energy))\n";
+
+        let notifications = parse_notifications(dumpsys);
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications[0].package, "com.example.game");
+        assert_eq!(notifications[0].key, "0|com.example.game|7|null|10234");
+        assert_eq!(notifications[0].title.as_deref(), Some("Energy refilled"));
+        assert_eq!(
+            notifications[0].summary(),
+            "Energy refilled: Your energy is full. Time to play!"
+        );
+        // Redacted title
+        assert_eq!(notifications[1].title, None);
+
+        let mut watcher = NotificationWatcher::default();
+        assert!(watcher.update(notifications.clone()).is_empty());
+        assert!(watcher.update(notifications.clone()).is_empty());
+        let mut updated = notifications;
+        updated[1].text = Some("USB debugging connected".into());
+        let posted = watcher.update(updated);
+        assert_eq!(posted.len(), 1);
+        assert_eq!(posted[0].package, "com.android.systemui");
+    }
+
+    #[test]
+    fn test_parse_ui_hierarchy_and_select() {
+        use super::super::ui_hierarchy::{UiSelector, parse_ui_hierarchy};
+
+        let dump = "<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><hierarchy rotation=\"0\">\
+             <node index=\"0\" text=\"\" resource-id=\"\" class=\"android.widget.FrameLayout\" \
+             package=\"com.example.app\" content-desc=\"\" clickable=\"false\" enabled=\"true\" \
+             bounds=\"[0,0][1080,2400]\"><node index=\"0\" text=\"Save &amp; exit\" \
+             resource-id=\"com.example.app:id/ok_button\" class=\"android.widget.Button\" \
+             package=\"com.example.app\" content-desc=\"\" clickable=\"true\" enabled=\"true\" \
+             bounds=\"[100,2000][500,2150]\" /><node index=\"1\" text=\"\" resource-id=\"\" \
+             class=\"android.widget.ImageButton\" package=\"com.example.app\" content-desc=\"Settings\" \
+             clickable=\"true\" enabled=\"false\" bounds=\"[960,80][1040,160]\" /></node></hierarchy>\
+             UI hierchary dumped to: /dev/tty\n";
+
+        let nodes = parse_ui_hierarchy(dump).unwrap();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[1].text, "Save & exit");
+        assert_eq!(nodes[1].bounds, (100, 2000, 400, 150));
+        assert_eq!(nodes[1].center(), (300, 2075));
+        assert!(nodes[1].clickable && !nodes[2].enabled);
+
+        let by_id = UiSelector {
+            resource_id: Some("ok_button".into()),
+            text: None,
+        };
+        assert_eq!(by_id.find(&nodes), Some(&nodes[1]));
+        let by_desc = UiSelector {
+            resource_id: None,
+            text: Some("Settings".into()),
+        };
+        assert_eq!(
+            by_desc.find(&nodes).map(|node| node.center()),
+            Some((1000, 120))
+        );
+        let both = UiSelector {
+            text: Some("Cancel".into()),
+            ..by_id
+        };
+        assert_eq!(both.find(&nodes), None);
+        assert_eq!(UiSelector::default().find(&nodes), None);
+
+        assert!(parse_ui_hierarchy("ERROR: could not get idle state.\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_ui_events() {
+        use super::super::ui_events::parse_ui_events;
+
+        let output = "Events Printer is running... press Ctrl-C to stop\n\
+             10-16 09:12:01.482 EventType: TYPE_WINDOW_STATE_CHANGED; EventTime: 84512077; \
+             PackageName: com.example.game; MovementGranularity: 0; Action: 0; \
+             ContentChangeTypes: []; WindowChangeTypes: [] [ ClassName: android.app.Dialog; ]\n\
+             10-16 09:12:01.530 EventType: TYPE_WINDOW_CONTENT_CHANGED; EventTime: 84512125; \
+             PackageName: null; MovementGranularity: 0; Action: 0\n\
+             10-16 09:12:01.611 EventType: TYPE_VIEW_CLICKED; EventTime: 84512206; \
+             PackageName: com.example.game; MovementGranularity: 0\n";
+
+        let events = parse_ui_events(output);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].event_type, "TYPE_WINDOW_STATE_CHANGED");
+        assert_eq!(events[0].package.as_deref(), Some("com.example.game"));
+        assert_eq!(events[1].package, None);
+        assert!(events[0].is_ui_change(false));
+        assert!(!events[1].is_ui_change(false));
+        assert!(events[1].is_ui_change(true));
+        assert!(!events[2].is_ui_change(true));
+        assert!(parse_ui_events("Error: could not connect to UiAutomation\n").is_empty());
+    }
+
+    #[test]
+    fn test_app_management_parsing() {
+        use super::super::usb_impl::{checked_package_name, parse_foreground_package};
+
+        assert!(checked_package_name("com.example.game").is_ok());
+        assert!(checked_package_name("com.example.game/.MainActivity").is_ok());
+        assert!(checked_package_name("com.example; reboot").is_err());
+        assert!(checked_package_name("a/b/c").is_err());
+        assert!(checked_package_name("").is_err());
+
+        let dumpsys = "ACTIVITY MANAGER ACTIVITIES (dumpsys activity activities)\n  \
+             topResumedActivity=ActivityRecord{8c1d3e u0 com.example.game/.MainActivity t42}\n";
+        assert_eq!(
+            parse_foreground_package(dumpsys).as_deref(),
+            Some("com.example.game")
+        );
+        assert_eq!(
+            parse_foreground_package(
+                "    mResumedActivity: ActivityRecord{1f2 u0 com.android.launcher3/.Launcher t1}"
+            )
+            .as_deref(),
+            Some("com.android.launcher3")
+        );
+        assert_eq!(parse_foreground_package("mFocusedApp=null"), None);
+    }
+
+    #[test]
+    fn test_parse_device_utc_offset() {
+        use super::super::usb_impl::parse_utc_offset;
+
+        assert_eq!(
+            parse_utc_offset("+1300\n"),
+            Some(time::UtcOffset::from_hms(13, 0, 0).unwrap())
+        );
+        assert_eq!(
+            parse_utc_offset("-0530"),
+            Some(time::UtcOffset::from_hms(-5, -30, 0).unwrap())
+        );
+        assert_eq!(parse_utc_offset("NZDT"), None);
+        assert_eq!(parse_utc_offset("+13"), None);
+    }
+
+    #[test]
+    fn test_orientation_parsing_and_remap() {
+        use super::super::types::Orientation;
+        use super::super::usb_impl::parse_orientation;
+
+        let touchscreen = "    Touch Input Mapper (mode - DIRECT):\n      \
+             Viewport INTERNAL: displayId=0, orientation=3, logicalFrame=[0, 0, 2400, 1080]\n      \
+             SurfaceOrientation: 1\n";
+        assert_eq!(parse_orientation(touchscreen), Some(Orientation::Landscape));
+        assert_eq!(
+            parse_orientation(
+                "  Viewport INTERNAL: displayId=0, orientation=ROTATION_270, deviceSize=[1080, 2400]"
+            ),
+            Some(Orientation::ReverseLandscape)
+        );
+        assert_eq!(parse_orientation("INPUT MANAGER (dumpsys input)"), None);
+
+        let natural = (1080, 2400);
+        assert_eq!(Orientation::Landscape.screen_size(natural), (2400, 1080));
+        // The top-right corner of the phone is top-left once turned counter-clockwise
+        assert_eq!(
+            Orientation::Portrait.remap(Orientation::Landscape, (1079, 0), natural),
+            (0, 0)
+        );
+        assert_eq!(
+            Orientation::Portrait.remap(Orientation::ReversePortrait, (100, 200), natural),
+            (979, 2199)
+        );
+        for to in [
+            Orientation::Landscape,
+            Orientation::ReversePortrait,
+            Orientation::ReverseLandscape,
+        ] {
+            let turned = Orientation::Portrait.remap(to, (540, 1800), natural);
+            assert_eq!(
+                to.remap(Orientation::Portrait, turned, natural),
+                (540, 1800)
+            );
+        }
+    }
+
+    #[test]
+    fn test_clipboard_output_and_quoting() {
+        use super::super::usb_impl::{parse_clipboard, shell_quote};
+
+        assert_eq!(parse_clipboard("REF-1234\n").as_deref(), Some("REF-1234"));
+        assert_eq!(parse_clipboard("null\n").as_deref(), Some(""));
+        assert_eq!(
+            parse_clipboard("ClipData { text/plain \"code\" {T(8):REF-1234} }\n").as_deref(),
+            Some("REF-1234")
+        );
+        assert_eq!(parse_clipboard("Unknown command: get-primary-clip\n"), None);
+        assert_eq!(shell_quote("it's $5"), r"'it'\''s $5'");
+    }
+
+    #[test]
+    fn test_shell_capture_splits_streams() {
+        use super::super::usb_impl::{parse_shell_capture, shell_capture_command};
+
+        let command = shell_capture_command("ls /sdcard | head -1", Duration::from_secs(10));
+        assert!(command.contains("timeout 10 sh -c 'ls /sdcard | head -1' 2>$e"));
+
+        let output =
+            parse_shell_capture("Download\n\n__ADB_RUN_SHELL_EXIT__1\nls: /x: No such file\n")
+                .unwrap();
+        assert_eq!(output.stdout, "Download\n");
+        assert_eq!(output.stderr, "ls: /x: No such file\n");
+        assert_eq!(output.exit_code, 1);
+        assert!(!output.success());
+        assert_eq!(
+            parse_shell_capture("/system/bin/sh: timeout: not found\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_slow_shell_command_keeps_the_connection() {
+        use super::super::error::{AdbError, AdbErrorKind};
+        use super::super::usb_impl::shell_capture_result;
+
+        let killed = shell_capture_result(
+            "sleep 60".into(),
+            "\n__ADB_RUN_SHELL_EXIT__124\n",
+            Duration::from_secs(10),
+        )
+        .unwrap_err();
+        assert!(matches!(killed, AdbError::ShellCommandTimedOut { .. }));
+        assert_ne!(killed.kind(), AdbErrorKind::Timeout);
+        assert!(!killed.needs_reconnect());
+
+        let done = shell_capture_result(
+            "echo hi".into(),
+            "hi\n\n__ADB_RUN_SHELL_EXIT__0\n",
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert!(done.success());
+        assert!(
+            shell_capture_result("echo hi".into(), "garbled", Duration::from_secs(10)).is_err()
+        );
+    }
+
+    #[test]
+    fn test_device_details_json() {
+        use super::super::types::{ConnectionType, DeviceDetails};
+
+        let details = DeviceDetails {
+            name: "192.168.1.50:5555".into(),
+            serial: None,
+            model: Some("Pixel 7".into()),
+            android_version: Some("14".into()),
+            screen_width: 1080,
+            screen_height: 2400,
+            connection: ConnectionType::Wifi,
+        };
+        let json = serde_json::to_value(&details).unwrap();
+        assert_eq!(json["connection"], "wifi");
+        assert_eq!(json["model"], "Pixel 7");
+        assert_eq!(json["screen_width"], 1080);
+        assert!(json["serial"].is_null());
+    }
+
+    #[test]
+    fn test_rgb_frame_round_trip() {
+        use super::super::types::RgbFrame;
+
+        // 2x1 RGBA framebuffer: red, then green; alpha is dropped
+        let frame = RgbFrame::from_rgba(2, 1, &[255, 0, 0, 255, 0, 255, 0, 128]);
+        assert_eq!(frame.pixels, vec![255, 0, 0, 0, 255, 0]);
+
+        let png = frame.to_png().unwrap();
+        assert_eq!(RgbFrame::from_png(&png).unwrap(), frame);
+
+        // Same grayscale as decoding the PNG and calling to_luma8
+        let decoded = image::load_from_memory(&png).unwrap().to_luma8();
+        assert_eq!(frame.to_luma(), decoded);
+
+        assert!(RgbFrame::from_png(b"not an image").is_err());
+    }
+
+    #[test]
+    fn test_rgb_frame_region() {
+        use super::super::types::RgbFrame;
+
+        // 3x2 RGBA framebuffer where each pixel's red channel is its index
+        let rgba: Vec<u8> = (0..6u8).flat_map(|i| [i, 0, 0, 255]).collect();
+        let frame = RgbFrame::from_rgba(3, 2, &rgba);
+
+        let region = RgbFrame::from_rgba_region(3, 2, &rgba, (1, 0, 2, 2)).unwrap();
+        assert_eq!((region.width, region.height), (2, 2));
+        assert_eq!(region.pixels, vec![1, 0, 0, 2, 0, 0, 4, 0, 0, 5, 0, 0]);
+        assert_eq!(frame.crop((1, 0, 2, 2)).unwrap(), region);
+
+        // Clipped at the screen edge; starting outside the screen is an error
+        let clipped = frame.crop((2, 1, 10, 10)).unwrap();
+        assert_eq!((clipped.width, clipped.height), (1, 1));
+        assert_eq!(clipped.pixels, vec![5, 0, 0]);
+        assert!(frame.crop((3, 0, 1, 1)).is_err());
+        assert!(frame.crop((0, 0, 0, 1)).is_err());
+    }
+
+    #[test]
+    fn test_gesture_sendevent_script() {
+        use super::super::gesture::{
+            TouchAxes, parse_touch_axes, pinch_paths, sendevent_script, validate_paths,
+        };
+
+        let getevent = "add device 1: /dev/input/event1\n  name:     \"gpio-keys\"\n\
+            add device 2: /dev/input/event2\n  name:     \"touchscreen\"\n  events:\n\
+            \x20   ABS (0003): 002f  : value 0, min 0, max 9, fuzz 0, flat 0, resolution 0\n\
+            \x20               0035  : value 0, min 0, max 2159, fuzz 0, flat 0, resolution 0\n\
+            \x20               0036  : value 0, min 0, max 4799, fuzz 0, flat 0, resolution 0\n";
+        let axes = parse_touch_axes(getevent).unwrap();
+        assert_eq!(
+            axes,
+            TouchAxes {
+                device: "/dev/input/event2".into(),
+                max_x: 2159,
+                max_y: 4799,
+            }
+        );
+        assert!(parse_touch_axes("add device 1: /dev/input/event1\n").is_none());
+
+        let paths = pinch_paths(540, 1200, 200, 600);
+        assert_eq!(paths[0], vec![(440, 1200), (240, 1200)]);
+        assert_eq!(paths[1], vec![(640, 1200), (840, 1200)]);
+        assert!(validate_paths(&paths, (1080, 2400)).is_ok());
+        assert!(validate_paths(&pinch_paths(540, 1200, 200, 2000), (1080, 2400)).is_err());
+        assert!(validate_paths(&[], (1080, 2400)).is_err());
+
+        // Touch axes are twice the screen resolution here
+        let script = sendevent_script(&axes, (1080, 2400), &paths, 100);
+        assert!(script.starts_with("e() { sendevent /dev/input/event2 $1 $2 $3; }"));
+        assert!(script.contains("e 3 53 880; e 3 54 2400"));
+        assert!(script.contains("e 3 53 480; e 3 54 2400"));
+        assert!(script.ends_with("e 3 47 1; e 3 57 -1; e 1 330 0; e 0 0 0"));
+    }
+
+    #[test]
+    fn test_drag_sendevent_script() {
+        use super::super::gesture::{TouchAxes, drag_script};
+
+        // Touch axes are twice the screen resolution here
+        let axes = TouchAxes {
+            device: "/dev/input/event2".into(),
+            max_x: 2159,
+            max_y: 4799,
+        };
+
+        // Drag holds still at the start before the first move
+        let drag = drag_script(&axes, (1080, 2400), (100, 100), (500, 100), (800, 100));
+        assert!(drag.contains("e 1 330 1; e 0 0 0; sleep 0.850; e 3 47 0; e 3 53 600;"));
+        assert!(drag.contains("e 3 53 1000; e 3 54 200; e 0 0 0; e 3 47 0; e 3 57 -1"));
+    }
+
+    #[tokio::test]
+    async fn test_mock_adb_serves_frames_and_records_input() {
+        use super::super::mock::{MockAction, MockAdb, load_frames};
+        use super::super::types::{AdbClient, RgbFrame};
+
+        let dir = std::env::temp_dir().join(format!("adb_mock_frames_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, shade) in [("2.png", 200u8), ("1.png", 100), ("notes.txt", 0)] {
+            let frame = RgbFrame {
+                width: 4,
+                height: 8,
+                pixels: vec![shade; 4 * 8 * 3],
+            };
+            std::fs::write(dir.join(name), frame.to_png().unwrap()).unwrap();
+        }
+        let frames = load_frames(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(frames.len(), 2);
+
+        // Served in file name order, cycling
+        let mock = MockAdb::from_frames("mock", frames);
+        let control = mock.control();
+        assert_eq!(mock.screen_dimensions(), (4, 8));
+        let shades: Vec<u8> = [
+            mock.screen_capture_rgb().await.unwrap(),
+            mock.screen_capture_rgb().await.unwrap(),
+            RgbFrame::from_png(&mock.screen_capture_bytes().await.unwrap()).unwrap(),
+        ]
+        .iter()
+        .map(|frame| frame.pixels[0])
+        .collect();
+        assert_eq!(shades, [100, 200, 100]);
+        assert_eq!(control.captures(), 3);
+
+        mock.tap(1, 2).await.unwrap();
+        assert!(mock.tap(4, 2).await.is_err());
+        mock.swipe(0, 0, 3, 7, Some(300)).await.unwrap();
+        mock.start_activity("com.example.game/.Main").await.unwrap();
+        assert!(mock.is_app_foreground("com.example.game").await.unwrap());
+        assert_eq!(
+            control.take_actions(),
+            [
+                MockAction::Tap { x: 1, y: 2 },
+                MockAction::Swipe {
+                    x1: 0,
+                    y1: 0,
+                    x2: 3,
+                    y2: 7,
+                    duration: Some(300),
+                },
+                MockAction::StartActivity("com.example.game/.Main".into()),
+            ]
+        );
+
+        control.set_shell_output("date +%z", "+0200");
+        assert_eq!(
+            mock.utc_offset().await.unwrap(),
+            time::UtcOffset::from_hms(2, 0, 0).ok()
+        );
+
+        // Unplugged: everything fails with an error that asks for a reconnect
+        control.set_disconnected(true);
+        assert!(
+            mock.screen_capture_rgb()
+                .await
+                .unwrap_err()
+                .needs_reconnect()
+        );
+        assert!(mock.tap(1, 2).await.unwrap_err().needs_reconnect());
+        assert!(control.actions().is_empty());
+        control.set_disconnected(false);
+
+        control.set_capture_delay(Duration::from_millis(50));
+        let started = std::time::Instant::now();
+        mock.screen_capture_rgb().await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_push_pull_report_progress() {
+        use super::super::mock::MockAdb;
+        use super::super::transfer::{ProgressSink, TransferProgress};
+        use super::super::types::AdbClient;
+        use std::io::{Read, Write};
+        use std::sync::Mutex;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let record = |seen: &Arc<Mutex<Vec<TransferProgress>>>| {
+            let seen = seen.clone();
+            Box::new(move |progress| seen.lock().unwrap().push(progress))
+                as super::super::transfer::ProgressCallback
+        };
+
+        let mut sink = ProgressSink::new(Some(record(&seen)), Some(8));
+        let mut copied = Vec::new();
+        sink.reader(&b"abcdefgh"[..])
+            .read_to_end(&mut copied)
+            .unwrap();
+        sink.writer(Vec::new()).write_all(b"ij").unwrap();
+        let last = *seen.lock().unwrap().last().unwrap();
+        assert_eq!(last.transferred, 10);
+        assert_eq!(last.fraction(), Some(1.0));
+
+        let dir = std::env::temp_dir().join(format!("adb_push_pull_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("claim.png"), b"png bytes").unwrap();
+        let mock = MockAdb::from_frames("mock", Vec::new());
+        seen.lock().unwrap().clear();
+        mock.push(
+            &dir.join("claim.png"),
+            "/sdcard/templates/claim.png",
+            Some(record(&seen)),
+        )
+        .await
+        .unwrap();
+        mock.pull("/sdcard/templates/claim.png", &dir.join("back.png"), None)
+            .await
+            .unwrap();
+        let pulled = std::fs::read(dir.join("back.png")).unwrap();
+        assert!(
+            mock.pull("/sdcard/missing.sav", &dir.join("x"), None)
+                .await
+                .is_err()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(pulled, b"png bytes");
+        assert_eq!(
+            seen.lock().unwrap().as_slice(),
+            &[TransferProgress {
+                transferred: 9,
+                total: Some(9)
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_folder_holds_or_loops_at_the_end() {
+        use super::super::mock::MockAdb;
+        use super::super::replay::ReplayFolder;
+        use super::super::types::{AdbClient, RgbFrame};
+
+        let dir = std::env::temp_dir().join(format!("adb_replay_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, shade) in [
+            ("shot_003.png", 30u8),
+            ("shot_001.png", 10),
+            ("shot_002.png", 20),
+        ] {
+            let frame = RgbFrame {
+                width: 6,
+                height: 9,
+                pixels: vec![shade; 6 * 9 * 3],
+            };
+            std::fs::write(dir.join(name), frame.to_png().unwrap()).unwrap();
+        }
+
+        let served = |replay: &mut ReplayFolder, count: usize| -> Vec<String> {
+            (0..count)
+                .map(|_| {
+                    let path = replay.advance();
+                    path.file_name().unwrap().to_string_lossy().into_owned()
+                })
+                .collect()
+        };
+        let mut once = ReplayFolder::open(&dir, false).unwrap();
+        assert_eq!(
+            served(&mut once, 4),
+            [
+                "shot_001.png",
+                "shot_002.png",
+                "shot_003.png",
+                "shot_003.png"
+            ]
+        );
+        assert_eq!(once.position(), (3, 3));
+        once.rewind();
+        assert_eq!(served(&mut once, 1), ["shot_001.png"]);
+
+        let mut looped = ReplayFolder::open(&dir, true).unwrap();
+        assert_eq!(
+            served(&mut looped, 4),
+            [
+                "shot_001.png",
+                "shot_002.png",
+                "shot_003.png",
+                "shot_001.png"
+            ]
+        );
+
+        // The replay backend decodes each file as it is captured
+        let mock = MockAdb::replay("replay:session", &dir, false).unwrap();
+        let control = mock.control();
+        assert_eq!(mock.screen_dimensions(), (6, 9));
+        let mut shades = Vec::new();
+        for _ in 0..4 {
+            shades.push(mock.screen_capture_rgb().await.unwrap().pixels[0]);
+        }
+        assert_eq!(shades, [10, 20, 30, 30]);
+        assert_eq!(control.replay_position(), Some((3, 3)));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(ReplayFolder::open(&dir, false).is_err());
+    }
 }
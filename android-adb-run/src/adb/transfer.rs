@@ -0,0 +1,100 @@
+// File transfer progress - `push`/`pull` stream through these adapters, which
+// count the bytes moved and report them to an optional callback.
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// Bytes moved so far by a `push` or `pull`, and the file size when known
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferProgress {
+    pub transferred: u64,
+    pub total: Option<u64>,
+}
+
+impl TransferProgress {
+    /// 0.0..=1.0, or `None` while the size is unknown
+    pub fn fraction(&self) -> Option<f64> {
+        self.total
+            .filter(|&total| total > 0)
+            .map(|total| (self.transferred as f64 / total as f64).min(1.0))
+    }
+}
+
+/// Called after every chunk of a transfer
+pub type ProgressCallback = Box<dyn FnMut(TransferProgress) + Send>;
+
+/// Optional progress callback plus the running byte count
+pub struct ProgressSink {
+    callback: Option<ProgressCallback>,
+    progress: TransferProgress,
+}
+
+impl ProgressSink {
+    pub fn new(callback: Option<ProgressCallback>, total: Option<u64>) -> Self {
+        Self {
+            callback,
+            progress: TransferProgress {
+                transferred: 0,
+                total,
+            },
+        }
+    }
+
+    /// Count `bytes` more as moved and tell the callback
+    pub fn advance(&mut self, bytes: usize) {
+        self.progress.transferred += bytes as u64;
+        if let Some(callback) = self.callback.as_mut() {
+            callback(self.progress);
+        }
+    }
+
+    /// Reads from `inner`, counting what it hands out
+    pub fn reader<R: Read>(&mut self, inner: R) -> ProgressReader<'_, R> {
+        ProgressReader { inner, sink: self }
+    }
+
+    /// Writes to `inner`, counting what it takes
+    pub fn writer<W: Write>(&mut self, inner: W) -> ProgressWriter<'_, W> {
+        ProgressWriter { inner, sink: self }
+    }
+}
+
+impl fmt::Debug for ProgressSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressSink")
+            .field("callback", &self.callback.is_some())
+            .field("progress", &self.progress)
+            .finish()
+    }
+}
+
+pub struct ProgressReader<'a, R> {
+    inner: R,
+    sink: &'a mut ProgressSink,
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read > 0 {
+            self.sink.advance(read);
+        }
+        Ok(read)
+    }
+}
+
+pub struct ProgressWriter<'a, W> {
+    inner: W,
+    sink: &'a mut ProgressSink,
+}
+
+impl<W: Write> Write for ProgressWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.sink.advance(written);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -1,7 +1,10 @@
-use super::error::AdbResult;
+use super::error::{AdbError, AdbResult};
+use super::gesture::{TouchPath, pinch_paths};
+use super::transfer::{ProgressCallback, ProgressSink};
+use super::ui_hierarchy::{UI_DUMP_COMMAND, UiNode, parse_ui_hierarchy};
 
 // Core ADB types and traits
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -25,15 +28,52 @@ pub enum UsbCommand {
     Screenshot {
         response_tx: tokio::sync::oneshot::Sender<AdbResult<Vec<u8>>>,
     },
+    ScreenshotRgb {
+        response_tx: tokio::sync::oneshot::Sender<AdbResult<RgbFrame>>,
+    },
+    ScreenshotRegion {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        response_tx: tokio::sync::oneshot::Sender<AdbResult<RgbFrame>>,
+    },
+    Shell {
+        args: Vec<String>,
+        response_tx: tokio::sync::oneshot::Sender<AdbResult<String>>,
+    },
     CheckTouchEvent {
         event_device: String,
         response_tx: tokio::sync::oneshot::Sender<AdbResult<bool>>,
     },
+    Install {
+        apk_path: std::path::PathBuf,
+        response_tx: tokio::sync::oneshot::Sender<AdbResult<()>>,
+    },
+    Push {
+        local: std::path::PathBuf,
+        remote: String,
+        progress: ProgressSink,
+        response_tx: tokio::sync::oneshot::Sender<AdbResult<()>>,
+    },
+    Pull {
+        remote: String,
+        local: std::path::PathBuf,
+        progress: ProgressSink,
+        response_tx: tokio::sync::oneshot::Sender<AdbResult<()>>,
+    },
 }
 
 // Backwards compatibility alias
 pub type TapCommand = UsbCommand;
 
+// Android key codes (android.view.KeyEvent) used with `AdbClient::key_event`
+pub const KEYCODE_HOME: u32 = 3;
+pub const KEYCODE_BACK: u32 = 4;
+pub const KEYCODE_ENTER: u32 = 66;
+pub const KEYCODE_TAB: u32 = 61;
+pub const KEYCODE_DEL: u32 = 67;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ImageCapture {
     pub bytes: Vec<u8>,
@@ -41,6 +81,235 @@ pub struct ImageCapture {
     pub index: u64, // sequential capture count (per backend instance)
 }
 
+/// Raw screen pixels, 3 bytes (R, G, B) per pixel in row-major order.
+///
+/// Template matching works on these directly; PNG encoding only happens when a
+/// frame is saved or shown in the GUI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RgbFrame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbFrame {
+    /// Build a frame from an RGBA buffer (as returned by the framebuffer), dropping alpha
+    pub fn from_rgba(width: u32, height: u32, rgba: &[u8]) -> Self {
+        let pixels = rgba
+            .chunks_exact(4)
+            .flat_map(|px| [px[0], px[1], px[2]])
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Copy just the `x,y width x height` area out of an RGBA framebuffer, so a
+    /// small check never converts the whole screen (clipped at the screen edge)
+    pub fn from_rgba_region(
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+        (x, y, w, h): (u32, u32, u32, u32),
+    ) -> AdbResult<Self> {
+        let (w, h) = clip_region(width, height, (x, y, w, h))?;
+        let mut pixels = Vec::with_capacity((w * h * 3) as usize);
+        for row in y..y + h {
+            let start = ((row * width + x) * 4) as usize;
+            let end = start + (w * 4) as usize;
+            let line = rgba
+                .get(start..end)
+                .ok_or_else(|| AdbError::FrameDecodeFailed {
+                    description: format!("framebuffer shorter than {}x{}", width, height),
+                })?;
+            pixels.extend(line.chunks_exact(4).flat_map(|px| [px[0], px[1], px[2]]));
+        }
+        Ok(Self {
+            width: w,
+            height: h,
+            pixels,
+        })
+    }
+
+    /// Decode an encoded screenshot (PNG from `screencap -p`, or any format `image` reads)
+    pub fn from_png(bytes: &[u8]) -> AdbResult<Self> {
+        let rgb = image::load_from_memory(bytes)
+            .map_err(|e| AdbError::FrameDecodeFailed {
+                description: e.to_string(),
+            })?
+            .to_rgb8();
+        Ok(Self::from_rgb_image(rgb))
+    }
+
+    pub fn from_rgb_image(image: image::RgbImage) -> Self {
+        Self {
+            width: image.width(),
+            height: image.height(),
+            pixels: image.into_raw(),
+        }
+    }
+
+    pub fn to_rgb_image(&self) -> image::RgbImage {
+        image::RgbImage::from_raw(self.width, self.height, self.pixels.clone())
+            .unwrap_or_else(|| image::RgbImage::new(self.width, self.height))
+    }
+
+    /// The `x,y width x height` area of this frame (clipped at the frame edge)
+    pub fn crop(&self, (x, y, w, h): (u32, u32, u32, u32)) -> AdbResult<Self> {
+        let (w, h) = clip_region(self.width, self.height, (x, y, w, h))?;
+        let mut pixels = Vec::with_capacity((w * h * 3) as usize);
+        for row in y..y + h {
+            let start = ((row * self.width + x) * 3) as usize;
+            pixels.extend_from_slice(&self.pixels[start..start + (w * 3) as usize]);
+        }
+        Ok(Self {
+            width: w,
+            height: h,
+            pixels,
+        })
+    }
+
+    /// Borrow the pixels as an image without copying (None if the buffer is short)
+    pub fn as_image(&self) -> Option<image::ImageBuffer<image::Rgb<u8>, &[u8]>> {
+        image::ImageBuffer::from_raw(self.width, self.height, &self.pixels[..])
+    }
+
+    /// Grayscale copy used by template matching (same weights as `DynamicImage::to_luma8`)
+    pub fn to_luma(&self) -> image::GrayImage {
+        match self.as_image() {
+            Some(view) => image::imageops::grayscale(&view),
+            None => image::GrayImage::new(self.width, self.height),
+        }
+    }
+
+    /// Encode as PNG for saving to disk or showing in the GUI
+    pub fn to_png(&self) -> AdbResult<Vec<u8>> {
+        use image::ImageEncoder;
+        let mut out = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut out)
+            .write_image(
+                &self.pixels,
+                self.width,
+                self.height,
+                image::ExtendedColorType::Rgb8,
+            )
+            .map_err(|e| AdbError::FramebufferToPngFailed {
+                description: e.to_string(),
+            })?;
+        Ok(out)
+    }
+}
+
+/// Width and height of `region` after clipping it to a `width x height` screen
+fn clip_region(
+    width: u32,
+    height: u32,
+    (x, y, w, h): (u32, u32, u32, u32),
+) -> AdbResult<(u32, u32)> {
+    if w == 0 || h == 0 || x >= width || y >= height {
+        return Err(AdbError::RegionOutOfBounds {
+            x,
+            y,
+            width: w,
+            height: h,
+            screen_width: width,
+            screen_height: height,
+        });
+    }
+    Ok((w.min(width - x), h.min(height - y)))
+}
+
+/// How long automation stays paused after a human touch, unless `[touch]` says otherwise
+pub const DEFAULT_TOUCH_TIMEOUT_SECS: u64 = 30;
+
+/// What `AdbClient::run_shell` captured from a device command
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShellOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+impl ShellOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// Display rotation, Android's `ROTATION_0` .. `ROTATION_270` counted from the
+/// natural orientation (portrait on phones)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Orientation {
+    #[default]
+    Portrait,
+    /// Turned a quarter counter-clockwise
+    Landscape,
+    ReversePortrait,
+    /// Turned a quarter clockwise
+    ReverseLandscape,
+}
+
+impl Orientation {
+    /// Orientation for Android rotation `0..=3`
+    pub fn from_rotation(rotation: u32) -> Option<Self> {
+        match rotation {
+            0 => Some(Self::Portrait),
+            1 => Some(Self::Landscape),
+            2 => Some(Self::ReversePortrait),
+            3 => Some(Self::ReverseLandscape),
+            _ => None,
+        }
+    }
+
+    /// Quarter turns from the natural orientation, Android's rotation number
+    pub fn rotation(self) -> u32 {
+        self as u32
+    }
+
+    pub fn is_landscape(self) -> bool {
+        self.rotation() % 2 == 1
+    }
+
+    /// Screen size in this orientation for a `natural` (`wm size`) size
+    pub fn screen_size(self, (width, height): (u32, u32)) -> (u32, u32) {
+        if self.is_landscape() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Move `point`, given in this orientation, to the same spot on the glass
+    /// as seen in `to`; `natural` is the `wm size` screen size
+    pub fn remap(self, to: Orientation, (x, y): (u32, u32), natural: (u32, u32)) -> (u32, u32) {
+        let (mut width, mut height) = self.screen_size(natural);
+        let (mut x, mut y) = (
+            x.min(width.saturating_sub(1)),
+            y.min(height.saturating_sub(1)),
+        );
+        // Each step turns the picture a quarter counter-clockwise: the right edge becomes the top
+        for _ in 0..(to.rotation() + 4 - self.rotation()) % 4 {
+            (x, y) = (y, width - 1 - x);
+            (width, height) = (height, width);
+        }
+        (x, y)
+    }
+}
+
+impl std::fmt::Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Portrait => "portrait",
+            Self::Landscape => "landscape",
+            Self::ReversePortrait => "reverse portrait",
+            Self::ReverseLandscape => "reverse landscape",
+        })
+    }
+}
+
 // Touch activity monitoring state
 #[derive(Debug, Clone)]
 pub struct TouchActivityState {
@@ -137,6 +406,25 @@ pub trait AdbClient: Send + Sync {
         })
     }
 
+    /// Raw RGB capture for template matching; skips the PNG encode/decode round trip
+    /// on backends that can read the framebuffer directly
+    async fn screen_capture_rgb(&self) -> AdbResult<RgbFrame> {
+        let bytes = self.screen_capture_bytes().await?;
+        RgbFrame::from_png(&bytes)
+    }
+
+    /// Capture only the `x,y width x height` area of the screen (clipped at the edge),
+    /// for checks on a small part of the UI
+    async fn screen_capture_region(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> AdbResult<RgbFrame> {
+        self.screen_capture_rgb().await?.crop((x, y, width, height))
+    }
+
     async fn tap(&self, x: u32, y: u32) -> AdbResult<()>;
     async fn swipe(
         &self,
@@ -146,8 +434,78 @@ pub trait AdbClient: Send + Sync {
         y2: u32,
         duration: Option<u32>,
     ) -> AdbResult<()>;
+    async fn send_text(&self, text: &str) -> AdbResult<()>;
+    async fn key_event(&self, keycode: u32) -> AdbResult<()>;
+    async fn long_press(&self, x: u32, y: u32, duration_ms: u32) -> AdbResult<()>;
+    /// Move several fingers at once, each through its own waypoints, over `duration_ms`
+    async fn multi_swipe(&self, paths: &[TouchPath], duration_ms: u32) -> AdbResult<()>;
+
+    /// Press at `from`, hold still for `hold_ms`, move to `to` over `move_ms` and
+    /// release (drag-and-drop of inventory items and the like)
+    async fn drag(
+        &self,
+        from: (u32, u32),
+        to: (u32, u32),
+        hold_ms: u32,
+        move_ms: u32,
+    ) -> AdbResult<()>;
+
+    /// Two-finger pinch centered on `(cx, cy)`; fingers start `start_distance` apart
+    /// and end `end_distance` apart (end > start zooms in)
+    async fn pinch(
+        &self,
+        cx: u32,
+        cy: u32,
+        start_distance: u32,
+        end_distance: u32,
+        duration_ms: u32,
+    ) -> AdbResult<()> {
+        let paths = pinch_paths(cx, cy, start_distance, end_distance);
+        self.multi_swipe(&paths, duration_ms).await
+    }
     async fn get_device_ip(&self) -> AdbResult<String>;
 
+    // App management; `package` is e.g. `com.example.game`
+    async fn install_apk(&self, apk_path: &std::path::Path) -> AdbResult<()>;
+    async fn uninstall(&self, package: &str) -> AdbResult<()>;
+    /// Launch `package/activity`, or the launcher activity of a bare `package`
+    async fn start_activity(&self, component: &str) -> AdbResult<()>;
+    async fn force_stop(&self, package: &str) -> AdbResult<()>;
+    async fn is_app_foreground(&self, package: &str) -> AdbResult<bool>;
+
+    // Files over the ADB sync protocol, e.g. templates to `/sdcard/...` or save files back
+    async fn push(
+        &self,
+        local: &std::path::Path,
+        remote: &str,
+        progress: Option<ProgressCallback>,
+    ) -> AdbResult<()>;
+    async fn pull(
+        &self,
+        remote: &str,
+        local: &std::path::Path,
+        progress: Option<ProgressCallback>,
+    ) -> AdbResult<()>;
+
+    /// Current display rotation; `screen_dimensions` stays the natural size
+    async fn orientation(&self) -> AdbResult<Orientation>;
+
+    /// Run any shell command line (`args` joined by spaces, so pipes work),
+    /// killed on the device once `timeout` passes
+    async fn run_shell(&self, args: &[&str], timeout: Duration) -> AdbResult<ShellOutput>;
+
+    /// Elements on screen from `uiautomator dump` (resource-id, text, bounds), outermost first
+    async fn dump_ui_hierarchy(&self) -> AdbResult<Vec<UiNode>> {
+        let output = self
+            .run_shell(&[UI_DUMP_COMMAND], Duration::from_secs(20))
+            .await?;
+        parse_ui_hierarchy(&output.stdout)
+    }
+
+    /// Text on the device clipboard, empty when it holds none
+    async fn get_clipboard(&self) -> AdbResult<String>;
+    async fn set_clipboard(&self, text: &str) -> AdbResult<()>;
+
     // Touch activity monitoring methods
     async fn is_human_touching(&self) -> bool;
     async fn get_touch_timeout_remaining(&self) -> Option<u64>;
@@ -155,9 +513,15 @@ pub trait AdbClient: Send + Sync {
     async fn register_touch_activity(&self) -> AdbResult<()>; // NEW: Marks touch to pause automation
     async fn start_touch_monitoring(&self) -> AdbResult<()>;
     async fn stop_touch_monitoring(&self) -> AdbResult<()>;
+    /// Stop background work and release the device
+    async fn shutdown(&mut self) -> AdbResult<()>;
 
     fn screen_dimensions(&self) -> (u32, u32);
     fn device_name(&self) -> &str;
+    /// `Device::key` of this device: its USB serial when known, else its name
+    fn device_key(&self) -> &str {
+        self.device_name()
+    }
     fn transport_id(&self) -> Option<u32>; // new optional shell-specific identifier
 }
 
@@ -165,4 +529,34 @@ pub trait AdbClient: Send + Sync {
 pub struct Device {
     pub name: String,
     pub transport_id: Option<String>,
+    /// USB serial number, when the device could be opened to read it
+    pub serial: Option<String>,
+}
+
+impl Device {
+    /// Tells apart two phones of the same model: the serial, else the name
+    pub fn key(&self) -> &str {
+        self.serial.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionType {
+    Usb,
+    Wifi,
+}
+
+/// A connected device as listed by `devices` (and `devices --json`)
+#[derive(Debug, PartialEq, Serialize, Clone)]
+pub struct DeviceDetails {
+    pub name: String,
+    pub serial: Option<String>,
+    /// `ro.product.model`, e.g. "Pixel 7"
+    pub model: Option<String>,
+    /// `ro.build.version.release`, e.g. "14"
+    pub android_version: Option<String>,
+    pub screen_width: u32,
+    pub screen_height: u32,
+    pub connection: ConnectionType,
 }
@@ -0,0 +1,64 @@
+// Accessibility events from `uiautomator events`, so automation can capture as soon
+// as the foreground UI changes instead of waiting for the next timed screenshot.
+// The command streams until killed; it is run for a short window at a time under
+// `timeout`, which keeps the device busy for that window.
+use super::backend::AdbBackend;
+use super::error::AdbResult;
+use std::time::Duration;
+
+/// A new window or dialog came to the front
+pub const WINDOW_STATE_CHANGED: &str = "TYPE_WINDOW_STATE_CHANGED";
+/// Something inside a window changed; frequent while anything animates
+pub const WINDOW_CONTENT_CHANGED: &str = "TYPE_WINDOW_CONTENT_CHANGED";
+/// Windows were added, removed or moved
+pub const WINDOWS_CHANGED: &str = "TYPE_WINDOWS_CHANGED";
+
+/// One line of `uiautomator events` output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UiEvent {
+    /// e.g. `TYPE_WINDOW_STATE_CHANGED`
+    pub event_type: String,
+    pub package: Option<String>,
+}
+
+impl UiEvent {
+    /// Window state changes, plus content changes when `content` is set
+    pub fn is_ui_change(&self, content: bool) -> bool {
+        match self.event_type.as_str() {
+            WINDOW_STATE_CHANGED | WINDOWS_CHANGED => true,
+            WINDOW_CONTENT_CHANGED => content,
+            _ => false,
+        }
+    }
+}
+
+/// `Name: value` field of an `AccessibilityEvent` line (fields end in `; `)
+fn event_field<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let start = line.find(&format!("{}: ", name))? + name.len() + 2;
+    let value = line[start..].split(';').next()?.trim();
+    (!value.is_empty() && value != "null").then_some(value)
+}
+
+/// Events in `uiautomator events` output; other lines (banner, errors) are skipped
+pub fn parse_ui_events(output: &str) -> Vec<UiEvent> {
+    output
+        .lines()
+        .filter_map(|line| {
+            Some(UiEvent {
+                event_type: event_field(line, "EventType")?.to_string(),
+                package: event_field(line, "PackageName").map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+impl AdbBackend {
+    /// UI events the device reports during the next `listen` (whole seconds, at least one)
+    pub async fn ui_events(&self, listen: Duration) -> AdbResult<Vec<UiEvent>> {
+        let seconds = listen.as_secs().max(1).to_string();
+        let output = self
+            .shell(&["timeout", &seconds, "uiautomator", "events"])
+            .await?;
+        Ok(parse_ui_events(&output))
+    }
+}
@@ -0,0 +1,150 @@
+// UI elements from `uiautomator dump`, so actions can target a button by its
+// resource-id or text instead of a pixel position. Works for regular apps;
+// games usually draw into a single surface without elements.
+use super::error::{AdbError, AdbResult};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Dump command; `/dev/tty` prints the XML instead of writing a file
+pub const UI_DUMP_COMMAND: &str = "uiautomator dump /dev/tty";
+
+/// One `<node>` of the dump; attributes the device leaves out stay empty
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UiNode {
+    /// e.g. `com.example.app:id/ok_button`
+    pub resource_id: String,
+    pub text: String,
+    pub content_desc: String,
+    pub class: String,
+    pub package: String,
+    /// `x, y, width, height` on screen
+    pub bounds: (u32, u32, u32, u32),
+    pub clickable: bool,
+    pub enabled: bool,
+}
+
+impl UiNode {
+    pub fn center(&self) -> (u32, u32) {
+        let (x, y, width, height) = self.bounds;
+        (x + width / 2, y + height / 2)
+    }
+}
+
+/// The element an action targets; every field that is set has to match
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UiSelector {
+    /// Full `com.example.app:id/ok_button` or just `ok_button`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_id: Option<String>,
+    /// Exact text, or content description for icon buttons
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl UiSelector {
+    pub fn is_empty(&self) -> bool {
+        self.resource_id.is_none() && self.text.is_none()
+    }
+
+    pub fn matches(&self, node: &UiNode) -> bool {
+        let id_matches = self.resource_id.as_ref().is_none_or(|id| {
+            node.resource_id == *id
+                || node
+                    .resource_id
+                    .rsplit_once(":id/")
+                    .is_some_and(|(_, short)| short == id)
+        });
+        let text_matches = self
+            .text
+            .as_ref()
+            .is_none_or(|text| node.text == *text || node.content_desc == *text);
+        !self.is_empty() && id_matches && text_matches
+    }
+
+    /// First matching element that is on screen
+    pub fn find<'a>(&self, nodes: &'a [UiNode]) -> Option<&'a UiNode> {
+        nodes
+            .iter()
+            .find(|node| node.bounds.2 > 0 && node.bounds.3 > 0 && self.matches(node))
+    }
+}
+
+impl fmt::Display for UiSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.resource_id, &self.text) {
+            (Some(id), Some(text)) => write!(f, "#{} \"{}\"", id, text),
+            (Some(id), None) => write!(f, "#{}", id),
+            (None, Some(text)) => write!(f, "\"{}\"", text),
+            (None, None) => f.write_str("(no selector)"),
+        }
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+/// `name="value"` attribute of a tag's attribute text
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let len = tag[start..].find('"')?;
+    Some(unescape(&tag[start..start + len]))
+}
+
+/// `[left,top][right,bottom]` as `x, y, width, height`
+fn parse_bounds(bounds: &str) -> Option<(u32, u32, u32, u32)> {
+    let numbers: Vec<u32> = bounds
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    match numbers[..] {
+        [left, top, right, bottom] => Some((
+            left,
+            top,
+            right.saturating_sub(left),
+            bottom.saturating_sub(top),
+        )),
+        _ => None,
+    }
+}
+
+/// Elements in `uiautomator dump` output, outermost first
+pub fn parse_ui_hierarchy(output: &str) -> AdbResult<Vec<UiNode>> {
+    if !output.contains("<hierarchy") {
+        return Err(AdbError::AppCommandFailed {
+            command: UI_DUMP_COMMAND.into(),
+            output: output.trim().to_string(),
+        });
+    }
+    let mut nodes = Vec::new();
+    let mut rest = output;
+    while let Some(start) = rest.find("<node ") {
+        // Attribute values are escaped, so the first '>' closes the tag
+        let tag = &rest[start + 5..];
+        let end = tag.find('>').unwrap_or(tag.len());
+        let (tag, after) = tag.split_at(end);
+        let text = |name: &str| attribute(tag, name).unwrap_or_default();
+        nodes.push(UiNode {
+            resource_id: text("resource-id"),
+            text: text("text"),
+            content_desc: text("content-desc"),
+            class: text("class"),
+            package: text("package"),
+            bounds: attribute(tag, "bounds")
+                .and_then(|bounds| parse_bounds(&bounds))
+                .unwrap_or_default(),
+            clickable: text("clickable") == "true",
+            enabled: text("enabled") == "true",
+        });
+        rest = after;
+    }
+    Ok(nodes)
+}
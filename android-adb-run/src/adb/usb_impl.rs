@@ -1,16 +1,77 @@
-use super::error::{AdbError, AdbResult};
-use super::types::{AdbClient, Device, TouchActivityMonitor, TouchActivityState, UsbCommand};
-use adb_client::{ADBDeviceExt, ADBUSBDevice};
+use super::discovery::{enumerate_usb_devices, find_usb_device, is_tcp_address, parse_vid_pid};
+use super::error::{AdbError, AdbErrorKind, AdbResult, message_kind};
+use super::gesture::{
+    TouchAxes, TouchPath, drag_script, parse_touch_axes, sendevent_script, validate_paths,
+};
+use super::transfer::{ProgressCallback, ProgressSink};
+use super::types::{
+    AdbClient, ConnectionType, DEFAULT_TOUCH_TIMEOUT_SECS, Device, DeviceDetails, Orientation,
+    RgbFrame, ShellOutput, TouchActivityMonitor, TouchActivityState, UsbCommand,
+};
+use adb_client::{ADBDeviceExt, ADBTcpDevice, ADBUSBDevice, USBTransport};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{Mutex, RwLock, mpsc};
 
+/// Any adb_client device (USB or TCP); all traffic still goes through the command queue
+pub type AdbTransport = Box<dyn ADBDeviceExt + Send>;
+
+/// Where the ADB private key is kept instead of `~/.android/adbkey`
+pub const ADB_KEY_ENV: &str = "ADB_KEY_PATH";
+
+/// `ADB_KEY_PATH`, or `~/.android/adbkey` shared with the Android SDK tools
+pub fn adb_key_path() -> AdbResult<PathBuf> {
+    if let Some(path) = std::env::var_os(ADB_KEY_ENV).filter(|path| !path.is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
+    homedir::my_home()
+        .ok()
+        .flatten()
+        .map(|home| home.join(".android").join("adbkey"))
+        .ok_or(AdbError::HomeDirectoryNotFound)
+}
+
+/// Create an RSA key at `path` when there is none, so the phone is shown the
+/// same key on every connection and "Always allow from this computer" sticks
+pub fn ensure_adb_key(path: &Path) -> AdbResult<()> {
+    if path.exists() {
+        return Ok(());
+    }
+    use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+
+    let failed = |description: String| AdbError::KeyGenerationFailed {
+        path: path.to_path_buf(),
+        description,
+    };
+    let key = rsa::RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 2048)
+        .map_err(|e| failed(e.to_string()))?;
+    let pem = key
+        .to_pkcs8_pem(LineEnding::LF)
+        .map_err(|e| failed(e.to_string()))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| failed(e.to_string()))?;
+    }
+    std::fs::write(path, pem.as_bytes()).map_err(|e| failed(e.to_string()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| failed(e.to_string()))?;
+    }
+    log::info!("🔑 Created ADB key {}", path.display());
+    Ok(())
+}
+
 pub struct UsbAdb {
     device: Device,
-    usb_device: Arc<Mutex<ADBUSBDevice>>,
+    usb_device: Arc<Mutex<AdbTransport>>,
     screen_x: u32,
     screen_y: u32,
     touch_monitor: TouchActivityMonitor,
+    // Touchscreen axes for multi-touch gestures, read on first use
+    touch_axes: Mutex<Option<TouchAxes>>,
     monitoring_task: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
 
     // Unified command queue for all USB operations
@@ -54,222 +115,26 @@ impl UsbAdb {
             })?
     }
 
-    async fn monitor_touch_activity_loop(
-        touch_monitor: TouchActivityMonitor,
-        usb_device: Arc<Mutex<ADBUSBDevice>>,
-        usb_queue_tx: mpsc::Sender<UsbCommand>,
-    ) -> AdbResult<()> {
-        let event_device = match Self::find_touch_event_device(usb_device).await {
-            Ok(device) => device,
-            Err(e) => {
-                log::warn!(
-                    "Falling back to default touch device /dev/input/event2: {}",
-                    e
-                );
-                "/dev/input/event2".to_string()
-            }
-        };
-        Self::stream_touch_events_polling(usb_queue_tx, &event_device, touch_monitor).await
-    }
-
-    async fn stream_touch_events_polling(
-        usb_queue_tx: mpsc::Sender<UsbCommand>,
-        event_device: &str,
-        touch_monitor: TouchActivityMonitor,
-    ) -> AdbResult<()> {
-        // Poll for touch events using the USB command queue
-        // Each poll uses "timeout 0.3 getevent -c 1" which blocks for max 300ms
-        // Polling every 1 second means we check for touches periodically without
-        // overloading the USB command queue with touch check requests
-        let poll_interval = Duration::from_secs(1);
-
-        log::info!("Touch monitoring started for device: {}", event_device);
-
-        loop {
-            // Check if we should stop monitoring
-            if !touch_monitor.read().await.is_monitoring {
-                log::info!("Touch monitoring stopped");
-                break;
-            }
-
-            // Clear expired touch activity
-            if touch_monitor.read().await.has_activity_expired() {
-                touch_monitor.write().await.last_touch_time = None;
-            }
-
-            // Poll for touch events through the USB queue
-            let (tx, rx) = tokio::sync::oneshot::channel();
-            let send_result = usb_queue_tx
-                .send(UsbCommand::CheckTouchEvent {
-                    event_device: event_device.to_string(),
-                    response_tx: tx,
-                })
-                .await;
-
-            if send_result.is_err() {
-                log::warn!("Touch monitor: USB queue closed");
-                break;
-            }
-
-            // Wait for the result with a timeout
-            match tokio::time::timeout(Duration::from_secs(2), rx).await {
-                Ok(Ok(Ok(touch_detected))) => {
-                    if touch_detected {
-                        log::info!("Human touch detected - marking activity");
-                        touch_monitor.write().await.mark_touch_activity();
-                    }
-                }
-                Ok(Ok(Err(e))) => {
-                    log::debug!("Touch check failed: {}", e);
-                    // Continue monitoring despite errors
-                }
-                Ok(Err(_)) => {
-                    log::warn!("Touch monitor: channel closed");
-                    break;
-                }
-                Err(_) => {
-                    log::warn!("Touch check timed out");
-                    // Continue monitoring
-                }
-            }
-
-            // Wait before next poll
-            tokio::time::sleep(poll_interval).await;
-        }
-        Ok(())
-    }
-
-    fn is_touch_event_line(line: &str) -> bool {
-        line.contains("ABS_MT")
-            || line.contains("BTN_TOUCH")
-            || line.contains("BTN_TOOL_FINGER")
-            || line.contains("ABS_X")
-            || line.contains("ABS_Y")
-            || (line.contains("0003") && (line.contains("0035") || line.contains("0036")))
-    }
-
-    async fn find_touch_event_device(usb_device: Arc<Mutex<ADBUSBDevice>>) -> AdbResult<String> {
-        let mut out = Vec::new();
-        usb_device
-            .lock()
-            .await
-            .shell_command(&["getevent", "-p"], &mut out)
-            .map_err(|e| AdbError::ShellCommandFailed {
-                command: "getevent -p".into(),
-                source: e,
-            })?;
-
-        let output = String::from_utf8_lossy(&out);
-        let mut current_device: Option<String> = None;
-        let mut has_touch_events = false;
-        let mut best_device: Option<String> = None;
-
-        for line in output.lines() {
-            if line.starts_with("add device") {
-                if has_touch_events {
-                    best_device = current_device.clone();
-                }
-                if let Some(path_start) = line.find("/dev/input/event") {
-                    current_device = Some(line[path_start..].to_string());
-                    has_touch_events = false;
-                }
-            } else if line.contains("0035") || line.contains("0036") {
-                has_touch_events = true;
-            }
-        }
-        if has_touch_events {
-            best_device = current_device;
-        }
-
-        best_device.ok_or(AdbError::NoTouchDeviceFound)
-    }
-}
-
-impl AdbClient for UsbAdb {
-    async fn list_devices() -> AdbResult<Vec<Device>> {
-        let list_future = tokio::task::spawn_blocking(|| match adb_client::search_adb_devices() {
-            Ok(Some((vendor_id, product_id))) => Ok(vec![Device {
-                name: format!("{:04x}:{:04x}", vendor_id, product_id),
-                transport_id: None,
-            }]),
-            Ok(None) => Ok(vec![]),
-            Err(e) => Err(AdbError::DeviceEnumerationFailed { source: e }),
-        });
-
-        match tokio::time::timeout(Duration::from_secs(2), list_future).await {
-            Ok(Ok(result)) => result,
-            Ok(Err(e)) => Err(AdbError::from(e)),
-            Err(_) => Err(AdbError::Timeout {
-                duration: Duration::from_secs(2),
-                description: "Device enumeration".into(),
-            }),
-        }
-    }
-
-    async fn new_with_device(device_name: &str) -> AdbResult<Self> {
-        let key_path = homedir::my_home()
-            .ok()
-            .flatten()
-            .map(|home| home.join(".android").join("adbkey"))
-            .ok_or(AdbError::HomeDirectoryNotFound)?;
-
-        if !key_path.exists() {
-            return Err(AdbError::KeyNotFound { path: key_path });
-        }
-
-        let mut usb_device = None;
-        let max_attempts = 5;
-
-        for _ in 1..=max_attempts {
-            let key_path_clone = key_path.clone();
-            let usb_future = tokio::task::spawn_blocking(move || {
-                ADBUSBDevice::autodetect_with_custom_private_key(key_path_clone)
-            });
-
-            match tokio::time::timeout(Duration::from_secs(10), usb_future).await {
-                Ok(Ok(device_result)) => match device_result {
-                    Ok(device) => {
-                        usb_device = Some(device);
-                        break;
-                    }
-                    Err(e) => {
-                        log::warn!("Connection attempt failed: {}. Retrying...", e);
-                        tokio::time::sleep(Duration::from_secs(1)).await;
-                    }
-                },
-                Ok(Err(e)) => return Err(AdbError::from(e)),
-                Err(_) => {
-                    return Err(AdbError::ConnectionTimeout {
-                        duration: Duration::from_secs(10),
-                    });
-                }
-            }
-        }
-
-        let mut usb_device = usb_device.ok_or_else(|| AdbError::ConnectionFailed {
-            source: adb_client::RustADBError::IOError(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "No USB devices found after retries",
-            )),
-        })?;
-
+    /// Finish connecting over an already opened transport (USB or TCP):
+    /// validate authorization, read the screen size and start the command processor.
+    async fn from_transport(device: Device, mut transport: AdbTransport) -> AdbResult<Self> {
         // Step 3.5: Validate authentication with timeout
         let auth_timeout = Duration::from_secs(30);
         let auth_start = std::time::Instant::now();
         loop {
             let mut test_output = Vec::new();
-            match usb_device.shell_command(&["echo", "test"], &mut test_output) {
+            match transport.shell_command(&["echo", "test"], &mut test_output) {
                 Ok(_) => {
                     let output = String::from_utf8_lossy(&test_output);
                     if output.trim() == "test" {
-                        println!("✅ Authenticated");
+                        log::info!("✅ Authenticated");
                         break;
                     } else {
-                        println!("⚠️ Unexpected echo response: {}", output.trim());
+                        log::warn!("⚠️ Unexpected echo response: {}", output.trim());
                     }
                 }
                 Err(e) => {
-                    println!("⚠️ Authorization check failed: {}", e);
+                    log::warn!("⚠️ Authorization check failed: {}", e);
                 }
             }
             if auth_start.elapsed() > auth_timeout {
@@ -280,15 +145,15 @@ impl AdbClient for UsbAdb {
 
         let (dummy_tx, _) = mpsc::channel(1);
         let mut tmp = UsbAdb {
-            device: Device {
-                name: device_name.to_string(),
-                transport_id: None,
-            },
+            device,
             debug_enabled: false,
-            usb_device: Arc::new(Mutex::new(usb_device)),
+            usb_device: Arc::new(Mutex::new(transport)),
             screen_x: 0,
             screen_y: 0,
-            touch_monitor: Arc::new(RwLock::new(TouchActivityState::new(30))),
+            touch_monitor: Arc::new(RwLock::new(TouchActivityState::new(
+                DEFAULT_TOUCH_TIMEOUT_SECS,
+            ))),
+            touch_axes: Mutex::new(None),
             monitoring_task: Arc::new(Mutex::new(None)),
             usb_queue_tx: dummy_tx,
             usb_processor_handle: None,
@@ -306,14 +171,14 @@ impl AdbClient for UsbAdb {
 
         // Unified USB command processor - serializes ALL USB operations
         let processor = tokio::spawn(async move {
-            println!("🔧 USB command processor started");
+            log::info!("🔧 USB command processor started");
             while let Some(cmd) = rx.recv().await {
                 let mut dev = usb_clone.lock().await;
 
                 match cmd {
                     UsbCommand::Tap { x, y, response_tx } => {
                         if x > screen_x || y > screen_y {
-                            println!("❌ Tap out of bounds: ({},{})", x, y);
+                            log::error!("❌ Tap out of bounds: ({},{})", x, y);
                             let _ = response_tx.send(Err(AdbError::TapOutOfBounds { x, y }));
                             continue;
                         }
@@ -333,12 +198,14 @@ impl AdbClient for UsbAdb {
                                     e,
                                 );
                                 if err.is_protocol_desync() {
-                                    eprintln!(
+                                    log::error!(
                                         "❌ Tap failed (PROTOCOL DESYNC - reconnection needed): {} ({},{})",
-                                        err, x, y
+                                        err,
+                                        x,
+                                        y
                                     );
                                 } else {
-                                    eprintln!("❌ Tap failed: {} ({},{})", err, x, y);
+                                    log::error!("❌ Tap failed: {} ({},{})", err, x, y);
                                 }
                                 Err(err)
                             }
@@ -370,7 +237,7 @@ impl AdbClient for UsbAdb {
                             &mut out,
                         ) {
                             Ok(_) => {
-                                println!("✅ Swipe executed");
+                                log::info!("✅ Swipe executed");
                                 Ok(())
                             }
                             Err(e) => {
@@ -379,12 +246,12 @@ impl AdbClient for UsbAdb {
                                     e,
                                 );
                                 if err.is_protocol_desync() {
-                                    eprintln!(
+                                    log::error!(
                                         "❌ Swipe failed (PROTOCOL DESYNC - reconnection needed): {}",
                                         err
                                     );
                                 } else {
-                                    eprintln!("❌ Swipe failed: {}", err);
+                                    log::error!("❌ Swipe failed: {}", err);
                                 }
                                 Err(err)
                             }
@@ -395,40 +262,58 @@ impl AdbClient for UsbAdb {
                     UsbCommand::Screenshot { response_tx } => {
                         let result = match dev.framebuffer_bytes() {
                             Ok(data) => Ok(data),
-                            Err(fb_err) => {
-                                // Framebuffer failed, try screencap fallback
-                                let mut out = Vec::new();
-                                match dev.shell_command(&["screencap", "-p"], &mut out) {
-                                    Ok(_) => Ok(out),
-                                    Err(e) => {
-                                        let err = AdbError::from_adb_error_with_desync_check(
-                                            "screencap -p".into(),
-                                            e,
-                                        );
-                                        if err.is_protocol_desync() {
-                                            eprintln!(
-                                                "❌ Screenshot failed (PROTOCOL DESYNC - reconnection needed): {}",
-                                                err
-                                            );
-                                        }
-                                        // Also check if framebuffer error was a desync
-                                        let fb_err_str = fb_err.to_string();
-                                        if fb_err_str.contains("CLSE")
-                                            || fb_err_str.contains("no write endpoint")
-                                        {
-                                            Err(AdbError::ProtocolDesync {
-                                                description: "Framebuffer and screencap both failed with protocol errors".to_string(),
-                                            })
-                                        } else {
-                                            Err(err)
-                                        }
-                                    }
-                                }
+                            Err(fb_err) => Self::screencap_fallback(&mut dev, fb_err),
+                        };
+                        let _ = response_tx.send(result);
+                    }
+
+                    UsbCommand::ScreenshotRgb { response_tx } => {
+                        // Raw framebuffer pixels, no PNG encode on the way out
+                        let result = match dev.framebuffer_inner() {
+                            Ok(img) => {
+                                Ok(RgbFrame::from_rgba(img.width(), img.height(), img.as_raw()))
                             }
+                            Err(fb_err) => Self::screencap_fallback(&mut dev, fb_err)
+                                .and_then(|png| RgbFrame::from_png(&png)),
+                        };
+                        let _ = response_tx.send(result);
+                    }
+
+                    UsbCommand::ScreenshotRegion {
+                        x,
+                        y,
+                        width,
+                        height,
+                        response_tx,
+                    } => {
+                        // Crop on the raw framebuffer; only the region is converted
+                        let region = (x, y, width, height);
+                        let result = match dev.framebuffer_inner() {
+                            Ok(img) => RgbFrame::from_rgba_region(
+                                img.width(),
+                                img.height(),
+                                img.as_raw(),
+                                region,
+                            ),
+                            Err(fb_err) => Self::screencap_fallback(&mut dev, fb_err)
+                                .and_then(|png| RgbFrame::from_png(&png))
+                                .and_then(|frame| frame.crop(region)),
                         };
                         let _ = response_tx.send(result);
                     }
 
+                    UsbCommand::Shell { args, response_tx } => {
+                        let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+                        let mut out = Vec::new();
+                        let result = dev
+                            .shell_command(&arg_refs, &mut out)
+                            .map(|_| String::from_utf8_lossy(&out).into_owned())
+                            .map_err(|e| {
+                                AdbError::from_adb_error_with_desync_check(args.join(" "), e)
+                            });
+                        let _ = response_tx.send(result);
+                    }
+
                     UsbCommand::CheckTouchEvent {
                         event_device,
                         response_tx,
@@ -456,6 +341,75 @@ impl AdbClient for UsbAdb {
                             });
                         let _ = response_tx.send(result);
                     }
+
+                    UsbCommand::Install {
+                        apk_path,
+                        response_tx,
+                    } => {
+                        log::info!("📦 Installing {}", apk_path.display());
+                        let result =
+                            dev.install(&apk_path)
+                                .map_err(|source| AdbError::InstallFailed {
+                                    path: apk_path.clone(),
+                                    source,
+                                });
+                        let _ = response_tx.send(result);
+                    }
+
+                    UsbCommand::Push {
+                        local,
+                        remote,
+                        mut progress,
+                        response_tx,
+                    } => {
+                        log::info!("📤 Pushing {} to {}", local.display(), remote);
+                        let result = std::fs::File::open(&local)
+                            .map_err(|source| AdbError::LocalFileFailed {
+                                path: local.clone(),
+                                source,
+                            })
+                            .and_then(|file| {
+                                let mut reader = progress.reader(std::io::BufReader::new(file));
+                                dev.push(&mut reader, &remote).map_err(|source| {
+                                    AdbError::FileTransferFailed {
+                                        path: remote.clone(),
+                                        source,
+                                    }
+                                })
+                            });
+                        let _ = response_tx.send(result);
+                    }
+
+                    UsbCommand::Pull {
+                        remote,
+                        local,
+                        mut progress,
+                        response_tx,
+                    } => {
+                        log::info!("📥 Pulling {} to {}", remote, local.display());
+                        let local_error = |source| AdbError::LocalFileFailed {
+                            path: local.clone(),
+                            source,
+                        };
+                        let result =
+                            std::fs::File::create(&local)
+                                .map_err(local_error)
+                                .and_then(|file| {
+                                    let mut writer = progress.writer(std::io::BufWriter::new(file));
+                                    dev.pull(&remote, &mut writer).map_err(|source| {
+                                        AdbError::FileTransferFailed {
+                                            path: remote.clone(),
+                                            source,
+                                        }
+                                    })?;
+                                    std::io::Write::flush(&mut writer).map_err(local_error)
+                                });
+                        // Don't leave half a save file behind
+                        if result.is_err() {
+                            let _ = std::fs::remove_file(&local);
+                        }
+                        let _ = response_tx.send(result);
+                    }
                 }
                 drop(dev);
             }
@@ -470,6 +424,7 @@ impl AdbClient for UsbAdb {
             screen_x: sx,
             screen_y: sy,
             touch_monitor: tmp.touch_monitor,
+            touch_axes: tmp.touch_axes,
             monitoring_task: tmp.monitoring_task,
             usb_queue_tx: tmp.usb_queue_tx,
             usb_processor_handle: tmp.usb_processor_handle,
@@ -477,31 +432,336 @@ impl AdbClient for UsbAdb {
         })
     }
 
-    async fn screen_capture_bytes(&self) -> AdbResult<Vec<u8>> {
-        let (tx, rx) = tokio::sync::oneshot::channel();
-
-        self.usb_queue_tx
-            .send(UsbCommand::Screenshot { response_tx: tx })
-            .await
-            .map_err(|_| AdbError::ChannelClosed)?;
-
-        match tokio::time::timeout(Duration::from_secs(30), rx).await {
-            Ok(Ok(result)) => result,
-            Ok(Err(_)) => Err(AdbError::ChannelClosed),
-            Err(_) => Err(AdbError::Timeout {
-                duration: Duration::from_secs(30),
-                description: "Screenshot capture".into(),
-            }),
+    /// `screencap -p` fallback used when reading the framebuffer fails
+    fn screencap_fallback(
+        dev: &mut AdbTransport,
+        fb_err: adb_client::RustADBError,
+    ) -> AdbResult<Vec<u8>> {
+        let mut out = Vec::new();
+        match dev.shell_command(&["screencap", "-p"], &mut out) {
+            Ok(_) => Ok(out),
+            Err(e) => {
+                let err = AdbError::from_adb_error_with_desync_check("screencap -p".into(), e);
+                if err.is_protocol_desync() {
+                    log::error!(
+                        "❌ Screenshot failed (PROTOCOL DESYNC - reconnection needed): {}",
+                        err
+                    );
+                }
+                // Also check if framebuffer error was a desync
+                let fb_err = AdbError::FramebufferCaptureFailed { source: fb_err };
+                if fb_err.is_protocol_desync() {
+                    Err(AdbError::ProtocolDesync {
+                        description: "Framebuffer and screencap both failed with protocol errors"
+                            .to_string(),
+                    })
+                } else {
+                    Err(err)
+                }
+            }
         }
     }
 
-    async fn tap(&self, x: u32, y: u32) -> AdbResult<()> {
-        if x > self.screen_x || y > self.screen_y {
-            return Err(AdbError::TapOutOfBounds { x, y });
-        }
-
-        let (tx, rx) = tokio::sync::oneshot::channel();
-        self.usb_queue_tx
+    async fn monitor_touch_activity_loop(
+        touch_monitor: TouchActivityMonitor,
+        usb_device: Arc<Mutex<AdbTransport>>,
+        usb_queue_tx: mpsc::Sender<UsbCommand>,
+    ) -> AdbResult<()> {
+        let event_device = match Self::find_touch_event_device(usb_device).await {
+            Ok(device) => device,
+            Err(e) => {
+                log::warn!(
+                    "Falling back to default touch device /dev/input/event2: {}",
+                    e
+                );
+                "/dev/input/event2".to_string()
+            }
+        };
+        Self::stream_touch_events_polling(usb_queue_tx, &event_device, touch_monitor).await
+    }
+
+    async fn stream_touch_events_polling(
+        usb_queue_tx: mpsc::Sender<UsbCommand>,
+        event_device: &str,
+        touch_monitor: TouchActivityMonitor,
+    ) -> AdbResult<()> {
+        // Poll for touch events using the USB command queue
+        // Each poll uses "timeout 0.3 getevent -c 1" which blocks for max 300ms
+        // Polling every 1 second means we check for touches periodically without
+        // overloading the USB command queue with touch check requests
+        let poll_interval = Duration::from_secs(1);
+
+        log::info!("Touch monitoring started for device: {}", event_device);
+
+        loop {
+            // Check if we should stop monitoring
+            if !touch_monitor.read().await.is_monitoring {
+                log::info!("Touch monitoring stopped");
+                break;
+            }
+
+            // Clear expired touch activity
+            if touch_monitor.read().await.has_activity_expired() {
+                touch_monitor.write().await.last_touch_time = None;
+            }
+
+            // Poll for touch events through the USB queue
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let send_result = usb_queue_tx
+                .send(UsbCommand::CheckTouchEvent {
+                    event_device: event_device.to_string(),
+                    response_tx: tx,
+                })
+                .await;
+
+            if send_result.is_err() {
+                log::warn!("Touch monitor: USB queue closed");
+                break;
+            }
+
+            // Wait for the result with a timeout
+            match tokio::time::timeout(Duration::from_secs(2), rx).await {
+                Ok(Ok(Ok(touch_detected))) => {
+                    if touch_detected {
+                        log::info!("Human touch detected - marking activity");
+                        touch_monitor.write().await.mark_touch_activity();
+                    }
+                }
+                Ok(Ok(Err(e))) => {
+                    log::debug!("Touch check failed: {}", e);
+                    // Continue monitoring despite errors
+                }
+                Ok(Err(_)) => {
+                    log::warn!("Touch monitor: channel closed");
+                    break;
+                }
+                Err(_) => {
+                    log::warn!("Touch check timed out");
+                    // Continue monitoring
+                }
+            }
+
+            // Wait before next poll
+            tokio::time::sleep(poll_interval).await;
+        }
+        Ok(())
+    }
+
+    fn is_touch_event_line(line: &str) -> bool {
+        line.contains("ABS_MT")
+            || line.contains("BTN_TOUCH")
+            || line.contains("BTN_TOOL_FINGER")
+            || line.contains("ABS_X")
+            || line.contains("ABS_Y")
+            || (line.contains("0003") && (line.contains("0035") || line.contains("0036")))
+    }
+
+    async fn find_touch_event_device(usb_device: Arc<Mutex<AdbTransport>>) -> AdbResult<String> {
+        let mut out = Vec::new();
+        usb_device
+            .lock()
+            .await
+            .shell_command(&["getevent", "-p"], &mut out)
+            .map_err(|e| AdbError::ShellCommandFailed {
+                command: "getevent -p".into(),
+                source: e,
+            })?;
+
+        let output = String::from_utf8_lossy(&out);
+        let mut current_device: Option<String> = None;
+        let mut has_touch_events = false;
+        let mut best_device: Option<String> = None;
+
+        for line in output.lines() {
+            if line.starts_with("add device") {
+                if has_touch_events {
+                    best_device = current_device.clone();
+                }
+                if let Some(path_start) = line.find("/dev/input/event") {
+                    current_device = Some(line[path_start..].to_string());
+                    has_touch_events = false;
+                }
+            } else if line.contains("0035") || line.contains("0036") {
+                has_touch_events = true;
+            }
+        }
+        if has_touch_events {
+            best_device = current_device;
+        }
+
+        best_device.ok_or(AdbError::NoTouchDeviceFound)
+    }
+}
+
+impl AdbClient for UsbAdb {
+    async fn list_devices() -> AdbResult<Vec<Device>> {
+        let list_future = tokio::task::spawn_blocking(enumerate_usb_devices);
+
+        match tokio::time::timeout(Duration::from_secs(2), list_future).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(AdbError::from(e)),
+            Err(_) => Err(AdbError::Timeout {
+                duration: Duration::from_secs(2),
+                description: "Device enumeration".into(),
+            }),
+        }
+    }
+
+    async fn new_with_device(device_name: &str) -> AdbResult<Self> {
+        let key_path = adb_key_path()?;
+        ensure_adb_key(&key_path)?;
+
+        let mut usb_device = None;
+        let max_attempts = 5;
+        // Names from list_devices are "vid:pid", keys of identical phones their USB
+        // serial; anything else falls back to autodetect
+        let vid_pid = parse_vid_pid(device_name);
+        let serial = device_name.to_string();
+        let mut name = device_name.to_string();
+
+        for _ in 1..=max_attempts {
+            let key_path_clone = key_path.clone();
+            let serial = serial.clone();
+            let usb_future = tokio::task::spawn_blocking(move || match vid_pid {
+                Some((vendor_id, product_id)) => {
+                    ADBUSBDevice::new_with_custom_private_key(vendor_id, product_id, key_path_clone)
+                        .map(|device| (device, None))
+                }
+                None => match find_usb_device(&serial) {
+                    Some((found, name)) => ADBUSBDevice::new_from_transport(
+                        USBTransport::new_from_device(found),
+                        Some(key_path_clone),
+                    )
+                    .map(|device| (device, Some(name))),
+                    None => ADBUSBDevice::autodetect_with_custom_private_key(key_path_clone)
+                        .map(|device| (device, None)),
+                },
+            });
+
+            match tokio::time::timeout(Duration::from_secs(10), usb_future).await {
+                Ok(Ok(device_result)) => match device_result {
+                    Ok((device, found_name)) => {
+                        usb_device = Some(device);
+                        if let Some(found_name) = found_name {
+                            name = found_name;
+                        }
+                        break;
+                    }
+                    Err(e) if message_kind(&e.to_string()) == AdbErrorKind::Unauthorized => {
+                        return Err(AdbError::AuthorizationPending {
+                            device: device_name.to_string(),
+                        });
+                    }
+                    Err(e) => {
+                        log::warn!("Connection attempt failed: {}. Retrying...", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                },
+                Ok(Err(e)) => return Err(AdbError::from(e)),
+                // The USB handshake blocks while the phone shows "Allow USB debugging?"
+                Err(_) => {
+                    log::info!(
+                        "🔐 {} has not accepted the ADB key at {} yet",
+                        device_name,
+                        key_path.display()
+                    );
+                    return Err(AdbError::AuthorizationPending {
+                        device: device_name.to_string(),
+                    });
+                }
+            }
+        }
+
+        let usb_device = usb_device.ok_or_else(|| AdbError::ConnectionFailed {
+            source: adb_client::RustADBError::IOError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "No USB devices found after retries",
+            )),
+        })?;
+
+        // Opened by serial: keep the `vid:pid` name shown for the device
+        let serial = (name != device_name).then(|| device_name.to_string());
+        let device = Device {
+            name,
+            transport_id: None,
+            serial,
+        };
+        Self::from_transport(device, Box::new(usb_device)).await
+    }
+
+    async fn screen_capture_bytes(&self) -> AdbResult<Vec<u8>> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        self.usb_queue_tx
+            .send(UsbCommand::Screenshot { response_tx: tx })
+            .await
+            .map_err(|_| AdbError::ChannelClosed)?;
+
+        match tokio::time::timeout(Duration::from_secs(30), rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(AdbError::ChannelClosed),
+            Err(_) => Err(AdbError::Timeout {
+                duration: Duration::from_secs(30),
+                description: "Screenshot capture".into(),
+            }),
+        }
+    }
+
+    async fn screen_capture_rgb(&self) -> AdbResult<RgbFrame> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        self.usb_queue_tx
+            .send(UsbCommand::ScreenshotRgb { response_tx: tx })
+            .await
+            .map_err(|_| AdbError::ChannelClosed)?;
+
+        match tokio::time::timeout(Duration::from_secs(30), rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(AdbError::ChannelClosed),
+            Err(_) => Err(AdbError::Timeout {
+                duration: Duration::from_secs(30),
+                description: "Screenshot capture".into(),
+            }),
+        }
+    }
+
+    async fn screen_capture_region(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> AdbResult<RgbFrame> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        self.usb_queue_tx
+            .send(UsbCommand::ScreenshotRegion {
+                x,
+                y,
+                width,
+                height,
+                response_tx: tx,
+            })
+            .await
+            .map_err(|_| AdbError::ChannelClosed)?;
+
+        match tokio::time::timeout(Duration::from_secs(30), rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(AdbError::ChannelClosed),
+            Err(_) => Err(AdbError::Timeout {
+                duration: Duration::from_secs(30),
+                description: "Region capture".into(),
+            }),
+        }
+    }
+
+    async fn tap(&self, x: u32, y: u32) -> AdbResult<()> {
+        if x > self.screen_x || y > self.screen_y {
+            return Err(AdbError::TapOutOfBounds { x, y });
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.usb_queue_tx
             .send(UsbCommand::Tap {
                 x,
                 y,
@@ -551,12 +811,232 @@ impl AdbClient for UsbAdb {
         }
     }
 
+    async fn send_text(&self, text: &str) -> AdbResult<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+        let escaped = escape_input_text(text);
+        self.shell(&["input", "text", &escaped]).await.map(|_| ())
+    }
+
+    async fn key_event(&self, keycode: u32) -> AdbResult<()> {
+        self.shell(&["input", "keyevent", &keycode.to_string()])
+            .await
+            .map(|_| ())
+    }
+
+    async fn long_press(&self, x: u32, y: u32, duration_ms: u32) -> AdbResult<()> {
+        if x > self.screen_x || y > self.screen_y {
+            return Err(AdbError::TapOutOfBounds { x, y });
+        }
+        // A swipe that doesn't move is a press held for `duration_ms`
+        self.swipe(x, y, x, y, Some(duration_ms)).await
+    }
+
+    async fn multi_swipe(&self, paths: &[TouchPath], duration_ms: u32) -> AdbResult<()> {
+        let screen = (self.screen_x, self.screen_y);
+        validate_paths(paths, screen)?;
+        let axes = self.touch_axes().await?;
+        let script = sendevent_script(&axes, screen, paths, duration_ms);
+        self.shell(&["sh", "-c", &script]).await.map(|_| ())
+    }
+
+    async fn drag(
+        &self,
+        (x1, y1): (u32, u32),
+        (x2, y2): (u32, u32),
+        hold_ms: u32,
+        move_ms: u32,
+    ) -> AdbResult<()> {
+        let screen = (self.screen_x, self.screen_y);
+        validate_paths(&[vec![(x1, y1), (x2, y2)]], screen)?;
+        match self.touch_axes().await {
+            Ok(axes) => {
+                let script = drag_script(&axes, screen, (x1, y1), (x2, y2), (hold_ms, move_ms));
+                self.shell(&["sh", "-c", &script]).await.map(|_| ())
+            }
+            Err(AdbError::NoTouchDeviceFound) => {
+                // Without the touch device a slow swipe is the closest match,
+                // though it starts moving straight away instead of holding
+                log::warn!("⚠️ No touch device for drag, falling back to a slow swipe");
+                self.swipe(x1, y1, x2, y2, Some(hold_ms + move_ms)).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
     async fn get_device_ip(&self) -> AdbResult<String> {
-        Err(AdbError::UnsupportedUsbOperation {
-            operation: "get_device_ip".into(),
+        let output = self
+            .shell(&["ip", "-f", "inet", "addr", "show", "wlan0"])
+            .await?;
+        parse_wlan_ip(&output).ok_or_else(|| AdbError::UnsupportedUsbOperation {
+            operation: "get_device_ip (Wi-Fi not connected)".into(),
         })
     }
 
+    async fn install_apk(&self, apk_path: &Path) -> AdbResult<()> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.usb_queue_tx
+            .send(UsbCommand::Install {
+                apk_path: apk_path.to_path_buf(),
+                response_tx: tx,
+            })
+            .await
+            .map_err(|_| AdbError::ChannelClosed)?;
+
+        // Large APKs over USB 2 take a while
+        match tokio::time::timeout(Duration::from_secs(300), rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(AdbError::ChannelClosed),
+            Err(_) => Err(AdbError::Timeout {
+                duration: Duration::from_secs(300),
+                description: format!("Installing {}", apk_path.display()),
+            }),
+        }
+    }
+
+    async fn push(
+        &self,
+        local: &Path,
+        remote: &str,
+        progress: Option<ProgressCallback>,
+    ) -> AdbResult<()> {
+        let total = std::fs::metadata(local)
+            .map_err(|source| AdbError::LocalFileFailed {
+                path: local.to_path_buf(),
+                source,
+            })?
+            .len();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.usb_queue_tx
+            .send(UsbCommand::Push {
+                local: local.to_path_buf(),
+                remote: remote.to_string(),
+                progress: ProgressSink::new(progress, Some(total)),
+                response_tx: tx,
+            })
+            .await
+            .map_err(|_| AdbError::ChannelClosed)?;
+        self.await_transfer(rx, format!("Pushing {}", local.display()))
+            .await
+    }
+
+    async fn pull(
+        &self,
+        remote: &str,
+        local: &Path,
+        progress: Option<ProgressCallback>,
+    ) -> AdbResult<()> {
+        // Size only feeds the progress fraction, so a failed `stat` is fine
+        let total = self
+            .shell(&["stat", "-c", "%s", &shell_quote(remote)])
+            .await
+            .ok()
+            .and_then(|size| size.trim().parse().ok());
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.usb_queue_tx
+            .send(UsbCommand::Pull {
+                remote: remote.to_string(),
+                local: local.to_path_buf(),
+                progress: ProgressSink::new(progress, total),
+                response_tx: tx,
+            })
+            .await
+            .map_err(|_| AdbError::ChannelClosed)?;
+        self.await_transfer(rx, format!("Pulling {}", remote)).await
+    }
+
+    async fn uninstall(&self, package: &str) -> AdbResult<()> {
+        let package = checked_package_name(package)?;
+        let output = self.shell(&["pm", "uninstall", package]).await?;
+        if output.contains("Success") {
+            Ok(())
+        } else {
+            Err(AdbError::AppCommandFailed {
+                command: format!("pm uninstall {}", package),
+                output: output.trim().to_string(),
+            })
+        }
+    }
+
+    async fn start_activity(&self, component: &str) -> AdbResult<()> {
+        let component = checked_package_name(component)?;
+        let args: Vec<&str> = if component.contains('/') {
+            vec!["am", "start", "-n", component]
+        } else {
+            // Any launcher activity of the package, like tapping its icon
+            vec![
+                "monkey",
+                "-p",
+                component,
+                "-c",
+                "android.intent.category.LAUNCHER",
+                "1",
+            ]
+        };
+        let output = self.shell(&args).await?;
+        if output.contains("Error") || output.contains("No activities found") {
+            return Err(AdbError::AppCommandFailed {
+                command: args.join(" "),
+                output: output.trim().to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    async fn force_stop(&self, package: &str) -> AdbResult<()> {
+        let package = checked_package_name(package)?;
+        self.shell(&["am", "force-stop", package]).await.map(|_| ())
+    }
+
+    async fn is_app_foreground(&self, package: &str) -> AdbResult<bool> {
+        let package = checked_package_name(package)?;
+        let output = self.shell(&["dumpsys", "activity", "activities"]).await?;
+        Ok(parse_foreground_package(&output).as_deref() == Some(package))
+    }
+
+    async fn orientation(&self) -> AdbResult<Orientation> {
+        let output = self.shell(&["dumpsys", "input"]).await?;
+        parse_orientation(&output).ok_or(AdbError::OrientationParseFailed)
+    }
+
+    async fn run_shell(&self, args: &[&str], timeout: Duration) -> AdbResult<ShellOutput> {
+        let command = args.join(" ");
+        // The device kills the command at `timeout`; the reply gets a little longer
+        let raw = self
+            .shell_with_timeout(
+                &[&shell_capture_command(&command, timeout)],
+                timeout + Duration::from_secs(5),
+            )
+            .await?;
+        shell_capture_result(command, &raw, timeout)
+    }
+
+    async fn get_clipboard(&self) -> AdbResult<String> {
+        let output = self
+            .shell(&["cmd", "clipboard", "get-primary-clip"])
+            .await?;
+        parse_clipboard(&output).ok_or_else(|| AdbError::AppCommandFailed {
+            command: "cmd clipboard get-primary-clip".into(),
+            output: output.trim().to_string(),
+        })
+    }
+
+    async fn set_clipboard(&self, text: &str) -> AdbResult<()> {
+        let quoted = shell_quote(text);
+        let output = self
+            .shell(&["cmd", "clipboard", "set-primary-clip", &quoted])
+            .await?;
+        if output.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(AdbError::AppCommandFailed {
+                command: "cmd clipboard set-primary-clip".into(),
+                output: output.trim().to_string(),
+            })
+        }
+    }
+
     async fn is_human_touching(&self) -> bool {
         self.touch_monitor.read().await.is_human_active()
     }
@@ -609,6 +1089,26 @@ impl AdbClient for UsbAdb {
         Ok(())
     }
 
+    /// Gracefully shutdown the USB processor task and release resources
+    async fn shutdown(&mut self) -> AdbResult<()> {
+        // Stop touch monitoring
+        self.stop_touch_monitoring().await?;
+
+        // Close the channel by creating a new empty sender (dropping the original)
+        // This signals the processor task to exit
+        let (new_tx, _) = tokio::sync::mpsc::channel(1);
+        self.usb_queue_tx = new_tx;
+
+        // Abort the processor task if it exists
+        if let Some(handle) = self.usb_processor_handle.take() {
+            handle.abort();
+            // Give it a moment to clean up
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        Ok(())
+    }
+
     fn screen_dimensions(&self) -> (u32, u32) {
         (self.screen_x, self.screen_y)
     }
@@ -617,30 +1117,338 @@ impl AdbClient for UsbAdb {
         &self.device.name
     }
 
+    fn device_key(&self) -> &str {
+        self.device.key()
+    }
+
     fn transport_id(&self) -> Option<u32> {
         None
     }
 }
 
+/// Escape `text` for `input text`: spaces become `%s` and shell metacharacters are quoted
+pub fn escape_input_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() * 2);
+    for c in text.chars() {
+        match c {
+            ' ' => escaped.push_str("%s"),
+            '\\' | '"' | '\'' | '`' | '$' | '&' | '|' | ';' | '<' | '>' | '(' | ')' | '*' | '?'
+            | '~' | '#' | '!' | '[' | ']' | '{' | '}' | '%' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Longest a single `push`/`pull` may take
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Line between a captured command's stdout and its stderr, followed by the exit code
+const SHELL_EXIT_MARKER: &str = "__ADB_RUN_SHELL_EXIT__";
+/// Exit code of toybox `timeout` when it had to kill the command
+const SHELL_TIMEOUT_EXIT: i32 = 124;
+
+/// `command` wrapped so one shell call returns its stdout, then the exit code
+/// after `SHELL_EXIT_MARKER`, then its stderr
+pub fn shell_capture_command(command: &str, timeout: Duration) -> String {
+    format!(
+        "e=/data/local/tmp/.run_shell.$$; timeout {} sh -c {} 2>$e; printf '\\n{}%d\\n' $?; cat $e; rm -f $e",
+        timeout.as_secs().max(1),
+        shell_quote(command),
+        SHELL_EXIT_MARKER
+    )
+}
+
+/// Split the output of a `shell_capture_command`
+pub fn parse_shell_capture(raw: &str) -> Option<ShellOutput> {
+    let (stdout, rest) = raw.rsplit_once(&format!("\n{}", SHELL_EXIT_MARKER))?;
+    let (exit_code, stderr) = rest.split_once('\n')?;
+    Some(ShellOutput {
+        stdout: stdout.to_string(),
+        stderr: stderr.to_string(),
+        exit_code: exit_code.trim().parse().ok()?,
+    })
+}
+
+/// `run_shell` result from the output of a `shell_capture_command`; a command
+/// the device stopped at `timeout` is an error that leaves the connection alone
+pub fn shell_capture_result(
+    command: String,
+    raw: &str,
+    timeout: Duration,
+) -> AdbResult<ShellOutput> {
+    match parse_shell_capture(raw) {
+        Some(output) if output.exit_code == SHELL_TIMEOUT_EXIT => {
+            Err(AdbError::ShellCommandTimedOut {
+                command,
+                duration: timeout,
+            })
+        }
+        Some(output) => Ok(output),
+        None => Err(AdbError::AppCommandFailed {
+            command,
+            output: raw.trim().to_string(),
+        }),
+    }
+}
+
+/// `text` as one single-quoted shell word
+pub fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// Clipboard text from `cmd clipboard get-primary-clip`: the bare text, `null`
+/// for an empty clipboard, or a `ClipData { text/plain {T:...} }` dump.
+/// `None` when the device has no clipboard shell command (before Android 13)
+pub fn parse_clipboard(output: &str) -> Option<String> {
+    let text = output.strip_suffix('\n').unwrap_or(output);
+    let text = text.strip_suffix('\r').unwrap_or(text);
+    if text.contains("No shell command implementation") || text.starts_with("Unknown command") {
+        return None;
+    }
+    if text == "null" {
+        return Some(String::new());
+    }
+    if let Some(clip) = text.strip_prefix("ClipData {") {
+        let item = &clip[clip.find("{T")?..];
+        let start = item.find(':')? + 1;
+        let end = item.rfind("} }")?;
+        return Some(item.get(start..end)?.to_string());
+    }
+    Some(text.to_string())
+}
+
+/// Package or `package/activity` name, rejected if it could break out of the shell command
+pub fn checked_package_name(name: &str) -> AdbResult<&str> {
+    let valid = !name.is_empty()
+        && name.split('/').count() <= 2
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '/'));
+    if valid {
+        Ok(name)
+    } else {
+        Err(AdbError::InvalidPackageName {
+            name: name.to_string(),
+        })
+    }
+}
+
+/// Package of the resumed (foreground) activity in `dumpsys activity activities` output
+pub fn parse_foreground_package(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let line = line.trim();
+        // `mResumedActivity` before Android 10, `topResumedActivity` after
+        if !(line.starts_with("mResumedActivity") || line.starts_with("topResumedActivity")) {
+            return None;
+        }
+        // ... ActivityRecord{8c1d3e u0 com.example.game/.MainActivity t42}
+        line.split_whitespace().find_map(|token| {
+            token
+                .split_once('/')
+                .map(|(package, _)| package.to_string())
+        })
+    })
+}
+
+/// Display rotation from `dumpsys input` output: the touchscreen's
+/// `SurfaceOrientation: 1`, or `orientation=1` of the internal viewport on newer Android
+pub fn parse_orientation(output: &str) -> Option<Orientation> {
+    let rotation = |value: &str| {
+        let value = value.trim().trim_start_matches("ROTATION_");
+        match value.parse::<u32>().ok()? {
+            degrees @ (90 | 180 | 270) => Orientation::from_rotation(degrees / 90),
+            rotation => Orientation::from_rotation(rotation),
+        }
+    };
+    let surface = output.lines().find_map(|line| {
+        let value = line.trim().strip_prefix("SurfaceOrientation:")?;
+        rotation(value)
+    });
+    surface.or_else(|| {
+        output
+            .lines()
+            .filter(|line| line.contains("Viewport INTERNAL"))
+            .find_map(|line| {
+                let (_, rest) = line.split_once("orientation=")?;
+                rotation(rest.split([',', ' ']).next()?)
+            })
+    })
+}
+
+/// UTC offset from `date +%z` output (`+1300`, `-0530`)
+pub fn parse_utc_offset(output: &str) -> Option<time::UtcOffset> {
+    let offset = output.trim();
+    let (sign, digits) = match offset.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i8 = digits[..2].parse().ok()?;
+    let minutes: i8 = digits[2..].parse().ok()?;
+    time::UtcOffset::from_hms(sign * hours, sign * minutes, 0).ok()
+}
+
+/// Extract the IPv4 address from `ip -f inet addr show wlan0` output
+pub fn parse_wlan_ip(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let addr = line.trim().strip_prefix("inet ")?;
+        let ip = addr.split(['/', ' ']).next()?;
+        ip.parse::<std::net::Ipv4Addr>()
+            .ok()
+            .map(|ip| ip.to_string())
+    })
+}
+
 // Additional methods for UsbAdb (not part of AdbClient trait)
 impl UsbAdb {
-    /// Gracefully shutdown the USB processor task and release resources
-    pub async fn shutdown(&mut self) -> AdbResult<()> {
-        // Stop touch monitoring
-        self.stop_touch_monitoring().await?;
+    /// Connect over Wi-Fi to a device that already listens for ADB on TCP
+    /// (`adb tcpip 5555` was run, or wireless debugging is enabled).
+    /// Accepts `"ip:port"` or an `(ip, port)` tuple.
+    pub async fn connect_tcp(address: impl ToSocketAddrs) -> AdbResult<Self> {
+        let address = resolve_socket_addr(address)?;
+
+        let tcp_future = tokio::task::spawn_blocking(move || ADBTcpDevice::new(address));
+        let tcp_device = match tokio::time::timeout(Duration::from_secs(10), tcp_future).await {
+            Ok(Ok(Ok(device))) => device,
+            Ok(Ok(Err(source))) => return Err(AdbError::ConnectionFailed { source }),
+            Ok(Err(e)) => return Err(AdbError::from(e)),
+            Err(_) => {
+                return Err(AdbError::ConnectionTimeout {
+                    duration: Duration::from_secs(10),
+                });
+            }
+        };
 
-        // Close the channel by creating a new empty sender (dropping the original)
-        // This signals the processor task to exit
-        let (new_tx, _) = tokio::sync::mpsc::channel(1);
-        self.usb_queue_tx = new_tx;
+        let device = Device {
+            name: address.to_string(),
+            transport_id: Some("tcp".into()),
+            serial: None,
+        };
+        Self::from_transport(device, Box::new(tcp_device)).await
+    }
 
-        // Abort the processor task if it exists
-        if let Some(handle) = self.usb_processor_handle.take() {
-            handle.abort();
-            // Give it a moment to clean up
-            tokio::time::sleep(Duration::from_millis(100)).await;
+    /// Ask adbd to also listen on TCP `port` (same effect as `adb tcpip <port>`).
+    ///
+    /// Restarting adbd from a shell needs a rooted/userdebug build; on stock
+    /// devices this only succeeds when wireless debugging is already enabled.
+    pub async fn enable_tcpip(&self, port: u16) -> AdbResult<()> {
+        self.shell(&["setprop", "service.adb.tcp.port", &port.to_string()])
+            .await?;
+        // adbd restarting drops this connection, so the result is expected to fail
+        if let Err(e) = self.shell(&["setprop", "ctl.restart", "adbd"]).await {
+            log::debug!("adbd restart returned: {}", e);
         }
-
         Ok(())
     }
+
+    /// Model, Android version, screen size and transport of this device
+    pub async fn details(&self) -> DeviceDetails {
+        let property = |name: &'static str| async move {
+            self.shell(&["getprop", name])
+                .await
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let connection = if is_tcp_address(&self.device.name) {
+            ConnectionType::Wifi
+        } else {
+            ConnectionType::Usb
+        };
+        DeviceDetails {
+            name: self.device.name.clone(),
+            serial: self.device.serial.clone(),
+            model: property("ro.product.model").await,
+            android_version: property("ro.build.version.release").await,
+            screen_width: self.screen_x,
+            screen_height: self.screen_y,
+            connection,
+        }
+    }
+
+    /// Touchscreen device and axis ranges, read once per connection
+    async fn touch_axes(&self) -> AdbResult<TouchAxes> {
+        let mut cached = self.touch_axes.lock().await;
+        if let Some(axes) = cached.as_ref() {
+            return Ok(axes.clone());
+        }
+        let output = self.shell(&["getevent", "-p"]).await?;
+        let axes = parse_touch_axes(&output).ok_or(AdbError::NoTouchDeviceFound)?;
+        log::info!(
+            "👆 Multi-touch device {} (axes 0-{} x 0-{})",
+            axes.device,
+            axes.max_x,
+            axes.max_y
+        );
+        *cached = Some(axes.clone());
+        Ok(axes)
+    }
+
+    /// Reply of a push or pull; large files over USB 2 take a while
+    async fn await_transfer(
+        &self,
+        rx: tokio::sync::oneshot::Receiver<AdbResult<()>>,
+        description: String,
+    ) -> AdbResult<()> {
+        match tokio::time::timeout(TRANSFER_TIMEOUT, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(AdbError::ChannelClosed),
+            Err(_) => Err(AdbError::Timeout {
+                duration: TRANSFER_TIMEOUT,
+                description,
+            }),
+        }
+    }
+
+    /// Run a shell command through the command queue and return its stdout
+    pub async fn shell(&self, args: &[&str]) -> AdbResult<String> {
+        self.shell_with_timeout(args, Duration::from_secs(30)).await
+    }
+
+    async fn shell_with_timeout(&self, args: &[&str], timeout: Duration) -> AdbResult<String> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        self.usb_queue_tx
+            .send(UsbCommand::Shell {
+                args: args.iter().map(|a| a.to_string()).collect(),
+                response_tx: tx,
+            })
+            .await
+            .map_err(|_| AdbError::ChannelClosed)?;
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(AdbError::ChannelClosed),
+            Err(_) => Err(AdbError::Timeout {
+                duration: timeout,
+                description: format!("Shell command '{}'", args.join(" ")),
+            }),
+        }
+    }
+
+    /// The device's current UTC offset, so schedules follow device local time.
+    /// `None` when `date` prints something unexpected.
+    pub async fn utc_offset(&self) -> AdbResult<Option<time::UtcOffset>> {
+        Ok(parse_utc_offset(&self.shell(&["date", "+%z"]).await?))
+    }
+
+    /// Change how long automation stays paused after a human touch
+    pub async fn set_touch_timeout(&self, timeout: Duration) {
+        self.touch_monitor.write().await.timeout_duration = timeout;
+    }
+}
+
+fn resolve_socket_addr(address: impl ToSocketAddrs) -> AdbResult<SocketAddr> {
+    address
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or(AdbError::InvalidAddress)
 }
@@ -0,0 +1,176 @@
+// Wireless ADB - pairing, known-device persistence and reconnects over TCP/IP.
+//
+// Connections themselves go through `AdbBackend::connect_tcp` (an ADBTcpDevice
+// behind the same UsbCommand queue as USB). Pairing with a code from Android 11+
+// "Wireless debugging" uses the local adb server, which implements the
+// SPAKE2/TLS pairing handshake; connecting afterwards does not need it.
+//
+// Every wireless device that connected successfully is remembered in
+// `wireless_devices.toml` so the device picker can offer it again. When the
+// phone drops off Wi-Fi, the automation reconnect loop (with its backoff)
+// connects to the same `ip:port` again.
+
+use super::backend::AdbBackend;
+use super::error::{AdbError, AdbResult};
+use adb_client::ADBServer;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddrV4;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const WIRELESS_DEVICES_PATH: &str = "wireless_devices.toml";
+/// Port used by `adb tcpip` when none is given
+pub const DEFAULT_TCP_PORT: u16 = 5555;
+
+const PAIRING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A wireless device that connected at least once
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnownWirelessDevice {
+    /// `ip:port` used to connect
+    pub address: String,
+    /// Device model, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Unix time of the last successful connection
+    #[serde(default)]
+    pub last_connected: u64,
+}
+
+/// Known wireless devices, most recently connected first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WirelessRegistry {
+    #[serde(default)]
+    pub devices: Vec<KnownWirelessDevice>,
+}
+
+impl WirelessRegistry {
+    /// Load the registry; a missing file is an empty registry
+    pub fn load(path: impl AsRef<Path>) -> AdbResult<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path).map_err(|e| registry_error(path, e))?;
+        toml::from_str(&text).map_err(|e| registry_error(path, e))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> AdbResult<()> {
+        let path = path.as_ref();
+        let text = toml::to_string_pretty(self).map_err(|e| registry_error(path, e))?;
+        std::fs::write(path, text).map_err(|e| registry_error(path, e))
+    }
+
+    /// Record a successful connection to `address`, moving it to the front
+    pub fn remember(&mut self, address: &str, name: Option<String>) {
+        let previous = self.forget(address);
+        let last_connected = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.devices.insert(
+            0,
+            KnownWirelessDevice {
+                address: address.to_string(),
+                name: name.or(previous.and_then(|d| d.name)),
+                last_connected,
+            },
+        );
+    }
+
+    /// Drop `address` from the registry, returning its entry
+    pub fn forget(&mut self, address: &str) -> Option<KnownWirelessDevice> {
+        let index = self.devices.iter().position(|d| d.address == address)?;
+        Some(self.devices.remove(index))
+    }
+
+    pub fn addresses(&self) -> Vec<String> {
+        self.devices.iter().map(|d| d.address.clone()).collect()
+    }
+}
+
+/// Known wireless device addresses from `wireless_devices.toml` (empty on error)
+pub fn known_addresses() -> Vec<String> {
+    WirelessRegistry::load(WIRELESS_DEVICES_PATH)
+        .inspect_err(|e| log::warn!("{}", e))
+        .map(|registry| registry.addresses())
+        .unwrap_or_default()
+}
+
+/// Remember `address` in `wireless_devices.toml`
+pub fn remember_device(address: &str, name: Option<String>) -> AdbResult<()> {
+    let mut registry = WirelessRegistry::load(WIRELESS_DEVICES_PATH)?;
+    registry.remember(address, name);
+    registry.save(WIRELESS_DEVICES_PATH)
+}
+
+/// Forget `address` in `wireless_devices.toml`; true when it was known
+pub fn forget_device(address: &str) -> AdbResult<bool> {
+    let mut registry = WirelessRegistry::load(WIRELESS_DEVICES_PATH)?;
+    let removed = registry.forget(address).is_some();
+    if removed {
+        registry.save(WIRELESS_DEVICES_PATH)?;
+    }
+    Ok(removed)
+}
+
+/// Validate a six digit "Wireless debugging" pairing code
+pub fn parse_pairing_code(code: &str) -> AdbResult<String> {
+    let code = code.trim();
+    if code.len() == 6 && code.chars().all(|c| c.is_ascii_digit()) {
+        Ok(code.to_string())
+    } else {
+        Err(AdbError::InvalidPairingCode {
+            code: code.to_string(),
+        })
+    }
+}
+
+/// Pair with a device showing "Pair device with pairing code" (`adb pair ip:port code`).
+///
+/// The pairing port differs from the connection port shown on the main
+/// Wireless debugging screen; connect to that one afterwards.
+pub async fn pair(address: &str, code: &str) -> AdbResult<()> {
+    let address: SocketAddrV4 = address.parse().map_err(|_| AdbError::InvalidAddress)?;
+    let code = parse_pairing_code(code)?;
+
+    let pair_future = tokio::task::spawn_blocking(move || {
+        let mut server = ADBServer::default();
+        server.pair(address, code)
+    });
+    match tokio::time::timeout(PAIRING_TIMEOUT, pair_future).await {
+        Ok(Ok(Ok(()))) => Ok(()),
+        Ok(Ok(Err(source))) => Err(AdbError::PairingFailed {
+            address: address.to_string(),
+            source,
+        }),
+        Ok(Err(e)) => Err(AdbError::from(e)),
+        Err(_) => Err(AdbError::Timeout {
+            duration: PAIRING_TIMEOUT,
+            description: format!("Pairing with {}", address),
+        }),
+    }
+}
+
+/// Connect to `ip:port` and remember it for later sessions
+pub async fn connect(ip: &str, port: u16) -> AdbResult<AdbBackend> {
+    connect_address(&format!("{}:{}", ip, port)).await
+}
+
+/// Connect to an `ip:port` address and remember it for later sessions.
+///
+/// `AdbBackend::connect` routes wireless selectors here, so automation
+/// reconnecting after the phone dropped off Wi-Fi refreshes the entry too.
+pub async fn connect_address(address: &str) -> AdbResult<AdbBackend> {
+    let client = AdbBackend::connect_tcp(address).await?;
+    if let Err(e) = remember_device(address, None) {
+        log::warn!("Could not remember wireless device {}: {}", address, e);
+    }
+    Ok(client)
+}
+
+fn registry_error(path: &Path, error: impl std::fmt::Display) -> AdbError {
+    AdbError::WirelessRegistryFailed {
+        path: PathBuf::from(path),
+        description: error.to_string(),
+    }
+}
@@ -1,81 +1,908 @@
-use std::env;
+use clap::error::ErrorKind;
+use clap::{Parser, Subcommand};
+use std::net::SocketAddr;
+use std::time::Duration;
 
-#[derive(Debug, Clone)]
+/// Control API address for a bare `--serve`; loopback only
+pub const DEFAULT_SERVE_ADDR: &str = "127.0.0.1:8080";
+
+/// Where `screenshot` writes without `--out`
+pub const DEFAULT_SCREENSHOT_PATH: &str = "cli-screenshot.png";
+
+/// Where batch screenshots go without `--out-dir`
+pub const DEFAULT_SCREENSHOT_DIR: &str = "screenshots";
+
+/// Batch size without `--count`
+pub const DEFAULT_SCREENSHOT_COUNT: u32 = 10;
+
+/// Wait between batch screenshots without `--interval`
+pub const DEFAULT_SCREENSHOT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Where `export-video` writes without `--out`
+pub const DEFAULT_VIDEO_PATH: &str = "timelapse.gif";
+
+/// Where `analyze` writes the annotated screenshot without `--out`
+pub const DEFAULT_ANALYZE_PATH: &str = "annotated.png";
+
+/// Where `daemon` writes its PID without `--pid-file`
+pub const DEFAULT_PID_FILE: &str = "android-adb-run.pid";
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Mode {
     Gui,
-    Screenshot,
+    Screenshot {
+        out: String,
+        /// Only this `(x, y, width, height)` area of the screen
+        region: Option<(u32, u32, u32, u32)>,
+        /// Several numbered screenshots into a folder instead of `out`
+        batch: Option<ScreenshotBatch>,
+    },
+    Devices {
+        json: bool,
+    },
+    Tap {
+        x: u32,
+        y: u32,
+    },
+    Swipe {
+        x1: u32,
+        y1: u32,
+        x2: u32,
+        y2: u32,
+        duration_ms: Option<u32>,
+    },
+    /// Headless automation; `config` replaces automation.toml
+    AutomationRun {
+        config: Option<String>,
+    },
+    Record {
+        path: String,
+    },
+    Replay {
+        path: String,
+    },
+    Pair {
+        address: String,
+        code: String,
+    },
+    Connect {
+        address: String,
+    },
+    /// Headless automation controlled over HTTP
+    Serve {
+        addr: SocketAddr,
+    },
+    /// Time each patch matching strategy on a screenshot
+    BenchMatch {
+        screenshot: String,
+    },
+    /// Template matches on a saved screenshot, drawn into `out`
+    Analyze {
+        image: String,
+        /// Replaces `[matching] template_dirs` when not empty
+        templates: Vec<String>,
+        out: String,
+        /// Templates that have to be found
+        expect: Vec<String>,
+    },
+    /// Screenshots in `dir` as a GIF/MP4 timelapse with times and taps
+    ExportVideo {
+        dir: String,
+        out: String,
+        fps: u32,
+        /// Output width in pixels, 0 keeps the screenshot size
+        width: u32,
+        /// History file with the taps, logs/history.jsonl when unset
+        history: Option<String>,
+    },
+    /// Headless automation for a service manager: PID file, stops on SIGTERM/SIGINT
+    Daemon {
+        config: Option<String>,
+        pid_file: String,
+        /// Also serve the HTTP control API
+        serve: Option<SocketAddr>,
+    },
+}
+
+/// `--count N --interval SECS --out-dir DIR`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenshotBatch {
+    pub count: u32,
+    pub interval: Duration,
+    pub out_dir: String,
 }
 
 #[derive(Debug)]
 pub struct Args {
     pub mode: Mode,
+    /// Device selector (`vid:pid` name or USB serial), first device when unset
+    pub device: Option<String>,
     pub debug_mode: bool,
     pub debug_mode_timeout_secs: Option<u64>,
+    /// Replaces `[touch] pause_timeout_seconds` for this run
+    pub touch_timeout_secs: Option<u64>,
+}
+
+/// Why parsing stopped without producing `Args`
+#[derive(Debug, PartialEq)]
+pub enum ArgsError {
+    Help,
+    Version,
+    Invalid(String),
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "android-adb-run",
+    version,
+    about = "🤖 Android ADB Automation Tool",
+    disable_version_flag = true,
+    after_help = EXAMPLES
+)]
+struct Cli {
+    /// Target device by name (vid:pid), serial or ip:port
+    #[arg(short, long, global = true, value_name = "SERIAL")]
+    device: Option<String>,
+
+    /// Enable debug output for automation
+    #[arg(long, global = true)]
+    debug: bool,
+
+    /// Auto-exit the GUI after N seconds (for testing)
+    #[arg(long, value_name = "N")]
+    timeout: Option<u64>,
+
+    /// Pause automation this long after a human touch (default: [touch] in the config)
+    #[arg(long, global = true, value_name = "SECS")]
+    touch_timeout: Option<u64>,
+
+    /// Headless automation with HTTP control API (127.0.0.1:8080)
+    #[arg(
+        long,
+        value_name = "IP:PORT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = DEFAULT_SERVE_ADDR
+    )]
+    serve: Option<SocketAddr>,
+
+    /// Headless automation without the GUI (same as `run`)
+    #[arg(long)]
+    automate: bool,
+
+    /// Automation config for --automate instead of automation.toml
+    #[arg(long, value_name = "FILE", requires = "automate")]
+    config: Option<String>,
+
+    /// Time each patch matching strategy on a screenshot and print a table
+    #[arg(long, value_name = "PNG")]
+    bench_match: Option<String>,
+
+    /// Launch GUI interface (same as `gui`)
+    #[arg(long, hide = true)]
+    gui: bool,
+
+    /// Save a screenshot to cli-screenshot.png (same as `screenshot`)
+    #[arg(short, long, hide = true)]
+    screenshot: bool,
+
+    #[command(flatten)]
+    batch: BatchArgs,
+
+    /// Show version information
+    #[arg(short = 'v', long, action = clap::ArgAction::Version)]
+    version: Option<bool>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Launch GUI interface (default)
+    Gui,
+    /// Save a screenshot as PNG
+    Screenshot {
+        /// Output file
+        #[arg(short, long, value_name = "FILE", default_value = DEFAULT_SCREENSHOT_PATH)]
+        out: String,
+        /// Only capture this area of the screen
+        #[arg(long, value_name = "X,Y,W,H", value_parser = parse_region)]
+        region: Option<(u32, u32, u32, u32)>,
+        #[command(flatten)]
+        batch: BatchArgs,
+    },
+    /// List connected ADB devices
+    Devices {
+        /// Print a JSON array (serial, model, screen size, Android version, connection)
+        #[arg(long)]
+        json: bool,
+    },
+    /// Tap at device coordinates
+    Tap { x: u32, y: u32 },
+    /// Swipe between two points
+    Swipe {
+        x1: u32,
+        y1: u32,
+        x2: u32,
+        y2: u32,
+        /// Swipe duration in milliseconds
+        #[arg(long, value_name = "N")]
+        ms: Option<u32>,
+        /// Older positional form of --ms
+        #[arg(hide = true, conflicts_with = "ms")]
+        duration_ms: Option<u32>,
+    },
+    /// Run timed-event automation without GUI
+    Run {
+        /// Automation config to use instead of automation.toml
+        #[arg(long, value_name = "FILE")]
+        config: Option<String>,
+    },
+    /// Run automation as a service: PID file, clean stop on SIGTERM/SIGINT
+    Daemon {
+        /// Automation config to use instead of automation.toml
+        #[arg(long, value_name = "FILE")]
+        config: Option<String>,
+        /// File holding the daemon's process id while it runs
+        #[arg(long, value_name = "FILE", default_value = DEFAULT_PID_FILE)]
+        pid_file: String,
+        /// Also serve the HTTP control API (127.0.0.1:8080); automation then waits for POST /start
+        #[arg(
+            long,
+            value_name = "IP:PORT",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = DEFAULT_SERVE_ADDR
+        )]
+        serve: Option<SocketAddr>,
+    },
+    /// Older spelling of `run`
+    #[command(hide = true)]
+    Automation {
+        #[command(subcommand)]
+        action: AutomationAction,
+    },
+    /// Record commands typed on stdin into a session
+    Record { file: String },
+    /// Replay a recorded session with its timing
+    Replay { file: String },
+    /// Pair with Wireless debugging (needs adb server)
+    Pair {
+        #[arg(value_name = "IP:PORT")]
+        address: String,
+        code: String,
+    },
+    /// Connect over Wi-Fi and remember the device
+    Connect {
+        #[arg(value_name = "IP[:PORT]")]
+        address: String,
+    },
+    /// Match templates on a saved screenshot and save it with the matches drawn
+    Analyze {
+        /// Screenshot to analyze
+        #[arg(long, value_name = "PNG")]
+        image: String,
+        /// Template folder (repeatable; default [matching] template_dirs)
+        #[arg(long, value_name = "DIR")]
+        templates: Vec<String>,
+        /// Annotated copy with match boxes and scores
+        #[arg(short, long, value_name = "FILE", default_value = DEFAULT_ANALYZE_PATH)]
+        out: String,
+        /// Fail unless this template is found (repeatable)
+        #[arg(long, value_name = "TEMPLATE")]
+        expect: Vec<String>,
+    },
+    /// Turn a folder of screenshots into a GIF/MP4 timelapse with times and taps
+    ExportVideo {
+        /// Folder with the PNG screenshots, e.g. from `screenshot --out-dir`
+        #[arg(value_name = "DIR")]
+        dir: String,
+        /// Output file; .gif, or .mp4 (needs ffmpeg)
+        #[arg(short, long, value_name = "FILE", default_value = DEFAULT_VIDEO_PATH)]
+        out: String,
+        /// Frames per second
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..=60))]
+        fps: u32,
+        /// Output width in pixels (0 keeps the screenshot size)
+        #[arg(long, value_name = "PX", default_value_t = 540)]
+        width: u32,
+        /// Execution history with the taps to mark
+        #[arg(long, value_name = "FILE")]
+        history: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AutomationAction {
+    Run,
+}
+
+/// Batch capture options, for `screenshot` and the `--screenshot` flag
+#[derive(clap::Args, Debug, Default)]
+struct BatchArgs {
+    /// Take N numbered, timestamped screenshots into --out-dir (default 10 once any batch option is given)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    count: Option<u32>,
+    /// Seconds between batch screenshots (default 5)
+    #[arg(long, value_name = "SECS", value_parser = parse_seconds)]
+    interval: Option<Duration>,
+    /// Folder for batch screenshots (default screenshots/)
+    #[arg(long, value_name = "DIR")]
+    out_dir: Option<String>,
+}
+
+impl BatchArgs {
+    /// A batch once any of its options is given
+    fn into_batch(self) -> Option<ScreenshotBatch> {
+        if self.count.is_none() && self.interval.is_none() && self.out_dir.is_none() {
+            return None;
+        }
+        Some(ScreenshotBatch {
+            count: self.count.unwrap_or(DEFAULT_SCREENSHOT_COUNT),
+            interval: self.interval.unwrap_or(DEFAULT_SCREENSHOT_INTERVAL),
+            out_dir: self
+                .out_dir
+                .unwrap_or_else(|| DEFAULT_SCREENSHOT_DIR.to_string()),
+        })
+    }
 }
 
+const EXAMPLES: &str = "EXAMPLES:
+    android-adb-run devices --json
+    android-adb-run screenshot --device 18d1:4ee7 --out home.png
+    android-adb-run tap 540 1200
+    android-adb-run --device 192.168.1.50:5555 screenshot
+    android-adb-run swipe 540 1800 540 600 --ms 300
+    android-adb-run --debug run --config farm.toml
+    android-adb-run --automate --config farm.toml
+    android-adb-run daemon --config farm.toml --pid-file /run/adb-run/adb-run.pid
+    android-adb-run --touch-timeout 10 run
+    android-adb-run --serve=0.0.0.0:8080
+    android-adb-run record farm.session
+    android-adb-run replay farm.session
+    android-adb-run pair 192.168.1.50:37123 482915
+    android-adb-run connect 192.168.1.50
+    android-adb-run --bench-match screenshot.png
+    android-adb-run screenshot --count 20 --interval 3 --out-dir captures
+    android-adb-run export-video captures --out run.gif --fps 4
+    android-adb-run analyze --image home.png --templates templates --out annotated.png --expect claim_button";
+
 impl Args {
-    pub fn parse() -> Option<Self> {
-        let args: Vec<String> = env::args().collect();
-
-        let mut mode: Option<Mode> = None;
-        let mut debug_mode: bool = false;
-        let mut timeout_secs: Option<u64> = None;
-
-        for arg in args.iter().skip(1) {
-            if arg == "--help" || arg == "-h" {
-                print_help();
-                return None;
-            } else if arg == "--version" || arg == "-v" {
-                println!("Android ADB Run v{}", env!("CARGO_PKG_VERSION"));
-                return None;
-            } else if arg == "--debug" {
-                debug_mode = true;
-            } else if arg == "--gui" {
-                mode = Some(Mode::Gui);
-            } else if arg == "--screenshot" || arg == "-s" {
-                mode = Some(Mode::Screenshot);
-            } else if arg.starts_with("--timeout=") {
-                if let Some(val) = arg.strip_prefix("--timeout=") {
-                    match val.parse::<u64>() {
-                        Ok(secs) => timeout_secs = Some(secs),
-                        Err(_) => {
-                            eprintln!("❌ Invalid timeout value: {}", val);
-                            return None;
-                        }
-                    }
-                }
-            } else {
-                eprintln!("❌ Unknown argument: {}", arg);
-                print_help();
-                return None;
+    /// Parse the process arguments; prints help, version or the error and
+    /// exits when there is nothing to run
+    pub fn parse() -> Self {
+        match Cli::try_parse() {
+            Ok(cli) => Self::from_cli(cli).unwrap_or_else(|msg| {
+                Cli::command_error(msg).exit();
+            }),
+            Err(e) => e.exit(),
+        }
+    }
+
+    /// Parse arguments (without the program name)
+    pub fn try_parse_from(args: &[String]) -> Result<Self, ArgsError> {
+        let argv = std::iter::once("android-adb-run").chain(args.iter().map(String::as_str));
+        let cli = Cli::try_parse_from(argv).map_err(|e| match e.kind() {
+            ErrorKind::DisplayHelp | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => {
+                ArgsError::Help
             }
+            ErrorKind::DisplayVersion => ArgsError::Version,
+            _ => ArgsError::Invalid(e.to_string()),
+        })?;
+        Self::from_cli(cli).map_err(ArgsError::Invalid)
+    }
+
+    fn from_cli(cli: Cli) -> Result<Self, String> {
+        if cli.automate && cli.serve.is_some() {
+            return Err("--automate cannot be combined with --serve".to_string());
+        }
+        let batch = cli.batch.into_batch();
+        if batch.is_some() && !cli.screenshot {
+            return Err(
+                "--count/--interval/--out-dir need --screenshot or `screenshot`".to_string(),
+            );
         }
+        let flag_mode = match (cli.gui, cli.screenshot, cli.serve) {
+            _ if cli.automate => Some(Mode::AutomationRun { config: cli.config }),
+            _ if cli.bench_match.is_some() => cli
+                .bench_match
+                .map(|screenshot| Mode::BenchMatch { screenshot }),
+            (_, _, Some(addr)) => Some(Mode::Serve { addr }),
+            (_, true, None) => Some(Mode::Screenshot {
+                out: DEFAULT_SCREENSHOT_PATH.to_string(),
+                region: None,
+                batch,
+            }),
+            (true, false, None) => Some(Mode::Gui),
+            (false, false, None) => None,
+        };
+        let mode = match (flag_mode, cli.command) {
+            (Some(_), Some(command)) => {
+                return Err(format!(
+                    "Subcommand '{}' cannot be combined with --gui/--screenshot/--serve/--automate/--bench-match",
+                    command.name()
+                ));
+            }
+            (Some(mode), None) => mode,
+            (None, Some(command)) => command.into_mode(),
+            (None, None) => Mode::Gui,
+        };
 
-        Some(Args {
-            mode: mode.unwrap_or(Mode::Gui),
-            debug_mode,
-            debug_mode_timeout_secs: timeout_secs,
+        Ok(Args {
+            mode,
+            device: cli.device,
+            debug_mode: cli.debug,
+            debug_mode_timeout_secs: cli.timeout,
+            touch_timeout_secs: cli.touch_timeout,
         })
     }
 }
 
-fn print_help() {
-    println!("🤖 Android ADB Automation Tool");
-    println!();
-    println!("USAGE:");
-    println!("    android-adb-run [FLAGS]");
-    println!();
-    println!("FLAGS:");
-    println!("    (no flags)          Launch GUI interface");
-    println!("    --gui               Launch GUI interface");
-    println!("    --screenshot, -s    Take a screenshot and save to file (cli-screenshot.png)");
-    println!("    --debug             Enable debug output for automation");
-    println!("    --timeout=N         Auto-exit after N seconds (for testing)");
-    println!("    --help, -h          Show this help message");
-    println!("    --version, -v       Show version information");
-    println!();
-    println!("EXAMPLES:");
-    println!("    android-adb-run --screenshot");
-    println!("    android-adb-run --gui");
-    println!("    android-adb-run --debug");
+impl Cli {
+    /// A usage error from checks clap cannot express, printed like its own
+    fn command_error(msg: String) -> clap::Error {
+        use clap::CommandFactory;
+        Cli::command().error(ErrorKind::ArgumentConflict, msg)
+    }
+}
+
+impl Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Command::Gui => "gui",
+            Command::Screenshot { .. } => "screenshot",
+            Command::Devices { .. } => "devices",
+            Command::Tap { .. } => "tap",
+            Command::Swipe { .. } => "swipe",
+            Command::Run { .. } => "run",
+            Command::Daemon { .. } => "daemon",
+            Command::Automation { .. } => "automation",
+            Command::Record { .. } => "record",
+            Command::Replay { .. } => "replay",
+            Command::Pair { .. } => "pair",
+            Command::Connect { .. } => "connect",
+            Command::Analyze { .. } => "analyze",
+            Command::ExportVideo { .. } => "export-video",
+        }
+    }
+
+    fn into_mode(self) -> Mode {
+        match self {
+            Command::Gui => Mode::Gui,
+            Command::Screenshot { out, region, batch } => Mode::Screenshot {
+                out,
+                region,
+                batch: batch.into_batch(),
+            },
+            Command::Devices { json } => Mode::Devices { json },
+            Command::Tap { x, y } => Mode::Tap { x, y },
+            Command::Swipe {
+                x1,
+                y1,
+                x2,
+                y2,
+                ms,
+                duration_ms,
+            } => Mode::Swipe {
+                x1,
+                y1,
+                x2,
+                y2,
+                duration_ms: ms.or(duration_ms),
+            },
+            Command::Run { config } => Mode::AutomationRun { config },
+            Command::Daemon {
+                config,
+                pid_file,
+                serve,
+            } => Mode::Daemon {
+                config,
+                pid_file,
+                serve,
+            },
+            Command::Automation {
+                action: AutomationAction::Run,
+            } => Mode::AutomationRun { config: None },
+            Command::Record { file } => Mode::Record { path: file },
+            Command::Replay { file } => Mode::Replay { path: file },
+            Command::Pair { address, code } => Mode::Pair { address, code },
+            Command::Connect { address } => Mode::Connect { address },
+            Command::Analyze {
+                image,
+                templates,
+                out,
+                expect,
+            } => Mode::Analyze {
+                image,
+                templates,
+                out,
+                expect,
+            },
+            Command::ExportVideo {
+                dir,
+                out,
+                fps,
+                width,
+                history,
+            } => Mode::ExportVideo {
+                dir,
+                out,
+                fps,
+                width,
+                history,
+            },
+        }
+    }
+}
+
+/// Seconds, fractions allowed (`0.5`)
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    let seconds: f64 = value
+        .trim()
+        .parse()
+        .map_err(|e| format!("expected seconds: {e}"))?;
+    Duration::try_from_secs_f64(seconds).map_err(|_| "seconds must be 0 or more".to_string())
+}
+
+/// `X,Y,W,H` in device pixels
+fn parse_region(value: &str) -> Result<(u32, u32, u32, u32), String> {
+    let parts = value
+        .split(',')
+        .map(|part| part.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("expected X,Y,W,H: {e}"))?;
+    match parts[..] {
+        [x, y, w, h] if w > 0 && h > 0 => Ok((x, y, w, h)),
+        [_, _, _, _] => Err("width and height must be greater than 0".to_string()),
+        _ => Err("expected X,Y,W,H".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, ArgsError> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        Args::try_parse_from(&args)
+    }
+
+    #[test]
+    fn test_defaults_to_gui() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(args.mode, Mode::Gui);
+        assert!(args.device.is_none());
+    }
+
+    #[test]
+    fn test_legacy_screenshot_flag() {
+        assert_eq!(
+            parse(&["-s"]).unwrap().mode,
+            Mode::Screenshot {
+                out: DEFAULT_SCREENSHOT_PATH.into(),
+                region: None,
+                batch: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_screenshot_batch() {
+        let batch = |args: &[&str]| match parse(args).unwrap().mode {
+            Mode::Screenshot { batch, .. } => batch,
+            mode => panic!("not a screenshot: {:?}", mode),
+        };
+        assert_eq!(
+            batch(&[
+                "-s",
+                "--count",
+                "3",
+                "--interval",
+                "0.5",
+                "--out-dir",
+                "captures"
+            ]),
+            Some(ScreenshotBatch {
+                count: 3,
+                interval: Duration::from_millis(500),
+                out_dir: "captures".into(),
+            })
+        );
+        assert_eq!(
+            batch(&["screenshot", "--count", "20"]),
+            Some(ScreenshotBatch {
+                count: 20,
+                interval: DEFAULT_SCREENSHOT_INTERVAL,
+                out_dir: DEFAULT_SCREENSHOT_DIR.into(),
+            })
+        );
+        assert!(parse(&["screenshot", "--count", "0"]).is_err());
+        assert!(parse(&["screenshot", "--interval", "-1"]).is_err());
+        assert!(
+            parse(&["--count", "5"]).is_err(),
+            "batch without screenshot"
+        );
+    }
+
+    #[test]
+    fn test_analyze() {
+        assert_eq!(
+            parse(&["analyze", "--image", "home.png"]).unwrap().mode,
+            Mode::Analyze {
+                image: "home.png".into(),
+                templates: Vec::new(),
+                out: DEFAULT_ANALYZE_PATH.into(),
+                expect: Vec::new(),
+            }
+        );
+        assert_eq!(
+            parse(&[
+                "analyze",
+                "--image",
+                "home.png",
+                "--templates",
+                "a",
+                "--templates",
+                "b",
+                "--out",
+                "x.png",
+                "--expect",
+                "claim",
+            ])
+            .unwrap()
+            .mode,
+            Mode::Analyze {
+                image: "home.png".into(),
+                templates: vec!["a".into(), "b".into()],
+                out: "x.png".into(),
+                expect: vec!["claim".into()],
+            }
+        );
+        assert!(parse(&["analyze"]).is_err(), "--image is required");
+    }
+
+    #[test]
+    fn test_export_video() {
+        assert_eq!(
+            parse(&["export-video", "captures"]).unwrap().mode,
+            Mode::ExportVideo {
+                dir: "captures".into(),
+                out: DEFAULT_VIDEO_PATH.into(),
+                fps: 4,
+                width: 540,
+                history: None,
+            }
+        );
+        assert!(matches!(
+            parse(&["export-video", "captures", "-o", "run.mp4", "--fps", "10", "--history", "h.jsonl"])
+                .unwrap()
+                .mode,
+            Mode::ExportVideo { fps: 10, ref out, history: Some(_), .. } if out == "run.mp4"
+        ));
+        assert!(parse(&["export-video", "captures", "--fps", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_tap_with_device() {
+        let args = parse(&["--device", "ABC123", "tap", "10", "20"]).unwrap();
+        assert_eq!(args.mode, Mode::Tap { x: 10, y: 20 });
+        assert_eq!(args.device.as_deref(), Some("ABC123"));
+    }
+
+    #[test]
+    fn test_swipe_optional_duration() {
+        let args = parse(&["swipe", "1", "2", "3", "4", "--device=x"]).unwrap();
+        assert_eq!(
+            args.mode,
+            Mode::Swipe {
+                x1: 1,
+                y1: 2,
+                x2: 3,
+                y2: 4,
+                duration_ms: None
+            }
+        );
+        let args = parse(&["swipe", "1", "2", "3", "4", "250"]).unwrap();
+        assert!(matches!(
+            args.mode,
+            Mode::Swipe {
+                duration_ms: Some(250),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_subcommand_options() {
+        let args = parse(&["screenshot", "--out", "home.png", "--device", "ABC123"]).unwrap();
+        assert_eq!(
+            args.mode,
+            Mode::Screenshot {
+                out: "home.png".into(),
+                region: None,
+                batch: None,
+            }
+        );
+        assert_eq!(
+            parse(&["screenshot", "--region", "0,100,200,50"])
+                .unwrap()
+                .mode,
+            Mode::Screenshot {
+                out: DEFAULT_SCREENSHOT_PATH.into(),
+                region: Some((0, 100, 200, 50)),
+                batch: None,
+            }
+        );
+        assert!(parse(&["screenshot", "--region", "0,100,200"]).is_err());
+        assert!(parse(&["screenshot", "--region", "0,100,0,50"]).is_err());
+        assert_eq!(args.device.as_deref(), Some("ABC123"));
+        assert_eq!(
+            parse(&["devices", "--json"]).unwrap().mode,
+            Mode::Devices { json: true }
+        );
+        assert!(matches!(
+            parse(&["swipe", "1", "2", "3", "4", "--ms", "300"])
+                .unwrap()
+                .mode,
+            Mode::Swipe {
+                duration_ms: Some(300),
+                ..
+            }
+        ));
+        assert!(matches!(
+            parse(&["swipe", "1", "2", "3", "4", "250", "--ms", "300"]),
+            Err(ArgsError::Invalid(_))
+        ));
+        let args = parse(&["--debug", "run", "--config", "farm.toml"]).unwrap();
+        assert_eq!(
+            args.mode,
+            Mode::AutomationRun {
+                config: Some("farm.toml".into())
+            }
+        );
+        assert!(args.debug_mode);
+        assert_eq!(
+            parse(&["--automate", "--config", "farm.toml"])
+                .unwrap()
+                .mode,
+            Mode::AutomationRun {
+                config: Some("farm.toml".into())
+            }
+        );
+        assert!(matches!(
+            parse(&["--config", "farm.toml"]),
+            Err(ArgsError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse(&["--automate", "devices"]),
+            Err(ArgsError::Invalid(_))
+        ));
+        assert_eq!(parse(&["--version"]).unwrap_err(), ArgsError::Version);
+    }
+
+    #[test]
+    fn test_automation_run_and_errors() {
+        assert_eq!(
+            parse(&["automation", "run"]).unwrap().mode,
+            Mode::AutomationRun { config: None }
+        );
+        assert!(matches!(parse(&["automation"]), Err(ArgsError::Invalid(_))));
+        assert_eq!(
+            parse(&["replay", "farm.session"]).unwrap().mode,
+            Mode::Replay {
+                path: "farm.session".into()
+            }
+        );
+        assert!(matches!(parse(&["record"]), Err(ArgsError::Invalid(_))));
+        assert_eq!(
+            parse(&["pair", "192.168.1.50:37123", "482915"])
+                .unwrap()
+                .mode,
+            Mode::Pair {
+                address: "192.168.1.50:37123".into(),
+                code: "482915".into()
+            }
+        );
+        assert!(matches!(
+            parse(&["pair", "1.2.3.4:5"]),
+            Err(ArgsError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse(&["tap", "x", "1"]),
+            Err(ArgsError::Invalid(_))
+        ));
+        assert!(matches!(parse(&["--device"]), Err(ArgsError::Invalid(_))));
+        assert_eq!(parse(&["devices", "-h"]).unwrap_err(), ArgsError::Help);
+    }
+
+    #[test]
+    fn test_touch_timeout_flag() {
+        assert_eq!(parse(&[]).unwrap().touch_timeout_secs, None);
+        let args = parse(&["run", "--touch-timeout", "10"]).unwrap();
+        assert_eq!(args.mode, Mode::AutomationRun { config: None });
+        assert_eq!(args.touch_timeout_secs, Some(10));
+        assert!(matches!(
+            parse(&["--touch-timeout", "soon"]),
+            Err(ArgsError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_serve_address() {
+        assert_eq!(
+            parse(&["--serve"]).unwrap().mode,
+            Mode::Serve {
+                addr: DEFAULT_SERVE_ADDR.parse().unwrap()
+            }
+        );
+        let args = parse(&["--serve=0.0.0.0:9000", "-d", "ABC123"]).unwrap();
+        assert_eq!(
+            args.mode,
+            Mode::Serve {
+                addr: "0.0.0.0:9000".parse().unwrap()
+            }
+        );
+        assert!(matches!(
+            parse(&["--serve=localhost"]),
+            Err(ArgsError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse(&["--serve", "tap", "1", "2"]),
+            Err(ArgsError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_daemon_options() {
+        assert_eq!(
+            parse(&["daemon"]).unwrap().mode,
+            Mode::Daemon {
+                config: None,
+                pid_file: DEFAULT_PID_FILE.into(),
+                serve: None
+            }
+        );
+        let args = parse(&[
+            "--debug",
+            "daemon",
+            "--config",
+            "farm.toml",
+            "--pid-file",
+            "/run/adb-run.pid",
+            "--serve",
+        ])
+        .unwrap();
+        assert!(args.debug_mode);
+        assert_eq!(
+            args.mode,
+            Mode::Daemon {
+                config: Some("farm.toml".into()),
+                pid_file: "/run/adb-run.pid".into(),
+                serve: Some(DEFAULT_SERVE_ADDR.parse().unwrap())
+            }
+        );
+        assert!(matches!(
+            parse(&["--automate", "daemon"]),
+            Err(ArgsError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_bench_match() {
+        assert_eq!(
+            parse(&["--bench-match", "home.png"]).unwrap().mode,
+            Mode::BenchMatch {
+                screenshot: "home.png".into()
+            }
+        );
+        assert!(matches!(
+            parse(&["--bench-match"]),
+            Err(ArgsError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse(&["--bench-match", "home.png", "devices"]),
+            Err(ArgsError::Invalid(_))
+        ));
+    }
 }
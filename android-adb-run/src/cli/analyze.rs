@@ -0,0 +1,88 @@
+// `analyze`: run template detection on a saved screenshot and write a copy with
+// every match boxed and scored. No device needed; `--expect` makes it fail when a
+// template is not found, so template sets can be checked in CI.
+
+use crate::game_automation::config::active_config;
+use crate::game_automation::overlay;
+use crate::game_automation::{GameStateDetector, TemplateMatch};
+
+/// Analyze `image_path` with the templates in `template_dirs` (else the configured
+/// `[matching] template_dirs`), save the annotated copy to `out`
+pub fn analyze(
+    image_path: &str,
+    template_dirs: &[String],
+    out: &str,
+    expect: &[String],
+) -> Result<(), String> {
+    let image = image::open(image_path)
+        .map_err(|e| format!("Failed to open {}: {}", image_path, e))?
+        .to_rgb8();
+    let matching = active_config().matching;
+    let dirs = if template_dirs.is_empty() {
+        matching.template_dirs.clone()
+    } else {
+        template_dirs.to_vec()
+    };
+    let mut detector = GameStateDetector::new(
+        image.width(),
+        image.height(),
+        matching.to_match_config(false),
+    );
+    let loaded = detector.load_templates_from(&dirs)?;
+    println!(
+        "🔍 Matching {} templates from {} in {} ({}x{})",
+        loaded,
+        dirs.join(", "),
+        image_path,
+        image.width(),
+        image.height()
+    );
+
+    let result = detector.analyze_gray(&image::imageops::grayscale(&image));
+    for found in &result.matches {
+        println!("  {}", match_line(found));
+    }
+    println!(
+        "{} match(es) in {} ms",
+        result.matches.len(),
+        result.processing_time_ms
+    );
+
+    let mut annotated = image;
+    overlay::draw_matches(&mut annotated, &result.matches);
+    annotated
+        .save(out)
+        .map_err(|e| format!("Failed to write {}: {}", out, e))?;
+    println!("✅ Annotated screenshot saved to {}", out);
+
+    let missing: Vec<&str> = expect
+        .iter()
+        .filter(|name| result.best_match_for(name).is_none())
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Expected template(s) not found: {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// `label  x,y wxh  0.93` plus the scale when it is not 1
+fn match_line(found: &TemplateMatch) -> String {
+    let (width, height) = found.screen_size();
+    let mut line = format!(
+        "{:<24} {},{} {}x{}  {:.2}",
+        found.template.label(),
+        found.x,
+        found.y,
+        width,
+        height,
+        found.confidence
+    );
+    if (found.scale_factor - 1.0).abs() > f32::EPSILON {
+        line.push_str(&format!("  scale {:.2}", found.scale_factor));
+    }
+    line
+}
@@ -0,0 +1,286 @@
+// Headless automation - runs the GameAutomation FSM without a window.
+//
+// The FSM talks to the UI through Dioxus signals, so it is hosted inside a
+// renderer-less VirtualDom that only polls tasks; status changes are printed.
+// `--serve` additionally exposes the FSM through the HTTP control API.
+// Ctrl-C sends `Shutdown` so the FSM stops cleanly; a second Ctrl-C quits at once.
+// `daemon` swaps that for SIGTERM/SIGINT handling, see daemon.rs.
+
+use crate::adb::{AdbBackend, AdbClient};
+use crate::game_automation::types::{AutomationSignals, DeviceInfo, RecoveryAttempt, TimedEvent};
+use crate::game_automation::{AutomationCommand, GameAutomation, GameState};
+use crate::gui::status::{ConnectionEvent, ConnectionStatus};
+use crate::server::events::EVENT_BUFFER;
+use crate::server::{self, ServerState, SharedSnapshot};
+use dioxus::dioxus_core::{NoOpMutations, VirtualDom};
+use dioxus::prelude::*;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{Mutex, broadcast, mpsc};
+
+/// Exit code when automation could not start (config, runtime, control API)
+pub const EXIT_FAILED: i32 = 1;
+/// Exit code when the device could not be opened
+pub const EXIT_NO_DEVICE: i32 = 3;
+/// Exit code after a second Ctrl-C (or stop signal) skipped the clean shutdown
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// Why headless automation ended early
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeadlessError {
+    NoDevice(String),
+    Failed(String),
+}
+
+impl HeadlessError {
+    /// Process exit code for this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            HeadlessError::NoDevice(_) => EXIT_NO_DEVICE,
+            HeadlessError::Failed(_) => EXIT_FAILED,
+        }
+    }
+}
+
+impl fmt::Display for HeadlessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeadlessError::NoDevice(e) => write!(f, "Open device error: {}", e),
+            HeadlessError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<String> for HeadlessError {
+    fn from(message: String) -> Self {
+        HeadlessError::Failed(message)
+    }
+}
+
+#[derive(Clone)]
+struct HeadlessProps {
+    device: Option<String>,
+    debug_mode: bool,
+    /// Control API address for `--serve`
+    serve: Option<SocketAddr>,
+    /// PID file of `daemon` mode, which stops on SIGTERM/SIGINT instead of Ctrl-C
+    daemon_pid_file: Option<PathBuf>,
+    done_tx: mpsc::UnboundedSender<Result<(), HeadlessError>>,
+}
+
+/// Connect to the selected device and run timed-event automation until Ctrl-C
+pub fn run_automation(device: Option<String>, debug_mode: bool) -> Result<(), HeadlessError> {
+    run_headless(device, debug_mode, None, None)
+}
+
+/// Connect to the selected device and serve the HTTP control API on `addr`;
+/// automation waits for `POST /start`
+pub fn serve_automation(
+    device: Option<String>,
+    debug_mode: bool,
+    addr: SocketAddr,
+) -> Result<(), HeadlessError> {
+    run_headless(device, debug_mode, Some(addr), None)
+}
+
+pub(super) fn run_headless(
+    device: Option<String>,
+    debug_mode: bool,
+    serve: Option<SocketAddr>,
+    daemon_pid_file: Option<PathBuf>,
+) -> Result<(), HeadlessError> {
+    let rt = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    rt.block_on(async move {
+        let (done_tx, mut done_rx) = mpsc::unbounded_channel();
+        let mut dom = VirtualDom::new_with_props(
+            HeadlessAutomation,
+            HeadlessProps {
+                device,
+                debug_mode,
+                serve,
+                daemon_pid_file,
+                done_tx,
+            },
+        );
+        dom.rebuild_in_place();
+
+        loop {
+            tokio::select! {
+                result = done_rx.recv() => return result.unwrap_or(Ok(())),
+                _ = dom.wait_for_work() => {}
+            }
+            dom.render_immediate(&mut NoOpMutations);
+        }
+    })
+}
+
+#[allow(non_snake_case)]
+fn HeadlessAutomation(props: HeadlessProps) -> Element {
+    let signals = AutomationSignals {
+        screenshot_data: use_signal(|| None::<String>),
+        screenshot_bytes: use_signal(|| None::<Vec<u8>>),
+        screenshot_status: use_signal(String::new),
+        screenshot_status_history: use_signal(Vec::new),
+        screenshot_matches: use_signal(Vec::new),
+        automation_state: use_signal(|| GameState::Idle),
+        is_paused_by_touch: use_signal(|| false),
+        touch_timeout_remaining: use_signal(|| None::<u64>),
+        timed_tap_countdown: use_signal(|| None::<(String, u64)>),
+        timed_events_list: use_signal(Vec::<TimedEvent>::new),
+        device_info: use_signal(|| None::<DeviceInfo>),
+        status: use_signal(ConnectionStatus::default),
+        screenshot_counter: use_signal(|| 0u64),
+        device_telemetry: use_signal(|| None),
+        health_pause: use_signal(|| None::<String>),
+        recovery: use_signal(|| None::<RecoveryAttempt>),
+        sequence_progress: use_signal(|| None),
+        stats: use_signal(Default::default),
+        custom_state: use_signal(|| None::<String>),
+        orientation: use_signal(|| None),
+        sleeping_until: use_signal(|| None),
+        stuck: use_signal(|| None),
+        counters: use_signal(Default::default),
+        template_test: use_signal(|| None),
+        config: use_signal(Default::default),
+    };
+
+    use_future(move || {
+        let props = props.clone();
+        async move {
+            let result = drive_automation(
+                props.device,
+                props.debug_mode,
+                props.serve,
+                props.daemon_pid_file,
+                signals,
+            )
+            .await;
+            let _ = props.done_tx.send(result);
+        }
+    });
+
+    rsx! {}
+}
+
+async fn drive_automation(
+    device: Option<String>,
+    debug_mode: bool,
+    serve: Option<SocketAddr>,
+    daemon_pid_file: Option<PathBuf>,
+    signals: AutomationSignals,
+) -> Result<(), HeadlessError> {
+    let client = AdbBackend::connect(device.as_deref())
+        .await
+        .map_err(|e| HeadlessError::NoDevice(e.to_string()))?;
+    let (sx, sy) = client.screen_dimensions();
+    println!("📱 Device: {} size: {}x{}", client.device_name(), sx, sy);
+
+    let mut status = signals.status;
+    *signals.device_info.write_unchecked() = Some(DeviceInfo {
+        name: client.device_name().to_string(),
+        transport_id: client.transport_id(),
+        screen_x: sx,
+        screen_y: sy,
+    });
+    status.with_mut(|s| s.apply(ConnectionEvent::ConnectSucceeded));
+
+    let (cmd_tx, cmd_rx) = mpsc::channel(32);
+    let mut game_automation = GameAutomation::new(cmd_rx, debug_mode, signals);
+    game_automation
+        .set_shared_adb_client(Arc::new(Mutex::new(client)))
+        .await?;
+
+    spawn(print_status_changes(signals));
+    let stop_hint = match daemon_pid_file {
+        Some(pid_file) => {
+            spawn(super::daemon::shutdown_on_signal(cmd_tx.clone(), pid_file));
+            "send SIGTERM to stop"
+        }
+        None => {
+            spawn(shutdown_on_ctrl_c(cmd_tx.clone()));
+            "press Ctrl-C to stop"
+        }
+    };
+    match serve {
+        Some(addr) => {
+            let snapshot = SharedSnapshot::default();
+            let (events, _) = broadcast::channel(EVENT_BUFFER);
+            spawn(server::mirror_signals(
+                signals,
+                snapshot.clone(),
+                events.clone(),
+            ));
+            server::serve(
+                addr,
+                ServerState {
+                    snapshot,
+                    commands: cmd_tx.clone(),
+                    events,
+                },
+            )
+            .await?;
+            // Fill the event list before the first Start
+            cmd_tx
+                .send(AutomationCommand::ListTimedEvents)
+                .await
+                .map_err(|e| e.to_string())?;
+            println!(
+                "🌐 Control API on http://{} - POST /start to begin, {}",
+                addr, stop_hint
+            );
+        }
+        None => {
+            cmd_tx
+                .send(AutomationCommand::Start)
+                .await
+                .map_err(|e| e.to_string())?;
+            println!("🤖 Automation running - {}", stop_hint);
+        }
+    }
+    game_automation.run().await;
+    drop(cmd_tx);
+    println!("🛑 Automation stopped");
+    Ok(())
+}
+
+/// First Ctrl-C asks the FSM to shut down, a second one exits immediately
+pub(super) async fn shutdown_on_ctrl_c(cmd_tx: mpsc::Sender<AutomationCommand>) {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        log::warn!("⚠️ Ctrl-C handler unavailable: {}", e);
+        return;
+    }
+    println!("🛑 Ctrl-C - stopping automation (press again to force quit)");
+    let _ = cmd_tx.send(AutomationCommand::Shutdown).await;
+    if tokio::signal::ctrl_c().await.is_ok() {
+        eprintln!("⚠️ Forced quit");
+        std::process::exit(EXIT_INTERRUPTED);
+    }
+}
+
+/// Echo connection/screenshot status and state changes to stdout
+async fn print_status_changes(signals: AutomationSignals) {
+    let mut last_status = String::new();
+    let mut last_screenshot_status = String::new();
+    let mut last_state = GameState::Idle;
+
+    loop {
+        let status = signals.status.peek().message();
+        if status != last_status {
+            println!("📟 {}", status);
+            last_status = status;
+        }
+        let screenshot_status = signals.screenshot_status.peek().clone();
+        if !screenshot_status.is_empty() && screenshot_status != last_screenshot_status {
+            println!("📸 {}", screenshot_status);
+            last_screenshot_status = screenshot_status;
+        }
+        let state = signals.automation_state.peek().clone();
+        if state != last_state {
+            println!("🎮 State: {:?}", state);
+            last_state = state;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    }
+}
@@ -0,0 +1,56 @@
+// `--bench-match`: time every matching strategy on one screenshot and the
+// patches in `[matching] patch_dir`, printed as a comparison table. No device needed.
+
+use crate::game_automation::config::active_config;
+use crate::template_matching::bench::{
+    BenchSettings, DEFAULT_BENCH_PYRAMID_FACTOR, Strategy, format_table, run_strategy,
+};
+use crate::template_matching::load_patches;
+use std::path::Path;
+
+pub fn bench_match(screenshot: &str) -> Result<(), String> {
+    let matching = active_config().matching;
+    let image = image::open(screenshot)
+        .map_err(|e| format!("Failed to open {}: {}", screenshot, e))?
+        .to_rgb8();
+    let patch_dir = Path::new(&matching.patch_dir);
+    let radius = |label: &str| {
+        matching
+            .templates
+            .get(label)
+            .and_then(|behavior| behavior.search_radius)
+    };
+    let patches = load_patches(patch_dir, radius)?;
+    if patches.is_empty() {
+        return Err(format!("No patch-*.png files in {}", patch_dir.display()));
+    }
+    let settings = BenchSettings {
+        threshold: matching.patch_threshold as f32,
+        search_margin: matching.patch_search_radius,
+        pyramid_factor: match matching.pyramid_factor {
+            0 | 1 => DEFAULT_BENCH_PYRAMID_FACTOR,
+            factor => factor,
+        },
+    };
+
+    println!(
+        "⏱️ Matching {} patches from {} in {} ({}x{}), threshold {:.2}, region ±{}px, pyramid 1/{}",
+        patches.len(),
+        patch_dir.display(),
+        screenshot,
+        image.width(),
+        image.height(),
+        settings.threshold,
+        settings.search_margin,
+        settings.pyramid_factor
+    );
+    let mut results = Vec::new();
+    for strategy in Strategy::ALL {
+        println!("  ⏳ {}...", strategy.name());
+        results.push(run_strategy(strategy, &image, &patches, &settings)?);
+    }
+    println!();
+    print!("{}", format_table(&results));
+    println!("* = found at or above the threshold");
+    Ok(())
+}
@@ -0,0 +1,178 @@
+// Daemon mode - headless automation run by a service manager (systemd on a
+// Raspberry Pi next to the phone). Runs in the foreground like systemd expects,
+// holds a PID file, turns SIGTERM/SIGINT into `Shutdown`, writes the run's
+// statistics on the way out, and gives up on a shutdown that hangs.
+
+use super::automation::{EXIT_INTERRUPTED, HeadlessError, run_headless};
+use crate::game_automation::AutomationCommand;
+use crate::logging::LOG_DIR;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Statistics written in `logs/` when the daemon stops
+pub const DAEMON_STATS_FILE: &str = "daemon_stats.json";
+/// Longest a stop signal waits for the clean shutdown before exiting anyway
+pub const SHUTDOWN_GRACE: Duration = Duration::from_secs(15);
+
+/// PID file held for the life of the daemon, removed when dropped
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Write this process id to `path`; fails while a running process owns it
+    pub fn create(path: &Path) -> Result<Self, String> {
+        if let Some(pid) = std::fs::read_to_string(path)
+            .ok()
+            .as_deref()
+            .and_then(parse_pid)
+            && pid != std::process::id()
+            && process_alive(pid)
+        {
+            return Err(format!(
+                "Already running as PID {} ({}), stop it first",
+                pid,
+                path.display()
+            ));
+        }
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(path, format!("{}\n", std::process::id()))
+            .map_err(|e| format!("Failed to write PID file {}: {}", path.display(), e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Process id in a PID file's contents
+pub fn parse_pid(content: &str) -> Option<u32> {
+    content.trim().parse().ok().filter(|&pid| pid > 0)
+}
+
+/// A stale PID file (no such process) can be taken over
+fn process_alive(pid: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        Path::new("/proc").join(pid.to_string()).exists()
+    } else {
+        // No cheap check elsewhere: trust the file
+        true
+    }
+}
+
+/// Run headless automation as a daemon until SIGTERM or SIGINT
+pub fn run_daemon(
+    device: Option<String>,
+    debug_mode: bool,
+    serve: Option<SocketAddr>,
+    pid_file: &Path,
+) -> Result<(), HeadlessError> {
+    let pid_file = PidFile::create(pid_file)?;
+    log::info!(
+        "👻 Daemon started, PID {} in {}",
+        std::process::id(),
+        pid_file.path().display()
+    );
+    let result = run_headless(
+        device,
+        debug_mode,
+        serve,
+        Some(pid_file.path().to_path_buf()),
+    );
+    log::info!("👻 Daemon stopped");
+    result
+}
+
+/// First SIGTERM/SIGINT saves the statistics and asks the FSM to shut down;
+/// a second signal, or a shutdown still running after `SHUTDOWN_GRACE`, exits
+/// at once (after removing the PID file)
+#[cfg(unix)]
+pub(super) async fn shutdown_on_signal(cmd_tx: mpsc::Sender<AutomationCommand>, pid_file: PathBuf) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let (mut term, mut int) = match (
+        signal(SignalKind::terminate()),
+        signal(SignalKind::interrupt()),
+    ) {
+        (Ok(term), Ok(int)) => (term, int),
+        (Err(e), _) | (_, Err(e)) => {
+            log::warn!("⚠️ Signal handlers unavailable: {}", e);
+            return;
+        }
+    };
+    let name = tokio::select! {
+        _ = term.recv() => "SIGTERM",
+        _ = int.recv() => "SIGINT",
+    };
+    log::info!("🛑 {} - stopping automation", name);
+    let stats_path = Path::new(LOG_DIR).join(DAEMON_STATS_FILE);
+    let _ = cmd_tx
+        .send(AutomationCommand::ExportStats(
+            stats_path.display().to_string(),
+        ))
+        .await;
+    let _ = cmd_tx.send(AutomationCommand::Shutdown).await;
+
+    tokio::select! {
+        _ = tokio::time::sleep(SHUTDOWN_GRACE) => log::error!(
+            "❌ Shutdown still running after {}s, exiting",
+            SHUTDOWN_GRACE.as_secs()
+        ),
+        _ = term.recv() => log::warn!("⚠️ Second stop signal, exiting"),
+        _ = int.recv() => log::warn!("⚠️ Second stop signal, exiting"),
+    }
+    let _ = std::fs::remove_file(&pid_file);
+    std::process::exit(EXIT_INTERRUPTED);
+}
+
+/// Without Unix signals only Ctrl-C can stop the daemon
+#[cfg(not(unix))]
+pub(super) async fn shutdown_on_signal(
+    cmd_tx: mpsc::Sender<AutomationCommand>,
+    _pid_file: PathBuf,
+) {
+    super::automation::shutdown_on_ctrl_c(cmd_tx).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pid_file_lifecycle() {
+        let path = std::env::temp_dir().join(format!("adb_run_{}.pid", std::process::id()));
+        assert_eq!(parse_pid(" 4242\n"), Some(4242));
+        assert_eq!(parse_pid("0"), None);
+        assert_eq!(parse_pid("not a pid"), None);
+
+        let pid_file = PidFile::create(&path).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(parse_pid(&written), Some(std::process::id()));
+        drop(pid_file);
+        assert!(!path.exists());
+
+        if cfg!(target_os = "linux") {
+            // A stale file from a process that is gone is taken over
+            std::fs::write(&path, format!("{}\n", u32::MAX)).unwrap();
+            let pid_file = PidFile::create(&path);
+            assert!(pid_file.is_ok());
+        }
+    }
+}
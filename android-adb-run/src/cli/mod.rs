@@ -0,0 +1,237 @@
+// CLI subcommands - one-shot device operations used by main.rs
+//
+// Each command opens its own USB connection (optionally to a selected device),
+// performs a single operation and shuts the connection down again.
+
+pub mod analyze;
+pub mod automation;
+pub mod bench;
+pub mod daemon;
+pub mod session;
+pub mod timelapse;
+pub mod wireless;
+
+use crate::adb::{AdbBackend, AdbClient, AdbError, AdbResult, DeviceDetails, ImageCapture};
+use std::path::Path;
+use std::time::Duration;
+
+pub use analyze::analyze;
+pub use automation::{HeadlessError, run_automation, serve_automation};
+pub use bench::bench_match;
+pub use daemon::run_daemon;
+pub use session::{record, replay};
+pub use timelapse::export_video;
+pub use wireless::{connect_wireless, pair};
+
+/// Print every attached USB device and reachable known wireless device, one
+/// per line or (`json`) as a JSON array of `DeviceDetails`
+pub async fn list_devices(json: bool) -> AdbResult<()> {
+    let usb = AdbBackend::list_devices().await?;
+    let known_wireless = crate::adb::wireless::known_addresses();
+
+    let mut details = Vec::new();
+    let mut unreachable = Vec::new();
+    for device in &usb {
+        match AdbBackend::new_with_device(device.key()).await {
+            Ok(client) => details.push(probe(client).await),
+            Err(e) => {
+                log::warn!("⚠️ Could not open {}: {}", device.name, e);
+                unreachable.push(device.name.clone());
+            }
+        }
+    }
+    for address in &known_wireless {
+        match AdbBackend::connect_tcp(address.as_str()).await {
+            Ok(client) => details.push(probe(client).await),
+            Err(e) => {
+                log::debug!("Wireless device {} not reachable: {}", address, e);
+                unreachable.push(address.clone());
+            }
+        }
+    }
+
+    if json {
+        // Plain strings and numbers only, serializing cannot fail
+        let text = serde_json::to_string_pretty(&details).unwrap_or_default();
+        println!("{}", text);
+        return Ok(());
+    }
+
+    if details.is_empty() && unreachable.is_empty() {
+        println!("🔌 No devices found");
+        return Ok(());
+    }
+    println!("📱 {} device(s):", details.len());
+    for d in &details {
+        println!(
+            "  {}  serial={}  model={}  android={}  screen={}x{}  {:?}",
+            d.name,
+            d.serial.as_deref().unwrap_or("?"),
+            d.model.as_deref().unwrap_or("?"),
+            d.android_version.as_deref().unwrap_or("?"),
+            d.screen_width,
+            d.screen_height,
+            d.connection
+        );
+    }
+    if !unreachable.is_empty() {
+        println!("⚠️ Not reachable (in use by another adb, or Wi-Fi off):");
+        for name in unreachable {
+            println!("  {}", name);
+        }
+    }
+    Ok(())
+}
+
+/// Read the device details, then release the connection
+async fn probe(mut client: AdbBackend) -> DeviceDetails {
+    let details = client.details().await;
+    shutdown(&mut client).await;
+    details
+}
+
+/// Capture one screenshot (or just `region` of it) and write it to `out_path`
+pub async fn screenshot(
+    device: Option<&str>,
+    out_path: &str,
+    region: Option<(u32, u32, u32, u32)>,
+) -> AdbResult<()> {
+    let mut client = connect(device).await?;
+    let result = capture(&client, region).await;
+    shutdown(&mut client).await;
+
+    let cap = result?;
+    tokio::fs::write(out_path, &cap.bytes)
+        .await
+        .map_err(|source| AdbError::LocalFileFailed {
+            path: out_path.into(),
+            source,
+        })?;
+    println!(
+        "✅ Screenshot ({}ms) saved to {}",
+        cap.duration_ms, out_path
+    );
+    Ok(())
+}
+
+/// Capture `count` screenshots `interval` apart into `out_dir`, named
+/// `screenshot-0001-YYYYMMDD_HHMMSS.png` so they sort in capture order
+pub async fn screenshot_batch(
+    device: Option<&str>,
+    region: Option<(u32, u32, u32, u32)>,
+    count: u32,
+    interval: Duration,
+    out_dir: &str,
+) -> AdbResult<()> {
+    let dir = Path::new(out_dir);
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|source| AdbError::LocalFileFailed {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+    let mut client = connect(device).await?;
+    let result = capture_batch(&client, region, count, interval, dir).await;
+    shutdown(&mut client).await;
+    let saved = result?;
+    println!("✅ {} screenshot(s) saved to {}", saved, dir.display());
+    Ok(())
+}
+
+async fn capture_batch(
+    client: &AdbBackend,
+    region: Option<(u32, u32, u32, u32)>,
+    count: u32,
+    interval: Duration,
+    dir: &Path,
+) -> AdbResult<u32> {
+    for index in 1..=count {
+        if index > 1 {
+            tokio::time::sleep(interval).await;
+        }
+        let cap = capture(client, region).await?;
+        let now =
+            time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc());
+        let path = dir.join(format!(
+            "screenshot-{:04}-{}.png",
+            index,
+            crate::game_automation::backup::backup_folder_name(now)
+        ));
+        tokio::fs::write(&path, &cap.bytes)
+            .await
+            .map_err(|source| AdbError::LocalFileFailed {
+                path: path.clone(),
+                source,
+            })?;
+        println!(
+            "📸 {}/{} ({}ms) {}",
+            index,
+            count,
+            cap.duration_ms,
+            path.display()
+        );
+    }
+    Ok(count)
+}
+
+/// The whole screen, or just `region` of it, as PNG
+async fn capture(
+    client: &AdbBackend,
+    region: Option<(u32, u32, u32, u32)>,
+) -> AdbResult<ImageCapture> {
+    match region {
+        Some((x, y, width, height)) => {
+            let start = std::time::Instant::now();
+            client
+                .screen_capture_region(x, y, width, height)
+                .await
+                .and_then(|frame| frame.to_png())
+                .map(|bytes| ImageCapture {
+                    bytes,
+                    duration_ms: start.elapsed().as_millis(),
+                    index: 0,
+                })
+        }
+        None => client.screen_capture().await,
+    }
+}
+
+/// Tap once at device coordinates
+pub async fn tap(device: Option<&str>, x: u32, y: u32) -> AdbResult<()> {
+    let mut client = connect(device).await?;
+    let result = client.tap(x, y).await;
+    shutdown(&mut client).await;
+
+    result?;
+    println!("✅ Tapped ({},{})", x, y);
+    Ok(())
+}
+
+/// Swipe once between two device coordinates
+pub async fn swipe(
+    device: Option<&str>,
+    (x1, y1): (u32, u32),
+    (x2, y2): (u32, u32),
+    duration_ms: Option<u32>,
+) -> AdbResult<()> {
+    let mut client = connect(device).await?;
+    let result = client.swipe(x1, y1, x2, y2, duration_ms).await;
+    shutdown(&mut client).await;
+
+    result?;
+    println!("✅ Swiped ({},{}) → ({},{})", x1, y1, x2, y2);
+    Ok(())
+}
+
+async fn connect(device: Option<&str>) -> AdbResult<AdbBackend> {
+    let client = AdbBackend::connect(device).await?;
+    let (sx, sy) = client.screen_dimensions();
+    println!("📱 Device: {} size: {}x{}", client.device_name(), sx, sy);
+    Ok(client)
+}
+
+async fn shutdown(client: &mut AdbBackend) {
+    if let Err(e) = client.shutdown().await {
+        log::debug!("USB shutdown warning: {}", e);
+    }
+}
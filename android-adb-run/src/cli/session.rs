@@ -0,0 +1,92 @@
+// Record / replay CLI - capture a tap/swipe/screenshot session and play it back.
+//
+// Recording reads commands from stdin (same syntax as the CLI subcommands),
+// runs each one on the device immediately and stores it with its timestamp.
+
+use super::{connect, shutdown};
+use crate::adb::{AdbResult, Session, SessionAction, SessionRecorder};
+use std::io::BufRead;
+
+/// Record commands typed on stdin into `path` until `quit` or end of input
+pub async fn record(device: Option<&str>, path: &str) -> AdbResult<()> {
+    let mut client = connect(device).await?;
+    println!(
+        "⏺️  Recording to {} - enter commands, 'quit' to finish:",
+        path
+    );
+    println!("    tap <x> <y> | swipe <x1> <y1> <x2> <y2> [ms] | screenshot [file]");
+
+    let mut recorder = SessionRecorder::new();
+    while let Some(line) = read_stdin_line().await {
+        let command = line.trim();
+        if command.is_empty() {
+            continue;
+        }
+        if command == "quit" || command == "exit" {
+            break;
+        }
+        let action = match SessionAction::parse(command) {
+            Ok(action) => action,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                continue;
+            }
+        };
+        match action.execute(&client).await {
+            Ok(()) => {
+                let event = recorder.record(action);
+                println!("✅ {:>6}ms {}", event.at_ms, event.action);
+            }
+            // Failed actions are not recorded so the replay matches what happened
+            Err(e) => eprintln!("❌ {}: {}", action, e),
+        }
+    }
+    shutdown(&mut client).await;
+
+    let session = recorder.finish();
+    session.save(path).await?;
+    println!(
+        "💾 Saved {} event(s) ({:.1}s) to {}",
+        session.events.len(),
+        session.duration().as_secs_f32(),
+        path
+    );
+    Ok(())
+}
+
+/// Replay a recorded session with its original timing
+pub async fn replay(device: Option<&str>, path: &str) -> AdbResult<()> {
+    let session = Session::load(path).await?;
+    let mut client = connect(device).await?;
+    println!(
+        "▶️  Replaying {} event(s) ({:.1}s) from {}",
+        session.events.len(),
+        session.duration().as_secs_f32(),
+        path
+    );
+
+    let result = session
+        .replay(&client, |event| {
+            println!("   {:>6}ms {}", event.at_ms, event.action)
+        })
+        .await;
+    shutdown(&mut client).await;
+
+    result?;
+    println!("✅ Replay finished");
+    Ok(())
+}
+
+/// Read one line without blocking the runtime; `None` at end of input
+async fn read_stdin_line() -> Option<String> {
+    tokio::task::spawn_blocking(|| {
+        let mut line = String::new();
+        match std::io::stdin().lock().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line),
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
@@ -0,0 +1,36 @@
+// `export-video`: a folder of screenshots as a GIF/MP4 timelapse with times and
+// taps from the execution history burned in. No device needed.
+
+use crate::game_automation::history::{self, HISTORY_FILE};
+use crate::game_automation::timelapse::{self, TimelapseOptions};
+use crate::logging::LOG_DIR;
+use std::path::{Path, PathBuf};
+
+/// Export the PNGs in `dir` to `out`; taps come from `history_path`, else logs/history.jsonl,
+/// and only those made on `device` when it is set
+pub fn export_video(
+    dir: &str,
+    out: &str,
+    options: TimelapseOptions,
+    history_path: Option<&str>,
+    device: Option<&str>,
+) -> Result<(), String> {
+    let frames = timelapse::frames_in(Path::new(dir), device)?;
+    if frames.is_empty() {
+        return Err(format!("No .png screenshots in {}", dir));
+    }
+    let history_path =
+        history_path.map_or_else(|| Path::new(LOG_DIR).join(HISTORY_FILE), PathBuf::from);
+    let entries = history::read_file(&history_path);
+    println!(
+        "🎞️ Exporting {} screenshot(s) from {} at {} fps ({} history entries from {})...",
+        frames.len(),
+        dir,
+        options.fps,
+        entries.len(),
+        history_path.display()
+    );
+    let written = timelapse::export(&frames, &entries, Path::new(out), options)?;
+    println!("✅ Timelapse with {} frame(s) saved to {}", written, out);
+    Ok(())
+}
@@ -0,0 +1,38 @@
+// Wireless CLI - pair with "Wireless debugging" and connect over Wi-Fi.
+
+use super::shutdown;
+use crate::adb::wireless::{self, DEFAULT_TCP_PORT};
+use crate::adb::{AdbClient, AdbResult};
+
+/// Pair using the code from "Pair device with pairing code"
+pub async fn pair(address: &str, code: &str) -> AdbResult<()> {
+    println!("🔐 Pairing with {}...", address);
+    wireless::pair(address, code).await?;
+    println!(
+        "✅ Paired - now run 'connect <ip:port>' with the port shown under Wireless debugging"
+    );
+    Ok(())
+}
+
+/// Connect to `ip[:port]` (default port 5555) and remember it for the device picker
+pub async fn connect_wireless(address: &str) -> AdbResult<()> {
+    let (ip, port) = match address.rsplit_once(':') {
+        Some((ip, port)) => (
+            ip,
+            port.parse::<u16>()
+                .map_err(|_| crate::adb::AdbError::InvalidAddress)?,
+        ),
+        None => (address, DEFAULT_TCP_PORT),
+    };
+    let mut client = wireless::connect(ip, port).await?;
+    let (sx, sy) = client.screen_dimensions();
+    println!(
+        "✅ Connected to {} size: {}x{} (saved to {})",
+        client.device_name(),
+        sx,
+        sy,
+        wireless::WIRELESS_DEVICES_PATH
+    );
+    shutdown(&mut client).await;
+    Ok(())
+}
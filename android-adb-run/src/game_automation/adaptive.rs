@@ -0,0 +1,85 @@
+// Adaptive screenshot interval - instead of the fixed `screenshot_interval_minutes`,
+// capture often while templates match or taps happen and back off while the game
+// is idle. From automation.toml:
+//
+//   [timed_events.adaptive_screenshots]
+//   enabled = true
+//   min_seconds = 10       # interval while there is activity
+//   max_seconds = 600      # longest idle interval
+//   backoff = 2.0          # each idle screenshot multiplies the interval by this
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveScreenshotConfig {
+    pub enabled: bool,
+    pub min_seconds: u64,
+    pub max_seconds: u64,
+    pub backoff: f64,
+}
+
+impl Default for AdaptiveScreenshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_seconds: 10,
+            max_seconds: 600,
+            backoff: 2.0,
+        }
+    }
+}
+
+impl AdaptiveScreenshotConfig {
+    /// Shortest and longest interval, at least a second and min <= max
+    pub fn bounds(&self) -> (Duration, Duration) {
+        let min = self.min_seconds.max(1);
+        (
+            Duration::from_secs(min),
+            Duration::from_secs(self.max_seconds.max(min)),
+        )
+    }
+
+    /// Interval after a screenshot: the minimum when there was activity since the
+    /// last one, else `current` backed off towards the maximum
+    pub fn next_interval(&self, current: Duration, active: bool) -> Duration {
+        let (min, max) = self.bounds();
+        if active {
+            return min;
+        }
+        let backoff = if self.backoff.is_finite() {
+            self.backoff.max(1.0)
+        } else {
+            1.0
+        };
+        current.mul_f64(backoff).clamp(min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_backs_off_when_idle() {
+        let config: AdaptiveScreenshotConfig =
+            toml::from_str("enabled = true\nmin_seconds = 10\nmax_seconds = 60").unwrap();
+        let secs = Duration::from_secs;
+
+        assert_eq!(config.next_interval(secs(10), false), secs(20));
+        assert_eq!(config.next_interval(secs(20), false), secs(40));
+        assert_eq!(config.next_interval(secs(40), false), secs(60), "capped");
+        assert_eq!(config.next_interval(secs(60), true), secs(10));
+        // An old fixed interval outside the bounds is pulled back in
+        assert_eq!(config.next_interval(secs(600), false), secs(60));
+
+        let odd = AdaptiveScreenshotConfig {
+            min_seconds: 0,
+            max_seconds: 0,
+            backoff: 0.5,
+            ..config
+        };
+        assert_eq!(odd.bounds(), (secs(1), secs(1)));
+        assert_eq!(odd.next_interval(secs(5), false), secs(1));
+    }
+}
@@ -0,0 +1,198 @@
+// Device file backups - a `[[timed_events.backups]]` entry pulls a file or a
+// whole folder off the device into a new timestamped folder under its
+// `local_dir`, then deletes the oldest folders beyond `keep`.
+
+use crate::adb::AdbClient;
+use crate::adb::usb_impl::shell_quote;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use time::OffsetDateTime;
+
+/// Longest the device may take to list the files to back up
+const LIST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What one backup run copied
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupReport {
+    pub folder: PathBuf,
+    pub files: usize,
+    pub bytes: u64,
+    /// Old backup folders deleted to stay within `keep`
+    pub pruned: usize,
+}
+
+/// `YYYYMMDD_HHMMSS`, so backup folders sort oldest first
+pub fn backup_folder_name(at: OffsetDateTime) -> String {
+    format!(
+        "{:04}{:02}{:02}_{:02}{:02}{:02}",
+        at.year(),
+        at.month() as u8,
+        at.day(),
+        at.hour(),
+        at.minute(),
+        at.second()
+    )
+}
+
+fn is_backup_folder(name: &str) -> bool {
+    name.len() == 15
+        && name.char_indices().all(|(i, c)| match i {
+            8 => c == '_',
+            _ => c.is_ascii_digit(),
+        })
+}
+
+/// Device files from `find <remote_path> -type f` output, each with the path
+/// it gets inside the backup folder. A single file keeps just its name.
+pub fn remote_files(remote_path: &str, find_output: &str) -> Vec<(String, PathBuf)> {
+    let root = remote_path.trim_end_matches('/');
+    find_output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|file| {
+            let relative = match file.strip_prefix(root) {
+                Some("") | None => Path::new(file).file_name()?.into(),
+                Some(rest) => PathBuf::from(rest.trim_start_matches('/')),
+            };
+            // Never write outside the backup folder
+            let contained = relative
+                .components()
+                .all(|part| matches!(part, Component::Normal(_)));
+            contained.then(|| (file.to_string(), relative))
+        })
+        .collect()
+}
+
+/// Delete the oldest backup folders in `local_dir` so at most `keep` remain
+/// (0 keeps all); returns the deleted folders
+pub fn prune_backups(local_dir: &Path, keep: usize) -> std::io::Result<Vec<PathBuf>> {
+    if keep == 0 {
+        return Ok(Vec::new());
+    }
+    let mut folders: Vec<PathBuf> = fs::read_dir(local_dir)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter(|entry| entry.file_name().to_str().is_some_and(is_backup_folder))
+        .map(|entry| entry.path())
+        .collect();
+    folders.sort();
+    let excess = folders.len().saturating_sub(keep);
+    let removed: Vec<PathBuf> = folders.into_iter().take(excess).collect();
+    for folder in &removed {
+        fs::remove_dir_all(folder)?;
+    }
+    Ok(removed)
+}
+
+/// Pull `remote_path` into a new folder under `local_dir`, then prune to `keep`.
+/// A failed run removes its partial folder so it never counts as a backup.
+pub async fn run_backup(
+    client: &impl AdbClient,
+    remote_path: &str,
+    local_dir: &Path,
+    keep: usize,
+) -> Result<BackupReport, String> {
+    let listing = client
+        .run_shell(
+            &["find", &shell_quote(remote_path), "-type", "f"],
+            LIST_TIMEOUT,
+        )
+        .await
+        .map_err(|e| format!("Listing {} failed: {}", remote_path, e))?;
+    let files = remote_files(remote_path, &listing.stdout);
+    if files.is_empty() {
+        return Err(format!(
+            "Nothing to back up at {}{}",
+            remote_path,
+            match listing.stderr.trim() {
+                "" => String::new(),
+                stderr => format!(": {}", stderr),
+            }
+        ));
+    }
+
+    let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+    let folder = local_dir.join(backup_folder_name(now));
+    let mut bytes = 0;
+    for (remote, relative) in &files {
+        let local = folder.join(relative);
+        let pulled = match local.parent().map(fs::create_dir_all) {
+            Some(Err(e)) => Err(format!("Failed to create {}: {}", folder.display(), e)),
+            _ => client
+                .pull(remote, &local, None)
+                .await
+                .map_err(|e| e.to_string()),
+        };
+        if let Err(e) = pulled {
+            let _ = fs::remove_dir_all(&folder);
+            return Err(e);
+        }
+        bytes += fs::metadata(&local).map(|meta| meta.len()).unwrap_or(0);
+    }
+
+    let pruned = prune_backups(local_dir, keep)
+        .map_err(|e| format!("Failed to prune {}: {}", local_dir.display(), e))?
+        .len();
+    Ok(BackupReport {
+        folder,
+        files: files.len(),
+        bytes,
+        pruned,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adb::MockAdb;
+
+    #[test]
+    fn test_remote_files_stay_inside_the_backup() {
+        let files = remote_files(
+            "/sdcard/game/",
+            "/sdcard/game/save.dat\n/sdcard/game/slots/1.dat\n/sdcard/game/../escape\n",
+        );
+        assert_eq!(
+            files,
+            vec![
+                (
+                    "/sdcard/game/save.dat".to_string(),
+                    PathBuf::from("save.dat")
+                ),
+                (
+                    "/sdcard/game/slots/1.dat".to_string(),
+                    PathBuf::from("slots/1.dat")
+                ),
+            ]
+        );
+        assert_eq!(
+            remote_files("/sdcard/save.dat", "/sdcard/save.dat\n"),
+            vec![("/sdcard/save.dat".to_string(), PathBuf::from("save.dat"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_backup_keeps_the_newest_folders() {
+        let dir = std::env::temp_dir().join(format!("adb_backups_{}", std::process::id()));
+        for old in ["20250101_000000", "20250102_000000", "notes"] {
+            fs::create_dir_all(dir.join(old)).unwrap();
+        }
+
+        let mock = MockAdb::from_frames("mock", Vec::new());
+        let control = mock.control();
+        control.set_file("/sdcard/game/save.dat", b"level 42");
+        control.set_shell_output("find '/sdcard/game' -type f", "/sdcard/game/save.dat\n");
+
+        let report = run_backup(&mock, "/sdcard/game", &dir, 2).await.unwrap();
+        let saved = fs::read(report.folder.join("save.dat")).unwrap();
+        let oldest_gone = !dir.join("20250101_000000").exists();
+        let others_kept = dir.join("20250102_000000").exists() && dir.join("notes").exists();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(saved, b"level 42");
+        assert_eq!((report.files, report.bytes, report.pruned), (1, 8, 1));
+        assert!(oldest_gone && others_kept);
+    }
+}
@@ -0,0 +1,231 @@
+// Pixel and color probes for `[[rules]]`, read straight from the raw screenshot
+// without template matching, e.g. a full energy bar or a red notification dot:
+//
+//   "pixel 1010,140 is #FF3B30 ~40"               one pixel near a color (default ~24)
+//   "pixel 1010,140 not #FF3B30"
+//   "color 80,2210,400,24 in #20A020..#80FF80"    average of X,Y,W,H within a range
+//   "color 80,2210,400,24 outside #20A020..#80FF80"
+//
+// `~T` is the largest difference allowed in each of red, green and blue.
+use crate::adb::RgbFrame;
+use std::fmt;
+use std::str::FromStr;
+
+pub const DEFAULT_TOLERANCE: u8 = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorTest {
+    /// Every channel within `tolerance` of `color`
+    Near { color: [u8; 3], tolerance: u8 },
+    /// Every channel between `low` and `high`
+    Range { low: [u8; 3], high: [u8; 3] },
+}
+
+impl ColorTest {
+    pub fn accepts(&self, rgb: [u8; 3]) -> bool {
+        (0..3).all(|i| match self {
+            Self::Near { color, tolerance } => rgb[i].abs_diff(color[i]) <= *tolerance,
+            Self::Range { low, high } => (low[i]..=high[i]).contains(&rgb[i]),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorProbe {
+    /// `x, y, width, height`; 1x1 for a pixel
+    pub area: (u32, u32, u32, u32),
+    pub test: ColorTest,
+    /// false for `not` / `outside`
+    pub expected: bool,
+}
+
+impl ColorProbe {
+    /// Average color of the area, `None` when it is not all on `frame`
+    pub fn average(&self, frame: &RgbFrame) -> Option<[u8; 3]> {
+        let (x, y, width, height) = self.area;
+        if width == 0
+            || height == 0
+            || x.checked_add(width)? > frame.width
+            || y.checked_add(height)? > frame.height
+        {
+            return None;
+        }
+        let mut sums = [0u64; 3];
+        for row in y..y + height {
+            let start = ((row * frame.width + x) * 3) as usize;
+            let line = frame.pixels.get(start..start + (width * 3) as usize)?;
+            for pixel in line.chunks_exact(3) {
+                for (sum, value) in sums.iter_mut().zip(pixel) {
+                    *sum += *value as u64;
+                }
+            }
+        }
+        let count = width as u64 * height as u64;
+        Some(sums.map(|sum| (sum / count) as u8))
+    }
+
+    /// Whether the probe holds on `frame`; false when the area is off screen
+    pub fn holds(&self, frame: &RgbFrame) -> bool {
+        self.average(frame)
+            .is_some_and(|rgb| self.test.accepts(rgb) == self.expected)
+    }
+
+    /// Middle of the area, for `is` / `in` probes
+    pub fn target(&self) -> Option<(u32, u32)> {
+        let (x, y, width, height) = self.area;
+        self.expected.then_some((x + width / 2, y + height / 2))
+    }
+}
+
+fn parse_hex(value: &str) -> Option<[u8; 3]> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn hex(rgb: [u8; 3]) -> String {
+    format!("#{:02X}{:02X}{:02X}", rgb[0], rgb[1], rgb[2])
+}
+
+impl fmt::Display for ColorProbe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.area {
+            (x, y, 1, 1) => write!(f, "pixel {},{}", x, y)?,
+            (x, y, width, height) => write!(f, "color {},{},{},{}", x, y, width, height)?,
+        }
+        match (self.test, self.expected) {
+            (ColorTest::Near { color, tolerance }, expected) => write!(
+                f,
+                " {} {} ~{}",
+                if expected { "is" } else { "not" },
+                hex(color),
+                tolerance
+            ),
+            (ColorTest::Range { low, high }, expected) => write!(
+                f,
+                " {} {}..{}",
+                if expected { "in" } else { "outside" },
+                hex(low),
+                hex(high)
+            ),
+        }
+    }
+}
+
+impl FromStr for ColorProbe {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let syntax = || {
+            format!(
+                "'{}': expected 'pixel X,Y is|not #RRGGBB [~T]' or 'color X,Y,W,H in|outside #RRGGBB..#RRGGBB'",
+                s
+            )
+        };
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        let [kind, position, operator, value, rest @ ..] = parts.as_slice() else {
+            return Err(syntax());
+        };
+        let numbers: Vec<u32> = position
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map_err(|_| syntax())?;
+        let area = match (*kind, numbers.as_slice()) {
+            ("pixel", &[x, y]) => (x, y, 1, 1),
+            ("color", &[x, y, width, height]) if width > 0 && height > 0 => (x, y, width, height),
+            _ => return Err(syntax()),
+        };
+        let (test, expected) = match (*operator, rest) {
+            ("is" | "not", [] | [_]) => {
+                let tolerance = match rest {
+                    [tolerance] => tolerance
+                        .strip_prefix('~')
+                        .and_then(|t| t.parse().ok())
+                        .ok_or_else(syntax)?,
+                    _ => DEFAULT_TOLERANCE,
+                };
+                let color = parse_hex(value).ok_or_else(syntax)?;
+                (ColorTest::Near { color, tolerance }, *operator == "is")
+            }
+            ("in" | "outside", []) => {
+                let (low, high) = value.split_once("..").ok_or_else(syntax)?;
+                let (low, high) = (
+                    parse_hex(low).ok_or_else(syntax)?,
+                    parse_hex(high).ok_or_else(syntax)?,
+                );
+                (ColorTest::Range { low, high }, *operator == "in")
+            }
+            _ => return Err(syntax()),
+        };
+        Ok(Self {
+            area,
+            test,
+            expected,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixel_and_region_probes() {
+        // Left half red, right half green
+        let (width, height) = (20, 10);
+        let pixels = (0..width * height)
+            .flat_map(|i| {
+                if i % width < width / 2 {
+                    [250, 20, 30]
+                } else {
+                    [40, 200, 60]
+                }
+            })
+            .collect();
+        let frame = RgbFrame {
+            width,
+            height,
+            pixels,
+        };
+        let probe = |text: &str| text.parse::<ColorProbe>().unwrap();
+
+        assert!(probe("pixel 3,3 is #FF1020").holds(&frame));
+        assert!(!probe("pixel 3,3 is #FF1020 ~4").holds(&frame));
+        assert!(probe("pixel 15,3 not #FF1020").holds(&frame));
+        assert!(probe("color 10,0,10,10 in #20A020..#60FF80").holds(&frame));
+        // Half red, half green averages to a brownish mix
+        assert_eq!(
+            probe("color 5,0,10,10 in #000000..#FFFFFF").average(&frame),
+            Some([145, 110, 45])
+        );
+        assert!(probe("color 5,0,10,10 outside #20A020..#60FF80").holds(&frame));
+        assert!(!probe("pixel 25,3 not #FF1020").holds(&frame), "off screen");
+        assert_eq!(
+            probe("color 10,0,10,4 in #000000..#FFFFFF").target(),
+            Some((15, 2))
+        );
+        assert_eq!(probe("pixel 15,3 not #FF1020").target(), None);
+
+        for text in [
+            "pixel 3,3 is #FF1020 ~24",
+            "pixel 3,3 not #FF1020 ~8",
+            "color 10,0,10,10 in #20A020..#60FF80",
+            "color 10,0,10,10 outside #20A020..#60FF80",
+        ] {
+            assert_eq!(probe(text).to_string(), text);
+        }
+        for bad in [
+            "pixel 3 is #FF1020",
+            "pixel 3,3 is red",
+            "pixel 3,3 in #FF1020",
+            "color 1,2,0,4 in #000000..#FFFFFF",
+            "pixel 3,3 is #FF1020 24",
+        ] {
+            assert!(bad.parse::<ColorProbe>().is_err(), "{}", bad);
+        }
+    }
+}
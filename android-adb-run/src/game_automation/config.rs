@@ -1,26 +1,97 @@
-use super::types::{MAX_TAP_INTERVAL_SECONDS, MIN_TAP_INTERVAL_SECONDS, TimedEvent};
+use super::adaptive::AdaptiveScreenshotConfig;
+use super::coord::Coord;
+use super::counters::CountersConfig;
+use super::humanize::Jitter;
+use super::match_image::config::{rotation_angles, scale_range};
+use super::match_image::{MatchConfig, TemplateBehavior};
+use super::quiet_hours::QuietHoursConfig;
+use super::rules::Rule;
+use super::scenes::ScenesConfig;
+use super::schedule::Schedule;
+use super::sequence::ActionSequence;
+use super::silence::SilenceConfig;
+use super::states::StatesConfig;
+use super::types::{
+    AutomationCommand, MAX_TAP_INTERVAL_SECONDS, MIN_TAP_INTERVAL_SECONDS, TimedEvent,
+    TimedEventType,
+};
+use super::watchdog::WatchdogConfig;
+use crate::adb::types::DEFAULT_TOUCH_TIMEOUT_SECS;
+use crate::adb::{DeviceNotification, DeviceTelemetry, Orientation};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
 use time::OffsetDateTime;
 
+pub const AUTOMATION_CONFIG_PATH: &str = "automation.toml";
+/// Older timed-events-only config, imported once into `automation.toml`
 pub const TIMED_EVENTS_CONFIG_PATH: &str = "conf_timed_events.toml";
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TapEventConfig {
     pub id: String,
-    pub x: u32,
-    pub y: u32,
+    /// Pixels, or a percentage of the screen like `"54%"`
+    pub x: Coord,
+    pub y: Coord,
+    pub interval_seconds: u64,
+    pub enabled: bool,
+    /// e.g. `"daily at 08:00"`; overrides `interval_seconds` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<Schedule>,
+    /// Only tap while this template is on screen
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Random tap offset and interval variation
+    #[serde(default, skip_serializing_if = "Jitter::is_off")]
+    pub jitter: Jitter,
+}
+
+/// `[[timed_events.backups]]`: copy device files off the phone on a timer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupEventConfig {
+    pub id: String,
+    /// Device file or folder, e.g. `/sdcard/Android/data/com.example.game/files`
+    pub remote_path: String,
+    /// Each run copies into a new `<local_dir>/YYYYMMDD_HHMMSS/` folder
+    pub local_dir: String,
+    #[serde(default = "default_backup_interval")]
     pub interval_seconds: u64,
+    /// Backup folders kept, oldest deleted first (0 = keep all)
+    #[serde(default = "default_backup_keep")]
+    pub keep: usize,
+    #[serde(default = "default_true")]
     pub enabled: bool,
+    /// e.g. `"daily at 03:00"`; overrides `interval_seconds` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<Schedule>,
+}
+
+fn default_backup_interval() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_backup_keep() -> usize {
+    7
+}
+
+fn default_true() -> bool {
+    true
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TimedEventsConfig {
     pub screenshot_interval_minutes: u64,
     pub countdown_interval_seconds: u64,
     pub taps: Vec<TapEventConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub backups: Vec<BackupEventConfig>,
+    /// Replaces `screenshot_interval_minutes` when enabled
+    #[serde(default)]
+    pub adaptive_screenshots: AdaptiveScreenshotConfig,
 }
 
 impl Default for TimedEventsConfig {
@@ -31,49 +102,951 @@ impl Default for TimedEventsConfig {
             taps: vec![
                 TapEventConfig {
                     id: "claim_5d_tap".to_string(),
-                    x: 120,
-                    y: 1250,
+                    x: Coord::Px(120),
+                    y: Coord::Px(1250),
                     interval_seconds: 60,
                     enabled: true,
+                    schedule: None,
+                    template: None,
+                    jitter: Jitter::default(),
                 },
                 TapEventConfig {
                     id: "restart_tap".to_string(),
-                    x: 110,
-                    y: 1600,
+                    x: Coord::Px(110),
+                    y: Coord::Px(1600),
                     interval_seconds: 120,
                     enabled: true,
+                    schedule: None,
+                    template: None,
+                    jitter: Jitter::default(),
                 },
                 TapEventConfig {
                     id: "claim_1d_tap".to_string(),
-                    x: 350,
-                    y: 628,
+                    x: Coord::Px(350),
+                    y: Coord::Px(628),
                     interval_seconds: 15,
                     enabled: true,
+                    schedule: None,
+                    template: None,
+                    jitter: Jitter::default(),
                 },
             ],
+            backups: Vec::new(),
+            adaptive_screenshots: AdaptiveScreenshotConfig::default(),
+        }
+    }
+}
+
+/// Template locations and thresholds for image matching
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchingConfig {
+    /// Directories scanned for automation templates (`*.png`)
+    pub template_dirs: Vec<String>,
+    /// Directory with `patch-*.png` files matched against GUI screenshots
+    pub patch_dir: String,
+    /// Minimum confidence (0.0 - 1.0) for a template match
+    pub confidence_threshold: f64,
+    /// Minimum correlation (0.0 - 1.0) for a patch match
+    pub patch_threshold: f64,
+    /// Pixels around its original position a patch is searched (per template: `search_radius`)
+    pub patch_search_radius: u32,
+    /// Search the whole screenshot for patches not found near their position
+    pub patch_full_screen_fallback: bool,
+    /// Score every Nth position first and refine around the best (1 = every position)
+    pub patch_search_step: u32,
+    /// How far below `patch_threshold` a coarse or strided candidate may score
+    /// and still be refined; smaller is faster but misses blurred matches
+    pub patch_early_exit_slack: f64,
+    /// Coarse or strided candidates refined at full resolution per patch
+    pub patch_max_candidates: usize,
+    pub max_matches_per_template: usize,
+    pub enable_multiscale: bool,
+    pub scale_factors: Vec<f64>,
+    /// `[min, max]` scales to try instead of `scale_factors`, `scale_steps` of them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale_range: Option<[f64; 2]>,
+    pub scale_steps: u32,
+    /// Also try templates turned up to this many degrees either way (0 = off)
+    pub rotation_tolerance_degrees: f64,
+    pub rotation_step_degrees: f64,
+    /// Search a screenshot downscaled by this factor first, then refine (1 = off)
+    pub pyramid_factor: u32,
+    /// Skip template matching when a screenshot looks the same as the last one
+    pub skip_unchanged_frames: bool,
+    /// Fraction (0.0 - 1.0) of a screenshot that may change and still count as unchanged
+    pub unchanged_threshold: f64,
+    /// Before acting on a match, wait until two screenshots `stable_interval_ms`
+    /// apart differ in at most `stable_threshold` (0.0 - 1.0) of the screen
+    pub wait_for_stable_screen: bool,
+    pub stable_interval_ms: u64,
+    pub stable_threshold: f64,
+    /// Longest wait for a still screen; the action is skipped after it
+    pub stable_timeout_ms: u64,
+    /// Act on a match at least this confident (0.0 - 1.0) as soon as it is
+    /// found, leaving the remaining templates unchecked for that screenshot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub early_action_confidence: Option<f64>,
+    /// Random tap offsets and curved swipes for template actions (`[matching.jitter]`)
+    #[serde(skip_serializing_if = "Jitter::is_off")]
+    pub jitter: Jitter,
+    /// Per-template action, offset, cooldown, priority and limits, keyed by
+    /// template name or label (`[matching.templates.claim_button]`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, TemplateBehavior>,
+}
+
+impl Default for MatchingConfig {
+    fn default() -> Self {
+        // Same values as `create_default_config`, kept as f64 so the TOML stays readable
+        Self {
+            template_dirs: vec![".".to_string()],
+            patch_dir: "assets/test_images".to_string(),
+            confidence_threshold: 0.85,
+            patch_threshold: 0.85,
+            patch_search_radius: 50,
+            patch_full_screen_fallback: false,
+            patch_search_step: 1,
+            patch_early_exit_slack: 0.05,
+            patch_max_candidates: 8,
+            max_matches_per_template: 3,
+            enable_multiscale: true,
+            scale_factors: vec![0.9, 1.0, 1.1],
+            scale_range: None,
+            scale_steps: 5,
+            rotation_tolerance_degrees: 0.0,
+            rotation_step_degrees: 2.0,
+            pyramid_factor: 4,
+            skip_unchanged_frames: true,
+            unchanged_threshold: 0.002,
+            wait_for_stable_screen: true,
+            stable_interval_ms: 300,
+            stable_threshold: 0.01,
+            stable_timeout_ms: 3000,
+            early_action_confidence: None,
+            jitter: Jitter::default(),
+            templates: BTreeMap::new(),
+        }
+    }
+}
+
+impl MatchingConfig {
+    /// Detector settings for these values
+    pub fn to_match_config(&self, debug_enabled: bool) -> MatchConfig {
+        MatchConfig {
+            confidence_threshold: self.confidence_threshold.clamp(0.0, 1.0) as f32,
+            max_matches_per_template: self.max_matches_per_template,
+            enable_multiscale: self.enable_multiscale,
+            scale_factors: match self.scale_range {
+                Some([min, max]) => {
+                    scale_range(min as f32, max as f32, self.scale_steps.max(1) as usize)
+                }
+                None => self.scale_factors.iter().map(|&s| s as f32).collect(),
+            },
+            rotation_angles: rotation_angles(
+                self.rotation_tolerance_degrees as f32,
+                self.rotation_step_degrees as f32,
+            ),
+            pyramid_factor: self.pyramid_factor.max(1),
+            match_patch_search_margin: self.patch_search_radius,
+            patch_full_screen_fallback: self.patch_full_screen_fallback,
+            patch_search_step: self.patch_search_step.max(1),
+            patch_early_exit_slack: self.patch_early_exit_slack.clamp(0.0, 1.0) as f32,
+            patch_max_candidates: self.patch_max_candidates.max(1),
+            template_behaviors: self.templates.clone(),
+            jitter: self.jitter,
+            early_action_confidence: self
+                .early_action_confidence
+                .map(|confidence| confidence.clamp(0.0, 1.0) as f32),
+            debug_enabled,
+            ..super::match_image::create_default_config()
+        }
+    }
+}
+
+/// Near-miss screenshots for tuning templates offline, `[match_debug]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchDebugConfig {
+    pub enabled: bool,
+    /// Bottom of the ambiguous band; the top is `[matching] confidence_threshold`
+    pub min_confidence: f64,
+    /// Where screenshots and `manifest.jsonl` go
+    pub dir: String,
+    /// Save the same template at most once in this many seconds
+    pub cooldown_seconds: u64,
+}
+
+impl Default for MatchDebugConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_confidence: 0.70,
+            dir: "debug".to_string(),
+            cooldown_seconds: 300,
+        }
+    }
+}
+
+impl MatchDebugConfig {
+    pub fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.cooldown_seconds)
+    }
+}
+
+/// Touch detection: how long automation stays paused after a human touch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TouchConfig {
+    pub pause_timeout_seconds: u64,
+}
+
+impl Default for TouchConfig {
+    fn default() -> Self {
+        Self {
+            pause_timeout_seconds: DEFAULT_TOUCH_TIMEOUT_SECS,
+        }
+    }
+}
+
+/// Slowest and fastest live mirroring rates the GUI will try
+pub const MIN_MIRROR_FPS: f64 = 0.2;
+pub const MAX_MIRROR_FPS: f64 = 30.0;
+
+/// Live screen mirroring: continuous screenshots feeding the GUI preview
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MirrorConfig {
+    /// Target frames per second; the device may deliver fewer
+    pub fps: f64,
+    /// Run patch matching on every Nth frame (0 = preview only)
+    pub analyze_every: u32,
+}
+
+impl Default for MirrorConfig {
+    fn default() -> Self {
+        Self {
+            fps: 2.0,
+            analyze_every: 0,
+        }
+    }
+}
+
+impl MirrorConfig {
+    /// Time budget for one frame at the target rate
+    pub fn frame_interval(&self) -> Duration {
+        let fps = if self.fps.is_finite() {
+            self.fps.clamp(MIN_MIRROR_FPS, MAX_MIRROR_FPS)
+        } else {
+            MIN_MIRROR_FPS
+        };
+        Duration::from_secs_f64(1.0 / fps)
+    }
+
+    /// Whether frame number `frame` (counting from 1) goes through patch matching
+    pub fn analyzes(&self, frame: u64) -> bool {
+        self.analyze_every > 0 && frame % self.analyze_every as u64 == 0
+    }
+}
+
+/// Device health limits: automation pauses when one is crossed and resumes
+/// once every reading is back on the safe side of its resume level
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuardrailsConfig {
+    pub enabled: bool,
+    /// Pause below this battery level (%) unless charging
+    pub pause_below_battery: u8,
+    /// Resume at or above this battery level (%), or once charging
+    pub resume_above_battery: u8,
+    /// Pause above this battery temperature (°C)
+    pub pause_above_temp_c: f64,
+    /// Resume at or below this battery temperature (°C)
+    pub resume_below_temp_c: f64,
+    /// Pause at this Android thermal status or worse (3 = severe)
+    pub pause_at_thermal_status: u8,
+}
+
+impl Default for GuardrailsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pause_below_battery: 20,
+            resume_above_battery: 30,
+            pause_above_temp_c: 42.0,
+            resume_below_temp_c: 39.0,
+            pause_at_thermal_status: 3,
+        }
+    }
+}
+
+impl GuardrailsConfig {
+    /// Why automation should be paused for `telemetry`, if it should. `paused`
+    /// says whether a guardrail already holds it, which switches to the resume levels.
+    pub fn pause_reason(&self, telemetry: &DeviceTelemetry, paused: bool) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+
+        if let Some(level) = telemetry.battery_level
+            && telemetry.charging != Some(true)
+        {
+            let low = if paused {
+                level < self.resume_above_battery
+            } else {
+                level < self.pause_below_battery
+            };
+            if low {
+                return Some(format!("🔋 Battery low ({}%)", level));
+            }
+        }
+
+        if let Some(temp) = telemetry.battery_temp_c {
+            let temp = temp as f64;
+            let hot = if paused {
+                temp > self.resume_below_temp_c
+            } else {
+                temp > self.pause_above_temp_c
+            };
+            if hot {
+                return Some(format!("🌡️ Too hot ({:.1}°C)", temp));
+            }
+        }
+
+        if let Some(status) = telemetry.thermal_status
+            && status >= self.pause_at_thermal_status
+        {
+            return Some(format!(
+                "🔥 Thermal status {}",
+                telemetry.thermal_label().unwrap_or("unknown")
+            ));
+        }
+
+        None
+    }
+}
+
+/// Longest wait between two relaunches of a crashed game
+pub const MAX_RESTART_DELAY_SECONDS: u64 = 300;
+
+/// The game being automated: relaunched when it is no longer in the foreground
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Package name, e.g. `com.example.game`; crash recovery is off without it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Activity to launch (`.MainActivity`); the launcher activity when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity: Option<String>,
+    /// How often a running automation checks the game is in the foreground
+    pub check_interval_seconds: u64,
+    /// Relaunches before automation gives up and pauses
+    pub max_restart_attempts: u32,
+    /// Wait after the first relaunch, doubled after each further one
+    pub restart_backoff_seconds: u64,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            package: None,
+            activity: None,
+            check_interval_seconds: 30,
+            max_restart_attempts: 5,
+            restart_backoff_seconds: 5,
+        }
+    }
+}
+
+impl AppConfig {
+    /// `package/activity` to pass to `start_activity`
+    pub fn launch_component(&self) -> Option<String> {
+        let package = self.package.as_deref()?;
+        Some(match self.activity.as_deref() {
+            Some(activity) => format!("{}/{}", package, activity),
+            None => package.to_string(),
+        })
+    }
+
+    /// Wait after relaunch number `attempt` (from 1) before checking again
+    pub fn restart_delay(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        Duration::from_secs(
+            self.restart_backoff_seconds
+                .max(1)
+                .saturating_mul(factor)
+                .min(MAX_RESTART_DELAY_SECONDS),
+        )
+    }
+}
+
+/// Desktop notifications for automation events, `[notifications]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Master switch, also toggled from the GUI
+    pub enabled: bool,
+    pub on_disconnect: bool,
+    /// A timed event or template action failed
+    pub on_error: bool,
+    /// Notify below this battery level (%) unless charging; 0 = off
+    pub battery_below: u8,
+    /// "Jackpot" templates (name or label) that notify when matched
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub templates: Vec<String>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            on_disconnect: true,
+            on_error: true,
+            battery_below: 15,
+            templates: Vec::new(),
+        }
+    }
+}
+
+/// Screen area automation must never tap, e.g. the in-app purchase button
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoTapZone {
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl NoTapZone {
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && x - self.x < self.width && y >= self.y && y - self.y < self.height
+    }
+}
+
+/// Limits every automated tap passes: timed events, template actions,
+/// sequences, scripts and GUI clicks while automation runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SafetyConfig {
+    /// Most taps in any 60 seconds (0 = unlimited)
+    pub max_taps_per_minute: u32,
+    /// Shortest gap between two taps
+    pub min_tap_delay_ms: u64,
+    /// `[[safety.no_tap_zones]]` areas that are never tapped
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub no_tap_zones: Vec<NoTapZone>,
+}
+
+impl Default for SafetyConfig {
+    fn default() -> Self {
+        Self {
+            max_taps_per_minute: 60,
+            min_tap_delay_ms: 250,
+            no_tap_zones: Vec::new(),
+        }
+    }
+}
+
+impl SafetyConfig {
+    /// First no-tap zone covering `(x, y)`
+    pub fn zone_at(&self, x: u32, y: u32) -> Option<&NoTapZone> {
+        self.no_tap_zones.iter().find(|zone| zone.contains(x, y))
+    }
+}
+
+/// What a `[device_notifications]` rule does when its notification is posted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationAction {
+    Start,
+    Pause,
+    Resume,
+    Stop,
+    /// Fire the timed event named by `target` now
+    Trigger,
+    /// Run the `[sequences.<target>]` sequence
+    Sequence,
+}
+
+/// Reaction to an Android notification, e.g. start automation on "energy refilled"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationRule {
+    /// Only notifications from this app; any app when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Text looked for (ignoring case) in the notification title and text
+    pub contains: String,
+    pub action: NotificationAction,
+    /// Timed event id for `trigger`, sequence name for `sequence`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+impl NotificationRule {
+    pub fn matches(&self, notification: &DeviceNotification) -> bool {
+        if self
+            .package
+            .as_ref()
+            .is_some_and(|package| *package != notification.package)
+        {
+            return false;
+        }
+        let wanted = self.contains.to_lowercase();
+        [&notification.title, &notification.text]
+            .into_iter()
+            .flatten()
+            .any(|text| text.to_lowercase().contains(&wanted))
+    }
+
+    /// Command this rule sends to the automation
+    pub fn command(&self) -> Result<AutomationCommand, String> {
+        let target = || {
+            self.target.clone().ok_or_else(|| {
+                format!(
+                    "'{:?}' rule for '{}' needs a target",
+                    self.action, self.contains
+                )
+            })
+        };
+        Ok(match self.action {
+            NotificationAction::Start => AutomationCommand::Start,
+            NotificationAction::Pause => AutomationCommand::Pause,
+            NotificationAction::Resume => AutomationCommand::Resume,
+            NotificationAction::Stop => AutomationCommand::Stop,
+            NotificationAction::Trigger => AutomationCommand::TriggerTimedEvent(target()?),
+            NotificationAction::Sequence => AutomationCommand::RunSequence(target()?),
+        })
+    }
+}
+
+/// Android notifications to watch for, `[device_notifications]`; off while `rules` is empty
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceNotificationsConfig {
+    pub poll_interval_seconds: u64,
+    /// Every matching rule acts, in order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<NotificationRule>,
+}
+
+impl Default for DeviceNotificationsConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_seconds: 15,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl DeviceNotificationsConfig {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_seconds.max(1))
+    }
+}
+
+/// Capture as soon as the foreground UI changes, `[ui_events]`: running
+/// automation listens to `uiautomator events` in windows of `listen_seconds`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiEventsConfig {
+    pub enabled: bool,
+    /// Each listening window; the device handles other commands between windows
+    pub listen_seconds: u64,
+    /// Also react to changes inside a window, not only new windows and dialogs;
+    /// games that animate all the time then capture after every window
+    pub content_changes: bool,
+    /// Least time between captures triggered by UI events
+    pub min_capture_interval_seconds: u64,
+}
+
+impl Default for UiEventsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_seconds: 2,
+            content_changes: false,
+            min_capture_interval_seconds: 2,
+        }
+    }
+}
+
+impl UiEventsConfig {
+    pub fn listen(&self) -> Duration {
+        Duration::from_secs(self.listen_seconds.max(1))
+    }
+
+    pub fn min_capture_interval(&self) -> Duration {
+        Duration::from_secs(self.min_capture_interval_seconds)
+    }
+}
+
+/// Device rotation checks, `[orientation]`: while the device is turned away from
+/// `expected`, timed taps are rotated to match and template matching waits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OrientationConfig {
+    pub enabled: bool,
+    /// Orientation the taps and templates were made in; unset takes the one
+    /// the device shows when automation first connects
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Orientation>,
+    /// Rotate timed-event taps onto the current orientation; false holds them instead
+    pub remap_taps: bool,
+    pub poll_interval_seconds: u64,
+}
+
+impl Default for OrientationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            expected: None,
+            remap_taps: true,
+            poll_interval_seconds: 10,
+        }
+    }
+}
+
+impl OrientationConfig {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_seconds.max(1))
+    }
+}
+
+/// Everything stored in `automation.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutomationConfig {
+    pub timed_events: TimedEventsConfig,
+    pub matching: MatchingConfig,
+    pub match_debug: MatchDebugConfig,
+    pub touch: TouchConfig,
+    pub mirror: MirrorConfig,
+    pub guardrails: GuardrailsConfig,
+    pub app: AppConfig,
+    pub notifications: NotificationsConfig,
+    pub safety: SafetyConfig,
+    pub orientation: OrientationConfig,
+    /// Android notifications that start, pause or trigger automation
+    pub device_notifications: DeviceNotificationsConfig,
+    /// Screenshot and match when `uiautomator events` reports a UI change
+    pub ui_events: UiEventsConfig,
+    /// Custom game states and their template transition rules
+    pub states: StatesConfig,
+    /// Daily window in which running automation sleeps
+    pub quiet_hours: QuietHoursConfig,
+    /// Recovery when no template matches and the screen stays the same
+    pub watchdog: WatchdogConfig,
+    /// Do Not Disturb, muted media and no vibration while automation runs
+    pub silence: SilenceConfig,
+    /// Screen classification against labeled reference screenshots
+    pub scenes: ScenesConfig,
+    /// Named action sequences, `[sequences.<name>]`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub sequences: BTreeMap<String, ActionSequence>,
+    /// Template and UI element conditions that tap or run a sequence, `[[rules]]`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<Rule>,
+    /// Numbers read by OCR after each screenshot, `[counters.<name>]`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub counters: CountersConfig,
+    /// Profile each device runs when it has its own automation, `[devices]`
+    /// `"<device name>" = "<profile>"`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub devices: BTreeMap<String, String>,
+}
+
+impl AutomationConfig {
+    /// Take the values the GUI settings panel edits from `edited`: `[matching]`,
+    /// the screenshot interval, `[touch]` and `[quiet_hours]`; taps and other sections stay
+    pub fn apply_settings(&mut self, edited: &AutomationConfig) {
+        self.matching = edited.matching.clone();
+        self.timed_events.screenshot_interval_minutes =
+            edited.timed_events.screenshot_interval_minutes.max(1);
+        self.touch = edited.touch.clone();
+        self.quiet_hours = edited.quiet_hours.clone();
+    }
+
+    /// Snapshot the FSM's timed events back into the config (taps and screenshot interval)
+    pub fn update_timed_events(&mut self, events: &HashMap<String, TimedEvent>) {
+        let mut taps: Vec<TapEventConfig> = events
+            .values()
+            .filter_map(|event| {
+                let (x, y) = event.tap_point()?;
+                let template = match &event.event_type {
+                    TimedEventType::ConditionalTap { template, .. } => Some(template.clone()),
+                    _ => None,
+                };
+                Some(TapEventConfig {
+                    id: event.id.clone(),
+                    x,
+                    y,
+                    interval_seconds: event.interval.as_secs(),
+                    enabled: event.enabled,
+                    schedule: event.schedule.clone(),
+                    template,
+                    jitter: event.jitter,
+                })
+            })
+            .collect();
+        taps.sort_by(|a, b| a.id.cmp(&b.id));
+        self.timed_events.taps = taps;
+
+        // An adaptive interval changes all the time, the configured one stays
+        if !self.timed_events.adaptive_screenshots.enabled
+            && let Some(screenshot) = events.get("screenshot")
+        {
+            self.timed_events.screenshot_interval_minutes =
+                (screenshot.interval.as_secs() / 60).max(1);
         }
     }
 }
 
-pub fn load_or_create_timed_events(debug_enabled: bool) -> HashMap<String, TimedEvent> {
-    let path = Path::new(TIMED_EVENTS_CONFIG_PATH);
-    match load_or_create_config(path) {
-        Ok(config) => build_timed_events(config),
+/// Per-game configs, `profiles/<name>.toml` next to the default config
+pub const PROFILES_DIR: &str = "profiles";
+/// Profile name of `automation.toml` (or the `run --config` file)
+pub const DEFAULT_PROFILE: &str = "default";
+
+static ACTIVE_CONFIG: OnceLock<RwLock<AutomationConfig>> = OnceLock::new();
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+static ACTIVE_PROFILE: RwLock<String> = RwLock::new(String::new());
+
+fn default_config_path() -> &'static Path {
+    CONFIG_PATH.get_or_init(|| PathBuf::from(AUTOMATION_CONFIG_PATH))
+}
+
+/// File behind `active_config`: the active profile's, `automation.toml` unless
+/// `run --config` chose another
+pub fn config_path() -> PathBuf {
+    profile_path(&active_profile())
+}
+
+/// Config file of profile `name`
+pub fn profile_path(name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        default_config_path().to_path_buf()
+    } else {
+        default_config_path()
+            .with_file_name(PROFILES_DIR)
+            .join(format!("{}.toml", name))
+    }
+}
+
+/// Name of the profile the active config came from
+pub fn active_profile() -> String {
+    ACTIVE_PROFILE
+        .read()
+        .ok()
+        .filter(|name| !name.is_empty())
+        .map(|name| name.clone())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// Profile names can only be used as file names
+pub fn valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// `default` followed by every `profiles/*.toml`, sorted
+pub fn list_profiles() -> Vec<String> {
+    let dir = default_config_path().with_file_name(PROFILES_DIR);
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                .filter_map(|path| path.file_stem()?.to_str().map(str::to_string))
+                .filter(|name| valid_profile_name(name) && name != DEFAULT_PROFILE)
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+/// Make `name` the active profile and return its config. A profile without a
+/// file yet starts as a copy of the current config and is saved right away.
+pub fn switch_profile(name: &str) -> Result<AutomationConfig, String> {
+    if !valid_profile_name(name) {
+        return Err(format!(
+            "Invalid profile name '{}' (letters, digits, - and _ only)",
+            name
+        ));
+    }
+    let path = profile_path(name);
+    let config = if path.exists() {
+        load_config::<AutomationConfig>(&path).map_err(|e| e.to_string())?
+    } else {
+        let config = active_config();
+        save_config(&path, &config).map_err(|e| e.to_string())?;
+        log::info!("🆕 Created profile '{}' at {}", name, path.display());
+        config
+    };
+
+    if let Ok(mut active) = ACTIVE_PROFILE.write() {
+        *active = name.to_string();
+    }
+    set_active_config(config.clone());
+    log::info!("👤 Switched to profile '{}' ({})", name, path.display());
+    Ok(config)
+}
+
+/// Config of profile `name` without making it the active profile
+pub fn load_profile(name: &str) -> Result<AutomationConfig, String> {
+    if !valid_profile_name(name) {
+        return Err(format!(
+            "Invalid profile name '{}' (letters, digits, - and _ only)",
+            name
+        ));
+    }
+    let path = profile_path(name);
+    if !path.exists() {
+        return Err(format!("Profile file not found: {}", path.display()));
+    }
+    load_config::<AutomationConfig>(&path).map_err(|e| e.to_string())
+}
+
+/// Write `config` to profile `name`'s file without touching the active config
+pub fn save_profile(name: &str, config: &AutomationConfig) -> Result<(), String> {
+    save_config(&profile_path(name), config).map_err(|e| e.to_string())
+}
+
+/// Profile `[devices]` assigns to `device`, if any
+pub fn device_profile(device: &str) -> Option<String> {
+    active_config().devices.get(device).cloned()
+}
+
+/// Load and save the config at `path`; only before the config is first used
+pub fn set_config_path(path: impl Into<PathBuf>) -> Result<(), String> {
+    let path = path.into();
+    if !path.is_file() {
+        return Err(format!("Config file not found: {}", path.display()));
+    }
+    CONFIG_PATH
+        .set(path)
+        .map_err(|path| format!("Config path already set, ignoring {}", path.display()))
+}
+
+/// Config shared by the FSM and GUI, loaded from `config_path()` on first use
+pub fn active_config() -> AutomationConfig {
+    ACTIVE_CONFIG
+        .get_or_init(|| RwLock::new(load_or_create_automation_config(false)))
+        .read()
+        .map(|config| config.clone())
+        .unwrap_or_default()
+}
+
+/// Write `config` to `config_path()` and make it the active config
+pub fn save_active_config(config: AutomationConfig) -> Result<(), String> {
+    save_config(&config_path(), &config).map_err(|e| e.to_string())?;
+    set_active_config(config);
+    Ok(())
+}
+
+/// Make `config` the active config without writing it (💾 Save does that)
+pub fn set_active_config(config: AutomationConfig) {
+    let lock = ACTIVE_CONFIG.get_or_init(|| RwLock::new(config.clone()));
+    if let Ok(mut active) = lock.write() {
+        *active = config;
+    }
+}
+
+pub fn load_or_create_automation_config(debug_enabled: bool) -> AutomationConfig {
+    let path = config_path();
+    let result = if path.exists() {
+        load_config::<AutomationConfig>(&path).inspect(|config| {
+            log::info!(
+                "📥 Loaded {} timed events from {}",
+                config.timed_events.taps.len(),
+                path.display()
+            )
+        })
+    } else {
+        create_automation_config(&path)
+    };
+
+    match result {
+        Ok(config) => config,
         Err(ConfigLoadError::InvalidConfig(error)) => {
-            eprintln!("❌ Invalid timed events config ({}). Please fix {} and restart.", error, path.display());
+            log::error!(
+                "❌ Invalid automation config ({}). Please fix {} and restart.",
+                error,
+                path.display()
+            );
             std::process::exit(1);
         }
         Err(error) => {
             debug_print!(
                 debug_enabled,
-                "⚠️ Timed events config error ({}), using defaults",
+                "⚠️ Automation config error ({}), using defaults",
                 error
             );
-            build_timed_events(TimedEventsConfig::default())
+            AutomationConfig::default()
         }
     }
 }
 
+/// Timed events for the FSM, clamped to the supported interval range
+pub fn build_timed_events(config: &TimedEventsConfig) -> HashMap<String, TimedEvent> {
+    let mut timed_events = HashMap::new();
+
+    let screenshot = if config.adaptive_screenshots.enabled {
+        // Start fast, idle screenshots back off from there
+        TimedEvent::new_screenshot_custom(config.adaptive_screenshots.bounds().0)
+    } else {
+        TimedEvent::new_screenshot_minutes(config.screenshot_interval_minutes)
+    };
+    timed_events.insert("screenshot".to_string(), screenshot);
+    timed_events.insert(
+        "countdown_update".to_string(),
+        TimedEvent::new_countdown_update(config.countdown_interval_seconds),
+    );
+
+    for tap in &config.taps {
+        let interval_seconds = tap
+            .interval_seconds
+            .clamp(MIN_TAP_INTERVAL_SECONDS, MAX_TAP_INTERVAL_SECONDS);
+
+        let mut event = match &tap.template {
+            Some(template) => TimedEvent::new_conditional_tap(
+                tap.id.clone(),
+                template.clone(),
+                tap.x,
+                tap.y,
+                Duration::from_secs(interval_seconds),
+            ),
+            None => TimedEvent::new_tap_seconds(tap.id.clone(), tap.x, tap.y, interval_seconds),
+        };
+        event.enabled = tap.enabled;
+        event.set_schedule(tap.schedule.clone());
+        event.set_jitter(tap.jitter);
+        timed_events.insert(tap.id.clone(), event);
+    }
+
+    for backup in &config.backups {
+        let event_type = TimedEventType::BackupFiles {
+            remote_path: backup.remote_path.clone(),
+            local_dir: backup.local_dir.clone(),
+            keep: backup.keep,
+        };
+        let interval = Duration::from_secs(backup.interval_seconds.max(1));
+        let mut event = TimedEvent::new(backup.id.clone(), event_type, interval);
+        event.enabled = backup.enabled;
+        event.set_schedule(backup.schedule.clone());
+        timed_events.insert(backup.id.clone(), event);
+    }
+
+    timed_events
+}
+
+#[derive(Debug)]
 enum ConfigLoadError {
     InvalidConfig(String),
     Other(String),
@@ -88,70 +1061,380 @@ impl std::fmt::Display for ConfigLoadError {
     }
 }
 
-fn load_or_create_config(path: &Path) -> Result<TimedEventsConfig, ConfigLoadError> {
-    if !path.exists() {
-        let default_config = TimedEventsConfig::default();
-        let serialized = toml::to_string_pretty(&default_config)
-            .map_err(|e| ConfigLoadError::Other(format!("Failed to serialize default timed events config: {}", e)))?;
-        let header = format!(
-            "# Default config created {} feel free to edit\n\n",
-            OffsetDateTime::now_utc().date()
+/// First run: start from defaults, importing timed events from the legacy file if present
+fn create_automation_config(path: &Path) -> Result<AutomationConfig, ConfigLoadError> {
+    let mut config = AutomationConfig::default();
+
+    let legacy_path = Path::new(TIMED_EVENTS_CONFIG_PATH);
+    if legacy_path.exists() {
+        config.timed_events = load_config::<TimedEventsConfig>(legacy_path)?;
+        log::info!(
+            "📦 Imported timed events from {} into {}",
+            legacy_path.display(),
+            path.display()
         );
+    }
 
-        if let Some(parent) = path.parent()
-            && !parent.as_os_str().is_empty()
-        {
-            fs::create_dir_all(parent)
-                .map_err(|e| ConfigLoadError::Other(format!("Failed to create config directory: {}", e)))?;
-        }
+    save_config(path, &config)?;
+    log::info!(
+        "🆕 Created {} with {} timed events",
+        path.display(),
+        config.timed_events.taps.len()
+    );
+    Ok(config)
+}
+
+fn load_config<T: DeserializeOwned>(path: &Path) -> Result<T, ConfigLoadError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| ConfigLoadError::Other(format!("Failed to read {}: {}", path.display(), e)))?;
+    toml::from_str::<T>(&content).map_err(|e| {
+        ConfigLoadError::InvalidConfig(format!("Failed to parse {}: {}", path.display(), e))
+    })
+}
+
+fn save_config<T: Serialize>(path: &Path, config: &T) -> Result<(), ConfigLoadError> {
+    let serialized = toml::to_string_pretty(config)
+        .map_err(|e| ConfigLoadError::Other(format!("Failed to serialize config: {}", e)))?;
+    let header = format!(
+        "# Automation config saved {} feel free to edit\n\n",
+        OffsetDateTime::now_utc().date()
+    );
+
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).map_err(|e| {
+            ConfigLoadError::Other(format!("Failed to create config directory: {}", e))
+        })?;
+    }
+
+    fs::write(path, format!("{}{}", header, serialized))
+        .map_err(|e| ConfigLoadError::Other(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        fs::write(path, format!("{}{}", header, serialized))
-            .map_err(|e| ConfigLoadError::Other(format!("Failed to write timed events config file: {}", e)))?;
+    #[test]
+    fn test_partial_config_uses_section_defaults() {
+        let config: AutomationConfig = toml::from_str(
+            "[matching]\nconfidence_threshold = 0.9\ntemplate_dirs = [\"templates\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.matching.confidence_threshold, 0.9);
+        assert_eq!(config.matching.template_dirs, vec!["templates".to_string()]);
+        assert_eq!(config.matching.patch_dir, "assets/test_images");
+        assert_eq!(config.touch.pause_timeout_seconds, 30);
+        assert_eq!(config.timed_events, TimedEventsConfig::default());
+    }
 
-        println!(
-            "🆕 Created {} with {} timed events",
-            path.display(),
-            default_config.taps.len()
+    #[test]
+    fn test_config_round_trip_and_timed_event_snapshot() {
+        let mut config = AutomationConfig::default();
+        let mut events = build_timed_events(&config.timed_events);
+        events.get_mut("claim_1d_tap").unwrap().enabled = false;
+        events
+            .get_mut("restart_tap")
+            .unwrap()
+            .set_schedule(Some("weekdays at 08:00".parse().unwrap()));
+        events.insert(
+            "claim_if_visible".into(),
+            TimedEvent::new_conditional_tap(
+                "claim_if_visible".into(),
+                "claim_button".into(),
+                540,
+                1200,
+                Duration::from_secs(30),
+            ),
         );
+        config.update_timed_events(&events);
+
+        let tap = config
+            .timed_events
+            .taps
+            .iter()
+            .find(|t| t.id == "claim_1d_tap")
+            .unwrap();
+        assert!(!tap.enabled);
+        assert_eq!(config.timed_events.taps.len(), 4);
 
-        return Ok(default_config);
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert!(text.contains("schedule = \"weekdays at 08:00\""));
+        assert!(text.contains("template = \"claim_button\""));
+        let rebuilt = build_timed_events(&config.timed_events);
+        assert_eq!(
+            rebuilt["claim_if_visible"].event_type,
+            events["claim_if_visible"].event_type
+        );
+        assert_eq!(toml::from_str::<AutomationConfig>(&text).unwrap(), config);
     }
 
-    let content = fs::read_to_string(path)
-        .map_err(|e| ConfigLoadError::Other(format!("Failed to read timed events config file: {}", e)))?;
-    let config = toml::from_str::<TimedEventsConfig>(&content)
-        .map_err(|e| ConfigLoadError::InvalidConfig(format!("Failed to parse timed events config file: {}", e)))?;
-
-    println!(
-        "📥 Loaded {} timed events from {}",
-        config.taps.len(),
-        path.display()
-    );
+    #[test]
+    fn test_percent_taps_round_trip() {
+        let text = "[timed_events]\nscreenshot_interval_minutes = 10\ncountdown_interval_seconds = 1\n\n\
+                    [[timed_events.taps]]\nid = \"claim\"\nx = \"10.2%\"\ny = 1200\n\
+                    interval_seconds = 60\nenabled = true\n";
+        let config: AutomationConfig = toml::from_str(text).unwrap();
+        let tap = &config.timed_events.taps[0];
+        assert_eq!((tap.x, tap.y), (Coord::Percent(10.2), Coord::Px(1200)));
 
-    Ok(config)
-}
+        let saved = toml::to_string_pretty(&config).unwrap();
+        assert!(saved.contains("x = \"10.2%\""));
+        assert!(saved.contains("y = 1200"));
+        assert_eq!(toml::from_str::<AutomationConfig>(&saved).unwrap(), config);
+    }
 
-fn build_timed_events(config: TimedEventsConfig) -> HashMap<String, TimedEvent> {
-    let mut timed_events = HashMap::new();
+    #[test]
+    fn test_adaptive_screenshots_keep_configured_interval() {
+        let text = "[timed_events]\nscreenshot_interval_minutes = 10\ncountdown_interval_seconds = 1\ntaps = []\n\n\
+                    [timed_events.adaptive_screenshots]\nenabled = true\nmin_seconds = 15\n";
+        let mut config: AutomationConfig = toml::from_str(text).unwrap();
+        let adaptive = &config.timed_events.adaptive_screenshots;
+        assert_eq!((adaptive.max_seconds, adaptive.backoff), (600, 2.0));
 
-    timed_events.insert(
-        "screenshot".to_string(),
-        TimedEvent::new_screenshot_minutes(config.screenshot_interval_minutes),
-    );
-    timed_events.insert(
-        "countdown_update".to_string(),
-        TimedEvent::new_countdown_update(config.countdown_interval_seconds),
-    );
+        let mut events = build_timed_events(&config.timed_events);
+        assert_eq!(events["screenshot"].interval, Duration::from_secs(15));
+        events.get_mut("screenshot").unwrap().interval = Duration::from_secs(120);
+        config.update_timed_events(&events);
+        assert_eq!(config.timed_events.screenshot_interval_minutes, 10);
+    }
 
-    for tap in config.taps {
-        let interval_seconds = tap
-            .interval_seconds
-            .clamp(MIN_TAP_INTERVAL_SECONDS, MAX_TAP_INTERVAL_SECONDS);
+    #[test]
+    fn test_backup_events_from_toml() {
+        let text = "[timed_events]\nscreenshot_interval_minutes = 10\ncountdown_interval_seconds = 1\ntaps = []\n\n\
+                    [[timed_events.backups]]\nid = \"save_backup\"\n\
+                    remote_path = \"/sdcard/Android/data/com.example.game/files\"\nlocal_dir = \"backups/game\"\n";
+        let config: AutomationConfig = toml::from_str(text).unwrap();
+        let backup = &config.timed_events.backups[0];
+        assert_eq!((backup.interval_seconds, backup.keep), (86400, 7));
+        assert!(backup.enabled);
 
-        let mut event = TimedEvent::new_tap_seconds(tap.id.clone(), tap.x, tap.y, interval_seconds);
-        event.enabled = tap.enabled;
-        timed_events.insert(tap.id, event);
+        let events = build_timed_events(&config.timed_events);
+        let event = &events["save_backup"];
+        assert_eq!(event.interval, Duration::from_secs(86400));
+        assert_eq!(
+            event.event_type,
+            TimedEventType::BackupFiles {
+                remote_path: "/sdcard/Android/data/com.example.game/files".into(),
+                local_dir: "backups/game".into(),
+                keep: 7,
+            }
+        );
+        let saved = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(toml::from_str::<AutomationConfig>(&saved).unwrap(), config);
     }
 
-    timed_events
+    #[test]
+    fn test_apply_settings_keeps_taps_and_other_sections() {
+        let mut config = AutomationConfig::default();
+        config.timed_events.taps.truncate(1);
+        config.app.package = Some("com.example.game".into());
+
+        let mut edited = AutomationConfig::default();
+        edited.matching.patch_threshold = 0.9;
+        edited.matching.template_dirs = vec!["templates".into()];
+        edited.timed_events.screenshot_interval_minutes = 0;
+        edited.touch.pause_timeout_seconds = 5;
+        config.apply_settings(&edited);
+
+        assert_eq!(config.matching, edited.matching);
+        assert_eq!(config.timed_events.screenshot_interval_minutes, 1);
+        assert_eq!(config.touch.pause_timeout_seconds, 5);
+        assert_eq!(config.timed_events.taps.len(), 1);
+        assert_eq!(config.app.package.as_deref(), Some("com.example.game"));
+    }
+
+    #[test]
+    fn test_template_behaviors_from_toml() {
+        use crate::game_automation::match_image::TemplateAction;
+
+        let config: AutomationConfig = toml::from_str(
+            "[matching.templates.claim_button]\npriority = 10\ncooldown_secs = 30\nmax_executions = 5\n\n\
+             [matching.templates.scroll]\naction = \"swipe\"\nswipe_dy = -600\nsource_resolution = [1080, 2400]\n",
+        )
+        .unwrap();
+        let claim = &config.matching.templates["claim_button"];
+        assert_eq!(claim.action, TemplateAction::Tap);
+        assert_eq!((claim.priority, claim.cooldown_secs), (10, 30));
+        assert_eq!(claim.max_executions, Some(5));
+        let scroll = &config.matching.templates["scroll"];
+        assert_eq!(scroll.action, TemplateAction::Swipe);
+        assert_eq!((scroll.swipe_dy, scroll.swipe_duration_ms), (-600, 300));
+        assert_eq!(scroll.source_resolution, Some((1080, 2400)));
+
+        let match_config = config.matching.to_match_config(false);
+        assert_eq!(match_config.template_behaviors.len(), 2);
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(toml::from_str::<AutomationConfig>(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn test_mirror_frame_interval_and_analysis() {
+        let config: AutomationConfig =
+            toml::from_str("[mirror]\nfps = 4.0\nanalyze_every = 3\n").unwrap();
+        assert_eq!(config.mirror.frame_interval(), Duration::from_millis(250));
+        assert!(!config.mirror.analyzes(1));
+        assert!(config.mirror.analyzes(3));
+
+        let fast = MirrorConfig {
+            fps: 1000.0,
+            analyze_every: 0,
+        };
+        assert_eq!(
+            fast.frame_interval(),
+            Duration::from_secs_f64(1.0 / MAX_MIRROR_FPS)
+        );
+        assert!(!fast.analyzes(3));
+        assert_eq!(
+            MirrorConfig {
+                fps: 0.0,
+                ..MirrorConfig::default()
+            }
+            .frame_interval(),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn test_orientation_section() {
+        let config: AutomationConfig =
+            toml::from_str("[orientation]\nexpected = \"landscape\"\nremap_taps = false\n")
+                .unwrap();
+        assert_eq!(config.orientation.expected, Some(Orientation::Landscape));
+        assert!(!config.orientation.remap_taps);
+        assert!(config.orientation.enabled);
+        assert_eq!(config.orientation.poll_interval(), Duration::from_secs(10));
+        assert!(
+            toml::from_str::<AutomationConfig>("[orientation]\nexpected = \"sideways\"\n").is_err()
+        );
+    }
+
+    #[test]
+    fn test_guardrails_pause_and_resume_with_hysteresis() {
+        let guardrails = GuardrailsConfig::default();
+        let mut telemetry = DeviceTelemetry {
+            battery_level: Some(25),
+            charging: Some(false),
+            battery_temp_c: Some(35.0),
+            thermal_status: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(guardrails.pause_reason(&telemetry, false), None);
+        // Between the pause and resume levels: stays paused, but does not pause
+        assert!(guardrails.pause_reason(&telemetry, true).is_some());
+
+        telemetry.battery_level = Some(15);
+        assert_eq!(
+            guardrails.pause_reason(&telemetry, false).as_deref(),
+            Some("🔋 Battery low (15%)")
+        );
+        telemetry.charging = Some(true);
+        assert_eq!(guardrails.pause_reason(&telemetry, true), None);
+
+        telemetry.battery_temp_c = Some(43.0);
+        assert!(guardrails.pause_reason(&telemetry, false).is_some());
+        telemetry.battery_temp_c = Some(40.0);
+        assert!(guardrails.pause_reason(&telemetry, false).is_none());
+        assert!(guardrails.pause_reason(&telemetry, true).is_some());
+
+        telemetry.battery_temp_c = Some(35.0);
+        telemetry.thermal_status = Some(4);
+        assert_eq!(
+            guardrails.pause_reason(&telemetry, false).as_deref(),
+            Some("🔥 Thermal status critical")
+        );
+
+        let disabled = GuardrailsConfig {
+            enabled: false,
+            ..GuardrailsConfig::default()
+        };
+        assert_eq!(disabled.pause_reason(&telemetry, false), None);
+    }
+
+    #[test]
+    fn test_app_restart_backoff() {
+        let config: AutomationConfig =
+            toml::from_str("[app]\npackage = \"com.example.game\"\nactivity = \".MainActivity\"\n")
+                .unwrap();
+        let app = &config.app;
+        assert_eq!(
+            app.launch_component().as_deref(),
+            Some("com.example.game/.MainActivity")
+        );
+        assert_eq!(app.restart_delay(1), Duration::from_secs(5));
+        assert_eq!(app.restart_delay(3), Duration::from_secs(20));
+        assert_eq!(
+            app.restart_delay(30),
+            Duration::from_secs(MAX_RESTART_DELAY_SECONDS)
+        );
+        assert_eq!(AppConfig::default().launch_component(), None);
+    }
+
+    #[test]
+    fn test_profile_names_and_paths() {
+        assert!(valid_profile_name("farm-2_eu"));
+        for bad in ["", "../escape", "two words", "x.toml"] {
+            assert!(!valid_profile_name(bad), "{:?}", bad);
+        }
+        assert_eq!(profile_path(DEFAULT_PROFILE), default_config_path());
+        assert_eq!(
+            profile_path("farm"),
+            default_config_path()
+                .with_file_name(PROFILES_DIR)
+                .join("farm.toml")
+        );
+        assert_eq!(
+            list_profiles().first().map(String::as_str),
+            Some(DEFAULT_PROFILE)
+        );
+    }
+
+    #[test]
+    fn test_device_notification_rules() {
+        let config: AutomationConfig = toml::from_str(
+            r#"
+[device_notifications]
+rules = [
+    { package = "com.example.game", contains = "energy refilled", action = "start" },
+    { contains = "chest", action = "sequence", target = "open_chest" },
+    { contains = "bonus", action = "trigger" },
+]
+"#,
+        )
+        .unwrap();
+        let rules = &config.device_notifications.rules;
+        assert_eq!(config.device_notifications.poll_interval_seconds, 15);
+
+        let refill = DeviceNotification {
+            key: "0|com.example.game|7|null|10234".into(),
+            package: "com.example.game".into(),
+            title: Some("Energy Refilled!".into()),
+            text: None,
+        };
+        assert!(rules[0].matches(&refill));
+        assert!(!rules[0].matches(&DeviceNotification {
+            package: "com.other.app".into(),
+            ..refill.clone()
+        }));
+        assert!(!rules[1].matches(&refill));
+        assert!(matches!(rules[0].command(), Ok(AutomationCommand::Start)));
+        assert!(matches!(
+            rules[1].command(),
+            Ok(AutomationCommand::RunSequence(name)) if name == "open_chest"
+        ));
+        assert!(rules[2].command().is_err());
+    }
+
+    #[test]
+    fn test_devices_map_to_profiles() {
+        let config: AutomationConfig = toml::from_str(
+            "[devices]\n\"R58M123ABC\" = \"game_a\"\n\"192.168.1.20:5555\" = \"game_b\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.devices["192.168.1.20:5555"], "game_b");
+        assert!(load_profile("../escape").is_err());
+        let text = toml::to_string(&AutomationConfig::default()).unwrap();
+        assert!(!text.contains("[devices]"));
+    }
 }
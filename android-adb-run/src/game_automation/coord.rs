@@ -0,0 +1,129 @@
+// Tap coordinates in device pixels or as a percentage of the screen (`"54%"`),
+// resolved against the screen size when the tap is sent, so one automation.toml
+// works across phones with different resolutions.
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// One axis of a tap point: `540` pixels, or `"54%"` of the screen width or height
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coord {
+    Px(u32),
+    Percent(f64),
+}
+
+impl Coord {
+    /// Pixel position along a screen side `side` pixels long
+    pub fn resolve(self, side: u32) -> u32 {
+        match self {
+            Self::Px(px) => px,
+            Self::Percent(percent) => {
+                ((percent / 100.0 * side as f64).round() as u32).min(side.saturating_sub(1))
+            }
+        }
+    }
+
+    pub fn is_percent(self) -> bool {
+        matches!(self, Self::Percent(_))
+    }
+}
+
+/// `(x, y)` in pixels on a `screen`-sized display
+pub fn resolve_point((x, y): (Coord, Coord), (width, height): (u32, u32)) -> (u32, u32) {
+    (x.resolve(width), y.resolve(height))
+}
+
+impl From<u32> for Coord {
+    fn from(px: u32) -> Self {
+        Self::Px(px)
+    }
+}
+
+impl FromStr for Coord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(percent) => match percent.trim().parse::<f64>() {
+                Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(Self::Percent(percent)),
+                _ => Err(format!("'{}' is not a percentage from 0% to 100%", s)),
+            },
+            None => s
+                .parse()
+                .map(Self::Px)
+                .map_err(|_| format!("'{}' is neither pixels nor a percentage like \"54%\"", s)),
+        }
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Px(px) => write!(f, "{}", px),
+            Self::Percent(percent) => write!(f, "{}%", percent),
+        }
+    }
+}
+
+impl Serialize for Coord {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Px(px) => serializer.serialize_u32(*px),
+            Self::Percent(_) => serializer.collect_str(self),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Coord {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CoordVisitor;
+
+        impl Visitor<'_> for CoordVisitor {
+            type Value = Coord;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("pixels or a percentage like \"54%\"")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Coord, E> {
+                u32::try_from(value)
+                    .map(Coord::Px)
+                    .map_err(|_| E::custom(format!("{} pixels is out of range", value)))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Coord, E> {
+                u64::try_from(value)
+                    .map_err(|_| E::custom(format!("{} pixels is out of range", value)))
+                    .and_then(|value| self.visit_u64(value))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Coord, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(CoordVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_coords_resolve_per_screen() {
+        let point = ("10.2%".parse().unwrap(), "54%".parse().unwrap());
+        assert_eq!(resolve_point(point, (1080, 2400)), (110, 1296));
+        assert_eq!(resolve_point(point, (1440, 3200)), (147, 1728));
+        assert_eq!(Coord::Percent(100.0).resolve(1080), 1079);
+        assert_eq!(Coord::from(540).resolve(1440), 540);
+
+        assert_eq!("540".parse::<Coord>(), Ok(Coord::Px(540)));
+        assert!("140%".parse::<Coord>().is_err());
+        assert!("left".parse::<Coord>().is_err());
+        assert_eq!(Coord::Percent(10.2).to_string(), "10.2%");
+    }
+}
@@ -0,0 +1,126 @@
+// OCR counters - numbers such as energy or gold read from fixed screen regions
+// after every automation screenshot. From `[counters]` in automation.toml:
+//
+//   [counters.energy]
+//   region = [40, 120, 180, 60]      # x, y, width, height in screen pixels
+//
+// Readings are kept (the last `MAX_COUNTER_SAMPLES` per counter) for the GUI
+// graph, and scripts read the latest with `counter("energy")`. Needs the `ocr`
+// feature; without it the counters stay empty.
+use super::match_image::OcrDetector;
+use image::RgbImage;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Readings kept per counter
+pub const MAX_COUNTER_SAMPLES: usize = 240;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CounterConfig {
+    /// `[x, y, width, height]` of the number on screen
+    pub region: [u32; 4],
+}
+
+/// `[counters.<name>]` sections
+pub type CountersConfig = BTreeMap<String, CounterConfig>;
+
+/// One reading, at a unix timestamp in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CounterSample {
+    pub at: i64,
+    pub value: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CounterSeries {
+    pub samples: VecDeque<CounterSample>,
+}
+
+impl CounterSeries {
+    pub fn push(&mut self, sample: CounterSample) {
+        if self.samples.len() == MAX_COUNTER_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn latest(&self) -> Option<i64> {
+        self.samples.back().map(|sample| sample.value)
+    }
+
+    /// Smallest and largest value read, for scaling a graph
+    pub fn range(&self) -> Option<(i64, i64)> {
+        let values = self.samples.iter().map(|sample| sample.value);
+        Some((values.clone().min()?, values.max()?))
+    }
+}
+
+/// Readings by counter name
+pub type CounterReadings = BTreeMap<String, CounterSeries>;
+
+/// Latest value of every counter, as handed to scripts
+pub fn latest_values(readings: &CounterReadings) -> HashMap<String, i64> {
+    readings
+        .iter()
+        .filter_map(|(name, series)| Some((name.clone(), series.latest()?)))
+        .collect()
+}
+
+/// `region` cut out of `image`, clipped to its edges; None when nothing is left
+pub fn crop_region(image: &RgbImage, [x, y, width, height]: [u32; 4]) -> Option<RgbImage> {
+    let width = width.min(image.width().saturating_sub(x));
+    let height = height.min(image.height().saturating_sub(y));
+    if width == 0 || height == 0 {
+        return None;
+    }
+    Some(image::imageops::crop_imm(image, x, y, width, height).to_image())
+}
+
+/// Read every counter on `image`: the first number recognized in its region,
+/// None when the region shows no number
+pub fn read_counters(
+    detector: &OcrDetector,
+    image: &RgbImage,
+    counters: &CountersConfig,
+) -> Vec<(String, Result<Option<i64>, String>)> {
+    counters
+        .iter()
+        .map(|(name, counter)| {
+            let value = match crop_region(image, counter.region) {
+                Some(region) => detector
+                    .recognize(&region)
+                    .map(|lines| lines.iter().find_map(|line| line.number())),
+                None => Err(format!("region {:?} is off screen", counter.region)),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_series_and_regions() {
+        let counters: CountersConfig =
+            toml::from_str("[energy]\nregion = [40, 120, 180, 60]").unwrap();
+        assert_eq!(counters["energy"].region, [40, 120, 180, 60]);
+
+        let mut series = CounterSeries::default();
+        assert_eq!(series.latest(), None);
+        for at in 0..MAX_COUNTER_SAMPLES as i64 + 5 {
+            series.push(CounterSample { at, value: at % 50 });
+        }
+        assert_eq!(series.samples.len(), MAX_COUNTER_SAMPLES);
+        assert_eq!(series.samples.front().unwrap().at, 5);
+        assert_eq!(series.range(), Some((0, 49)));
+        let readings = CounterReadings::from([("energy".to_string(), series)]);
+        assert_eq!(latest_values(&readings)["energy"], 244 % 50);
+
+        let screen = RgbImage::new(100, 200);
+        let clipped = crop_region(&screen, [80, 190, 50, 50]).unwrap();
+        assert_eq!(clipped.dimensions(), (20, 10));
+        assert!(crop_region(&screen, [100, 0, 10, 10]).is_none());
+    }
+}
@@ -0,0 +1,45 @@
+use super::*;
+use crate::game_automation::backup;
+use std::path::Path;
+
+impl GameAutomation {
+    /// Copy `remote_path` off the device for a `[[timed_events.backups]]` event
+    pub(super) async fn run_backup(
+        &mut self,
+        event_id: &str,
+        remote_path: &str,
+        local_dir: &str,
+        keep: usize,
+    ) -> Result<(), String> {
+        let client = self
+            .adb_client
+            .clone()
+            .ok_or_else(|| "ADB client not available".to_string())?;
+
+        log::info!(
+            "💾 {}: backing up {} to {}",
+            event_id,
+            remote_path,
+            local_dir
+        );
+        let result = {
+            let client_guard = client.lock().await;
+            backup::run_backup(&*client_guard, remote_path, Path::new(local_dir), keep).await
+        };
+        let report = result.map_err(|e| format!("Backup '{}' failed: {}", event_id, e))?;
+
+        let message = format!(
+            "💾 {}: {} file(s), {} KB saved to {}",
+            event_id,
+            report.files,
+            report.bytes.div_ceil(1024),
+            report.folder.display()
+        );
+        log::info!("{}", message);
+        if report.pruned > 0 {
+            log::info!("💾 {}: removed {} old backup(s)", event_id, report.pruned);
+        }
+        *self.screenshot_status.write_unchecked() = message;
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::game_automation::config::{active_config, set_active_config};
 
 impl GameAutomation {
     pub(super) async fn process_command(&mut self, command: AutomationCommand) {
@@ -16,15 +17,28 @@ impl GameAutomation {
                 );
                 if !self.is_running {
                     if self.adb_client.is_none() {
-                        println!("⚠️ Cannot start automation: ADB client not initialized");
+                        log::warn!("⚠️ Cannot start automation: ADB client not initialized");
                         return;
                     }
 
                     self.is_running = true;
+                    // Each run starts with fresh template cooldowns and execution limits
+                    self.action_history.clear();
+                    self.rule_cooldowns.reset();
+                    self.last_frame = None;
+                    self.next_app_check = None;
+                    self.clear_stuck();
+                    self.stats.start_run();
+                    *self.custom_state.write_unchecked() = self
+                        .restored_custom_state
+                        .take()
+                        .or_else(|| self.config.states.initial.clone());
                     self.change_state(GameState::Running).await;
-                    println!(
+                    self.silence_device().await;
+                    log::info!(
                         "🚀 Game automation STARTED. is_running={}, state={:?}",
-                        self.is_running, self.state
+                        self.is_running,
+                        self.state
                     );
 
                     debug_print!(
@@ -61,12 +75,15 @@ impl GameAutomation {
             }
             AutomationCommand::Pause => {
                 if self.is_running {
+                    self.clear_recovery();
                     self.change_state(GameState::Paused).await;
                     debug_print!(self.debug_enabled, "⏸️ Game automation paused");
                 }
             }
             AutomationCommand::Resume => {
                 if self.is_running && self.state == GameState::Paused {
+                    // A manual resume overrides a guardrail until it trips again
+                    self.clear_health_pause();
                     self.change_state(GameState::Running).await;
                     debug_print!(self.debug_enabled, "▶️ Game automation resumed");
                     self.send_timed_events_list().await;
@@ -75,6 +92,8 @@ impl GameAutomation {
             }
             AutomationCommand::Stop => {
                 self.is_running = false;
+                self.stats.stop_run();
+                self.save_runtime_state();
 
                 if let Some(client_arc) = &self.adb_client {
                     let client_guard = client_arc.lock().await;
@@ -89,6 +108,10 @@ impl GameAutomation {
                     }
                 }
 
+                self.clear_health_pause();
+                self.clear_recovery();
+                self.clear_sequence_progress();
+                self.restore_device_sound().await;
                 self.change_state(GameState::Idle).await;
                 debug_print!(self.debug_enabled, "⏹️ Game automation stopped");
             }
@@ -121,12 +144,14 @@ impl GameAutomation {
                             e
                         );
                     } else {
+                        let pause_secs = self.config.touch.pause_timeout_seconds;
                         debug_print!(
                             self.debug_enabled,
-                            "👆 GUI touch registered - pausing automation for 30s"
+                            "👆 GUI touch registered - pausing automation for {}s",
+                            pause_secs
                         );
                         *self.is_paused_by_touch.write_unchecked() = true;
-                        *self.touch_timeout_remaining.write_unchecked() = Some(30);
+                        *self.touch_timeout_remaining.write_unchecked() = Some(pause_secs);
                     }
                 }
             }
@@ -144,13 +169,19 @@ impl GameAutomation {
                     *self.screenshot_status.write_unchecked() = format!("❌ {}", e);
                 }
             }
+            AutomationCommand::TestTemplate(name) => {
+                if let Err(e) = self.test_template(&name).await {
+                    *self.screenshot_status.write_unchecked() =
+                        format!("❌ Test of {} failed: {}", name, e);
+                }
+            }
             AutomationCommand::RescanTemplates => {
                 debug_print!(self.debug_enabled, "🔄 Template rescan requested");
                 if let Err(e) = self.rescan_templates().await {
                     *self.screenshot_status.write_unchecked() = format!("❌ {}", e);
                 }
             }
-            AutomationCommand::AddTimedEvent(event) => {
+            AutomationCommand::AddTimedEvent(mut event) => {
                 debug_print!(
                     self.debug_enabled,
                     "➕ Adding timed event '{}': {:?} every {}s",
@@ -158,11 +189,18 @@ impl GameAutomation {
                     event.event_type,
                     event.interval.as_secs()
                 );
+                // Replacing (editing) an event keeps its run count
+                if let Some(previous) = self.timed_events.get(&event.id) {
+                    event.execution_count = previous.execution_count;
+                }
                 self.timed_events.insert(event.id.clone(), event);
+                self.plan_schedules();
+                self.send_timed_events_list().await;
             }
             AutomationCommand::RemoveTimedEvent(id) => {
                 if self.timed_events.remove(&id).is_some() {
                     debug_print!(self.debug_enabled, "➖ Removed timed event '{}'", id);
+                    self.send_timed_events_list().await;
                 } else {
                     debug_print!(
                         self.debug_enabled,
@@ -199,7 +237,7 @@ impl GameAutomation {
             }
             AutomationCommand::AdjustTimedEventInterval { id, delta_seconds } => {
                 if let Some(event) = self.timed_events.get_mut(&id) {
-                    if matches!(event.event_type, TimedEventType::Tap { .. }) {
+                    if event.tap_point().is_some() {
                         let current_secs = event.interval.as_secs();
                         let current_secs_i64 = current_secs as i64;
                         let min_secs = MIN_TAP_INTERVAL_SECONDS as i64;
@@ -241,6 +279,34 @@ impl GameAutomation {
                     );
                 }
             }
+            AutomationCommand::SetTimedEventSchedule { id, schedule } => {
+                if let Some(event) = self.timed_events.get_mut(&id) {
+                    match &schedule {
+                        Some(schedule) => debug_print!(
+                            self.debug_enabled,
+                            "📅 Timed event '{}' now runs {}",
+                            id,
+                            schedule
+                        ),
+                        None => debug_print!(
+                            self.debug_enabled,
+                            "⏱️ Timed event '{}' back to every {}s",
+                            id,
+                            event.interval.as_secs()
+                        ),
+                    }
+                    event.set_schedule(schedule);
+                    self.plan_schedules();
+                    self.send_timed_events_list().await;
+                    self.send_timed_tap_countdowns().await;
+                } else {
+                    debug_print!(
+                        self.debug_enabled,
+                        "⚠️ Timed event '{}' not found for scheduling",
+                        id
+                    );
+                }
+            }
             AutomationCommand::TriggerTimedEvent(id) => {
                 if let Some(event) = self.timed_events.get(&id) {
                     if event.enabled {
@@ -253,10 +319,34 @@ impl GameAutomation {
                             TimedEventType::Screenshot => {
                                 let _ = self.take_screenshot().await;
                             }
+                            TimedEventType::Script { ref name } => {
+                                let name = name.clone();
+                                if let Err(e) = self.run_script(&name).await {
+                                    *self.screenshot_status.write_unchecked() = format!("❌ {}", e);
+                                }
+                            }
                             TimedEventType::Tap { x, y } => {
-                                if let Some(adb_client) = &self.adb_client {
+                                let screen = self.tap_screen();
+                                let (x, y) = event
+                                    .jitter
+                                    .tap_point(resolve_point((x, y), screen), screen);
+                                let Some(device_point) = self.oriented_tap(x, y) else {
+                                    *self.screenshot_status.write_unchecked() = format!(
+                                        "🔄 {} not sent: device turned away from the expected orientation",
+                                        id
+                                    );
+                                    return;
+                                };
+                                let entry =
+                                    self.history_entry(HistoryAction::Tap, &id, device_point);
+                                if let Err(blocked) = self.allow_tap(x, y) {
+                                    self.report_blocked_tap(entry, &blocked);
+                                } else if let Some(adb_client) = &self.adb_client {
+                                    let (x, y) = device_point;
                                     let client = adb_client.lock().await;
-                                    if let Err(e) = client.tap(x, y).await {
+                                    let result = client.tap(x, y).await;
+                                    history::record(entry.with_result(&result));
+                                    if let Err(e) = result {
                                         debug_print!(
                                             self.debug_enabled,
                                             "⚠️ Failed to execute tap ({}, {}): {}",
@@ -264,7 +354,7 @@ impl GameAutomation {
                                             y,
                                             e
                                         );
-                                        if is_disconnect_error(&e.to_string()) {
+                                        if e.needs_reconnect() {
                                             debug_print!(
                                                 self.debug_enabled,
                                                 "🔌 Device disconnect detected during manual tap trigger: {}",
@@ -279,12 +369,42 @@ impl GameAutomation {
                                                 "🔌 USB DISCONNECTED: {} - Please reconnect",
                                                 e
                                             );
-                                            *self.status.write_unchecked() =
-                                                "🔌 Device Disconnected - Paused".to_string();
+                                            self.status.write_unchecked().apply(
+                                                ConnectionEvent::Lost(
+                                                    DisconnectReason::AutomationPaused,
+                                                ),
+                                            );
                                         }
                                     }
                                 }
                             }
+                            TimedEventType::Sequence { ref name } => {
+                                let name = name.clone();
+                                if let Err(e) = self.run_sequence(&name).await {
+                                    *self.screenshot_status.write_unchecked() = format!("❌ {}", e);
+                                }
+                            }
+                            TimedEventType::BackupFiles {
+                                ref remote_path,
+                                ref local_dir,
+                                keep,
+                            } => {
+                                let (remote_path, local_dir) =
+                                    (remote_path.clone(), local_dir.clone());
+                                if let Err(e) =
+                                    self.run_backup(&id, &remote_path, &local_dir, keep).await
+                                {
+                                    *self.screenshot_status.write_unchecked() = format!("❌ {}", e);
+                                }
+                            }
+                            TimedEventType::ConditionalTap { .. } => {
+                                // Checks the template and records the run (or skip) itself
+                                let event_type = event.event_type.clone();
+                                if let Err(e) = self.execute_timed_event(&id, &event_type).await {
+                                    *self.screenshot_status.write_unchecked() = format!("❌ {}", e);
+                                }
+                                return;
+                            }
                             TimedEventType::CountdownUpdate => {
                                 debug_print!(
                                     self.debug_enabled,
@@ -336,12 +456,143 @@ impl GameAutomation {
                 }
                 *self.timed_events_list.write_unchecked() = events;
             }
+            AutomationCommand::SwitchDevice(handle) => {
+                if let Some(current) = &self.adb_client
+                    && Arc::ptr_eq(current, &handle.0)
+                {
+                    return;
+                }
+
+                self.restore_device_sound().await;
+                if let Some(old_client) = &self.adb_client {
+                    let client_guard = old_client.lock().await;
+                    if let Err(e) = client_guard.stop_touch_monitoring().await {
+                        debug_print!(
+                            self.debug_enabled,
+                            "⚠️ Failed to stop touch monitoring on previous device: {}",
+                            e
+                        );
+                    }
+                }
+
+                self.device_disconnected = false;
+                self.last_reconnect_attempt = None;
+                self.latest_screenshot = None;
+                self.last_frame = None;
+                self.notification_watcher.reset();
+                self.reset_orientation();
+                if let Err(e) = self.set_shared_adb_client(handle.0).await {
+                    log::error!("❌ Failed to switch automation device: {}", e);
+                    return;
+                }
+                log::info!(
+                    "📱 Automation now driving {}",
+                    self.device_key.as_deref().unwrap_or("?")
+                );
+                self.send_timed_events_list().await;
+                self.send_timed_tap_countdowns().await;
+            }
+            AutomationCommand::SaveConfig => {
+                self.config.update_timed_events(&self.timed_events);
+                self.publish_config();
+                let message = match self.save_config() {
+                    Ok(path) => format!("💾 Saved timed events to {}", path.display()),
+                    Err(e) => format!("❌ Failed to save config: {}", e),
+                };
+                log::info!("{}", message);
+                *self.screenshot_status.write_unchecked() = message;
+            }
+            AutomationCommand::RunSequence(name) => {
+                if let Err(e) = self.run_sequence(&name).await {
+                    log::error!("❌ {}", e);
+                    *self.screenshot_status.write_unchecked() = format!("❌ {}", e);
+                }
+            }
+            AutomationCommand::ExportStats(path) => {
+                let message = match self.stats.summary().export(std::path::Path::new(&path)) {
+                    Ok(()) => format!("📊 Statistics written to {}", path),
+                    Err(e) => format!("❌ {}", e),
+                };
+                log::info!("{}", message);
+                *self.screenshot_status.write_unchecked() = message;
+            }
+            AutomationCommand::SetNotifications(enabled) => {
+                self.config.notifications.enabled = enabled;
+                self.publish_config();
+                let message = if enabled {
+                    "🔔 Desktop notifications on"
+                } else {
+                    "🔕 Desktop notifications off"
+                };
+                log::info!("{}", message);
+                *self.screenshot_status.write_unchecked() = message.to_string();
+            }
+            AutomationCommand::UpdateConfig {
+                config,
+                debug_enabled,
+            } => {
+                let dirs_changed =
+                    config.matching.template_dirs != self.config.matching.template_dirs;
+                self.config.apply_settings(&config);
+                self.debug_enabled = debug_enabled;
+                let mut detector = GameStateDetector::clone(&self.game_detector);
+                detector.update_config(self.config.matching.to_match_config(self.debug_enabled));
+                self.game_detector = Arc::new(detector);
+                if dirs_changed && let Err(e) = self.rescan_templates().await {
+                    log::warn!("⚠️ Template rescan after settings change failed: {}", e);
+                }
+
+                let interval =
+                    Duration::from_secs(self.config.timed_events.screenshot_interval_minutes * 60);
+                if !self.config.timed_events.adaptive_screenshots.enabled
+                    && let Some(event) = self.timed_events.get_mut("screenshot")
+                    && event.interval != interval
+                {
+                    event.set_interval(interval);
+                }
+                self.apply_touch_timeout().await;
+                self.send_timed_events_list().await;
+
+                self.config.update_timed_events(&self.timed_events);
+                self.publish_config();
+                let message = match self.save_config() {
+                    Ok(path) => format!("⚙️ Settings saved to {}", path.display()),
+                    Err(e) => format!("❌ Settings applied but not saved: {}", e),
+                };
+                log::info!("{}", message);
+                *self.screenshot_status.write_unchecked() = message;
+            }
+            AutomationCommand::SetTouchTimeout(seconds) => {
+                self.config.touch.pause_timeout_seconds = seconds;
+                self.apply_touch_timeout().await;
+                // Kept for the GUI and the next 💾 Save, not written yet
+                self.publish_config();
+                if self.profile.is_none() {
+                    let mut active = active_config();
+                    active.touch.pause_timeout_seconds = seconds;
+                    set_active_config(active);
+                }
+                let message = format!("👆 Touch pause set to {}s", seconds);
+                log::info!("{}", message);
+                *self.screenshot_status.write_unchecked() = message;
+            }
+            AutomationCommand::SwitchProfile(name) => self.switch_profile(&name).await,
             AutomationCommand::Shutdown => {
                 self.should_exit = true;
                 self.is_running = false;
                 self.change_state(GameState::Idle).await;
-                println!("🛑 Game automation shutting down");
+                log::info!("🛑 Game automation shutting down");
             }
         }
     }
+
+    /// Give the live touch monitor the `[touch]` pause
+    pub(super) async fn apply_touch_timeout(&self) {
+        if let Some(client_arc) = &self.adb_client {
+            let client_guard = client_arc.lock().await;
+            client_guard
+                .set_touch_timeout(Duration::from_secs(self.config.touch.pause_timeout_seconds))
+                .await;
+        }
+    }
 }
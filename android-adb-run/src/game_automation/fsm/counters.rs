@@ -0,0 +1,50 @@
+use super::*;
+use crate::game_automation::counters::{CounterSample, read_counters};
+use crate::game_automation::match_image::shared_detector;
+
+impl GameAutomation {
+    /// Read the `[counters]` regions of a fresh screenshot and add the values to their series
+    pub(super) async fn read_counters(&mut self, frame: &Arc<RgbFrame>) {
+        if self.config.counters.is_empty() || self.counters_unavailable {
+            return;
+        }
+        let frame = frame.clone();
+        let counters = self.config.counters.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let detector = shared_detector()?;
+            Ok::<_, String>(read_counters(detector, &frame.to_rgb_image(), &counters))
+        })
+        .await;
+        let readings = match result {
+            Ok(Ok(readings)) => readings,
+            Ok(Err(e)) => {
+                log::warn!("⚠️ Counters not read: {}", e);
+                self.counters_unavailable = true;
+                return;
+            }
+            Err(e) => {
+                log::warn!("⚠️ Counter OCR task failed: {}", e);
+                return;
+            }
+        };
+
+        let at = time::OffsetDateTime::now_utc().unix_timestamp();
+        let mut samples = Vec::new();
+        for (name, value) in readings {
+            match value {
+                Ok(Some(value)) => {
+                    debug_print!(self.debug_enabled, "🔢 Counter '{}': {}", name, value);
+                    samples.push((name, CounterSample { at, value }));
+                }
+                Ok(None) => debug_print!(self.debug_enabled, "🔢 Counter '{}': no number", name),
+                Err(e) => log::warn!("⚠️ Counter '{}': {}", name, e),
+            }
+        }
+        if !samples.is_empty() {
+            let mut series = self.counters.write_unchecked();
+            for (name, sample) in samples {
+                series.entry(name).or_default().push(sample);
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use super::*;
+
+impl GameAutomation {
+    /// Check the device's notifications every `poll_interval_seconds` and send the
+    /// command of each `[device_notifications]` rule a newly posted one matches
+    pub(super) async fn poll_device_notifications(&mut self) {
+        let config = &self.config.device_notifications;
+        if config.rules.is_empty()
+            || self
+                .last_notification_poll
+                .is_some_and(|last| last.elapsed() < config.poll_interval())
+        {
+            return;
+        }
+        let Some(client) = self.adb_client.clone() else {
+            return;
+        };
+        self.last_notification_poll = Some(std::time::Instant::now());
+
+        let result = client.lock().await.notifications().await;
+        let posted = match result {
+            Ok(current) => self.notification_watcher.update(current),
+            Err(e) => {
+                log::warn!("⚠️ Notification poll failed: {}", e);
+                return;
+            }
+        };
+
+        for notification in posted {
+            debug_print!(
+                self.debug_enabled,
+                "📨 Notification from {}: {}",
+                notification.package,
+                notification.summary()
+            );
+            let commands: Vec<_> = self
+                .config
+                .device_notifications
+                .rules
+                .iter()
+                .filter(|rule| rule.matches(&notification))
+                .map(|rule| rule.command())
+                .collect();
+            for command in commands {
+                match command {
+                    Ok(command) => {
+                        log::info!(
+                            "📨 '{}' from {} → {:?}",
+                            notification.summary(),
+                            notification.package,
+                            command
+                        );
+                        *self.screenshot_status.write_unchecked() =
+                            format!("📨 {} → {:?}", notification.summary(), command);
+                        self.process_command(command).await;
+                    }
+                    Err(e) => log::warn!("⚠️ Notification rule skipped: {}", e),
+                }
+            }
+        }
+    }
+}
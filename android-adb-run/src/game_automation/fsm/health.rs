@@ -0,0 +1,85 @@
+use super::*;
+use crate::adb::telemetry::TELEMETRY_INTERVAL;
+use dioxus::prelude::ReadableExt;
+
+impl GameAutomation {
+    /// Sample device health every `TELEMETRY_INTERVAL`, whether or not automation runs
+    pub(super) async fn poll_telemetry(&mut self) {
+        if self
+            .last_telemetry_poll
+            .is_some_and(|last| last.elapsed() < TELEMETRY_INTERVAL)
+        {
+            return;
+        }
+        let Some(client) = self.adb_client.clone() else {
+            return;
+        };
+        self.last_telemetry_poll = Some(std::time::Instant::now());
+
+        let result = client.lock().await.telemetry().await;
+        match result {
+            Ok(telemetry) => {
+                debug_print!(
+                    self.debug_enabled,
+                    "🩺 Telemetry: battery {:?}% charging={:?} {:?}°C thermal={:?} mem {:?}/{:?}MB",
+                    telemetry.battery_level,
+                    telemetry.charging,
+                    telemetry.battery_temp_c,
+                    telemetry.thermal_label(),
+                    telemetry.mem_available_mb,
+                    telemetry.mem_total_mb
+                );
+                self.apply_guardrails(&telemetry).await;
+                if let Some(level) = telemetry.battery_level
+                    && telemetry.charging != Some(true)
+                {
+                    self.notifier
+                        .notify(&self.config.notifications, Notice::BatteryLow { level });
+                }
+                self.device_telemetry.set(Some(telemetry));
+            }
+            Err(e) => log::warn!("⚠️ Telemetry poll failed: {}", e),
+        }
+    }
+
+    /// Pause a running automation when a health limit is crossed, and resume it
+    /// once the device is back within the resume levels
+    async fn apply_guardrails(&mut self, telemetry: &DeviceTelemetry) {
+        let held = self.health_pause.peek().clone();
+        let reason = self
+            .config
+            .guardrails
+            .pause_reason(telemetry, held.is_some());
+
+        match (held, reason) {
+            (None, Some(reason)) => {
+                if self.is_running && self.state == GameState::Running {
+                    log::warn!("⏸️ Automation paused: {}", reason);
+                    self.change_state(GameState::Paused).await;
+                    self.health_pause.set(Some(reason));
+                }
+            }
+            (Some(held), Some(reason)) => {
+                if held != reason {
+                    self.health_pause.set(Some(reason));
+                }
+            }
+            (Some(held), None) => {
+                self.clear_health_pause();
+                if self.is_running && self.state == GameState::Paused {
+                    log::info!("▶️ Automation resumed, device recovered from: {}", held);
+                    self.change_state(GameState::Running).await;
+                    self.send_timed_events_list().await;
+                    self.send_timed_tap_countdowns().await;
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    pub(super) fn clear_health_pause(&mut self) {
+        if self.health_pause.peek().is_some() {
+            self.health_pause.set(None);
+        }
+    }
+}
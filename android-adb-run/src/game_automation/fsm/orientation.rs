@@ -0,0 +1,102 @@
+use super::*;
+
+impl GameAutomation {
+    /// Read the device rotation every `[orientation] poll_interval_seconds` and
+    /// announce turns away from, and back to, the expected orientation
+    pub(super) async fn poll_orientation(&mut self) {
+        let config = &self.config.orientation;
+        if !config.enabled
+            || self
+                .last_orientation_poll
+                .is_some_and(|last| last.elapsed() < config.poll_interval())
+        {
+            return;
+        }
+        let Some(client) = self.adb_client.clone() else {
+            return;
+        };
+        self.last_orientation_poll = Some(std::time::Instant::now());
+
+        let result = client.lock().await.orientation().await;
+        let current = match result {
+            Ok(orientation) => orientation,
+            Err(e) => {
+                log::warn!("⚠️ Orientation poll failed: {}", e);
+                return;
+            }
+        };
+        let expected = self
+            .config
+            .orientation
+            .expected
+            .unwrap_or(*self.first_orientation.get_or_insert(current));
+        let status = OrientationStatus { current, expected };
+        let previous = *self.device_orientation.peek();
+        if previous == Some(status) {
+            return;
+        }
+
+        if status.rotated() || previous.is_some_and(|previous| previous.rotated()) {
+            let message = if !status.rotated() {
+                format!("🔄 Device back in {}, matching resumed", current)
+            } else if self.config.orientation.remap_taps {
+                format!(
+                    "🔄 Device turned to {} (expected {}): taps remapped, matching paused",
+                    current, expected
+                )
+            } else {
+                format!(
+                    "🔄 Device turned to {} (expected {}): taps and matching paused",
+                    current, expected
+                )
+            };
+            log::info!("{}", message);
+            *self.screenshot_status.write_unchecked() = message;
+        }
+        self.device_orientation.set(Some(status));
+    }
+
+    /// Why template matching has to wait: the device is turned away from the
+    /// orientation the templates were cut in
+    pub(super) fn rotation_hold(&self) -> Option<String> {
+        let status = (*self.device_orientation.peek())?;
+        (self.config.orientation.enabled && status.rotated()).then(|| {
+            format!(
+                "device in {}, templates made in {}",
+                status.current, status.expected
+            )
+        })
+    }
+
+    /// Tap `(x, y)`, given in the expected orientation, as seen on the device now;
+    /// `None` while the device is turned and `[orientation] remap_taps` is off
+    pub(super) fn oriented_tap(&self, x: u32, y: u32) -> Option<(u32, u32)> {
+        match *self.device_orientation.peek() {
+            Some(status) if self.config.orientation.enabled && status.rotated() => {
+                self.config.orientation.remap_taps.then(|| {
+                    status
+                        .expected
+                        .remap(status.current, (x, y), self.game_detector.screen_size())
+                })
+            }
+            _ => Some((x, y)),
+        }
+    }
+
+    /// Screen size taps are written against: the `wm size` screen turned to the
+    /// expected orientation, so `"54%"` means the same spot on every phone
+    pub(super) fn tap_screen(&self) -> (u32, u32) {
+        let natural = self.game_detector.screen_size();
+        match *self.device_orientation.peek() {
+            Some(status) => status.expected.screen_size(natural),
+            None => natural,
+        }
+    }
+
+    /// Forget the rotation of the previous device
+    pub(super) fn reset_orientation(&mut self) {
+        self.first_orientation = None;
+        self.last_orientation_poll = None;
+        self.device_orientation.set(None);
+    }
+}
@@ -0,0 +1,67 @@
+use super::*;
+use crate::game_automation::config::active_profile;
+use crate::game_automation::runtime_state::{
+    RUNTIME_STATE_SAVE_INTERVAL, RuntimeState, device_state_path, profile_state_path,
+};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+impl GameAutomation {
+    /// State file of this automation's `[devices]` profile, or the active one, and
+    /// of its device when it runs in a device tab
+    fn state_path(&self) -> PathBuf {
+        let profile = self.profile.clone().unwrap_or_else(active_profile);
+        match &self.device {
+            Some(device) => device_state_path(&profile, device),
+            None => profile_state_path(&profile),
+        }
+    }
+
+    /// Pick up timed event counters, schedules and the game state saved by the
+    /// previous run of the app
+    pub(super) fn restore_runtime_state(&mut self) {
+        let path = self.state_path();
+        match RuntimeState::load(&path) {
+            Ok(Some(saved)) => {
+                let restored = saved.apply(&mut self.timed_events, SystemTime::now());
+                self.restored_custom_state = saved.custom_state;
+                log::info!(
+                    "📥 Restored {} timed events{} from {}",
+                    restored,
+                    self.restored_custom_state
+                        .as_deref()
+                        .map(|state| format!(" and game state '{}'", state))
+                        .unwrap_or_default(),
+                    path.display()
+                );
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("⚠️ Runtime state not restored: {}", e),
+        }
+    }
+
+    pub(super) fn save_runtime_state(&mut self) {
+        self.last_state_save = Some(std::time::Instant::now());
+        let state = RuntimeState::capture(
+            &self.timed_events,
+            self.custom_state.peek().clone(),
+            SystemTime::now(),
+        );
+        match state.save(&self.state_path()) {
+            Ok(()) => debug_print!(self.debug_enabled, "💾 Runtime state saved"),
+            Err(e) => log::warn!("⚠️ Runtime state not saved: {}", e),
+        }
+    }
+
+    /// Save every `RUNTIME_STATE_SAVE_INTERVAL` while running, so a killed app
+    /// loses at most that much
+    pub(super) fn autosave_runtime_state(&mut self) {
+        if self.is_running
+            && self
+                .last_state_save
+                .is_none_or(|last| last.elapsed() >= RUNTIME_STATE_SAVE_INTERVAL)
+        {
+            self.save_runtime_state();
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use super::*;
+use crate::game_automation::config::{self, active_profile, save_active_config};
+use std::path::PathBuf;
+
+impl GameAutomation {
+    /// Write `config` to this automation's own profile, the `[devices]` one
+    /// when it has one, and return the file written
+    pub(super) fn save_config(&self) -> Result<PathBuf, String> {
+        match &self.profile {
+            Some(profile) => {
+                config::save_profile(profile, &self.config).map(|()| config::profile_path(profile))
+            }
+            None => save_active_config(self.config.clone()).map(|()| config::config_path()),
+        }
+    }
+
+    /// Show the settings panel the config this automation now runs
+    pub(super) fn publish_config(&self) {
+        *self.automation_config.write_unchecked() = self.config.clone();
+    }
+
+    /// Swap in another profile's config: stop, rebuild the timed events and
+    /// templates from it, then start again if automation was running
+    pub(super) async fn switch_profile(&mut self, name: &str) {
+        if name == active_profile() {
+            return;
+        }
+        let was_running = self.is_running;
+        if was_running {
+            Box::pin(self.process_command(AutomationCommand::Stop)).await;
+        } else {
+            self.save_runtime_state();
+        }
+
+        match config::switch_profile(name) {
+            Ok(profile_config) => {
+                let dirs_changed =
+                    profile_config.matching.template_dirs != self.config.matching.template_dirs;
+                self.config = profile_config;
+                // From now on this automation follows the active profile
+                self.profile = None;
+
+                // Scripts live outside the profiles, keep their events
+                let scripts: Vec<(String, TimedEvent)> = self
+                    .timed_events
+                    .drain()
+                    .filter(|(_, event)| matches!(event.event_type, TimedEventType::Script { .. }))
+                    .collect();
+                self.timed_events = build_timed_events(&self.config.timed_events);
+                self.timed_events.extend(scripts);
+                self.register_sequences();
+                self.restored_custom_state = None;
+                self.restore_runtime_state();
+                self.plan_schedules();
+
+                let mut detector = GameStateDetector::clone(&self.game_detector);
+                detector.update_config(self.config.matching.to_match_config(self.debug_enabled));
+                self.game_detector = Arc::new(detector);
+                if dirs_changed && let Err(e) = self.rescan_templates().await {
+                    log::warn!("⚠️ Template rescan after profile switch failed: {}", e);
+                }
+                self.apply_touch_timeout().await;
+                self.publish_config();
+                self.send_timed_events_list().await;
+                *self.screenshot_status.write_unchecked() = format!("👤 Profile '{}' active", name);
+            }
+            Err(e) => {
+                log::error!("❌ Profile '{}' not loaded: {}", name, e);
+                *self.screenshot_status.write_unchecked() =
+                    format!("❌ Profile '{}' not loaded: {}", name, e);
+            }
+        }
+
+        if was_running {
+            Box::pin(self.process_command(AutomationCommand::Start)).await;
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use super::*;
+
+impl GameAutomation {
+    /// Put running automation to sleep while `[quiet_hours]` are on, and wake
+    /// it once they end. Automation paused by hand or a guardrail is left alone.
+    pub(super) async fn apply_quiet_hours(&mut self) {
+        let until = self.config.quiet_hours.sleeping_until(self.device_now());
+        match (&self.state, until) {
+            (GameState::Running, Some(until)) => {
+                let until = format!("{:02}:{:02}", until.hour(), until.minute());
+                log::info!("😴 Quiet hours: automation sleeping until {}", until);
+                self.change_state(GameState::Sleeping).await;
+                self.sleeping_until.set(Some(until));
+            }
+            (GameState::Sleeping, None) => {
+                log::info!("⏰ Quiet hours over, automation resumed");
+                self.change_state(GameState::Running).await;
+                self.send_timed_events_list().await;
+                self.send_timed_tap_countdowns().await;
+            }
+            _ => {}
+        }
+    }
+}
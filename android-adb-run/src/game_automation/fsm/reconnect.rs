@@ -1,112 +1,166 @@
 use super::*;
+use crate::adb::hotplug;
+use tokio::sync::broadcast::error::TryRecvError;
+
+/// Wait before the first retry, doubled after every failed attempt
+pub const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+/// Longest wait between reconnection attempts
+pub const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Wait after `failed_attempts` unsuccessful reconnections: 1s, 2s, 4s ... 60s
+pub fn reconnect_delay(failed_attempts: u32) -> Duration {
+    RECONNECT_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(failed_attempts.saturating_sub(1)))
+        .min(RECONNECT_MAX_DELAY)
+}
 
 impl GameAutomation {
-    pub(super) async fn check_reconnection(&mut self) {
-        let backoff_secs = match self.last_reconnect_attempt {
-            None => 0,
-            Some(last_attempt) => {
-                let elapsed = std::time::Instant::now()
-                    .duration_since(last_attempt)
-                    .as_secs();
-                let attempt_count = (elapsed / 10).min(4);
-                let backoff = 2u64.pow(attempt_count as u32);
-                backoff.min(30)
-            }
+    /// Act on USB hot-plug events: an unplugged device is disconnected right
+    /// away, and plugging it back in skips the remaining reconnect backoff
+    pub(super) async fn poll_hotplug(&mut self) {
+        let Some(device_key) = self.device_key.clone() else {
+            return;
         };
+        if self.hotplug_events.is_none() {
+            self.hotplug_events = hotplug::subscribe();
+        }
+        let Some(events) = self.hotplug_events.as_mut() else {
+            return;
+        };
+        let mut detached = false;
+        loop {
+            match events.try_recv() {
+                Ok(HotplugEvent::DeviceDetached(key)) if key == device_key => detached = true,
+                Ok(HotplugEvent::DeviceAttached(key)) if key == device_key => {
+                    self.device_attached = true;
+                }
+                Ok(_) | Err(TryRecvError::Lagged(_)) => {}
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
 
-        let now = std::time::Instant::now();
-        let should_attempt = match self.last_reconnect_attempt {
-            None => true,
-            Some(last_attempt) => {
-                let elapsed = now.duration_since(last_attempt).as_secs();
-                elapsed >= backoff_secs
+        if detached && !self.device_disconnected {
+            log::info!("🔌 {} unplugged", device_key);
+            if self.is_running {
+                self.change_state(GameState::Paused).await;
             }
+            self.device_disconnected = true;
+            self.last_reconnect_attempt = None;
+            *self.device_info.write_unchecked() = None;
+            *self.screenshot_data.write_unchecked() = None;
+            *self.screenshot_bytes.write_unchecked() = None;
+            *self.screenshot_status.write_unchecked() =
+                "🔌 USB DISCONNECTED: device unplugged - Please reconnect".to_string();
+            self.status
+                .write_unchecked()
+                .apply(ConnectionEvent::Lost(DisconnectReason::AutomationPaused));
+        }
+    }
+
+    /// Called while the device is disconnected: retry the connection with
+    /// exponential backoff and show the countdown
+    pub(super) async fn check_reconnection(&mut self) {
+        let now = std::time::Instant::now();
+        let Some(last_attempt) = self.last_reconnect_attempt else {
+            // Just disconnected: notify and try straight away
+            let device = self
+                .device_key
+                .clone()
+                .unwrap_or_else(|| "device".to_string());
+            self.notifier
+                .notify(&self.config.notifications, Notice::Disconnected { device });
+            self.reconnect_attempts = 0;
+            self.try_reconnect(now).await;
+            return;
         };
 
-        if should_attempt {
-            println!(
-                "🔄 Attempting device reconnection (elapsed: {:?})...",
-                self.last_reconnect_attempt
-                    .map(|t| now.duration_since(t))
-                    .unwrap_or_default()
-            );
-            self.last_reconnect_attempt = Some(now);
-
-            if let Ok(()) = self.attempt_reconnection().await {
-                return;
-            }
+        let delay = reconnect_delay(self.reconnect_attempts);
+        let elapsed = now.duration_since(last_attempt);
+        if elapsed >= delay || std::mem::take(&mut self.device_attached) {
+            self.try_reconnect(now).await;
+            return;
         }
 
-        if let Some(last_attempt) = self.last_reconnect_attempt {
-            let elapsed = now.duration_since(last_attempt).as_secs();
-            let backoff_secs = match self.last_reconnect_attempt {
-                None => 0,
-                Some(last_attempt) => {
-                    let elapsed = std::time::Instant::now()
-                        .duration_since(last_attempt)
-                        .as_secs();
-                    let attempt_count = (elapsed / 10).min(4);
-                    let backoff = 2u64.pow(attempt_count as u32);
-                    backoff.min(30)
-                }
-            };
-            let remaining = backoff_secs.saturating_sub(elapsed);
+        let retry_in = (delay - elapsed).as_secs().max(1) as u32;
+        *self.screenshot_status.write_unchecked() = format!(
+            "🔌 Device disconnected - Next retry in {}s (attempt {})...",
+            retry_in,
+            self.reconnect_attempts + 1
+        );
+        self.status
+            .write_unchecked()
+            .apply(ConnectionEvent::Reconnecting {
+                attempt: self.reconnect_attempts + 1,
+                retry_in,
+            });
+    }
 
-            if remaining > 0 {
-                *self.screenshot_status.write_unchecked() =
-                    format!("🔌 Device disconnected - Next retry in {}s...", remaining);
-            } else {
-                *self.screenshot_status.write_unchecked() =
-                    "🔌 Device disconnected - Attempting reconnection...".to_string();
-            }
+    async fn try_reconnect(&mut self, now: std::time::Instant) {
+        self.reconnect_attempts += 1;
+        log::info!(
+            "🔄 Reconnection attempt {} (next in {:?} if it fails)",
+            self.reconnect_attempts,
+            reconnect_delay(self.reconnect_attempts)
+        );
+        *self.screenshot_status.write_unchecked() =
+            "🔌 Device disconnected - Attempting reconnection...".to_string();
+        self.last_reconnect_attempt = Some(now);
+        if self.attempt_reconnection().await.is_ok() {
+            self.reconnect_attempts = 0;
         }
     }
 
     pub(super) async fn attempt_reconnection(&mut self) -> Result<(), String> {
-        println!("🔄 Attempting to reconnect to device...");
+        log::info!("🔄 Attempting to reconnect to device...");
 
         if let Some(old_client_arc) = self.adb_client.take() {
-            println!("🔧 Shutting down old USB connection...");
+            log::info!("🔧 Shutting down old USB connection...");
             match Arc::try_unwrap(old_client_arc) {
                 Ok(mutex) => {
                     let mut old_client = mutex.into_inner();
                     match old_client.shutdown().await {
                         Ok(_) => {
-                            println!("✅ Old connection shut down cleanly");
+                            log::info!("✅ Old connection shut down cleanly");
                         }
                         Err(e) => {
-                            println!("⚠️ Old connection shutdown warning: {}", e);
+                            log::warn!("⚠️ Old connection shutdown warning: {}", e);
                         }
                     }
                 }
                 Err(arc) => {
-                    println!("⚠️ Old connection has other references, forcing drop...");
+                    log::warn!("⚠️ Old connection has other references, forcing drop...");
                     drop(arc);
                 }
             }
             tokio::time::sleep(Duration::from_millis(200)).await;
         }
 
-        match AdbBackend::connect_first().await {
+        // By serial, so a second phone of the same model is never picked up instead
+        match AdbBackend::connect(self.device_key.as_deref()).await {
             Ok(client) => {
                 let (screen_width, screen_height) = client.screen_dimensions();
-                println!(
+                log::info!(
                     "✅ Device reconnected! ({}x{})",
-                    screen_width, screen_height
+                    screen_width,
+                    screen_height
                 );
 
-                let mut config = create_default_config();
-                config.debug_enabled = self.debug_enabled;
-                self.game_detector = GameStateDetector::new(screen_width, screen_height, config);
+                self.load_detector(screen_width, screen_height).await;
 
                 self.adb_client = Some(Arc::new(Mutex::new(client)));
+                self.sync_device_clock().await;
 
                 if let Some(client_arc) = &self.adb_client {
                     let client_guard = client_arc.lock().await;
+                    client_guard
+                        .set_touch_timeout(Duration::from_secs(
+                            self.config.touch.pause_timeout_seconds,
+                        ))
+                        .await;
                     if let Err(e) = client_guard.start_touch_monitoring().await {
-                        println!("⚠️ Failed to start touch monitoring after reconnect: {}", e);
+                        log::warn!("⚠️ Failed to start touch monitoring after reconnect: {}", e);
                     } else {
-                        println!("👆 Touch monitoring restarted");
+                        log::info!("👆 Touch monitoring restarted");
                     }
                 }
 
@@ -115,8 +169,10 @@ impl GameAutomation {
 
                 if self.is_running && self.state == GameState::Paused {
                     self.change_state(GameState::Running).await;
-                    println!("▶️ Auto-resuming automation after reconnection");
+                    log::info!("▶️ Auto-resuming automation after reconnection");
                 }
+                // Timed events kept their timers while disconnected; republish them
+                self.send_timed_events_list().await;
 
                 if let Some(client_arc) = &self.adb_client {
                     let client_guard = client_arc.lock().await;
@@ -130,16 +186,33 @@ impl GameAutomation {
                 }
                 *self.screenshot_status.write_unchecked() =
                     "✅ Reconnected! Automation ready.".to_string();
-                *self.status.write_unchecked() = "✅ Device Reconnected - Resuming".to_string();
+                self.status
+                    .write_unchecked()
+                    .apply(ConnectionEvent::Reconnected);
 
-                println!("✅ Device reconnected successfully - automation auto-resumed");
+                log::info!(
+                    "✅ Device reconnected after {} attempt(s)",
+                    self.reconnect_attempts
+                );
 
                 Ok(())
             }
             Err(e) => {
-                println!("❌ Reconnection failed: {}", e);
+                log::error!("❌ Reconnection failed: {}", e);
                 Err(e.to_string())
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay_doubles_up_to_max() {
+        let delays: Vec<u64> = (0..9).map(|n| reconnect_delay(n).as_secs()).collect();
+        assert_eq!(delays, [1, 1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(reconnect_delay(u32::MAX), RECONNECT_MAX_DELAY);
+    }
+}
@@ -0,0 +1,116 @@
+use super::*;
+use crate::game_automation::types::RecoveryAttempt;
+use dioxus::prelude::ReadableExt;
+use std::time::Instant;
+
+impl GameAutomation {
+    /// Check that the `[app]` game is still in the foreground, and relaunch it
+    /// with growing delays while it is not
+    pub(super) async fn check_app_foreground(&mut self) {
+        let Some(package) = self.config.app.package.clone() else {
+            return;
+        };
+        if !matches!(self.state, GameState::Running | GameState::Recovering)
+            || self.next_app_check.is_some_and(|at| Instant::now() < at)
+        {
+            return;
+        }
+        let Some(client) = self.adb_client.clone() else {
+            return;
+        };
+
+        let foreground = client.lock().await.is_app_foreground(&package).await;
+        let foreground = match foreground {
+            Ok(foreground) => foreground,
+            Err(e) => {
+                debug_print!(self.debug_enabled, "⚠️ Foreground app check failed: {}", e);
+                self.schedule_app_check();
+                return;
+            }
+        };
+
+        match (self.state.clone(), foreground) {
+            (GameState::Running, true) => self.schedule_app_check(),
+            (GameState::Running, false) => {
+                log::warn!("💥 {} is no longer in the foreground", package);
+                self.recovery_attempts = 0;
+                self.change_state(GameState::Recovering).await;
+                self.recovery_step(&client, &package).await;
+            }
+            (GameState::Recovering, true) => {
+                log::info!(
+                    "✅ {} is back after {} relaunch(es)",
+                    package,
+                    self.recovery_attempts
+                );
+                *self.screenshot_status.write_unchecked() = format!("✅ {} recovered", package);
+                self.clear_recovery();
+                self.change_state(GameState::Running).await;
+                self.schedule_app_check();
+                self.send_timed_events_list().await;
+                self.send_timed_tap_countdowns().await;
+            }
+            _ => self.recovery_step(&client, &package).await,
+        }
+    }
+
+    /// Relaunch the game, or pause once the attempts are used up
+    async fn recovery_step(&mut self, client: &Arc<Mutex<AdbBackend>>, package: &str) {
+        let max_attempts = self.config.app.max_restart_attempts;
+        if self.recovery_attempts >= max_attempts {
+            log::error!(
+                "❌ {} did not come back after {} relaunches, pausing automation",
+                package,
+                self.recovery_attempts
+            );
+            *self.screenshot_status.write_unchecked() = format!(
+                "❌ {} did not restart after {} attempts - automation paused",
+                package, self.recovery_attempts
+            );
+            self.clear_recovery();
+            self.change_state(GameState::Paused).await;
+            return;
+        }
+
+        self.recovery_attempts += 1;
+        let attempt = RecoveryAttempt {
+            package: package.to_string(),
+            attempt: self.recovery_attempts,
+            max_attempts,
+        };
+        log::info!(
+            "🔄 Relaunching {} (attempt {}/{})",
+            package,
+            attempt.attempt,
+            max_attempts
+        );
+        *self.screenshot_status.write_unchecked() = format!(
+            "🔄 Relaunching {} (attempt {}/{})",
+            package, attempt.attempt, max_attempts
+        );
+        self.recovery.set(Some(attempt));
+
+        let component = self
+            .config
+            .app
+            .launch_component()
+            .unwrap_or_else(|| package.to_string());
+        if let Err(e) = client.lock().await.start_activity(&component).await {
+            log::warn!("⚠️ Launching {} failed: {}", component, e);
+        }
+        self.next_app_check =
+            Some(Instant::now() + self.config.app.restart_delay(self.recovery_attempts));
+    }
+
+    fn schedule_app_check(&mut self) {
+        let interval = Duration::from_secs(self.config.app.check_interval_seconds.max(1));
+        self.next_app_check = Some(Instant::now() + interval);
+    }
+
+    pub(super) fn clear_recovery(&mut self) {
+        self.recovery_attempts = 0;
+        if self.recovery.peek().is_some() {
+            self.recovery.set(None);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use super::*;
+use crate::adb::{RgbFrame, UiNode};
+use crate::game_automation::rules::{Rule, RuleAction};
+use std::time::Instant;
+
+impl GameAutomation {
+    /// Act on the first `[[rules]]` entry whose conditions all hold on `detection`
+    /// and `frame`, dumping the UI once if a rule needs it. `before_matching` picks the
+    /// rules checked before template matching, or all the others. True when a rule acted.
+    pub(super) async fn apply_rules(
+        &mut self,
+        detection: &DetectionResult,
+        frame: &RgbFrame,
+        before_matching: bool,
+    ) -> Result<bool, String> {
+        let now = Instant::now();
+        let candidates: Vec<Rule> = self
+            .config
+            .rules
+            .iter()
+            .filter(|rule| {
+                rule.enabled
+                    && rule.before_matching() == before_matching
+                    && self.rule_cooldowns.ready(rule, now)
+                    && rule.screen_holds(detection, frame)
+            })
+            .cloned()
+            .collect();
+
+        let mut ui: Option<Option<Vec<UiNode>>> = None;
+        for rule in candidates {
+            let nodes = if rule.needs_ui() {
+                if ui.is_none() {
+                    ui = Some(self.dump_ui_for_rules().await);
+                }
+                match ui.as_ref().and_then(Option::as_deref) {
+                    Some(nodes) => nodes,
+                    // No UI dump: rules with element conditions cannot be decided
+                    None => continue,
+                }
+            } else {
+                &[]
+            };
+            if !rule.holds(detection, frame, nodes) {
+                continue;
+            }
+            let target = rule.target(detection, nodes);
+            self.rule_cooldowns.record(&rule, now);
+            self.run_rule(&rule, target).await?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    async fn dump_ui_for_rules(&self) -> Option<Vec<UiNode>> {
+        let client = self.adb_client.clone()?;
+        let result = client.lock().await.dump_ui_hierarchy().await;
+        result
+            .inspect_err(|e| log::warn!("⚠️ UI dump for rules failed: {}", e))
+            .ok()
+    }
+
+    async fn run_rule(&mut self, rule: &Rule, target: Option<(u32, u32)>) -> Result<(), String> {
+        log::info!(
+            "📐 Rule '{}' ({}) → {:?}",
+            rule.name,
+            rule.when
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            rule.action
+        );
+        match rule.action {
+            RuleAction::Sequence => {
+                let name = rule.sequence.clone().ok_or_else(|| {
+                    format!(
+                        "Rule '{}' has action = \"sequence\" but no sequence name",
+                        rule.name
+                    )
+                })?;
+                self.run_sequence(&name).await
+            }
+            RuleAction::Tap => {
+                let (x, y) = target.ok_or_else(|| {
+                    format!(
+                        "Rule '{}' has nothing to tap: no 'seen' or 'shown' condition",
+                        rule.name
+                    )
+                })?;
+                let client = self
+                    .adb_client
+                    .clone()
+                    .ok_or_else(|| "ADB client not available for tap action".to_string())?;
+                let entry =
+                    self.history_entry(HistoryAction::Tap, format!("rule:{}", rule.name), (x, y));
+                if let Err(blocked) = self.allow_tap(x, y) {
+                    self.report_blocked_tap(entry, &blocked);
+                    return Ok(());
+                }
+                let result = client.lock().await.tap(x, y).await;
+                history::record(entry.with_result(&result));
+                result.map_err(|e| {
+                    format!("Rule '{}' tap at ({}, {}) failed: {}", rule.name, x, y, e)
+                })
+            }
+        }
+    }
+}
@@ -3,7 +3,7 @@ use super::*;
 impl GameAutomation {
     pub async fn run(&mut self) {
         debug_print!(self.debug_enabled, "🎮 Event-driven automation FSM started");
-        println!("🎮 Automation run() loop starting");
+        log::info!("🎮 Automation run() loop starting");
 
         let mut loop_count = 0u32;
         loop {
@@ -28,27 +28,58 @@ impl GameAutomation {
                 Err(_) => {}
             }
 
+            if self.sync_scripts(false) {
+                self.send_timed_events_list().await;
+            }
+
+            self.poll_hotplug().await;
             if self.device_disconnected {
                 self.check_reconnection().await;
+            } else {
+                self.poll_telemetry().await;
+                self.poll_orientation().await;
+                self.poll_device_notifications().await;
+            }
+
+            if self.is_running && !self.device_disconnected {
+                self.check_app_foreground().await;
             }
 
-            if self.is_running && self.state != GameState::Paused {
+            if self.is_running {
+                self.apply_quiet_hours().await;
+            }
+
+            if self.is_running
+                && !matches!(
+                    self.state,
+                    GameState::Paused | GameState::Recovering | GameState::Sleeping
+                )
+            {
                 self.process_timed_events().await;
+                self.check_stuck().await;
             } else {
                 static ONCE: std::sync::Once = std::sync::Once::new();
                 ONCE.call_once(|| {
-                    println!(
+                    log::warn!(
                         "⚠️ NOT processing events: is_running={}, state={:?}",
-                        self.is_running, self.state
+                        self.is_running,
+                        self.state
                     );
                 });
             }
 
+            self.poll_ui_events().await;
+            self.autosave_runtime_state();
+
             if self.should_exit {
                 break;
             }
         }
 
+        self.stop_ui_listener();
+        self.save_runtime_state();
+        self.restore_device_sound().await;
+
         debug_print!(self.debug_enabled, "🎮 Event-driven automation FSM ended");
     }
 }
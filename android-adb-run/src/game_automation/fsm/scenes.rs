@@ -0,0 +1,47 @@
+use super::*;
+use crate::game_automation::scenes::SceneClassifier;
+use std::path::PathBuf;
+
+impl GameAutomation {
+    /// `[scenes]` classification of `frame`, loading the reference screenshots on
+    /// first use and again when `dir` changes
+    pub(super) async fn classify_scene(&mut self, frame: &Arc<RgbFrame>) -> Option<String> {
+        let scenes = self.config.scenes.clone();
+        if !scenes.enabled {
+            self.current_scene = None;
+            return None;
+        }
+        let classifier = match &self.scene_classifier {
+            Some((dir, classifier)) if *dir == scenes.dir => classifier.clone(),
+            _ => {
+                let dir = PathBuf::from(&scenes.dir);
+                let loaded = tokio::task::spawn_blocking(move || SceneClassifier::load(&dir))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|loaded| loaded);
+                let classifier = Arc::new(loaded.unwrap_or_else(|e| {
+                    log::warn!("⚠️ Scene references not loaded: {}", e);
+                    SceneClassifier::default()
+                }));
+                log::info!(
+                    "🎬 {} scene reference(s) loaded from {}",
+                    classifier.len(),
+                    scenes.dir
+                );
+                self.scene_classifier = Some((scenes.dir.clone(), classifier.clone()));
+                classifier
+            }
+        };
+
+        let found = classifier.classify(frame, scenes.min_score);
+        let scene = found.as_ref().map(|found| found.scene.clone());
+        if scene != self.current_scene {
+            match &found {
+                Some(found) => log::info!("🎬 Scene: {} ({:.2})", found.scene, found.score),
+                None => debug_print!(self.debug_enabled, "🎬 Scene: unknown"),
+            }
+            self.current_scene = scene.clone();
+        }
+        scene
+    }
+}
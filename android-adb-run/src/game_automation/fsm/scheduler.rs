@@ -51,6 +51,7 @@ impl GameAutomation {
             }
         }
 
+        self.plan_schedules();
         let mut events_to_execute = Vec::new();
         for (id, event) in &self.timed_events {
             if event.is_ready(self.debug_enabled) {
@@ -63,12 +64,18 @@ impl GameAutomation {
             let order_a = match a.1 {
                 TimedEventType::Screenshot => 0,
                 TimedEventType::CountdownUpdate => 1,
-                TimedEventType::Tap { .. } => 2,
+                TimedEventType::Tap { .. } | TimedEventType::ConditionalTap { .. } => 2,
+                TimedEventType::Script { .. }
+                | TimedEventType::Sequence { .. }
+                | TimedEventType::BackupFiles { .. } => 3,
             };
             let order_b = match b.1 {
                 TimedEventType::Screenshot => 0,
                 TimedEventType::CountdownUpdate => 1,
-                TimedEventType::Tap { .. } => 2,
+                TimedEventType::Tap { .. } | TimedEventType::ConditionalTap { .. } => 2,
+                TimedEventType::Script { .. }
+                | TimedEventType::Sequence { .. }
+                | TimedEventType::BackupFiles { .. } => 3,
             };
             order_a.cmp(&order_b)
         });
@@ -77,16 +84,18 @@ impl GameAutomation {
             static EMPTY_COUNT: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
             let count = EMPTY_COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
             if count.is_multiple_of(30) && count > 0 {
-                println!("📭 No events ready (checked {} times)", count);
+                log::info!("📭 No events ready (checked {} times)", count);
                 for (id, event) in &self.timed_events {
                     if let Some(last) = event.last_executed {
                         let elapsed = last.elapsed();
-                        println!(
+                        log::info!(
                             "  - {}: elapsed={:?} vs interval={:?}",
-                            id, elapsed, event.interval
+                            id,
+                            elapsed,
+                            event.interval
                         );
                     } else {
-                        println!("  - {}: never executed", id);
+                        log::info!("  - {}: never executed", id);
                     }
                 }
             }
@@ -101,7 +110,8 @@ impl GameAutomation {
                     e
                 );
 
-                if is_disconnect_error(&e.to_string()) {
+                // Set by the screenshot, tap and sequence error handling
+                if self.device_disconnected {
                     debug_print!(
                         self.debug_enabled,
                         "🔌 Device disconnect detected during timed event: {}",
@@ -109,7 +119,6 @@ impl GameAutomation {
                     );
 
                     self.change_state(GameState::Paused).await;
-                    self.device_disconnected = true;
                     self.last_reconnect_attempt = None;
 
                     *self.device_info.write_unchecked() = None;
@@ -117,11 +126,17 @@ impl GameAutomation {
                     *self.screenshot_bytes.write_unchecked() = None;
                     *self.screenshot_status.write_unchecked() =
                         format!("🔌 USB DISCONNECTED: {} - Please reconnect", e);
-                    *self.status.write_unchecked() = "🔌 Device Disconnected - Paused".to_string();
+                    self.status
+                        .write_unchecked()
+                        .apply(ConnectionEvent::Lost(DisconnectReason::AutomationPaused));
                     return;
                 } else {
-                    *self.screenshot_status.write_unchecked() =
-                        format!("❌ Timed event '{}' failed: {}", event_id, e);
+                    let message = format!("❌ Timed event '{}' failed: {}", event_id, e);
+                    self.notifier.notify(
+                        &self.config.notifications,
+                        Notice::AutomationError(message.clone()),
+                    );
+                    *self.screenshot_status.write_unchecked() = message;
                 }
             }
         }
@@ -141,6 +156,7 @@ impl GameAutomation {
 
         match event_type {
             TimedEventType::Screenshot => {
+                self.adapt_screenshot_interval();
                 if let Some(client) = &self.adb_client {
                     let client_clone = client.clone();
                     let screenshot_data = self.screenshot_data;
@@ -165,13 +181,13 @@ impl GameAutomation {
 
                                 let bytes_clone = bytes.clone();
                                 dioxus::prelude::spawn(async move {
-                                    use crate::gui::util::base64_encode;
-                                    let base64_string = tokio::task::spawn_blocking(move || {
-                                        base64_encode(&bytes_clone)
+                                    use crate::gui::util::preview_data_url_from_bytes;
+                                    let preview = tokio::task::spawn_blocking(move || {
+                                        preview_data_url_from_bytes(&bytes_clone)
                                     })
                                     .await
                                     .unwrap_or_default();
-                                    *screenshot_data.write_unchecked() = Some(base64_string);
+                                    *screenshot_data.write_unchecked() = Some(preview);
                                     *screenshot_bytes_sig.write_unchecked() = Some(bytes);
                                     *screenshot_status.write_unchecked() = format!(
                                         "🤖 Automation screenshot #{} ({}ms)",
@@ -192,50 +208,58 @@ impl GameAutomation {
                 }
             }
             TimedEventType::Tap { x, y } => {
-                if let Some(client) = &self.adb_client {
+                if !self.queue_tap(event_id, *x, *y).await? {
+                    return Ok(());
+                }
+            }
+            TimedEventType::ConditionalTap { template, x, y } => {
+                let visible = match self.template_visible(template).await {
+                    Ok(visible) => visible,
+                    Err(e) => {
+                        // Wait a full interval instead of re-capturing every tick
+                        if let Some(event) = self.timed_events.get_mut(event_id) {
+                            event.mark_skipped();
+                        }
+                        return Err(e);
+                    }
+                };
+                if !visible {
                     debug_print!(
                         self.debug_enabled,
-                        "🎯 Queuing tap: {} at ({},{})",
+                        "⏭️ {} skipped: '{}' not on screen",
                         event_id,
-                        x,
-                        y
+                        template
                     );
-                    let result = {
-                        let client_guard = client.lock().await;
-                        client_guard.tap(*x, *y).await
-                    };
-
-                    match result {
-                        Ok(()) => {
-                            debug_print!(self.debug_enabled, "✅ {} queued", event_id);
-                        }
-                        Err(e) => {
-                            let error_str = e.to_string();
-                            println!("❌ {} queue failed: {}", event_id, error_str);
-
-                            if is_disconnect_error(&error_str) {
-                                debug_print!(
-                                    self.debug_enabled,
-                                    "🔌 Device disconnect detected during tap '{}': {}",
-                                    event_id,
-                                    error_str
-                                );
-                                self.device_disconnected = true;
-                                self.last_reconnect_attempt = None;
-                                *self.device_info.write_unchecked() = None;
-                                *self.screenshot_data.write_unchecked() = None;
-                                *self.screenshot_bytes.write_unchecked() = None;
-                                *self.screenshot_status.write_unchecked() = format!(
-                                    "🔌 USB DISCONNECTED: {} (during tap) - Please reconnect",
-                                    error_str
-                                );
-                                *self.status.write_unchecked() =
-                                    "🔌 Device Disconnected - Paused".to_string();
-                            }
-                        }
+                    if let Some(event) = self.timed_events.get_mut(event_id) {
+                        event.mark_skipped();
                     }
-                } else {
-                    return Err("ADB client not available".to_string());
+                    self.send_timed_events_list().await;
+                    return Ok(());
+                }
+                if !self.queue_tap(event_id, *x, *y).await? {
+                    return Ok(());
+                }
+            }
+            TimedEventType::Script { name } => {
+                if let Err(e) = self.run_script(name).await {
+                    return self.fail_and_reschedule(event_id, e);
+                }
+            }
+            TimedEventType::Sequence { name } => {
+                if let Err(e) = self.run_sequence(name).await {
+                    return self.fail_and_reschedule(event_id, e);
+                }
+            }
+            TimedEventType::BackupFiles {
+                remote_path,
+                local_dir,
+                keep,
+            } => {
+                if let Err(e) = self
+                    .run_backup(event_id, remote_path, local_dir, *keep)
+                    .await
+                {
+                    return self.fail_and_reschedule(event_id, e);
                 }
             }
             TimedEventType::CountdownUpdate => {
@@ -247,8 +271,9 @@ impl GameAutomation {
                         && let Some(last) = event.last_executed
                     {
                         let elapsed = last.elapsed();
-                        let remaining = if elapsed < event.interval {
-                            event.interval - elapsed
+                        let interval = event.current_interval();
+                        let remaining = if elapsed < interval {
+                            interval - elapsed
                         } else {
                             Duration::from_secs(0)
                         };
@@ -266,10 +291,205 @@ impl GameAutomation {
         if let Some(event) = self.timed_events.get_mut(event_id) {
             event.mark_executed();
         }
+        // Publish the new execution count to the GUI list and the control API
+        self.send_timed_events_list().await;
 
         Ok(())
     }
 
+    /// Fail a run with `err`; failed runs still wait a full interval
+    /// instead of retrying every tick
+    fn fail_and_reschedule(&mut self, event_id: &str, err: String) -> Result<(), String> {
+        if let Some(event) = self.timed_events.get_mut(event_id) {
+            event.mark_executed();
+        }
+        Err(err)
+    }
+
+    /// A match or tap: adaptive screenshots come every `min_seconds` again,
+    /// starting with the current wait
+    pub(super) fn note_activity(&mut self) {
+        let adaptive = &self.config.timed_events.adaptive_screenshots;
+        if !adaptive.enabled {
+            return;
+        }
+        self.screenshot_activity = true;
+        let (min, _) = adaptive.bounds();
+        if let Some(event) = self.timed_events.get_mut("screenshot")
+            && event.interval > min
+        {
+            event.interval = min;
+        }
+    }
+
+    /// Wait before the next adaptive screenshot: short after activity, longer while idle
+    fn adapt_screenshot_interval(&mut self) {
+        let adaptive = &self.config.timed_events.adaptive_screenshots;
+        if !adaptive.enabled {
+            return;
+        }
+        let active = std::mem::take(&mut self.screenshot_activity);
+        if let Some(event) = self.timed_events.get_mut("screenshot") {
+            let next = adaptive.next_interval(event.interval, active);
+            if next != event.interval {
+                debug_print!(
+                    self.debug_enabled,
+                    "📸 Screenshot interval {:?} -> {:?} ({})",
+                    event.interval,
+                    next,
+                    if active { "activity" } else { "idle" }
+                );
+                event.interval = next;
+            }
+        }
+    }
+
+    /// Read the device UTC offset so schedules fire at device local time
+    pub(super) async fn sync_device_clock(&mut self) {
+        let Some(client) = &self.adb_client else {
+            return;
+        };
+        let result = client.lock().await.utc_offset().await;
+        match result {
+            Ok(Some(offset)) => {
+                if self.device_utc_offset != Some(offset) {
+                    debug_print!(self.debug_enabled, "🕰️ Device UTC offset: {}", offset);
+                    // Plans made against another offset would fire at the wrong time
+                    for event in self.timed_events.values_mut() {
+                        event.next_fire = None;
+                    }
+                }
+                self.device_utc_offset = Some(offset);
+            }
+            Ok(None) => log::warn!("⚠️ Unrecognised device UTC offset, using host time"),
+            Err(e) => log::warn!("⚠️ Failed to read device UTC offset: {}", e),
+        }
+        self.plan_schedules();
+    }
+
+    /// Current wall-clock time on the device (host local time until the offset is known)
+    pub(super) fn device_now(&self) -> time::OffsetDateTime {
+        match self.device_utc_offset {
+            Some(offset) => time::OffsetDateTime::now_utc().to_offset(offset),
+            None => time::OffsetDateTime::now_local()
+                .unwrap_or_else(|_| time::OffsetDateTime::now_utc()),
+        }
+    }
+
+    /// Plan the next fire of scheduled events that have none yet
+    pub(super) fn plan_schedules(&mut self) {
+        let now = self.device_now();
+        for event in self.timed_events.values_mut() {
+            event.plan_schedule(now);
+        }
+    }
+
+    /// Queue a tap for a timed event, flagging a disconnect if the device is gone.
+    /// `false` when `[safety]` held the tap back: rate limited taps retry on the
+    /// next tick, taps into a no-tap zone wait a full interval. Taps follow a
+    /// turned device (`[orientation]`), or wait for it to turn back.
+    async fn queue_tap(&mut self, event_id: &str, x: Coord, y: Coord) -> Result<bool, String> {
+        let screen = self.tap_screen();
+        let (x, y) = resolve_point((x, y), screen);
+        let (x, y) = match self.timed_events.get(event_id) {
+            Some(event) => event.jitter.tap_point((x, y), screen),
+            None => (x, y),
+        };
+        let Some(device_point) = self.oriented_tap(x, y) else {
+            debug_print!(
+                self.debug_enabled,
+                "🔄 {} held: device turned away from the expected orientation",
+                event_id
+            );
+            return Ok(false);
+        };
+        let entry = self.history_entry(HistoryAction::Tap, event_id, device_point);
+        // No-tap zones are drawn in the same orientation as the taps
+        if let Err(blocked) = self.allow_tap(x, y) {
+            self.report_blocked_tap(entry, &blocked);
+            if !blocked.is_transient()
+                && let Some(event) = self.timed_events.get_mut(event_id)
+            {
+                event.mark_skipped();
+            }
+            return Ok(false);
+        }
+        let (x, y) = device_point;
+        if let Some(client) = &self.adb_client {
+            debug_print!(
+                self.debug_enabled,
+                "🎯 Queuing tap: {} at ({},{})",
+                event_id,
+                x,
+                y
+            );
+            let result = {
+                let client_guard = client.lock().await;
+                client_guard.tap(x, y).await
+            };
+
+            match result {
+                Ok(()) => {
+                    history::record(entry);
+                    self.stats.record_tap(event_id);
+                    self.note_activity();
+                    debug_print!(self.debug_enabled, "✅ {} queued", event_id);
+                }
+                Err(e) => {
+                    let error_str = e.to_string();
+                    log::error!("❌ {} queue failed: {}", event_id, error_str);
+                    history::record(entry.failed(&error_str));
+
+                    if e.needs_reconnect() {
+                        debug_print!(
+                            self.debug_enabled,
+                            "🔌 Device disconnect detected during tap '{}': {}",
+                            event_id,
+                            error_str
+                        );
+                        self.device_disconnected = true;
+                        self.last_reconnect_attempt = None;
+                        *self.device_info.write_unchecked() = None;
+                        *self.screenshot_data.write_unchecked() = None;
+                        *self.screenshot_bytes.write_unchecked() = None;
+                        *self.screenshot_status.write_unchecked() = format!(
+                            "🔌 USB DISCONNECTED: {} (during tap) - Please reconnect",
+                            error_str
+                        );
+                        self.status
+                            .write_unchecked()
+                            .apply(ConnectionEvent::Lost(DisconnectReason::AutomationPaused));
+                    }
+                }
+            }
+        } else {
+            return Err("ADB client not available".to_string());
+        }
+        Ok(true)
+    }
+
+    /// Capture a fresh screenshot and check whether `template` is on it
+    async fn template_visible(&mut self, template: &str) -> Result<bool, String> {
+        if let Some(reason) = self.rotation_hold() {
+            debug_print!(
+                self.debug_enabled,
+                "🔄 '{}' not checked: {}",
+                template,
+                reason
+            );
+            return Ok(false);
+        }
+        let frame = self.take_screenshot().await?;
+        let detector = self.game_detector.clone();
+        let detection =
+            tokio::task::spawn_blocking(move || detector.analyze_gray(&frame.to_luma()))
+                .await
+                .map_err(|e| format!("Background analysis task failed: {}", e))?;
+        self.record_detection(&detection);
+        self.show_matches(&detection, None);
+        Ok(detection.best_match_for(template).is_some())
+    }
+
     pub(super) async fn send_timed_tap_countdowns(&self) {
         if let Some((next_tap_id, seconds_remaining)) = self.get_next_tap_info() {
             *self.timed_tap_countdown.write_unchecked() = Some((next_tap_id, seconds_remaining));
@@ -280,13 +500,15 @@ impl GameAutomation {
         let events: Vec<crate::game_automation::types::TimedEvent> =
             self.timed_events.values().cloned().collect();
         *self.timed_events_list.write_unchecked() = events;
+        // Rides on the countdown tick, so the run time stays current too
+        *self.stats_summary.write_unchecked() = self.stats.summary();
     }
 
     fn get_next_tap_info(&self) -> Option<(String, u64)> {
         let mut next_tap: Option<(String, u64)> = None;
 
         for (id, event) in &self.timed_events {
-            if !matches!(event.event_type, TimedEventType::Tap { .. }) {
+            if event.tap_point().is_none() {
                 continue;
             }
 
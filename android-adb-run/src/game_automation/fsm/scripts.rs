@@ -0,0 +1,78 @@
+use super::*;
+use crate::game_automation::counters::latest_values;
+use crate::game_automation::script::{ScriptChange, ScriptContext, script_event_id};
+use dioxus::prelude::ReadableExt;
+
+impl GameAutomation {
+    /// Apply script files added, edited or deleted on disk to the timed events.
+    ///
+    /// Returns true when the timed event list changed.
+    pub(super) fn sync_scripts(&mut self, force: bool) -> bool {
+        let changes = self.scripts.rescan(force);
+        let mut events_changed = false;
+
+        for change in changes {
+            match change {
+                ScriptChange::Loaded { name, interval } => {
+                    let id = script_event_id(&name);
+                    match self.timed_events.get_mut(&id) {
+                        Some(event) if event.interval != interval => event.set_interval(interval),
+                        Some(_) => {}
+                        None => {
+                            let event_type = TimedEventType::Script { name: name.clone() };
+                            self.timed_events
+                                .insert(id.clone(), TimedEvent::new(id, event_type, interval));
+                        }
+                    }
+                    events_changed = true;
+                    log::info!(
+                        "📜 Loaded script '{}' (every {}s)",
+                        name,
+                        interval.as_secs()
+                    );
+                }
+                ScriptChange::Removed { name } => {
+                    self.timed_events.remove(&script_event_id(&name));
+                    events_changed = true;
+                    log::info!("📜 Removed script '{}'", name);
+                }
+                ScriptChange::Failed { name, error } => {
+                    log::error!("❌ Script '{}' not loaded: {}", name, error);
+                    *self.screenshot_status.write_unchecked() =
+                        format!("❌ Script '{}': {}", name, error);
+                }
+            }
+        }
+
+        events_changed
+    }
+
+    /// Run a script against the current device
+    pub(super) async fn run_script(&mut self, name: &str) -> Result<(), String> {
+        let client = self
+            .adb_client
+            .clone()
+            .ok_or_else(|| "ADB client not available".to_string())?;
+        let context = ScriptContext {
+            client,
+            detector: self.game_detector.clone(),
+            counters: latest_values(&self.counters.peek()),
+            safety: self.config.safety.clone(),
+        };
+
+        let outcome = self.scripts.run(name, context).await?;
+        debug_print!(
+            self.debug_enabled,
+            "📜 Script '{}' finished ({} actions)",
+            name,
+            outcome.actions
+        );
+        if let Some(bytes) = outcome.screenshot {
+            match RgbFrame::from_png(&bytes) {
+                Ok(frame) => self.latest_screenshot = Some(Arc::new(frame)),
+                Err(e) => log::warn!("⚠️ Cannot decode script screenshot: {}", e),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use super::*;
+use crate::game_automation::sequence::{SequenceStatus, sequence_event_id};
+use dioxus::prelude::ReadableExt;
+
+impl GameAutomation {
+    /// Add a timed event for every `[sequences.<name>]` with an `interval_seconds`
+    pub(super) fn register_sequences(&mut self) {
+        for (name, sequence) in &self.config.sequences {
+            let Some(interval_seconds) = sequence.interval_seconds else {
+                continue;
+            };
+            let id = sequence_event_id(name);
+            let event_type = TimedEventType::Sequence { name: name.clone() };
+            let interval = Duration::from_secs(interval_seconds.max(1));
+            self.timed_events
+                .entry(id.clone())
+                .or_insert_with(|| TimedEvent::new(id, event_type, interval));
+        }
+    }
+
+    /// Run a named sequence while holding the device, publishing each step
+    pub(super) async fn run_sequence(&mut self, name: &str) -> Result<(), String> {
+        let sequence = self
+            .config
+            .sequences
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown sequence '{}'", name))?;
+        let client = self
+            .adb_client
+            .clone()
+            .ok_or_else(|| "ADB client not available".to_string())?;
+        let detector = self.game_detector.clone();
+        let progress_signal = self.sequence_progress;
+
+        log::info!(
+            "🧩 Running sequence '{}' ({} steps)",
+            name,
+            sequence.steps.len()
+        );
+        let result = {
+            // Held for the whole sequence so no other tap lands between steps
+            let client_guard = client.lock().await;
+            sequence
+                .run(
+                    name,
+                    &*client_guard,
+                    &detector,
+                    &self.config.safety,
+                    |progress| {
+                        debug_print!(
+                            self.debug_enabled,
+                            "🧩 {} {}/{}: {}",
+                            progress.name,
+                            progress.step,
+                            progress.total,
+                            progress.description
+                        );
+                        *progress_signal.write_unchecked() = Some(progress);
+                    },
+                )
+                .await
+        };
+
+        match result {
+            Ok(0) => {
+                *self.screenshot_status.write_unchecked() =
+                    format!("🧩 Sequence '{}' completed", name);
+                Ok(())
+            }
+            Ok(failed) => {
+                *self.screenshot_status.write_unchecked() = format!(
+                    "🧩 Sequence '{}' completed, {} step(s) failed",
+                    name, failed
+                );
+                Ok(())
+            }
+            Err(failure) => {
+                if failure.disconnected {
+                    self.device_disconnected = true;
+                    self.last_reconnect_attempt = None;
+                    *self.device_info.write_unchecked() = None;
+                    self.status
+                        .write_unchecked()
+                        .apply(ConnectionEvent::Lost(DisconnectReason::AutomationPaused));
+                }
+                Err(format!("Sequence '{}' failed at {}", name, failure.message))
+            }
+        }
+    }
+
+    /// Drop a finished sequence from the GUI once automation stops
+    pub(super) fn clear_sequence_progress(&mut self) {
+        let finished = self
+            .sequence_progress
+            .peek()
+            .as_ref()
+            .is_some_and(|p| p.status != SequenceStatus::Running);
+        if finished {
+            *self.sequence_progress.write_unchecked() = None;
+        }
+    }
+}
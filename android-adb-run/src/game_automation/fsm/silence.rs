@@ -0,0 +1,33 @@
+use super::*;
+use crate::game_automation::silence::{restore, silence};
+
+impl GameAutomation {
+    /// Apply `[silence]` to the device, once per run; the previous values are kept
+    pub(super) async fn silence_device(&mut self) {
+        if self.silenced.is_some() || !self.config.silence.enabled() {
+            return;
+        }
+        let Some(client) = self.adb_client.clone() else {
+            return;
+        };
+        let saved = silence(&*client.lock().await, &self.config.silence).await;
+        debug_print!(self.debug_enabled, "🔕 Device silenced, saved {:?}", saved);
+        log::info!("🔕 Device silenced while automation runs");
+        self.silenced = Some(saved);
+    }
+
+    /// Put back the sound settings `silence_device` changed
+    pub(super) async fn restore_device_sound(&mut self) {
+        let Some(saved) = self.silenced.take() else {
+            return;
+        };
+        let Some(client) = self.adb_client.clone() else {
+            return;
+        };
+        let result = restore(&*client.lock().await, &saved).await;
+        match result {
+            Ok(()) => log::info!("🔔 Device sound settings restored"),
+            Err(e) => log::warn!("⚠️ Restoring device sound settings failed: {}", e),
+        }
+    }
+}
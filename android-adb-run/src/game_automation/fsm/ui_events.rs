@@ -0,0 +1,128 @@
+use super::*;
+use crate::game_automation::config::UiEventsConfig;
+use dioxus::prelude::Task;
+use std::time::Instant;
+
+/// Background `uiautomator events` windows on one client, counting UI changes
+pub(super) struct UiEventListener {
+    client: Arc<Mutex<AdbBackend>>,
+    config: UiEventsConfig,
+    package: Option<String>,
+    task: Task,
+    changes: mpsc::Receiver<usize>,
+}
+
+impl UiEventListener {
+    fn start(
+        client: Arc<Mutex<AdbBackend>>,
+        config: UiEventsConfig,
+        package: Option<String>,
+    ) -> Self {
+        let (tx, changes) = mpsc::channel(4);
+        let device = client.clone();
+        let (listen_config, listen_package) = (config.clone(), package.clone());
+        let task = dioxus::prelude::spawn(async move {
+            while !tx.is_closed() {
+                let started = Instant::now();
+                let result = device.lock().await.ui_events(listen_config.listen()).await;
+                match result {
+                    Ok(events) => {
+                        let changed = events
+                            .iter()
+                            .filter(|event| {
+                                event.is_ui_change(listen_config.content_changes)
+                                    && listen_package.as_ref().is_none_or(|package| {
+                                        event.package.as_ref() == Some(package)
+                                    })
+                            })
+                            .count();
+                        if changed > 0 {
+                            // A full channel already has a capture waiting
+                            let _ = tx.try_send(changed);
+                        }
+                    }
+                    Err(e) => log::debug!("UI event listening failed: {}", e),
+                }
+                // Don't spin when the command returns early, e.g. without uiautomator
+                tokio::time::sleep(listen_config.listen().saturating_sub(started.elapsed())).await;
+            }
+        });
+        Self {
+            client,
+            config,
+            package,
+            task,
+            changes,
+        }
+    }
+
+    /// UI changes reported since the last call
+    fn take_changes(&mut self) -> usize {
+        let mut changes = 0;
+        while let Ok(changed) = self.changes.try_recv() {
+            changes += changed;
+        }
+        changes
+    }
+}
+
+impl GameAutomation {
+    /// With `[ui_events]` on, listen for UI changes while automation runs and
+    /// capture (and match) as soon as one is reported
+    pub(super) async fn poll_ui_events(&mut self) {
+        let listening = self.config.ui_events.enabled
+            && self.is_running
+            && self.state == GameState::Running
+            && !self.device_disconnected;
+        let Some(client) = self.adb_client.clone().filter(|_| listening) else {
+            self.stop_ui_listener();
+            return;
+        };
+        let package = self.config.app.package.clone();
+        let current = self.ui_listener.as_ref().is_some_and(|listener| {
+            Arc::ptr_eq(&listener.client, &client)
+                && listener.config == self.config.ui_events
+                && listener.package == package
+        });
+        if !current {
+            self.stop_ui_listener();
+            log::info!("👁️ Listening for UI changes");
+            self.ui_listener = Some(UiEventListener::start(
+                client,
+                self.config.ui_events.clone(),
+                package,
+            ));
+        }
+
+        // Changes during the pause stay queued for the next capture
+        if self
+            .last_ui_capture
+            .is_some_and(|last| last.elapsed() < self.config.ui_events.min_capture_interval())
+        {
+            return;
+        }
+        let changes = self
+            .ui_listener
+            .as_mut()
+            .map_or(0, UiEventListener::take_changes);
+        if changes == 0 {
+            return;
+        }
+        self.last_ui_capture = Some(Instant::now());
+        debug_print!(
+            self.debug_enabled,
+            "👁️ {} UI change(s) - capturing",
+            changes
+        );
+        if let Err(e) = self.take_screenshot().await {
+            log::warn!("⚠️ Capture after UI change failed: {}", e);
+        }
+    }
+
+    pub(super) fn stop_ui_listener(&mut self) {
+        if let Some(listener) = self.ui_listener.take() {
+            listener.task.cancel();
+            log::info!("👁️ Stopped listening for UI changes");
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use super::*;
+use crate::adb::types::KEYCODE_BACK;
+use crate::game_automation::watchdog::{StuckRecovery, WatchdogConfig};
+use dioxus::prelude::ReadableExt;
+use std::time::Instant;
+
+impl GameAutomation {
+    /// Feed a captured screenshot to the `[watchdog]`
+    pub(super) async fn watch_frame(&mut self, frame: &Arc<RgbFrame>) {
+        if !self.config.watchdog.enabled {
+            return;
+        }
+        let frame = frame.clone();
+        match tokio::task::spawn_blocking(move || FrameFingerprint::from_gray(&frame.to_luma()))
+            .await
+        {
+            Ok(fingerprint) => self.watchdog.saw_frame(
+                fingerprint,
+                self.config.watchdog.change_threshold.clamp(0.0, 1.0),
+                Instant::now(),
+            ),
+            Err(e) => log::warn!("⚠️ Fingerprint task failed: {}", e),
+        }
+    }
+
+    /// A template match is progress: the watchdog starts over
+    pub(super) fn watchdog_saw_match(&mut self) {
+        self.watchdog.saw_match(Instant::now());
+        self.clear_stuck();
+    }
+
+    pub(super) fn clear_stuck(&mut self) {
+        if self.stuck.peek().is_some() {
+            self.stuck.set(None);
+        }
+    }
+
+    /// Run the `[watchdog]` recovery once running automation has been stuck
+    /// for `stuck_after_seconds`, or pause when the recoveries are used up
+    pub(super) async fn check_stuck(&mut self) {
+        if self.state != GameState::Running {
+            return;
+        }
+        let now = Instant::now();
+        let Some(idle) = self.watchdog.stuck_for(&self.config.watchdog, now) else {
+            return;
+        };
+        let config = self.config.watchdog.clone();
+        if !self.watchdog.start_recovery(&config, now) {
+            log::error!(
+                "❌ Still stuck after {} recoveries, pausing automation",
+                self.watchdog.recoveries()
+            );
+            *self.screenshot_status.write_unchecked() = format!(
+                "❌ Stuck after {} recoveries - automation paused",
+                self.watchdog.recoveries()
+            );
+            self.change_state(GameState::Paused).await;
+            return;
+        }
+
+        let recovery = match (config.recovery, &config.sequence) {
+            (StuckRecovery::Sequence, Some(name)) => format!("run sequence '{}'", name),
+            (recovery, _) => recovery.to_string(),
+        };
+        let detection = StuckDetection {
+            idle_seconds: idle.as_secs(),
+            recovery,
+            attempt: self.watchdog.recoveries(),
+        };
+        log::warn!(
+            "🧭 Stuck: no match or screen change for {}s, {} (attempt {})",
+            detection.idle_seconds,
+            detection.recovery,
+            detection.attempt
+        );
+        *self.screenshot_status.write_unchecked() = format!(
+            "🧭 Stuck for {}s - {}",
+            detection.idle_seconds, detection.recovery
+        );
+        self.stuck.set(Some(detection));
+
+        if let Err(e) = self.recover_from_stuck(&config).await {
+            log::warn!("⚠️ Stuck recovery failed: {}", e);
+        }
+    }
+
+    async fn recover_from_stuck(&mut self, config: &WatchdogConfig) -> Result<(), String> {
+        let client = self
+            .adb_client
+            .clone()
+            .ok_or_else(|| "ADB client not available".to_string())?;
+        match config.recovery {
+            StuckRecovery::Back => client
+                .lock()
+                .await
+                .key_event(KEYCODE_BACK)
+                .await
+                .map_err(|e| e.to_string()),
+            StuckRecovery::RestartApp => {
+                let package = self
+                    .config
+                    .app
+                    .package
+                    .clone()
+                    .ok_or_else(|| "restart_app needs [app] package".to_string())?;
+                let component = self
+                    .config
+                    .app
+                    .launch_component()
+                    .unwrap_or_else(|| package.clone());
+                let client = client.lock().await;
+                client
+                    .force_stop(&package)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                client
+                    .start_activity(&component)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            StuckRecovery::Sequence => {
+                let name = config
+                    .sequence
+                    .clone()
+                    .ok_or_else(|| "recovery = \"sequence\" needs a sequence name".to_string())?;
+                self.run_sequence(&name).await
+            }
+        }
+    }
+}
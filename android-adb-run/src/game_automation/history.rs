@@ -0,0 +1,331 @@
+// Execution history: every tap, swipe and template match automation performed,
+// with where, what caused it and how it went. Appended as JSON Lines to
+// `logs/history.jsonl` (one entry per line, never rewritten) to work out later
+// why the game ended up where it did, and fed to the GUI History panel.
+use crate::logging::LOG_DIR;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+pub const HISTORY_FILE: &str = "history.jsonl";
+/// Entries kept in memory (and read back from the file at startup) for the GUI
+const HISTORY_KEPT: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryAction {
+    Tap,
+    Swipe,
+    /// A template was found on a screenshot
+    Match,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Done,
+    /// Held back by `[safety]`
+    Blocked,
+    Failed,
+}
+
+impl fmt::Display for HistoryAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tap => write!(f, "tap"),
+            Self::Swipe => write!(f, "swipe"),
+            Self::Match => write!(f, "match"),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Done => write!(f, "done"),
+            Self::Blocked => write!(f, "blocked"),
+            Self::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// One line of `history.jsonl`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub unix_ms: u64,
+    pub action: HistoryAction,
+    /// Timed event id, template, `sequence:<name>` or `script:<name>` behind it
+    pub source: String,
+    pub x: u32,
+    pub y: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    pub outcome: Outcome,
+    /// Swipe end, or why it was blocked or failed
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+    /// `Device::key` of the device it happened on
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub device: String,
+}
+
+impl HistoryEntry {
+    pub fn new(action: HistoryAction, source: impl Into<String>, (x, y): (u32, u32)) -> Self {
+        Self {
+            unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since| since.as_millis() as u64)
+                .unwrap_or(0),
+            action,
+            source: source.into(),
+            x,
+            y,
+            confidence: None,
+            outcome: Outcome::Done,
+            detail: String::new(),
+            device: String::new(),
+        }
+    }
+
+    pub fn on_device(mut self, device: impl Into<String>) -> Self {
+        self.device = device.into();
+        self
+    }
+
+    pub fn with_confidence(mut self, confidence: f32) -> Self {
+        self.confidence = Some(confidence);
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+
+    /// Done, or failed with the error
+    pub fn with_result<T, E: fmt::Display>(self, result: &Result<T, E>) -> Self {
+        match result {
+            Ok(_) => self,
+            Err(e) => self.failed(e),
+        }
+    }
+
+    pub fn failed(mut self, error: impl fmt::Display) -> Self {
+        self.outcome = Outcome::Failed;
+        self.detail = error.to_string();
+        self
+    }
+
+    pub fn blocked(mut self, reason: impl fmt::Display) -> Self {
+        self.outcome = Outcome::Blocked;
+        self.detail = reason.to_string();
+        self
+    }
+
+    /// Local `MM-DD HH:MM:SS`
+    pub fn time_label(&self) -> String {
+        let offset = time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC);
+        match time::OffsetDateTime::from_unix_timestamp_nanos(self.unix_ms as i128 * 1_000_000) {
+            Ok(at) => {
+                let at = at.to_offset(offset);
+                format!(
+                    "{:02}-{:02} {:02}:{:02}:{:02}",
+                    at.month() as u8,
+                    at.day(),
+                    at.hour(),
+                    at.minute(),
+                    at.second()
+                )
+            }
+            Err(_) => "?".to_string(),
+        }
+    }
+}
+
+/// What the History panel shows; unset parts let everything through
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryFilter {
+    pub action: Option<HistoryAction>,
+    pub outcome: Option<Outcome>,
+    /// Looked for (ignoring case) in the source and detail
+    pub text: String,
+    /// Only this device's entries (`Device::key`)
+    pub device: Option<String>,
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        let text = self.text.trim().to_lowercase();
+        self.device
+            .as_ref()
+            .is_none_or(|device| *device == entry.device)
+            && self.action.is_none_or(|action| action == entry.action)
+            && self.outcome.is_none_or(|outcome| outcome == entry.outcome)
+            && (text.is_empty()
+                || entry.source.to_lowercase().contains(&text)
+                || entry.detail.to_lowercase().contains(&text))
+    }
+}
+
+struct HistoryLog {
+    path: PathBuf,
+    recent: Mutex<VecDeque<HistoryEntry>>,
+    sender: broadcast::Sender<HistoryEntry>,
+}
+
+static HISTORY: OnceLock<HistoryLog> = OnceLock::new();
+
+fn history() -> &'static HistoryLog {
+    HISTORY.get_or_init(|| {
+        let path = Path::new(LOG_DIR).join(HISTORY_FILE);
+        HistoryLog {
+            recent: Mutex::new(read_tail(&path, HISTORY_KEPT)),
+            path,
+            sender: broadcast::channel(256).0,
+        }
+    })
+}
+
+/// Last `count` readable entries of the file at `path`
+fn read_tail(path: &Path, count: usize) -> VecDeque<HistoryEntry> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return VecDeque::new();
+    };
+    let mut entries: VecDeque<HistoryEntry> = content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(count)
+        .collect();
+    entries.make_contiguous().reverse();
+    entries
+}
+
+/// Every readable entry of a `history.jsonl` file, oldest first
+pub fn read_file(path: &Path) -> Vec<HistoryEntry> {
+    read_tail(path, usize::MAX).into()
+}
+
+/// Append `entry` to `history.jsonl` and show it in the GUI
+pub fn record(entry: HistoryEntry) {
+    let log = history();
+    if let Err(e) = append(&log.path, &entry) {
+        log::debug!("History not written to {}: {}", log.path.display(), e);
+    }
+
+    let mut recent = log.recent.lock().unwrap();
+    if recent.len() == HISTORY_KEPT {
+        recent.pop_front();
+    }
+    recent.push_back(entry.clone());
+    // No receivers until the GUI History panel opens
+    let _ = log.sender.send(entry);
+}
+
+fn append(path: &Path, entry: &HistoryEntry) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Recent entries plus a receiver for everything recorded from now on
+pub fn subscribe() -> (Vec<HistoryEntry>, broadcast::Receiver<HistoryEntry>) {
+    let log = history();
+    // Hold the lock so no entry lands between the snapshot and the receiver
+    let recent = log.recent.lock().unwrap();
+    (recent.iter().cloned().collect(), log.sender.subscribe())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_lines_and_filter() {
+        let tap = HistoryEntry::new(HistoryAction::Tap, "claim_1d_tap", (350, 628));
+        let blocked = HistoryEntry::new(HistoryAction::Tap, "shop_button", (900, 100))
+            .with_confidence(0.93)
+            .blocked("inside no-tap zone 'shop'");
+        let failed = HistoryEntry::new(HistoryAction::Swipe, "sequence:daily", (540, 1800))
+            .with_result(&Err::<(), _>("device offline"));
+
+        let line = serde_json::to_string(&blocked).unwrap();
+        assert!(line.contains(r#""action":"tap""#) && line.contains(r#""outcome":"blocked""#));
+        assert_eq!(
+            serde_json::from_str::<HistoryEntry>(&line).unwrap(),
+            blocked
+        );
+        assert!(!serde_json::to_string(&tap).unwrap().contains("detail"));
+        assert_eq!(failed.outcome, Outcome::Failed);
+
+        let entries = [tap, blocked, failed];
+        let shown = |filter: HistoryFilter| {
+            entries
+                .iter()
+                .filter(|entry| filter.matches(entry))
+                .map(|entry| entry.source.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shown(HistoryFilter::default()).len(), 3);
+        assert_eq!(
+            shown(HistoryFilter {
+                action: Some(HistoryAction::Tap),
+                ..Default::default()
+            }),
+            ["claim_1d_tap", "shop_button"]
+        );
+        assert_eq!(
+            shown(HistoryFilter {
+                outcome: Some(Outcome::Failed),
+                ..Default::default()
+            }),
+            ["sequence:daily"]
+        );
+        assert_eq!(
+            shown(HistoryFilter {
+                text: "NO-TAP".into(),
+                ..Default::default()
+            }),
+            ["shop_button"]
+        );
+    }
+
+    #[test]
+    fn test_each_device_sees_its_own_history() {
+        let entries = [
+            HistoryEntry::new(HistoryAction::Tap, "claim_tap", (350, 628)).on_device("R58M123ABC"),
+            HistoryEntry::new(HistoryAction::Tap, "claim_tap", (350, 628)).on_device("R58M456DEF"),
+            HistoryEntry::new(HistoryAction::Match, "shop_button", (900, 100))
+                .on_device("192.168.1.20:5555"),
+        ];
+        let line = serde_json::to_string(&entries[0]).unwrap();
+        assert!(line.contains(r#""device":"R58M123ABC""#));
+        assert_eq!(
+            serde_json::from_str::<HistoryEntry>(&line).unwrap(),
+            entries[0]
+        );
+
+        let on = |device: &str| {
+            let filter = HistoryFilter {
+                device: Some(device.into()),
+                ..Default::default()
+            };
+            entries.iter().filter(|entry| filter.matches(entry)).count()
+        };
+        assert_eq!(on("R58M123ABC"), 1);
+        assert_eq!(on("R58M456DEF"), 1);
+        assert_eq!(on("18d1:4ee7"), 0);
+    }
+}
@@ -0,0 +1,165 @@
+// Humanized input: small random variations in where and when automation
+// touches the screen, so its taps and swipes look less machine-made
+use crate::adb::{AdbClient, AdbError, AdbResult, TouchPath};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Largest interval variation, so a jittered event never fires back to back
+pub const MAX_INTERVAL_PERCENT: u8 = 90;
+
+/// Waypoints on a curved swipe; the gesture spreads its duration evenly over them
+const SWIPE_POINTS: usize = 12;
+/// Most a curved swipe bows away from the straight line, as a share of its length
+const MAX_BOW: f32 = 0.15;
+
+/// Random variation for one timed event (`jitter = { ... }`) or for template actions
+/// (`[matching.jitter]`); all off by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Jitter {
+    /// Taps land anywhere within this many pixels of their target
+    pub tap_radius_px: u32,
+    /// Intervals vary by up to this percentage either way
+    pub interval_percent: u8,
+    /// Swipes bow along a Bezier curve and change speed along the way
+    pub curved_swipes: bool,
+}
+
+impl Jitter {
+    pub fn is_off(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Random point within `tap_radius_px` of `(x, y)`, kept on a `screen`-sized display
+    pub fn tap_point(&self, point: (u32, u32), screen: (u32, u32)) -> (u32, u32) {
+        self.tap_point_with(&mut rand::rng(), point, screen)
+    }
+
+    pub fn tap_point_with<R: Rng>(
+        &self,
+        rng: &mut R,
+        (x, y): (u32, u32),
+        (screen_x, screen_y): (u32, u32),
+    ) -> (u32, u32) {
+        if self.tap_radius_px == 0 {
+            return (x, y);
+        }
+        // Square root spreads taps evenly over the disc instead of bunching them in the middle
+        let radius = self.tap_radius_px as f32 * rng.random::<f32>().sqrt();
+        let angle = rng.random_range(0.0..std::f32::consts::TAU);
+        (
+            on_screen(x as f32 + radius * angle.cos(), screen_x),
+            on_screen(y as f32 + radius * angle.sin(), screen_y),
+        )
+    }
+
+    /// Multiplier for the next wait, within `interval_percent` of 1.0
+    pub fn interval_scale(&self) -> f32 {
+        self.interval_scale_with(&mut rand::rng())
+    }
+
+    pub fn interval_scale_with<R: Rng>(&self, rng: &mut R) -> f32 {
+        let spread = self.interval_percent.min(MAX_INTERVAL_PERCENT) as f32 / 100.0;
+        if spread == 0.0 {
+            1.0
+        } else {
+            1.0 + rng.random_range(-spread..=spread)
+        }
+    }
+}
+
+/// Round a coordinate onto a screen `size` pixels wide (or high)
+fn on_screen(value: f32, size: u32) -> u32 {
+    value.round().clamp(0.0, size.saturating_sub(1) as f32) as u32
+}
+
+/// Finger path from `from` to `to` along a quadratic Bezier curve bowed to a
+/// random side, spaced so the swipe eases in and out at a random bias
+pub fn curved_swipe_path<R: Rng>(
+    rng: &mut R,
+    from: (u32, u32),
+    to: (u32, u32),
+    (screen_x, screen_y): (u32, u32),
+) -> TouchPath {
+    let (x1, y1) = (from.0 as f32, from.1 as f32);
+    let (x2, y2) = (to.0 as f32, to.1 as f32);
+    // Control point off the midpoint, perpendicular to the swipe
+    let bow = rng.random_range(-MAX_BOW..=MAX_BOW);
+    let (cx, cy) = (
+        (x1 + x2) / 2.0 - (y2 - y1) * bow,
+        (y1 + y2) / 2.0 + (x2 - x1) * bow,
+    );
+    // Above 1 the finger lingers at the start, below 1 at the end
+    let bias = rng.random_range(0.8..=1.25f32);
+
+    (0..=SWIPE_POINTS)
+        .map(|i| {
+            let linear = (i as f32 / SWIPE_POINTS as f32).powf(bias);
+            let t = linear * linear * (3.0 - 2.0 * linear);
+            let u = 1.0 - t;
+            let x = u * u * x1 + 2.0 * u * t * cx + t * t * x2;
+            let y = u * u * y1 + 2.0 * u * t * cy + t * t * y2;
+            (on_screen(x, screen_x), on_screen(y, screen_y))
+        })
+        .collect()
+}
+
+/// Swipe from `from` to `to`, curved when `jitter` asks for it; devices without a
+/// usable touchscreen for `sendevent` get the straight `input swipe`. Both start at
+/// `from`, the point callers check with `safety::allow_tap` before swiping.
+pub async fn swipe<C: AdbClient>(
+    client: &C,
+    jitter: &Jitter,
+    from: (u32, u32),
+    to: (u32, u32),
+    duration_ms: u32,
+) -> AdbResult<()> {
+    if jitter.curved_swipes {
+        let path = curved_swipe_path(&mut rand::rng(), from, to, client.screen_dimensions());
+        match client.multi_swipe(&[path], duration_ms).await {
+            Err(AdbError::NoTouchDeviceFound) => {}
+            result => return result,
+        }
+    }
+    client
+        .swipe(from.0, from.1, to.0, to.1, Some(duration_ms))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_jitter_stays_in_bounds() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let jitter = Jitter {
+            tap_radius_px: 10,
+            interval_percent: 20,
+            curved_swipes: true,
+        };
+
+        for _ in 0..200 {
+            let (x, y) = jitter.tap_point_with(&mut rng, (500, 1000), (1080, 2400));
+            let (dx, dy) = (x as f32 - 500.0, y as f32 - 1000.0);
+            assert!((dx * dx + dy * dy).sqrt() <= 10.5);
+            // Clamped onto the screen near an edge
+            let (x, y) = jitter.tap_point_with(&mut rng, (0, 2399), (1080, 2400));
+            assert!(x <= 10 && (2389..2400).contains(&y));
+
+            let scale = jitter.interval_scale_with(&mut rng);
+            assert!((0.8..=1.2).contains(&scale));
+        }
+        assert_eq!(Jitter::default().interval_scale(), 1.0);
+        assert_eq!(Jitter::default().tap_point((5, 5), (10, 10)), (5, 5));
+
+        let path = curved_swipe_path(&mut rng, (540, 1800), (540, 600), (1080, 2400));
+        assert_eq!(path.len(), SWIPE_POINTS + 1);
+        assert_eq!((path[0], path[SWIPE_POINTS]), ((540, 1800), (540, 600)));
+        // Always heading up the screen, bowed at most 15% of 1200px sideways
+        assert!(path.windows(2).all(|leg| leg[1].1 <= leg[0].1));
+        assert!(path.iter().all(|&(x, _)| x.abs_diff(540) <= 180));
+    }
+}
@@ -0,0 +1,197 @@
+// Near-miss captures: when a template scores just under the confidence threshold
+// (`[match_debug]`), save the screenshot with the searched area and the best
+// candidate outlined, plus a manifest line, so templates can be improved offline.
+use super::match_image::NearMiss;
+use super::overlay::outline;
+use crate::adb::RgbFrame;
+use image::{Rgb, RgbImage};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// One line per near miss, next to the saved screenshots
+pub const MANIFEST_FILE: &str = "manifest.jsonl";
+
+const SEARCH_AREA_COLOR: Rgb<u8> = Rgb([64, 160, 255]);
+const CANDIDATE_COLOR: Rgb<u8> = Rgb([255, 140, 0]);
+
+/// Manifest line describing one near miss in a saved screenshot
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManifestEntry {
+    /// Screenshot file name within the debug directory
+    pub file: String,
+    pub unix_ms: u64,
+    pub template: String,
+    pub confidence: f32,
+    pub threshold: f32,
+    /// Best candidate as `[x, y, width, height]`
+    pub candidate: [u32; 4],
+    pub scale: f32,
+    /// Area searched as `[x, y, width, height]`
+    pub search_area: [u32; 4],
+}
+
+/// Keeps one template from being saved on every screenshot while it stays ambiguous
+#[derive(Debug, Default)]
+pub struct NearMissCooldown {
+    last_saved: HashMap<String, Instant>,
+}
+
+impl NearMissCooldown {
+    /// The near misses whose template was not saved within `cooldown`, marked saved now
+    pub fn due(&mut self, misses: Vec<NearMiss>, cooldown: Duration) -> Vec<NearMiss> {
+        let now = Instant::now();
+        misses
+            .into_iter()
+            .filter(|miss| {
+                let name = &miss.best.template.name;
+                if self
+                    .last_saved
+                    .get(name)
+                    .is_some_and(|last| now.duration_since(*last) < cooldown)
+                {
+                    return false;
+                }
+                self.last_saved.insert(name.clone(), now);
+                true
+            })
+            .collect()
+    }
+}
+
+/// `frame` with every near miss's search area (blue) and best candidate (orange) drawn
+pub fn annotate(frame: &RgbFrame, misses: &[NearMiss]) -> RgbImage {
+    let mut image = frame.to_rgb_image();
+    for miss in misses {
+        let area = &miss.search_area;
+        outline(
+            &mut image,
+            (area.x, area.y, area.width, area.height),
+            SEARCH_AREA_COLOR,
+        );
+        let (width, height) = miss.best.screen_size();
+        outline(
+            &mut image,
+            (miss.best.x, miss.best.y, width, height),
+            CANDIDATE_COLOR,
+        );
+    }
+    image
+}
+
+/// Manifest lines for `misses` saved as `file`
+pub fn manifest_entries(file: &str, misses: &[NearMiss], threshold: f32) -> Vec<ManifestEntry> {
+    let unix_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_millis() as u64)
+        .unwrap_or(0);
+    misses
+        .iter()
+        .map(|miss| {
+            let (width, height) = miss.best.screen_size();
+            let area = &miss.search_area;
+            ManifestEntry {
+                file: file.to_string(),
+                unix_ms,
+                template: miss.best.template.name.clone(),
+                confidence: miss.best.confidence,
+                threshold,
+                candidate: [miss.best.x, miss.best.y, width, height],
+                scale: miss.best.scale_factor,
+                search_area: [area.x, area.y, area.width, area.height],
+            }
+        })
+        .collect()
+}
+
+/// Save the annotated screenshot into `dir` and append its manifest lines;
+/// returns the screenshot path
+pub fn save_near_misses(
+    dir: &Path,
+    frame: &RgbFrame,
+    misses: &[NearMiss],
+    threshold: f32,
+) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let now = time::OffsetDateTime::now_utc();
+    let file = format!(
+        "near_miss_{:04}{:02}{:02}_{:02}{:02}{:02}_{:03}.png",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second(),
+        now.millisecond()
+    );
+    let path = dir.join(&file);
+    annotate(frame, misses)
+        .save(&path)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let mut lines = String::new();
+    for entry in manifest_entries(&file, misses, threshold) {
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| format!("Failed to encode manifest entry: {}", e))?;
+        lines.push_str(&line);
+        lines.push('\n');
+    }
+    let manifest = dir.join(MANIFEST_FILE);
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&manifest)
+        .and_then(|mut out| out.write_all(lines.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", manifest.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_automation::match_image::{
+        SearchRegion, Template, TemplateBehavior, TemplateCategory, TemplateMatch,
+    };
+
+    #[test]
+    fn test_near_miss_annotation_and_cooldown() {
+        let search_area = SearchRegion::new(10, 20, 80, 60, "claim".to_string());
+        let template = Template {
+            path: "claim_button.png".to_string(),
+            name: "claim_button".to_string(),
+            search_region: search_area.clone(),
+            width: 20,
+            height: 10,
+            category: TemplateCategory::Unknown,
+            behavior: TemplateBehavior::default(),
+            source_size: None,
+        };
+        let miss = NearMiss {
+            best: TemplateMatch::new(template, 30, 40, 0.78, 1.0),
+            search_area,
+        };
+        let frame = RgbFrame {
+            width: 100,
+            height: 100,
+            pixels: vec![0; 100 * 100 * 3],
+        };
+
+        let image = annotate(&frame, std::slice::from_ref(&miss));
+        assert_eq!(image.get_pixel(10, 50), &SEARCH_AREA_COLOR);
+        assert_eq!(image.get_pixel(30, 45), &CANDIDATE_COLOR);
+        assert_eq!(image.get_pixel(50, 50), &Rgb([0, 0, 0]));
+
+        let entries = manifest_entries("a.png", std::slice::from_ref(&miss), 0.85);
+        assert_eq!(entries[0].candidate, [30, 40, 20, 10]);
+        assert_eq!(entries[0].search_area, [10, 20, 80, 60]);
+
+        let mut cooldown = NearMissCooldown::default();
+        let minute = Duration::from_secs(60);
+        assert_eq!(cooldown.due(vec![miss.clone()], minute).len(), 1);
+        assert!(cooldown.due(vec![miss.clone()], minute).is_empty());
+        assert_eq!(cooldown.due(vec![miss], Duration::ZERO).len(), 1);
+    }
+}
@@ -1,5 +1,10 @@
 //! Configuration for image matching operations
 
+use super::template::TemplateBehavior;
+use crate::game_automation::humanize::Jitter;
+use crate::template_matching::matcher::{DEFAULT_EARLY_EXIT_SLACK, DEFAULT_MAX_CANDIDATES};
+use std::collections::BTreeMap;
+
 #[derive(Debug, Clone)]
 pub struct MatchConfig {
     /// Confidence threshold for template matching (0.0 to 1.0)
@@ -10,12 +15,30 @@ pub struct MatchConfig {
     pub enable_multiscale: bool,
     /// Scale factors for multi-scale matching
     pub scale_factors: Vec<f32>,
+    /// Template rotations (degrees) also tried; empty = upright only
+    pub rotation_angles: Vec<f32>,
     /// Debug mode flag
     pub debug_enabled: bool,
     /// Use optimized match-patch algorithm with early exit
     pub use_match_patch_optimization: bool,
     /// Search margin for localized match-patch search (±N pixels)
     pub match_patch_search_margin: u32,
+    /// Downscale factor for the coarse pre-pass of patch matching (1 = off)
+    pub pyramid_factor: u32,
+    /// Score every Nth position of a patch search first, then refine (1 = off)
+    pub patch_search_step: u32,
+    /// How far below the threshold a coarse or strided candidate may score
+    pub patch_early_exit_slack: f32,
+    /// Coarse or strided candidates rescored at full resolution per patch
+    pub patch_max_candidates: usize,
+    /// Search whole screenshots for patches not found near their position
+    pub patch_full_screen_fallback: bool,
+    /// Action, priority and limits per template name
+    pub template_behaviors: BTreeMap<String, TemplateBehavior>,
+    /// Random tap offsets and curved swipes for template actions
+    pub jitter: Jitter,
+    /// Act on a match at least this confident without trying the remaining templates
+    pub early_action_confidence: Option<f32>,
 }
 
 impl Default for MatchConfig {
@@ -28,6 +51,15 @@ impl Default for MatchConfig {
             debug_enabled: false,
             use_match_patch_optimization: false,
             match_patch_search_margin: 10,
+            pyramid_factor: 1,
+            patch_search_step: 1,
+            patch_early_exit_slack: DEFAULT_EARLY_EXIT_SLACK,
+            patch_max_candidates: DEFAULT_MAX_CANDIDATES,
+            patch_full_screen_fallback: false,
+            rotation_angles: Vec::new(),
+            template_behaviors: BTreeMap::new(),
+            jitter: Jitter::default(),
+            early_action_confidence: None,
         }
     }
 }
@@ -42,6 +74,15 @@ pub fn create_default_config() -> MatchConfig {
         debug_enabled: false,
         use_match_patch_optimization: false,
         match_patch_search_margin: 10,
+        pyramid_factor: 4,
+        patch_search_step: 1,
+        patch_early_exit_slack: DEFAULT_EARLY_EXIT_SLACK,
+        patch_max_candidates: DEFAULT_MAX_CANDIDATES,
+        patch_full_screen_fallback: false,
+        rotation_angles: Vec::new(),
+        template_behaviors: BTreeMap::new(),
+        jitter: Jitter::default(),
+        early_action_confidence: None,
     }
 }
 
@@ -55,6 +96,15 @@ pub fn create_ui_config() -> MatchConfig {
         debug_enabled: false,
         use_match_patch_optimization: true,
         match_patch_search_margin: 20,
+        pyramid_factor: 1,
+        patch_search_step: 1,
+        patch_early_exit_slack: DEFAULT_EARLY_EXIT_SLACK,
+        patch_max_candidates: DEFAULT_MAX_CANDIDATES,
+        patch_full_screen_fallback: false,
+        rotation_angles: Vec::new(),
+        template_behaviors: BTreeMap::new(),
+        jitter: Jitter::default(),
+        early_action_confidence: None,
     }
 }
 
@@ -68,5 +118,42 @@ pub fn create_game_object_config() -> MatchConfig {
         debug_enabled: false,
         use_match_patch_optimization: false,
         match_patch_search_margin: 50,
+        pyramid_factor: 4,
+        patch_search_step: 1,
+        patch_early_exit_slack: DEFAULT_EARLY_EXIT_SLACK,
+        patch_max_candidates: DEFAULT_MAX_CANDIDATES,
+        patch_full_screen_fallback: false,
+        rotation_angles: Vec::new(),
+        template_behaviors: BTreeMap::new(),
+        jitter: Jitter::default(),
+        early_action_confidence: None,
+    }
+}
+
+/// `steps` scale factors spread evenly over `min..=max`
+pub fn scale_range(min: f32, max: f32, steps: usize) -> Vec<f32> {
+    let (min, max) = (min.min(max).max(0.05), max.max(min).max(0.05));
+    if steps <= 1 || (max - min).abs() < f32::EPSILON {
+        return vec![(min + max) / 2.0];
+    }
+    let step = (max - min) / (steps - 1) as f32;
+    (0..steps).map(|i| min + step * i as f32).collect()
+}
+
+/// Angles in degrees from -`tolerance` to +`tolerance` every `step`, upright first;
+/// empty when `tolerance` is 0
+pub fn rotation_angles(tolerance: f32, step: f32) -> Vec<f32> {
+    let tolerance = tolerance.abs();
+    if tolerance < 0.01 {
+        return Vec::new();
+    }
+    let step = step.abs().max(0.5).min(tolerance);
+    let mut angles = vec![0.0];
+    let mut angle = step;
+    while angle <= tolerance + 0.001 {
+        angles.push(-angle);
+        angles.push(angle);
+        angle += step;
     }
+    angles
 }
@@ -2,12 +2,20 @@
 
 use super::{
     config::MatchConfig,
+    features::{FeatureSet, match_features},
+    gpu::match_template_gpu,
+    mask::{MASK_ON, alpha_mask, masked_pixel_count},
     match_patch::PatchMatcher,
-    template::{Template, TemplateManager, TemplateMatch},
+    region::SearchRegion,
+    template::{ActionHistory, MatchBackend, Template, TemplateManager, TemplateMatch},
 };
 use crate::game_automation::types::GameState;
-use image::{ImageBuffer, Luma};
-use imageproc::template_matching::{MatchTemplateMethod, match_template};
+use image::{GrayImage, ImageBuffer, Luma};
+use imageproc::geometric_transformations::{Interpolation, rotate_about_center};
+use imageproc::template_matching::{MatchTemplateMethod, match_template, match_template_with_mask};
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct DetectionResult {
@@ -15,6 +23,10 @@ pub struct DetectionResult {
     pub suggested_state: Option<GameState>,
     pub confidence_score: f32,
     pub processing_time_ms: u128,
+    /// The match callback stopped the analysis before every template was tried
+    pub stopped_early: bool,
+    /// Closest `[scenes]` reference screen, when it is close enough
+    pub scene: Option<String>,
 }
 
 impl Default for DetectionResult {
@@ -30,6 +42,8 @@ impl DetectionResult {
             suggested_state: None,
             confidence_score: 0.0,
             processing_time_ms: 0,
+            stopped_early: false,
+            scene: None,
         }
     }
 
@@ -37,6 +51,32 @@ impl DetectionResult {
         !self.matches.is_empty()
     }
 
+    /// Match to act on: allowed by `history`, highest priority, then highest confidence
+    pub fn next_action(&self, history: &ActionHistory) -> Option<&TemplateMatch> {
+        self.matches
+            .iter()
+            .filter(|m| history.allows(&m.template))
+            .max_by(|a, b| {
+                a.template
+                    .behavior
+                    .priority
+                    .cmp(&b.template.behavior.priority)
+                    .then(
+                        a.confidence
+                            .partial_cmp(&b.confidence)
+                            .unwrap_or(std::cmp::Ordering::Equal),
+                    )
+            })
+    }
+
+    /// Most confident match of the named template (file name without extension)
+    pub fn best_match_for(&self, template: &str) -> Option<&TemplateMatch> {
+        self.matches
+            .iter()
+            .filter(|m| template_name_matches(&m.template.name, template))
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+    }
+
     pub fn best_match(&self) -> Option<&TemplateMatch> {
         self.matches.iter().max_by(|a, b| {
             a.confidence
@@ -46,9 +86,104 @@ impl DetectionResult {
     }
 }
 
-/// Main game state detector that performs image matching and analysis
+/// A template that was not matched, with its best candidate below the threshold
+#[derive(Debug, Clone)]
+pub struct NearMiss {
+    pub best: TemplateMatch,
+    /// Screen area the template was searched in
+    pub search_area: SearchRegion,
+}
+
+/// Spread of the correlation scores over a template's search area, every
+/// scale and rotation included
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScoreStats {
+    /// Positions scored
+    pub positions: u64,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    /// Positions scoring at or above the confidence threshold
+    pub above_threshold: u64,
+    sum: f64,
+}
+
+impl ScoreStats {
+    fn add(&mut self, score: f32, threshold: f32) {
+        if self.positions == 0 {
+            (self.min, self.max) = (score, score);
+        } else {
+            self.min = self.min.min(score);
+            self.max = self.max.max(score);
+        }
+        self.positions += 1;
+        self.sum += score as f64;
+        self.mean = (self.sum / self.positions as f64) as f32;
+        if score >= threshold {
+            self.above_threshold += 1;
+        }
+    }
+}
+
+/// `test_template` outcome for one template name
+#[derive(Debug, Clone)]
+pub struct TemplateTest {
+    pub template: String,
+    /// Matches at or above the threshold, as analysis reports them, and the time taken
+    pub result: DetectionResult,
+    /// Best position whatever its score
+    pub best: Option<TemplateMatch>,
+    /// Correlation score map; None for `backend = "features"` templates
+    pub scores: Option<ScoreStats>,
+    pub threshold: f32,
+    /// Screen area searched, covering every template of that name
+    pub search_area: Option<(u32, u32, u32, u32)>,
+}
+
+/// Templates may carry a region or resolution suffix in the file name
+/// (`button[10,20,30,40]`, `button@1080x2400`)
+pub(crate) fn template_name_matches(template_name: &str, wanted: &str) -> bool {
+    template_name == wanted || template_name.split(['[', '@']).next() == Some(wanted)
+}
+
+/// Decoded template, cropped and in grayscale
+struct TemplateImage {
+    gray: ImageBuffer<Luma<u8>, Vec<u8>>,
+    /// From the PNG alpha channel; 0 marks pixels left out of the score
+    mask: Option<ImageBuffer<Luma<u8>, Vec<u8>>>,
+}
+
+impl TemplateImage {
+    fn from_dynamic(image: &image::DynamicImage, name: &str) -> Result<Self, String> {
+        let mask = alpha_mask(image);
+        if let Some(mask) = &mask {
+            if masked_pixel_count(mask) == 0 {
+                return Err(format!("Template '{}' is fully transparent", name));
+            }
+            log::debug!(
+                "🎭 Template '{}' masked: {} of {} pixels compared",
+                name,
+                masked_pixel_count(mask),
+                mask.width() * mask.height()
+            );
+        }
+        Ok(Self {
+            gray: image.to_luma8(),
+            mask,
+        })
+    }
+}
+
+/// Main game state detector that performs image matching and analysis.
+///
+/// Template images are decoded, cropped and converted to grayscale once when
+/// loaded, so one detector can be shared (e.g. behind an `Arc`) and reused for
+/// every screenshot until the templates are reloaded.
+#[derive(Clone)]
 pub struct GameStateDetector {
     template_manager: TemplateManager,
+    /// Template images keyed by template path
+    template_images: HashMap<String, Arc<TemplateImage>>,
     config: MatchConfig,
     screen_width: u32,
     screen_height: u32,
@@ -58,6 +193,7 @@ impl GameStateDetector {
     pub fn new(screen_width: u32, screen_height: u32, config: MatchConfig) -> Self {
         Self {
             template_manager: TemplateManager::new(screen_width, screen_height),
+            template_images: HashMap::new(),
             config,
             screen_width,
             screen_height,
@@ -66,25 +202,101 @@ impl GameStateDetector {
 
     /// Load templates from directory
     pub fn load_templates(&mut self, directory: &str) -> Result<usize, String> {
+        let count = self
+            .template_manager
+            .load_templates_from_directory(directory)?;
         self.template_manager
-            .load_templates_from_directory(directory)
+            .apply_behaviors(&self.config.template_behaviors);
+        self.preload_template_images();
+        Ok(count)
+    }
+
+    /// Decode every loaded template not cached yet; failures are reported again at match time
+    fn preload_template_images(&mut self) {
+        let missing: Vec<Template> = self
+            .template_manager
+            .get_templates()
+            .iter()
+            .filter(|t| !self.template_images.contains_key(&t.path))
+            .cloned()
+            .collect();
+        for template in missing {
+            match self.load_and_crop_template(&template) {
+                Ok(image) => {
+                    self.template_images
+                        .insert(template.path.clone(), Arc::new(image));
+                }
+                Err(e) => log::warn!("⚠️ Template '{}' not preloaded: {}", template.name, e),
+            }
+        }
+    }
+
+    /// Grayscale image and mask of `template`, from the cache or read from disk
+    fn template_image(&self, template: &Template) -> Result<Arc<TemplateImage>, String> {
+        match self.template_images.get(&template.path) {
+            Some(image) => Ok(image.clone()),
+            None => self.load_and_crop_template(template).map(Arc::new),
+        }
+    }
+
+    /// Load templates from several directories; fails only when none could be read
+    pub fn load_templates_from(&mut self, directories: &[String]) -> Result<usize, String> {
+        let mut loaded = 0;
+        let mut errors = Vec::new();
+        for directory in directories {
+            match self.load_templates(directory) {
+                Ok(count) => loaded += count,
+                Err(e) => errors.push(e),
+            }
+        }
+        if !errors.is_empty() && errors.len() == directories.len() {
+            return Err(errors.join("; "));
+        }
+        Ok(loaded)
     }
 
     /// Analyze screenshot and detect game state
     pub fn analyze_screenshot(&self, screenshot_bytes: &[u8]) -> Result<DetectionResult, String> {
-        let start_time = std::time::Instant::now();
-
         // Load screenshot image
         let screenshot = image::load_from_memory(screenshot_bytes)
             .map_err(|e| format!("Failed to load screenshot: {e}"))?;
-        let screenshot_gray = screenshot.to_luma8();
+        Ok(self.analyze_gray(&screenshot.to_luma8()))
+    }
+
+    /// `analyze_screenshot` handing each match to `on_match` as soon as its
+    /// template is done; `ControlFlow::Break` skips the remaining templates
+    pub fn analyze_screenshot_streaming(
+        &self,
+        screenshot_bytes: &[u8],
+        on_match: impl FnMut(&TemplateMatch) -> ControlFlow<()>,
+    ) -> Result<DetectionResult, String> {
+        let screenshot = image::load_from_memory(screenshot_bytes)
+            .map_err(|e| format!("Failed to load screenshot: {e}"))?;
+        Ok(self.analyze_gray_streaming(&screenshot.to_luma8(), on_match))
+    }
+
+    /// Analyze an already-decoded grayscale screenshot (e.g. from `RgbFrame::to_luma`)
+    pub fn analyze_gray(&self, screenshot_gray: &GrayImage) -> DetectionResult {
+        self.analyze_gray_streaming(screenshot_gray, |_| ControlFlow::Continue(()))
+    }
+
+    /// `analyze_gray` with matches streamed to `on_match` as templates finish
+    pub fn analyze_gray_streaming(
+        &self,
+        screenshot_gray: &GrayImage,
+        mut on_match: impl FnMut(&TemplateMatch) -> ControlFlow<()>,
+    ) -> DetectionResult {
+        let start_time = std::time::Instant::now();
 
         let mut result = DetectionResult::new();
 
         // Process each template
         for (i, template) in self.template_manager.get_templates().iter().enumerate() {
+            if !template.behavior.enabled {
+                continue;
+            }
             if self.config.debug_enabled {
-                println!(
+                log::info!(
                     "🔍 Processing template {}/{}: {}",
                     i + 1,
                     self.template_manager.get_templates().len(),
@@ -92,24 +304,34 @@ impl GameStateDetector {
                 );
             }
 
-            match if self.config.use_match_patch_optimization {
-                self.match_template_optimized(&screenshot_gray, template)
-            } else {
-                self.match_template_in_region(&screenshot_gray, template)
-            } {
+            match self.match_one_template(
+                screenshot_gray,
+                template,
+                self.config.confidence_threshold,
+            ) {
                 Ok(matches) => {
                     if self.config.debug_enabled && !matches.is_empty() {
-                        println!(
+                        log::info!(
                             "✅ Found {} matches for template '{}'",
                             matches.len(),
                             template.name
                         );
                     }
+                    let stop = matches
+                        .iter()
+                        .fold(false, |stop, found| on_match(found).is_break() || stop);
                     result.matches.extend(matches);
+                    if stop {
+                        result.stopped_early = true;
+                        if self.config.debug_enabled {
+                            log::info!("⏩ Stopping analysis early after '{}'", template.name);
+                        }
+                        break;
+                    }
                 }
                 Err(e) => {
                     if self.config.debug_enabled {
-                        println!("❌ Template matching failed for '{}': {}", template.name, e);
+                        log::error!("❌ Template matching failed for '{}': {}", template.name, e);
                     }
                 }
             }
@@ -134,7 +356,223 @@ impl GameStateDetector {
             self.log_detection_results(&result);
         }
 
-        Ok(result)
+        result
+    }
+
+    /// Best match of one template (file name or name without its region) on
+    /// a grayscale screenshot, searching only where that template is expected
+    pub fn find_template(
+        &self,
+        wanted: &str,
+        screenshot_gray: &GrayImage,
+    ) -> Option<TemplateMatch> {
+        self.template_manager
+            .get_templates()
+            .iter()
+            .filter(|template| template_name_matches(&template.name, wanted))
+            .filter_map(|template| {
+                self.match_one_template(screenshot_gray, template, self.config.confidence_threshold)
+                    .ok()
+            })
+            .flatten()
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+    }
+
+    /// `find_template` with the details for tuning: the correlation score map over
+    /// the search area, the best position even below the threshold, and the time
+    pub fn test_template(
+        &self,
+        wanted: &str,
+        screenshot_gray: &GrayImage,
+    ) -> Result<TemplateTest, String> {
+        let start_time = std::time::Instant::now();
+        let templates: Vec<&Template> = self
+            .template_manager
+            .get_templates()
+            .iter()
+            .filter(|template| template_name_matches(&template.name, wanted))
+            .collect();
+        if templates.is_empty() {
+            return Err(format!("Template '{}' is not loaded", wanted));
+        }
+
+        let threshold = self.config.confidence_threshold;
+        let mut result = DetectionResult::new();
+        let mut best: Option<TemplateMatch> = None;
+        let mut scores: Option<ScoreStats> = None;
+        for template in templates {
+            let found = self.match_one_template(screenshot_gray, template, threshold)?;
+            result.matches.extend(found);
+            if template.behavior.backend == MatchBackend::Features {
+                continue;
+            }
+
+            let template_image = self.template_image(template)?;
+            let region = self.template_search_area(template);
+            if !region.is_valid() {
+                continue;
+            }
+            let cropped = image::imageops::crop_imm(
+                screenshot_gray,
+                region.x,
+                region.y,
+                region.width,
+                region.height,
+            )
+            .to_image();
+            let stats = scores.get_or_insert_with(ScoreStats::default);
+            for scale in self.match_scales(template) {
+                for angle in self.match_angles() {
+                    let Some(map) = self.score_map(&cropped, &template_image, (scale, angle))
+                    else {
+                        continue;
+                    };
+                    for (x, y, pixel) in map.enumerate_pixels() {
+                        let score = pixel[0];
+                        stats.add(score, threshold);
+                        if best.as_ref().is_none_or(|b| score > b.confidence) {
+                            best = Some(
+                                TemplateMatch::new(
+                                    template.clone(),
+                                    region.x + x,
+                                    region.y + y,
+                                    score,
+                                    scale,
+                                )
+                                .with_rotation(angle),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        result
+            .matches
+            .sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        if best.is_none() {
+            best = result.matches.first().cloned();
+        }
+        result.confidence_score = self.calculate_overall_confidence(&result.matches);
+        result.processing_time_ms = start_time.elapsed().as_millis();
+        Ok(TemplateTest {
+            template: wanted.to_string(),
+            result,
+            best,
+            scores,
+            threshold,
+            search_area: self.search_area_for(wanted),
+        })
+    }
+
+    /// Screen area `find_template` looks at for `wanted` as `(x, y, width, height)`,
+    /// covering every template of that name; `None` for unknown templates
+    pub fn search_area_for(&self, wanted: &str) -> Option<(u32, u32, u32, u32)> {
+        self.template_manager
+            .get_templates()
+            .iter()
+            .filter(|template| template_name_matches(&template.name, wanted))
+            .map(|template| {
+                let region = self.template_search_area(template);
+                (
+                    region.x,
+                    region.y,
+                    region.x + region.width,
+                    region.y + region.height,
+                )
+            })
+            .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+            .filter(|&(left, top, right, bottom)| right > left && bottom > top)
+            .map(|(left, top, right, bottom)| (left, top, right - left, bottom - top))
+    }
+
+    /// Best candidate of every template that has no match in `result` but
+    /// scored at least `floor`, i.e. just missed the confidence threshold
+    pub fn near_misses(
+        &self,
+        screenshot_gray: &GrayImage,
+        result: &DetectionResult,
+        floor: f32,
+    ) -> Vec<NearMiss> {
+        if floor >= self.config.confidence_threshold {
+            return Vec::new();
+        }
+        self.template_manager
+            .get_templates()
+            .iter()
+            .filter(|template| {
+                template.behavior.enabled
+                    && !result
+                        .matches
+                        .iter()
+                        .any(|found| found.template.name == template.name)
+            })
+            .filter_map(|template| {
+                let best = self
+                    .match_one_template(screenshot_gray, template, floor)
+                    .ok()?
+                    .into_iter()
+                    .filter(|candidate| candidate.confidence < self.config.confidence_threshold)
+                    .max_by(|a, b| a.confidence.total_cmp(&b.confidence))?;
+                Some(NearMiss {
+                    search_area: self.template_search_area(template),
+                    best,
+                })
+            })
+            .collect()
+    }
+
+    /// Where `template` is searched for on this screen, large enough for every scale
+    fn template_search_area(&self, template: &Template) -> SearchRegion {
+        let device_scale = self.device_scale(template);
+        let max_scale = self
+            .match_scales(template)
+            .into_iter()
+            .fold(device_scale, f32::max);
+        self.search_area(template, device_scale, max_scale)
+    }
+
+    /// Scales relative to the template file: the configured steps, times the
+    /// size difference between this screen and the one the template came from
+    fn match_scales(&self, template: &Template) -> Vec<f32> {
+        let device_scale = self.device_scale(template);
+        if self.config.enable_multiscale {
+            self.config
+                .scale_factors
+                .iter()
+                .map(|scale| scale * device_scale)
+                .collect()
+        } else {
+            vec![device_scale]
+        }
+    }
+
+    /// Rotations (degrees) correlation tries, upright when none are configured
+    fn match_angles(&self) -> Vec<f32> {
+        if self.config.rotation_angles.is_empty() {
+            vec![0.0]
+        } else {
+            self.config.rotation_angles.clone()
+        }
+    }
+
+    /// Match one template with its configured backend, keeping candidates
+    /// scoring at least `threshold`
+    fn match_one_template(
+        &self,
+        screenshot_gray: &GrayImage,
+        template: &Template,
+        threshold: f32,
+    ) -> Result<Vec<TemplateMatch>, String> {
+        match template.behavior.backend {
+            MatchBackend::Features => self.match_template_features(screenshot_gray, template),
+            MatchBackend::Correlation if self.config.use_match_patch_optimization => {
+                self.match_template_optimized(screenshot_gray, template, threshold)
+            }
+            MatchBackend::Correlation => {
+                self.match_template_in_region(screenshot_gray, template, threshold)
+            }
+        }
     }
 
     /// Match a single template within its search region
@@ -142,9 +580,10 @@ impl GameStateDetector {
         &self,
         screenshot_gray: &ImageBuffer<Luma<u8>, Vec<u8>>,
         template: &Template,
+        threshold: f32,
     ) -> Result<Vec<TemplateMatch>, String> {
         if self.config.debug_enabled {
-            println!(
+            log::info!(
                 "🔍 Loading template: {} (search region at {},{} {}x{})",
                 template.name,
                 template.search_region.x,
@@ -155,16 +594,16 @@ impl GameStateDetector {
         }
 
         // Load and crop template image to the region specified in filename
-        let template_gray = self.load_and_crop_template(template)?;
+        let template_image = self.template_image(template)?;
 
         let mut matches = Vec::new();
+        let scales = self.match_scales(template);
+        let angles = self.match_angles();
 
         // Crop screenshot to search region
-        let region = &template.search_region;
-        if region.x + region.width > self.screen_width
-            || region.y + region.height > self.screen_height
-        {
-            return Err("Search region exceeds screen bounds".to_string());
+        let region = self.template_search_area(template);
+        if !region.is_valid() {
+            return Err("Search region is outside the screen".to_string());
         }
 
         let cropped_view = image::imageops::crop_imm(
@@ -178,22 +617,19 @@ impl GameStateDetector {
         // Convert SubImage to ImageBuffer
         let cropped = cropped_view.to_image();
 
-        if self.config.enable_multiscale {
-            // Multi-scale matching
-            for &scale in &self.config.scale_factors {
-                if let Ok(scaled_matches) =
-                    self.match_at_scale(&cropped, &template_gray, template, scale, region)
-                {
-                    matches.extend(scaled_matches);
+        for &scale in &scales {
+            for &angle in &angles {
+                if let Ok(found) = self.match_at_scale(
+                    &cropped,
+                    &template_image,
+                    template,
+                    (scale, angle),
+                    &region,
+                    threshold,
+                ) {
+                    matches.extend(found);
                 }
             }
-        } else {
-            // Single-scale matching
-            if let Ok(single_matches) =
-                self.match_at_scale(&cropped, &template_gray, template, 1.0, region)
-            {
-                matches.extend(single_matches);
-            }
         }
 
         // Keep only the best matches
@@ -207,34 +643,240 @@ impl GameStateDetector {
         Ok(matches)
     }
 
-    /// Perform template matching at a specific scale
+    /// Match a single template by its corner features within its search region;
+    /// scales come from `MatchConfig` as for correlation, rotations do not apply
+    fn match_template_features(
+        &self,
+        screenshot_gray: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        template: &Template,
+    ) -> Result<Vec<TemplateMatch>, String> {
+        let template_image = self.template_image(template)?;
+        let scales = self.match_scales(template);
+
+        let region = self.template_search_area(template);
+        if !region.is_valid() {
+            return Err("Search region is outside the screen".to_string());
+        }
+        let cropped = image::imageops::crop_imm(
+            screenshot_gray,
+            region.x,
+            region.y,
+            region.width,
+            region.height,
+        )
+        .to_image();
+        let scene = FeatureSet::detect(&cropped, None);
+
+        let mut matches: Vec<TemplateMatch> = Vec::new();
+        for &scale in &scales {
+            let (gray, mask) = if (scale - 1.0).abs() < 0.001 {
+                (template_image.gray.clone(), template_image.mask.clone())
+            } else {
+                let width = ((template_image.gray.width() as f32 * scale) as u32).max(1);
+                let height = ((template_image.gray.height() as f32 * scale) as u32).max(1);
+                (
+                    image::imageops::resize(
+                        &template_image.gray,
+                        width,
+                        height,
+                        image::imageops::FilterType::Lanczos3,
+                    ),
+                    template_image.mask.as_ref().map(|mask| {
+                        image::imageops::resize(
+                            mask,
+                            width,
+                            height,
+                            image::imageops::FilterType::Nearest,
+                        )
+                    }),
+                )
+            };
+            let features = FeatureSet::detect(&gray, mask.as_ref());
+            let Some(found) = match_features(&features, &scene) else {
+                continue;
+            };
+            // Votes can place a partly visible template over the region edge
+            let x = region.x as i64 + found.x as i64;
+            let y = region.y as i64 + found.y as i64;
+            if x < 0 || y < 0 {
+                continue;
+            }
+            if self.config.debug_enabled {
+                log::info!(
+                    "🧭 '{}' features: {} of {} corners agree at scale {:.2}",
+                    template.name,
+                    found.inliers,
+                    features.len(),
+                    scale
+                );
+            }
+            matches.push(
+                TemplateMatch::new(
+                    template.clone(),
+                    x as u32,
+                    y as u32,
+                    found.confidence,
+                    scale,
+                )
+                .with_backend(MatchBackend::Features),
+            );
+        }
+
+        matches.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        matches.truncate(self.config.max_matches_per_template);
+        Ok(matches)
+    }
+
+    /// Screen size relative to the one `template` was captured on (1.0 when unknown)
+    fn device_scale(&self, template: &Template) -> f32 {
+        match template.source_resolution() {
+            Some((width, _)) if width > 0 && width != self.screen_width => {
+                self.screen_width as f32 / width as f32
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Where to look for `template` on this screen: its region moved to this
+    /// screen's resolution, grown so the template still fits at `max_scale`
+    fn search_area(&self, template: &Template, device_scale: f32, max_scale: f32) -> SearchRegion {
+        let region = &template.search_region;
+        // The filename region is in source pixels; the loaded one is already clipped
+        let (x, y, width, height) = match Template::extract_region_from_filename(&template.name) {
+            Some(source) if (device_scale - 1.0).abs() > 0.001 => {
+                let scaled = |v: u32| (v as f32 * device_scale).round() as u32;
+                (
+                    scaled(source.0),
+                    scaled(source.1),
+                    scaled(source.2),
+                    scaled(source.3),
+                )
+            }
+            _ => (region.x, region.y, region.width, region.height),
+        };
+
+        let grow = |start: u32, size: u32, needed: u32| {
+            let pad = needed.saturating_sub(size).div_ceil(2);
+            (start.saturating_sub(pad), size + pad * 2)
+        };
+        let (x, width) = grow(x, width, (template.width as f32 * max_scale).ceil() as u32);
+        let (y, height) = grow(
+            y,
+            height,
+            (template.height as f32 * max_scale).ceil() as u32,
+        );
+
+        let x = x.min(self.screen_width);
+        let y = y.min(self.screen_height);
+        SearchRegion::new(
+            x,
+            y,
+            width.min(self.screen_width - x),
+            height.min(self.screen_height - y),
+            region.name.clone(),
+        )
+    }
+
+    /// Perform template matching at a specific scale and rotation (degrees)
     fn match_at_scale(
         &self,
         cropped_screenshot: &ImageBuffer<Luma<u8>, Vec<u8>>,
-        template_gray: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        template_image: &TemplateImage,
         template: &Template,
-        scale: f32,
-        region: &super::region::SearchRegion,
+        (scale, angle): (f32, f32),
+        region: &SearchRegion,
+        threshold: f32,
     ) -> Result<Vec<TemplateMatch>, String> {
         let mut matches = Vec::new();
+        let Some(result) = self.score_map(cropped_screenshot, template_image, (scale, angle))
+        else {
+            return Ok(matches);
+        };
+
+        // Find matches above threshold
+        // Note: CrossCorrelationNormalized returns f32 values in range [-1, 1]
+        // where 1.0 is a perfect match
+        for (x, y, pixel) in result.enumerate_pixels() {
+            let confidence = pixel[0]; // Already f32 in range [-1, 1]
+
+            if confidence >= threshold {
+                // Convert coordinates back to screen space
+                let screen_x = region.x + x;
+                let screen_y = region.y + y;
+
+                let template_match =
+                    TemplateMatch::new(template.clone(), screen_x, screen_y, confidence, scale)
+                        .with_rotation(angle);
 
-        let scaled_template = if (scale - 1.0).abs() > 0.01 {
+                if template_match.is_within_bounds(self.screen_width, self.screen_height) {
+                    matches.push(template_match);
+                }
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// Correlation score of the template, scaled and turned, at every position of
+    /// `cropped_screenshot`; None when it does not fit or would take too long
+    fn score_map(
+        &self,
+        cropped_screenshot: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        template_image: &TemplateImage,
+        (scale, angle): (f32, f32),
+    ) -> Option<ImageBuffer<Luma<f32>, Vec<f32>>> {
+        let template_gray = &template_image.gray;
+
+        let (scaled_template, scaled_mask) = if (scale - 1.0).abs() > 0.01 {
             // Scale template if needed
             let new_width = (template_gray.width() as f32 * scale) as u32;
             let new_height = (template_gray.height() as f32 * scale) as u32;
 
             if new_width == 0 || new_height == 0 {
-                return Ok(matches);
+                return None;
             }
 
-            image::imageops::resize(
+            let scaled = image::imageops::resize(
                 template_gray,
                 new_width,
                 new_height,
                 image::imageops::FilterType::Lanczos3,
+            );
+            // Nearest keeps the mask strictly on/off
+            let mask = template_image.mask.as_ref().map(|mask| {
+                image::imageops::resize(
+                    mask,
+                    new_width,
+                    new_height,
+                    image::imageops::FilterType::Nearest,
+                )
+            });
+            (scaled, mask)
+        } else {
+            (template_gray.clone(), template_image.mask.clone())
+        };
+
+        // Turned templates leave empty corners, which the mask leaves out
+        let (scaled_template, scaled_mask) = if angle.abs() > 0.01 {
+            let theta = angle.to_radians();
+            let mask = scaled_mask.unwrap_or_else(|| {
+                ImageBuffer::from_pixel(
+                    scaled_template.width(),
+                    scaled_template.height(),
+                    Luma([MASK_ON]),
+                )
+            });
+            (
+                rotate_about_center(&scaled_template, theta, Interpolation::Bilinear, Luma([0])),
+                Some(rotate_about_center(
+                    &mask,
+                    theta,
+                    Interpolation::Nearest,
+                    Luma([0]),
+                )),
             )
         } else {
-            template_gray.clone()
+            (scaled_template, scaled_mask)
         };
 
         // Skip if scaled template is larger than search area
@@ -242,7 +884,7 @@ impl GameStateDetector {
             || scaled_template.height() > cropped_screenshot.height()
         {
             if self.config.debug_enabled {
-                println!(
+                log::warn!(
                     "⚠️ Skipping template - too large for region: {}x{} > {}x{}",
                     scaled_template.width(),
                     scaled_template.height(),
@@ -250,7 +892,7 @@ impl GameStateDetector {
                     cropped_screenshot.height()
                 );
             }
-            return Ok(matches);
+            return None;
         }
 
         // Additional safety check for very large templates that could cause hangs
@@ -259,7 +901,7 @@ impl GameStateDetector {
 
         if template_pixels > 1_000_000 || search_pixels > 5_000_000 {
             if self.config.debug_enabled {
-                println!(
+                log::warn!(
                     "⚠️ Skipping large template matching to prevent hang: template={}x{}, search={}x{}",
                     scaled_template.width(),
                     scaled_template.height(),
@@ -267,11 +909,11 @@ impl GameStateDetector {
                     cropped_screenshot.height()
                 );
             }
-            return Ok(matches);
+            return None;
         }
 
         if self.config.debug_enabled {
-            println!(
+            log::info!(
                 "🔍 Performing template matching: {}x{} in {}x{} region",
                 scaled_template.width(),
                 scaled_template.height(),
@@ -280,34 +922,24 @@ impl GameStateDetector {
             );
         }
 
-        // Perform template matching
-        let result = match_template(
-            cropped_screenshot,
-            &scaled_template,
-            MatchTemplateMethod::CrossCorrelationNormalized,
-        );
-
-        // Find matches above threshold
-        // Note: CrossCorrelationNormalized returns f32 values in range [-1, 1]
-        // where 1.0 is a perfect match
-        for (x, y, pixel) in result.enumerate_pixels() {
-            let confidence = pixel[0]; // Already f32 in range [-1, 1]
-
-            if confidence >= self.config.confidence_threshold {
-                // Convert coordinates back to screen space
-                let screen_x = region.x + x;
-                let screen_y = region.y + y;
-
-                let template_match =
-                    TemplateMatch::new(template.clone(), screen_x, screen_y, confidence, scale);
-
-                if template_match.is_within_bounds(self.screen_width, self.screen_height) {
-                    matches.push(template_match);
-                }
-            }
-        }
-
-        Ok(matches)
+        // Perform template matching; transparent template pixels do not count.
+        // With --features gpu-match the GPU scores the same way when available
+        let gpu_result =
+            match_template_gpu(cropped_screenshot, &scaled_template, scaled_mask.as_ref());
+        Some(match (gpu_result, &scaled_mask) {
+            (Some(scores), _) => scores,
+            (None, Some(mask)) => match_template_with_mask(
+                cropped_screenshot,
+                &scaled_template,
+                MatchTemplateMethod::CrossCorrelationNormalized,
+                mask,
+            ),
+            (None, None) => match_template(
+                cropped_screenshot,
+                &scaled_template,
+                MatchTemplateMethod::CrossCorrelationNormalized,
+            ),
+        })
     }
 
     /// Match template using optimized match-patch algorithm with early exit
@@ -316,9 +948,10 @@ impl GameStateDetector {
         &self,
         screenshot_gray: &ImageBuffer<Luma<u8>, Vec<u8>>,
         template: &Template,
+        threshold: f32,
     ) -> Result<Vec<TemplateMatch>, String> {
         if self.config.debug_enabled {
-            println!(
+            log::info!(
                 "🔍 Optimized matching: {} (search region at {},{} {}x{})",
                 template.name,
                 template.search_region.x,
@@ -329,7 +962,7 @@ impl GameStateDetector {
         }
 
         // Load and crop template image to the region specified in filename
-        let template_gray = self.load_and_crop_template(template)?;
+        let template_image = self.template_image(template)?;
 
         let mut matches = Vec::new();
 
@@ -354,13 +987,19 @@ impl GameStateDetector {
 
         // Use optimized match-patch matcher
         let matcher = PatchMatcher::new(
-            self.config.confidence_threshold,
+            threshold,
             self.config.max_matches_per_template,
             self.config.match_patch_search_margin,
             self.config.debug_enabled,
         );
 
-        let patch_matches = matcher.find_matches(&cropped, &template_gray, None, None);
+        let patch_matches = matcher.find_matches_masked(
+            &cropped,
+            &template_image.gray,
+            template_image.mask.as_ref(),
+            None,
+            None,
+        );
 
         for (local_x, local_y, correlation) in patch_matches {
             // Convert coordinates back to screen space
@@ -376,7 +1015,7 @@ impl GameStateDetector {
         }
 
         if self.config.debug_enabled && !matches.is_empty() {
-            println!(
+            log::info!(
                 "✅ Optimized matching found {} matches for template '{}'",
                 matches.len(),
                 template.name
@@ -424,31 +1063,40 @@ impl GameStateDetector {
 
     /// Log detection results for debugging
     fn log_detection_results(&self, result: &DetectionResult) {
-        println!("🔍 Detection Results:");
-        println!("  Processing time: {}ms", result.processing_time_ms);
-        println!("  Overall confidence: {:.3}", result.confidence_score);
-        println!("  Matches found: {}", result.matches.len());
+        log::info!("🔍 Detection Results:");
+        log::info!("  Processing time: {}ms", result.processing_time_ms);
+        log::info!("  Overall confidence: {:.3}", result.confidence_score);
+        log::info!("  Matches found: {}", result.matches.len());
 
         for (i, m) in result.matches.iter().take(5).enumerate() {
-            println!(
-                "    {}. {} at ({},{}) conf={:.3} scale={:.2}",
+            log::info!(
+                "    {}. {} at ({},{}) conf={:.3} scale={:.2} rotation={:.1}° backend={:?}",
                 i + 1,
                 m.template.name,
                 m.x,
                 m.y,
                 m.confidence,
-                m.scale_factor
+                m.scale_factor,
+                m.rotation_degrees,
+                m.backend
             );
         }
 
         if let Some(state) = &result.suggested_state {
-            println!("  Suggested state: {:?}", state);
+            log::info!("  Suggested state: {:?}", state);
         }
     }
 
     /// Update configuration
     pub fn update_config(&mut self, config: MatchConfig) {
         self.config = config;
+        self.template_manager
+            .apply_behaviors(&self.config.template_behaviors);
+    }
+
+    /// Device screen the detector was set up for
+    pub fn screen_size(&self) -> (u32, u32) {
+        (self.screen_width, self.screen_height)
     }
 
     /// Get current configuration
@@ -458,7 +1106,26 @@ impl GameStateDetector {
 
     /// Reload templates
     pub fn reload_templates(&mut self, directory: &str) -> Result<usize, String> {
-        self.template_manager.reload_templates(directory)
+        self.template_manager.clear();
+        self.template_images.clear();
+        self.load_templates(directory)
+    }
+
+    /// Clear and reload templates from several directories
+    pub fn reload_templates_from(&mut self, directories: &[String]) -> Result<usize, String> {
+        self.template_manager.clear();
+        self.template_images.clear();
+        self.load_templates_from(directories)
+    }
+
+    /// Names of the loaded templates analysis looks for (the enabled ones)
+    pub fn template_names(&self) -> Vec<String> {
+        self.template_manager
+            .get_templates()
+            .iter()
+            .filter(|t| t.behavior.enabled)
+            .map(|t| t.name.clone())
+            .collect()
     }
 
     /// Get template count
@@ -466,6 +1133,11 @@ impl GameStateDetector {
         self.template_manager.count()
     }
 
+    /// Number of templates held decoded in memory
+    pub fn preloaded_template_count(&self) -> usize {
+        self.template_images.len()
+    }
+
     /// Get screen dimensions
     pub fn get_screen_dimensions(&self) -> (u32, u32) {
         (self.screen_width, self.screen_height)
@@ -474,10 +1146,7 @@ impl GameStateDetector {
     /// Load template image and crop it to the region specified in the filename
     /// For files like "img-[300,1682,50,50].png", this extracts the 50x50 region
     /// at coordinates (300,1682) from the full screenshot stored in the file
-    fn load_and_crop_template(
-        &self,
-        template: &Template,
-    ) -> Result<ImageBuffer<Luma<u8>, Vec<u8>>, String> {
+    fn load_and_crop_template(&self, template: &Template) -> Result<TemplateImage, String> {
         // Load the full template image (which may be a full screenshot)
         let template_image = image::open(&template.path)
             .map_err(|e| format!("Failed to load template {}: {e}", template.path))?;
@@ -485,7 +1154,7 @@ impl GameStateDetector {
         // Check if filename contains region coordinates [x,y,width,height]
         if let Some(region_coords) = self.extract_template_region_from_filename(&template.name) {
             if self.config.debug_enabled {
-                println!(
+                log::info!(
                     "📐 Cropping template '{}' from full image ({}x{}) to region: [{},{},{},{}]",
                     template.name,
                     template_image.width(),
@@ -516,36 +1185,36 @@ impl GameStateDetector {
             let cropped =
                 image::imageops::crop_imm(&template_image, crop_x, crop_y, crop_w, crop_h);
             let cropped_dynamic = image::DynamicImage::ImageRgba8(cropped.to_image());
-            let cropped_gray = cropped_dynamic.to_luma8();
+            let cropped_template = TemplateImage::from_dynamic(&cropped_dynamic, &template.name)?;
 
             if self.config.debug_enabled {
-                println!(
+                log::info!(
                     "✂️ Template '{}' cropped to {}x{} (was {}x{})",
                     template.name,
-                    cropped_gray.width(),
-                    cropped_gray.height(),
+                    cropped_template.gray.width(),
+                    cropped_template.gray.height(),
                     template_image.width(),
                     template_image.height()
                 );
             }
 
-            Ok(cropped_gray)
+            Ok(cropped_template)
         } else {
             // No region specified in filename, use full image
-            let template_gray = template_image.to_luma8();
+            let full_template = TemplateImage::from_dynamic(&template_image, &template.name)?;
 
             // Still warn if template is very large
-            if (template_gray.width() > 500 || template_gray.height() > 500)
+            if (full_template.gray.width() > 500 || full_template.gray.height() > 500)
                 && self.config.debug_enabled
             {
-                println!(
+                log::warn!(
                     "⚠️ Large template detected: {}x{} - this may be slow!",
-                    template_gray.width(),
-                    template_gray.height()
+                    full_template.gray.width(),
+                    full_template.gray.height()
                 );
             }
 
-            Ok(template_gray)
+            Ok(full_template)
         }
     }
 
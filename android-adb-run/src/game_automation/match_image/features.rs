@@ -0,0 +1,210 @@
+//! Keypoint matching backend (ORB-style: FAST corners + BRIEF descriptors)
+//!
+//! Correlation compares every pixel, so an icon on an animated background or
+//! drawn at another size scores poorly. Here only distinctive corners of the
+//! template are described and looked up in the screenshot; the template is
+//! found where enough of them agree on the same position.
+
+use image::{GrayImage, Luma};
+use imageproc::corners::corners_fast9;
+use imageproc::filter::gaussian_blur_f32;
+use std::sync::OnceLock;
+
+/// FAST corner threshold (gray levels around the ring vs the center)
+pub const FAST_THRESHOLD: u8 = 20;
+/// Strongest corners kept per image
+pub const MAX_KEYPOINTS: usize = 500;
+/// Template corners that must agree on the position
+pub const MIN_INLIERS: usize = 6;
+/// Share of template corners that must be found for a match
+pub const MIN_INLIER_RATIO: f32 = 0.25;
+
+/// Descriptor sample pairs lie within this many pixels of the corner
+const PATCH_RADIUS: i32 = 12;
+const DESCRIPTOR_WORDS: usize = 4;
+const DESCRIPTOR_BITS: usize = DESCRIPTOR_WORDS * 64;
+/// Descriptors further apart (in differing bits) never match
+const MAX_HAMMING: u32 = 60;
+/// Best candidate must beat the second best by this ratio (Lowe's ratio test)
+const RATIO_TEST: f32 = 0.8;
+/// Corners closer than this to a stronger one are dropped
+const MIN_CORNER_DISTANCE: u32 = 3;
+/// Matches within this many pixels of the agreed position count as inliers
+const INLIER_TOLERANCE: i32 = 3;
+const BLUR_SIGMA: f32 = 2.0;
+
+type Descriptor = [u64; DESCRIPTOR_WORDS];
+
+#[derive(Debug, Clone)]
+pub struct Keypoint {
+    pub x: u32,
+    pub y: u32,
+    descriptor: Descriptor,
+}
+
+/// Described corners of one image
+#[derive(Debug, Clone)]
+pub struct FeatureSet {
+    pub keypoints: Vec<Keypoint>,
+}
+
+/// Where a template's corners were found in a scene
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureMatch {
+    /// Template top-left in scene coordinates
+    pub x: i32,
+    pub y: i32,
+    /// Share of template corners that agree (0.0 - 1.0)
+    pub confidence: f32,
+    pub inliers: usize,
+}
+
+impl FeatureSet {
+    /// Detect and describe corners; with a `mask`, corners on pixels where it
+    /// is 0 are skipped
+    pub fn detect(gray: &GrayImage, mask: Option<&GrayImage>) -> Self {
+        let (width, height) = gray.dimensions();
+        let radius = PATCH_RADIUS as u32;
+        if width <= radius * 2 || height <= radius * 2 {
+            return Self {
+                keypoints: Vec::new(),
+            };
+        }
+
+        let mut corners: Vec<_> = corners_fast9(gray, FAST_THRESHOLD)
+            .into_iter()
+            .filter(|c| {
+                c.x >= radius && c.y >= radius && c.x < width - radius && c.y < height - radius
+            })
+            .filter(|c| mask.is_none_or(|m| m.get_pixel(c.x, c.y)[0] != 0))
+            .collect();
+        corners.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        let mut kept: Vec<(u32, u32)> = Vec::new();
+        for corner in corners {
+            if kept.len() >= MAX_KEYPOINTS {
+                break;
+            }
+            let crowded = kept.iter().any(|&(x, y)| {
+                x.abs_diff(corner.x) < MIN_CORNER_DISTANCE
+                    && y.abs_diff(corner.y) < MIN_CORNER_DISTANCE
+            });
+            if !crowded {
+                kept.push((corner.x, corner.y));
+            }
+        }
+
+        // Descriptors compare smoothed pixels so single-pixel noise does not flip bits
+        let smooth = gaussian_blur_f32(gray, BLUR_SIGMA);
+        let keypoints = kept
+            .into_iter()
+            .map(|(x, y)| Keypoint {
+                x,
+                y,
+                descriptor: describe(&smooth, x, y),
+            })
+            .collect();
+        Self { keypoints }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keypoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keypoints.is_empty()
+    }
+}
+
+/// Find `template` in `scene`: every template corner votes for the template
+/// position its best descriptor match implies, and the most agreed position wins
+pub fn match_features(template: &FeatureSet, scene: &FeatureSet) -> Option<FeatureMatch> {
+    if template.len() < MIN_INLIERS || scene.len() < MIN_INLIERS {
+        return None;
+    }
+
+    let votes: Vec<(i32, i32)> = template
+        .keypoints
+        .iter()
+        .filter_map(|kp| {
+            let mut best = (u32::MAX, None);
+            let mut second = u32::MAX;
+            for candidate in &scene.keypoints {
+                let distance = hamming(&kp.descriptor, &candidate.descriptor);
+                if distance < best.0 {
+                    second = best.0;
+                    best = (distance, Some(candidate));
+                } else if distance < second {
+                    second = distance;
+                }
+            }
+            let (distance, candidate) = (best.0, best.1?);
+            let distinct = (distance as f32) < RATIO_TEST * second as f32;
+            (distance <= MAX_HAMMING && distinct).then(|| {
+                (
+                    candidate.x as i32 - kp.x as i32,
+                    candidate.y as i32 - kp.y as i32,
+                )
+            })
+        })
+        .collect();
+
+    // The vote with the most others nearby is the template position
+    let (position, inliers) = votes
+        .iter()
+        .map(|&(x, y)| {
+            let support = votes
+                .iter()
+                .filter(|&&(vx, vy)| {
+                    (vx - x).abs() <= INLIER_TOLERANCE && (vy - y).abs() <= INLIER_TOLERANCE
+                })
+                .count();
+            ((x, y), support)
+        })
+        .max_by_key(|&(_, support)| support)?;
+
+    let confidence = (inliers as f32 / template.len() as f32).min(1.0);
+    (inliers >= MIN_INLIERS && confidence >= MIN_INLIER_RATIO).then_some(FeatureMatch {
+        x: position.0,
+        y: position.1,
+        confidence,
+        inliers,
+    })
+}
+
+/// BRIEF descriptor: one bit per sample pair, set when the first pixel is darker
+fn describe(smooth: &GrayImage, x: u32, y: u32) -> Descriptor {
+    let pixel = |dx: i32, dy: i32| -> u8 {
+        let Luma([v]) = *smooth.get_pixel((x as i32 + dx) as u32, (y as i32 + dy) as u32);
+        v
+    };
+    let mut descriptor = [0u64; DESCRIPTOR_WORDS];
+    for (bit, &((ax, ay), (bx, by))) in sample_pairs().iter().enumerate() {
+        if pixel(ax, ay) < pixel(bx, by) {
+            descriptor[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+    descriptor
+}
+
+fn hamming(a: &Descriptor, b: &Descriptor) -> u32 {
+    a.iter().zip(b).map(|(a, b)| (a ^ b).count_ones()).sum()
+}
+
+type SamplePair = ((i32, i32), (i32, i32));
+
+/// Fixed pseudo-random sample pairs, the same for every image
+fn sample_pairs() -> &'static [SamplePair; DESCRIPTOR_BITS] {
+    static PAIRS: OnceLock<[SamplePair; DESCRIPTOR_BITS]> = OnceLock::new();
+    PAIRS.get_or_init(|| {
+        let mut state: u32 = 0x9E37_79B9;
+        let mut next = move || {
+            // xorshift32
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state % (PATCH_RADIUS as u32 * 2 + 1)) as i32 - PATCH_RADIUS
+        };
+        std::array::from_fn(|_| ((next(), next()), (next(), next())))
+    })
+}
@@ -0,0 +1,59 @@
+//! Cheap screenshot comparison, used to skip template matching while the
+//! game screen stands still
+
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage};
+
+/// Thumbnail edge length; each cell averages a block of the screenshot
+pub const FINGERPRINT_SIZE: u32 = 64;
+
+/// Gray levels (0-255) a cell may drift before it counts as changed,
+/// absorbing PNG noise and tiny animations
+pub const CELL_TOLERANCE: u8 = 12;
+
+/// Grayscale thumbnail of a screenshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameFingerprint {
+    cells: Vec<u8>,
+}
+
+impl FrameFingerprint {
+    pub fn from_image(image: &DynamicImage) -> Self {
+        Self::from_gray(&image.to_luma8())
+    }
+
+    pub fn from_gray(gray: &GrayImage) -> Self {
+        let thumbnail = image::imageops::resize(
+            gray,
+            FINGERPRINT_SIZE,
+            FINGERPRINT_SIZE,
+            FilterType::Triangle,
+        );
+        Self {
+            cells: thumbnail.into_raw(),
+        }
+    }
+
+    /// Decode PNG (or any supported format) bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let image = image::load_from_memory(bytes)
+            .map_err(|e| format!("Failed to load screenshot: {e}"))?;
+        Ok(Self::from_image(&image))
+    }
+
+    /// Fraction (0.0 - 1.0) of cells that differ by more than `CELL_TOLERANCE`
+    pub fn changed_fraction(&self, other: &Self) -> f64 {
+        let changed = self
+            .cells
+            .iter()
+            .zip(&other.cells)
+            .filter(|(a, b)| a.abs_diff(**b) > CELL_TOLERANCE)
+            .count();
+        changed as f64 / self.cells.len().max(1) as f64
+    }
+
+    /// True when at most `threshold` of the cells changed
+    pub fn is_unchanged(&self, other: &Self, threshold: f64) -> bool {
+        self.changed_fraction(other) <= threshold
+    }
+}
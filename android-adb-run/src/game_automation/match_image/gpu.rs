@@ -0,0 +1,322 @@
+//! GPU template correlation (wgpu compute shader), enabled with `--features gpu-match`
+//!
+//! Scores match imageproc's `CrossCorrelationNormalized`, so results are
+//! interchangeable with the CPU path. Without the feature, or on machines
+//! without a usable adapter, `match_template_gpu` returns `None` and the
+//! detector correlates on the CPU as before.
+
+use image::{GrayImage, ImageBuffer, Luma};
+
+/// Correlation scores as produced by `imageproc::template_matching::match_template`
+pub type ScoreImage = ImageBuffer<Luma<f32>, Vec<f32>>;
+
+/// Normalized cross-correlation of `template` over every position in `image`;
+/// pixels where `mask` is 0 are left out. `None` means use the CPU instead
+#[cfg(feature = "gpu-match")]
+pub fn match_template_gpu(
+    image: &GrayImage,
+    template: &GrayImage,
+    mask: Option<&GrayImage>,
+) -> Option<ScoreImage> {
+    let matcher = GpuMatcher::shared()?;
+    match matcher.correlate(image, template, mask) {
+        Ok(scores) => Some(scores),
+        Err(e) => {
+            log::warn!("⚠️ GPU matching failed, using CPU: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "gpu-match"))]
+pub fn match_template_gpu(
+    _image: &GrayImage,
+    _template: &GrayImage,
+    _mask: Option<&GrayImage>,
+) -> Option<ScoreImage> {
+    None
+}
+
+/// True when matching runs on a GPU adapter
+pub fn gpu_available() -> bool {
+    #[cfg(feature = "gpu-match")]
+    {
+        GpuMatcher::shared().is_some()
+    }
+    #[cfg(not(feature = "gpu-match"))]
+    {
+        false
+    }
+}
+
+#[cfg(feature = "gpu-match")]
+const SHADER: &str = r#"
+struct Params {
+    image_width: u32,
+    image_height: u32,
+    template_width: u32,
+    template_height: u32,
+    out_width: u32,
+    out_height: u32,
+    template_norm: f32,
+    _pad: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+// One gray pixel per u32
+@group(0) @binding(1) var<storage, read> screen: array<u32>;
+// Template pixels already multiplied by their weight
+@group(0) @binding(2) var<storage, read> tmpl: array<f32>;
+// 1.0 for compared pixels, 0.0 for masked ones
+@group(0) @binding(3) var<storage, read> weights: array<f32>;
+@group(0) @binding(4) var<storage, read_write> scores: array<f32>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.out_width || id.y >= params.out_height) {
+        return;
+    }
+    var product = 0.0;
+    var energy = 0.0;
+    for (var ty = 0u; ty < params.template_height; ty++) {
+        let row = (id.y + ty) * params.image_width + id.x;
+        let template_row = ty * params.template_width;
+        for (var tx = 0u; tx < params.template_width; tx++) {
+            let value = f32(screen[row + tx]);
+            product += value * tmpl[template_row + tx];
+            energy += value * value * weights[template_row + tx];
+        }
+    }
+    let denominator = sqrt(energy) * params.template_norm;
+    var score = 0.0;
+    if (denominator > 0.0) {
+        score = product / denominator;
+    }
+    scores[id.y * params.out_width + id.x] = score;
+}
+"#;
+
+#[cfg(feature = "gpu-match")]
+const WORKGROUP_SIZE: u32 = 8;
+
+#[cfg(feature = "gpu-match")]
+struct GpuMatcher {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+#[cfg(feature = "gpu-match")]
+impl GpuMatcher {
+    /// Adapter chosen on first use; `None` (logged once) when there is none
+    fn shared() -> Option<&'static GpuMatcher> {
+        static MATCHER: std::sync::OnceLock<Option<GpuMatcher>> = std::sync::OnceLock::new();
+        MATCHER
+            .get_or_init(|| match pollster::block_on(Self::new()) {
+                Ok(matcher) => Some(matcher),
+                Err(e) => {
+                    log::warn!("⚠️ {e}, template matching stays on the CPU");
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    async fn new() -> Result<Self, String> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+            .ok_or("No GPU adapter found")?;
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("template-matching"),
+                    required_features: wgpu::Features::empty(),
+                    required_limits: wgpu::Limits::downlevel_defaults(),
+                    memory_hints: wgpu::MemoryHints::Performance,
+                },
+                None,
+            )
+            .await
+            .map_err(|e| format!("GPU device request failed: {e}"))?;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("ccorr-normed"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("ccorr-normed"),
+            layout: None,
+            module: &shader,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        log::info!("🎮 GPU template matching on {}", adapter.get_info().name);
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+        })
+    }
+
+    fn correlate(
+        &self,
+        image: &GrayImage,
+        template: &GrayImage,
+        mask: Option<&GrayImage>,
+    ) -> Result<ScoreImage, String> {
+        use wgpu::util::DeviceExt;
+
+        let (image_width, image_height) = image.dimensions();
+        let (template_width, template_height) = template.dimensions();
+        if template_width > image_width || template_height > image_height {
+            return Err("template larger than image".to_string());
+        }
+        let out_width = image_width - template_width + 1;
+        let out_height = image_height - template_height + 1;
+
+        let weights: Vec<f32> = match mask {
+            Some(mask) => mask
+                .as_raw()
+                .iter()
+                .map(|&m| if m != 0 { 1.0 } else { 0.0 })
+                .collect(),
+            None => vec![1.0; template.as_raw().len()],
+        };
+        let weighted: Vec<f32> = template
+            .as_raw()
+            .iter()
+            .zip(&weights)
+            .map(|(&t, &w)| t as f32 * w)
+            .collect();
+        let template_norm = weighted.iter().map(|t| t * t).sum::<f32>().sqrt();
+
+        let params: Vec<u8> = [
+            image_width,
+            image_height,
+            template_width,
+            template_height,
+            out_width,
+            out_height,
+            template_norm.to_bits(),
+            0,
+        ]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect();
+        let screen: Vec<u8> = image
+            .as_raw()
+            .iter()
+            .flat_map(|&v| (v as u32).to_le_bytes())
+            .collect();
+        let f32_bytes =
+            |values: &[f32]| -> Vec<u8> { values.iter().flat_map(|v| v.to_le_bytes()).collect() };
+
+        let storage = |label, contents: &[u8]| {
+            self.device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some(label),
+                    contents,
+                    usage: wgpu::BufferUsages::STORAGE,
+                })
+        };
+        let params_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("params"),
+                contents: &params,
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+        let screen_buffer = storage("screen", &screen);
+        let template_buffer = storage("template", &f32_bytes(&weighted));
+        let weights_buffer = storage("weights", &f32_bytes(&weights));
+
+        let scores_size = (out_width as u64) * (out_height as u64) * 4;
+        let scores_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("scores"),
+            size: scores_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size: scores_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: screen_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: template_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: weights_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: scores_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: None,
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(
+                out_width.div_ceil(WORKGROUP_SIZE),
+                out_height.div_ceil(WORKGROUP_SIZE),
+                1,
+            );
+        }
+        encoder.copy_buffer_to_buffer(&scores_buffer, 0, &readback, 0, scores_size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|e| format!("GPU readback lost: {e}"))?
+            .map_err(|e| format!("GPU readback failed: {e}"))?;
+
+        let scores: Vec<f32> = slice
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+        readback.unmap();
+
+        ImageBuffer::from_raw(out_width, out_height, scores)
+            .ok_or_else(|| "GPU returned a short score buffer".to_string())
+    }
+}
@@ -0,0 +1,35 @@
+//! Transparency masks for templates
+//!
+//! Template PNGs with an alpha channel only compare their opaque pixels, so a
+//! button cut out of its background matches whatever is behind it.
+
+use image::{DynamicImage, GrayImage, Luma};
+
+/// Pixels at least this opaque are compared; the rest are ignored
+pub const MASK_ALPHA_THRESHOLD: u8 = 128;
+
+/// Mask value of a compared pixel; ignored pixels are 0
+pub const MASK_ON: u8 = 255;
+
+/// Mask from the alpha channel of `image`, or `None` when every pixel is opaque
+pub fn alpha_mask(image: &DynamicImage) -> Option<GrayImage> {
+    if !image.color().has_alpha() {
+        return None;
+    }
+    let rgba = image.to_rgba8();
+    if rgba.pixels().all(|p| p[3] >= MASK_ALPHA_THRESHOLD) {
+        return None;
+    }
+    Some(GrayImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        if rgba.get_pixel(x, y)[3] >= MASK_ALPHA_THRESHOLD {
+            Luma([MASK_ON])
+        } else {
+            Luma([0])
+        }
+    }))
+}
+
+/// Number of pixels a mask compares
+pub fn masked_pixel_count(mask: &GrayImage) -> usize {
+    mask.as_raw().iter().filter(|&&m| m != 0).count()
+}
@@ -42,6 +42,24 @@ impl PatchMatcher {
         expected_x: Option<u32>,
         expected_y: Option<u32>,
     ) -> Vec<(u32, u32, f32)> {
+        self.find_matches_masked(image, template, None, expected_x, expected_y)
+    }
+
+    /// Like `find_matches`, but template pixels where `mask` is 0 are ignored
+    pub fn find_matches_masked(
+        &self,
+        image: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        template: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        mask: Option<&ImageBuffer<Luma<u8>, Vec<u8>>>,
+        expected_x: Option<u32>,
+        expected_y: Option<u32>,
+    ) -> Vec<(u32, u32, f32)> {
+        if let Some(mask) = mask
+            && mask.dimensions() != template.dimensions()
+        {
+            log::warn!("⚠️ Mask size does not match template, ignoring mask");
+            return self.find_matches_masked(image, template, None, expected_x, expected_y);
+        }
         let image_width = image.width();
         let image_height = image.height();
         let template_width = template.width();
@@ -49,9 +67,12 @@ impl PatchMatcher {
 
         if template_width > image_width || template_height > image_height {
             if self.debug {
-                println!(
+                log::warn!(
                     "⚠️ Template {}x{} larger than image {}x{}",
-                    template_width, template_height, image_width, image_height
+                    template_width,
+                    template_height,
+                    image_width,
+                    image_height
                 );
             }
             return Vec::new();
@@ -70,9 +91,12 @@ impl PatchMatcher {
                 .min(image_height.saturating_sub(template_height));
 
             if self.debug {
-                println!(
+                log::info!(
                     "🔍 Localized search: x:[{},{}] y:[{},{}]",
-                    x_min, x_max, y_min, y_max
+                    x_min,
+                    x_max,
+                    y_min,
+                    y_max
                 );
             }
 
@@ -80,7 +104,7 @@ impl PatchMatcher {
         } else {
             // Full image search
             if self.debug {
-                println!("🔍 Full image search");
+                log::info!("🔍 Full image search");
             }
             (
                 0,
@@ -100,13 +124,11 @@ impl PatchMatcher {
 
                 if self.debug && progress_idx.is_multiple_of(report_interval) {
                     let progress_pct = progress_idx as f32 / total_positions as f32 * 100.0;
-                    print!("\r⏳ Search progress: {}%", progress_pct);
-                    use std::io::{self, Write};
-                    let _ = io::stdout().flush();
+                    log::debug!("⏳ Search progress: {:.0}%", progress_pct);
                 }
 
                 // Extract region from image
-                let correlation = self.calculate_correlation_at(image, template, x, y);
+                let correlation = self.calculate_correlation_at(image, template, mask, x, y);
 
                 if correlation >= self.threshold {
                     matches.push((x, y, correlation));
@@ -115,7 +137,7 @@ impl PatchMatcher {
         }
 
         if self.debug {
-            println!("\r⏳ Search progress: 100%");
+            log::debug!("⏳ Search progress: 100%");
         }
 
         // Sort by correlation descending and keep only top matches
@@ -123,7 +145,7 @@ impl PatchMatcher {
         matches.truncate(self.max_matches);
 
         if self.debug {
-            println!("✅ Found {} matches", matches.len());
+            log::info!("✅ Found {} matches", matches.len());
         }
 
         matches
@@ -136,6 +158,7 @@ impl PatchMatcher {
         &self,
         image: &ImageBuffer<Luma<u8>, Vec<u8>>,
         template: &ImageBuffer<Luma<u8>, Vec<u8>>,
+        mask: Option<&ImageBuffer<Luma<u8>, Vec<u8>>>,
         x: u32,
         y: u32,
     ) -> f32 {
@@ -152,6 +175,7 @@ impl PatchMatcher {
 
         let template_data = template.as_raw();
         let image_data = image.as_raw();
+        let mask_data = mask.map(|m| m.as_raw());
 
         let pixels_to_check = (template_width * template_height) as usize;
         let check_interval = (pixels_to_check / 10).max(1); // Check early exit every 10% of pixels
@@ -164,6 +188,9 @@ impl PatchMatcher {
                 if template_idx >= template_data.len() || image_idx >= image_data.len() {
                     return 0.0;
                 }
+                if mask_data.is_some_and(|m| m[template_idx] == 0) {
+                    continue;
+                }
 
                 let template_val = template_data[template_idx] as f64;
                 let image_val = image_data[image_idx] as f64;
@@ -285,4 +312,32 @@ mod tests {
         // Should return at most 3 matches
         assert!(matches.len() <= 3);
     }
+
+    #[test]
+    fn test_mask_ignores_transparent_border() {
+        // 6x6 template: a 4x4 center on a border that differs from the image
+        let template = ImageBuffer::from_fn(6, 6, |x, y| {
+            let inside = (1..5).contains(&x) && (1..5).contains(&y);
+            Luma([if inside { 200 } else { 255 }])
+        });
+        let mask = ImageBuffer::from_fn(6, 6, |x, y| {
+            let inside = (1..5).contains(&x) && (1..5).contains(&y);
+            Luma([if inside { 255 } else { 0 }])
+        });
+        let image = ImageBuffer::from_fn(6, 6, |x, y| {
+            let inside = (1..5).contains(&x) && (1..5).contains(&y);
+            Luma([if inside { 200 } else { 0 }])
+        });
+
+        let matcher = PatchMatcher::new(0.9, 1, 0, false);
+        assert!(
+            matcher
+                .find_matches(&image, &template, None, None)
+                .is_empty()
+        );
+
+        let masked = matcher.find_matches_masked(&image, &template, Some(&mask), None, None);
+        assert_eq!(masked.len(), 1);
+        assert!(masked[0].2 > 0.99);
+    }
 }
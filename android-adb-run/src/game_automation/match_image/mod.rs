@@ -5,7 +5,12 @@
 
 pub mod config;
 pub mod detector;
+pub mod features;
+pub mod frame_diff;
+pub mod gpu;
+pub mod mask;
 pub mod match_patch;
+pub mod ocr;
 pub mod region;
 pub mod template;
 
@@ -14,7 +19,15 @@ mod tests;
 
 // Re-export main types and functions
 pub use config::{MatchConfig, create_default_config, create_game_object_config, create_ui_config};
-pub use detector::{DetectionResult, GameStateDetector};
+pub use detector::{DetectionResult, GameStateDetector, NearMiss, ScoreStats, TemplateTest};
+pub use features::{FeatureMatch, FeatureSet, match_features};
+pub use frame_diff::FrameFingerprint;
+pub use gpu::gpu_available;
+pub use mask::alpha_mask;
 pub use match_patch::PatchMatcher;
+pub use ocr::{OcrDetector, TextRegion, find_text, shared_detector};
 pub use region::{RegionManager, SearchRegion};
-pub use template::{Template, TemplateCategory, TemplateManager, TemplateMatch};
+pub use template::{
+    ActionHistory, MatchBackend, Template, TemplateAction, TemplateBehavior, TemplateCategory,
+    TemplateManager, TemplateMatch,
+};
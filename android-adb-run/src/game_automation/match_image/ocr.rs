@@ -0,0 +1,164 @@
+//! On-screen text recognition for branching on values such as counters or labels
+//!
+//! Recognition uses the pure-Rust `ocrs` engine and is only compiled with the
+//! `ocr` feature (`cargo build --features ocr`). The model files
+//! `text-detection.rten` and `text-recognition.rten` are loaded from
+//! `OCR_MODELS_DIR`; download them from the ocrs project releases.
+//! Without the feature, `OcrDetector::new` returns an error and the rest of
+//! the automation keeps working.
+
+use image::RgbImage;
+use std::sync::OnceLock;
+
+/// Directory holding the OCR model files
+pub const OCR_MODELS_DIR: &str = "ocr_models";
+pub const DETECTION_MODEL_FILE: &str = "text-detection.rten";
+pub const RECOGNITION_MODEL_FILE: &str = "text-recognition.rten";
+
+/// A line of recognized text and where it is on screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRegion {
+    pub text: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TextRegion {
+    pub fn new(text: String, x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            text,
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Center point, for tapping on the text
+    pub fn center(&self) -> (u32, u32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    /// Case-insensitive substring check
+    pub fn contains(&self, needle: &str) -> bool {
+        self.text.to_lowercase().contains(&needle.to_lowercase())
+    }
+
+    /// First number in the text, ignoring thousands separators (`"Gold: 12,500"` -> 12500)
+    pub fn number(&self) -> Option<i64> {
+        let start = self.text.find(|c: char| c.is_ascii_digit())?;
+        let negative = self.text[..start].ends_with('-');
+        let digits: String = self.text[start..]
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == ',' || *c == '.' || *c == '\'')
+            .filter(char::is_ascii_digit)
+            .collect();
+        let value = digits.parse::<i64>().ok()?;
+        Some(if negative { -value } else { value })
+    }
+}
+
+/// First region containing `needle` (case-insensitive)
+pub fn find_text<'a>(regions: &'a [TextRegion], needle: &str) -> Option<&'a TextRegion> {
+    regions.iter().find(|region| region.contains(needle))
+}
+
+/// Text recognizer; loading the models is slow, so create it once and reuse it
+pub struct OcrDetector {
+    #[cfg(feature = "ocr")]
+    engine: ocrs::OcrEngine,
+}
+
+impl OcrDetector {
+    /// Load the detection and recognition models from `models_dir`
+    #[cfg(feature = "ocr")]
+    pub fn new(models_dir: &str) -> Result<Self, String> {
+        use ocrs::{OcrEngine, OcrEngineParams};
+        use rten::Model;
+
+        let dir = std::path::Path::new(models_dir);
+        let load = |file: &str| {
+            let path = dir.join(file);
+            Model::load_file(&path)
+                .map_err(|e| format!("Failed to load OCR model {}: {}", path.display(), e))
+        };
+        let engine = OcrEngine::new(OcrEngineParams {
+            detection_model: Some(load(DETECTION_MODEL_FILE)?),
+            recognition_model: Some(load(RECOGNITION_MODEL_FILE)?),
+            ..Default::default()
+        })
+        .map_err(|e| format!("Failed to create OCR engine: {}", e))?;
+        Ok(Self { engine })
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub fn new(_models_dir: &str) -> Result<Self, String> {
+        Err("OCR support not compiled in (rebuild with --features ocr)".to_string())
+    }
+
+    /// Recognize every text line in `image`
+    #[cfg(feature = "ocr")]
+    pub fn recognize(&self, image: &RgbImage) -> Result<Vec<TextRegion>, String> {
+        use ocrs::{ImageSource, TextItem};
+
+        let source = ImageSource::from_bytes(image.as_raw(), image.dimensions())
+            .map_err(|e| format!("Invalid image for OCR: {}", e))?;
+        let input = self
+            .engine
+            .prepare_input(source)
+            .map_err(|e| format!("OCR preprocessing failed: {}", e))?;
+        let words = self
+            .engine
+            .detect_words(&input)
+            .map_err(|e| format!("OCR text detection failed: {}", e))?;
+        let lines = self.engine.find_text_lines(&input, &words);
+        let texts = self
+            .engine
+            .recognize_text(&input, &lines)
+            .map_err(|e| format!("OCR recognition failed: {}", e))?;
+
+        Ok(texts
+            .iter()
+            .flatten()
+            .filter_map(|line| {
+                let text = line.to_string().trim().to_string();
+                if text.is_empty() {
+                    return None;
+                }
+                let rect = line.bounding_rect();
+                Some(TextRegion::new(
+                    text,
+                    rect.left().max(0.0) as u32,
+                    rect.top().max(0.0) as u32,
+                    rect.width().max(0.0) as u32,
+                    rect.height().max(0.0) as u32,
+                ))
+            })
+            .collect())
+    }
+
+    #[cfg(not(feature = "ocr"))]
+    pub fn recognize(&self, _image: &RgbImage) -> Result<Vec<TextRegion>, String> {
+        Err("OCR support not compiled in (rebuild with --features ocr)".to_string())
+    }
+
+    /// Decode a PNG/JPEG screenshot and recognize its text
+    pub fn recognize_screenshot(&self, screenshot_bytes: &[u8]) -> Result<Vec<TextRegion>, String> {
+        let image = image::load_from_memory(screenshot_bytes)
+            .map_err(|e| format!("Failed to decode screenshot: {}", e))?
+            .to_rgb8();
+        self.recognize(&image)
+    }
+}
+
+static SHARED_DETECTOR: OnceLock<Result<OcrDetector, String>> = OnceLock::new();
+
+/// Process-wide detector using `OCR_MODELS_DIR`, loaded on first use
+pub fn shared_detector() -> Result<&'static OcrDetector, String> {
+    SHARED_DETECTOR
+        .get_or_init(|| OcrDetector::new(OCR_MODELS_DIR))
+        .as_ref()
+        .map_err(Clone::clone)
+}
@@ -1,7 +1,10 @@
 //! Template management and matching functionality
 
 use super::region::SearchRegion;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum TemplateCategory {
@@ -13,6 +16,119 @@ pub enum TemplateCategory {
     Unknown,
 }
 
+impl TemplateCategory {
+    /// Categories offered when creating a template
+    pub const ALL: [TemplateCategory; 6] = [
+        TemplateCategory::Button,
+        TemplateCategory::Icon,
+        TemplateCategory::GameObject,
+        TemplateCategory::UI,
+        TemplateCategory::Text,
+        TemplateCategory::Unknown,
+    ];
+
+    /// Name keyword that makes `Template` pick this category
+    pub fn keyword(&self) -> Option<&'static str> {
+        match self {
+            TemplateCategory::Button => Some("button"),
+            TemplateCategory::Icon => Some("icon"),
+            TemplateCategory::GameObject => Some("object"),
+            TemplateCategory::UI => Some("ui"),
+            TemplateCategory::Text => Some("text"),
+            TemplateCategory::Unknown => None,
+        }
+    }
+
+    /// `name`, suffixed with this category's keyword unless it already implies it
+    pub fn label_for(&self, name: &str) -> String {
+        match self.keyword() {
+            Some(keyword) if Template::determine_category(name) != *self => {
+                format!("{}_{}", name, keyword)
+            }
+            _ => name.to_string(),
+        }
+    }
+}
+
+/// What automation does when a template is detected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateAction {
+    #[default]
+    Tap,
+    /// Swipe from the match by (`swipe_dx`, `swipe_dy`)
+    Swipe,
+    /// Run the `[sequences.<name>]` named by `sequence`
+    Sequence,
+    /// Detect only, never act
+    None,
+}
+
+/// Engine that searched for a template
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchBackend {
+    /// Pixel correlation (`MatchConfig` scales and rotations apply)
+    #[default]
+    Correlation,
+    /// Keypoint features, for icons on animated backgrounds
+    Features,
+}
+
+/// Per-template behavior, from `[matching.templates.<name>]` in `automation.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateBehavior {
+    /// False keeps the template loaded but never looks for it
+    pub enabled: bool,
+    pub action: TemplateAction,
+    /// Added to the match center before acting
+    pub offset_x: i32,
+    pub offset_y: i32,
+    pub swipe_dx: i32,
+    pub swipe_dy: i32,
+    pub swipe_duration_ms: u32,
+    /// Seconds before this template may act again
+    pub cooldown_secs: u64,
+    /// Higher priority matches act first, whatever their confidence
+    pub priority: i32,
+    /// Stop acting after this many actions per automation run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_executions: Option<u64>,
+    /// Sequence to run for `action = "sequence"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<String>,
+    /// Matching engine for this template
+    pub backend: MatchBackend,
+    /// Patches only: pixels around the original position to search
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_radius: Option<u32>,
+    /// Screen size the template was captured on, e.g. `[1080, 2400]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_resolution: Option<(u32, u32)>,
+}
+
+impl Default for TemplateBehavior {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: TemplateAction::Tap,
+            offset_x: 0,
+            offset_y: 0,
+            swipe_dx: 0,
+            swipe_dy: 0,
+            swipe_duration_ms: 300,
+            cooldown_secs: 0,
+            priority: 0,
+            max_executions: None,
+            sequence: None,
+            backend: MatchBackend::Correlation,
+            search_radius: None,
+            source_resolution: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Template {
     pub path: String,
@@ -21,6 +137,10 @@ pub struct Template {
     pub width: u32,
     pub height: u32,
     pub category: TemplateCategory,
+    pub behavior: TemplateBehavior,
+    /// Size of the screenshot a `[x,y,w,h]` template was cut from, or the
+    /// `@WxH` resolution in its file name
+    pub source_size: Option<(u32, u32)>,
 }
 
 impl Template {
@@ -39,6 +159,10 @@ impl Template {
 
         // Calculate actual template dimensions (cropped if region is specified in filename)
         let (width, height) = Self::calculate_template_dimensions(&name, &image)?;
+        let source_size = match Self::extract_region_from_filename(&name) {
+            Some(_) => Some((image.width(), image.height())),
+            None => Self::extract_resolution_from_filename(&name),
+        };
 
         Ok(Self {
             path,
@@ -47,9 +171,95 @@ impl Template {
             width,
             height,
             category,
+            behavior: TemplateBehavior::default(),
+            source_size,
         })
     }
 
+    /// Name without the `patch-` prefix and `[x,y,w,h]` or `@WxH` suffix, e.g. `claim_button`
+    pub fn label(&self) -> &str {
+        let name = self.name.strip_prefix("patch-").unwrap_or(&self.name);
+        match name.rfind('[') {
+            Some(bracket) => name[..bracket].trim_end_matches('-'),
+            None if Self::extract_resolution_from_filename(name).is_some() => {
+                name.rsplit_once('@').map_or(name, |(label, _)| label)
+            }
+            None => name,
+        }
+    }
+
+    /// Screen size the template was captured on: `source_resolution` from the
+    /// config, else what its file name tells
+    pub fn source_resolution(&self) -> Option<(u32, u32)> {
+        self.behavior.source_resolution.or(self.source_size)
+    }
+
+    /// Template pixels: the `[x,y,w,h]` region of the file, or the whole file
+    pub fn load_image(&self) -> Result<image::DynamicImage, String> {
+        let image = image::open(&self.path)
+            .map_err(|e| format!("Failed to load template {}: {}", self.path, e))?;
+        Self::calculate_template_dimensions(&self.name, &image)?;
+        Ok(match Self::extract_region_from_filename(&self.name) {
+            Some((x, y, width, height)) => image.crop_imm(x, y, width, height),
+            None => image,
+        })
+    }
+
+    /// Replace the template with `(x, y, width, height)` of `screenshot`, returning
+    /// its new path. A `[x,y,w,h]` template keeps the whole screenshot under a name
+    /// with the new region; any other file is overwritten with the cropped pixels.
+    pub fn recrop(
+        &self,
+        screenshot: &image::RgbImage,
+        (x, y, width, height): (u32, u32, u32, u32),
+    ) -> Result<String, String> {
+        if width == 0
+            || height == 0
+            || x + width > screenshot.width()
+            || y + height > screenshot.height()
+        {
+            return Err(format!(
+                "Region [{},{},{},{}] is outside the {}x{} screenshot",
+                x,
+                y,
+                width,
+                height,
+                screenshot.width(),
+                screenshot.height()
+            ));
+        }
+
+        let path = Path::new(&self.path);
+        if let (Some(start), Some(end)) = (self.name.find('['), self.name.find(']'))
+            && Self::extract_region_from_filename(&self.name).is_some()
+        {
+            let file_name = format!(
+                "{}[{},{},{},{}]{}.png",
+                &self.name[..start],
+                x,
+                y,
+                width,
+                height,
+                &self.name[end + 1..]
+            );
+            let new_path = path.with_file_name(file_name);
+            screenshot
+                .save(&new_path)
+                .map_err(|e| format!("Failed to save {}: {}", new_path.display(), e))?;
+            if new_path != path {
+                std::fs::remove_file(path)
+                    .map_err(|e| format!("Failed to remove {}: {}", self.path, e))?;
+            }
+            Ok(new_path.to_string_lossy().to_string())
+        } else {
+            image::imageops::crop_imm(screenshot, x, y, width, height)
+                .to_image()
+                .save(path)
+                .map_err(|e| format!("Failed to save {}: {}", self.path, e))?;
+            Ok(self.path.clone())
+        }
+    }
+
     /// Calculate the actual template dimensions after cropping (if applicable)
     fn calculate_template_dimensions(
         filename: &str,
@@ -81,7 +291,7 @@ impl Template {
     }
 
     /// Extract region coordinates from filename
-    fn extract_region_from_filename(filename: &str) -> Option<(u32, u32, u32, u32)> {
+    pub(super) fn extract_region_from_filename(filename: &str) -> Option<(u32, u32, u32, u32)> {
         if let Some(start) = filename.find('[')
             && let Some(end) = filename.find(']')
             && end > start
@@ -102,6 +312,16 @@ impl Template {
         None
     }
 
+    /// Capture resolution from a `name@1080x2400` file name
+    pub(super) fn extract_resolution_from_filename(filename: &str) -> Option<(u32, u32)> {
+        let (_, resolution) = filename.rsplit_once('@')?;
+        let (width, height) = resolution.split_once('x')?;
+        match (width.parse::<u32>(), height.parse::<u32>()) {
+            (Ok(width), Ok(height)) if width > 0 && height > 0 => Some((width, height)),
+            _ => None,
+        }
+    }
+
     fn determine_category(name: &str) -> TemplateCategory {
         let name_lower = name.to_lowercase();
 
@@ -128,19 +348,32 @@ impl Template {
             && self.height > 0
     }
 
-    /// Get the center tap coordinates for this template at a match location
+    /// Get the tap coordinates for this template at a match location: its
+    /// center, moved by the behavior offset
     pub fn get_tap_coordinates(&self, match_x: u32, match_y: u32) -> (u32, u32) {
-        (match_x + self.width / 2, match_y + self.height / 2)
+        (
+            offset(match_x + self.width / 2, self.behavior.offset_x),
+            offset(match_y + self.height / 2, self.behavior.offset_y),
+        )
     }
 }
 
+fn offset(value: u32, delta: i32) -> u32 {
+    (value as i64 + delta as i64).clamp(0, u32::MAX as i64) as u32
+}
+
 #[derive(Debug, Clone)]
 pub struct TemplateMatch {
     pub template: Template,
     pub x: u32,
     pub y: u32,
     pub confidence: f32,
+    /// Template size on screen relative to the template file
     pub scale_factor: f32,
+    /// Degrees the template was turned to match (0 = upright)
+    pub rotation_degrees: f32,
+    /// Engine that produced this match
+    pub backend: MatchBackend,
 }
 
 impl TemplateMatch {
@@ -151,12 +384,57 @@ impl TemplateMatch {
             y,
             confidence,
             scale_factor,
+            rotation_degrees: 0.0,
+            backend: MatchBackend::Correlation,
         }
     }
 
-    /// Get tap coordinates at the center of this match
+    pub fn with_backend(mut self, backend: MatchBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn with_rotation(mut self, degrees: f32) -> Self {
+        self.rotation_degrees = degrees;
+        self
+    }
+
+    /// `value` template pixels in screen pixels at this match's scale
+    fn scaled(&self, value: i32) -> i32 {
+        (value as f32 * self.scale_factor).round() as i32
+    }
+
+    /// Width and height of the matched area on screen
+    pub fn screen_size(&self) -> (u32, u32) {
+        (
+            self.scaled(self.template.width as i32) as u32,
+            self.scaled(self.template.height as i32) as u32,
+        )
+    }
+
+    /// Get tap coordinates at the center of this match; the size and offset
+    /// grow or shrink with the matched scale
     pub fn get_tap_coordinates(&self) -> (u32, u32) {
-        self.template.get_tap_coordinates(self.x, self.y)
+        let (width, height) = self.screen_size();
+        (
+            offset(
+                self.x + width / 2,
+                self.scaled(self.template.behavior.offset_x),
+            ),
+            offset(
+                self.y + height / 2,
+                self.scaled(self.template.behavior.offset_y),
+            ),
+        )
+    }
+
+    /// End point of this template's swipe action
+    pub fn get_swipe_end(&self) -> (u32, u32) {
+        let (x, y) = self.get_tap_coordinates();
+        (
+            offset(x, self.scaled(self.template.behavior.swipe_dx)),
+            offset(y, self.scaled(self.template.behavior.swipe_dy)),
+        )
     }
 
     /// Check if this match is within screen bounds
@@ -166,7 +444,46 @@ impl TemplateMatch {
     }
 }
 
+/// Actions taken per template during a run, for cooldowns and `max_executions`
+#[derive(Debug, Clone, Default)]
+pub struct ActionHistory {
+    runs: HashMap<String, (u64, Instant)>,
+}
+
+impl ActionHistory {
+    pub fn record(&mut self, template: &Template) {
+        let now = Instant::now();
+        let entry = self.runs.entry(template.name.clone()).or_insert((0, now));
+        entry.0 += 1;
+        entry.1 = now;
+    }
+
+    pub fn count(&self, template: &Template) -> u64 {
+        self.runs.get(&template.name).map_or(0, |(count, _)| *count)
+    }
+
+    /// Whether `template` may act now
+    pub fn allows(&self, template: &Template) -> bool {
+        let behavior = &template.behavior;
+        if behavior.action == TemplateAction::None
+            || behavior
+                .max_executions
+                .is_some_and(|max| self.count(template) >= max)
+        {
+            return false;
+        }
+        self.runs
+            .get(&template.name)
+            .is_none_or(|(_, last)| last.elapsed() >= Duration::from_secs(behavior.cooldown_secs))
+    }
+
+    pub fn clear(&mut self) {
+        self.runs.clear();
+    }
+}
+
 /// Manager for loading and organizing templates
+#[derive(Clone)]
 pub struct TemplateManager {
     templates: Vec<Template>,
     screen_width: u32,
@@ -215,11 +532,11 @@ impl TemplateManager {
                             self.templates.push(template);
                             loaded_count += 1;
                         } else {
-                            eprintln!("⚠️ Invalid template skipped: {}", file_name);
+                            log::warn!("⚠️ Invalid template skipped: {}", file_name);
                         }
                     }
                     Err(e) => {
-                        eprintln!("⚠️ Failed to load template {}: {}", file_name, e);
+                        log::warn!("⚠️ Failed to load template {}: {}", file_name, e);
                     }
                 }
             }
@@ -236,6 +553,17 @@ impl TemplateManager {
         Ok(loaded_count)
     }
 
+    /// Give each template its configured behavior, keyed by full name or label
+    pub fn apply_behaviors(&mut self, behaviors: &BTreeMap<String, TemplateBehavior>) {
+        for template in &mut self.templates {
+            template.behavior = behaviors
+                .get(&template.name)
+                .or_else(|| behaviors.get(template.label()))
+                .cloned()
+                .unwrap_or_default();
+        }
+    }
+
     /// Get all loaded templates
     pub fn get_templates(&self) -> &[Template] {
         &self.templates
@@ -1,7 +1,9 @@
 //! Tests for image matching functionality
 
 use crate::game_automation::match_image::{
-    DetectionResult, MatchConfig, SearchRegion, Template, TemplateCategory, TemplateMatch,
+    ActionHistory, DetectionResult, FeatureSet, FrameFingerprint, GameStateDetector, MatchConfig,
+    SearchRegion, Template, TemplateAction, TemplateBehavior, TemplateCategory, TemplateMatch,
+    TextRegion, alpha_mask, find_text, match_features,
 };
 use std::path::Path;
 
@@ -72,6 +74,8 @@ fn test_detection_result_has_matches() {
         width: 50,
         height: 50,
         category: TemplateCategory::Unknown,
+        behavior: TemplateBehavior::default(),
+        source_size: None,
     };
     let template_match = TemplateMatch::new(template, 10, 10, 0.95, 1.0);
     result.matches.push(template_match);
@@ -94,6 +98,8 @@ fn test_detection_result_best_match() {
             width: 50,
             height: 50,
             category: TemplateCategory::Unknown,
+            behavior: TemplateBehavior::default(),
+            source_size: None,
         };
         result
             .matches
@@ -115,6 +121,8 @@ fn test_template_match_tap_coordinates() {
         width: 50,
         height: 50,
         category: TemplateCategory::Unknown,
+        behavior: TemplateBehavior::default(),
+        source_size: None,
     };
 
     // Match at position (100, 150), template is 50x50
@@ -136,6 +144,8 @@ fn test_template_match_within_bounds() {
         width: 50,
         height: 50,
         category: TemplateCategory::Unknown,
+        behavior: TemplateBehavior::default(),
+        source_size: None,
     };
 
     // Match within bounds
@@ -294,3 +304,461 @@ fn test_match_patch_against_different_screenshot() {
     // CrossCorrelationNormalized should be in [-1, 1] range
     assert!((-1.0..=1.0).contains(&max_confidence));
 }
+
+#[test]
+fn test_text_region_number_and_find() {
+    let regions = vec![
+        TextRegion::new("Gold: 12,500".to_string(), 10, 20, 200, 40),
+        TextRegion::new("CLAIM REWARD".to_string(), 300, 1200, 180, 60),
+        TextRegion::new("Level -3".to_string(), 0, 0, 50, 20),
+    ];
+
+    assert_eq!(regions[0].number(), Some(12500));
+    assert_eq!(regions[1].number(), None);
+    assert_eq!(regions[2].number(), Some(-3));
+
+    let claim = find_text(&regions, "claim").expect("case-insensitive match");
+    assert_eq!(claim.center(), (390, 1230));
+    assert!(find_text(&regions, "settings").is_none());
+}
+
+#[test]
+fn test_category_label_for() {
+    assert_eq!(TemplateCategory::Button.label_for("claim"), "claim_button");
+    assert_eq!(TemplateCategory::Button.label_for("claim_btn"), "claim_btn");
+    assert_eq!(TemplateCategory::Icon.label_for("gold"), "gold_icon");
+    assert_eq!(TemplateCategory::Unknown.label_for("gold"), "gold");
+}
+
+#[test]
+fn test_next_action_respects_priority_and_limits() {
+    let region = SearchRegion::new(0, 0, 1000, 1000, "test".to_string());
+    let template = |name: &str, behavior: TemplateBehavior| Template {
+        path: format!("{name}.png"),
+        name: name.to_string(),
+        search_region: region.clone(),
+        width: 40,
+        height: 20,
+        category: TemplateCategory::Button,
+        behavior,
+        source_size: None,
+    };
+    let mut result = DetectionResult::new();
+    result.matches.push(TemplateMatch::new(
+        template("patch-close-[0,0,40,20]", TemplateBehavior::default()),
+        100,
+        100,
+        0.99,
+        1.0,
+    ));
+    result.matches.push(TemplateMatch::new(
+        template(
+            "claim",
+            TemplateBehavior {
+                priority: 5,
+                offset_y: 30,
+                max_executions: Some(1),
+                ..TemplateBehavior::default()
+            },
+        ),
+        200,
+        400,
+        0.86,
+        1.0,
+    ));
+    result.matches.push(TemplateMatch::new(
+        template(
+            "banner",
+            TemplateBehavior {
+                action: TemplateAction::None,
+                priority: 9,
+                ..TemplateBehavior::default()
+            },
+        ),
+        0,
+        0,
+        0.95,
+        1.0,
+    ));
+
+    let mut history = ActionHistory::default();
+    let first = result.next_action(&history).unwrap();
+    assert_eq!(first.template.name, "claim");
+    assert_eq!(first.get_tap_coordinates(), (220, 440));
+
+    history.record(&first.template.clone());
+    let second = result.next_action(&history).unwrap();
+    assert_eq!(second.template.label(), "close");
+    assert_eq!(second.get_tap_coordinates(), (120, 110));
+}
+
+#[test]
+fn test_detector_reuses_preloaded_templates() {
+    let dir = std::env::temp_dir().join(format!("adb_templates_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // Checkerboard screen with the 20x20 template cut from it at (40,40)
+    let screen = image::GrayImage::from_fn(200, 200, |x, y| {
+        image::Luma([if (x / 10 + y / 10) % 2 == 0 { 255 } else { 0 }])
+    });
+    let template_path = dir.join("button-ok-[40,40,20,20].png");
+    screen.save(&template_path).unwrap();
+    let mut screenshot = Vec::new();
+    image::DynamicImage::ImageLuma8(screen)
+        .write_to(
+            &mut std::io::Cursor::new(&mut screenshot),
+            image::ImageFormat::Png,
+        )
+        .unwrap();
+
+    let config = MatchConfig {
+        use_match_patch_optimization: false,
+        ..MatchConfig::default()
+    };
+    let mut detector = GameStateDetector::new(200, 200, config);
+    let dirs = vec![dir.to_string_lossy().to_string()];
+    assert_eq!(detector.load_templates_from(&dirs).unwrap(), 1);
+    assert_eq!(detector.preloaded_template_count(), 1);
+
+    // Analysis no longer touches the template file
+    std::fs::remove_file(&template_path).unwrap();
+    let shared = std::sync::Arc::new(detector.clone());
+    let result = shared.analyze_screenshot(&screenshot).unwrap();
+    assert!(result.has_matches());
+
+    // Only a rescan drops what was loaded
+    assert_eq!(detector.reload_templates_from(&dirs).unwrap(), 0);
+    assert_eq!(detector.preloaded_template_count(), 0);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_frame_fingerprint_ignores_noise_but_sees_changes() {
+    let base = image::GrayImage::from_fn(1080, 2280, |x, y| image::Luma([((x + y) % 200) as u8]));
+    let fingerprint = FrameFingerprint::from_gray(&base);
+    assert_eq!(fingerprint.changed_fraction(&fingerprint.clone()), 0.0);
+
+    // A few stray pixels (compression noise, a blinking cursor) average away
+    let mut noisy = base.clone();
+    for x in 500..504 {
+        noisy.put_pixel(x, 1000, image::Luma([255]));
+    }
+    assert!(fingerprint.is_unchanged(&FrameFingerprint::from_gray(&noisy), 0.002));
+
+    // A popup covering part of the screen does not
+    let mut popup = base.clone();
+    for y in 900..1300 {
+        for x in 200..880 {
+            popup.put_pixel(x, y, image::Luma([255]));
+        }
+    }
+    assert!(!fingerprint.is_unchanged(&FrameFingerprint::from_gray(&popup), 0.002));
+}
+
+#[test]
+fn test_alpha_mask_from_transparent_pixels() {
+    let opaque = image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+        4,
+        4,
+        image::Rgba([10, 20, 30, 255]),
+    ));
+    assert!(alpha_mask(&opaque).is_none());
+
+    let cutout = image::DynamicImage::ImageRgba8(image::RgbaImage::from_fn(4, 4, |x, _| {
+        image::Rgba([10, 20, 30, if x < 2 { 0 } else { 200 }])
+    }));
+    let mask = alpha_mask(&cutout).unwrap();
+    assert_eq!(mask.get_pixel(0, 0)[0], 0);
+    assert_eq!(mask.get_pixel(3, 3)[0], 255);
+}
+
+#[test]
+fn test_scale_range_and_rotation_angles() {
+    use crate::game_automation::match_image::config::{rotation_angles, scale_range};
+
+    assert_eq!(scale_range(0.5, 1.5, 5), vec![0.5, 0.75, 1.0, 1.25, 1.5]);
+    assert_eq!(scale_range(1.2, 0.8, 1), vec![1.0]);
+    assert!(rotation_angles(0.0, 2.0).is_empty());
+    assert_eq!(rotation_angles(4.0, 2.0), vec![0.0, -2.0, 2.0, -4.0, 4.0]);
+}
+
+#[test]
+fn test_scaled_match_taps_scaled_center() {
+    let template = Template {
+        path: "test.png".to_string(),
+        name: "test".to_string(),
+        search_region: SearchRegion::new(0, 0, 400, 400, "test".to_string()),
+        width: 40,
+        height: 20,
+        category: TemplateCategory::Unknown,
+        behavior: TemplateBehavior {
+            offset_y: 10,
+            ..TemplateBehavior::default()
+        },
+        source_size: None,
+    };
+    let found = TemplateMatch::new(template, 100, 100, 0.9, 1.5);
+    // 60x30 on screen, offset 15px
+    assert_eq!(found.get_tap_coordinates(), (130, 130));
+}
+
+#[test]
+fn test_detector_matches_template_from_other_resolution() {
+    let dir = std::env::temp_dir().join(format!("adb_scaled_templates_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // Template cut at (40,40) from a 200x200 screenshot, searched on a 300x300 screen
+    let source = image::GrayImage::from_fn(200, 200, |x, y| {
+        let v = 128.0 + 100.0 * (x as f32 / 7.0).sin() * (y as f32 / 9.0).cos();
+        image::Luma([v as u8])
+    });
+    source.save(dir.join("icon-[40,40,20,20].png")).unwrap();
+    let screen = image::imageops::resize(&source, 300, 300, image::imageops::FilterType::Triangle);
+    let mut screenshot = Vec::new();
+    image::DynamicImage::ImageLuma8(screen)
+        .write_to(
+            &mut std::io::Cursor::new(&mut screenshot),
+            image::ImageFormat::Png,
+        )
+        .unwrap();
+
+    let mut detector = GameStateDetector::new(300, 300, MatchConfig::default());
+    let dirs = vec![dir.to_string_lossy().to_string()];
+    assert_eq!(detector.load_templates_from(&dirs).unwrap(), 1);
+    let result = detector.analyze_screenshot(&screenshot).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let best = result.best_match_for("icon-[40,40,20,20]").unwrap();
+    assert_eq!((best.x, best.y), (60, 60));
+    assert!((best.scale_factor - 1.5).abs() < 0.01);
+    assert_eq!(best.get_tap_coordinates(), (75, 75));
+}
+
+#[test]
+fn test_cropped_template_scaled_from_capture_resolution() {
+    let dir = std::env::temp_dir().join(format!("adb_resolution_templates_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // Icon cropped from a 200x200 capture, named with that resolution
+    let source = image::GrayImage::from_fn(200, 200, |x, y| {
+        let v = 128.0 + 100.0 * (x as f32 / 7.0).sin() * (y as f32 / 9.0).cos();
+        image::Luma([v as u8])
+    });
+    image::imageops::crop_imm(&source, 40, 40, 20, 20)
+        .to_image()
+        .save(dir.join("icon@200x200.png"))
+        .unwrap();
+    let screen = image::imageops::resize(&source, 300, 300, image::imageops::FilterType::Triangle);
+    let mut screenshot = Vec::new();
+    image::DynamicImage::ImageLuma8(screen)
+        .write_to(
+            &mut std::io::Cursor::new(&mut screenshot),
+            image::ImageFormat::Png,
+        )
+        .unwrap();
+
+    let mut detector = GameStateDetector::new(300, 300, MatchConfig::default());
+    let dirs = vec![dir.to_string_lossy().to_string()];
+    assert_eq!(detector.load_templates_from(&dirs).unwrap(), 1);
+    let result = detector.analyze_screenshot(&screenshot).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let best = result.best_match_for("icon").unwrap();
+    assert_eq!(best.template.label(), "icon");
+    assert_eq!(best.template.source_resolution(), Some((200, 200)));
+    assert_eq!((best.x, best.y), (60, 60));
+    assert!((best.scale_factor - 1.5).abs() < 0.01);
+}
+
+#[test]
+fn test_near_misses_report_best_candidate_below_threshold() {
+    let dir = std::env::temp_dir().join(format!("adb_near_miss_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let screen = image::GrayImage::from_fn(200, 200, |x, y| {
+        let v = 128.0 + 100.0 * (x as f32 / 7.0).sin() * (y as f32 / 9.0).cos();
+        image::Luma([v as u8])
+    });
+    screen.save(dir.join("icon-[40,40,20,20].png")).unwrap();
+
+    // Nothing can reach this threshold, so the perfect spot becomes a near miss
+    let config = MatchConfig {
+        confidence_threshold: 1.01,
+        ..MatchConfig::default()
+    };
+    let mut detector = GameStateDetector::new(200, 200, config);
+    let dirs = vec![dir.to_string_lossy().to_string()];
+    assert_eq!(detector.load_templates_from(&dirs).unwrap(), 1);
+    let result = detector.analyze_gray(&screen);
+    let misses = detector.near_misses(&screen, &result, 0.9);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(!result.has_matches());
+    assert_eq!(misses.len(), 1);
+    assert_eq!((misses[0].best.x, misses[0].best.y), (40, 40));
+    assert!(misses[0].best.confidence > 0.99);
+    let area = &misses[0].search_area;
+    assert!(area.x <= 40 && area.x + area.width >= 60);
+    assert!(detector.near_misses(&screen, &result, 1.05).is_empty());
+}
+
+/// Blocky pseudo-random texture, distinct per `seed`
+fn noise_texture(width: u32, height: u32, seed: u32) -> image::GrayImage {
+    image::GrayImage::from_fn(width, height, |x, y| {
+        let mut h = (x / 3).wrapping_mul(73_856_093) ^ (y / 3).wrapping_mul(19_349_663) ^ seed;
+        h ^= h >> 13;
+        h = h.wrapping_mul(0x5bd1_e995);
+        h ^= h >> 15;
+        image::Luma([(h & 0xff) as u8])
+    })
+}
+
+#[test]
+fn test_feature_matching_ignores_background() {
+    let template = noise_texture(80, 80, 1);
+    let mut scene = noise_texture(300, 200, 2);
+    image::imageops::replace(&mut scene, &template, 150, 70);
+
+    let template_features = FeatureSet::detect(&template, None);
+    let scene_features = FeatureSet::detect(&scene, None);
+    let found = match_features(&template_features, &scene_features).unwrap();
+    assert_eq!((found.x, found.y), (150, 70));
+    assert!(found.confidence > 0.5);
+
+    // A different background without the template has nothing to agree on
+    let empty = FeatureSet::detect(&noise_texture(300, 200, 3), None);
+    assert!(match_features(&template_features, &empty).is_none());
+}
+
+#[test]
+fn test_streaming_analysis_stops_on_break() {
+    let dir = std::env::temp_dir().join(format!("adb_streaming_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let screen = image::GrayImage::from_fn(200, 200, |x, y| {
+        let v = 128.0 + 100.0 * (x as f32 / 7.0).sin() * (y as f32 / 9.0).cos();
+        image::Luma([v as u8])
+    });
+    screen.save(dir.join("first-[20,20,20,20].png")).unwrap();
+    screen.save(dir.join("second-[120,120,20,20].png")).unwrap();
+
+    let mut detector = GameStateDetector::new(200, 200, MatchConfig::default());
+    let dirs = vec![dir.to_string_lossy().to_string()];
+    assert_eq!(detector.load_templates_from(&dirs).unwrap(), 2);
+
+    let mut streamed = Vec::new();
+    let full = detector.analyze_gray_streaming(&screen, |found| {
+        streamed.push(found.template.name.clone());
+        std::ops::ControlFlow::Continue(())
+    });
+    assert!(!full.stopped_early);
+    assert_eq!(streamed.len(), full.matches.len());
+    assert!(full.best_match_for("first").is_some() && full.best_match_for("second").is_some());
+
+    let mut calls = 0;
+    let early = detector.analyze_gray_streaming(&screen, |_| {
+        calls += 1;
+        std::ops::ControlFlow::Break(())
+    });
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(early.stopped_early);
+    assert_eq!(calls, early.matches.len());
+    let names: std::collections::HashSet<_> = early
+        .matches
+        .iter()
+        .map(|m| m.template.name.as_str())
+        .collect();
+    assert_eq!(names.len(), 1, "only the first template was matched");
+}
+
+#[test]
+fn test_disabled_template_is_not_analyzed() {
+    let dir = std::env::temp_dir().join(format!("adb_disabled_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let screen = image::GrayImage::from_fn(200, 200, |x, y| {
+        image::Luma([if (x / 10 + y / 10) % 2 == 0 { 255 } else { 0 }])
+    });
+    screen
+        .save(dir.join("ok_button-[40,40,20,20].png"))
+        .unwrap();
+
+    let mut config = MatchConfig {
+        use_match_patch_optimization: false,
+        ..MatchConfig::default()
+    };
+    config.template_behaviors.insert(
+        "ok_button".to_string(),
+        TemplateBehavior {
+            enabled: false,
+            ..TemplateBehavior::default()
+        },
+    );
+    let mut detector = GameStateDetector::new(200, 200, config);
+    let dirs = vec![dir.to_string_lossy().to_string()];
+    assert_eq!(detector.load_templates_from(&dirs).unwrap(), 1);
+    let result = detector.analyze_gray(&screen);
+    let found = detector.find_template("ok_button", &screen);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(!result.has_matches());
+    assert!(detector.template_names().is_empty());
+    assert!(found.is_some(), "still found when asked for by name");
+}
+
+#[test]
+fn test_template_recrop_from_screenshot() {
+    let dir = std::env::temp_dir().join(format!("adb_recrop_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let screen = image::RgbImage::from_fn(200, 100, |x, y| image::Rgb([x as u8, y as u8, 0]));
+    let region = SearchRegion::new(0, 0, 200, 100, "test".to_string());
+
+    let path = dir.join("claim-[40,40,20,20].png");
+    screen.save(&path).unwrap();
+    let template = Template::new(path.to_string_lossy().to_string(), region.clone()).unwrap();
+    assert_eq!(template.load_image().unwrap().width(), 20);
+    let moved = template.recrop(&screen, (10, 5, 30, 15)).unwrap();
+    assert!(moved.ends_with("claim-[10,5,30,15].png"));
+    assert!(!path.exists());
+
+    let plain = dir.join("plain.png");
+    screen.save(&plain).unwrap();
+    let template = Template::new(plain.to_string_lossy().to_string(), region).unwrap();
+    assert!(template.recrop(&screen, (190, 0, 20, 10)).is_err());
+    template.recrop(&screen, (10, 5, 30, 15)).unwrap();
+    let cropped = image::open(&plain).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!((cropped.width(), cropped.height()), (30, 15));
+}
+
+#[test]
+fn test_single_template_test_reports_score_map() {
+    let dir = std::env::temp_dir().join(format!("adb_template_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let screen = image::GrayImage::from_fn(200, 200, |x, y| {
+        let v = 128.0 + 100.0 * (x as f32 / 7.0).sin() * (y as f32 / 9.0).cos();
+        image::Luma([v as u8])
+    });
+    image::imageops::crop_imm(&screen, 60, 80, 20, 20)
+        .to_image()
+        .save(dir.join("coin.png"))
+        .unwrap();
+
+    let config = MatchConfig {
+        use_match_patch_optimization: false,
+        enable_multiscale: false,
+        ..MatchConfig::default()
+    };
+    let mut detector = GameStateDetector::new(200, 200, config);
+    let dirs = vec![dir.to_string_lossy().to_string()];
+    assert_eq!(detector.load_templates_from(&dirs).unwrap(), 1);
+    let test = detector.test_template("coin", &screen).unwrap();
+    let missing = detector.test_template("nothing", &screen);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let best = test.best.unwrap();
+    assert_eq!((best.x, best.y), (60, 80));
+    assert!(test.result.has_matches());
+    let scores = test.scores.unwrap();
+    assert_eq!(scores.positions, 181 * 181, "every position on screen");
+    assert!(scores.min <= scores.mean && scores.mean <= scores.max);
+    assert!(scores.above_threshold >= 1);
+    assert_eq!(scores.max, best.confidence);
+    assert!(missing.is_err());
+}
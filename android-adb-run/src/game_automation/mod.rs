@@ -2,10 +2,35 @@
 // This module provides a finite state machine for automating game interactions
 // with Android devices via ADB.
 
+pub mod adaptive;
+pub mod backup;
+pub mod color_probe;
 pub mod config;
+pub mod coord;
+pub mod counters;
 pub mod fsm;
+pub mod history;
+pub mod humanize;
+pub mod match_debug;
 pub mod match_image;
+pub mod notify;
+pub mod overlay;
+pub mod plugins;
+pub mod quiet_hours;
+pub mod rules;
+pub mod runtime_state;
+pub mod safety;
+pub mod scenes;
+pub mod schedule;
+pub mod script;
+pub mod sequence;
+pub mod silence;
+pub mod states;
+pub mod stats;
+pub mod timelapse;
 pub mod types;
+pub mod wait;
+pub mod watchdog;
 
 // Re-export the main types and functions for easy access
 pub use fsm::GameAutomation;
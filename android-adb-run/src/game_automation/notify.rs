@@ -0,0 +1,142 @@
+// Desktop notifications (notify-rust) for events worth leaving the desk for:
+// device disconnected, automation error, jackpot template matched, battery low
+use super::config::NotificationsConfig;
+use super::match_image::detector::template_name_matches;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The same notice (e.g. one template) is not repeated within this time
+pub const NOTIFY_COOLDOWN: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Notice {
+    Disconnected { device: String },
+    AutomationError(String),
+    TemplateMatched { template: String, confidence: f32 },
+    BatteryLow { level: u8 },
+}
+
+impl Notice {
+    /// Cooldown key: errors share one, templates have one each
+    fn key(&self) -> String {
+        match self {
+            Notice::Disconnected { .. } => "disconnected".to_string(),
+            Notice::AutomationError(_) => "error".to_string(),
+            Notice::TemplateMatched { template, .. } => format!("template:{}", template),
+            Notice::BatteryLow { .. } => "battery".to_string(),
+        }
+    }
+
+    fn summary(&self) -> &'static str {
+        match self {
+            Notice::Disconnected { .. } => "📵 Device disconnected",
+            Notice::AutomationError(_) => "❌ Automation error",
+            Notice::TemplateMatched { .. } => "🎰 Template matched",
+            Notice::BatteryLow { .. } => "🔋 Battery low",
+        }
+    }
+
+    fn body(&self) -> String {
+        match self {
+            Notice::Disconnected { device } => format!("Lost connection to {}", device),
+            Notice::AutomationError(message) => message.clone(),
+            Notice::TemplateMatched {
+                template,
+                confidence,
+            } => format!("{} ({:.0}%)", template, confidence * 100.0),
+            Notice::BatteryLow { level } => format!("Battery at {}% and not charging", level),
+        }
+    }
+
+    /// Whether `config` asks for this kind of notice
+    fn wanted(&self, config: &NotificationsConfig) -> bool {
+        match self {
+            Notice::Disconnected { .. } => config.on_disconnect,
+            Notice::AutomationError(_) => config.on_error,
+            Notice::TemplateMatched { template, .. } => config
+                .templates
+                .iter()
+                .any(|wanted| template_name_matches(template, wanted)),
+            Notice::BatteryLow { level } => *level < config.battery_below,
+        }
+    }
+}
+
+/// Sends notices allowed by the config, at most once per cooldown each
+#[derive(Debug, Default)]
+pub struct Notifier {
+    last_sent: HashMap<String, Instant>,
+}
+
+impl Notifier {
+    /// True (and the cooldown restarted) when `notice` should be shown now
+    pub fn should_send(&mut self, config: &NotificationsConfig, notice: &Notice) -> bool {
+        if !config.enabled || !notice.wanted(config) {
+            return false;
+        }
+        let key = notice.key();
+        let now = Instant::now();
+        if self
+            .last_sent
+            .get(&key)
+            .is_some_and(|sent| now.duration_since(*sent) < NOTIFY_COOLDOWN)
+        {
+            return false;
+        }
+        self.last_sent.insert(key, now);
+        true
+    }
+
+    /// Show `notice` as a desktop notification if the config allows it
+    pub fn notify(&mut self, config: &NotificationsConfig, notice: Notice) {
+        if !self.should_send(config, &notice) {
+            return;
+        }
+        log::info!("🔔 {}: {}", notice.summary(), notice.body());
+        // The D-Bus / WinRT call can block, keep it off the automation loop
+        std::thread::spawn(move || {
+            if let Err(e) = notify_rust::Notification::new()
+                .appname("android-adb-run")
+                .summary(notice.summary())
+                .body(&notice.body())
+                .show()
+            {
+                log::warn!("⚠️ Desktop notification failed: {}", e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_send_respects_config_and_cooldown() {
+        let mut config = NotificationsConfig {
+            templates: vec!["jackpot".to_string()],
+            ..Default::default()
+        };
+        let mut notifier = Notifier::default();
+        let jackpot = Notice::TemplateMatched {
+            template: "jackpot[1]".to_string(),
+            confidence: 0.9,
+        };
+        assert!(
+            !notifier.should_send(&config, &jackpot),
+            "disabled by default"
+        );
+
+        config.enabled = true;
+        assert!(notifier.should_send(&config, &jackpot));
+        assert!(!notifier.should_send(&config, &jackpot), "cooldown");
+
+        let other = Notice::TemplateMatched {
+            template: "claim".to_string(),
+            confidence: 0.9,
+        };
+        assert!(!notifier.should_send(&config, &other));
+        assert!(!notifier.should_send(&config, &Notice::BatteryLow { level: 40 }));
+        assert!(notifier.should_send(&config, &Notice::BatteryLow { level: 10 }));
+    }
+}
@@ -0,0 +1,133 @@
+// Drawing on screenshots for offline review: box outlines and a tiny built-in
+// pixel font for times and scores, so no font file has to ship. Used by near-miss
+// captures, timelapse export and `analyze`.
+use super::match_image::TemplateMatch;
+use image::{Rgb, RgbImage};
+use imageproc::drawing::{draw_filled_rect_mut, draw_hollow_rect_mut};
+use imageproc::rect::Rect;
+
+pub const TEXT_COLOR: Rgb<u8> = Rgb([255, 255, 255]);
+pub const TEXT_BACKGROUND: Rgb<u8> = Rgb([0, 0, 0]);
+const MATCH_COLOR: Rgb<u8> = Rgb([40, 220, 80]);
+
+/// 3x5 pixel glyphs, top row first; other characters are blank
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        _ => [0; 5],
+    }
+}
+
+/// Font pixel size that stays readable on a screenshot this wide
+pub fn text_scale(image: &RgbImage) -> u32 {
+    (image.width() / 270).max(1)
+}
+
+/// Width and height of `text` on its background bar
+pub fn text_size(text: &str, scale: u32) -> (u32, u32) {
+    let pad = scale * 2;
+    (
+        text.chars().count() as u32 * 4 * scale + pad * 2,
+        5 * scale + pad * 2,
+    )
+}
+
+/// White `text` (digits, `-`, `:`, `.`) on a black bar with its top left at `(x, y)`
+pub fn draw_text(image: &mut RgbImage, (x, y): (u32, u32), text: &str, scale: u32) {
+    let pad = scale * 2;
+    let (width, height) = text_size(text, scale);
+    draw_filled_rect_mut(
+        image,
+        Rect::at(x as i32, y as i32).of_size(width, height),
+        TEXT_BACKGROUND,
+    );
+    for (i, c) in text.chars().enumerate() {
+        let left = x + pad + i as u32 * 4 * scale;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) != 0 {
+                    let rect = Rect::at(
+                        (left + col * scale) as i32,
+                        (y + pad + row as u32 * scale) as i32,
+                    )
+                    .of_size(scale, scale);
+                    draw_filled_rect_mut(image, rect, TEXT_COLOR);
+                }
+            }
+        }
+    }
+}
+
+/// Outline a `width` x `height` box, 2px thick, clipped to the image
+pub fn outline(image: &mut RgbImage, (x, y, width, height): (u32, u32, u32, u32), color: Rgb<u8>) {
+    for inset in 0..2u32 {
+        if width <= inset * 2 || height <= inset * 2 {
+            break;
+        }
+        let rect = Rect::at((x + inset) as i32, (y + inset) as i32)
+            .of_size(width - inset * 2, height - inset * 2);
+        draw_hollow_rect_mut(image, rect, color);
+    }
+}
+
+/// A green box per match with its confidence above it (below when at the top edge)
+pub fn draw_matches(image: &mut RgbImage, matches: &[TemplateMatch]) {
+    let scale = text_scale(image);
+    for found in matches {
+        let (width, height) = found.screen_size();
+        outline(image, (found.x, found.y, width, height), MATCH_COLOR);
+        let score = format!("{:.2}", found.confidence);
+        let (_, text_height) = text_size(&score, scale);
+        let y = found.y.checked_sub(text_height).unwrap_or(found.y + height);
+        draw_text(image, (found.x, y), &score, scale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_automation::match_image::{
+        SearchRegion, Template, TemplateBehavior, TemplateCategory,
+    };
+
+    #[test]
+    fn test_match_box_and_score() {
+        let template = Template {
+            path: "claim.png".to_string(),
+            name: "claim".to_string(),
+            search_region: SearchRegion::new(0, 0, 540, 960, "claim".to_string()),
+            width: 100,
+            height: 40,
+            category: TemplateCategory::Unknown,
+            behavior: TemplateBehavior::default(),
+            source_size: None,
+        };
+        let mut image = RgbImage::new(540, 960);
+        draw_matches(
+            &mut image,
+            &[TemplateMatch::new(template, 200, 300, 0.87, 1.0)],
+        );
+        assert_eq!(*image.get_pixel(200, 300), MATCH_COLOR);
+        assert_eq!(*image.get_pixel(299, 339), MATCH_COLOR);
+        assert_eq!(*image.get_pixel(250, 320), Rgb([0, 0, 0]), "box is hollow");
+        // Score bar above the box: 4 glyphs at scale 2 with 4px padding
+        assert_eq!(text_size("0.87", 2), (40, 18));
+        assert_eq!(
+            *image.get_pixel(204, 300 - 18 + 4),
+            TEXT_COLOR,
+            "top of '0'"
+        );
+    }
+}
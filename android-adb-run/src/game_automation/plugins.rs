@@ -0,0 +1,248 @@
+// Plugins: detection methods and action types added from outside the FSM, e.g. by
+// a crate that embeds android-adb-run and registers them before starting automation.
+//
+// - A `Detector` looks at every analyzed screenshot (in color) next to the template
+//   matcher. What it finds joins the detection result as a match named like a
+//   template, so `[[rules]]` (`"low_health seen"`), `[states]` and the overlay use it
+//   as they would a template. It only acts once `[matching.templates.<name>]` gives
+//   it an action.
+// - An `ActionHandler` is run by a sequence step and plans ordinary steps, so its
+//   taps still go through `[safety]` and the history:
+//
+//     [sequences.open_chest]
+//     steps = [{ action = "plugin", handler = "open_chest", params = { slot = 2 } }]
+//
+// Plugins are plain Rust; loading them at runtime (shared libraries, WASM) would be
+// a host that implements these traits and registers itself here.
+use super::match_image::{
+    SearchRegion, Template, TemplateAction, TemplateBehavior, TemplateCategory, TemplateMatch,
+};
+use super::sequence::StepAction;
+use image::RgbImage;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Something a `Detector` found, in screenshot pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginDetection {
+    /// Used like a template name in rules, states and `[matching.templates]`
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub confidence: f32,
+}
+
+/// A detection method run on every analyzed screenshot
+pub trait Detector: Send + Sync {
+    /// Registry key; registering the same name again replaces the detector
+    fn name(&self) -> &str;
+
+    /// Everything found on `screen`; runs on a background thread, keep it quick
+    fn detect(&self, screen: &RgbImage) -> Vec<PluginDetection>;
+}
+
+/// What an `ActionHandler` plans with
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionContext<'a> {
+    /// Sequence running the step
+    pub sequence: &'a str,
+    pub screen: (u32, u32),
+    /// `params` of the step
+    pub params: &'a toml::Table,
+}
+
+/// An action type for `{ action = "plugin", handler = "<name>" }` steps
+pub trait ActionHandler: Send + Sync {
+    /// The `handler` name steps refer to
+    fn name(&self) -> &str;
+
+    /// Steps to run in place of the plugin step; plugin steps are not allowed here
+    fn plan(&self, context: &ActionContext) -> Result<Vec<StepAction>, String>;
+}
+
+#[derive(Default)]
+struct Registry {
+    detectors: Vec<Arc<dyn Detector>>,
+    actions: HashMap<String, Arc<dyn ActionHandler>>,
+}
+
+static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+
+fn registry() -> &'static RwLock<Registry> {
+    REGISTRY.get_or_init(Default::default)
+}
+
+pub fn register_detector(detector: impl Detector + 'static) {
+    let detector: Arc<dyn Detector> = Arc::new(detector);
+    let mut registry = registry().write().unwrap();
+    registry.detectors.retain(|d| d.name() != detector.name());
+    log::info!("🧩 Detector plugin '{}' registered", detector.name());
+    registry.detectors.push(detector);
+}
+
+pub fn register_action(handler: impl ActionHandler + 'static) {
+    let handler: Arc<dyn ActionHandler> = Arc::new(handler);
+    log::info!("🧩 Action plugin '{}' registered", handler.name());
+    registry()
+        .write()
+        .unwrap()
+        .actions
+        .insert(handler.name().to_string(), handler);
+}
+
+pub fn has_detectors() -> bool {
+    !registry().read().unwrap().detectors.is_empty()
+}
+
+pub fn action_handler(name: &str) -> Option<Arc<dyn ActionHandler>> {
+    registry().read().unwrap().actions.get(name).cloned()
+}
+
+/// Run every registered detector on `screen`, as matches with the configured
+/// `behaviors` (detect-only without one)
+pub fn detect(
+    screen: &RgbImage,
+    behaviors: &BTreeMap<String, TemplateBehavior>,
+) -> Vec<TemplateMatch> {
+    let detectors = registry().read().unwrap().detectors.clone();
+    let (screen_width, screen_height) = screen.dimensions();
+    detectors
+        .iter()
+        .flat_map(|detector| {
+            detector
+                .detect(screen)
+                .into_iter()
+                .map(move |found| (detector.name().to_string(), found))
+        })
+        .map(|(detector, found)| {
+            let behavior =
+                behaviors
+                    .get(&found.name)
+                    .cloned()
+                    .unwrap_or_else(|| TemplateBehavior {
+                        action: TemplateAction::None,
+                        ..TemplateBehavior::default()
+                    });
+            let template = Template {
+                path: format!("plugin:{}/{}", detector, found.name),
+                search_region: SearchRegion::new(
+                    0,
+                    0,
+                    screen_width,
+                    screen_height,
+                    found.name.clone(),
+                ),
+                name: found.name,
+                width: found.width,
+                height: found.height,
+                category: TemplateCategory::Unknown,
+                behavior,
+                source_size: None,
+            };
+            TemplateMatch::new(template, found.x, found.y, found.confidence, 1.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_automation::coord::Coord;
+    use image::Rgb;
+
+    /// Finds a solid red 10x10 square in the top left corner
+    struct RedCorner;
+
+    impl Detector for RedCorner {
+        fn name(&self) -> &str {
+            "red_corner_test"
+        }
+
+        fn detect(&self, screen: &RgbImage) -> Vec<PluginDetection> {
+            if *screen.get_pixel(5, 5) != Rgb([255, 0, 0]) {
+                return Vec::new();
+            }
+            vec![PluginDetection {
+                name: "alert_test".to_string(),
+                x: 0,
+                y: 0,
+                width: 10,
+                height: 10,
+                confidence: 0.9,
+            }]
+        }
+    }
+
+    /// Taps `slot` of a row of slots 100px apart
+    struct OpenSlot;
+
+    impl ActionHandler for OpenSlot {
+        fn name(&self) -> &str {
+            "open_slot_test"
+        }
+
+        fn plan(&self, context: &ActionContext) -> Result<Vec<StepAction>, String> {
+            let slot = context
+                .params
+                .get("slot")
+                .and_then(toml::Value::as_integer)
+                .ok_or("slot missing")?;
+            Ok(vec![StepAction::Tap {
+                x: Coord::Px(100 * slot as u32),
+                y: Coord::Px(context.screen.1 / 2),
+            }])
+        }
+    }
+
+    #[test]
+    fn test_detector_results_become_matches() {
+        register_detector(RedCorner);
+        let mut screen = RgbImage::new(100, 200);
+        assert!(detect(&screen, &BTreeMap::new()).is_empty());
+
+        for pixel in screen.pixels_mut().take(1000) {
+            *pixel = Rgb([255, 0, 0]);
+        }
+        let found = detect(&screen, &BTreeMap::new());
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].template.name, "alert_test");
+        assert_eq!(found[0].template.path, "plugin:red_corner_test/alert_test");
+        assert_eq!(found[0].template.behavior.action, TemplateAction::None);
+
+        let behaviors = BTreeMap::from([("alert_test".to_string(), TemplateBehavior::default())]);
+        assert_eq!(
+            detect(&screen, &behaviors)[0].template.behavior.action,
+            TemplateAction::Tap
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plugin_step_runs_planned_steps() {
+        use crate::adb::{MockAction, MockAdb};
+        use crate::game_automation::match_image::GameStateDetector;
+        use crate::game_automation::sequence::ActionSequence;
+
+        register_action(OpenSlot);
+        let sequence: ActionSequence = toml::from_str(
+            r#"steps = [{ action = "plugin", handler = "open_slot_test", params = { slot = 3 } }, { action = "plugin", handler = "missing_test" }]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            sequence.steps[0].action.to_string(),
+            "plugin open_slot_test"
+        );
+        let mock = MockAdb::from_frames("mock", Vec::new());
+        let detector = Arc::new(GameStateDetector::new(1080, 2400, Default::default()));
+        let error = sequence
+            .run("chest", &mock, &detector, |_| {})
+            .await
+            .unwrap_err();
+        assert!(error.message.contains("no action plugin 'missing_test'"));
+        assert_eq!(
+            mock.control().actions(),
+            vec![MockAction::Tap { x: 300, y: 1200 }]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+// Quiet hours - a daily window in device local time, such as 23:00 to 07:00,
+// in which running automation sleeps. From `[quiet_hours]` in automation.toml:
+//
+//   [quiet_hours]
+//   enabled = true
+//   start = "23:00"
+//   end = "07:00"
+//
+// The FSM moves to `GameState::Sleeping` when the window opens and back to
+// Running when it closes; automation stopped or paused by hand stays that way.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use time::OffsetDateTime;
+
+/// `HH:MM` on a 24 hour clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay {
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl TimeOfDay {
+    pub fn new(hour: u8, minute: u8) -> Result<Self, String> {
+        if hour > 23 || minute > 59 {
+            return Err(format!("invalid time {:02}:{:02}", hour, minute));
+        }
+        Ok(Self { hour, minute })
+    }
+
+    fn minutes(self) -> u16 {
+        u16::from(self.hour) * 60 + u16::from(self.minute)
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (h, m) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected HH:MM, got '{}'", s))?;
+        let hour = h.parse().map_err(|_| format!("bad hour in '{}'", s))?;
+        let minute = m.parse().map_err(|_| format!("bad minute in '{}'", s))?;
+        Self::new(hour, minute)
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(time: TimeOfDay) -> Self {
+        time.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuietHoursConfig {
+    pub enabled: bool,
+    /// Automation sleeps from here...
+    pub start: TimeOfDay,
+    /// ...until here, the next day when `end` is earlier than `start`
+    pub end: TimeOfDay,
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: TimeOfDay {
+                hour: 23,
+                minute: 0,
+            },
+            end: TimeOfDay { hour: 7, minute: 0 },
+        }
+    }
+}
+
+impl QuietHoursConfig {
+    /// When `now` is inside quiet hours, the time they end (same offset as `now`)
+    pub fn sleeping_until(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        let (start, end) = (self.start.minutes(), self.end.minutes());
+        if !self.enabled || start == end {
+            return None;
+        }
+        let minute = u16::from(now.hour()) * 60 + u16::from(now.minute());
+        let inside = if start < end {
+            (start..end).contains(&minute)
+        } else {
+            // Overnight window, e.g. 23:00-07:00
+            minute >= start || minute < end
+        };
+        if !inside {
+            return None;
+        }
+        let date = if minute >= end {
+            now.date().next_day()?
+        } else {
+            now.date()
+        };
+        date.with_hms(self.end.hour, self.end.minute, 0)
+            .ok()
+            .map(|end| end.assume_offset(now.offset()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::{Date, Month, UtcOffset};
+
+    fn at(day: u8, hour: u8, minute: u8) -> OffsetDateTime {
+        Date::from_calendar_date(2025, Month::January, day)
+            .unwrap()
+            .with_hms(hour, minute, 0)
+            .unwrap()
+            .assume_offset(UtcOffset::from_hms(2, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn test_quiet_hours_window() {
+        let night: QuietHoursConfig =
+            toml::from_str("enabled = true\nstart = \"23:00\"\nend = \"7:00\"").unwrap();
+        assert_eq!(night.end.to_string(), "07:00");
+        assert_eq!(night.sleeping_until(at(6, 22, 59)), None);
+        assert_eq!(night.sleeping_until(at(6, 23, 0)), Some(at(7, 7, 0)));
+        assert_eq!(night.sleeping_until(at(7, 3, 30)), Some(at(7, 7, 0)));
+        assert_eq!(night.sleeping_until(at(7, 7, 0)), None);
+
+        let lunch = QuietHoursConfig {
+            enabled: true,
+            start: "12:00".parse().unwrap(),
+            end: "13:30".parse().unwrap(),
+        };
+        assert_eq!(lunch.sleeping_until(at(6, 12, 45)), Some(at(6, 13, 30)));
+        assert_eq!(lunch.sleeping_until(at(6, 23, 0)), None);
+
+        let off = QuietHoursConfig {
+            enabled: false,
+            ..night
+        };
+        assert_eq!(off.sleeping_until(at(6, 23, 30)), None);
+        assert!("24:00".parse::<TimeOfDay>().is_err());
+        assert!("7am".parse::<TimeOfDay>().is_err());
+    }
+}
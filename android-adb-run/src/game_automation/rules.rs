@@ -0,0 +1,381 @@
+// Rules that combine template matches with UI elements before acting, from
+// `[[rules]]` in automation.toml:
+//
+//   [[rules]]
+//   name = "claim_reward"
+//   when = ["chest_icon seen", "text 'Claim' shown", "ad_banner gone"]
+//   action = "tap"              # tap what the first condition found, or "sequence"
+//   cooldown_secs = 30
+//
+// Template and color conditions are checked on the screenshot first; the UI
+// hierarchy is only dumped when a rule still needs it. Rules made only of color
+// conditions (see color_probe.rs) and `scene <name> seen|gone` (see scenes.rs) are
+// checked before template matching runs.
+use super::color_probe::ColorProbe;
+use super::match_image::DetectionResult;
+use crate::adb::{RgbFrame, UiNode, UiSelector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// One condition, written `<template> seen|gone`, `text '<text>' shown|gone`,
+/// `id <resource-id> shown|gone`, `scene <name> seen|gone` or as a `pixel`/`color` probe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum RuleCondition {
+    /// The template (name or label) was matched, or not
+    Template { name: String, seen: bool },
+    /// A UI element with this text / resource-id is on screen, or not
+    Element { selector: UiSelector, shown: bool },
+    /// A pixel or the average of an area has a color, or not
+    Color(ColorProbe),
+    /// The screenshot was classified as this `[scenes]` scene, or not
+    Scene { scene: String, seen: bool },
+}
+
+impl RuleCondition {
+    pub fn needs_ui(&self) -> bool {
+        matches!(self, Self::Element { .. })
+    }
+
+    /// Whether the condition holds on the screenshot; element conditions need `ui`
+    pub fn holds(&self, detection: &DetectionResult, frame: &RgbFrame, ui: &[UiNode]) -> bool {
+        match self {
+            Self::Template { name, seen } => detection.best_match_for(name).is_some() == *seen,
+            Self::Element { selector, shown } => selector.find(ui).is_some() == *shown,
+            Self::Color(probe) => probe.holds(frame),
+            Self::Scene { scene, seen } => (detection.scene.as_ref() == Some(scene)) == *seen,
+        }
+    }
+
+    /// Where the template matched, the element or the probed area is; `None`
+    /// for `gone` / `not` conditions
+    pub fn target(&self, detection: &DetectionResult, ui: &[UiNode]) -> Option<(u32, u32)> {
+        match self {
+            Self::Color(probe) => probe.target(),
+            Self::Template { name, seen: true } => detection
+                .best_match_for(name)
+                .map(|found| found.get_tap_coordinates()),
+            Self::Element {
+                selector,
+                shown: true,
+            } => selector.find(ui).map(UiNode::center),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RuleCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Template { name, seen } => {
+                write!(f, "{} {}", name, if *seen { "seen" } else { "gone" })
+            }
+            Self::Element { selector, shown } => {
+                let state = if *shown { "shown" } else { "gone" };
+                match (&selector.resource_id, &selector.text) {
+                    (Some(id), _) => write!(f, "id {} {}", id, state),
+                    (None, Some(text)) => write!(f, "text '{}' {}", text, state),
+                    (None, None) => write!(f, "text '' {}", state),
+                }
+            }
+            Self::Color(probe) => probe.fmt(f),
+            Self::Scene { scene, seen } => {
+                write!(f, "scene {} {}", scene, if *seen { "seen" } else { "gone" })
+            }
+        }
+    }
+}
+
+impl FromStr for RuleCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let syntax = || {
+            format!(
+                "'{}': expected '<template> seen|gone', \"text '<text>' shown|gone\", 'id <resource-id> shown|gone' or 'scene <name> seen|gone'",
+                s
+            )
+        };
+        if s.trim_start().starts_with("pixel ") || s.trim_start().starts_with("color ") {
+            return s.parse().map(Self::Color);
+        }
+        let (subject, state) = s.trim().rsplit_once(' ').ok_or_else(syntax)?;
+        let subject = subject.trim();
+        let element = |selector: UiSelector| match state {
+            "shown" => Ok(Self::Element {
+                selector,
+                shown: true,
+            }),
+            "gone" => Ok(Self::Element {
+                selector,
+                shown: false,
+            }),
+            _ => Err(syntax()),
+        };
+        if let Some(text) = subject.strip_prefix("text ") {
+            let text = text
+                .trim()
+                .strip_prefix('\'')
+                .and_then(|text| text.strip_suffix('\''))
+                .filter(|text| !text.is_empty())
+                .ok_or_else(syntax)?;
+            return element(UiSelector {
+                resource_id: None,
+                text: Some(text.to_string()),
+            });
+        }
+        if let Some(id) = subject.strip_prefix("id ") {
+            let id = id.trim();
+            if id.is_empty() || id.contains(char::is_whitespace) {
+                return Err(syntax());
+            }
+            return element(UiSelector {
+                resource_id: Some(id.to_string()),
+                text: None,
+            });
+        }
+        let seen = match state {
+            "seen" => true,
+            "gone" => false,
+            _ => return Err(syntax()),
+        };
+        if let Some(scene) = subject.strip_prefix("scene ") {
+            let scene = scene.trim();
+            if scene.is_empty() || scene.contains(char::is_whitespace) {
+                return Err(syntax());
+            }
+            return Ok(Self::Scene {
+                scene: scene.to_string(),
+                seen,
+            });
+        }
+        if subject.is_empty() || subject.contains(char::is_whitespace) {
+            return Err(syntax());
+        }
+        Ok(Self::Template {
+            name: subject.to_string(),
+            seen,
+        })
+    }
+}
+
+impl TryFrom<String> for RuleCondition {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<RuleCondition> for String {
+    fn from(condition: RuleCondition) -> Self {
+        condition.to_string()
+    }
+}
+
+/// What a rule does once all its conditions hold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    /// Tap where the first `seen`/`shown` condition found its template or element
+    #[default]
+    Tap,
+    /// Run `[sequences.<sequence>]`
+    Sequence,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    /// Every condition has to hold
+    pub when: Vec<RuleCondition>,
+    #[serde(default)]
+    pub action: RuleAction,
+    /// Sequence name for `action = "sequence"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<String>,
+    /// Seconds before the rule may act again
+    #[serde(default)]
+    pub cooldown_secs: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Rule {
+    /// Needs a UI dump to be evaluated
+    pub fn needs_ui(&self) -> bool {
+        self.when.iter().any(RuleCondition::needs_ui)
+    }
+
+    /// Only color and scene conditions, so it can be checked before template matching
+    pub fn before_matching(&self) -> bool {
+        !self.when.is_empty()
+            && self.when.iter().all(|condition| {
+                matches!(
+                    condition,
+                    RuleCondition::Color(_) | RuleCondition::Scene { .. }
+                )
+            })
+    }
+
+    /// The template and color conditions hold; cheap check before dumping the UI
+    pub fn screen_holds(&self, detection: &DetectionResult, frame: &RgbFrame) -> bool {
+        self.when
+            .iter()
+            .filter(|condition| !condition.needs_ui())
+            .all(|condition| condition.holds(detection, frame, &[]))
+    }
+
+    pub fn holds(&self, detection: &DetectionResult, frame: &RgbFrame, ui: &[UiNode]) -> bool {
+        !self.when.is_empty()
+            && self
+                .when
+                .iter()
+                .all(|condition| condition.holds(detection, frame, ui))
+    }
+
+    /// Tap point: what the first `seen`/`shown` condition found
+    pub fn target(&self, detection: &DetectionResult, ui: &[UiNode]) -> Option<(u32, u32)> {
+        self.when
+            .iter()
+            .find_map(|condition| condition.target(detection, ui))
+    }
+}
+
+/// When each rule last acted
+#[derive(Debug, Default)]
+pub struct RuleCooldowns {
+    last_acted: HashMap<String, Instant>,
+}
+
+impl RuleCooldowns {
+    pub fn ready(&self, rule: &Rule, now: Instant) -> bool {
+        self.last_acted.get(&rule.name).is_none_or(|last| {
+            now.saturating_duration_since(*last) >= Duration::from_secs(rule.cooldown_secs)
+        })
+    }
+
+    pub fn record(&mut self, rule: &Rule, now: Instant) {
+        self.last_acted.insert(rule.name.clone(), now);
+    }
+
+    /// Every rule may act again, e.g. on a new automation run
+    pub fn reset(&mut self) {
+        self.last_acted.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_automation::match_image::{
+        SearchRegion, Template, TemplateBehavior, TemplateCategory, TemplateMatch,
+    };
+
+    fn detection(templates: &[&str]) -> DetectionResult {
+        let mut result = DetectionResult::new();
+        for name in templates {
+            let template = Template {
+                path: format!("{}.png", name),
+                name: name.to_string(),
+                search_region: SearchRegion::new(0, 0, 1080, 2400, name.to_string()),
+                width: 40,
+                height: 20,
+                category: TemplateCategory::Unknown,
+                behavior: TemplateBehavior::default(),
+                source_size: None,
+            };
+            result
+                .matches
+                .push(TemplateMatch::new(template, 100, 200, 0.95, 1.0));
+        }
+        result
+    }
+
+    #[test]
+    fn test_rule_needs_template_and_ui_text() {
+        let rule: Rule = toml::from_str(
+            "name = \"claim\"\nwhen = [\"chest seen\", \"text 'Claim' shown\", \"id ad_close gone\"]\ncooldown_secs = 30",
+        )
+        .unwrap();
+        assert_eq!(rule.action, RuleAction::Tap);
+        assert!(rule.needs_ui());
+        assert_eq!(
+            rule.when[1],
+            RuleCondition::Element {
+                selector: UiSelector {
+                    resource_id: None,
+                    text: Some("Claim".into()),
+                },
+                shown: true,
+            }
+        );
+        let claim = UiNode {
+            text: "Claim".into(),
+            bounds: (400, 1800, 280, 120),
+            ..UiNode::default()
+        };
+
+        let chest = detection(&["chest"]);
+        let screen = RgbFrame {
+            width: 40,
+            height: 40,
+            pixels: vec![0; 40 * 40 * 3],
+        };
+        assert!(rule.screen_holds(&chest, &screen));
+        assert!(!rule.holds(&chest, &screen, &[]), "no Claim button");
+        assert!(rule.holds(&chest, &screen, std::slice::from_ref(&claim)));
+        assert_eq!(
+            rule.target(&chest, std::slice::from_ref(&claim)),
+            Some((120, 210)),
+            "the chest match comes first"
+        );
+        assert!(!rule.screen_holds(&detection(&[]), &screen));
+        assert!(!rule.before_matching());
+
+        let dark: Rule =
+            toml::from_str("name = \"dark\"\nwhen = [\"pixel 5,5 is #000000\", \"color 0,0,40,40 outside #101010..#FFFFFF\"]")
+                .unwrap();
+        assert!(dark.before_matching() && !dark.needs_ui());
+        assert!(dark.holds(&detection(&[]), &screen, &[]));
+        assert_eq!(dark.target(&detection(&[]), &[]), Some((5, 5)));
+
+        let in_battle: Rule = toml::from_str(
+            "name = \"battle\"\nwhen = [\"scene battle seen\", \"pixel 5,5 is #000000\"]",
+        )
+        .unwrap();
+        assert!(in_battle.before_matching());
+        let mut battle = detection(&[]);
+        assert!(!in_battle.holds(&battle, &screen, &[]));
+        battle.scene = Some("battle".to_string());
+        assert!(in_battle.holds(&battle, &screen, &[]));
+
+        let mut cooldowns = RuleCooldowns::default();
+        let now = Instant::now();
+        assert!(cooldowns.ready(&rule, now));
+        cooldowns.record(&rule, now);
+        assert!(!cooldowns.ready(&rule, now + Duration::from_secs(29)));
+        assert!(cooldowns.ready(&rule, now + Duration::from_secs(30)));
+
+        for text in [
+            "chest seen",
+            "text 'Claim' shown",
+            "id ad_close gone",
+            "pixel 5,5 is #000000 ~24",
+            "scene main_menu gone",
+        ] {
+            assert_eq!(text.parse::<RuleCondition>().unwrap().to_string(), text);
+        }
+        assert!("chest visible".parse::<RuleCondition>().is_err());
+        assert!("text Claim shown".parse::<RuleCondition>().is_err());
+        let saved = toml::to_string(&rule).unwrap();
+        assert_eq!(toml::from_str::<Rule>(&saved).unwrap(), rule);
+    }
+}
@@ -0,0 +1,193 @@
+// Automation runtime state kept across restarts: which timed events are enabled,
+// how often and when they last ran, and the current `[states]` game state.
+// Saved as JSON next to the config file, so restarting the app resumes every
+// schedule where it was instead of firing everything at once.
+use super::config::{DEFAULT_PROFILE, profile_path};
+use super::types::TimedEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub const RUNTIME_STATE_FILE: &str = "automation_state.json";
+/// How often running automation saves its state, in case the app is killed
+pub const RUNTIME_STATE_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Saved state of one timed event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventState {
+    pub enabled: bool,
+    pub execution_count: u64,
+    /// Wall-clock time of the last run, Unix milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_executed_unix_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeState {
+    /// Keyed by timed event id
+    pub events: BTreeMap<String, EventState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_state: Option<String>,
+}
+
+/// Runtime state file of profile `name`: `automation_state.json` in the config
+/// file's directory; other profiles keep theirs beside the profile file as
+/// `<name>.state.json`
+pub fn profile_state_path(name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        profile_path(name).with_file_name(RUNTIME_STATE_FILE)
+    } else {
+        profile_path(name).with_extension("state.json")
+    }
+}
+
+/// `device`'s own state file next to profile `name`'s, e.g.
+/// `automation_state.R58M123ABC.json`, so devices running side by side keep apart
+pub fn device_state_path(name: &str, device: &str) -> PathBuf {
+    let device: String = device
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    profile_state_path(name).with_extension(format!("{}.json", device))
+}
+
+fn unix_ms(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|since| since.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl RuntimeState {
+    /// Snapshot `events` and `custom_state` as of `now`
+    pub fn capture(
+        events: &HashMap<String, TimedEvent>,
+        custom_state: Option<String>,
+        now: SystemTime,
+    ) -> Self {
+        let events = events
+            .values()
+            .map(|event| {
+                let state = EventState {
+                    enabled: event.enabled,
+                    execution_count: event.execution_count,
+                    last_executed_unix_ms: event
+                        .last_executed
+                        .and_then(|last| now.checked_sub(last.elapsed()))
+                        .map(unix_ms),
+                };
+                (event.id.clone(), state)
+            })
+            .collect();
+        Self {
+            events,
+            custom_state,
+        }
+    }
+
+    /// Put the saved values back on the events that still exist, returning how
+    /// many were restored. Runs too long ago to place on the monotonic clock
+    /// count as never run, so those events are due at once.
+    pub fn apply(&self, events: &mut HashMap<String, TimedEvent>, now: SystemTime) -> usize {
+        let mut restored = 0;
+        for (id, saved) in &self.events {
+            let Some(event) = events.get_mut(id) else {
+                continue;
+            };
+            event.enabled = saved.enabled;
+            event.execution_count = saved.execution_count;
+            event.last_executed = saved.last_executed_unix_ms.and_then(|ms| {
+                let ago = Duration::from_millis(unix_ms(now).saturating_sub(ms));
+                Instant::now().checked_sub(ago)
+            });
+            event.next_fire = None;
+            restored += 1;
+        }
+        restored
+    }
+
+    /// Saved state at `path`; `Ok(None)` when nothing was saved yet
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to encode runtime state: {}", e))?;
+        std::fs::write(path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_state_round_trip() {
+        let now = SystemTime::now();
+        let mut events = HashMap::new();
+        let mut tap = TimedEvent::new_tap_seconds("claim_tap".into(), 10, 20, 600);
+        tap.mark_executed();
+        tap.mark_executed();
+        tap.last_executed = Instant::now().checked_sub(Duration::from_secs(120));
+        tap.enabled = false;
+        events.insert(tap.id.clone(), tap);
+
+        let state = RuntimeState::capture(&events, Some("Battle".into()), now);
+        let json = serde_json::to_string(&state).unwrap();
+        let loaded: RuntimeState = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, state);
+
+        // A fresh start from config, then restored
+        let mut fresh: HashMap<String, TimedEvent> = events
+            .keys()
+            .map(|id| {
+                (
+                    id.clone(),
+                    TimedEvent::new_tap_seconds(id.clone(), 10, 20, 600),
+                )
+            })
+            .collect();
+        assert_eq!(loaded.apply(&mut fresh, now), 1);
+        let tap = &fresh["claim_tap"];
+        assert!(!tap.enabled);
+        assert_eq!(tap.execution_count, 2);
+        let since = tap.last_executed.unwrap().elapsed().as_secs();
+        assert!((119..=121).contains(&since));
+        assert_eq!(loaded.custom_state.as_deref(), Some("Battle"));
+    }
+
+    #[test]
+    fn test_each_device_has_its_own_state_file() {
+        let shared = profile_state_path(DEFAULT_PROFILE);
+        let phone = device_state_path(DEFAULT_PROFILE, "R58M123ABC");
+        let wireless = device_state_path(DEFAULT_PROFILE, "192.168.1.20:5555");
+        assert_eq!(
+            phone,
+            shared.with_file_name("automation_state.R58M123ABC.json")
+        );
+        assert_eq!(
+            wireless,
+            shared.with_file_name("automation_state.192_168_1_20_5555.json")
+        );
+        assert_eq!(
+            device_state_path("farm", "18d1:4ee7"),
+            profile_path("farm").with_file_name("farm.state.18d1_4ee7.json")
+        );
+    }
+}
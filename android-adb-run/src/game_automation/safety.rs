@@ -0,0 +1,227 @@
+// Tap safety: rate limits and no-tap zones from `[safety]`, shared by every
+// automated tap path so a runaway timer or template can't hammer the screen.
+// Each device has its own limits, checked against its automation's config.
+use super::config::SafetyConfig;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+/// Why a tap was not sent
+#[derive(Debug, Clone, PartialEq)]
+pub enum TapBlocked {
+    /// The point lies in a configured no-tap zone
+    NoTapZone { zone: String, x: u32, y: u32 },
+    /// Less than `min_tap_delay_ms` since the last tap
+    TooSoon { wait: Duration },
+    /// `max_taps_per_minute` already reached
+    RateLimited { per_minute: u32, wait: Duration },
+}
+
+impl TapBlocked {
+    /// Rate limits clear on their own; the same tap is allowed a little later
+    pub fn is_transient(&self) -> bool {
+        !matches!(self, Self::NoTapZone { .. })
+    }
+}
+
+impl fmt::Display for TapBlocked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoTapZone { zone, x, y } => {
+                write!(f, "({}, {}) is in no-tap zone '{}'", x, y, zone)
+            }
+            Self::TooSoon { wait } => {
+                write!(
+                    f,
+                    "too soon after the last tap, wait {}ms",
+                    wait.as_millis()
+                )
+            }
+            Self::RateLimited { per_minute, wait } => write!(
+                f,
+                "{} taps/minute limit reached, wait {}s",
+                per_minute,
+                wait.as_secs_f32().ceil() as u64
+            ),
+        }
+    }
+}
+
+/// Taps allowed recently, for the per-minute and minimum-gap limits
+#[derive(Debug, Default)]
+pub struct TapLimiter {
+    recent: VecDeque<Instant>,
+    last: Option<Instant>,
+}
+
+impl TapLimiter {
+    /// Check a tap at `(x, y)` against `config` and count it when allowed
+    pub fn check(
+        &mut self,
+        config: &SafetyConfig,
+        (x, y): (u32, u32),
+        now: Instant,
+    ) -> Result<(), TapBlocked> {
+        check_zones(config, (x, y))?;
+
+        if let Some(last) = self.last {
+            let gap = Duration::from_millis(config.min_tap_delay_ms);
+            let since = now.saturating_duration_since(last);
+            if since < gap {
+                return Err(TapBlocked::TooSoon { wait: gap - since });
+            }
+        }
+
+        while let Some(&oldest) = self.recent.front()
+            && now.saturating_duration_since(oldest) >= MINUTE
+        {
+            self.recent.pop_front();
+        }
+        if config.max_taps_per_minute > 0
+            && self.recent.len() >= config.max_taps_per_minute as usize
+            && let Some(&oldest) = self.recent.front()
+        {
+            return Err(TapBlocked::RateLimited {
+                per_minute: config.max_taps_per_minute,
+                wait: MINUTE - now.saturating_duration_since(oldest),
+            });
+        }
+
+        self.recent.push_back(now);
+        self.last = Some(now);
+        Ok(())
+    }
+}
+
+/// `(x, y)` outside every no-tap zone of `config`
+fn check_zones(config: &SafetyConfig, (x, y): (u32, u32)) -> Result<(), TapBlocked> {
+    match config.zone_at(x, y) {
+        Some(zone) => Err(TapBlocked::NoTapZone {
+            zone: zone.name.clone(),
+            x,
+            y,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Limits of each device, by `Device::key`
+static LIMITERS: LazyLock<Mutex<HashMap<String, TapLimiter>>> = LazyLock::new(Default::default);
+
+/// Check an automated tap on `device` against `config`, the `[safety]` of the
+/// automation driving it; allowed taps count towards that device's limits, so
+/// only call this right before tapping
+pub fn allow_tap(device: &str, config: &SafetyConfig, x: u32, y: u32) -> Result<(), TapBlocked> {
+    LIMITERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(device.to_string())
+        .or_default()
+        .check(config, (x, y), Instant::now())
+}
+
+/// `allow_tap` for step-by-step runners (sequences, scripts): sits out the
+/// minimum gap after the previous tap instead of failing on it
+pub async fn wait_for_tap(
+    device: &str,
+    config: &SafetyConfig,
+    x: u32,
+    y: u32,
+) -> Result<(), TapBlocked> {
+    loop {
+        match allow_tap(device, config, x, y) {
+            Err(TapBlocked::TooSoon { wait }) => tokio::time::sleep(wait).await,
+            result => return result,
+        }
+    }
+}
+
+/// `wait_for_tap` for a gesture whose fingers touch down at `points` (pinch,
+/// multi-finger swipe): none may start in a no-tap zone, and it counts as one tap
+pub async fn wait_for_touch(
+    device: &str,
+    config: &SafetyConfig,
+    points: &[(u32, u32)],
+) -> Result<(), TapBlocked> {
+    let Some((&(x, y), others)) = points.split_first() else {
+        return Ok(());
+    };
+    for &point in others {
+        check_zones(config, point)?;
+    }
+    wait_for_tap(device, config, x, y).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_automation::config::NoTapZone;
+
+    #[test]
+    fn test_limits_and_no_tap_zones() {
+        let config = SafetyConfig {
+            max_taps_per_minute: 2,
+            min_tap_delay_ms: 500,
+            no_tap_zones: vec![NoTapZone {
+                name: "shop".into(),
+                x: 900,
+                y: 100,
+                width: 180,
+                height: 120,
+            }],
+        };
+        let mut limiter = TapLimiter::default();
+        let start = Instant::now();
+
+        assert!(matches!(
+            limiter.check(&config, (950, 150), start),
+            Err(TapBlocked::NoTapZone { ref zone, .. }) if zone == "shop"
+        ));
+        assert!(limiter.check(&config, (1080, 150), start).is_ok());
+        assert_eq!(
+            limiter.check(&config, (100, 100), start + Duration::from_millis(200)),
+            Err(TapBlocked::TooSoon {
+                wait: Duration::from_millis(300)
+            })
+        );
+        assert!(
+            limiter
+                .check(&config, (100, 100), start + Duration::from_secs(1))
+                .is_ok()
+        );
+        let blocked = limiter
+            .check(&config, (100, 100), start + Duration::from_secs(10))
+            .unwrap_err();
+        assert!(blocked.is_transient());
+        assert_eq!(
+            blocked,
+            TapBlocked::RateLimited {
+                per_minute: 2,
+                wait: Duration::from_secs(50)
+            }
+        );
+        assert!(
+            limiter
+                .check(&config, (100, 100), start + Duration::from_secs(60))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_each_device_has_its_own_limits() {
+        let config = SafetyConfig {
+            max_taps_per_minute: 1,
+            min_tap_delay_ms: 0,
+            no_tap_zones: Vec::new(),
+        };
+        assert!(allow_tap("safety-test-a", &config, 10, 10).is_ok());
+        assert!(allow_tap("safety-test-b", &config, 10, 10).is_ok());
+        assert!(matches!(
+            allow_tap("safety-test-a", &config, 10, 10),
+            Err(TapBlocked::RateLimited { per_minute: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,237 @@
+// Scene classification: which screen the game shows ("main_menu", "battle",
+// "loading"), from a coarse color histogram and an average hash of the screenshot
+// compared with labeled reference screenshots. It takes a few milliseconds and
+// runs before template matching, so `[states]` and `[[rules]]` can use the scene:
+//
+//   [scenes]
+//   enabled = true
+//   dir = "scenes"          # scenes/battle.png, or several in scenes/battle/*.png
+//   min_score = 0.85
+//
+//   [states]
+//   transitions = ["* -> Battle when scene battle"]
+use crate::adb::RgbFrame;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Levels per color channel in the histogram (4 x 4 x 4 bins)
+const LEVELS: usize = 4;
+/// Average hash edge; 8x8 cells give 64 bits
+const HASH_SIZE: u32 = 8;
+/// Pixels sampled per row and column for the histogram
+const SAMPLES: u32 = 64;
+
+/// `[scenes]` in automation.toml
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScenesConfig {
+    pub enabled: bool,
+    /// Reference screenshots: `<dir>/<scene>.png` or `<dir>/<scene>/*.png`
+    pub dir: String,
+    /// Least similarity (0-1) to the closest reference for a scene to be named
+    pub min_score: f32,
+}
+
+impl Default for ScenesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: "scenes".to_string(),
+            min_score: 0.85,
+        }
+    }
+}
+
+/// Coarse summary of a screenshot: what colors it has and roughly where it is light
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneSignature {
+    /// Share of sampled pixels per color bin, adds up to 1
+    histogram: Vec<f32>,
+    /// Bit set where the 8x8 cell is lighter than the screen's average
+    hash: u64,
+}
+
+impl SceneSignature {
+    pub fn from_frame(frame: &RgbFrame) -> Self {
+        let (width, height) = (frame.width.max(1), frame.height.max(1));
+        let pixel = |x: u32, y: u32| {
+            let i = ((y * frame.width + x) * 3) as usize;
+            frame
+                .pixels
+                .get(i..i + 3)
+                .map_or([0; 3], |p| [p[0], p[1], p[2]])
+        };
+
+        let mut histogram = vec![0f32; LEVELS * LEVELS * LEVELS];
+        let mut cells = [0u64; (HASH_SIZE * HASH_SIZE) as usize];
+        let mut cell_counts = [0u64; (HASH_SIZE * HASH_SIZE) as usize];
+        for row in 0..SAMPLES {
+            for col in 0..SAMPLES {
+                let (x, y) = (col * width / SAMPLES, row * height / SAMPLES);
+                let [r, g, b] = pixel(x, y);
+                let bin = |v: u8| v as usize * LEVELS / 256;
+                histogram[(bin(r) * LEVELS + bin(g)) * LEVELS + bin(b)] += 1.0;
+                let cell =
+                    (row * HASH_SIZE / SAMPLES * HASH_SIZE + col * HASH_SIZE / SAMPLES) as usize;
+                cells[cell] += (r as u64 * 299 + g as u64 * 587 + b as u64 * 114) / 1000;
+                cell_counts[cell] += 1;
+            }
+        }
+        let total = (SAMPLES * SAMPLES) as f32;
+        histogram.iter_mut().for_each(|share| *share /= total);
+
+        let lightness: Vec<u64> = cells
+            .iter()
+            .zip(&cell_counts)
+            .map(|(sum, count)| sum / (*count).max(1))
+            .collect();
+        let average = lightness.iter().sum::<u64>() / lightness.len() as u64;
+        let hash = lightness
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value > average)
+            .fold(0u64, |hash, (i, _)| hash | 1 << i);
+        Self { histogram, hash }
+    }
+
+    /// 0-1: the histogram overlap and the share of equal hash bits, averaged
+    pub fn similarity(&self, other: &Self) -> f32 {
+        let overlap: f32 = self
+            .histogram
+            .iter()
+            .zip(&other.histogram)
+            .map(|(a, b)| a.min(*b))
+            .sum();
+        let same_bits = 64 - (self.hash ^ other.hash).count_ones();
+        (overlap + same_bits as f32 / 64.0) / 2.0
+    }
+}
+
+/// Best reference scene for a screenshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneMatch {
+    pub scene: String,
+    pub score: f32,
+}
+
+/// Labeled reference signatures
+#[derive(Debug, Clone, Default)]
+pub struct SceneClassifier {
+    references: Vec<(String, SceneSignature)>,
+}
+
+impl SceneClassifier {
+    pub fn add(&mut self, scene: impl Into<String>, frame: &RgbFrame) {
+        self.references
+            .push((scene.into(), SceneSignature::from_frame(frame)));
+    }
+
+    /// Read `<dir>/<scene>.png` and `<dir>/<scene>/*.png`
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let mut classifier = Self::default();
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+        let is_png = |path: &Path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+        };
+        for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+            let Some(stem) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            let files: Vec<_> = if path.is_dir() {
+                std::fs::read_dir(&path)
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|file| is_png(file))
+                    .collect()
+            } else if is_png(&path) {
+                vec![path]
+            } else {
+                continue;
+            };
+            for file in files {
+                let image = image::open(&file)
+                    .map_err(|e| format!("Failed to open {}: {}", file.display(), e))?
+                    .to_rgb8();
+                let frame = RgbFrame {
+                    width: image.width(),
+                    height: image.height(),
+                    pixels: image.into_raw(),
+                };
+                classifier.add(stem.clone(), &frame);
+            }
+        }
+        Ok(classifier)
+    }
+
+    pub fn len(&self) -> usize {
+        self.references.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.references.is_empty()
+    }
+
+    /// The closest reference scene, if it scores at least `min_score`
+    pub fn classify(&self, frame: &RgbFrame, min_score: f32) -> Option<SceneMatch> {
+        let signature = SceneSignature::from_frame(frame);
+        self.references
+            .iter()
+            .map(|(scene, reference)| SceneMatch {
+                scene: scene.clone(),
+                score: signature.similarity(reference),
+            })
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+            .filter(|best| best.score >= min_score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `top` color over the upper `split` rows, `bottom` below
+    fn screen(top: [u8; 3], bottom: [u8; 3], split: u32) -> RgbFrame {
+        let (width, height) = (90, 160);
+        RgbFrame {
+            width,
+            height,
+            pixels: (0..width * height)
+                .flat_map(|i| if i / width < split { top } else { bottom })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_classifies_by_colors_and_layout() {
+        let mut classifier = SceneClassifier::default();
+        classifier.add("main_menu", &screen([20, 40, 160], [240, 240, 240], 40));
+        classifier.add("battle", &screen([200, 30, 30], [30, 120, 30], 120));
+        classifier.add("loading", &screen([0, 0, 0], [0, 0, 0], 0));
+
+        // Same menu with the banner a little taller
+        let menu = classifier
+            .classify(&screen([24, 44, 150], [235, 235, 235], 48), 0.85)
+            .unwrap();
+        assert_eq!(menu.scene, "main_menu");
+        assert!(menu.score > 0.85 && menu.score < 1.0);
+        assert_eq!(
+            classifier
+                .classify(&screen([200, 30, 30], [30, 120, 30], 120), 0.85)
+                .map(|found| found.scene),
+            Some("battle".to_string())
+        );
+        // Same colors as the menu, swapped around
+        assert!(
+            classifier
+                .classify(&screen([240, 240, 240], [20, 40, 160], 120), 0.85)
+                .is_none()
+        );
+    }
+}
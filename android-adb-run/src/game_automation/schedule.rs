@@ -0,0 +1,211 @@
+// Wall-clock schedules for timed events ("daily at 08:00", "mon,fri at 07:30,19:00")
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use time::{Date, OffsetDateTime, Weekday};
+
+const DAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+const FULL_DAY_NAMES: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+const ALL_DAYS: u8 = 0b111_1111;
+const WEEKDAYS: u8 = 0b001_1111;
+const WEEKENDS: u8 = 0b110_0000;
+
+/// Days of the week and times of day an event fires, in device local time.
+///
+/// Written as `<days> at <HH:MM>[,<HH:MM>...]` where `<days>` is `daily`,
+/// `weekdays`, `weekends` or a comma list such as `mon,wed,fri`.
+/// `at 08:00` on its own means every day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Schedule {
+    /// Bit 0 = Monday ... bit 6 = Sunday
+    days: u8,
+    /// Sorted `(hour, minute)` pairs
+    times: Vec<(u8, u8)>,
+}
+
+impl Schedule {
+    fn new(days: u8, mut times: Vec<(u8, u8)>) -> Result<Self, String> {
+        if days & ALL_DAYS == 0 {
+            return Err("schedule needs at least one day".to_string());
+        }
+        if times.is_empty() {
+            return Err("schedule needs at least one time".to_string());
+        }
+        if let Some((h, m)) = times.iter().find(|(h, m)| *h > 23 || *m > 59) {
+            return Err(format!("invalid time {:02}:{:02}", h, m));
+        }
+        times.sort_unstable();
+        times.dedup();
+        Ok(Self {
+            days: days & ALL_DAYS,
+            times,
+        })
+    }
+
+    pub fn runs_on(&self, weekday: Weekday) -> bool {
+        self.days & (1 << weekday.number_days_from_monday()) != 0
+    }
+
+    /// First fire time strictly after `now`, in the same UTC offset as `now`
+    pub fn next_after(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        let today = now.date();
+        // A week ahead always reaches a scheduled day; day 7 covers "today, earlier time"
+        (0..=7)
+            .filter_map(|offset| today.checked_add(time::Duration::days(offset)))
+            .filter(|date| self.runs_on(date.weekday()))
+            .flat_map(|date: Date| {
+                self.times.iter().filter_map(move |&(hour, minute)| {
+                    date.with_hms(hour, minute, 0)
+                        .ok()
+                        .map(|dt| dt.assume_offset(now.offset()))
+                })
+            })
+            .find(|candidate| *candidate > now)
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.days {
+            ALL_DAYS => f.write_str("daily")?,
+            WEEKDAYS => f.write_str("weekdays")?,
+            WEEKENDS => f.write_str("weekends")?,
+            days => {
+                let names: Vec<&str> = DAY_NAMES
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| days & (1 << i) != 0)
+                    .map(|(_, name)| *name)
+                    .collect();
+                f.write_str(&names.join(","))?;
+            }
+        }
+        let times: Vec<String> = self
+            .times
+            .iter()
+            .map(|(h, m)| format!("{:02}:{:02}", h, m))
+            .collect();
+        write!(f, " at {}", times.join(","))
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        let (days_part, times_part) = match lower.split_once(" at ") {
+            Some((days, times)) => (days.trim(), times.trim()),
+            None => match lower.strip_prefix("at ") {
+                Some(times) => ("daily", times.trim()),
+                None => return Err(format!("expected '<days> at HH:MM', got '{}'", s.trim())),
+            },
+        };
+
+        let days = match days_part {
+            "" | "daily" | "every day" => ALL_DAYS,
+            "weekdays" => WEEKDAYS,
+            "weekends" => WEEKENDS,
+            list => list.split(',').try_fold(0u8, |mask, name| {
+                let name = name.trim();
+                DAY_NAMES
+                    .iter()
+                    .zip(FULL_DAY_NAMES)
+                    .position(|(short, full)| name == *short || name == full)
+                    .map(|i| mask | (1 << i))
+                    .ok_or_else(|| format!("unknown day '{}'", name))
+            })?,
+        };
+
+        let times = times_part
+            .split(',')
+            .map(|t| {
+                let t = t.trim();
+                let (h, m) = t
+                    .split_once(':')
+                    .ok_or_else(|| format!("expected HH:MM, got '{}'", t))?;
+                let hour = h
+                    .parse::<u8>()
+                    .map_err(|_| format!("bad hour in '{}'", t))?;
+                let minute = m
+                    .parse::<u8>()
+                    .map_err(|_| format!("bad minute in '{}'", t))?;
+                Ok((hour, minute))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Self::new(days, times)
+    }
+}
+
+impl TryFrom<String> for Schedule {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Schedule> for String {
+    fn from(schedule: Schedule) -> Self {
+        schedule.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::{Month, UtcOffset};
+
+    fn at(day: u8, hour: u8, minute: u8, offset_hours: i8) -> OffsetDateTime {
+        // January 2025; the 6th is a Monday
+        Date::from_calendar_date(2025, Month::January, day)
+            .unwrap()
+            .with_hms(hour, minute, 0)
+            .unwrap()
+            .assume_offset(UtcOffset::from_hms(offset_hours, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn test_schedule_parse_and_display() {
+        let daily: Schedule = "daily at 8:00".parse().unwrap();
+        assert_eq!(daily.to_string(), "daily at 08:00");
+        assert_eq!("at 08:00".parse::<Schedule>().unwrap(), daily);
+
+        let custom: Schedule = "Fri,Mon at 19:00, 07:30".parse().unwrap();
+        assert_eq!(custom.to_string(), "mon,fri at 07:30,19:00");
+        assert_eq!(
+            "weekdays at 06:45".parse::<Schedule>().unwrap().to_string(),
+            "weekdays at 06:45"
+        );
+
+        assert!("daily at 24:00".parse::<Schedule>().is_err());
+        assert!("someday at 08:00".parse::<Schedule>().is_err());
+        assert!("08:00".parse::<Schedule>().is_err());
+    }
+
+    #[test]
+    fn test_schedule_next_after() {
+        let daily: Schedule = "daily at 08:00".parse().unwrap();
+        let before = at(6, 7, 59, 13);
+        assert_eq!(daily.next_after(before), Some(at(6, 8, 0, 13)));
+        // Exactly at the fire time moves on to tomorrow
+        assert_eq!(daily.next_after(at(6, 8, 0, 13)), Some(at(7, 8, 0, 13)));
+
+        let weekends: Schedule = "weekends at 10:00,18:00".parse().unwrap();
+        assert_eq!(weekends.next_after(at(6, 12, 0, 0)), Some(at(11, 10, 0, 0)));
+        assert_eq!(
+            weekends.next_after(at(12, 18, 30, 0)),
+            Some(at(18, 10, 0, 0))
+        );
+    }
+}
@@ -0,0 +1,905 @@
+// Automation scripts - Rhai scripts that run as timed events with device bindings.
+//
+// Every `*.rhai` file in `automation_scripts/` becomes a timed event named
+// `script:<file stem>`. Files are re-read when they change on disk, so a script
+// can be edited while automation keeps running.
+//
+// Bindings available to scripts (x/y may be pixels or screen percentages, "54%"):
+//     tap(x, y)
+//     swipe(x1, y1, x2, y2) / swipe(x1, y1, x2, y2, ms)
+//     long_press(x, y, ms)
+//     pinch(cx, cy, start_d, end_d, ms)  two fingers from start_d to end_d apart (zoom in when end_d > start_d)
+//     drag(x1, y1, x2, y2, hold_ms, move_ms)   press, hold still, move slowly, release
+//     multi_swipe([[[x, y], [x, y]], [[x, y], [x, y]]], ms)   one waypoint list per finger
+//     text("user@example.com")       type into the focused field
+//     key(KEY_BACK)                  key event (KEY_BACK, KEY_HOME, KEY_ENTER or a keycode)
+//     clipboard()                    text on the device clipboard
+//     set_clipboard("REF-1234")      put text on the device clipboard, e.g. to paste a code
+//     shell("getprop ro.product.model") / shell(cmd, timeout_ms)   #{ stdout, stderr, exit_code }
+//     screenshot()                   capture a fresh screen for matching
+//     find("template")               #{ x, y, confidence, scale } of the best match, or ()
+//     matched("template")            true when the template is on screen
+//     find_text("Claim")             #{ text, x, y, value } of the first line containing it, or ()
+//     read_text()                    every text line on screen (needs the `ocr` feature)
+//     counter("energy")              latest `[counters]` reading, or () before the first
+//     sleep(ms)
+//
+// The run interval comes from a `// interval: <seconds>` comment (default 60s).
+
+use super::config::SafetyConfig;
+use super::coord::{Coord, resolve_point};
+use super::history::{self, HistoryAction, HistoryEntry};
+use super::match_image::{
+    DetectionResult, GameStateDetector, TextRegion, find_text, shared_detector,
+};
+use super::safety::{TapBlocked, wait_for_touch};
+use super::sequence::WAIT_FOR_TEMPLATE_POLL;
+use super::types::{MAX_TAP_INTERVAL_SECONDS, MIN_TAP_INTERVAL_SECONDS};
+use super::wait::{WaitError, wait_for_template};
+use crate::adb::gesture::pinch_paths;
+use crate::adb::types::{KEYCODE_BACK, KEYCODE_ENTER, KEYCODE_HOME};
+use crate::adb::{AdbBackend, AdbClient, TouchPath};
+use rhai::{AST, Dynamic, Engine, EvalAltResult, Scope};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::runtime::Handle;
+use tokio::sync::Mutex;
+
+pub const SCRIPTS_DIR: &str = "automation_scripts";
+pub const SCRIPT_EVENT_PREFIX: &str = "script:";
+
+const DEFAULT_SCRIPT_INTERVAL_SECONDS: u64 = 60;
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(60);
+/// `shell(cmd)` without a timeout of its own
+const SCRIPT_SHELL_TIMEOUT: Duration = Duration::from_secs(10);
+const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Timed event id used for the script called `name`
+pub fn script_event_id(name: &str) -> String {
+    format!("{}{}", SCRIPT_EVENT_PREFIX, name)
+}
+
+/// Read `// interval: <seconds>` from the leading comment block
+pub fn parse_script_interval(source: &str) -> Option<u64> {
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with("//"))
+        .find_map(|line| {
+            let value = line
+                .trim_start_matches('/')
+                .trim()
+                .strip_prefix("interval:")?;
+            value.trim().trim_end_matches('s').parse().ok()
+        })
+}
+
+/// What changed on disk since the previous scan
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptChange {
+    Loaded { name: String, interval: Duration },
+    Removed { name: String },
+    Failed { name: String, error: String },
+}
+
+struct LoadedScript {
+    ast: Arc<AST>,
+    modified: Option<SystemTime>,
+}
+
+/// Everything a script run needs from the FSM
+pub struct ScriptContext {
+    pub client: Arc<Mutex<AdbBackend>>,
+    /// Detector with the templates already loaded
+    pub detector: Arc<GameStateDetector>,
+    /// Latest value of each `[counters]` region
+    pub counters: HashMap<String, i64>,
+    /// `[safety]` limits every touch waits for
+    pub safety: SafetyConfig,
+}
+
+/// Result of a finished script run
+#[derive(Debug, Default)]
+pub struct ScriptOutcome {
+    /// Last screenshot the script captured, if any
+    pub screenshot: Option<Vec<u8>>,
+    /// Number of taps and swipes sent to the device
+    pub actions: u32,
+}
+
+/// Loads scripts from a directory and keeps them in sync with the files
+pub struct ScriptManager {
+    dir: PathBuf,
+    engine: Engine,
+    scripts: HashMap<String, LoadedScript>,
+    // Broken files are remembered by timestamp so each edit is reported once
+    failed: HashMap<String, Option<SystemTime>>,
+    last_scan: Option<Instant>,
+}
+
+impl ScriptManager {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            engine: Engine::new(),
+            scripts: HashMap::new(),
+            failed: HashMap::new(),
+            last_scan: None,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn is_loaded(&self, name: &str) -> bool {
+        self.scripts.contains_key(name)
+    }
+
+    /// Compare the directory with loaded scripts; throttled unless `force` is set
+    pub fn rescan(&mut self, force: bool) -> Vec<ScriptChange> {
+        if !force
+            && self
+                .last_scan
+                .is_some_and(|t| t.elapsed() < RESCAN_INTERVAL)
+        {
+            return Vec::new();
+        }
+        self.last_scan = Some(Instant::now());
+
+        let mut paths: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        paths.sort();
+
+        let mut changes = Vec::new();
+        let mut seen = HashSet::new();
+        for path in paths {
+            let Some(name) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            seen.insert(name.clone());
+
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+            let unchanged = self
+                .scripts
+                .get(&name)
+                .is_some_and(|s| s.modified == modified)
+                || self.failed.get(&name) == Some(&modified);
+            if unchanged {
+                continue;
+            }
+
+            match self.compile(&path) {
+                Ok((ast, interval)) => {
+                    self.failed.remove(&name);
+                    self.scripts.insert(
+                        name.clone(),
+                        LoadedScript {
+                            ast: Arc::new(ast),
+                            modified,
+                        },
+                    );
+                    changes.push(ScriptChange::Loaded { name, interval });
+                }
+                Err(error) => {
+                    // A previously loaded version keeps running until the file is fixed
+                    self.failed.insert(name.clone(), modified);
+                    changes.push(ScriptChange::Failed { name, error });
+                }
+            }
+        }
+
+        let mut removed: Vec<String> = self
+            .scripts
+            .keys()
+            .filter(|name| !seen.contains(*name))
+            .cloned()
+            .collect();
+        removed.sort();
+        for name in removed {
+            self.scripts.remove(&name);
+            changes.push(ScriptChange::Removed { name });
+        }
+        self.failed.retain(|name, _| seen.contains(name));
+
+        changes
+    }
+
+    fn compile(&self, path: &Path) -> Result<(AST, Duration), String> {
+        let source = fs::read_to_string(path).map_err(|e| format!("Failed to read: {}", e))?;
+        let ast = self.engine.compile(&source).map_err(|e| e.to_string())?;
+        let interval = parse_script_interval(&source)
+            .unwrap_or(DEFAULT_SCRIPT_INTERVAL_SECONDS)
+            .clamp(MIN_TAP_INTERVAL_SECONDS, MAX_TAP_INTERVAL_SECONDS);
+        Ok((ast, Duration::from_secs(interval)))
+    }
+
+    /// Run a loaded script on a blocking thread (bindings wait on the async device)
+    pub async fn run(&self, name: &str, context: ScriptContext) -> Result<ScriptOutcome, String> {
+        let ast = self
+            .scripts
+            .get(name)
+            .map(|script| script.ast.clone())
+            .ok_or_else(|| format!("Script '{}' is not loaded", name))?;
+        let runtime = Handle::current();
+        let name = name.to_string();
+
+        tokio::task::spawn_blocking(move || run_blocking(&name, &ast, context, runtime))
+            .await
+            .map_err(|e| format!("Script task failed: {}", e))?
+    }
+}
+
+#[derive(Default)]
+struct ScriptState {
+    screenshot: Option<Vec<u8>>,
+    detection: Option<DetectionResult>,
+    text: Option<Vec<TextRegion>>,
+    actions: u32,
+}
+
+/// Device handle shared by the registered script functions
+#[derive(Clone)]
+struct ScriptDevice {
+    /// `script:<name>`, the source of this script's history entries
+    source: String,
+    /// `Device::key` the history entries are recorded on
+    device: String,
+    client: Arc<Mutex<AdbBackend>>,
+    runtime: Handle,
+    state: Arc<StdMutex<ScriptState>>,
+    detector: Arc<GameStateDetector>,
+    counters: Arc<HashMap<String, i64>>,
+    safety: Arc<SafetyConfig>,
+}
+
+impl ScriptDevice {
+    /// `(x, y)` in pixels; percentages are taken of the device screen
+    fn point(&self, x: &Dynamic, y: &Dynamic) -> ScriptResult<(u32, u32)> {
+        let point = (script_coord(x)?, script_coord(y)?);
+        let screen = if point.0.is_percent() || point.1.is_percent() {
+            self.runtime
+                .block_on(async { self.client.lock().await.screen_dimensions() })
+        } else {
+            (0, 0)
+        };
+        Ok(resolve_point(point, screen))
+    }
+
+    /// Wait until `[safety]` allows fingers touching down at `points`
+    fn wait_for_touch(&self, points: &[(u32, u32)]) -> Result<(), TapBlocked> {
+        self.runtime
+            .block_on(wait_for_touch(&self.device, &self.safety, points))
+    }
+
+    /// Wait until `[safety]` allows a touch at the entry's point; a blocked one is recorded
+    fn wait_for_entry(&self, entry: &HistoryEntry) -> ScriptResult<()> {
+        self.wait_for_fingers(entry, &[(entry.x, entry.y)])
+    }
+
+    /// `wait_for_entry` for a gesture with a finger touching down at each of `points`
+    fn wait_for_fingers(&self, entry: &HistoryEntry, points: &[(u32, u32)]) -> ScriptResult<()> {
+        self.wait_for_touch(points).map_err(|blocked| {
+            history::record(entry.clone().blocked(&blocked));
+            blocked.to_string().into()
+        })
+    }
+
+    fn tap(&self, x: &Dynamic, y: &Dynamic) -> ScriptResult<()> {
+        let (x, y) = self.point(x, y)?;
+        let entry =
+            HistoryEntry::new(HistoryAction::Tap, &self.source, (x, y)).on_device(&self.device);
+        self.wait_for_entry(&entry)?;
+        let result = self
+            .runtime
+            .block_on(async { self.client.lock().await.tap(x, y).await });
+        history::record(entry.with_result(&result));
+        result.map_err(|e| e.to_string())?;
+        self.state.lock().unwrap().actions += 1;
+        Ok(())
+    }
+
+    fn swipe(
+        &self,
+        from: (Dynamic, Dynamic),
+        to: (Dynamic, Dynamic),
+        duration_ms: Option<i64>,
+    ) -> ScriptResult<()> {
+        let (x1, y1) = self.point(&from.0, &from.1)?;
+        let (x2, y2) = self.point(&to.0, &to.1)?;
+        let duration = duration_ms.map(coordinate).transpose()?;
+        let entry = HistoryEntry::new(HistoryAction::Swipe, &self.source, (x1, y1))
+            .on_device(&self.device)
+            .with_detail(format!("to ({}, {})", x2, y2));
+        self.wait_for_entry(&entry)?;
+        let result = self.runtime.block_on(async {
+            let client = self.client.lock().await;
+            client.swipe(x1, y1, x2, y2, duration).await
+        });
+        history::record(entry.with_result(&result));
+        result.map_err(|e| e.to_string())?;
+        self.state.lock().unwrap().actions += 1;
+        Ok(())
+    }
+
+    fn text(&self, text: &str) -> ScriptResult<()> {
+        self.runtime
+            .block_on(async { self.client.lock().await.send_text(text).await })
+            .map_err(|e| e.to_string())?;
+        self.state.lock().unwrap().actions += 1;
+        Ok(())
+    }
+
+    fn clipboard(&self) -> ScriptResult<String> {
+        self.runtime
+            .block_on(async { self.client.lock().await.get_clipboard().await })
+            .map_err(|e| e.to_string().into())
+    }
+
+    fn set_clipboard(&self, text: &str) -> ScriptResult<()> {
+        self.runtime
+            .block_on(async { self.client.lock().await.set_clipboard(text).await })
+            .map_err(|e| e.to_string().into())
+    }
+
+    fn shell(&self, command: &str, timeout_ms: i64) -> ScriptResult<rhai::Map> {
+        let timeout = Duration::from_millis(coordinate(timeout_ms)? as u64);
+        let output = self
+            .runtime
+            .block_on(async {
+                self.client
+                    .lock()
+                    .await
+                    .run_shell(&[command], timeout)
+                    .await
+            })
+            .map_err(|e| e.to_string())?;
+        let mut map = rhai::Map::new();
+        map.insert("stdout".into(), Dynamic::from(output.stdout));
+        map.insert("stderr".into(), Dynamic::from(output.stderr));
+        map.insert("exit_code".into(), Dynamic::from(output.exit_code as i64));
+        Ok(map)
+    }
+
+    fn key(&self, keycode: i64) -> ScriptResult<()> {
+        let keycode = coordinate(keycode)?;
+        self.runtime
+            .block_on(async { self.client.lock().await.key_event(keycode).await })
+            .map_err(|e| e.to_string())?;
+        self.state.lock().unwrap().actions += 1;
+        Ok(())
+    }
+
+    fn long_press(&self, x: &Dynamic, y: &Dynamic, duration_ms: i64) -> ScriptResult<()> {
+        let (x, y) = self.point(x, y)?;
+        let duration = coordinate(duration_ms)?;
+        let entry = HistoryEntry::new(HistoryAction::Tap, &self.source, (x, y))
+            .on_device(&self.device)
+            .with_detail(format!("long press {}ms", duration));
+        self.wait_for_entry(&entry)?;
+        let result = self
+            .runtime
+            .block_on(async { self.client.lock().await.long_press(x, y, duration).await });
+        history::record(entry.with_result(&result));
+        result.map_err(|e| e.to_string())?;
+        self.state.lock().unwrap().actions += 1;
+        Ok(())
+    }
+
+    fn pinch(
+        &self,
+        center: (i64, i64),
+        distances: (i64, i64),
+        duration_ms: i64,
+    ) -> ScriptResult<()> {
+        let (cx, cy) = (coordinate(center.0)?, coordinate(center.1)?);
+        let (start, end) = (coordinate(distances.0)?, coordinate(distances.1)?);
+        let duration = coordinate(duration_ms)?;
+        let fingers = pinch_paths(cx, cy, start, end).map(|path| path[0]);
+        let entry = HistoryEntry::new(HistoryAction::Swipe, &self.source, fingers[0])
+            .on_device(&self.device)
+            .with_detail(format!(
+                "pinch at ({}, {}) from {} to {}px",
+                cx, cy, start, end
+            ));
+        self.wait_for_fingers(&entry, &fingers)?;
+        let result = self.runtime.block_on(async {
+            let client = self.client.lock().await;
+            client.pinch(cx, cy, start, end, duration).await
+        });
+        history::record(entry.with_result(&result));
+        result.map_err(|e| e.to_string())?;
+        self.state.lock().unwrap().actions += 1;
+        Ok(())
+    }
+
+    fn drag(
+        &self,
+        from: (Dynamic, Dynamic),
+        to: (Dynamic, Dynamic),
+        hold_ms: i64,
+        move_ms: i64,
+    ) -> ScriptResult<()> {
+        let (x1, y1) = self.point(&from.0, &from.1)?;
+        let (x2, y2) = self.point(&to.0, &to.1)?;
+        let (hold, duration) = (coordinate(hold_ms)?, coordinate(move_ms)?);
+        let entry = HistoryEntry::new(HistoryAction::Swipe, &self.source, (x1, y1))
+            .on_device(&self.device)
+            .with_detail(format!("drag to ({}, {})", x2, y2));
+        self.wait_for_entry(&entry)?;
+        let result = self.runtime.block_on(async {
+            let client = self.client.lock().await;
+            client.drag((x1, y1), (x2, y2), hold, duration).await
+        });
+        history::record(entry.with_result(&result));
+        result.map_err(|e| e.to_string())?;
+        self.state.lock().unwrap().actions += 1;
+        Ok(())
+    }
+
+    fn multi_swipe(&self, paths: rhai::Array, duration_ms: i64) -> ScriptResult<()> {
+        let paths = touch_paths(paths)?;
+        let duration = coordinate(duration_ms)?;
+        let fingers: Vec<(u32, u32)> = paths
+            .iter()
+            .filter_map(|path| path.first())
+            .copied()
+            .collect();
+        let Some(&first) = fingers.first() else {
+            return Err("multi_swipe needs at least one finger".into());
+        };
+        let entry = HistoryEntry::new(HistoryAction::Swipe, &self.source, first)
+            .on_device(&self.device)
+            .with_detail(format!("{} fingers", fingers.len()));
+        self.wait_for_fingers(&entry, &fingers)?;
+        let result = self
+            .runtime
+            .block_on(async { self.client.lock().await.multi_swipe(&paths, duration).await });
+        history::record(entry.with_result(&result));
+        result.map_err(|e| e.to_string())?;
+        self.state.lock().unwrap().actions += 1;
+        Ok(())
+    }
+
+    fn screenshot(&self) -> ScriptResult<bool> {
+        let bytes = self
+            .runtime
+            .block_on(async { self.client.lock().await.screen_capture_bytes().await })
+            .map_err(|e| e.to_string())?;
+        let mut state = self.state.lock().unwrap();
+        state.screenshot = Some(bytes);
+        state.detection = None;
+        state.text = None;
+        Ok(true)
+    }
+
+    /// Best match for `template` on the current screenshot (captured on first use)
+    fn find_match(&self, template: &str) -> ScriptResult<Option<(u32, u32, f32, f32)>> {
+        if self.state.lock().unwrap().screenshot.is_none() {
+            self.screenshot()?;
+        }
+
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        if state.detection.is_none() {
+            let bytes = state.screenshot.as_deref().unwrap_or_default();
+            state.detection = Some(self.detector.analyze_screenshot(bytes)?);
+        }
+
+        Ok(state.detection.as_ref().and_then(|detection| {
+            detection.best_match_for(template).map(|m| {
+                let (x, y) = m.get_tap_coordinates();
+                (x, y, m.confidence, m.scale_factor)
+            })
+        }))
+    }
+
+    fn find(&self, template: &str) -> ScriptResult<Dynamic> {
+        Ok(match self.find_match(template)? {
+            Some(found) => Dynamic::from(match_map(found)),
+            None => Dynamic::UNIT,
+        })
+    }
+
+    /// Poll `template`'s search area until it appears; () once `timeout` passes
+    fn wait_for(&self, template: &str, timeout: Duration) -> ScriptResult<Dynamic> {
+        let result = self.runtime.block_on(async {
+            wait_for_template(
+                &*self.client,
+                &self.detector,
+                template,
+                timeout,
+                WAIT_FOR_TEMPLATE_POLL,
+            )
+            .await
+        });
+        match result {
+            Ok(found) => {
+                let (x, y) = found.get_tap_coordinates();
+                Ok(Dynamic::from(match_map((
+                    x,
+                    y,
+                    found.confidence,
+                    found.scale_factor,
+                ))))
+            }
+            Err(WaitError::Timeout { .. }) => Ok(Dynamic::UNIT),
+            Err(e) => Err(e.to_string().into()),
+        }
+    }
+
+    /// Text lines on the current screenshot (captured on first use)
+    fn text_regions(&self) -> ScriptResult<Vec<TextRegion>> {
+        if self.state.lock().unwrap().screenshot.is_none() {
+            self.screenshot()?;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if state.text.is_none() {
+            let bytes = state.screenshot.as_deref().unwrap_or_default();
+            state.text = Some(shared_detector()?.recognize_screenshot(bytes)?);
+        }
+        Ok(state.text.clone().unwrap_or_default())
+    }
+
+    fn read_text(&self) -> ScriptResult<rhai::Array> {
+        Ok(self
+            .text_regions()?
+            .iter()
+            .map(|region| Dynamic::from(text_region_map(region)))
+            .collect())
+    }
+
+    fn find_text(&self, needle: &str) -> ScriptResult<Dynamic> {
+        Ok(match find_text(&self.text_regions()?, needle) {
+            Some(region) => Dynamic::from(text_region_map(region)),
+            None => Dynamic::UNIT,
+        })
+    }
+
+    fn counter(&self, name: &str) -> Dynamic {
+        self.counters
+            .get(name)
+            .map_or(Dynamic::UNIT, |value| Dynamic::from(*value))
+    }
+}
+
+/// `#{ x, y, confidence, scale }` for a template match
+fn match_map((x, y, confidence, scale): (u32, u32, f32, f32)) -> rhai::Map {
+    let mut map = rhai::Map::new();
+    map.insert("x".into(), Dynamic::from(x as i64));
+    map.insert("y".into(), Dynamic::from(y as i64));
+    map.insert("confidence".into(), Dynamic::from(confidence as f64));
+    map.insert("scale".into(), Dynamic::from(scale as f64));
+    map
+}
+
+/// `#{ text, x, y, value }` with the region center and its number (or ())
+fn text_region_map(region: &TextRegion) -> rhai::Map {
+    let (x, y) = region.center();
+    let mut map = rhai::Map::new();
+    map.insert("text".into(), Dynamic::from(region.text.clone()));
+    map.insert("x".into(), Dynamic::from(x as i64));
+    map.insert("y".into(), Dynamic::from(y as i64));
+    map.insert(
+        "value".into(),
+        region.number().map_or(Dynamic::UNIT, Dynamic::from),
+    );
+    map
+}
+
+fn coordinate(value: i64) -> ScriptResult<u32> {
+    u32::try_from(value).map_err(|_| format!("Value out of range: {}", value).into())
+}
+
+/// A script coordinate: an integer, or a percentage string like `"54%"`
+fn script_coord(value: &Dynamic) -> ScriptResult<Coord> {
+    if let Ok(px) = value.as_int() {
+        return coordinate(px).map(Coord::Px);
+    }
+    value
+        .clone()
+        .into_string()
+        .map_err(|_| "Coordinates must be integers or percentages like \"54%\"")?
+        .parse::<Coord>()
+        .map_err(Into::into)
+}
+
+/// `[[[x, y], ...], ...]` from a script: one list of `[x, y]` points per finger
+fn touch_paths(paths: rhai::Array) -> ScriptResult<Vec<TouchPath>> {
+    let point = |point: Dynamic| -> ScriptResult<(u32, u32)> {
+        let xy = point.try_cast::<rhai::Array>().unwrap_or_default();
+        match &xy[..] {
+            [x, y] => Ok((
+                coordinate(
+                    x.as_int()
+                        .map_err(|_| "Point coordinates must be integers")?,
+                )?,
+                coordinate(
+                    y.as_int()
+                        .map_err(|_| "Point coordinates must be integers")?,
+                )?,
+            )),
+            _ => Err("Each point must be [x, y]".into()),
+        }
+    };
+    paths
+        .into_iter()
+        .map(|path| {
+            path.try_cast::<rhai::Array>()
+                .ok_or_else(|| "Each finger must be a list of [x, y] points".into())
+                .and_then(|points| points.into_iter().map(&point).collect())
+        })
+        .collect()
+}
+
+fn run_blocking(
+    name: &str,
+    ast: &AST,
+    context: ScriptContext,
+    runtime: Handle,
+) -> Result<ScriptOutcome, String> {
+    let state = Arc::new(StdMutex::new(ScriptState::default()));
+    let device_key =
+        runtime.block_on(async { context.client.lock().await.device_key().to_string() });
+    let device = ScriptDevice {
+        source: format!("script:{}", name),
+        device: device_key,
+        client: context.client,
+        runtime,
+        state: state.clone(),
+        detector: context.detector,
+        counters: Arc::new(context.counters),
+        safety: Arc::new(context.safety),
+    };
+
+    let mut engine = Engine::new();
+    let deadline = Instant::now() + SCRIPT_TIMEOUT;
+    engine.on_progress(move |_| (Instant::now() > deadline).then_some(Dynamic::UNIT));
+    let prefix = name.to_string();
+    engine.on_print(move |text| log::info!("📜 [{}] {}", prefix, text));
+
+    let d = device.clone();
+    engine.register_fn("tap", move |x: Dynamic, y: Dynamic| d.tap(&x, &y));
+    let d = device.clone();
+    engine.register_fn(
+        "swipe",
+        move |x1: Dynamic, y1: Dynamic, x2: Dynamic, y2: Dynamic| d.swipe((x1, y1), (x2, y2), None),
+    );
+    let d = device.clone();
+    engine.register_fn(
+        "swipe",
+        move |x1: Dynamic, y1: Dynamic, x2: Dynamic, y2: Dynamic, ms: i64| {
+            d.swipe((x1, y1), (x2, y2), Some(ms))
+        },
+    );
+    let d = device.clone();
+    engine.register_fn("long_press", move |x: Dynamic, y: Dynamic, ms: i64| {
+        d.long_press(&x, &y, ms)
+    });
+    let d = device.clone();
+    engine.register_fn(
+        "pinch",
+        move |cx: i64, cy: i64, start_d: i64, end_d: i64, ms: i64| {
+            d.pinch((cx, cy), (start_d, end_d), ms)
+        },
+    );
+    let d = device.clone();
+    engine.register_fn(
+        "drag",
+        move |x1: Dynamic, y1: Dynamic, x2: Dynamic, y2: Dynamic, hold_ms: i64, move_ms: i64| {
+            d.drag((x1, y1), (x2, y2), hold_ms, move_ms)
+        },
+    );
+    let d = device.clone();
+    engine.register_fn("multi_swipe", move |paths: rhai::Array, ms: i64| {
+        d.multi_swipe(paths, ms)
+    });
+    let d = device.clone();
+    engine.register_fn("text", move |text: &str| d.text(text));
+    let d = device.clone();
+    engine.register_fn("key", move |keycode: i64| d.key(keycode));
+    let d = device.clone();
+    engine.register_fn("clipboard", move || d.clipboard());
+    let d = device.clone();
+    engine.register_fn("set_clipboard", move |text: &str| d.set_clipboard(text));
+    let d = device.clone();
+    engine.register_fn("shell", move |command: &str| {
+        d.shell(command, SCRIPT_SHELL_TIMEOUT.as_millis() as i64)
+    });
+    let d = device.clone();
+    engine.register_fn("shell", move |command: &str, timeout_ms: i64| {
+        d.shell(command, timeout_ms)
+    });
+    let d = device.clone();
+    engine.register_fn("screenshot", move || d.screenshot());
+    let d = device.clone();
+    engine.register_fn("find", move |template: &str| d.find(template));
+    let d = device.clone();
+    engine.register_fn("read_text", move || d.read_text());
+    let d = device.clone();
+    engine.register_fn("find_text", move |needle: &str| d.find_text(needle));
+    let d = device.clone();
+    engine.register_fn("counter", move |name: &str| d.counter(name));
+    let d = device.clone();
+    engine.register_fn("wait_for", move |template: &str, timeout_ms: i64| {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let timeout = Duration::from_millis(timeout_ms.max(0) as u64).min(remaining);
+        d.wait_for(template, timeout)
+    });
+    let d = device;
+    engine.register_fn("matched", move |template: &str| {
+        d.find_match(template).map(|m| m.is_some())
+    });
+    engine.register_fn("sleep", move |ms: i64| {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        std::thread::sleep(Duration::from_millis(ms.max(0) as u64).min(remaining));
+    });
+
+    let mut scope = Scope::new();
+    scope.push_constant("KEY_BACK", KEYCODE_BACK as i64);
+    scope.push_constant("KEY_HOME", KEYCODE_HOME as i64);
+    scope.push_constant("KEY_ENTER", KEYCODE_ENTER as i64);
+
+    engine
+        .run_ast_with_scope(&mut scope, ast)
+        .map_err(|e| match *e {
+            EvalAltResult::ErrorTerminated(..) => {
+                format!("Script '{}' timed out after {:?}", name, SCRIPT_TIMEOUT)
+            }
+            e => format!("Script '{}' failed: {}", name, e),
+        })?;
+
+    let mut state = state.lock().unwrap();
+    Ok(ScriptOutcome {
+        screenshot: state.screenshot.take(),
+        actions: state.actions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_automation::match_image::detector::template_name_matches;
+
+    #[test]
+    fn test_touch_paths_from_script() {
+        let engine = Engine::new();
+        let paths: rhai::Array = engine
+            .eval("[[[100, 500], [100, 200]], [[300, 500], [300, 200]]]")
+            .unwrap();
+        assert_eq!(
+            touch_paths(paths).unwrap(),
+            vec![vec![(100, 500), (100, 200)], vec![(300, 500), (300, 200)]]
+        );
+
+        let bad: rhai::Array = engine.eval("[[[100, 500, 1]]]").unwrap();
+        assert!(touch_paths(bad).is_err());
+        let negative: rhai::Array = engine.eval("[[[-1, 5]]]").unwrap();
+        assert!(touch_paths(negative).is_err());
+    }
+
+    #[test]
+    fn test_parse_script_interval() {
+        assert_eq!(
+            parse_script_interval("// interval: 30\ntap(1, 2);"),
+            Some(30)
+        );
+        assert_eq!(parse_script_interval("//   interval: 45s\n"), Some(45));
+        assert_eq!(parse_script_interval("tap(1, 2);\n// interval: 30"), None);
+        assert_eq!(parse_script_interval(""), None);
+    }
+
+    #[test]
+    fn test_template_name_matches_region_suffix() {
+        assert!(template_name_matches("claim_button", "claim_button"));
+        assert!(template_name_matches(
+            "claim_button[10,20,30,40]",
+            "claim_button"
+        ));
+        assert!(!template_name_matches("claim_button_old", "claim_button"));
+        assert!(template_name_matches(
+            "claim_button@1080x2400",
+            "claim_button"
+        ));
+    }
+
+    /// `drag` takes percentages like the other gestures and shows up in the history
+    #[cfg(feature = "mock")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_drag_resolves_points_and_records_history() {
+        use crate::adb::{MockAction, MockAdb};
+        use crate::game_automation::match_image::MatchConfig;
+
+        let dir = std::env::temp_dir().join(format!("adb_drag_script_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("drag_icon.rhai"),
+            r#"drag("10%", "50%", 900, "25%", 300, 800);"#,
+        )
+        .unwrap();
+        let mut manager = ScriptManager::new(&dir);
+        manager.rescan(true);
+        let _ = fs::remove_dir_all(&dir);
+
+        let mock = MockAdb::from_frames("mock", Vec::new());
+        let control = mock.control();
+        let context = ScriptContext {
+            client: Arc::new(Mutex::new(mock)),
+            detector: Arc::new(GameStateDetector::new(1080, 2400, MatchConfig::default())),
+            counters: HashMap::new(),
+            safety: SafetyConfig::default(),
+        };
+        let (_, mut recorded) = history::subscribe();
+        let outcome = manager.run("drag_icon", context).await.unwrap();
+
+        assert_eq!(outcome.actions, 1);
+        assert_eq!(
+            control.actions(),
+            [MockAction::Drag {
+                from: (108, 1200),
+                to: (900, 600)
+            }]
+        );
+        let entry = loop {
+            let entry = recorded.recv().await.unwrap();
+            if entry.source == "script:drag_icon" {
+                break entry;
+            }
+        };
+        assert_eq!(entry.action, HistoryAction::Swipe);
+        assert_eq!((entry.x, entry.y), (108, 1200));
+        assert_eq!(entry.detail, "drag to (900, 600)");
+        assert_eq!(entry.device, "mock");
+    }
+
+    #[test]
+    fn test_rescan_tracks_file_changes() {
+        let dir = std::env::temp_dir().join(format!("adb_scripts_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("daily.rhai"), "// interval: 20\ntap(1, 2);").unwrap();
+        fs::write(dir.join("broken.rhai"), "tap(1, ").unwrap();
+
+        let mut manager = ScriptManager::new(&dir);
+        let changes = manager.rescan(true);
+        assert!(matches!(&changes[0], ScriptChange::Failed { name, .. } if name == "broken"));
+        assert_eq!(
+            changes[1],
+            ScriptChange::Loaded {
+                name: "daily".into(),
+                interval: Duration::from_secs(20)
+            }
+        );
+        assert!(
+            manager.rescan(true).is_empty(),
+            "unchanged files are skipped"
+        );
+
+        fs::remove_file(dir.join("daily.rhai")).unwrap();
+        assert_eq!(
+            manager.rescan(true),
+            vec![ScriptChange::Removed {
+                name: "daily".into()
+            }]
+        );
+        assert!(!manager.is_loaded("daily"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
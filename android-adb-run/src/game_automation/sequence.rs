@@ -0,0 +1,521 @@
+// Multi-step action sequences (macros) from `[sequences.<name>]` in automation.toml
+use super::config::SafetyConfig;
+use super::coord::{Coord, resolve_point};
+use super::history::{self, HistoryAction, HistoryEntry};
+use super::match_image::GameStateDetector;
+use super::plugins::{self, ActionContext};
+use super::safety::wait_for_tap;
+use super::wait::{WaitError, wait_for_template};
+use crate::adb::{AdbClient, AdbError, UiSelector};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often `wait_for_template` re-captures the screen
+pub const WAIT_FOR_TEMPLATE_POLL: Duration = Duration::from_millis(500);
+
+/// Why a sequence stopped before its last step
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceFailure {
+    pub message: String,
+    /// The device connection was lost; the FSM reconnects before going on
+    pub disconnected: bool,
+}
+
+/// A failed step: a device command, or a template that never showed up
+#[derive(Debug)]
+enum StepError {
+    Device(AdbError),
+    Other(String),
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepError::Device(e) => write!(f, "{}", e),
+            StepError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Timed event id for a sequence that runs on an interval
+pub fn sequence_event_id(name: &str) -> String {
+    format!("sequence_{}", name)
+}
+
+/// One thing a sequence does, written as `{ action = "tap", x = 540, y = 1200 }`;
+/// coordinates may also be percentages of the screen, `x = "50%"`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum StepAction {
+    Tap {
+        x: Coord,
+        y: Coord,
+    },
+    Swipe {
+        x1: Coord,
+        y1: Coord,
+        x2: Coord,
+        y2: Coord,
+        #[serde(default = "default_swipe_ms")]
+        duration_ms: u32,
+    },
+    Wait {
+        ms: u64,
+    },
+    /// Tap the middle of a UI element found by `uiautomator dump`, matched by
+    /// `resource_id` and/or `text`; fails when it does not show up within `timeout_ms`
+    TapElement {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        resource_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+        #[serde(default = "default_element_timeout_ms")]
+        timeout_ms: u64,
+    },
+    /// Poll the screen until the template appears; fails after `timeout_ms`
+    WaitForTemplate {
+        template: String,
+        #[serde(default = "default_template_timeout_ms")]
+        timeout_ms: u64,
+    },
+    /// Run the steps a registered `plugins::ActionHandler` plans from `params`
+    Plugin {
+        handler: String,
+        #[serde(default, skip_serializing_if = "toml::Table::is_empty")]
+        params: toml::Table,
+    },
+}
+
+fn default_swipe_ms() -> u32 {
+    300
+}
+
+fn default_template_timeout_ms() -> u64 {
+    10_000
+}
+
+fn default_element_timeout_ms() -> u64 {
+    5_000
+}
+
+impl fmt::Display for StepAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tap { x, y } => write!(f, "tap ({}, {})", x, y),
+            Self::Swipe {
+                x1,
+                y1,
+                x2,
+                y2,
+                duration_ms,
+            } => write!(
+                f,
+                "swipe ({}, {}) → ({}, {}) {}ms",
+                x1, y1, x2, y2, duration_ms
+            ),
+            Self::Wait { ms } => write!(f, "wait {}ms", ms),
+            Self::TapElement {
+                resource_id,
+                text,
+                timeout_ms,
+            } => {
+                let selector = UiSelector {
+                    resource_id: resource_id.clone(),
+                    text: text.clone(),
+                };
+                write!(f, "tap element {} ({}ms)", selector, timeout_ms)
+            }
+            Self::WaitForTemplate {
+                template,
+                timeout_ms,
+            } => write!(f, "wait for '{}' ({}ms)", template, timeout_ms),
+            Self::Plugin { handler, .. } => write!(f, "plugin {}", handler),
+        }
+    }
+}
+
+/// What to do when a step fails
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnStepError {
+    /// Stop the sequence (default)
+    #[default]
+    Abort,
+    /// Note the failure and carry on with the next step
+    Continue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SequenceStep {
+    #[serde(flatten)]
+    pub action: StepAction,
+    #[serde(default, skip_serializing_if = "is_abort")]
+    pub on_error: OnStepError,
+}
+
+fn is_abort(on_error: &OnStepError) -> bool {
+    *on_error == OnStepError::Abort
+}
+
+/// Steps run in order while holding the device, so nothing else taps in between
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActionSequence {
+    pub steps: Vec<SequenceStep>,
+    /// Also run as a timed event every this many seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum SequenceStatus {
+    Running,
+    Completed,
+    Failed { error: String },
+}
+
+/// Where a running sequence is, published to the GUI and control API
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SequenceProgress {
+    pub name: String,
+    /// 1-based step being run (or the last one run once finished)
+    pub step: usize,
+    pub total: usize,
+    pub description: String,
+    pub status: SequenceStatus,
+    /// Steps that failed with `on_error = "continue"`
+    pub failed_steps: usize,
+}
+
+impl ActionSequence {
+    /// Run every step against `client`, touching it within `safety`, reporting progress
+    /// before each step and once at the end. Returns the number of steps that failed but
+    /// were skipped.
+    pub async fn run<C: AdbClient>(
+        &self,
+        name: &str,
+        client: &C,
+        detector: &Arc<GameStateDetector>,
+        safety: &SafetyConfig,
+        mut report: impl FnMut(SequenceProgress),
+    ) -> Result<usize, SequenceFailure> {
+        let total = self.steps.len();
+        let mut failed_steps = 0;
+        let progress = |step: usize, description: String, status: SequenceStatus, failed_steps| {
+            SequenceProgress {
+                name: name.to_string(),
+                step,
+                total,
+                description,
+                status,
+                failed_steps,
+            }
+        };
+
+        for (i, step) in self.steps.iter().enumerate() {
+            let description = step.action.to_string();
+            report(progress(
+                i + 1,
+                description.clone(),
+                SequenceStatus::Running,
+                failed_steps,
+            ));
+
+            if let Err(e) = run_step(name, &step.action, client, detector, safety).await {
+                let error = format!("step {} ({}): {}", i + 1, description, e);
+                let disconnected = matches!(&e, StepError::Device(e) if e.needs_reconnect());
+                if step.on_error == OnStepError::Continue && !disconnected {
+                    log::warn!("⚠️ Sequence '{}' {} - continuing", name, error);
+                    failed_steps += 1;
+                    continue;
+                }
+                report(progress(
+                    i + 1,
+                    description,
+                    SequenceStatus::Failed {
+                        error: error.clone(),
+                    },
+                    failed_steps,
+                ));
+                return Err(SequenceFailure {
+                    message: error,
+                    disconnected,
+                });
+            }
+        }
+
+        report(progress(
+            total,
+            "done".to_string(),
+            SequenceStatus::Completed,
+            failed_steps,
+        ));
+        Ok(failed_steps)
+    }
+}
+
+async fn run_step<C: AdbClient>(
+    name: &str,
+    action: &StepAction,
+    client: &C,
+    detector: &Arc<GameStateDetector>,
+    safety: &SafetyConfig,
+) -> Result<(), StepError> {
+    let source = format!("sequence:{}", name);
+    let device = client.device_key();
+    let screen = client.screen_dimensions();
+    match action {
+        StepAction::Tap { x, y } => {
+            let (x, y) = resolve_point((*x, *y), screen);
+            tap(
+                client,
+                safety,
+                HistoryEntry::new(HistoryAction::Tap, source, (x, y)).on_device(device),
+            )
+            .await
+        }
+        StepAction::TapElement {
+            resource_id,
+            text,
+            timeout_ms,
+        } => {
+            let selector = UiSelector {
+                resource_id: resource_id.clone(),
+                text: text.clone(),
+            };
+            if selector.is_empty() {
+                return Err(StepError::Other(
+                    "tap_element needs a resource_id or text".to_string(),
+                ));
+            }
+            let point = find_element(client, &selector, Duration::from_millis(*timeout_ms)).await?;
+            let entry = HistoryEntry::new(HistoryAction::Tap, source, point)
+                .on_device(device)
+                .with_detail(format!("element {}", selector));
+            tap(client, safety, entry).await
+        }
+        StepAction::Swipe {
+            x1,
+            y1,
+            x2,
+            y2,
+            duration_ms,
+        } => {
+            let (x1, y1) = resolve_point((*x1, *y1), screen);
+            let (x2, y2) = resolve_point((*x2, *y2), screen);
+            let entry = HistoryEntry::new(HistoryAction::Swipe, source, (x1, y1))
+                .on_device(device)
+                .with_detail(format!("to ({}, {})", x2, y2));
+            wait_for_safe_tap(safety, &entry).await?;
+            let result = client.swipe(x1, y1, x2, y2, Some(*duration_ms)).await;
+            history::record(entry.with_result(&result));
+            result.map_err(StepError::Device)
+        }
+        StepAction::Wait { ms } => {
+            tokio::time::sleep(Duration::from_millis(*ms)).await;
+            Ok(())
+        }
+        StepAction::WaitForTemplate {
+            template,
+            timeout_ms,
+        } => wait_for_template(
+            client,
+            detector,
+            template,
+            Duration::from_millis(*timeout_ms),
+            WAIT_FOR_TEMPLATE_POLL,
+        )
+        .await
+        .map(|_| ())
+        .map_err(|e| match e {
+            WaitError::Device(e) => StepError::Device(e),
+            other => StepError::Other(other.to_string()),
+        }),
+        StepAction::Plugin { handler, params } => {
+            let plugin = plugins::action_handler(handler)
+                .ok_or_else(|| StepError::Other(format!("no action plugin '{}'", handler)))?;
+            let context = ActionContext {
+                sequence: name,
+                screen,
+                params,
+            };
+            let steps = plugin.plan(&context).map_err(StepError::Other)?;
+            for step in &steps {
+                if matches!(step, StepAction::Plugin { .. }) {
+                    return Err(StepError::Other(format!(
+                        "plugin '{}' planned another plugin step",
+                        handler
+                    )));
+                }
+                Box::pin(run_step(name, step, client, detector, safety)).await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Tap at the entry's point once the `[safety]` limits allow it, recorded in the history
+async fn tap<C: AdbClient>(
+    client: &C,
+    safety: &SafetyConfig,
+    entry: HistoryEntry,
+) -> Result<(), StepError> {
+    let (x, y) = (entry.x, entry.y);
+    wait_for_safe_tap(safety, &entry).await?;
+    let result = client.tap(x, y).await;
+    history::record(entry.with_result(&result));
+    result.map_err(StepError::Device)
+}
+
+/// Wait until `safety` allows a touch at the entry's point on its device; a
+/// blocked touch is recorded in the history
+async fn wait_for_safe_tap(safety: &SafetyConfig, entry: &HistoryEntry) -> Result<(), StepError> {
+    wait_for_tap(&entry.device, safety, entry.x, entry.y)
+        .await
+        .map_err(|blocked| {
+            history::record(entry.clone().blocked(&blocked));
+            StepError::Other(blocked.to_string())
+        })
+}
+
+/// Dump the UI until `selector` shows up, for up to `timeout`; the element's middle
+async fn find_element<C: AdbClient>(
+    client: &C,
+    selector: &UiSelector,
+    timeout: Duration,
+) -> Result<(u32, u32), StepError> {
+    let start = Instant::now();
+    loop {
+        let nodes = client
+            .dump_ui_hierarchy()
+            .await
+            .map_err(StepError::Device)?;
+        if let Some(node) = selector.find(&nodes) {
+            return Ok(node.center());
+        }
+        if start.elapsed() >= timeout {
+            return Err(StepError::Other(format!(
+                "no element {} on screen",
+                selector
+            )));
+        }
+        tokio::time::sleep(WAIT_FOR_TEMPLATE_POLL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_from_toml() {
+        let sequence: ActionSequence = toml::from_str(
+            r#"
+interval_seconds = 600
+steps = [
+    { action = "tap", x = 540, y = 1200 },
+    { action = "wait", ms = 500 },
+    { action = "wait_for_template", template = "claim_button", on_error = "continue" },
+    { action = "swipe", x1 = 540, y1 = 1800, x2 = 540, y2 = 600 },
+]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(sequence.interval_seconds, Some(600));
+        assert_eq!(sequence.steps.len(), 4);
+        assert_eq!(
+            sequence.steps[0].action,
+            StepAction::Tap {
+                x: Coord::Px(540),
+                y: Coord::Px(1200)
+            }
+        );
+        assert_eq!(sequence.steps[0].on_error, OnStepError::Abort);
+        assert_eq!(sequence.steps[2].on_error, OnStepError::Continue);
+        assert_eq!(
+            sequence.steps[2].action.to_string(),
+            "wait for 'claim_button' (10000ms)"
+        );
+        assert_eq!(
+            sequence.steps[3].action,
+            StepAction::Swipe {
+                x1: Coord::Px(540),
+                y1: Coord::Px(1800),
+                x2: Coord::Px(540),
+                y2: Coord::Px(600),
+                duration_ms: 300
+            }
+        );
+
+        let text = toml::to_string(&sequence).unwrap();
+        assert_eq!(toml::from_str::<ActionSequence>(&text).unwrap(), sequence);
+    }
+
+    #[tokio::test]
+    async fn test_percent_steps_follow_the_screen() {
+        use crate::adb::{MockAction, MockAdb, RgbFrame};
+
+        let sequence: ActionSequence = toml::from_str(
+            r#"steps = [{ action = "tap", x = "50%", y = "75%" }, { action = "swipe", x1 = "50%", y1 = "90%", x2 = 100, y2 = "10%" }]"#,
+        )
+        .unwrap();
+        let frame = RgbFrame {
+            width: 1440,
+            height: 3200,
+            pixels: vec![0; 1440 * 3200 * 3],
+        };
+        let mock = MockAdb::from_frames("mock", vec![frame]);
+        let detector = Arc::new(GameStateDetector::new(1440, 3200, Default::default()));
+        sequence
+            .run("claim", &mock, &detector, &SafetyConfig::default(), |_| {})
+            .await
+            .unwrap();
+        assert_eq!(
+            mock.control().actions(),
+            vec![
+                MockAction::Tap { x: 720, y: 2400 },
+                MockAction::Swipe {
+                    x1: 720,
+                    y1: 2880,
+                    x2: 100,
+                    y2: 320,
+                    duration: Some(300)
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tap_element_uses_ui_dump() {
+        use crate::adb::ui_hierarchy::UI_DUMP_COMMAND;
+        use crate::adb::{MockAction, MockAdb};
+
+        let sequence: ActionSequence = toml::from_str(
+            r#"steps = [{ action = "tap_element", resource_id = "ok_button" }, { action = "tap_element", text = "Missing", timeout_ms = 0 }]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            sequence.steps[0].action.to_string(),
+            "tap element #ok_button (5000ms)"
+        );
+        let mock = MockAdb::from_frames("mock", Vec::new());
+        mock.control().set_shell_output(
+            UI_DUMP_COMMAND,
+            "<hierarchy rotation=\"0\"><node text=\"OK\" resource-id=\"com.example.app:id/ok_button\" \
+             bounds=\"[100,2000][500,2150]\" /></hierarchy>",
+        );
+        let detector = Arc::new(GameStateDetector::new(1080, 2400, Default::default()));
+        let error = sequence
+            .run("dialog", &mock, &detector, &SafetyConfig::default(), |_| {})
+            .await
+            .unwrap_err();
+        assert!(error.message.contains("no element \"Missing\""));
+        assert_eq!(
+            mock.control().actions(),
+            vec![MockAction::Tap { x: 300, y: 2075 }]
+        );
+    }
+}
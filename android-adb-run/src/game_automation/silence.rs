@@ -0,0 +1,237 @@
+// Device silence - keeps the phone quiet while automation runs: Do Not Disturb,
+// media volume muted and touch vibration off. From `[silence]` in automation.toml:
+//
+//   [silence]
+//   do_not_disturb = true
+//   mute_media = true
+//   disable_haptics = true
+//
+// The values found on the device are saved before changing them and written
+// back when automation stops, so the phone ends up as it was.
+use crate::adb::AdbClient;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SETTINGS_TIMEOUT: Duration = Duration::from_secs(5);
+/// `AudioManager.STREAM_MUSIC`, the stream games play through
+const MEDIA_STREAM: &str = "3";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SilenceConfig {
+    /// Turn on Do Not Disturb (priority only)
+    pub do_not_disturb: bool,
+    /// Media volume to 0
+    pub mute_media: bool,
+    /// Touch vibration (`haptic_feedback_enabled`) off
+    pub disable_haptics: bool,
+}
+
+impl SilenceConfig {
+    pub fn enabled(&self) -> bool {
+        self.do_not_disturb || self.mute_media || self.disable_haptics
+    }
+}
+
+/// What the device had before it was silenced; None for settings left alone
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SavedSettings {
+    pub zen_mode: Option<u8>,
+    pub media_volume: Option<u32>,
+    pub haptic_feedback: Option<String>,
+}
+
+/// `cmd notification set_dnd` argument for a `zen_mode` setting value
+pub fn dnd_argument(zen_mode: u8) -> &'static str {
+    match zen_mode {
+        1 => "priority",
+        2 => "none",
+        3 => "alarms",
+        _ => "off",
+    }
+}
+
+/// Volume from `cmd media_session volume --get` ("volume is 7 in range [0..15]")
+pub fn parse_volume(output: &str) -> Option<u32> {
+    output
+        .split("volume is ")
+        .nth(1)?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// A `settings get` value; "null" when the setting was never written
+fn setting_value(output: &str) -> Option<String> {
+    let value = output.trim();
+    (!value.is_empty() && value != "null").then(|| value.to_string())
+}
+
+async fn shell(client: &impl AdbClient, args: &[&str]) -> Result<String, String> {
+    let output = client
+        .run_shell(args, SETTINGS_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !output.success() {
+        return Err(format!(
+            "`{}` failed: {}",
+            args.join(" "),
+            output.stderr.trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// Apply `config`, returning the previous values. Each setting is tried on its
+/// own; failures are logged and that setting is left alone.
+pub async fn silence(client: &impl AdbClient, config: &SilenceConfig) -> SavedSettings {
+    let mut saved = SavedSettings::default();
+
+    if config.do_not_disturb {
+        let result = async {
+            let current = shell(client, &["settings", "get", "global", "zen_mode"]).await?;
+            let zen_mode = setting_value(&current)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0);
+            shell(client, &["cmd", "notification", "set_dnd", "priority"]).await?;
+            Ok::<_, String>(zen_mode)
+        }
+        .await;
+        match result {
+            Ok(zen_mode) => saved.zen_mode = Some(zen_mode),
+            Err(e) => log::warn!("⚠️ Cannot turn on Do Not Disturb: {}", e),
+        }
+    }
+
+    if config.mute_media {
+        let result = async {
+            let current = shell(
+                client,
+                &[
+                    "cmd",
+                    "media_session",
+                    "volume",
+                    "--stream",
+                    MEDIA_STREAM,
+                    "--get",
+                ],
+            )
+            .await?;
+            let volume = parse_volume(&current)
+                .ok_or_else(|| format!("unexpected volume output '{}'", current.trim()))?;
+            shell(
+                client,
+                &[
+                    "cmd",
+                    "media_session",
+                    "volume",
+                    "--stream",
+                    MEDIA_STREAM,
+                    "--set",
+                    "0",
+                ],
+            )
+            .await?;
+            Ok::<_, String>(volume)
+        }
+        .await;
+        match result {
+            Ok(volume) => saved.media_volume = Some(volume),
+            Err(e) => log::warn!("⚠️ Cannot mute media volume: {}", e),
+        }
+    }
+
+    if config.disable_haptics {
+        let result = async {
+            let current = shell(
+                client,
+                &["settings", "get", "system", "haptic_feedback_enabled"],
+            )
+            .await?;
+            shell(
+                client,
+                &["settings", "put", "system", "haptic_feedback_enabled", "0"],
+            )
+            .await?;
+            Ok::<_, String>(setting_value(&current).unwrap_or_else(|| "1".to_string()))
+        }
+        .await;
+        match result {
+            Ok(value) => saved.haptic_feedback = Some(value),
+            Err(e) => log::warn!("⚠️ Cannot turn off touch vibration: {}", e),
+        }
+    }
+
+    saved
+}
+
+/// Write back what `silence` saved
+pub async fn restore(client: &impl AdbClient, saved: &SavedSettings) -> Result<(), String> {
+    let mut errors = Vec::new();
+    if let Some(zen_mode) = saved.zen_mode
+        && let Err(e) = shell(
+            client,
+            &["cmd", "notification", "set_dnd", dnd_argument(zen_mode)],
+        )
+        .await
+    {
+        errors.push(e);
+    }
+    if let Some(volume) = saved.media_volume
+        && let Err(e) = shell(
+            client,
+            &[
+                "cmd",
+                "media_session",
+                "volume",
+                "--stream",
+                MEDIA_STREAM,
+                "--set",
+                &volume.to_string(),
+            ],
+        )
+        .await
+    {
+        errors.push(e);
+    }
+    if let Some(value) = &saved.haptic_feedback
+        && let Err(e) = shell(
+            client,
+            &[
+                "settings",
+                "put",
+                "system",
+                "haptic_feedback_enabled",
+                value,
+            ],
+        )
+        .await
+    {
+        errors.push(e);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_silence_parsing() {
+        let config: SilenceConfig = toml::from_str("mute_media = true").unwrap();
+        assert!(config.enabled() && !config.do_not_disturb);
+        assert!(!SilenceConfig::default().enabled());
+
+        assert_eq!(parse_volume("volume is 7 in range [0..15]\n"), Some(7));
+        assert_eq!(parse_volume("Error: unknown command"), None);
+        assert_eq!(dnd_argument(0), "off");
+        assert_eq!(dnd_argument(3), "alarms");
+        assert_eq!(setting_value("null\n"), None);
+        assert_eq!(setting_value(" 1\n"), Some("1".to_string()));
+    }
+}
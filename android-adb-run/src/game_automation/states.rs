@@ -0,0 +1,214 @@
+// User-defined game states ("MainMenu", "Battle") and the template rules that
+// move automation between them, from `[states]` in automation.toml:
+//
+//   "MainMenu -> Battle when attack_button seen"
+//   "Battle -> MainMenu when attack_button gone"
+//   "* -> Shop when shop_title seen"
+//   "* -> Loading when scene loading"     # `[scenes]` classification
+//
+// `GameState` stays Idle/Running/Paused; the custom state says where in the game
+// automation thinks it is.
+use super::match_image::DetectionResult;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// What a transition waits for on a screenshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    /// The template (name or label) was matched
+    Seen(String),
+    /// The template was not matched
+    Gone(String),
+    /// The screenshot was classified as this `[scenes]` scene
+    Scene(String),
+}
+
+impl Condition {
+    pub fn holds(&self, result: &DetectionResult) -> bool {
+        match self {
+            Self::Seen(template) => result.best_match_for(template).is_some(),
+            Self::Gone(template) => result.best_match_for(template).is_none(),
+            Self::Scene(scene) => result.scene.as_ref() == Some(scene),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Seen(template) => write!(f, "{} seen", template),
+            Self::Gone(template) => write!(f, "{} gone", template),
+            Self::Scene(scene) => write!(f, "scene {}", scene),
+        }
+    }
+}
+
+/// One rule, written `<from> -> <to> when <template> seen|gone` or
+/// `... when scene <name>`; `*` as `<from>`
+/// applies in every state (and before the first one is known)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Transition {
+    /// `None` for `*`
+    pub from: Option<String>,
+    pub to: String,
+    pub when: Condition,
+}
+
+impl Transition {
+    /// Whether this rule moves automation out of `current` on `result`
+    pub fn fires(&self, current: Option<&str>, result: &DetectionResult) -> bool {
+        let from_matches = match &self.from {
+            Some(from) => current == Some(from.as_str()),
+            None => current != Some(self.to.as_str()),
+        };
+        from_matches && self.when.holds(result)
+    }
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {} when {}",
+            self.from.as_deref().unwrap_or("*"),
+            self.to,
+            self.when
+        )
+    }
+}
+
+impl FromStr for Transition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let syntax = || {
+            format!(
+                "'{}': expected '<from> -> <to> when <template> seen|gone' or '... when scene <name>'",
+                s
+            )
+        };
+        let (states, condition) = s.split_once(" when ").ok_or_else(syntax)?;
+        let (from, to) = states.split_once("->").ok_or_else(syntax)?;
+        let (from, to) = (from.trim(), to.trim());
+        let mut words = condition.split_whitespace();
+        let (Some(subject), Some(kind), None) = (words.next(), words.next(), words.next()) else {
+            return Err(syntax());
+        };
+        if from.is_empty() || to.is_empty() || to == "*" || to.contains(char::is_whitespace) {
+            return Err(syntax());
+        }
+        let when = match (subject, kind) {
+            ("scene", scene) => Condition::Scene(scene.to_string()),
+            (template, "seen") => Condition::Seen(template.to_string()),
+            (template, "gone") => Condition::Gone(template.to_string()),
+            (_, other) => return Err(format!("'{}': unknown condition '{}'", s, other)),
+        };
+        Ok(Self {
+            from: (from != "*").then(|| from.to_string()),
+            to: to.to_string(),
+            when,
+        })
+    }
+}
+
+impl TryFrom<String> for Transition {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Transition> for String {
+    fn from(transition: Transition) -> Self {
+        transition.to_string()
+    }
+}
+
+/// Custom game states, `[states]`; off while `transitions` is empty
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatesConfig {
+    /// State each automation run starts in; unknown until a `*` rule fires when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial: Option<String>,
+    /// Checked in order on every analyzed screenshot; the first that fires wins
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transitions: Vec<Transition>,
+}
+
+impl StatesConfig {
+    /// State to move to from `current` after `result`, if any rule fires
+    pub fn next_state(&self, current: Option<&str>, result: &DetectionResult) -> Option<&str> {
+        self.transitions
+            .iter()
+            .find(|rule| rule.fires(current, result))
+            .map(|rule| rule.to.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transition_rules_parse_and_round_trip() {
+        let config: StatesConfig = toml::from_str(
+            r#"
+initial = "MainMenu"
+transitions = [
+    "MainMenu -> Battle when attack_button seen",
+    "Battle -> MainMenu when attack_button gone",
+    "* -> Shop when shop_title seen",
+]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.initial.as_deref(), Some("MainMenu"));
+        assert_eq!(
+            config.transitions[0],
+            Transition {
+                from: Some("MainMenu".into()),
+                to: "Battle".into(),
+                when: Condition::Seen("attack_button".into()),
+            }
+        );
+        assert_eq!(
+            config.transitions[1].when,
+            Condition::Gone("attack_button".into())
+        );
+        assert_eq!(config.transitions[2].from, None);
+        assert_eq!(
+            config.transitions[2].to_string(),
+            "* -> Shop when shop_title seen"
+        );
+
+        let text = toml::to_string(&config).unwrap();
+        assert_eq!(toml::from_str::<StatesConfig>(&text).unwrap(), config);
+
+        assert!("MainMenu -> Battle".parse::<Transition>().is_err());
+        assert!("A -> B when x visible".parse::<Transition>().is_err());
+        assert!("A -> * when x seen".parse::<Transition>().is_err());
+
+        // Nothing matched: only "gone" rules can fire
+        let nothing = DetectionResult::default();
+        assert_eq!(config.next_state(Some("MainMenu"), &nothing), None);
+        assert_eq!(
+            config.next_state(Some("Battle"), &nothing),
+            Some("MainMenu")
+        );
+
+        let loading: Transition = "* -> Loading when scene loading".parse().unwrap();
+        assert_eq!(loading.when, Condition::Scene("loading".into()));
+        assert_eq!(loading.to_string(), "* -> Loading when scene loading");
+        let classified = DetectionResult {
+            scene: Some("loading".into()),
+            ..DetectionResult::default()
+        };
+        assert!(!loading.fires(Some("Battle"), &nothing));
+        assert!(loading.fires(Some("Battle"), &classified));
+    }
+}
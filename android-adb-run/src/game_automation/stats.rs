@@ -0,0 +1,295 @@
+// Automation statistics: taps per timed event, match rates per template,
+// screenshot latency and run durations, exportable as CSV or JSON
+use super::match_image::DetectionResult;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Screenshot latencies kept for the percentiles (oldest dropped first)
+pub const MAX_LATENCY_SAMPLES: usize = 1000;
+
+/// How often a template was looked for, found and acted on
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TemplateStats {
+    pub checks: u64,
+    pub matches: u64,
+    pub actions: u64,
+    /// Unix time (seconds) of the latest match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_match_unix: Option<i64>,
+    /// Best confidence of the latest match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_confidence: Option<f32>,
+}
+
+impl TemplateStats {
+    /// Share of checks (0.0 - 1.0) that found the template
+    pub fn success_rate(&self) -> f64 {
+        if self.checks == 0 {
+            0.0
+        } else {
+            self.matches as f64 / self.checks as f64
+        }
+    }
+}
+
+/// Screenshot capture time percentiles over the recent samples
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Time spent template matching screenshots
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct MatchTime {
+    pub analyses: u64,
+    pub total_ms: u64,
+}
+
+/// One finished Start..Stop automation run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunRecord {
+    /// Unix time (seconds) the run started
+    pub started_unix: i64,
+    pub duration_secs: u64,
+}
+
+/// Counters kept by the FSM while it runs
+#[derive(Debug, Clone, Default)]
+pub struct AutomationStats {
+    taps: BTreeMap<String, u64>,
+    templates: BTreeMap<String, TemplateStats>,
+    latencies_ms: VecDeque<u64>,
+    match_time: MatchTime,
+    runs: Vec<RunRecord>,
+    run_started: Option<(Instant, i64)>,
+}
+
+/// Snapshot of the statistics, shown in the GUI and written by `ExportStats`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StatsSummary {
+    /// Executions per timed event id
+    pub taps: BTreeMap<String, u64>,
+    pub templates: BTreeMap<String, TemplateStats>,
+    pub screenshot_latency: LatencySummary,
+    pub match_time: MatchTime,
+    pub runs: Vec<RunRecord>,
+    /// Seconds the current run has lasted, if automation is running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_run_secs: Option<u64>,
+}
+
+impl AutomationStats {
+    pub fn record_tap(&mut self, event_id: &str) {
+        *self.taps.entry(event_id.to_string()).or_default() += 1;
+    }
+
+    /// Count one check of every template in `checked`, and a match for those in `result`
+    pub fn record_detection(&mut self, checked: &[String], result: &DetectionResult) {
+        self.match_time.analyses += 1;
+        self.match_time.total_ms += result.processing_time_ms as u64;
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        for name in checked {
+            let stats = self.templates.entry(name.clone()).or_default();
+            stats.checks += 1;
+            let best = result
+                .matches
+                .iter()
+                .filter(|m| &m.template.name == name)
+                .map(|m| m.confidence)
+                .reduce(f32::max);
+            if let Some(confidence) = best {
+                stats.matches += 1;
+                stats.last_match_unix = Some(now);
+                stats.last_confidence = Some(confidence);
+            }
+        }
+    }
+
+    /// A template match led to a tap, swipe or sequence
+    pub fn record_action(&mut self, template: &str) {
+        self.templates
+            .entry(template.to_string())
+            .or_default()
+            .actions += 1;
+    }
+
+    pub fn record_screenshot(&mut self, latency: Duration) {
+        if self.latencies_ms.len() >= MAX_LATENCY_SAMPLES {
+            self.latencies_ms.pop_front();
+        }
+        self.latencies_ms.push_back(latency.as_millis() as u64);
+    }
+
+    pub fn start_run(&mut self) {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        self.run_started = Some((Instant::now(), now));
+    }
+
+    pub fn stop_run(&mut self) {
+        if let Some((started, started_unix)) = self.run_started.take() {
+            self.runs.push(RunRecord {
+                started_unix,
+                duration_secs: started.elapsed().as_secs(),
+            });
+        }
+    }
+
+    pub fn summary(&self) -> StatsSummary {
+        StatsSummary {
+            taps: self.taps.clone(),
+            templates: self.templates.clone(),
+            screenshot_latency: latency_summary(&self.latencies_ms),
+            match_time: self.match_time,
+            runs: self.runs.clone(),
+            current_run_secs: self
+                .run_started
+                .map(|(started, _)| started.elapsed().as_secs()),
+        }
+    }
+}
+
+fn latency_summary(samples: &VecDeque<u64>) -> LatencySummary {
+    let mut sorted: Vec<u64> = samples.iter().copied().collect();
+    sorted.sort_unstable();
+    let percentile = |p: usize| -> u64 {
+        if sorted.is_empty() {
+            return 0;
+        }
+        // Nearest-rank percentile
+        let rank = (p * sorted.len()).div_ceil(100).max(1);
+        sorted[rank - 1]
+    };
+    LatencySummary {
+        samples: sorted.len(),
+        p50_ms: percentile(50),
+        p90_ms: percentile(90),
+        p99_ms: percentile(99),
+        max_ms: sorted.last().copied().unwrap_or(0),
+    }
+}
+
+impl StatsSummary {
+    pub fn total_taps(&self) -> u64 {
+        self.taps.values().sum()
+    }
+
+    /// Taps, swipes and sequences started by template matches
+    pub fn total_template_actions(&self) -> u64 {
+        self.templates.values().map(|t| t.actions).sum()
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to encode stats: {}", e))
+    }
+
+    /// One `section,name,metric,value` row per number
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec!["section,name,metric,value".to_string()];
+        for (id, count) in &self.taps {
+            rows.push(format!("tap,{},count,{}", csv_field(id), count));
+        }
+        for (name, stats) in &self.templates {
+            let name = csv_field(name);
+            rows.push(format!("template,{},checks,{}", name, stats.checks));
+            rows.push(format!("template,{},matches,{}", name, stats.matches));
+            rows.push(format!("template,{},actions,{}", name, stats.actions));
+            rows.push(format!(
+                "template,{},success_rate,{:.3}",
+                name,
+                stats.success_rate()
+            ));
+        }
+        let latency = &self.screenshot_latency;
+        for (metric, value) in [
+            ("samples", latency.samples as u64),
+            ("p50_ms", latency.p50_ms),
+            ("p90_ms", latency.p90_ms),
+            ("p99_ms", latency.p99_ms),
+            ("max_ms", latency.max_ms),
+        ] {
+            rows.push(format!("screenshot,latency,{},{}", metric, value));
+        }
+        rows.push(format!(
+            "matching,all,analyses,{}",
+            self.match_time.analyses
+        ));
+        rows.push(format!(
+            "matching,all,total_ms,{}",
+            self.match_time.total_ms
+        ));
+        for (i, run) in self.runs.iter().enumerate() {
+            rows.push(format!("run,{},started_unix,{}", i + 1, run.started_unix));
+            rows.push(format!("run,{},duration_secs,{}", i + 1, run.duration_secs));
+        }
+        if let Some(secs) = self.current_run_secs {
+            rows.push(format!("run,current,duration_secs,{}", secs));
+        }
+        rows.join("\n") + "\n"
+    }
+
+    /// Write as CSV (`.csv`) or JSON (`.json`), chosen by the file extension
+    pub fn export(&self, path: &Path) -> Result<(), String> {
+        let contents = match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => self.to_csv(),
+            Some(ext) if ext.eq_ignore_ascii_case("json") => self.to_json()?,
+            _ => {
+                return Err(format!(
+                    "Unknown stats format for {} (use .csv or .json)",
+                    path.display()
+                ));
+            }
+        };
+        std::fs::write(path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// Quote a CSV field when it holds a comma, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_percentiles_and_csv() {
+        let mut stats = AutomationStats::default();
+        for ms in 1..=100 {
+            stats.record_screenshot(Duration::from_millis(ms));
+        }
+        stats.record_tap("claim");
+        stats.record_tap("claim");
+        stats.record_detection(&["a,b".to_string()], &DetectionResult::new());
+        stats.record_action("a,b");
+
+        let summary = stats.summary();
+        assert_eq!(summary.total_taps(), 2);
+        assert_eq!(
+            (
+                summary.screenshot_latency.p50_ms,
+                summary.screenshot_latency.p90_ms,
+                summary.screenshot_latency.max_ms
+            ),
+            (50, 90, 100)
+        );
+        assert_eq!(summary.templates["a,b"].success_rate(), 0.0);
+        assert_eq!(summary.templates["a,b"].last_match_unix, None);
+
+        let csv = summary.to_csv();
+        assert!(csv.starts_with("section,name,metric,value\ntap,claim,count,2\n"));
+        assert!(csv.contains("template,\"a,b\",actions,1\n"));
+        assert!(summary.export(Path::new("stats.txt")).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+// Timelapse export: a folder of screenshots (`screenshot --count`, near-miss
+// captures, saved GUI screenshots) assembled into a GIF or MP4 with each frame's
+// time and the automation's taps burned in, for sharing bug reports:
+//
+//   android-adb-run export-video screenshots --out run.gif --fps 4
+//
+// Frames are ordered by modification time, which is also the time shown. Taps
+// come from `logs/history.jsonl`: a frame shows the taps made after it was taken
+// and before the next one, on the same device when `--device` names it. GIFs are encoded here, MP4 needs `ffmpeg` on the PATH.
+use super::history::{HistoryAction, HistoryEntry, Outcome};
+use super::overlay;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, DynamicImage, Frame, Rgb, RgbImage, imageops};
+use imageproc::drawing::{draw_filled_circle_mut, draw_hollow_circle_mut};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const TAP_COLOR: Rgb<u8> = Rgb([255, 40, 40]);
+/// Taps held back by `[safety]` or that failed
+const SKIPPED_TAP_COLOR: Rgb<u8> = Rgb([160, 160, 160]);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelapseFrame {
+    pub path: PathBuf,
+    pub unix_ms: u64,
+    /// `Device::key` of the device it shows; taps from every device when unset
+    pub device: Option<String>,
+}
+
+/// PNG files in `dir` taken on `device`, oldest first
+pub fn frames_in(dir: &Path, device: Option<&str>) -> Result<Vec<TimelapseFrame>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    let mut frames: Vec<TimelapseFrame> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+        })
+        .map(|path| {
+            let unix_ms = std::fs::metadata(&path)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |since| since.as_millis() as u64);
+            TimelapseFrame {
+                path,
+                unix_ms,
+                device: device.map(str::to_string),
+            }
+        })
+        .collect();
+    frames.sort_by(|a, b| (a.unix_ms, &a.path).cmp(&(b.unix_ms, &b.path)));
+    Ok(frames)
+}
+
+/// Taps shown on each frame: made on its device after it was taken and before
+/// the next one; the last frame keeps them as long as the gap before it
+pub fn frame_taps<'a>(
+    frames: &[TimelapseFrame],
+    history: &'a [HistoryEntry],
+) -> Vec<Vec<&'a HistoryEntry>> {
+    frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let until = match (frames.get(i + 1), i.checked_sub(1)) {
+                (Some(next), _) => next.unix_ms,
+                (None, Some(prev)) => frame.unix_ms * 2 - frames[prev].unix_ms,
+                (None, None) => frame.unix_ms,
+            };
+            history
+                .iter()
+                .filter(|entry| {
+                    entry.action == HistoryAction::Tap
+                        && frame
+                            .device
+                            .as_ref()
+                            .is_none_or(|device| *device == entry.device)
+                        && entry.unix_ms >= frame.unix_ms
+                        && entry.unix_ms < until
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Local `YYYY-MM-DD HH:MM:SS`
+pub fn time_label(unix_ms: u64) -> String {
+    let offset = time::UtcOffset::current_local_offset().unwrap_or(time::UtcOffset::UTC);
+    match time::OffsetDateTime::from_unix_timestamp_nanos(unix_ms as i128 * 1_000_000) {
+        Ok(at) => {
+            let at = at.to_offset(offset);
+            format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                at.year(),
+                at.month() as u8,
+                at.day(),
+                at.hour(),
+                at.minute(),
+                at.second()
+            )
+        }
+        Err(_) => "?".to_string(),
+    }
+}
+
+/// Burn the time label and a ring per tap (red, grey when blocked or failed) into `image`
+pub fn annotate(image: &mut RgbImage, label: &str, taps: &[&HistoryEntry]) {
+    let radius = (image.width() / 30).max(6) as i32;
+    for tap in taps {
+        let color = match tap.outcome {
+            Outcome::Done => TAP_COLOR,
+            Outcome::Blocked | Outcome::Failed => SKIPPED_TAP_COLOR,
+        };
+        let center = (tap.x as i32, tap.y as i32);
+        for ring in 0..3 {
+            draw_hollow_circle_mut(image, center, radius - ring, color);
+        }
+        draw_filled_circle_mut(image, center, radius / 5, color);
+    }
+    let scale = overlay::text_scale(image);
+    overlay::draw_text(image, (0, 0), label, scale);
+}
+
+/// Frames per second and output width (0 keeps the screenshot size)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimelapseOptions {
+    pub fps: u32,
+    pub width: u32,
+}
+
+fn render(
+    frame: &TimelapseFrame,
+    taps: &[&HistoryEntry],
+    options: TimelapseOptions,
+) -> Result<RgbImage, String> {
+    let mut image = image::open(&frame.path)
+        .map_err(|e| format!("Failed to open {}: {}", frame.path.display(), e))?
+        .to_rgb8();
+    annotate(&mut image, &time_label(frame.unix_ms), taps);
+    if options.width == 0 || options.width >= image.width() {
+        return Ok(image);
+    }
+    let height = (image.height() as u64 * options.width as u64 / image.width() as u64) as u32;
+    Ok(imageops::resize(
+        &image,
+        options.width,
+        height.max(1),
+        imageops::FilterType::Triangle,
+    ))
+}
+
+/// Write `frames` with their taps to `out`: GIF for `.gif`, anything else through ffmpeg.
+/// Returns the number of frames written.
+pub fn export(
+    frames: &[TimelapseFrame],
+    history: &[HistoryEntry],
+    out: &Path,
+    options: TimelapseOptions,
+) -> Result<usize, String> {
+    if frames.is_empty() {
+        return Err("No screenshots to export".to_string());
+    }
+    let fps = options.fps.max(1);
+    let taps = frame_taps(frames, history);
+    let is_gif = out
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gif"));
+    if is_gif {
+        let file = std::fs::File::create(out)
+            .map_err(|e| format!("Failed to create {}: {}", out.display(), e))?;
+        let mut encoder = GifEncoder::new(std::io::BufWriter::new(file));
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(|e| format!("GIF encoding failed: {}", e))?;
+        for (frame, taps) in frames.iter().zip(&taps) {
+            let image = DynamicImage::ImageRgb8(render(frame, taps, options)?).to_rgba8();
+            let delay = Delay::from_numer_denom_ms(1000, fps);
+            encoder
+                .encode_frame(Frame::from_parts(image, 0, 0, delay))
+                .map_err(|e| format!("GIF encoding failed: {}", e))?;
+        }
+        return Ok(frames.len());
+    }
+
+    // ffmpeg reads numbered PNGs from a scratch folder
+    let scratch =
+        std::env::temp_dir().join(format!("android-adb-run-timelapse-{}", std::process::id()));
+    std::fs::create_dir_all(&scratch)
+        .map_err(|e| format!("Failed to create {}: {}", scratch.display(), e))?;
+    let result = frames
+        .iter()
+        .zip(&taps)
+        .enumerate()
+        .try_for_each(|(i, (frame, taps))| {
+            let path = scratch.join(format!("frame-{:05}.png", i));
+            render(frame, taps, options)?
+                .save(&path)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+        })
+        .and_then(|()| run_ffmpeg(&scratch, out, fps));
+    let _ = std::fs::remove_dir_all(&scratch);
+    result.map(|()| frames.len())
+}
+
+fn run_ffmpeg(scratch: &Path, out: &Path, fps: u32) -> Result<(), String> {
+    let output = std::process::Command::new("ffmpeg")
+        .args([
+            "-y",
+            "-loglevel",
+            "error",
+            "-framerate",
+            &fps.to_string(),
+            "-i",
+        ])
+        .arg(scratch.join("frame-%05d.png"))
+        // H.264 needs even dimensions
+        .args([
+            "-vf",
+            "scale=trunc(iw/2)*2:trunc(ih/2)*2",
+            "-pix_fmt",
+            "yuv420p",
+        ])
+        .arg(out)
+        .output()
+        .map_err(|e| format!("Running ffmpeg failed ({}); export to a .gif instead", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "ffmpeg failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tap_at(unix_ms: u64, (x, y): (u32, u32)) -> HistoryEntry {
+        HistoryEntry {
+            unix_ms,
+            ..HistoryEntry::new(HistoryAction::Tap, "claim", (x, y)).on_device("phone")
+        }
+    }
+
+    #[test]
+    fn test_taps_follow_their_frame_and_are_drawn() {
+        let frames: Vec<TimelapseFrame> = [1_000, 3_000, 5_000]
+            .into_iter()
+            .map(|unix_ms| TimelapseFrame {
+                path: PathBuf::from(format!("{}.png", unix_ms)),
+                unix_ms,
+                device: Some("phone".to_string()),
+            })
+            .collect();
+        let swipe = HistoryEntry {
+            unix_ms: 1_500,
+            ..HistoryEntry::new(HistoryAction::Swipe, "scroll", (10, 10))
+        };
+        let history = [
+            tap_at(500, (1, 1)),
+            tap_at(1_200, (200, 300)),
+            swipe,
+            tap_at(1_300, (5, 5)).on_device("tablet"),
+            tap_at(3_000, (2, 2)),
+            tap_at(6_500, (3, 3)),
+            tap_at(7_000, (4, 4)),
+        ];
+        let shown: Vec<Vec<u64>> = frame_taps(&frames, &history)
+            .iter()
+            .map(|taps| taps.iter().map(|tap| tap.unix_ms).collect())
+            .collect();
+        assert_eq!(shown, [vec![1_200], vec![3_000], vec![6_500]]);
+
+        let mut image = RgbImage::new(540, 960);
+        let tap = &history[1];
+        annotate(&mut image, "2026-10-16 12:00:00", &[tap]);
+        assert_eq!(*image.get_pixel(200, 300), TAP_COLOR, "tap center");
+        assert_eq!(*image.get_pixel(200 + 18, 300), TAP_COLOR, "tap ring");
+        // '2' at scale 2 fills its top row after the 4px padding
+        assert_eq!(*image.get_pixel(4, 4), overlay::TEXT_COLOR);
+        assert_eq!(*image.get_pixel(4, 6), overlay::TEXT_BACKGROUND);
+    }
+}
@@ -1,4 +1,14 @@
 // Types and enums for game automation
+use super::config::AutomationConfig;
+use super::coord::Coord;
+use super::counters::CounterReadings;
+use super::humanize::Jitter;
+use super::match_image::TemplateTest;
+use super::schedule::Schedule;
+use super::sequence::SequenceProgress;
+use super::stats::StatsSummary;
+use super::watchdog::StuckDetection;
+use crate::adb::Orientation;
 use std::time::{Duration, Instant};
 
 pub const MIN_TAP_INTERVAL_SECONDS: u64 = 5;
@@ -17,29 +27,117 @@ pub enum GameState {
     Idle,
     Running, // Simplified from multiple states
     Paused,
+    /// The game left the foreground; relaunching it
+    Recovering,
+    /// Inside `[quiet_hours]`; wakes up by itself when they end
+    Sleeping,
+}
+
+/// Progress of relaunching a game that left the foreground
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct RecoveryAttempt {
+    pub package: String,
+    pub attempt: u32,
+    pub max_attempts: u32,
+}
+
+/// Device rotation as last polled, and the one taps and templates were made in
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct OrientationStatus {
+    pub current: Orientation,
+    pub expected: Orientation,
+}
+
+impl OrientationStatus {
+    pub fn rotated(&self) -> bool {
+        self.current != self.expected
+    }
+}
+
+/// A match outlined on the GUI screenshot, in device pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchOverlay {
+    pub label: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub confidence: f32,
+    /// Where automation is about to tap (or start its swipe) for this match
+    pub tap: Option<(u32, u32)>,
 }
 
 // Config struct to reduce function argument count
+#[derive(Clone, Copy)]
 pub struct AutomationSignals {
     pub screenshot_data: dioxus::prelude::Signal<Option<String>>,
     pub screenshot_bytes: dioxus::prelude::Signal<Option<Vec<u8>>>,
     pub screenshot_status: dioxus::prelude::Signal<String>,
     pub screenshot_status_history: dioxus::prelude::Signal<Vec<(String, bool)>>,
+    /// Matches found on the shown screenshot
+    pub screenshot_matches: dioxus::prelude::Signal<Vec<MatchOverlay>>,
     pub automation_state: dioxus::prelude::Signal<GameState>,
     pub is_paused_by_touch: dioxus::prelude::Signal<bool>,
     pub touch_timeout_remaining: dioxus::prelude::Signal<Option<u64>>,
     pub timed_tap_countdown: dioxus::prelude::Signal<Option<(String, u64)>>,
     pub timed_events_list: dioxus::prelude::Signal<Vec<TimedEvent>>,
     pub device_info: dioxus::prelude::Signal<Option<DeviceInfo>>,
-    pub status: dioxus::prelude::Signal<String>,
+    pub status: dioxus::prelude::Signal<crate::gui::status::ConnectionStatus>,
     pub screenshot_counter: dioxus::prelude::Signal<u64>,
+    pub device_telemetry: dioxus::prelude::Signal<Option<crate::adb::DeviceTelemetry>>,
+    /// Why a device health guardrail paused automation
+    pub health_pause: dioxus::prelude::Signal<Option<String>>,
+    /// Latest relaunch while `GameState::Recovering`
+    pub recovery: dioxus::prelude::Signal<Option<RecoveryAttempt>>,
+    /// Latest step of the running (or last finished) action sequence
+    pub sequence_progress: dioxus::prelude::Signal<Option<SequenceProgress>>,
+    /// Tap, match, latency and run statistics
+    pub stats: dioxus::prelude::Signal<StatsSummary>,
+    /// Current `[states]` game state, if any rule has placed it
+    pub custom_state: dioxus::prelude::Signal<Option<String>>,
+    /// Device rotation against `[orientation] expected`, once polled
+    pub orientation: dioxus::prelude::Signal<Option<OrientationStatus>>,
+    /// `HH:MM` the `[quiet_hours]` sleep ends, while `GameState::Sleeping`
+    pub sleeping_until: dioxus::prelude::Signal<Option<String>>,
+    /// Latest `[watchdog]` stuck detection, until a template matches again
+    pub stuck: dioxus::prelude::Signal<Option<StuckDetection>>,
+    /// `[counters]` readings over time
+    pub counters: dioxus::prelude::Signal<CounterReadings>,
+    /// Latest `TestTemplate` result
+    pub template_test: dioxus::prelude::Signal<Option<TemplateTest>>,
+    /// Config this automation runs: its `[devices]` profile, else the active one
+    pub config: dioxus::prelude::Signal<AutomationConfig>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TimedEventType {
     Screenshot,
-    Tap { x: u32, y: u32 },
+    Tap {
+        x: Coord,
+        y: Coord,
+    },
     CountdownUpdate,
+    /// Run the named script from `automation_scripts/`
+    Script {
+        name: String,
+    },
+    /// Run the `[sequences.<name>]` action sequence
+    Sequence {
+        name: String,
+    },
+    /// Tap only while `template` is on screen; otherwise skip until the next interval
+    ConditionalTap {
+        template: String,
+        x: Coord,
+        y: Coord,
+    },
+    /// Pull `remote_path` into a new timestamped folder under `local_dir`,
+    /// keeping the newest `keep` folders (0 = all)
+    BackupFiles {
+        remote_path: String,
+        local_dir: String,
+        keep: usize,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -51,6 +149,14 @@ pub struct TimedEvent {
     pub enabled: bool,
     pub repeating: bool,
     pub execution_count: u64, // Counter for number of times this event has been executed
+    /// Fire at wall-clock times instead of every `interval`
+    pub schedule: Option<Schedule>,
+    /// Next scheduled fire, planned from the device clock by the FSM
+    pub next_fire: Option<Instant>,
+    /// Random tap offset and interval variation
+    pub jitter: Jitter,
+    /// `interval` multiplier for the current wait, re-rolled after every run
+    pub interval_scale: f32,
 }
 
 // Custom PartialEq implementation since Instant doesn't implement PartialEq
@@ -62,6 +168,8 @@ impl PartialEq for TimedEvent {
             && self.enabled == other.enabled
             && self.repeating == other.repeating
             && self.execution_count == other.execution_count
+            && self.schedule == other.schedule
+            && self.jitter == other.jitter
         // Intentionally skip last_executed for comparison since Instant doesn't implement PartialEq
     }
 }
@@ -77,6 +185,10 @@ impl TimedEvent {
             enabled: true,
             repeating: true,
             execution_count: 0,
+            schedule: None,
+            next_fire: None,
+            jitter: Jitter::default(),
+            interval_scale: 1.0,
         }
     }
 
@@ -89,6 +201,10 @@ impl TimedEvent {
             enabled: true,
             repeating: true,
             execution_count: 0,
+            schedule: None,
+            next_fire: None,
+            jitter: Jitter::default(),
+            interval_scale: 1.0,
         }
     }
 
@@ -96,22 +212,58 @@ impl TimedEvent {
         Self::new_screenshot(interval_minutes * 60)
     }
 
-    pub fn new_tap(id: String, x: u32, y: u32, interval: Duration) -> Self {
+    /// `x`/`y` in pixels, or `Coord::Percent` of the screen
+    pub fn new_tap(
+        id: String,
+        x: impl Into<Coord>,
+        y: impl Into<Coord>,
+        interval: Duration,
+    ) -> Self {
         Self {
             id,
-            event_type: TimedEventType::Tap { x, y },
+            event_type: TimedEventType::Tap {
+                x: x.into(),
+                y: y.into(),
+            },
             interval,
             last_executed: None,
             enabled: true,
             repeating: true,
             execution_count: 0,
+            schedule: None,
+            next_fire: None,
+            jitter: Jitter::default(),
+            interval_scale: 1.0,
         }
     }
 
-    pub fn new_tap_seconds(id: String, x: u32, y: u32, interval_seconds: u64) -> Self {
+    pub fn new_tap_seconds(
+        id: String,
+        x: impl Into<Coord>,
+        y: impl Into<Coord>,
+        interval_seconds: u64,
+    ) -> Self {
         Self::new_tap(id, x, y, Duration::from_secs(interval_seconds))
     }
 
+    pub fn new_conditional_tap(
+        id: String,
+        template: String,
+        x: impl Into<Coord>,
+        y: impl Into<Coord>,
+        interval: Duration,
+    ) -> Self {
+        Self::new(
+            id,
+            TimedEventType::ConditionalTap {
+                template,
+                x: x.into(),
+                y: y.into(),
+            },
+            interval,
+        )
+    }
+
     pub fn new_tap_minutes(id: String, x: u32, y: u32, interval_minutes: u64) -> Self {
         Self::new_tap(id, x, y, Duration::from_secs(interval_minutes * 60))
     }
@@ -125,6 +277,10 @@ impl TimedEvent {
             enabled: true,
             repeating: true,
             execution_count: 0,
+            schedule: None,
+            next_fire: None,
+            jitter: Jitter::default(),
+            interval_scale: 1.0,
         }
     }
 
@@ -158,6 +314,10 @@ impl TimedEvent {
             enabled: true,
             repeating: true,
             execution_count: 0,
+            schedule: None,
+            next_fire: None,
+            jitter: Jitter::default(),
+            interval_scale: 1.0,
         }
     }
 
@@ -166,6 +326,11 @@ impl TimedEvent {
             return false;
         }
 
+        if self.schedule.is_some() {
+            // Unplanned schedules wait for the FSM instead of firing at startup
+            return self.next_fire.is_some_and(|at| Instant::now() >= at);
+        }
+
         match self.last_executed {
             None => {
                 // Never executed, ready to go
@@ -173,14 +338,14 @@ impl TimedEvent {
             }
             Some(last) => {
                 let elapsed = last.elapsed();
-                let ready = elapsed >= self.interval;
+                let ready = elapsed >= self.current_interval();
                 if ready && self.id != "countdown_update" && self.id != "screenshot" {
                     debug_print!(
                         debug_enabled,
                         "🔔 Event '{}' is ready: elapsed={:?}, interval={:?}",
                         self.id,
                         elapsed,
-                        self.interval
+                        self.current_interval()
                     );
                 }
                 ready
@@ -188,9 +353,58 @@ impl TimedEvent {
         }
     }
 
+    /// This wait: `interval` varied by the event's jitter
+    pub fn current_interval(&self) -> Duration {
+        self.interval.mul_f32(self.interval_scale)
+    }
+
+    /// Set the jitter and roll a varied interval for the current wait
+    pub fn set_jitter(&mut self, jitter: Jitter) {
+        self.jitter = jitter;
+        self.interval_scale = jitter.interval_scale();
+    }
+
     pub fn mark_executed(&mut self) {
         self.last_executed = Some(Instant::now());
         self.execution_count += 1;
+        self.interval_scale = self.jitter.interval_scale();
+        // Replanned from the device clock on the next tick
+        self.next_fire = None;
+    }
+
+    /// Switch between wall-clock schedule (`Some`) and fixed interval (`None`)
+    pub fn set_schedule(&mut self, schedule: Option<Schedule>) {
+        self.schedule = schedule;
+        self.next_fire = None;
+    }
+
+    /// Plan the next scheduled fire relative to `now` on the device clock, if not already planned
+    pub fn plan_schedule(&mut self, now: time::OffsetDateTime) {
+        if self.next_fire.is_some() {
+            return;
+        }
+        let Some(next) = self.schedule.as_ref().and_then(|s| s.next_after(now)) else {
+            return;
+        };
+        let wait = Duration::try_from(next - now).unwrap_or_default();
+        self.next_fire = Some(Instant::now() + wait);
+    }
+
+    /// Wait a full interval (or until the next scheduled time) without counting a run
+    pub fn mark_skipped(&mut self) {
+        self.last_executed = Some(Instant::now());
+        self.interval_scale = self.jitter.interval_scale();
+        self.next_fire = None;
+    }
+
+    /// Taps at a fixed point, conditional or not
+    pub fn tap_point(&self) -> Option<(Coord, Coord)> {
+        match self.event_type {
+            TimedEventType::Tap { x, y } | TimedEventType::ConditionalTap { x, y, .. } => {
+                Some((x, y))
+            }
+            _ => None,
+        }
     }
 
     pub fn set_interval(&mut self, interval: Duration) {
@@ -203,14 +417,21 @@ impl TimedEvent {
             return None;
         }
 
+        if self.schedule.is_some() {
+            return self
+                .next_fire
+                .map(|at| at.saturating_duration_since(Instant::now()));
+        }
+
         match self.last_executed {
             None => Some(Duration::from_secs(0)), // Ready now
             Some(last) => {
                 let elapsed = last.elapsed();
-                if elapsed >= self.interval {
+                let interval = self.current_interval();
+                if elapsed >= interval {
                     Some(Duration::from_secs(0)) // Ready now
                 } else {
-                    Some(self.interval - elapsed)
+                    Some(interval - elapsed)
                 }
             }
         }
@@ -221,13 +442,27 @@ impl TimedEvent {
             return None;
         }
 
+        if self.schedule.is_some() {
+            return self.next_fire;
+        }
+
         match self.last_executed {
             None => Some(Instant::now()),
-            Some(last) => Some(last + self.interval),
+            Some(last) => Some(last + self.current_interval()),
         }
     }
 }
 
+/// Shared device connection handed to the FSM when the active device changes
+#[derive(Clone)]
+pub struct AdbClientHandle(pub std::sync::Arc<tokio::sync::Mutex<crate::adb::AdbBackend>>);
+
+impl std::fmt::Debug for AdbClientHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AdbClientHandle")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum AutomationCommand {
     Start,
@@ -237,6 +472,7 @@ pub enum AutomationCommand {
     TakeScreenshot,
     TestImageRecognition,      // Test current screenshot for template matches
     RescanTemplates,           // Rescan directory for new template files
+    TestTemplate(String),      // Match one template against the latest screenshot, in detail
     AddTimedEvent(TimedEvent), // Add a new timed event
     RemoveTimedEvent(String),  // Remove timed event by ID
     EnableTimedEvent(String),  // Enable timed event by ID
@@ -244,7 +480,25 @@ pub enum AutomationCommand {
     TriggerTimedEvent(String), // Trigger timed event immediately by ID
     ListTimedEvents,           // List all configured timed events
     ClearTouchActivity,        // Clear touch activity to resume automation immediately
-    RegisterTouchActivity,     // Register touch activity to pause automation for 30 seconds
-    AdjustTimedEventInterval { id: String, delta_seconds: i64 }, // Adjust interval for timed tap events
+    RegisterTouchActivity, // Register touch activity to pause automation for the `[touch]` timeout
+    AdjustTimedEventInterval {
+        id: String,
+        delta_seconds: i64,
+    }, // Adjust interval for timed tap events
+    SetTimedEventSchedule {
+        id: String,
+        schedule: Option<Schedule>,
+    }, // Wall-clock schedule, None = back to interval
+    SwitchDevice(AdbClientHandle), // Drive a different (already connected) device
+    SaveConfig,            // Write current timed events to automation.toml
+    RunSequence(String),   // Run a `[sequences.<name>]` action sequence now
+    ExportStats(String),   // Write statistics to a .csv or .json file
+    SetNotifications(bool), // Turn `[notifications]` desktop notifications on/off
+    SetTouchTimeout(u64),  // Human-touch pause in seconds, applied to the live monitor
+    UpdateConfig {
+        config: Box<AutomationConfig>,
+        debug_enabled: bool,
+    }, // Apply and save settings edited in the GUI
+    SwitchProfile(String), // Stop, load another per-game profile, restart if it was running
     Shutdown,
 }
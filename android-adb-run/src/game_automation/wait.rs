@@ -0,0 +1,260 @@
+// Waiting for a template: capture only the area it is expected in, search it,
+// and repeat until it shows up or time runs out. Scripts, sequences and the FSM
+// build their "tap once the button appears" flows on this.
+//
+// Waiting for a stable screen: capture frames a short interval apart until two in a
+// row look the same, so a tap does not land while an animation or loading screen
+// is still moving things around.
+use super::match_image::{FrameFingerprint, GameStateDetector, TemplateMatch};
+use crate::adb::{AdbClient, AdbError, AdbResult, RgbFrame};
+use image::GrayImage;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Where the waits capture the screen from: a client already held, or a shared one
+#[allow(async_fn_in_trait)]
+pub trait CaptureSource {
+    /// The whole screen, or only `area` (x, y, width, height) of it
+    async fn capture(&self, area: Option<(u32, u32, u32, u32)>) -> AdbResult<RgbFrame>;
+}
+
+impl<C: AdbClient> CaptureSource for C {
+    async fn capture(&self, area: Option<(u32, u32, u32, u32)>) -> AdbResult<RgbFrame> {
+        match area {
+            Some((x, y, width, height)) => self.screen_capture_region(x, y, width, height).await,
+            None => self.screen_capture_rgb().await,
+        }
+    }
+}
+
+/// Locked for each capture only, so GUI taps, screenshots and touch monitoring
+/// get the device between polls
+impl<C: AdbClient> CaptureSource for Mutex<C> {
+    async fn capture(&self, area: Option<(u32, u32, u32, u32)>) -> AdbResult<RgbFrame> {
+        self.lock().await.capture(area).await
+    }
+}
+
+/// Why `wait_for_template` gave up
+#[derive(Debug)]
+pub enum WaitError {
+    /// No loaded template has that name
+    UnknownTemplate(String),
+    Timeout {
+        template: String,
+        waited: Duration,
+    },
+    /// The screen kept changing
+    Unstable {
+        waited: Duration,
+    },
+    Device(AdbError),
+    Analysis(String),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTemplate(template) => write!(f, "No template named '{}'", template),
+            Self::Timeout { template, waited } => {
+                write!(f, "'{}' not seen within {}ms", template, waited.as_millis())
+            }
+            Self::Unstable { waited } => {
+                write!(f, "Screen still changing after {}ms", waited.as_millis())
+            }
+            Self::Device(e) => write!(f, "{}", e),
+            Self::Analysis(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Poll `client` every `poll_interval` until `template` (file name or name
+/// without its region) is found, for at most `timeout`. Only the template's
+/// search area is captured, so each poll costs a fraction of a full screenshot.
+pub async fn wait_for_template<C: CaptureSource>(
+    client: &C,
+    detector: &Arc<GameStateDetector>,
+    template: &str,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Result<TemplateMatch, WaitError> {
+    let area = detector
+        .search_area_for(template)
+        .ok_or_else(|| WaitError::UnknownTemplate(template.to_string()))?;
+    let (screen_x, screen_y) = detector.screen_size();
+    let full_screen = area == (0, 0, screen_x, screen_y);
+    let start = Instant::now();
+
+    loop {
+        let frame = client
+            .capture((!full_screen).then_some(area))
+            .await
+            .map_err(WaitError::Device)?;
+
+        let detector = detector.clone();
+        let wanted = template.to_string();
+        let found = tokio::task::spawn_blocking(move || {
+            detector.find_template(
+                &wanted,
+                &place_on_screen(&frame, area, (screen_x, screen_y)),
+            )
+        })
+        .await
+        .map_err(|e| WaitError::Analysis(format!("Background analysis task failed: {}", e)))?;
+        if let Some(found) = found {
+            return Ok(found);
+        }
+
+        let waited = start.elapsed();
+        if waited >= timeout {
+            return Err(WaitError::Timeout {
+                template: template.to_string(),
+                waited,
+            });
+        }
+        tokio::time::sleep(poll_interval.min(timeout - waited)).await;
+    }
+}
+
+/// A screenshot that looked the same as the one captured `interval` before it
+#[derive(Debug)]
+pub struct StableScreen {
+    pub frame: RgbFrame,
+    /// Captures that differed from the one before; 0 when the screen was
+    /// already still since `reference`
+    pub changes: u32,
+    pub waited: Duration,
+}
+
+/// Capture the screen every `interval` until two frames in a row differ in at most
+/// `max_changed` (0.0 - 1.0) of their area, for at most `timeout`. `reference` is
+/// a frame already in hand, saving the first capture.
+pub async fn wait_for_stable_screen<C: CaptureSource>(
+    client: &C,
+    reference: Option<&RgbFrame>,
+    interval: Duration,
+    max_changed: f64,
+    timeout: Duration,
+) -> Result<StableScreen, WaitError> {
+    let start = Instant::now();
+    let mut previous = match reference {
+        Some(frame) => fingerprint(frame.clone()).await?,
+        None => {
+            let frame = client.capture(None).await.map_err(WaitError::Device)?;
+            fingerprint(frame).await?
+        }
+    };
+    let mut changes = 0;
+
+    loop {
+        let waited = start.elapsed();
+        if waited >= timeout {
+            return Err(WaitError::Unstable { waited });
+        }
+        tokio::time::sleep(interval.min(timeout - waited)).await;
+        let frame = client.capture(None).await.map_err(WaitError::Device)?;
+        let (frame, current) = fingerprint_kept(frame).await?;
+        if current.changed_fraction(&previous) <= max_changed {
+            return Ok(StableScreen {
+                frame,
+                changes,
+                waited: start.elapsed(),
+            });
+        }
+        changes += 1;
+        previous = current;
+    }
+}
+
+async fn fingerprint(frame: RgbFrame) -> Result<FrameFingerprint, WaitError> {
+    fingerprint_kept(frame)
+        .await
+        .map(|(_, fingerprint)| fingerprint)
+}
+
+/// `frame` with its fingerprint, computed off the async runtime
+async fn fingerprint_kept(frame: RgbFrame) -> Result<(RgbFrame, FrameFingerprint), WaitError> {
+    tokio::task::spawn_blocking(move || {
+        let fingerprint = FrameFingerprint::from_gray(&frame.to_luma());
+        (frame, fingerprint)
+    })
+    .await
+    .map_err(|e| WaitError::Analysis(format!("Fingerprint task failed: {}", e)))
+}
+
+/// Grayscale screen with `frame` pasted where it was captured, so matches come
+/// back in screen coordinates
+fn place_on_screen(
+    frame: &RgbFrame,
+    (x, y, _, _): (u32, u32, u32, u32),
+    (screen_x, screen_y): (u32, u32),
+) -> GrayImage {
+    let gray = frame.to_luma();
+    if (x, y) == (0, 0) && gray.dimensions() == (screen_x, screen_y) {
+        return gray;
+    }
+    let mut screen = GrayImage::new(screen_x, screen_y);
+    image::imageops::replace(&mut screen, &gray, i64::from(x), i64::from(y));
+    screen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_placed_in_screen_coordinates() {
+        let frame = RgbFrame {
+            width: 2,
+            height: 1,
+            pixels: vec![255; 6],
+        };
+        let screen = place_on_screen(&frame, (3, 4, 2, 1), (10, 10));
+        assert_eq!(screen.dimensions(), (10, 10));
+        assert_eq!(screen.get_pixel(3, 4).0, [255]);
+        assert_eq!(screen.get_pixel(4, 4).0, [255]);
+        assert_eq!(screen.get_pixel(5, 4).0, [0]);
+        assert_eq!(screen.get_pixel(0, 0).0, [0]);
+    }
+
+    #[tokio::test]
+    async fn test_stable_screen_waits_for_two_equal_frames() {
+        use crate::adb::MockAdb;
+
+        let solid = |value: u8| RgbFrame {
+            width: 64,
+            height: 64,
+            pixels: vec![value; 64 * 64 * 3],
+        };
+        let interval = Duration::from_millis(5);
+        let mock = MockAdb::from_frames("mock", vec![solid(0), solid(200), solid(200)]);
+        let stable = wait_for_stable_screen(&mock, None, interval, 0.01, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(stable.changes, 1);
+        assert_eq!(stable.frame.pixels[0], 200);
+        assert_eq!(mock.control().captures(), 3);
+
+        // Already still: one capture to confirm the frame in hand
+        let still = MockAdb::from_frames("mock", vec![solid(0)]);
+        let stable = wait_for_stable_screen(
+            &still,
+            Some(&solid(0)),
+            interval,
+            0.01,
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+        assert_eq!((stable.changes, still.control().captures()), (0, 1));
+
+        let blinking = MockAdb::from_frames("mock", vec![solid(0), solid(200)]);
+        let error =
+            wait_for_stable_screen(&blinking, None, interval, 0.01, Duration::from_millis(40))
+                .await
+                .unwrap_err();
+        assert!(matches!(error, WaitError::Unstable { .. }), "{}", error);
+    }
+}
@@ -0,0 +1,188 @@
+// Stuck watchdog - notices running automation making no progress: no template
+// has matched and the screen has not changed for `stuck_after_seconds`, e.g. an
+// unexpected dialog or a frozen loading screen. From `[watchdog]` in automation.toml:
+//
+//   [watchdog]
+//   enabled = true
+//   stuck_after_seconds = 300
+//   recovery = "sequence"          # or "back" / "restart_app"
+//   sequence = "unstick"
+//   max_recoveries = 3
+//
+// Each detection runs the recovery; after `max_recoveries` in a row without a
+// template match automation pauses instead.
+use super::match_image::FrameFingerprint;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// What to do when automation is stuck
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StuckRecovery {
+    /// Press the Android BACK key
+    Back,
+    /// Force-stop and relaunch the `[app]` package
+    RestartApp,
+    /// Run the `[sequences.<sequence>]` action sequence
+    Sequence,
+}
+
+impl fmt::Display for StuckRecovery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StuckRecovery::Back => "press BACK",
+            StuckRecovery::RestartApp => "restart app",
+            StuckRecovery::Sequence => "run sequence",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    pub stuck_after_seconds: u64,
+    /// Fraction (0.0 - 1.0) of the screen that has to change to count as
+    /// progress; small animations such as a spinner stay under it
+    pub change_threshold: f64,
+    pub recovery: StuckRecovery,
+    /// Sequence name for `recovery = "sequence"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<String>,
+    /// Recoveries in a row without a template match before pausing; 0 = never pause
+    pub max_recoveries: u32,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stuck_after_seconds: 300,
+            change_threshold: 0.05,
+            recovery: StuckRecovery::Back,
+            sequence: None,
+            max_recoveries: 3,
+        }
+    }
+}
+
+impl WatchdogConfig {
+    pub fn stuck_after(&self) -> Duration {
+        Duration::from_secs(self.stuck_after_seconds.max(1))
+    }
+}
+
+/// Latest stuck detection, shown in the GUI and sent as `StuckDetected`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StuckDetection {
+    /// Seconds without a match or screen change
+    pub idle_seconds: u64,
+    pub recovery: String,
+    /// Detections in a row without a template match
+    pub attempt: u32,
+}
+
+/// Tracks the last sign of progress of a running automation
+#[derive(Debug, Default)]
+pub struct Watchdog {
+    reference: Option<FrameFingerprint>,
+    last_progress: Option<Instant>,
+    recoveries: u32,
+}
+
+impl Watchdog {
+    /// Start counting from `now`, e.g. when automation (re)enters Running
+    pub fn reset(&mut self, now: Instant) {
+        self.reference = None;
+        self.last_progress = Some(now);
+        self.recoveries = 0;
+    }
+
+    pub fn saw_match(&mut self, now: Instant) {
+        self.last_progress = Some(now);
+        self.recoveries = 0;
+    }
+
+    /// Compare a screenshot with the last one that changed the screen
+    pub fn saw_frame(&mut self, fingerprint: FrameFingerprint, threshold: f64, now: Instant) {
+        let unchanged = self
+            .reference
+            .as_ref()
+            .is_some_and(|reference| fingerprint.is_unchanged(reference, threshold));
+        if !unchanged {
+            self.reference = Some(fingerprint);
+            self.last_progress = Some(now);
+        }
+    }
+
+    /// How long automation has made no progress, once that reaches `stuck_after`
+    pub fn stuck_for(&self, config: &WatchdogConfig, now: Instant) -> Option<Duration> {
+        let idle = now.saturating_duration_since(self.last_progress?);
+        (config.enabled && idle >= config.stuck_after()).then_some(idle)
+    }
+
+    /// Count a recovery and give it a full `stuck_after` to work.
+    /// False once `max_recoveries` have run without a template match.
+    pub fn start_recovery(&mut self, config: &WatchdogConfig, now: Instant) -> bool {
+        if config.max_recoveries > 0 && self.recoveries >= config.max_recoveries {
+            return false;
+        }
+        self.recoveries += 1;
+        self.last_progress = Some(now);
+        true
+    }
+
+    pub fn recoveries(&self) -> u32 {
+        self.recoveries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::GrayImage;
+
+    fn frame(shade: u8) -> FrameFingerprint {
+        FrameFingerprint::from_gray(&GrayImage::from_pixel(64, 64, image::Luma([shade])))
+    }
+
+    #[test]
+    fn test_watchdog_detects_stuck_screen() {
+        let config: WatchdogConfig = toml::from_str(
+            "enabled = true\nstuck_after_seconds = 60\nrecovery = \"restart_app\"\nmax_recoveries = 2",
+        )
+        .unwrap();
+        assert_eq!(config.recovery, StuckRecovery::RestartApp);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        let mut watchdog = Watchdog::default();
+        assert_eq!(watchdog.stuck_for(&config, at(600)), None, "not started");
+        watchdog.reset(start);
+        watchdog.saw_frame(frame(10), config.change_threshold, at(1));
+        watchdog.saw_frame(frame(10), config.change_threshold, at(50));
+        assert_eq!(watchdog.stuck_for(&config, at(60)), None);
+        assert_eq!(watchdog.stuck_for(&config, at(61)), Some(at(61) - at(1)));
+
+        // A new screen is progress
+        watchdog.saw_frame(frame(200), config.change_threshold, at(70));
+        assert_eq!(watchdog.stuck_for(&config, at(129)), None);
+
+        assert!(watchdog.start_recovery(&config, at(130)));
+        assert_eq!(watchdog.stuck_for(&config, at(150)), None);
+        assert!(watchdog.start_recovery(&config, at(190)));
+        assert!(
+            !watchdog.start_recovery(&config, at(250)),
+            "out of recoveries"
+        );
+
+        // A match starts the count over
+        watchdog.saw_match(at(260));
+        assert!(watchdog.start_recovery(&config, at(320)));
+        assert_eq!(watchdog.recoveries(), 1);
+
+        let off = WatchdogConfig::default();
+        assert_eq!(watchdog.stuck_for(&off, at(10_000)), None);
+    }
+}
@@ -1,9 +1,17 @@
 // gui/components/actions.rs
+use crate::game_automation::config::active_config;
+use crate::game_automation::coord::resolve_point;
+use crate::game_automation::schedule::Schedule;
+use crate::game_automation::sequence::SequenceStatus;
 use crate::game_automation::types::{
-    MAX_TAP_INTERVAL_SECONDS, MIN_TAP_INTERVAL_SECONDS, TimedEvent, TimedEventType,
+    MAX_TAP_INTERVAL_SECONDS, MIN_TAP_INTERVAL_SECONDS, OrientationStatus, TimedEvent,
+    TimedEventType,
 };
 use crate::game_automation::{AutomationCommand, GameState};
+use crate::gui::components::profile_picker::ProfilePicker;
+use crate::gui::components::timed_event_editor::TimedEventEditor;
 use crate::gui::dioxus_app::AppContext;
+use crate::gui::preferences::save_window_geometry;
 use dioxus::prelude::*;
 use tokio::sync::mpsc;
 
@@ -14,22 +22,36 @@ pub fn Actions() -> Element {
     // Access grouped signals via the new structure
     let mut screenshot_status = ctx.screenshot.status;
     let screenshot_bytes = ctx.screenshot.bytes;
+    let mut mirroring = ctx.screenshot.mirroring;
+    let mirror_fps = ctx.screenshot.mirror_fps;
 
     let mut auto_update_on_touch = ctx.interaction.auto_update_on_touch;
     let mut select_box = ctx.interaction.select_box;
     let hover_tap_preview = ctx.interaction.hover_tap_preview;
+    let mut editing_event = ctx.interaction.editing_event;
 
     let automation_state = ctx.automation.state;
     let automation_command_tx = ctx.automation.command_tx;
     let timed_events_list = ctx.automation.timed_events_list;
     let is_paused_by_touch = ctx.automation.is_paused_by_touch;
     let touch_timeout_remaining = ctx.automation.touch_timeout_remaining;
+    let health_pause = ctx.automation.health_pause;
+    let recovery = ctx.automation.recovery;
+    let sequence_progress = ctx.automation.sequence_progress;
+    let custom_state = ctx.automation.custom_state;
+    let orientation = ctx.automation.orientation;
+    let sleeping_until = ctx.automation.sleeping_until;
+    let stuck = ctx.automation.stuck;
+    let device_info = ctx.device.info;
+    // Starts from automation.toml; 💾 Save keeps the choice
+    let mut notifications = use_signal(|| active_config().notifications.enabled);
 
     rsx! {
-        div { style: "background: rgba(255,255,255,0.1); backdrop-filter: blur(10px); padding: 15px; border-radius: 15px; margin-bottom: 15px; border: 1px solid rgba(255,255,255,0.2);",
+        div { class: "panel", style: "padding: 15px; border-radius: 15px; margin-bottom: 15px;",
             // Header
-            div { style: "display: flex; align-items: center; justify-content: center; margin-bottom: 15px;",
-                h2 { style: "margin: 0; color: #87ceeb; font-size: 1.1em;", "🤖 Automation Controls" }
+            div { style: "display: flex; align-items: center; justify-content: center; gap: 10px; margin-bottom: 15px;",
+                h2 { style: "margin: 0; color: var(--accent); font-size: 1.1em;", "🤖 Automation Controls" }
+                ProfilePicker {}
             }
 
             // Controls
@@ -50,11 +72,27 @@ pub fn Actions() -> Element {
                                 "Paused - Activity".to_string()
                             };
                             (text, "background: #ff6b6b; color: white; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; font-weight: 600;".to_string())
+                        } else if let (Some(reason), GameState::Paused) = (health_pause.read().clone(), &state) {
+                            (format!("Paused - {}", reason), "background: #dc3545; color: white; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; font-weight: 600;".to_string())
                         } else {
                             let (text, style_str) = match state {
                                 GameState::Idle => ("Idle".to_string(), "background: #666; color: white; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; font-weight: 600;"),
                                 GameState::Running => ("Running".to_string(), "background: #28a745; color: white; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; font-weight: 600;"),
                                 GameState::Paused => ("Paused".to_string(), "background: #fd7e14; color: white; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; font-weight: 600;"),
+                                GameState::Recovering => (
+                                    match recovery.read().as_ref() {
+                                        Some(r) => format!("Recovering {}/{}", r.attempt, r.max_attempts),
+                                        None => "Recovering".to_string(),
+                                    },
+                                    "background: #6f42c1; color: white; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; font-weight: 600;",
+                                ),
+                                GameState::Sleeping => (
+                                    match sleeping_until.read().as_ref() {
+                                        Some(until) => format!("😴 Sleeping until {}", until),
+                                        None => "😴 Sleeping".to_string(),
+                                    },
+                                    "background: #34495e; color: white; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; font-weight: 600;",
+                                ),
                             };
                             (text, style_str.to_string())
                         };
@@ -63,6 +101,30 @@ pub fn Actions() -> Element {
                         }
                     }
 
+                    // Where in the game the [states] rules place automation
+                    if let Some(name) = custom_state.read().as_ref() {
+                        div { style: "background: #17a2b8; color: white; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; font-weight: 600;",
+                            title: "Game state from the [states] rules in automation.toml",
+                            "🗺️ {name}"
+                        }
+                    }
+
+                    // Turned away from the orientation the taps and templates were made in
+                    if let Some(status) = (*orientation.read()).filter(OrientationStatus::rotated) {
+                        div { style: "background: #ffc107; color: #222; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; font-weight: 600;",
+                            title: "Expected {status.expected} ([orientation] in automation.toml): matching waits until the device turns back",
+                            "🔄 {status.current}"
+                        }
+                    }
+
+                    // [watchdog] found no progress and ran its recovery
+                    if let Some(detection) = stuck.read().as_ref() {
+                        div { style: "background: #e83e8c; color: white; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; font-weight: 600;",
+                            title: "No match or screen change for {detection.idle_seconds}s ([watchdog] in automation.toml)",
+                            "🧭 Stuck: {detection.recovery} ({detection.attempt})"
+                        }
+                    }
+
                     // Control buttons - show Resume when touch paused
                     {
                         let is_touch_paused = *is_paused_by_touch.read();
@@ -109,7 +171,7 @@ pub fn Actions() -> Element {
                                     }
                                 }
                             }
-                            if effective_state == GameState::Running {
+                            if matches!(effective_state, GameState::Running | GameState::Recovering | GameState::Sleeping) {
                                 button { style: "background: linear-gradient(45deg, #fd7e14, #f39c12); color: white; padding: 6px 12px; border: none; border-radius: 6px; cursor: pointer; font-size: 0.85em; font-weight: bold;",
                                     onclick: move |_| {
                                         if let Some(tx) = automation_command_tx.read().as_ref() {
@@ -171,11 +233,37 @@ pub fn Actions() -> Element {
                         }
                     }
                     button { style: "background: linear-gradient(45deg, #dc3545, #e74c3c); color: white; padding: 8px 16px; border: none; border-radius: 6px; cursor: pointer; font-size: 0.9em; font-weight: bold;",
-                        onclick: move |_| { std::thread::spawn(|| std::process::exit(0)); },
+                        onclick: move |_| {
+                            save_window_geometry(ctx.preferences);
+                            std::thread::spawn(|| std::process::exit(0));
+                        },
                         "🚪 Exit"
                     }
                 }
 
+                // Running or last action sequence
+                if let Some(progress) = sequence_progress.read().as_ref() {
+                    {
+                        let (text, color) = match &progress.status {
+                            SequenceStatus::Running => (
+                                format!("🧩 {} {}/{}: {}", progress.name, progress.step, progress.total, progress.description),
+                                "var(--accent)",
+                            ),
+                            SequenceStatus::Completed if progress.failed_steps > 0 => (
+                                format!("🧩 {} done, {} step(s) failed", progress.name, progress.failed_steps),
+                                "#ffc107",
+                            ),
+                            SequenceStatus::Completed => (format!("🧩 {} done", progress.name), "#28a745"),
+                            SequenceStatus::Failed { error } => (format!("🧩 {} failed at {}", progress.name, error), "#ff6b6b"),
+                        };
+                        rsx! {
+                            div { style: "font-size: 0.8em; color: {color}; text-align: center;", "{text}" }
+                        }
+                    }
+                }
+
+                TimedEventEditor {}
+
                 // Timed Events List Display
                 if !timed_events_list.read().is_empty() {
                     div { style: "background: rgba(0,0,0,0.2); border-radius: 8px; padding: 10px 12px; border: 1px solid rgba(255,255,255,0.2);",
@@ -188,8 +276,21 @@ pub fn Actions() -> Element {
 
                             rsx! {
                                 div { style: "display: flex; align-items: center; gap: 6px; margin-bottom: 8px;",
-                                    span { style: "font-size: 0.9em; color: #87ceeb; font-weight: bold;", "🕒 Timed Events" }
+                                    span { style: "font-size: 0.9em; color: var(--accent); font-weight: bold;", "🕒 Timed Events" }
                                     span { style: "font-size: 0.75em; color: #ccc;", "({visible_events.len()} events)" }
+                                    button {
+                                        style: "margin-left: auto; background: rgba(255,255,255,0.08); color: var(--accent); border: 1px solid rgba(135,206,235,0.45); border-radius: 4px; padding: 2px 6px; font-size: 0.7em; cursor: pointer;",
+                                        title: "Save taps and intervals to automation.toml",
+                                        onclick: move |_| {
+                                            if let Some(tx) = automation_command_tx.read().as_ref() {
+                                                let tx = tx.clone();
+                                                spawn(async move {
+                                                    let _ = tx.send(AutomationCommand::SaveConfig).await;
+                                                });
+                                            }
+                                        },
+                                        "💾 Save config"
+                                    }
                                 }
 
                                 // Individual event displays
@@ -197,14 +298,15 @@ pub fn Actions() -> Element {
                                     div {
                                         style: "background: rgba(255,255,255,0.05); border-radius: 6px; padding: 8px; margin-bottom: 6px; border: 1px solid rgba(255,255,255,0.1);",
                                         onmouseenter: {
-                                            let event_type = event.event_type.clone();
+                                            // Percentages land where they would on this device
+                                            let screen = device_info
+                                                .read()
+                                                .as_ref()
+                                                .map_or((0, 0), |info| (info.screen_x, info.screen_y));
+                                            let tap_point = event.tap_point().map(|point| resolve_point(point, screen));
                                             let mut hover_signal = hover_tap_preview;
                                             move |_| {
-                                                if let TimedEventType::Tap { x, y } = event_type {
-                                                    hover_signal.set(Some((x, y)));
-                                                } else {
-                                                    hover_signal.set(None);
-                                                }
+                                                hover_signal.set(tap_point);
                                             }
                                         },
                                         onmouseleave: {
@@ -222,15 +324,19 @@ pub fn Actions() -> Element {
                                                             TimedEventType::Screenshot => "📸".to_string(),
                                                             TimedEventType::Tap { .. } => "👆".to_string(),
                                                             TimedEventType::CountdownUpdate => "⏰".to_string(),
+                                                            TimedEventType::Script { .. } => "📜".to_string(),
+                                                            TimedEventType::ConditionalTap { .. } => "🎯".to_string(),
+                                                            TimedEventType::Sequence { .. } => "🧩".to_string(),
+                                                            TimedEventType::BackupFiles { .. } => "💾".to_string(),
                                                         }
                                                     }
                                                 }
                                                 span {
-                                                    style: "font-size: 0.8em; color: #87ceeb;",
+                                                    style: "font-size: 0.8em; color: var(--accent);",
                                                     {event.id.clone()}
                                                 }
                                                 span {
-                                                    style: "font-size: 0.7em; color: #ffd700; background: rgba(255,215,0,0.1); padding: 1px 4px; border-radius: 8px; font-weight: bold;",
+                                                    style: "font-size: 0.7em; color: var(--value); background: rgba(255,215,0,0.1); padding: 1px 4px; border-radius: 8px; font-weight: bold;",
                                                     "({event.execution_count})"
                                                 }
                                             }
@@ -291,6 +397,36 @@ pub fn Actions() -> Element {
                                                     },
                                                     "🔫"
                                                 }
+                                                if !matches!(event.event_type, TimedEventType::BackupFiles { .. }) {
+                                                    button {
+                                                        style: "background: rgba(255,255,255,0.08); color: var(--accent); padding: 2px 6px; border-radius: 10px; font-size: 0.7em; border: 1px solid rgba(135,206,235,0.45); cursor: pointer;",
+                                                        title: "Edit this event",
+                                                        onclick: {
+                                                            let event = event.clone();
+                                                            move |_| editing_event.set(Some(event.clone()))
+                                                        },
+                                                        "✏️"
+                                                    }
+                                                }
+                                                if !matches!(event.event_type, TimedEventType::Screenshot) {
+                                                    button {
+                                                        style: "background: rgba(220,53,69,0.25); color: #ff6b6b; padding: 2px 6px; border-radius: 10px; font-size: 0.7em; border: 1px solid rgba(220,53,69,0.5); cursor: pointer;",
+                                                        title: "Delete this event",
+                                                        onclick: {
+                                                            let event_id = event.id.clone();
+                                                            move |_| {
+                                                                if let Some(tx) = automation_command_tx.read().as_ref() {
+                                                                    let tx = tx.clone();
+                                                                    let event_id = event_id.clone();
+                                                                    spawn(async move {
+                                                                        let _ = tx.send(AutomationCommand::RemoveTimedEvent(event_id)).await;
+                                                                    });
+                                                                }
+                                                            }
+                                                        },
+                                                        "🗑"
+                                                    }
+                                                }
                                             }
                                         }
 
@@ -301,12 +437,16 @@ pub fn Actions() -> Element {
                                                 span {
                                                     style: "font-size: 0.75em;",
                                                     {
-                                                        let seconds = event.interval.as_secs();
-                                                        let label = format_interval_short(seconds);
-                                                        format!("Interval: {} ({}s)", label, seconds)
+                                                        if let Some(schedule) = &event.schedule {
+                                                            format!("📅 {}", schedule)
+                                                        } else {
+                                                            let seconds = event.interval.as_secs();
+                                                            let label = format_interval_short(seconds);
+                                                            format!("Interval: {} ({}s)", label, seconds)
+                                                        }
                                                     }
                                                 }
-                                                if let TimedEventType::Tap { x, y } = &event.event_type {
+                                                if let Some((x, y)) = event.tap_point() {
                                                     span {
                                                         style: "font-size: 0.75em; color: #999;",
                                                         {
@@ -314,9 +454,15 @@ pub fn Actions() -> Element {
                                                         }
                                                     }
                                                 }
+                                                if let TimedEventType::ConditionalTap { template, .. } = &event.event_type {
+                                                    span {
+                                                        style: "font-size: 0.75em; color: #999;",
+                                                        "if '{template}' visible"
+                                                    }
+                                                }
                                             }
 
-                                            div { style: "color: #87ceeb; font-weight: bold;",
+                                            div { style: "color: var(--accent); font-weight: bold;",
                                                 {
                                                     if let Some(time_until) = event.time_until_next() {
                                                         let seconds = time_until.as_secs();
@@ -324,9 +470,13 @@ pub fn Actions() -> Element {
                                                             "Ready Now".to_string()
                                                         } else if seconds < 60 {
                                                             format!("{}s", seconds)
-                                                        } else {
+                                                        } else if seconds < 3600 {
                                                             format!("{}m {}s", seconds / 60, seconds % 60)
+                                                        } else {
+                                                            format!("{}h {}m", seconds / 3600, (seconds % 3600) / 60)
                                                         }
+                                                    } else if event.enabled {
+                                                        "Scheduling…".to_string()
                                                     } else {
                                                         "Disabled".to_string()
                                                     }
@@ -334,13 +484,21 @@ pub fn Actions() -> Element {
                                             }
                                         }
 
-                                        if event.enabled {
+                                        if event.tap_point().is_some() {
+                                            ScheduleEditor {
+                                                key: "{event.id}",
+                                                event_id: event.id.clone(),
+                                                schedule: event.schedule.as_ref().map(|s| s.to_string()),
+                                            }
+                                        }
+
+                                        if event.enabled && event.schedule.is_none() {
                                             div { style: "margin-top: 4px; background: rgba(255,255,255,0.1); border-radius: 3px; height: 4px; overflow: hidden;",
                                                 div {
                                                     style: {
 
                                                         if let Some(time_until) = event.time_until_next() {
-                                                            let total_seconds = event.interval.as_secs() as f64;
+                                                            let total_seconds = event.current_interval().as_secs() as f64;
                                                             let remaining_seconds = time_until.as_secs() as f64;
                                                             let progress = ((total_seconds - remaining_seconds) / total_seconds * 100.0).clamp(0.0, 100.0);
                                                             format!("background: linear-gradient(90deg, #28a745, #20c997); width: {}%; height: 100%; transition: width 0.5s ease;", progress)
@@ -437,6 +595,41 @@ pub fn Actions() -> Element {
                         }
                         label { r#for: "select-box-checkbox", style: "font-size: 0.85em; cursor: pointer; user-select: none;", "🟦 Select box" }
                     }
+                    div { style: "display: flex; align-items: center; gap: 6px;",
+                        input {
+                            r#type: "checkbox",
+                            id: "live-mirror-checkbox",
+                            checked: *mirroring.read(),
+                            onchange: move |evt| mirroring.set(evt.value().parse().unwrap_or(false)),
+                            style: "width: 14px; height: 14px; cursor: pointer;"
+                        }
+                        label { r#for: "live-mirror-checkbox", style: "font-size: 0.85em; cursor: pointer; user-select: none;",
+                            if let Some(fps) = *mirror_fps.read() {
+                                "🎥 Live ({fps:.1} fps)"
+                            } else {
+                                "🎥 Live"
+                            }
+                        }
+                    }
+                    div { style: "display: flex; align-items: center; gap: 6px;",
+                        input {
+                            r#type: "checkbox",
+                            id: "notifications-checkbox",
+                            checked: *notifications.read(),
+                            onchange: move |evt| {
+                                let enabled = evt.value().parse().unwrap_or(false);
+                                notifications.set(enabled);
+                                if let Some(tx) = automation_command_tx.read().as_ref() {
+                                    let tx = tx.clone();
+                                    spawn(async move {
+                                        let _ = tx.send(AutomationCommand::SetNotifications(enabled)).await;
+                                    });
+                                }
+                            },
+                            style: "width: 14px; height: 14px; cursor: pointer;"
+                        }
+                        label { r#for: "notifications-checkbox", style: "font-size: 0.85em; cursor: pointer; user-select: none;", "🔔 Notifications" }
+                    }
                 }
             }
         }
@@ -447,7 +640,7 @@ fn render_tap_interval_controls(
     event: &TimedEvent,
     automation_command_tx: Signal<Option<mpsc::Sender<AutomationCommand>>>,
 ) -> Element {
-    if let TimedEventType::Tap { .. } = &event.event_type {
+    if event.tap_point().is_some() {
         let interval_secs = event.interval.as_secs();
         let adjust_step = interval_adjust_step(interval_secs);
         let step_label = format_interval_short(adjust_step);
@@ -462,7 +655,7 @@ fn render_tap_interval_controls(
             div { style: "display: flex; gap: 4px; align-items: center;",
                 button {
                     style: if can_increase {
-                        "background: rgba(255,255,255,0.08); color: #87ceeb; border: 1px solid rgba(135,206,235,0.45); border-radius: 3px; width: 18px; height: 18px; display: flex; align-items: center; justify-content: center; font-size: 0.55em; cursor: pointer; transition: all 0.2s ease;"
+                        "background: rgba(255,255,255,0.08); color: var(--accent); border: 1px solid rgba(135,206,235,0.45); border-radius: 3px; width: 18px; height: 18px; display: flex; align-items: center; justify-content: center; font-size: 0.55em; cursor: pointer; transition: all 0.2s ease;"
                     } else {
                         "background: rgba(255,255,255,0.03); color: #666; border: 1px solid rgba(255,255,255,0.1); border-radius: 3px; width: 18px; height: 18px; display: flex; align-items: center; justify-content: center; font-size: 0.55em; cursor: not-allowed;"
                     },
@@ -492,7 +685,7 @@ fn render_tap_interval_controls(
                 }
                 button {
                     style: if can_decrease {
-                        "background: rgba(255,255,255,0.08); color: #87ceeb; border: 1px solid rgba(135,206,235,0.45); border-radius: 3px; width: 18px; height: 18px; display: flex; align-items: center; justify-content: center; font-size: 0.55em; cursor: pointer; transition: all 0.2s ease;"
+                        "background: rgba(255,255,255,0.08); color: var(--accent); border: 1px solid rgba(135,206,235,0.45); border-radius: 3px; width: 18px; height: 18px; display: flex; align-items: center; justify-content: center; font-size: 0.55em; cursor: pointer; transition: all 0.2s ease;"
                     } else {
                         "background: rgba(255,255,255,0.03); color: #666; border: 1px solid rgba(255,255,255,0.1); border-radius: 3px; width: 18px; height: 18px; display: flex; align-items: center; justify-content: center; font-size: 0.55em; cursor: not-allowed;"
                     },
@@ -527,6 +720,50 @@ fn render_tap_interval_controls(
     }
 }
 
+/// Text field for a tap's wall-clock schedule; empty returns it to its interval
+#[component]
+fn ScheduleEditor(event_id: String, schedule: Option<String>) -> Element {
+    let automation_command_tx = use_context::<AppContext>().automation.command_tx;
+    let mut error = use_signal(|| None::<String>);
+
+    rsx! {
+        div { style: "display: flex; align-items: center; gap: 6px; margin-top: 4px; font-size: 0.7em;",
+            span { style: "color: #999;", "📅" }
+            input {
+                r#type: "text",
+                style: "flex: 1; background: rgba(0,0,0,0.25); color: #eee; border: 1px solid rgba(255,255,255,0.2); border-radius: 4px; padding: 2px 6px; font-size: 1em;",
+                placeholder: "interval (e.g. daily at 08:00, mon,fri at 07:30)",
+                value: schedule.clone().unwrap_or_default(),
+                title: "Press Enter to apply. Times are device local time; clear to use the interval.",
+                onchange: move |evt| {
+                    let text = evt.value();
+                    let parsed = if text.trim().is_empty() {
+                        Ok(None)
+                    } else {
+                        text.parse::<Schedule>().map(Some)
+                    };
+                    match parsed {
+                        Ok(schedule) => {
+                            error.set(None);
+                            if let Some(tx) = automation_command_tx.read().as_ref() {
+                                let tx = tx.clone();
+                                let id = event_id.clone();
+                                spawn(async move {
+                                    let _ = tx.send(AutomationCommand::SetTimedEventSchedule { id, schedule }).await;
+                                });
+                            }
+                        }
+                        Err(e) => error.set(Some(e)),
+                    }
+                },
+            }
+            if let Some(e) = error.read().as_ref() {
+                span { style: "color: #ff6b6b;", "{e}" }
+            }
+        }
+    }
+}
+
 fn format_interval_short(seconds: u64) -> String {
     if seconds == 0 {
         return "0s".to_string();
@@ -0,0 +1,92 @@
+// gui/components/counters_panel.rs
+use crate::game_automation::counters::CounterSeries;
+use crate::gui::dioxus_app::AppContext;
+use dioxus::prelude::*;
+
+/// Size of each counter graph, in SVG user units
+const GRAPH_WIDTH: f64 = 200.0;
+const GRAPH_HEIGHT: f64 = 40.0;
+
+/// `[counters]` values read by OCR, each with its latest value and a graph of
+/// the recent readings; hidden until the first reading
+#[component]
+pub fn CountersPanel() -> Element {
+    let ctx = use_context::<AppContext>();
+    let readings = ctx.automation.counters.read().clone();
+    if readings.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "panel", style: "padding: 12px; border-radius: 12px;",
+            h2 { style: "margin: 0 0 8px 0; color: var(--accent); font-size: 0.95em;", "📈 Counters" }
+            for (name, series) in readings {
+                {
+                    let latest = series.latest().map_or("-".to_string(), |value| value.to_string());
+                    let (low, high) = series.range().unwrap_or_default();
+                    let points = sparkline_points(&series, GRAPH_WIDTH, GRAPH_HEIGHT);
+                    rsx! {
+                        div { key: "{name}", style: "margin-bottom: 6px;",
+                            p { style: "margin:2px 0; font-size:0.8em;",
+                                strong { "{name}: " }
+                                span { style: "color:var(--value);", "{latest}" }
+                                span { style: "opacity:0.6; font-size:0.9em;", "  ({low} - {high}, {series.samples.len()} readings)" }
+                            }
+                            svg {
+                                style: "width:100%; height:{GRAPH_HEIGHT}px; background:rgba(0,0,0,0.2); border-radius:4px;",
+                                view_box: "0 0 {GRAPH_WIDTH} {GRAPH_HEIGHT}",
+                                preserve_aspect_ratio: "none",
+                                polyline { points: "{points}", fill: "none", stroke: "var(--accent)", stroke_width: "1.5", vector_effect: "non-scaling-stroke" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// SVG polyline points for `series`, oldest reading on the left and the
+/// lowest value at the bottom; a flat series is drawn through the middle
+fn sparkline_points(series: &CounterSeries, width: f64, height: f64) -> String {
+    let Some((low, high)) = series.range() else {
+        return String::new();
+    };
+    let steps = series.samples.len().saturating_sub(1).max(1) as f64;
+    let span = (high - low) as f64;
+    series
+        .samples
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| {
+            let x = width * i as f64 / steps;
+            let y = if span == 0.0 {
+                height / 2.0
+            } else {
+                height - height * (sample.value - low) as f64 / span
+            };
+            format!("{:.1},{:.1}", x, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_automation::counters::CounterSample;
+
+    #[test]
+    fn test_sparkline_points() {
+        let mut series = CounterSeries::default();
+        assert_eq!(sparkline_points(&series, 100.0, 10.0), "");
+        series.push(CounterSample { at: 0, value: 5 });
+        assert_eq!(sparkline_points(&series, 100.0, 10.0), "0.0,5.0");
+        series.push(CounterSample { at: 60, value: 15 });
+        series.push(CounterSample { at: 120, value: 10 });
+        assert_eq!(
+            sparkline_points(&series, 100.0, 10.0),
+            "0.0,10.0 50.0,0.0 100.0,5.0"
+        );
+    }
+}
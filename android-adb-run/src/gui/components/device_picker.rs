@@ -0,0 +1,137 @@
+// gui/components/device_picker.rs
+use crate::adb::AdbClient;
+use crate::adb::discovery::is_tcp_address;
+use crate::adb::wireless;
+use crate::gui::dioxus_app::AppContext;
+use dioxus::prelude::*;
+
+/// Drop-down listing attached devices; picking one switches the active device
+#[component]
+pub fn DevicePicker() -> Element {
+    let ctx = use_context::<AppContext>();
+    let available = ctx.device.available;
+    let mut selected = ctx.device.selected;
+    // A tab opened for one device stays on it
+    if ctx.device.pinned.read().is_some() {
+        return rsx! {};
+    }
+
+    // Devices open in their own tab are driven there
+    let claimed = ctx.device.claimed.read().clone();
+    let devices: Vec<_> = available
+        .read()
+        .iter()
+        .filter(|device| !claimed.iter().any(|key| key == device.key()))
+        .cloned()
+        .collect();
+    if devices.len() < 2 {
+        return rsx! {};
+    }
+    let current = selected.read().clone().unwrap_or_default();
+
+    rsx! {
+        select {
+            style: "background: rgba(0,0,0,0.3); color: var(--value); border: 1px solid rgba(255,215,0,0.4); padding: 3px 8px; border-radius: 6px; font-size: 0.75em; font-weight: 600;",
+            title: "Switch active device",
+            value: "{current}",
+            onchange: move |evt| {
+                let name = evt.value();
+                if !name.is_empty() {
+                    selected.set(Some(name));
+                }
+            },
+            for device in devices {
+                option {
+                    key: "{device.key()}",
+                    value: "{device.key()}",
+                    selected: device.key() == current,
+                    {
+                        match &device.serial {
+                            Some(serial) => format!("📱 {} ({})", device.name, serial),
+                            None => format!("📱 {}", device.name),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Port used when switching a USB device to wireless ADB
+const WIFI_ADB_PORT: u16 = wireless::DEFAULT_TCP_PORT;
+
+/// Moves the active USB device to wireless ADB and selects the new connection
+#[component]
+pub fn SwitchToWifi() -> Element {
+    let ctx = use_context::<AppContext>();
+    let shared_adb_client = ctx.shared_adb_client;
+    let pool = ctx.device.pool;
+    let mut selected = ctx.device.selected;
+    let mut screenshot_status = ctx.screenshot.status;
+    let mut is_switching = use_signal(|| false);
+    // Reconnecting under a new address would leave the tab's device behind
+    if ctx.device.pinned.read().is_some() {
+        return rsx! {};
+    }
+
+    let current = selected.read().clone().unwrap_or_default();
+    if is_tcp_address(&current) {
+        return rsx! {
+            span { style: "background: rgba(0,0,0,0.25); color:#8ad0ff; border:1px solid rgba(138,208,255,0.35); padding:3px 8px; border-radius:6px; font-size:0.75em; font-weight:600;", "📶 WiFi" }
+        };
+    }
+
+    rsx! {
+        button {
+            style: "background: rgba(0,0,0,0.3); color:#8ad0ff; border:1px solid rgba(138,208,255,0.4); padding:3px 8px; border-radius:6px; font-size:0.75em; font-weight:600; cursor:pointer;",
+            disabled: *is_switching.read(),
+            title: "Enable ADB over TCP/IP and reconnect without the cable",
+            onclick: move |_| {
+                let Some(client) = shared_adb_client.read().clone() else {
+                    return;
+                };
+                let pool = pool.read().clone();
+                is_switching.set(true);
+                spawn(async move {
+                    screenshot_status.set("📶 Looking up device WiFi address...".to_string());
+                    let ip = {
+                        let guard = client.lock().await;
+                        match guard.get_device_ip().await {
+                            Ok(ip) => {
+                                if let Err(e) = guard.enable_tcpip(WIFI_ADB_PORT).await {
+                                    screenshot_status.set(format!("❌ Enabling TCP/IP failed: {}", e));
+                                    is_switching.set(false);
+                                    return;
+                                }
+                                ip
+                            }
+                            Err(e) => {
+                                screenshot_status.set(format!("❌ No WiFi address: {}", e));
+                                is_switching.set(false);
+                                return;
+                            }
+                        }
+                    };
+
+                    let address = format!("{}:{}", ip, WIFI_ADB_PORT);
+                    screenshot_status.set(format!("📶 Connecting to {}...", address));
+                    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+                    match pool.connect(&address).await {
+                        Ok(_) => {
+                            screenshot_status.set(format!("✅ Connected over WiFi ({})", address));
+                            selected.set(Some(address));
+                        }
+                        Err(e) => {
+                            screenshot_status.set(format!(
+                                "❌ WiFi connect failed: {} - run 'adb tcpip {}' or enable Wireless debugging",
+                                e, WIFI_ADB_PORT
+                            ));
+                        }
+                    }
+                    is_switching.set(false);
+                });
+            },
+            if *is_switching.read() { "📶 Switching..." } else { "📶 Switch to WiFi" }
+        }
+    }
+}
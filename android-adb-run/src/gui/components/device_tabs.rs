@@ -0,0 +1,190 @@
+// gui/components/device_tabs.rs
+use crate::adb::Device;
+use crate::game_automation::GameState;
+use dioxus::prelude::*;
+use std::collections::BTreeMap;
+
+/// Key of the first tab, which follows the device picker; other tabs use their device name
+pub const PRIMARY_TAB: &str = "primary";
+
+/// What the tab bar shows about one tab's automation
+#[derive(Debug, Clone, PartialEq)]
+pub struct TabSummary {
+    pub device: Option<String>,
+    pub state: GameState,
+    pub connected: bool,
+}
+
+/// Window-wide tab state, shared by every device session
+#[derive(Clone, Copy)]
+pub struct DeviceTabs {
+    /// Devices opened in a tab of their own, in tab order
+    pub pinned: Signal<Vec<String>>,
+    /// Key of the tab on screen
+    pub active: Signal<String>,
+    /// Latest summary per tab key
+    pub summaries: Signal<BTreeMap<String, TabSummary>>,
+    /// The first tab's picker list and the device it drives
+    pub available: Signal<Vec<Device>>,
+    pub selected: Signal<Option<String>>,
+}
+
+fn state_icon(summary: &TabSummary) -> &'static str {
+    if !summary.connected {
+        return "🔌";
+    }
+    match summary.state {
+        GameState::Idle => "⏹️",
+        GameState::Running => "▶️",
+        GameState::Paused => "⏸️",
+        GameState::Recovering => "🔄",
+        GameState::Sleeping => "😴",
+    }
+}
+
+/// "2/3 running, 1 disconnected" over every tab with a device
+pub fn aggregate_status(summaries: &BTreeMap<String, TabSummary>) -> String {
+    let devices: Vec<&TabSummary> = summaries.values().filter(|s| s.device.is_some()).collect();
+    let running = devices
+        .iter()
+        .filter(|s| s.connected && s.state == GameState::Running)
+        .count();
+    let disconnected = devices.iter().filter(|s| !s.connected).count();
+    let mut status = format!("{}/{} running", running, devices.len());
+    if disconnected > 0 {
+        status.push_str(&format!(", {} disconnected", disconnected));
+    }
+    status
+}
+
+/// One tab per device with automation, plus a menu to open another attached device;
+/// hidden while there is only one device
+#[component]
+pub fn DeviceTabBar() -> Element {
+    let tabs = use_context::<DeviceTabs>();
+    let mut pinned = tabs.pinned;
+    let mut active = tabs.active;
+
+    let pinned_names = pinned.read().clone();
+    let selected = tabs.selected.read().clone();
+    let openable: Vec<Device> = tabs
+        .available
+        .read()
+        .iter()
+        .filter(|d| {
+            selected.as_deref() != Some(d.key()) && !pinned_names.iter().any(|key| key == d.key())
+        })
+        .cloned()
+        .collect();
+    if pinned_names.is_empty() && openable.is_empty() {
+        return rsx! {};
+    }
+
+    let summaries = tabs.summaries.read().clone();
+    let overall = aggregate_status(&summaries);
+    let current = active.read().clone();
+    let keys: Vec<String> = std::iter::once(PRIMARY_TAB.to_string())
+        .chain(pinned_names)
+        .collect();
+
+    rsx! {
+        div { class: "device-tabs",
+            for key in keys {
+                {
+                    let summary = summaries.get(&key);
+                    let label = summary
+                        .and_then(|s| s.device.clone())
+                        .unwrap_or_else(|| if key == PRIMARY_TAB { "No device".to_string() } else { key.clone() });
+                    let icon = summary.map(state_icon).unwrap_or("🔌");
+                    let tab_key = key.clone();
+                    let close_key = key.clone();
+                    let is_active = key == current;
+                    rsx! {
+                        div { key: "{key}", class: "device-tab", "data-active": "{is_active}",
+                            span {
+                                style: "cursor:pointer;",
+                                onclick: move |_| active.set(tab_key.clone()),
+                                "{icon} {label}"
+                            }
+                            if key != PRIMARY_TAB {
+                                span {
+                                    style: "cursor:pointer; opacity:0.7;",
+                                    title: "Stop automation on this device and close its tab",
+                                    onclick: move |_| {
+                                        pinned.write().retain(|name| name != &close_key);
+                                        if *active.peek() == close_key {
+                                            active.set(PRIMARY_TAB.to_string());
+                                        }
+                                    },
+                                    "✕"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if !openable.is_empty() {
+                select {
+                    class: "device-tab",
+                    title: "Run automation on another device at the same time",
+                    value: "",
+                    onchange: move |evt| {
+                        let name = evt.value();
+                        if !name.is_empty() {
+                            pinned.write().push(name.clone());
+                            active.set(name);
+                        }
+                    },
+                    option { value: "", "➕ Open device" }
+                    for device in openable {
+                        option { key: "{device.key()}", value: "{device.key()}",
+                            {
+                                match &device.serial {
+                                    Some(serial) => format!("📱 {} ({})", device.name, serial),
+                                    None => format!("📱 {}", device.name),
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            span { style: "margin-left:auto; font-size:0.75em; font-weight:600; opacity:0.85;",
+                "🤖 {overall}"
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate_counts_tabs_with_a_device() {
+        let summary = |device: Option<&str>, state: GameState, connected: bool| TabSummary {
+            device: device.map(str::to_string),
+            state,
+            connected,
+        };
+        let summaries = BTreeMap::from([
+            (
+                PRIMARY_TAB.to_string(),
+                summary(Some("18d1:4ee7"), GameState::Running, true),
+            ),
+            (
+                "192.168.1.20:5555".to_string(),
+                summary(Some("192.168.1.20:5555"), GameState::Paused, true),
+            ),
+            (
+                "04e8:6860".to_string(),
+                summary(Some("04e8:6860"), GameState::Running, false),
+            ),
+        ]);
+        assert_eq!(aggregate_status(&summaries), "1/3 running, 1 disconnected");
+        let searching = BTreeMap::from([(
+            PRIMARY_TAB.to_string(),
+            summary(None, GameState::Idle, false),
+        )]);
+        assert_eq!(aggregate_status(&searching), "0/0 running");
+    }
+}
@@ -0,0 +1,141 @@
+// gui/components/history_panel.rs
+use crate::game_automation::history::{
+    self, HISTORY_FILE, HistoryAction, HistoryEntry, HistoryFilter, Outcome,
+};
+use crate::gui::dioxus_app::AppContext;
+use crate::gui::preferences::use_panel_open;
+use crate::logging::LOG_DIR;
+use dioxus::prelude::*;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Entries kept in the panel
+const MAX_VISIBLE_ENTRIES: usize = 500;
+
+/// Collapsible panel listing executed taps, swipes and matches on this tab's
+/// device, newest first, filterable by action, outcome and source
+#[component]
+pub fn HistoryPanel() -> Element {
+    let ctx = use_context::<AppContext>();
+    let mut entries = use_signal(Vec::<HistoryEntry>::new);
+    let is_open = use_panel_open("history");
+    let mut filter = use_signal(HistoryFilter::default);
+
+    use_future(move || async move {
+        let (recent, mut rx) = history::subscribe();
+        entries.set(recent);
+        loop {
+            match rx.recv().await {
+                Ok(entry) => entries.with_mut(|list| {
+                    list.push(entry);
+                    if list.len() > MAX_VISIBLE_ENTRIES {
+                        let excess = list.len() - MAX_VISIBLE_ENTRIES;
+                        list.drain(0..excess);
+                    }
+                }),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let mut current = filter.read().clone();
+    current.device = ctx.device.selected.read().clone();
+    let visible: Vec<HistoryEntry> = entries
+        .read()
+        .iter()
+        .filter(|entry| current.matches(entry))
+        .rev()
+        .cloned()
+        .collect();
+    let count = visible.len();
+    let select_style = "background: var(--control-bg); color: var(--control-text); border:1px solid var(--control-border); border-radius:6px; font-size:0.75em;";
+
+    rsx! {
+        div { class: "panel", style: "padding: 10px 12px; border-radius: 12px;",
+            div { style: "display:flex; align-items:center; gap:8px;",
+                button {
+                    style: "background:none; border:none; color:var(--heading); font-size:0.9em; font-weight:600; cursor:pointer; padding:0;",
+                    onclick: move |_| {
+                        let open = is_open.get();
+                        is_open.set(!open);
+                    },
+                    if is_open.get() { "▾ 🧾 History" } else { "▸ 🧾 History" }
+                }
+                span { style: "font-size:0.75em; opacity:0.7;", "{count} entries · full history in {LOG_DIR}/{HISTORY_FILE}" }
+            }
+            if is_open.get() {
+                div { style: "display:flex; gap:6px; margin-top:8px;",
+                    select {
+                        style: "{select_style}",
+                        onchange: move |evt| {
+                            let action = match evt.value().as_str() {
+                                "tap" => Some(HistoryAction::Tap),
+                                "swipe" => Some(HistoryAction::Swipe),
+                                "match" => Some(HistoryAction::Match),
+                                _ => None,
+                            };
+                            filter.with_mut(|f| f.action = action);
+                        },
+                        option { value: "", "All actions" }
+                        for action in [HistoryAction::Tap, HistoryAction::Swipe, HistoryAction::Match] {
+                            option { value: "{action}", selected: current.action == Some(action), "{action}" }
+                        }
+                    }
+                    select {
+                        style: "{select_style}",
+                        onchange: move |evt| {
+                            let outcome = match evt.value().as_str() {
+                                "done" => Some(Outcome::Done),
+                                "blocked" => Some(Outcome::Blocked),
+                                "failed" => Some(Outcome::Failed),
+                                _ => None,
+                            };
+                            filter.with_mut(|f| f.outcome = outcome);
+                        },
+                        option { value: "", "All outcomes" }
+                        for outcome in [Outcome::Done, Outcome::Blocked, Outcome::Failed] {
+                            option { value: "{outcome}", selected: current.outcome == Some(outcome), "{outcome}" }
+                        }
+                    }
+                    input {
+                        style: "flex:1; min-width:0; {select_style} padding:2px 6px;",
+                        placeholder: "Source or reason...",
+                        value: "{current.text}",
+                        oninput: move |evt| filter.with_mut(|f| f.text = evt.value()),
+                    }
+                }
+                div { style: "margin-top:8px; max-height:240px; overflow-y:auto; background:rgba(0,0,0,0.35); border-radius:8px; padding:6px 8px; font-family:monospace; font-size:0.72em; line-height:1.4;",
+                    for entry in visible {{
+                        let time = entry.time_label();
+                        let outcome_style = format!("color:{}; font-weight:600;", outcome_color(entry.outcome));
+                        let confidence = entry
+                            .confidence
+                            .map(|c| format!(" {:.0}%", c * 100.0))
+                            .unwrap_or_default();
+                        let detail = if entry.detail.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" - {}", entry.detail)
+                        };
+                        rsx! {
+                            div { style: "white-space:pre-wrap; word-break:break-word;",
+                                span { style: "opacity:0.6;", "{time} " }
+                                span { style: "{outcome_style}", "{entry.outcome} " }
+                                span { "{entry.action} {entry.source} ({entry.x}, {entry.y}){confidence}" }
+                                span { style: "opacity:0.7;", "{detail}" }
+                            }
+                        }
+                    }}
+                }
+            }
+        }
+    }
+}
+
+fn outcome_color(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Done => "#4dff88",
+        Outcome::Blocked => "#ffd857",
+        Outcome::Failed => "#ff6b6b",
+    }
+}
@@ -0,0 +1,100 @@
+// gui/components/log_viewer.rs
+use crate::gui::preferences::use_panel_open;
+use crate::logging::{self, LOG_DIR, LogEntry};
+use dioxus::prelude::*;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::Level;
+
+/// Entries kept in the panel
+const MAX_VISIBLE_ENTRIES: usize = 300;
+
+/// Collapsible panel showing live log output, filterable by level
+#[component]
+pub fn LogViewer() -> Element {
+    let mut entries = use_signal(Vec::<LogEntry>::new);
+    let is_open = use_panel_open("logs");
+    let mut min_level = use_signal(|| Level::INFO);
+
+    use_future(move || async move {
+        let (history, mut rx) = logging::subscribe();
+        entries.set(history);
+        loop {
+            match rx.recv().await {
+                Ok(entry) => entries.with_mut(|list| {
+                    list.push(entry);
+                    if list.len() > MAX_VISIBLE_ENTRIES {
+                        let excess = list.len() - MAX_VISIBLE_ENTRIES;
+                        list.drain(0..excess);
+                    }
+                }),
+                // Fell behind a burst of messages; keep showing what arrives next
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let level = *min_level.read();
+    // tracing orders levels by verbosity: ERROR < WARN < INFO < DEBUG < TRACE
+    let visible: Vec<LogEntry> = entries
+        .read()
+        .iter()
+        .filter(|entry| entry.level <= level)
+        .rev()
+        .cloned()
+        .collect();
+    let count = visible.len();
+
+    rsx! {
+        div { class: "panel", style: "padding: 10px 12px; border-radius: 12px;",
+            div { style: "display:flex; align-items:center; gap:8px;",
+                button {
+                    style: "background:none; border:none; color:var(--heading); font-size:0.9em; font-weight:600; cursor:pointer; padding:0;",
+                    onclick: move |_| {
+                        let open = is_open.get();
+                        is_open.set(!open);
+                    },
+                    if is_open.get() { "▾ 📜 Logs" } else { "▸ 📜 Logs" }
+                }
+                span { style: "font-size:0.75em; opacity:0.7;", "{count} entries · full log in {LOG_DIR}/" }
+                if is_open.get() {
+                    select {
+                        style: "margin-left:auto; background: var(--control-bg); color: var(--control-text); border:1px solid var(--control-border); border-radius:6px; font-size:0.75em;",
+                        onchange: move |evt| {
+                            let level = evt.value().parse::<Level>().unwrap_or(Level::INFO);
+                            min_level.set(level);
+                        },
+                        for name in ["ERROR", "WARN", "INFO", "DEBUG"] {
+                            option { value: name, selected: level.as_str() == name, "{name}" }
+                        }
+                    }
+                }
+            }
+            if is_open.get() {
+                div { style: "margin-top:8px; max-height:240px; overflow-y:auto; background:rgba(0,0,0,0.35); border-radius:8px; padding:6px 8px; font-family:monospace; font-size:0.72em; line-height:1.4;",
+                    for entry in visible {{
+                        let level_style = format!("color:{}; font-weight:600;", level_color(entry.level));
+                        let level_name = entry.level.as_str();
+                        rsx! {
+                            div { style: "white-space:pre-wrap; word-break:break-word;",
+                                span { style: "opacity:0.6;", "{entry.time} " }
+                                span { style: "{level_style}", "{level_name} " }
+                                span { style: "opacity:0.6;", "{entry.target} " }
+                                span { "{entry.message}" }
+                            }
+                        }
+                    }}
+                }
+            }
+        }
+    }
+}
+
+fn level_color(level: Level) -> &'static str {
+    match level {
+        Level::ERROR => "#ff6b6b",
+        Level::WARN => "#ffd857",
+        Level::INFO => "#4dff88",
+        _ => "#8ad0ff",
+    }
+}
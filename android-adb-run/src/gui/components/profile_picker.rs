@@ -0,0 +1,96 @@
+// gui/components/profile_picker.rs
+use crate::game_automation::AutomationCommand;
+use crate::game_automation::config::{
+    active_profile, list_profiles, switch_profile, valid_profile_name,
+};
+use crate::gui::dioxus_app::AppContext;
+use dioxus::prelude::*;
+
+/// Option that turns the drop-down into a name field for a new profile
+const NEW_PROFILE: &str = "+new";
+
+/// Drop-down of the per-game profiles in `profiles/`; picking one stops the
+/// automation, loads that profile's config and starts again if it was running
+#[component]
+pub fn ProfilePicker() -> Element {
+    let ctx = use_context::<AppContext>();
+    let automation_command_tx = ctx.automation.command_tx;
+    let mut screenshot_status = ctx.screenshot.status;
+    let mut profiles = use_signal(list_profiles);
+    let mut current = use_signal(active_profile);
+    let mut new_name = use_signal(|| None::<String>);
+
+    let mut switch_to = move |name: String| {
+        if let Some(tx) = automation_command_tx.read().as_ref() {
+            let tx = tx.clone();
+            let command_name = name.clone();
+            spawn(async move {
+                let _ = tx
+                    .send(AutomationCommand::SwitchProfile(command_name))
+                    .await;
+            });
+        } else if let Err(e) = switch_profile(&name) {
+            screenshot_status.set(format!("❌ {}", e));
+            return;
+        }
+        if !profiles.read().contains(&name) {
+            profiles.write().push(name.clone());
+        }
+        current.set(name);
+    };
+
+    let field_style = "background: rgba(0,0,0,0.3); color: var(--accent); border: 1px solid rgba(135,206,235,0.4); padding: 3px 8px; border-radius: 6px; font-size: 0.75em; font-weight: 600;";
+    let selected = current.read().clone();
+
+    rsx! {
+        if let Some(name) = new_name.read().clone() {
+            input {
+                style: "{field_style} width: 110px;",
+                placeholder: "profile name",
+                value: "{name}",
+                autofocus: true,
+                oninput: move |evt| new_name.set(Some(evt.value())),
+                onkeydown: move |evt| match evt.key() {
+                    Key::Enter => {
+                        let name = new_name.read().clone().unwrap_or_default();
+                        let name = name.trim().to_string();
+                        if valid_profile_name(&name) {
+                            new_name.set(None);
+                            switch_to(name);
+                        } else {
+                            screenshot_status.set(
+                                "❌ Profile names use letters, digits, - and _ only".to_string(),
+                            );
+                        }
+                    }
+                    Key::Escape => new_name.set(None),
+                    _ => {}
+                },
+            }
+        } else {
+            select {
+                style: "{field_style}",
+                title: "Per-game profile: its own templates, timed events, matching and target app",
+                value: "{selected}",
+                onfocus: move |_| profiles.set(list_profiles()),
+                onchange: move |evt| {
+                    let name = evt.value();
+                    if name == NEW_PROFILE {
+                        new_name.set(Some(String::new()));
+                    } else if !name.is_empty() && name != *current.read() {
+                        switch_to(name);
+                    }
+                },
+                for name in profiles.read().clone() {
+                    option {
+                        key: "{name}",
+                        value: "{name}",
+                        selected: name == selected,
+                        "👤 {name}"
+                    }
+                }
+                option { value: NEW_PROFILE, "➕ New profile..." }
+            }
+        }
+    }
+}
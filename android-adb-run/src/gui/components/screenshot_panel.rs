@@ -1,11 +1,21 @@
 // gui/components/screenshot_panel.rs
 use crate::adb::{AdbClient, AdbResult};
+use crate::game_automation::GameState;
+use crate::game_automation::history::{self, HistoryEntry};
+use crate::game_automation::safety::allow_tap;
+use crate::gui::components::template_editor::TemplateEditor;
 use crate::gui::dioxus_app::AppContext;
+use crate::gui::heatmap::{HEATMAP_CELL_PX, TapHeatmap};
 use crate::gui::hooks::{device_loop::decode_screenshot_to_rgb, start_template_matching_phase};
-use crate::gui::util::base64_encode;
+use crate::gui::screen_diff::ScreenDiff;
+use crate::gui::util::{SCREENSHOT_BORDER_PX, display_size, preview_data_url};
 use dioxus::html::geometry::ElementPoint;
+use dioxus::html::input_data::MouseButton;
 use dioxus::prelude::*;
-use std::time::Instant;
+use image::RgbImage;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
 
 #[derive(Clone, PartialEq)]
 pub struct TapMarker {
@@ -13,6 +23,83 @@ pub struct TapMarker {
     pub timestamp: Instant,
 }
 
+/// Shortest swipe sent to the device; quicker drags still register as a fling
+const MIN_SWIPE_MS: u32 = 50;
+/// Longest swipe sent to the device, for drags that paused along the way
+const MAX_SWIPE_MS: u32 = 5000;
+/// Minimum mouse movement (px) between recorded drag path points
+const SWIPE_PATH_STEP: f64 = 3.0;
+
+/// Swipe duration that replays a mouse drag at the speed it was drawn
+pub fn swipe_duration_ms(drag: Duration) -> u32 {
+    (drag.as_millis().min(MAX_SWIPE_MS as u128) as u32).max(MIN_SWIPE_MS)
+}
+
+/// Most the screenshot view zooms in
+const MAX_ZOOM: f64 = 8.0;
+/// Zoom change per mouse wheel notch
+const ZOOM_STEP: f64 = 1.25;
+
+/// Zoom and pan of the screenshot view. Mouse positions on the image stay in
+/// unzoomed image pixels, so device coordinates map the same at any zoom.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewTransform {
+    pub zoom: f64,
+    /// Screen offset of the image's top-left corner
+    pub pan: (f64, f64),
+}
+
+impl Default for ViewTransform {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan: (0.0, 0.0),
+        }
+    }
+}
+
+impl ViewTransform {
+    /// Zoom by `factor`, keeping image point `at` under the cursor; `size` is the unzoomed view
+    pub fn zoom_at(self, factor: f64, at: (f64, f64), size: (f64, f64)) -> Self {
+        let zoom = (self.zoom * factor).clamp(1.0, MAX_ZOOM);
+        let pan = (
+            self.pan.0 + (self.zoom - zoom) * at.0,
+            self.pan.1 + (self.zoom - zoom) * at.1,
+        );
+        Self { zoom, pan }.clamped(size)
+    }
+
+    /// Move the zoomed image by a screen-pixel delta
+    pub fn pan_by(self, delta: (f64, f64), size: (f64, f64)) -> Self {
+        Self {
+            pan: (self.pan.0 + delta.0, self.pan.1 + delta.1),
+            ..self
+        }
+        .clamped(size)
+    }
+
+    /// Keep the view covered by the image (no empty space past its edges)
+    fn clamped(self, (width, height): (f64, f64)) -> Self {
+        let min_x = width - width * self.zoom;
+        let min_y = height - height * self.zoom;
+        Self {
+            pan: (self.pan.0.clamp(min_x, 0.0), self.pan.1.clamp(min_y, 0.0)),
+            ..self
+        }
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom > 1.0
+    }
+
+    fn css(&self) -> String {
+        format!(
+            "transform-origin:0 0; transform:translate({:.1}px, {:.1}px) scale({:.3});",
+            self.pan.0, self.pan.1, self.zoom
+        )
+    }
+}
+
 #[component]
 pub fn screenshot_panel() -> Element {
     let ctx = use_context::<AppContext>();
@@ -31,19 +118,109 @@ pub fn screenshot_panel() -> Element {
     let mut is_swiping = ctx.interaction.is_swiping;
     let mut swipe_start = ctx.interaction.swipe_start;
     let mut swipe_end = ctx.interaction.swipe_end;
+    let mut swipe_path = ctx.interaction.swipe_path;
+    let mut swipe_started_at = ctx.interaction.swipe_started_at;
     let auto_update_on_touch = ctx.interaction.auto_update_on_touch;
     let select_box = ctx.interaction.select_box;
     let mut selection_start = ctx.interaction.selection_start;
     let mut selection_end = ctx.interaction.selection_end;
+    let mut selected_region = ctx.interaction.selected_region;
     let hover_tap_preview = ctx.interaction.hover_tap_preview;
+    let mut picking_point = ctx.interaction.picking_point;
+    let mut picked_point = ctx.interaction.picked_point;
 
     let automation_command_tx = ctx.automation.command_tx;
+    let automation_state = ctx.automation.state;
 
     let calculate_device_coords = ctx.calculate_device_coords;
     let mut tap_markers = ctx.tap_markers;
     let shared_adb_client = ctx.shared_adb_client;
     let loading = *is_loading_screenshot.read();
 
+    let mut view = use_signal(ViewTransform::default);
+    // Last mouse position (client coordinates) while panning
+    let mut pan_from = use_signal(|| None::<(f64, f64)>);
+    // Unzoomed size of the image including its border
+    let view_size = move || {
+        device_info.read().as_ref().map(|info| {
+            let (w, h) = display_size(info.screen_x, info.screen_y);
+            let border = 2.0 * SCREENSHOT_BORDER_PX;
+            ((w + border) as f64, (h + border) as f64)
+        })
+    };
+    let zoom = view.read().zoom;
+    let stage_css = view.read().css();
+
+    // Automation taps this session, from the execution history
+    let mut heatmap = use_signal(TapHeatmap::default);
+    let mut show_heatmap = use_signal(|| false);
+    use_future(move || async move {
+        // Only this tab's device; other tabs tap other screens
+        let selected = ctx.device.selected;
+        let ours =
+            move |entry: &HistoryEntry| selected.peek().as_deref() == Some(entry.device.as_str());
+        let (recent, mut rx) = history::subscribe();
+        heatmap.with_mut(|map| {
+            recent
+                .iter()
+                .filter(|entry| ours(entry))
+                .for_each(|entry| map.record_entry(entry))
+        });
+        loop {
+            match rx.recv().await {
+                Ok(entry) if ours(&entry) => heatmap.with_mut(|map| map.record_entry(&entry)),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    // Pinned reference screenshot (pixels and preview) and what changed since
+    let mut reference = use_signal(|| None::<(Arc<RgbImage>, String)>);
+    let mut show_reference = use_signal(|| false);
+    let mut screen_diff = use_signal(|| None::<Result<ScreenDiff, String>>);
+    use_effect(move || {
+        let pinned = reference.read().as_ref().map(|(image, _)| image.clone());
+        let bytes = screenshot_bytes.read().clone();
+        let (Some(pinned), Some(bytes)) = (pinned, bytes) else {
+            screen_diff.set(None);
+            return;
+        };
+        spawn(async move {
+            let diff = tokio::task::spawn_blocking(move || {
+                ScreenDiff::compare(&pinned, &decode_screenshot_to_rgb(&bytes)?)
+            })
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+            screen_diff.set(Some(diff));
+        });
+    });
+    let pin_reference = move |_| {
+        let Some(bytes) = screenshot_bytes.read().clone() else {
+            return;
+        };
+        spawn(async move {
+            let pinned = tokio::task::spawn_blocking(move || {
+                decode_screenshot_to_rgb(&bytes).map(|rgb| {
+                    let preview = preview_data_url(&rgb);
+                    (Arc::new(rgb), preview)
+                })
+            })
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+            match pinned {
+                Ok(pinned) => {
+                    reference.set(Some(pinned));
+                    show_reference.set(false);
+                    screenshot_status
+                        .set("📌 Reference pinned - changes since are outlined".to_string());
+                }
+                Err(e) => screenshot_status.set(format!("❌ Cannot pin reference: {}", e)),
+            }
+        });
+    };
+
     use_effect(move || {
         spawn(async move {
             loop {
@@ -91,20 +268,11 @@ pub fn screenshot_panel() -> Element {
             if screen_x == 0 || screen_y == 0 {
                 return (0.0, 0.0);
             }
-            let max_content_width = 400.0;
-            let max_content_height = 600.0;
-            let border_px = 8.0;
-            let image_aspect = screen_x as f32 / screen_y as f32;
-            let container_aspect = max_content_width / max_content_height;
-            let (content_w, content_h) = if image_aspect > container_aspect {
-                (max_content_width, max_content_width / image_aspect)
-            } else {
-                (max_content_height * image_aspect, max_content_height)
-            };
-            let scale_x = content_w.max(1.0) / screen_x as f32;
-            let scale_y = content_h.max(1.0) / screen_y as f32;
-            let px = device_x as f32 * scale_x + border_px;
-            let py = device_y as f32 * scale_y + border_px;
+            let (content_w, content_h) = display_size(screen_x, screen_y);
+            let scale_x = content_w / screen_x as f32;
+            let scale_y = content_h / screen_y as f32;
+            let px = device_x as f32 * scale_x + SCREENSHOT_BORDER_PX;
+            let py = device_y as f32 * scale_y + SCREENSHOT_BORDER_PX;
             (px, py)
         };
 
@@ -120,6 +288,117 @@ pub fn screenshot_panel() -> Element {
         }
     };
 
+    // Outlines of the latest matches (and planned taps) in display pixels
+    let match_boxes = {
+        let info = device_info.read();
+        match info.as_ref() {
+            Some(info) if info.screen_x > 0 && info.screen_y > 0 => {
+                let (sx, sy) = (info.screen_x, info.screen_y);
+                ctx.screenshot
+                    .matches
+                    .read()
+                    .iter()
+                    .map(|found| {
+                        let (left, top) = device_to_display(found.x, found.y, sx, sy);
+                        let (right, bottom) = device_to_display(
+                            found.x + found.width,
+                            found.y + found.height,
+                            sx,
+                            sy,
+                        );
+                        let label = format!("{} {:.0}%", found.label, found.confidence * 100.0);
+                        let tap = found.tap.map(|(x, y)| device_to_display(x, y, sx, sy));
+                        (left, top, right - left, bottom - top, label, tap)
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    };
+
+    // Heat spots in display pixels: (x, y, diameter, heat), plus taps off this screen
+    let (heat_spots, heat_outside) = {
+        let info = device_info.read();
+        match info.as_ref() {
+            Some(info) if *show_heatmap.read() && info.screen_x > 0 && info.screen_y > 0 => {
+                let (sx, sy) = (info.screen_x, info.screen_y);
+                let map = heatmap.read();
+                let (content_w, _) = display_size(sx, sy);
+                let cell = HEATMAP_CELL_PX as f32 * content_w / sx as f32;
+                let spots: Vec<(f32, f32, f32, f32)> = map
+                    .cells()
+                    .iter()
+                    .filter(|c| c.x < sx && c.y < sy)
+                    .map(|c| {
+                        let (x, y) = device_to_display(c.x, c.y, sx, sy);
+                        (x, y, cell * (1.2 + c.heat), c.heat)
+                    })
+                    .collect();
+                (spots, map.outside(sx, sy))
+            }
+            _ => (Vec::new(), 0),
+        }
+    };
+    let heatmap_total = heatmap.read().total();
+
+    // Changed areas against the pinned reference in display pixels, and a summary
+    let (diff_boxes, diff_summary) = {
+        let info = device_info.read();
+        match (info.as_ref(), screen_diff.read().as_ref()) {
+            (Some(info), Some(Ok(diff))) if info.screen_x > 0 && info.screen_y > 0 => {
+                let (sx, sy) = (info.screen_x, info.screen_y);
+                let boxes: Vec<(f32, f32, f32, f32)> = diff
+                    .regions
+                    .iter()
+                    .map(|region| {
+                        let (left, top) = device_to_display(region.x, region.y, sx, sy);
+                        let (right, bottom) = device_to_display(
+                            region.x + region.width,
+                            region.y + region.height,
+                            sx,
+                            sy,
+                        );
+                        (left, top, right - left, bottom - top)
+                    })
+                    .collect();
+                let summary = format!(
+                    "Δ {:.1}% of pixels changed in {} area(s)",
+                    diff.changed_fraction() * 100.0,
+                    diff.regions.len()
+                );
+                (boxes, Some(summary))
+            }
+            (_, Some(Err(e))) => (Vec::new(), Some(format!("⚠️ {}", e))),
+            _ => (Vec::new(), None),
+        }
+    };
+    let pinned = reference.read().is_some();
+    let showing_reference = pinned && *show_reference.read();
+    let shown_image = if showing_reference {
+        reference
+            .read()
+            .as_ref()
+            .map(|(_, preview)| preview.clone())
+    } else {
+        screenshot_data.read().clone()
+    };
+
+    // Drag path drawn while swiping, as SVG polyline points
+    let swipe_trail: Option<(String, ElementPoint, ElementPoint)> = {
+        let path = swipe_path.read();
+        match (path.first(), path.last()) {
+            (Some(first), Some(last)) if *is_swiping.read() && path.len() > 1 => {
+                let points = path
+                    .iter()
+                    .map(|p| format!("{:.0},{:.0}", p.x, p.y))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Some((points, *first, *last))
+            }
+            _ => None,
+        }
+    };
+
     let hover_css = r#"
         @keyframes hover-pulse-ring { 0% { transform: translate(-50%, -50%) scale(1.0); opacity: 0.8; } 50% { transform: translate(-50%, -50%) scale(1.35); opacity: 0.65; } 100% { transform: translate(-50%, -50%) scale(1.0); opacity: 0.8; } }
         @keyframes hover-pulse-core { 0% { transform: translate(-50%, -50%) scale(0.9); opacity: 0.95; } 50% { transform: translate(-50%, -50%) scale(1.3); opacity: 0.8; } 100% { transform: translate(-50%, -50%) scale(0.9); opacity: 0.95; } }
@@ -127,20 +406,38 @@ pub fn screenshot_panel() -> Element {
 
     rsx! {
         style { dangerous_inner_html: "{hover_css}" }
-        div { style: "flex:0 0 400px; background:rgba(255,255,255,0.1); backdrop-filter:blur(10px); padding:15px; border-radius:15px; border:1px solid rgba(255,255,255,0.2); height:fit-content;",
-            if let Some(image_data) = screenshot_data.read().as_ref() {
+        div { class: "panel screenshot-panel", style: "padding:15px; border-radius:15px; height:fit-content;",
+            if let Some(image_data) = shown_image.as_ref() {
                 div { style: "display:flex; justify-content:center;",
-                    div { style: "position:relative; width:fit-content;",
+                    div { style: "position:relative; width:fit-content; overflow:hidden; border-radius:10px;",
+                        onwheel: move |evt| {
+                            let Some(size) = view_size() else { return };
+                            evt.prevent_default();
+                            let r = evt.element_coordinates();
+                            let factor = if evt.delta().strip_units().y < 0.0 { ZOOM_STEP } else { 1.0 / ZOOM_STEP };
+                            let zoomed = view.read().zoom_at(factor, (r.x, r.y), size);
+                            view.set(zoomed);
+                        },
                         if let Some((device_x, device_y)) = *device_coords.read() {
                             div {
-                                style: "position: absolute; top: 8px; left: 50%; transform: translateX(-50%); z-index: 10; background: rgba(0,0,0,0.8); color: white; padding: 4px 8px; border-radius: 6px; font-size: 0.8em; font-weight: bold; pointer-events: none; border: 1px solid rgba(255,255,255,0.3);",
+                                style: "position: absolute; top: 8px; left: 50%; transform: translateX(-50%); z-index: 30; background: rgba(0,0,0,0.8); color: white; padding: 4px 8px; border-radius: 6px; font-size: 0.8em; font-weight: bold; pointer-events: none; border: 1px solid rgba(255,255,255,0.3);",
                                 "({device_x}, {device_y})"
                             }
                         }
+                        div { style: "position:relative; {stage_css}",
                         img {
-                            src: "data:image/png;base64,{image_data}",
+                            src: "{image_data}",
                             style: if loading { "max-width:100%; max-height:600px; border-radius:10px; cursor:crosshair; border:8px solid #ff4444; box-shadow:0 0 40px rgba(255,68,68,0.8); user-select:none;" } else { "max-width:100%; max-height:600px; border-radius:10px; cursor:crosshair; border:8px solid rgba(255,255,255,0.2); box-shadow:0 4px 15px rgba(0,0,0,0.3); user-select:none;" },
                             onmousemove: move |evt| {
+                                if let Some((last_x, last_y)) = *pan_from.read() {
+                                    let c = evt.client_coordinates();
+                                    if let Some(size) = view_size() {
+                                        let panned = view.read().pan_by((c.x - last_x, c.y - last_y), size);
+                                        view.set(panned);
+                                    }
+                                    pan_from.set(Some((c.x, c.y)));
+                                    return;
+                                }
                                 let r = evt.element_coordinates();
                                 mouse_coords.set(Some((r.x as i32, r.y as i32)));
                                 if let Some(info) = device_info.read().as_ref() {
@@ -148,20 +445,38 @@ pub fn screenshot_panel() -> Element {
                                     device_coords.set(Some((cx, cy)));
                                 }
                                 if *select_box.read() && selection_start.read().is_some() { let adj = ElementPoint { x: r.x - CURSOR_OFFSET, y: r.y - CURSOR_OFFSET, ..r }; selection_end.set(Some(adj)); }
+                                if *is_swiping.read() {
+                                    swipe_end.set(*device_coords.read());
+                                    swipe_path.with_mut(|path| {
+                                        if path.last().is_none_or(|last| (last.x - r.x).abs() + (last.y - r.y).abs() >= SWIPE_PATH_STEP) { path.push(r); }
+                                    });
+                                }
                             },
                             onmouseleave: move |_| {
-                                mouse_coords.set(None); device_coords.set(None); is_swiping.set(false); swipe_start.set(None); swipe_end.set(None);
+                                pan_from.set(None);
+                                mouse_coords.set(None); device_coords.set(None); is_swiping.set(false); swipe_start.set(None); swipe_end.set(None); swipe_path.set(Vec::new()); swipe_started_at.set(None);
                                 if *select_box.read() { selection_start.set(None); selection_end.set(None); }
                             },
                             onmousedown: move |evt| {
+                                // Shift+drag or middle-button drag pans a zoomed view instead of swiping
+                                let pan_drag = evt.modifiers().shift() || evt.trigger_button() == Some(MouseButton::Auxiliary);
+                                if pan_drag && view.read().is_zoomed() {
+                                    let c = evt.client_coordinates();
+                                    pan_from.set(Some((c.x, c.y)));
+                                    return;
+                                }
                                 if *select_box.read() {
                                     let r = evt.element_coordinates(); let adj = ElementPoint { x: r.x - CURSOR_OFFSET, y: r.y - CURSOR_OFFSET, ..r }; selection_start.set(Some(adj)); selection_end.set(None);
                                 } else if let Some(info) = device_info.read().as_ref() {
                                     let r = evt.element_coordinates(); let (sx0, sy0) = calculate_device_coords(r, info.screen_x, info.screen_y);
-                                    is_swiping.set(true); swipe_start.set(Some((sx0, sy0))); swipe_end.set(None);
+                                    is_swiping.set(true); swipe_start.set(Some((sx0, sy0))); swipe_end.set(None); swipe_path.set(vec![r]); swipe_started_at.set(Some(Instant::now()));
                                 }
                             },
                             onmouseup: move |evt| {
+                                if pan_from.read().is_some() {
+                                    pan_from.set(None);
+                                    return;
+                                }
                                 if *select_box.read() {
                                     if let (Some(start), Some(end)) = (*selection_start.read(), *selection_end.read())
                                         && let Some(info) = device_info.read().as_ref() {
@@ -171,16 +486,46 @@ pub fn screenshot_panel() -> Element {
                                         let (d_tl_x, d_tl_y) = calculate_device_coords(tl, info.screen_x, info.screen_y);
                                         let (d_br_x, d_br_y) = calculate_device_coords(br, info.screen_x, info.screen_y);
                                         screenshot_status.set(format!("🟦 Selected: ({},{}) to ({},{}) size {}x{}", d_tl_x, d_tl_y, d_br_x, d_br_y, (d_br_x - d_tl_x).max(1), (d_br_y - d_tl_y).max(1)));
+                                        selected_region.set(Some((d_tl_x, d_tl_y, (d_br_x - d_tl_x).max(1), (d_br_y - d_tl_y).max(1))));
                                     }
                                     return;
                                 }
 
+                                // Picking a point for the timed event editor: record the click, don't tap
+                                if *is_swiping.read() && *picking_point.read() {
+                                    let start = *swipe_start.read();
+                                    if let Some((x, y)) = start {
+                                        picked_point.set(Some((x, y)));
+                                        screenshot_status.set(format!("📍 Picked ({}, {}) for timed event", x, y));
+                                    }
+                                    picking_point.set(false);
+                                    is_swiping.set(false); swipe_start.set(None); swipe_end.set(None); swipe_path.set(Vec::new()); swipe_started_at.set(None);
+                                    return;
+                                }
+
                                 if *is_swiping.read() {
                                     if let Some((sx0, sy0)) = *swipe_start.read() {
                                         let r = evt.element_coordinates();
                                         if let Some(info) = device_info.read().as_ref() {
                                             let (ex, ey) = calculate_device_coords(r, info.screen_x, info.screen_y);
                                             let distance = ((ex as i32 - sx0 as i32).pow(2) as f32 + (ey as i32 - sy0 as i32).pow(2) as f32).sqrt();
+
+                                            // While automation runs, clicks and drags count towards this
+                                            // device's tap limits; a drag is checked where it touches down
+                                            if *automation_state.read() == GameState::Running
+                                                && let Err(blocked) = allow_tap(
+                                                    ctx.device.selected.read().as_deref().unwrap_or_default(),
+                                                    &ctx.automation.config.read().safety,
+                                                    sx0,
+                                                    sy0,
+                                                ) {
+                                                let gesture = if distance < 10.0 { "Click" } else { "Swipe" };
+                                                screenshot_status.set(format!("🛡️ {} not sent: {}", gesture, blocked));
+                                                is_swiping.set(false); swipe_start.set(None); swipe_end.set(None); swipe_path.set(Vec::new()); swipe_started_at.set(None);
+                                                return;
+                                            }
+
+                                            let swipe_ms = swipe_duration_ms(swipe_started_at.read().map(|t| t.elapsed()).unwrap_or_default());
                                             let auto = *auto_update_on_touch.read();
                                             let already_loading = *is_loading_screenshot.read();
                                             let refresh_after = auto && !already_loading;
@@ -213,7 +558,8 @@ pub fn screenshot_panel() -> Element {
                                                             });
                                                         });
                                                     } else {
-                                                        client.swipe(sx0, sy0, ex, ey, Some(300)).await?;
+                                                        log::info!("👉 Swipe ({},{}) → ({},{}) over {}ms", sx0, sy0, ex, ey, swipe_ms);
+                                                        client.swipe(sx0, sy0, ex, ey, Some(swipe_ms)).await?;
                                                     }
 
                                                     if refresh_after {
@@ -232,18 +578,18 @@ pub fn screenshot_panel() -> Element {
                                                     Ok(cap_opt) => {
                                                         if let Some((bytes, duration_ms, counter_val)) = cap_opt {
                                                             let bytes_clone = bytes.clone();
-                                                            let b64 = tokio::task::spawn_blocking(move || base64_encode(&bytes_clone)).await.unwrap_or_default();
-                                                            screenshot_data.set(Some(b64));
+                                                            let (preview, rgb_decoded) = tokio::task::spawn_blocking(move || {
+                                                                let rgb = decode_screenshot_to_rgb(&bytes_clone).ok();
+                                                                (rgb.as_ref().map(preview_data_url).unwrap_or_default(), rgb)
+                                                            }).await.unwrap_or_default();
+                                                            screenshot_data.set(Some(preview));
                                                             screenshot_bytes.set(Some(bytes.clone()));
                                                             screenshot_status.set(format!("✅ Action successful - Screenshot #{} ({}ms)", counter_val, duration_ms));
                                                             is_loading_screenshot.set(false);
 
                                                             // Phase 3: Start template matching for this screenshot
-                                                            let bytes_for_matching = bytes.clone();
-                                                            let rgb_decoded = tokio::task::spawn_blocking(move || {
-                                                                decode_screenshot_to_rgb(&bytes_for_matching).ok()
-                                                            }).await.ok().flatten();
-                                                            start_template_matching_phase(bytes, rgb_decoded, counter_val as u32, screenshot_status, ctx.screenshot.status_history);
+                                                            let device = ctx.device.selected.read().clone().unwrap_or_default();
+                                                            start_template_matching_phase(&device, bytes, rgb_decoded, counter_val as u32, screenshot_status, ctx.screenshot.status_history, ctx.screenshot.matches);
                                                         } else {
                                                             screenshot_status.set("✅ Action successful".to_string());
                                                         }
@@ -256,7 +602,7 @@ pub fn screenshot_panel() -> Element {
                                             });
                                         }
                                     }
-                                    is_swiping.set(false); swipe_start.set(None); swipe_end.set(None);
+                                    is_swiping.set(false); swipe_start.set(None); swipe_end.set(None); swipe_path.set(Vec::new()); swipe_started_at.set(None);
                                 }
                             }
                         }
@@ -265,6 +611,33 @@ pub fn screenshot_panel() -> Element {
                                 div { style: "position:absolute; right:0; bottom:0; background:rgba(0,0,0,0.55); color:#fff; font-size:10px; padding:2px 4px; border-top-left-radius:4px;", "{ow}x{oh}" }
                             }
                         }
+                        if let Some((points, first, last)) = swipe_trail {
+                            svg { style: "position:absolute; left:0; top:0; width:100%; height:100%; overflow:visible; pointer-events:none; z-index:11;",
+                                polyline { points: "{points}", fill: "none", stroke: "#ffd857", stroke_width: "3", stroke_linecap: "round", stroke_linejoin: "round", opacity: "0.9" }
+                                circle { cx: "{first.x}", cy: "{first.y}", r: "5", fill: "#4dff88", stroke: "#ffffff", stroke_width: "1.5" }
+                                circle { cx: "{last.x}", cy: "{last.y}", r: "6", fill: "#ff4444", stroke: "#ffffff", stroke_width: "1.5" }
+                            }
+                        }
+                        for (left, top, width, height, label, tap) in match_boxes {
+                            div { style: format!("position:absolute; left:{left}px; top:{top}px; width:{width}px; height:{height}px; border:2px solid #39ff88; box-sizing:border-box; box-shadow:0 0 6px rgba(57,255,136,0.6); pointer-events:none; z-index:8;"),
+                                div { style: "position:absolute; left:-2px; bottom:100%; background:rgba(0,0,0,0.7); color:#39ff88; font-size:10px; padding:1px 4px; border-radius:3px 3px 0 0; white-space:nowrap;", "{label}" }
+                            }
+                            if let Some((tap_x, tap_y)) = tap {
+                                div { style: format!("position:absolute; left:{tap_x}px; top:{tap_y}px; width:14px; height:14px; border:2px solid #ffd857; background:rgba(255,216,87,0.35); border-radius:50%; transform:translate(-50%, -50%); pointer-events:none; z-index:8;"), }
+                            }
+                        }
+                        for (left, top, width, height) in diff_boxes {
+                            div { style: format!("position:absolute; left:{left}px; top:{top}px; width:{width}px; height:{height}px; border:2px dashed #ff4dd2; background:rgba(255,77,210,0.15); box-sizing:border-box; pointer-events:none; z-index:7;"), }
+                        }
+                        if showing_reference {
+                            div { style: "position:absolute; left:12px; top:12px; background:rgba(255,77,210,0.85); color:#fff; font-size:11px; font-weight:bold; padding:2px 6px; border-radius:4px; pointer-events:none; z-index:14;", "A · reference" }
+                        }
+                        for (spot_x, spot_y, size, heat) in heat_spots {{
+                            // Yellow for occasional taps, red for the busiest spots
+                            let green = (216.0 * (1.0 - heat)) as u8;
+                            let alpha = 0.35 + 0.45 * heat;
+                            rsx!{ div { style: format!("position:absolute; left:{spot_x}px; top:{spot_y}px; width:{size}px; height:{size}px; border-radius:50%; background:radial-gradient(circle, rgba(255,{green},60,{alpha:.2}) 0%, rgba(255,{green},60,0) 70%); transform:translate(-50%, -50%); pointer-events:none; z-index:7;"), } }
+                        }}
                         if let Some((disp_x, disp_y)) = hover_preview_point {
                             div { style: format!("position:absolute; left:{disp_x}px; top:{disp_y}px; width:20px; height:20px; border:2px solid #ff2d2d; background:rgba(255,45,45,0.2); border-radius:50%; box-shadow:0 0 12px rgba(255,45,45,0.75); transform:translate(-50%, -50%); pointer-events:none; z-index:12; animation:hover-pulse-ring 1.6s ease-in-out infinite;"), }
                             div { style: format!("position:absolute; left:{disp_x}px; top:{disp_y}px; width:6px; height:6px; background:#ff4545; border-radius:50%; transform:translate(-50%, -50%); pointer-events:none; z-index:13; animation:hover-pulse-core 1.6s ease-in-out infinite;"), }
@@ -276,9 +649,70 @@ pub fn screenshot_panel() -> Element {
                             let opacity = (1.0f32 - (age_secs / 30.0f32)).clamp(0.0f32, 1.0f32);
                             rsx!{ div { style: format!("position:absolute; left:{marker_x}px; top:{marker_y}px; width:10px; height:10px; background:#ffffff; border:2px solid #ff4444; border-radius:50%; box-shadow:0 0 6px rgba(255,255,255,0.8); transform:translate(-50%, -50%); pointer-events:none; z-index:9; opacity:{opacity};"), } }
                         }}
+                        }
                         if loading { div { style: "position: absolute; top: 50%; left: 50%; transform: translate(-50%, -50%); background: rgba(255, 68, 68, 0.95); color: white; padding: 15px 25px; border-radius: 25px; font-size: 1.2em; font-weight: bold; border: 2px solid white; box-shadow: 0 4px 20px rgba(0,0,0,0.5); z-index: 20;", "📸 LOADING..." } }
                     }
                 }
+                div { style: "display:flex; justify-content:center; align-items:center; gap:8px; margin-top:6px; font-size:0.8em; opacity:0.85;",
+                    button {
+                        style: "padding:2px 8px; border-radius:6px; border:1px solid rgba(255,255,255,0.3); background:rgba(255,255,255,0.15); color:inherit; cursor:pointer;",
+                        onclick: move |_| {
+                            let shown = *show_heatmap.read();
+                            show_heatmap.set(!shown);
+                        },
+                        if *show_heatmap.read() { "🔥 Hide tap heatmap" } else { "🔥 Tap heatmap" }
+                    }
+                    if *show_heatmap.read() {
+                        "{heatmap_total} automation taps"
+                        button {
+                            style: "padding:2px 8px; border-radius:6px; border:1px solid rgba(255,255,255,0.3); background:rgba(255,255,255,0.15); color:inherit; cursor:pointer;",
+                            onclick: move |_| heatmap.set(TapHeatmap::default()),
+                            "Clear"
+                        }
+                    }
+                }
+                div { style: "display:flex; justify-content:center; align-items:center; gap:8px; margin-top:6px; font-size:0.8em; opacity:0.85; flex-wrap:wrap;",
+                    button {
+                        style: "padding:2px 8px; border-radius:6px; border:1px solid rgba(255,255,255,0.3); background:rgba(255,255,255,0.15); color:inherit; cursor:pointer;",
+                        title: "Keep this screenshot to compare later ones against",
+                        onclick: pin_reference,
+                        if pinned { "📌 Re-pin reference" } else { "📌 Pin reference" }
+                    }
+                    if pinned {
+                        button {
+                            style: "padding:2px 8px; border-radius:6px; border:1px solid rgba(255,255,255,0.3); background:rgba(255,255,255,0.15); color:inherit; cursor:pointer;",
+                            onclick: move |_| show_reference.set(!showing_reference),
+                            if showing_reference { "🔀 Show B (live)" } else { "🔀 Show A (reference)" }
+                        }
+                        button {
+                            style: "padding:2px 8px; border-radius:6px; border:1px solid rgba(255,255,255,0.3); background:rgba(255,255,255,0.15); color:inherit; cursor:pointer;",
+                            onclick: move |_| {
+                                reference.set(None);
+                                show_reference.set(false);
+                            },
+                            "Unpin"
+                        }
+                    }
+                    if let Some(summary) = diff_summary {
+                        "{summary}"
+                    }
+                }
+                if heat_outside > 0 {
+                    div { style: "text-align:center; margin-top:4px; font-size:0.8em; color:#ffd857;",
+                        "⚠️ {heat_outside} taps landed outside this screen - coordinates from another resolution or rotation?"
+                    }
+                }
+                if zoom > 1.0 {
+                    div { style: "display:flex; justify-content:center; align-items:center; gap:8px; margin-top:6px; font-size:0.8em; opacity:0.85;",
+                        "🔍 {zoom:.1}x - wheel to zoom, Shift+drag to pan"
+                        button {
+                            style: "padding:2px 8px; border-radius:6px; border:1px solid rgba(255,255,255,0.3); background:rgba(255,255,255,0.15); color:inherit; cursor:pointer;",
+                            onclick: move |_| view.set(ViewTransform::default()),
+                            "Reset"
+                        }
+                    }
+                }
+                TemplateEditor {}
             } else {
                 div { style: "display:flex; justify-content:center; align-items:center; min-height:300px;",
                     if loading {
@@ -291,3 +725,44 @@ pub fn screenshot_panel() -> Element {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_keeps_cursor_point_and_clamps_pan() {
+        let size = (286.0, 616.0);
+        let view = ViewTransform::default().zoom_at(2.0, (100.0, 200.0), size);
+        assert_eq!(view.zoom, 2.0);
+        // The image point under the cursor stays at the same screen position
+        assert_eq!(
+            (
+                view.pan.0 + 100.0 * view.zoom,
+                view.pan.1 + 200.0 * view.zoom
+            ),
+            (100.0, 200.0)
+        );
+
+        // Panning stops at the image edges
+        let panned = view.pan_by((1000.0, -1000.0), size);
+        assert_eq!(panned.pan, (0.0, -616.0));
+
+        // Zooming back out never leaves the image off-center
+        let reset = panned.zoom_at(0.1, (10.0, 10.0), size);
+        assert_eq!(reset, ViewTransform::default());
+        assert_eq!(
+            ViewTransform::default()
+                .zoom_at(100.0, (0.0, 0.0), size)
+                .zoom,
+            MAX_ZOOM
+        );
+    }
+
+    #[test]
+    fn test_swipe_duration_follows_drag_speed() {
+        assert_eq!(swipe_duration_ms(Duration::from_millis(420)), 420);
+        assert_eq!(swipe_duration_ms(Duration::from_millis(5)), MIN_SWIPE_MS);
+        assert_eq!(swipe_duration_ms(Duration::from_secs(60)), MAX_SWIPE_MS);
+    }
+}
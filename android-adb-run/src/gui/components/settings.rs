@@ -0,0 +1,305 @@
+// gui/components/settings.rs
+use crate::adb::BACKEND_NAME;
+use crate::game_automation::AutomationCommand;
+use crate::game_automation::config::{config_path, save_active_config};
+use crate::game_automation::quiet_hours::TimeOfDay;
+use crate::gui::dioxus_app::{AppContext, is_debug_mode, set_debug_mode};
+use crate::gui::preferences::{update_preferences, use_panel_open};
+use crate::gui::theme::{Layout, Theme};
+use dioxus::prelude::*;
+
+const FIELD_STYLE: &str = "background: var(--control-bg); color: var(--control-text); border:1px solid var(--control-border); border-radius:6px; padding:3px 6px; font-size:0.95em;";
+
+/// Matching thresholds, screenshot interval, touch pause, quiet hours, template
+/// directories and debug mode, applied to this tab's automation and saved to its profile.
+/// Theme and layout are GUI preferences, saved as soon as they change.
+#[component]
+pub fn Settings() -> Element {
+    let ctx = use_context::<AppContext>();
+    let automation_command_tx = ctx.automation.command_tx;
+    let mut screenshot_status = ctx.screenshot.status;
+    let open = use_panel_open("settings");
+    let mut tab_config = ctx.automation.config;
+    let mut config = use_signal(move || tab_config.peek().clone());
+    let mut debug = use_signal(is_debug_mode);
+
+    let save = move |_| {
+        let edited = config.read().clone();
+        let debug_enabled = *debug.read();
+        set_debug_mode(debug_enabled);
+        if let Some(tx) = automation_command_tx.read().as_ref() {
+            let tx = tx.clone();
+            spawn(async move {
+                let _ = tx
+                    .send(AutomationCommand::UpdateConfig {
+                        config: Box::new(edited),
+                        debug_enabled,
+                    })
+                    .await;
+            });
+        } else {
+            // No automation running: save directly, the GUI matching reads the active config
+            let mut saved = tab_config.read().clone();
+            saved.apply_settings(&edited);
+            tab_config.set(saved.clone());
+            let message = match save_active_config(saved) {
+                Ok(()) => format!("⚙️ Settings saved to {}", config_path().display()),
+                Err(e) => format!("❌ Failed to save settings: {}", e),
+            };
+            screenshot_status.set(message);
+        }
+    };
+
+    let current = config.read().clone();
+    let template_dirs = current.matching.template_dirs.join(", ");
+    let look = ctx.preferences.read().clone();
+    let preferences = ctx.preferences;
+    let button_style = "background: var(--control-bg); color: var(--control-text); border:1px solid var(--control-border); padding:4px 10px; border-radius:6px; font-size:0.8em; font-weight:600; cursor:pointer;";
+
+    rsx! {
+        div { class: "panel", style: "padding: 12px; border-radius: 12px;",
+            div { style: "display:flex; align-items:center; justify-content:space-between; cursor:pointer;",
+                onclick: move |_| {
+                    let is_open = open.get();
+                    if !is_open {
+                        // Start from what is saved, not from earlier unsaved edits
+                        config.set(tab_config.read().clone());
+                        debug.set(is_debug_mode());
+                    }
+                    open.set(!is_open);
+                },
+                h2 { style: "margin: 0; color: var(--heading); font-size: 0.95em;", "⚙️ Settings" }
+                span { style: "font-size:0.8em; opacity:0.75;", if open.get() { "▾" } else { "▸" } }
+            }
+            if open.get() {
+                div { style: "display: grid; grid-template-columns: 1fr 1fr; gap: 6px 10px; margin-top: 8px;",
+                    NumberField {
+                        label: "Confidence threshold",
+                        value: current.matching.confidence_threshold.to_string(),
+                        step: "0.01",
+                        on_change: move |value: String| {
+                            if let Ok(threshold) = value.parse::<f64>() {
+                                config.write().matching.confidence_threshold = threshold.clamp(0.0, 1.0);
+                            }
+                        },
+                    }
+                    NumberField {
+                        label: "Patch threshold",
+                        value: current.matching.patch_threshold.to_string(),
+                        step: "0.01",
+                        on_change: move |value: String| {
+                            if let Ok(threshold) = value.parse::<f64>() {
+                                config.write().matching.patch_threshold = threshold.clamp(0.0, 1.0);
+                            }
+                        },
+                    }
+                    NumberField {
+                        label: "Search radius (px)",
+                        value: current.matching.patch_search_radius.to_string(),
+                        step: "10",
+                        on_change: move |value: String| {
+                            if let Ok(radius) = value.parse::<u32>() {
+                                config.write().matching.patch_search_radius = radius;
+                            }
+                        },
+                    }
+                    NumberField {
+                        label: "Search step (px)",
+                        value: current.matching.patch_search_step.to_string(),
+                        step: "1",
+                        on_change: move |value: String| {
+                            if let Ok(step) = value.parse::<u32>() {
+                                config.write().matching.patch_search_step = step.max(1);
+                            }
+                        },
+                    }
+                    NumberField {
+                        label: "Pyramid factor",
+                        value: current.matching.pyramid_factor.to_string(),
+                        step: "1",
+                        on_change: move |value: String| {
+                            if let Ok(factor) = value.parse::<u32>() {
+                                config.write().matching.pyramid_factor = factor.max(1);
+                            }
+                        },
+                    }
+                    NumberField {
+                        label: "Early exit slack",
+                        value: current.matching.patch_early_exit_slack.to_string(),
+                        step: "0.01",
+                        on_change: move |value: String| {
+                            if let Ok(slack) = value.parse::<f64>() {
+                                config.write().matching.patch_early_exit_slack = slack.clamp(0.0, 1.0);
+                            }
+                        },
+                    }
+                    NumberField {
+                        label: "Max candidates",
+                        value: current.matching.patch_max_candidates.to_string(),
+                        step: "1",
+                        on_change: move |value: String| {
+                            if let Ok(max) = value.parse::<usize>() {
+                                config.write().matching.patch_max_candidates = max.max(1);
+                            }
+                        },
+                    }
+                    NumberField {
+                        label: "Screenshot every (min)",
+                        value: current.timed_events.screenshot_interval_minutes.to_string(),
+                        step: "1",
+                        on_change: move |value: String| {
+                            if let Ok(minutes) = value.parse::<u64>() {
+                                config.write().timed_events.screenshot_interval_minutes = minutes.max(1);
+                            }
+                        },
+                    }
+                    label { style: "display:flex; flex-direction:column; gap:2px; font-size:0.8em;",
+                        "Touch pause: {current.touch.pause_timeout_seconds}s"
+                        input {
+                            r#type: "range",
+                            min: "5",
+                            max: "300",
+                            step: "5",
+                            value: "{current.touch.pause_timeout_seconds}",
+                            oninput: move |evt| {
+                                if let Ok(seconds) = evt.value().parse::<u64>() {
+                                    config.write().touch.pause_timeout_seconds = seconds;
+                                }
+                            },
+                            // Live: the running automation takes the new pause on release
+                            onchange: move |evt| {
+                                if let Ok(seconds) = evt.value().parse::<u64>()
+                                    && let Some(tx) = automation_command_tx.read().as_ref()
+                                {
+                                    let tx = tx.clone();
+                                    spawn(async move {
+                                        let _ = tx.send(AutomationCommand::SetTouchTimeout(seconds)).await;
+                                    });
+                                }
+                            },
+                        }
+                    }
+                    label { style: "display:flex; align-items:center; gap:6px; font-size:0.8em;",
+                        input {
+                            r#type: "checkbox",
+                            checked: *debug.read(),
+                            onchange: move |evt| debug.set(evt.checked()),
+                        }
+                        "Debug output"
+                    }
+                    // [quiet_hours]: running automation sleeps from start to end
+                    label { style: "display:flex; align-items:center; gap:6px; font-size:0.8em;",
+                        input {
+                            r#type: "checkbox",
+                            checked: current.quiet_hours.enabled,
+                            onchange: move |evt| config.write().quiet_hours.enabled = evt.checked(),
+                        }
+                        "Quiet hours"
+                    }
+                    label { style: "display:flex; flex-direction:column; gap:2px; font-size:0.8em;",
+                        "Sleep from"
+                        input {
+                            r#type: "time",
+                            style: FIELD_STYLE,
+                            value: "{current.quiet_hours.start}",
+                            onchange: move |evt| {
+                                if let Ok(start) = evt.value().parse::<TimeOfDay>() {
+                                    config.write().quiet_hours.start = start;
+                                }
+                            },
+                        }
+                    }
+                    label { style: "display:flex; flex-direction:column; gap:2px; font-size:0.8em;",
+                        "Wake at"
+                        input {
+                            r#type: "time",
+                            style: FIELD_STYLE,
+                            value: "{current.quiet_hours.end}",
+                            onchange: move |evt| {
+                                if let Ok(end) = evt.value().parse::<TimeOfDay>() {
+                                    config.write().quiet_hours.end = end;
+                                }
+                            },
+                        }
+                    }
+                    // Window look, applied and remembered right away
+                    label { style: "display:flex; flex-direction:column; gap:2px; font-size:0.8em;",
+                        "Theme"
+                        select {
+                            style: FIELD_STYLE,
+                            onchange: move |evt| {
+                                if let Ok(theme) = evt.value().parse::<Theme>() {
+                                    update_preferences(preferences, |prefs| prefs.theme = theme);
+                                }
+                            },
+                            for theme in Theme::ALL {
+                                option { value: theme.name(), selected: theme == look.theme, "{theme.label()}" }
+                            }
+                        }
+                    }
+                    label { style: "display:flex; flex-direction:column; gap:2px; font-size:0.8em;",
+                        "Layout"
+                        select {
+                            style: FIELD_STYLE,
+                            onchange: move |evt| {
+                                if let Ok(layout) = evt.value().parse::<Layout>() {
+                                    update_preferences(preferences, |prefs| prefs.layout = layout);
+                                }
+                            },
+                            for layout in Layout::ALL {
+                                option { value: layout.name(), selected: layout == look.layout, "{layout.label()}" }
+                            }
+                        }
+                    }
+                }
+                label { style: "display:flex; flex-direction:column; gap:2px; font-size:0.8em; margin-top:6px;",
+                    "Template directories (comma separated)"
+                    input {
+                        r#type: "text",
+                        style: FIELD_STYLE,
+                        value: "{template_dirs}",
+                        onchange: move |evt| {
+                            let dirs: Vec<String> = evt
+                                .value()
+                                .split(',')
+                                .map(|dir| dir.trim().to_string())
+                                .filter(|dir| !dir.is_empty())
+                                .collect();
+                            if !dirs.is_empty() {
+                                config.write().matching.template_dirs = dirs;
+                            }
+                        },
+                    }
+                }
+                div { style: "display:flex; gap:6px; align-items:center; justify-content:space-between; margin-top:8px;",
+                    span { style: "font-size:0.75em; opacity:0.75;",
+                        "Backend: {BACKEND_NAME} (chosen at build time)"
+                    }
+                    button { style: button_style, onclick: save, "💾 Save" }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn NumberField(
+    label: &'static str,
+    value: String,
+    step: &'static str,
+    on_change: EventHandler<String>,
+) -> Element {
+    rsx! {
+        label { style: "display:flex; flex-direction:column; gap:2px; font-size:0.8em;",
+            "{label}"
+            input {
+                r#type: "number",
+                step: step,
+                min: "0",
+                style: FIELD_STYLE,
+                value: "{value}",
+                onchange: move |evt| on_change.call(evt.value()),
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// gui/components/shell_console.rs
+use crate::adb::{AdbClient, ShellOutput};
+use crate::gui::dioxus_app::AppContext;
+use crate::gui::preferences::use_panel_open;
+use dioxus::prelude::*;
+use std::time::Duration;
+
+/// Commands typed into the console are killed on the device after this long
+const CONSOLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// One-line device shell with its stdout, stderr and exit code
+#[component]
+pub fn ShellConsole() -> Element {
+    let ctx = use_context::<AppContext>();
+    let shared_adb_client = ctx.shared_adb_client;
+    let mut command = use_signal(String::new);
+    let mut output = use_signal(|| None::<Result<ShellOutput, String>>);
+    let mut is_running = use_signal(|| false);
+    let expanded = use_panel_open("shell");
+
+    let mut run = move || {
+        let line = command.read().trim().to_string();
+        if line.is_empty() || *is_running.read() {
+            return;
+        }
+        let Some(client) = shared_adb_client.read().clone() else {
+            output.set(Some(Err("ADB client not connected".to_string())));
+            return;
+        };
+        is_running.set(true);
+        spawn(async move {
+            let result = client
+                .lock()
+                .await
+                .run_shell(&[&line], CONSOLE_TIMEOUT)
+                .await
+                .map_err(|e| e.to_string());
+            output.set(Some(result));
+            is_running.set(false);
+        });
+    };
+
+    let running = *is_running.read();
+    let button_style = "background: var(--control-bg); color: var(--control-text); border:1px solid var(--control-border); padding:4px 10px; border-radius:6px; font-size:0.8em; font-weight:600; cursor:pointer;";
+    let pre_style = "margin:0; max-height:180px; overflow:auto; background: rgba(0,0,0,0.35); border-radius:6px; padding:6px 8px; font-size:0.75em; white-space:pre-wrap; word-break:break-all;";
+
+    rsx! {
+        div { class: "panel", style: "padding: 10px 12px; border-radius: 12px; display:flex; flex-direction:column; gap:6px;",
+            div { style: "display:flex; align-items:center; gap:6px;",
+                span {
+                    style: "font-size:0.85em; font-weight:600; color:var(--heading); cursor:pointer;",
+                    onclick: move |_| { let open = expanded.get(); expanded.set(!open); },
+                    if expanded.get() { "▾ 🐚 Shell" } else { "▸ 🐚 Shell" }
+                }
+            }
+            if expanded.get() {
+                div { style: "display:flex; align-items:center; gap:6px;",
+                    input {
+                        style: "flex:1; min-width:140px; background: var(--control-bg); color: var(--control-text); border:1px solid var(--control-border); border-radius:6px; padding:4px 8px; font-size:0.85em; font-family:monospace;",
+                        placeholder: "getprop ro.product.model",
+                        value: "{command}",
+                        oninput: move |evt| command.set(evt.value()),
+                        onkeydown: move |evt| {
+                            if evt.key() == Key::Enter {
+                                run();
+                            }
+                        },
+                    }
+                    button { style: button_style, disabled: running, title: "Run on the device (killed after 30s)", onclick: move |_| run(), if running { "⏳" } else { "Run" } }
+                }
+                match output.read().as_ref() {
+                    Some(Ok(result)) => rsx! {
+                        span {
+                            style: if result.success() { "font-size:0.75em; color:var(--heading);" } else { "font-size:0.75em; color:#ff6b6b;" },
+                            "exit {result.exit_code}"
+                        }
+                        if !result.stdout.is_empty() {
+                            pre { style: pre_style, "{result.stdout}" }
+                        }
+                        if !result.stderr.is_empty() {
+                            pre { style: "{pre_style} color:#ffb3b3;", "{result.stderr}" }
+                        }
+                    },
+                    Some(Err(e)) => rsx! {
+                        span { style: "font-size:0.75em; color:#ff6b6b;", "❌ {e}" }
+                    },
+                    None => rsx! {},
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// gui/components/stats_panel.rs
+use crate::game_automation::AutomationCommand;
+use crate::game_automation::stats::StatsSummary;
+use crate::gui::dioxus_app::AppContext;
+use dioxus::prelude::*;
+
+/// Files written by the export buttons, next to automation.toml
+const STATS_CSV_PATH: &str = "automation_stats.csv";
+const STATS_JSON_PATH: &str = "automation_stats.json";
+
+/// Templates listed in the panel, most checked first
+const TEMPLATES_SHOWN: usize = 5;
+
+/// Tap counts, template match rates, screenshot latency and run time,
+/// with buttons to export the full statistics
+#[component]
+pub fn StatsPanel() -> Element {
+    let ctx = use_context::<AppContext>();
+    let command_tx = ctx.automation.command_tx;
+    let stats = ctx.automation.stats.read().clone();
+    if stats == StatsSummary::default() {
+        return rsx! {};
+    }
+
+    let latency = stats.screenshot_latency;
+    let run_time = match stats.current_run_secs {
+        Some(secs) => format_duration(secs),
+        None => "stopped".to_string(),
+    };
+    let finished_runs = stats.runs.len();
+    let total_taps = stats.total_taps();
+    let mut templates: Vec<_> = stats.templates.iter().collect();
+    templates.sort_by(|a, b| b.1.checks.cmp(&a.1.checks));
+    let template_lines: Vec<String> = templates
+        .into_iter()
+        .take(TEMPLATES_SHOWN)
+        .map(|(name, t)| {
+            format!(
+                "🎯 {}: {}/{} found ({:.0}%), {} acted",
+                name,
+                t.matches,
+                t.checks,
+                t.success_rate() * 100.0,
+                t.actions
+            )
+        })
+        .collect();
+
+    let export = move |path: &'static str| {
+        if let Some(tx) = command_tx.read().as_ref() {
+            let tx = tx.clone();
+            spawn(async move {
+                let _ = tx
+                    .send(AutomationCommand::ExportStats(path.to_string()))
+                    .await;
+            });
+        }
+    };
+
+    rsx! {
+        div { class: "panel", style: "padding: 12px; border-radius: 12px;",
+            div { style: "display: flex; align-items: center; gap: 6px; margin-bottom: 8px;",
+                h2 { style: "margin: 0; color: var(--accent); font-size: 0.95em;", "📊 Statistics" }
+                button {
+                    style: "margin-left: auto; background: rgba(255,255,255,0.08); color: var(--accent); border: 1px solid rgba(135,206,235,0.45); border-radius: 4px; padding: 2px 6px; font-size: 0.7em; cursor: pointer;",
+                    title: "Write statistics to {STATS_CSV_PATH}",
+                    onclick: move |_| export(STATS_CSV_PATH),
+                    "⬇️ CSV"
+                }
+                button {
+                    style: "background: rgba(255,255,255,0.08); color: var(--accent); border: 1px solid rgba(135,206,235,0.45); border-radius: 4px; padding: 2px 6px; font-size: 0.7em; cursor: pointer;",
+                    title: "Write statistics to {STATS_JSON_PATH}",
+                    onclick: move |_| export(STATS_JSON_PATH),
+                    "⬇️ JSON"
+                }
+            }
+            div { style: "display: grid; grid-template-columns: 1fr 1fr; gap: 4px 10px;",
+                p { style: "margin:3px 0; font-size:0.8em;", strong { "Run: " } span { style: "color:var(--value);", "{run_time} ({finished_runs} finished)" } }
+                p { style: "margin:3px 0; font-size:0.8em;", strong { "Taps: " } span { style: "color:var(--value);", "{total_taps}" } }
+                p { style: "margin:3px 0; font-size:0.8em; grid-column: span 2;",
+                    strong { "Screenshot: " }
+                    span { style: "color:var(--value);", "p50 {latency.p50_ms}ms · p90 {latency.p90_ms}ms · p99 {latency.p99_ms}ms" }
+                }
+            }
+            for line in template_lines {
+                p { style: "margin:3px 0; font-size:0.75em; color:#ddd;", "{line}" }
+            }
+        }
+    }
+}
+
+fn format_duration(secs: u64) -> String {
+    match secs {
+        s if s >= 3600 => format!("{}h {:02}m", s / 3600, s % 3600 / 60),
+        s if s >= 60 => format!("{}m {:02}s", s / 60, s % 60),
+        s => format!("{}s", s),
+    }
+}
@@ -0,0 +1,68 @@
+// gui/components/telemetry_panel.rs
+use crate::adb::DeviceTelemetry;
+use crate::game_automation::config::{GuardrailsConfig, active_config};
+use crate::gui::dioxus_app::AppContext;
+use dioxus::prelude::*;
+
+/// Battery, temperature, thermal status and memory of the connected device;
+/// readings past a `[guardrails]` pause level are shown in red
+#[component]
+pub fn TelemetryPanel() -> Element {
+    let ctx = use_context::<AppContext>();
+    let Some(telemetry) = ctx.device.telemetry.read().clone() else {
+        return rsx! {};
+    };
+    let guardrails = active_config().guardrails;
+
+    let (battery, battery_color) = battery_text(&telemetry, &guardrails);
+    let (temperature, temperature_color) = match telemetry.battery_temp_c {
+        Some(temp) if temp as f64 > guardrails.pause_above_temp_c => {
+            (format!("{:.1}°C", temp), "#ff6b6b")
+        }
+        Some(temp) => (format!("{:.1}°C", temp), "var(--value)"),
+        None => ("-".to_string(), "#999"),
+    };
+    let (thermal, thermal_color) = match (telemetry.thermal_status, telemetry.thermal_label()) {
+        (Some(status), Some(label)) if status >= guardrails.pause_at_thermal_status => {
+            (label.to_string(), "#ff6b6b")
+        }
+        (_, Some(label)) => (label.to_string(), "var(--value)"),
+        _ => ("-".to_string(), "#999"),
+    };
+    let memory = match (telemetry.mem_available_mb, telemetry.mem_total_mb) {
+        (Some(available), Some(total)) => format!("{} / {} MB free", available, total),
+        _ => "-".to_string(),
+    };
+
+    rsx! {
+        div { class: "panel", style: "padding: 12px; border-radius: 12px;",
+            h2 { style: "margin: 0 0 8px 0; color: var(--heading); font-size: 0.95em;", "🩺 Device Health" }
+            div { style: "display: grid; grid-template-columns: 1fr 1fr; gap: 4px 10px;",
+                p { style: "margin:3px 0; font-size:0.8em;", strong { "Battery: " } span { style: "color:{battery_color};", "{battery}" } }
+                p { style: "margin:3px 0; font-size:0.8em;", strong { "Temperature: " } span { style: "color:{temperature_color};", "{temperature}" } }
+                p { style: "margin:3px 0; font-size:0.8em;", strong { "Thermal: " } span { style: "color:{thermal_color};", "{thermal}" } }
+                p { style: "margin:3px 0; font-size:0.8em;", strong { "Memory: " } span { style: "color:var(--value);", "{memory}" } }
+            }
+        }
+    }
+}
+
+fn battery_text(
+    telemetry: &DeviceTelemetry,
+    guardrails: &GuardrailsConfig,
+) -> (String, &'static str) {
+    let Some(level) = telemetry.battery_level else {
+        return ("-".to_string(), "#999");
+    };
+    let plug = if telemetry.charging == Some(true) {
+        " ⚡"
+    } else {
+        ""
+    };
+    let color = if level < guardrails.pause_below_battery && telemetry.charging != Some(true) {
+        "#ff6b6b"
+    } else {
+        "var(--value)"
+    };
+    (format!("{}%{}", level, plug), color)
+}
@@ -0,0 +1,122 @@
+// gui/components/template_editor.rs
+use crate::game_automation::AutomationCommand;
+use crate::game_automation::config::active_config;
+use crate::game_automation::match_image::TemplateCategory;
+use crate::gui::dioxus_app::AppContext;
+use crate::template_matching::{PatchInfo, sanitize_label};
+use dioxus::prelude::*;
+
+/// Save the box selected on the screenshot as a `patch-label-[x,y,w,h].png` template
+#[component]
+pub fn TemplateEditor() -> Element {
+    let ctx = use_context::<AppContext>();
+    let mut selected_region = ctx.interaction.selected_region;
+    let screenshot_bytes = ctx.screenshot.bytes;
+    let mut screenshot_status = ctx.screenshot.status;
+    let automation_command_tx = ctx.automation.command_tx;
+    let mut name = use_signal(String::new);
+    let mut category = use_signal(|| TemplateCategory::Button);
+    let mut is_saving = use_signal(|| false);
+
+    let Some((x, y, width, height)) = *selected_region.read() else {
+        return rsx! {};
+    };
+
+    let label_for_name =
+        move || sanitize_label(&name.read()).map(|n| category.read().label_for(&n));
+    let file_name = label_for_name()
+        .map(|label| PatchInfo::new(Some(label), x, y, width, height, Vec::new()).file_name());
+
+    let mut save = move |_: ()| {
+        let Some(label) = label_for_name() else {
+            return;
+        };
+        let Some(screenshot) = screenshot_bytes.read().clone() else {
+            screenshot_status.set("❌ No screenshot to crop the template from".to_string());
+            return;
+        };
+        let patch_dir = active_config().matching.patch_dir;
+        is_saving.set(true);
+        spawn(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                PatchInfo::from_screenshot(&screenshot, Some(label), x, y, width, height)?
+                    .save(&patch_dir)
+            })
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+            match result {
+                Ok(path) => {
+                    log::info!("🧩 Template saved: {}", path.display());
+                    screenshot_status.set(format!("🧩 Saved template {}", path.display()));
+                    // The GUI pipeline rereads the patch directory on every screenshot;
+                    // the automation detector needs a rescan
+                    if let Some(cmd_tx) = automation_command_tx.read().as_ref() {
+                        let _ = cmd_tx.try_send(AutomationCommand::RescanTemplates);
+                    }
+                    selected_region.set(None);
+                    name.set(String::new());
+                }
+                Err(e) => screenshot_status.set(format!("❌ Template not saved: {}", e)),
+            }
+            is_saving.set(false);
+        });
+    };
+
+    let saving = *is_saving.read();
+    let can_save = file_name.is_some() && !saving;
+    let field_style = "background: var(--control-bg); color: var(--control-text); border:1px solid var(--control-border); border-radius:6px; padding:4px 8px; font-size:0.85em;";
+    let button_style = "background: var(--control-bg); color: var(--control-text); border:1px solid var(--control-border); padding:4px 10px; border-radius:6px; font-size:0.8em; font-weight:600; cursor:pointer;";
+
+    rsx! {
+        div { style: "margin-top:10px; background: rgba(77,163,255,0.12); padding:10px 12px; border-radius:10px; border:1px solid rgba(77,163,255,0.5); display:flex; flex-direction:column; gap:6px;",
+            div { style: "font-size:0.85em; font-weight:600; color:#8ad0ff;",
+                "🧩 New template from [{x},{y},{width},{height}]"
+            }
+            div { style: "display:flex; gap:6px; flex-wrap:wrap;",
+                input {
+                    style: "flex:1; min-width:120px; {field_style}",
+                    placeholder: "Name, e.g. claim",
+                    value: "{name}",
+                    oninput: move |evt| name.set(evt.value()),
+                    onkeydown: move |evt| {
+                        if evt.key() == Key::Enter && can_save {
+                            save(());
+                        }
+                    },
+                }
+                select {
+                    style: field_style,
+                    onchange: move |evt| {
+                        let index = evt.value().parse::<usize>().unwrap_or(0);
+                        category.set(TemplateCategory::ALL[index.min(TemplateCategory::ALL.len() - 1)].clone());
+                    },
+                    for (index, option_category) in TemplateCategory::ALL.iter().enumerate() {
+                        option {
+                            value: "{index}",
+                            selected: *option_category == *category.read(),
+                            "{option_category:?}"
+                        }
+                    }
+                }
+            }
+            div { style: "font-size:0.75em; opacity:0.75; word-break:break-all;",
+                if let Some(file_name) = file_name.as_ref() {
+                    "Saves {file_name}"
+                } else {
+                    "Enter a name for the template"
+                }
+            }
+            div { style: "display:flex; gap:6px; justify-content:flex-end;",
+                button {
+                    style: button_style,
+                    onclick: move |_| {
+                        selected_region.set(None);
+                        name.set(String::new());
+                    },
+                    "Cancel"
+                }
+                button { style: button_style, disabled: !can_save, onclick: move |_| save(()), if saving { "Saving..." } else { "💾 Save template" } }
+            }
+        }
+    }
+}
@@ -0,0 +1,366 @@
+// gui/components/templates_panel.rs
+use crate::game_automation::AutomationCommand;
+use crate::game_automation::config::{AutomationConfig, active_config, save_active_config};
+use crate::game_automation::match_image::{
+    GameStateDetector, Template, TemplateManager, TemplateTest,
+};
+use crate::gui::dioxus_app::{AppContext, is_debug_mode};
+use crate::gui::hooks::types::CommandTxSignal;
+use crate::gui::preferences::use_panel_open;
+use crate::gui::util::scaled_data_url;
+use dioxus::prelude::*;
+
+/// Largest side of a template thumbnail, in pixels
+const THUMBNAIL_SIZE: u32 = 48;
+/// Screen size templates are listed for before a device is connected
+const DEFAULT_SCREEN: (u32, u32) = (1080, 2400);
+
+/// A template from `[matching] template_dirs` with its thumbnail `data:` URL
+#[derive(Clone)]
+struct TemplateRow {
+    template: Template,
+    thumbnail: String,
+}
+
+/// Every template automation loads, with its thumbnail, category, threshold and
+/// latest match; buttons disable, delete, re-crop or test a single template
+#[component]
+pub fn TemplatesPanel() -> Element {
+    let ctx = use_context::<AppContext>();
+    let automation_command_tx = ctx.automation.command_tx;
+    let stats = ctx.automation.stats;
+    let device_info = ctx.device.info;
+    let screenshot_bytes = ctx.screenshot.bytes;
+    let mut screenshot_status = ctx.screenshot.status;
+    let mut selected_region = ctx.interaction.selected_region;
+    let mut template_test = ctx.automation.template_test;
+    let open = use_panel_open("templates");
+    let mut rows = use_signal(Vec::<TemplateRow>::new);
+    let mut loaded = use_signal(|| false);
+    let mut confirm_delete = use_signal(|| None::<String>);
+    let mut busy = use_signal(|| false);
+
+    let mut reload = move || {
+        loaded.set(true);
+        let screen = device_info
+            .read()
+            .as_ref()
+            .map_or(DEFAULT_SCREEN, |info| (info.screen_x, info.screen_y));
+        spawn(async move {
+            let config = active_config();
+            match tokio::task::spawn_blocking(move || load_rows(&config, screen)).await {
+                Ok(loaded_rows) => rows.set(loaded_rows),
+                Err(e) => log::warn!("⚠️ Template list task failed: {}", e),
+            }
+        });
+    };
+
+    use_effect(move || {
+        if open.get() && !*loaded.peek() {
+            reload();
+        }
+    });
+
+    let rescan = move || {
+        if let Some(tx) = automation_command_tx.read().as_ref() {
+            let _ = tx.try_send(AutomationCommand::RescanTemplates);
+        }
+    };
+
+    let mut toggle = move |index: usize| {
+        let Some(row) = rows.read().get(index).cloned() else {
+            return;
+        };
+        let enabled = !row.template.behavior.enabled;
+        let mut edited = active_config();
+        let key = if edited.matching.templates.contains_key(&row.template.name) {
+            row.template.name.clone()
+        } else {
+            row.template.label().to_string()
+        };
+        edited.matching.templates.entry(key).or_default().enabled = enabled;
+        apply_config(edited, automation_command_tx, screenshot_status);
+        if let Some(row) = rows.write().get_mut(index) {
+            row.template.behavior.enabled = enabled;
+        }
+    };
+
+    let mut delete = move |template: Template| {
+        if confirm_delete.read().as_deref() != Some(template.name.as_str()) {
+            confirm_delete.set(Some(template.name.clone()));
+            return;
+        }
+        confirm_delete.set(None);
+        match std::fs::remove_file(&template.path) {
+            Ok(()) => {
+                log::info!("🗑️ Template deleted: {}", template.path);
+                screenshot_status.set(format!("🗑️ Deleted template {}", template.name));
+                rescan();
+                reload();
+            }
+            Err(e) => screenshot_status.set(format!("❌ Cannot delete {}: {}", template.path, e)),
+        }
+    };
+
+    let mut recrop = move |template: Template| {
+        let Some(region) = *selected_region.read() else {
+            screenshot_status.set("❌ Select a box on the screenshot to re-crop from".to_string());
+            return;
+        };
+        let Some(screenshot) = screenshot_bytes.read().clone() else {
+            screenshot_status.set("❌ No screenshot to re-crop from".to_string());
+            return;
+        };
+        busy.set(true);
+        spawn(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                let image = image::load_from_memory(&screenshot)
+                    .map_err(|e| format!("Failed to decode screenshot: {}", e))?
+                    .to_rgb8();
+                template.recrop(&image, region)
+            })
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+            match result {
+                Ok(path) => {
+                    log::info!("✂️ Template re-cropped: {}", path);
+                    screenshot_status.set(format!("✂️ Re-cropped template {}", path));
+                    selected_region.set(None);
+                    rescan();
+                    reload();
+                }
+                Err(e) => screenshot_status.set(format!("❌ Template not re-cropped: {}", e)),
+            }
+            busy.set(false);
+        });
+    };
+
+    let mut test = move |template: Template| {
+        // Running automation tests on its own latest screenshot and detector
+        if let Some(tx) = automation_command_tx.read().as_ref() {
+            let _ = tx.try_send(AutomationCommand::TestTemplate(template.name));
+            return;
+        }
+        let Some(screenshot) = screenshot_bytes.read().clone() else {
+            screenshot_status.set("❌ No screenshot to test the template on".to_string());
+            return;
+        };
+        busy.set(true);
+        spawn(async move {
+            let name = template.name.clone();
+            let result = tokio::task::spawn_blocking(move || test_template(&screenshot, &name))
+                .await
+                .unwrap_or_else(|e| Err(e.to_string()));
+            match result {
+                Ok(test) => template_test.set(Some(test)),
+                Err(e) => {
+                    screenshot_status.set(format!("❌ Test of {} failed: {}", template.name, e))
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    let threshold = active_config().matching.confidence_threshold;
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let template_stats = stats.read().templates.clone();
+    let has_region = selected_region.read().is_some();
+    let working = *busy.read();
+    let last_test = template_test.read().clone();
+    let button_style = "background: var(--control-bg); color: var(--control-text); border:1px solid var(--control-border); padding:2px 6px; border-radius:4px; font-size:0.7em; cursor:pointer;";
+
+    rsx! {
+        div { class: "panel", style: "padding: 12px; border-radius: 12px;",
+            div { style: "display:flex; align-items:center; justify-content:space-between; cursor:pointer;",
+                onclick: move |_| {
+                    let is_open = open.get();
+                    if !is_open {
+                        reload();
+                    }
+                    open.set(!is_open);
+                },
+                h2 { style: "margin: 0; color: var(--heading); font-size: 0.95em;", "🧩 Templates ({rows.read().len()})" }
+                span { style: "font-size:0.8em; opacity:0.75;", if open.get() { "▾" } else { "▸" } }
+            }
+            if open.get() {
+                div { style: "margin-top:8px; display:flex; flex-direction:column; gap:6px; max-height:360px; overflow-y:auto;",
+                    if rows.read().is_empty() {
+                        p { style: "margin:0; font-size:0.8em; opacity:0.7;", "No templates in the template directories" }
+                    }
+                    for (index, row) in rows.read().iter().cloned().enumerate() {
+                        {
+                            let template = row.template;
+                            let enabled = template.behavior.enabled;
+                            let last_match = template_stats.get(&template.name).and_then(|t| {
+                                Some(format!(
+                                    "{:.2} {}",
+                                    t.last_confidence?,
+                                    format_ago(now - t.last_match_unix?)
+                                ))
+                            });
+                            let deleting = confirm_delete.read().as_deref() == Some(template.name.as_str());
+                            let (for_delete, for_recrop, for_test) = (template.clone(), template.clone(), template.clone());
+                            rsx! {
+                                div { key: "{template.path}", style: "display:flex; gap:8px; align-items:center; opacity:{if enabled { 1.0 } else { 0.5 }};",
+                                    img { src: "{row.thumbnail}", style: "width:{THUMBNAIL_SIZE}px; height:{THUMBNAIL_SIZE}px; object-fit:contain; background:rgba(0,0,0,0.25); border-radius:4px;" }
+                                    div { style: "flex:1; min-width:0; font-size:0.75em;",
+                                        div { style: "font-weight:600; overflow:hidden; text-overflow:ellipsis; white-space:nowrap;", title: "{template.path}", "{template.name}" }
+                                        div { style: "opacity:0.75;",
+                                            "{template.category:?} · ≥{threshold:.2} · "
+                                            if let Some(last_match) = last_match.as_ref() {
+                                                span { style: "color:var(--value);", "{last_match}" }
+                                            } else {
+                                                "never matched"
+                                            }
+                                        }
+                                        div { style: "display:flex; gap:4px; margin-top:3px; flex-wrap:wrap;",
+                                            button { style: button_style, onclick: move |_| toggle(index), if enabled { "⏸ Disable" } else { "▶ Enable" } }
+                                            button { style: button_style, onclick: move |_| delete(for_delete.clone()), if deleting { "⚠️ Confirm delete" } else { "🗑️ Delete" } }
+                                            button {
+                                                style: button_style,
+                                                disabled: !has_region || working,
+                                                title: "Replace with the box selected on the screenshot",
+                                                onclick: move |_| recrop(for_recrop.clone()),
+                                                "✂️ Re-crop"
+                                            }
+                                            button { style: button_style, disabled: working, onclick: move |_| test(for_test.clone()), "🎯 Test" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                if let Some(test) = last_test {
+                    div { style: "margin-top:8px; background:rgba(0,0,0,0.2); padding:6px 8px; border-radius:6px; font-size:0.75em; display:flex; flex-direction:column; gap:2px;",
+                        div { style: "display:flex; justify-content:space-between;",
+                            strong { "🎯 {test.template}" }
+                            span { style: "cursor:pointer; opacity:0.7;", onclick: move |_| template_test.set(None), "✕" }
+                        }
+                        for line in test_summary(&test) {
+                            div { "{line}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Load every template in `config`'s template directories with its behavior and thumbnail
+fn load_rows(config: &AutomationConfig, (width, height): (u32, u32)) -> Vec<TemplateRow> {
+    let mut manager = TemplateManager::new(width, height);
+    for directory in &config.matching.template_dirs {
+        if let Err(e) = manager.load_templates_from_directory(directory) {
+            log::debug!("Template directory skipped: {}", e);
+        }
+    }
+    manager.apply_behaviors(&config.matching.templates);
+    manager
+        .get_templates()
+        .iter()
+        .map(|template| {
+            let thumbnail = match template.load_image() {
+                Ok(image) => scaled_data_url(&image.to_rgb8(), THUMBNAIL_SIZE, THUMBNAIL_SIZE),
+                Err(e) => {
+                    log::warn!("⚠️ No thumbnail for {}: {}", template.name, e);
+                    String::new()
+                }
+            };
+            TemplateRow {
+                template: template.clone(),
+                thumbnail,
+            }
+        })
+        .collect()
+}
+
+/// Test one template on an encoded screenshot, with the saved matching settings
+fn test_template(screenshot: &[u8], name: &str) -> Result<TemplateTest, String> {
+    let image = image::load_from_memory(screenshot)
+        .map_err(|e| format!("Failed to decode screenshot: {}", e))?;
+    let config = active_config();
+    let mut detector = GameStateDetector::new(
+        image.width(),
+        image.height(),
+        config.matching.to_match_config(false),
+    );
+    detector.load_templates_from(&config.matching.template_dirs)?;
+    detector.test_template(name, &image.to_luma8())
+}
+
+/// Lines describing a template test: verdict and time, best position, score map
+fn test_summary(test: &TemplateTest) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{} match(es) ≥{:.2} in {} ms",
+        test.result.matches.len(),
+        test.threshold,
+        test.result.processing_time_ms
+    )];
+    if let Some(best) = &test.best {
+        lines.push(format!(
+            "Best {:.3} at ({}, {}), scale {:.2}",
+            best.confidence, best.x, best.y, best.scale_factor
+        ));
+    }
+    if let Some(scores) = &test.scores {
+        lines.push(format!(
+            "Scores min {:.3} · mean {:.3} · max {:.3}",
+            scores.min, scores.mean, scores.max
+        ));
+        lines.push(format!(
+            "{} of {} positions ≥ threshold",
+            scores.above_threshold, scores.positions
+        ));
+    }
+    if let Some((x, y, width, height)) = test.search_area {
+        lines.push(format!("Searched [{},{},{},{}]", x, y, width, height));
+    }
+    lines
+}
+
+/// Apply an edited config to running automation, or save it when none runs
+fn apply_config(
+    edited: AutomationConfig,
+    automation_command_tx: CommandTxSignal,
+    mut screenshot_status: Signal<String>,
+) {
+    if let Some(tx) = automation_command_tx.read().as_ref() {
+        let tx = tx.clone();
+        spawn(async move {
+            let _ = tx
+                .send(AutomationCommand::UpdateConfig {
+                    config: Box::new(edited),
+                    debug_enabled: is_debug_mode(),
+                })
+                .await;
+        });
+    } else if let Err(e) = save_active_config(edited) {
+        screenshot_status.set(format!("❌ Failed to save template settings: {}", e));
+    }
+}
+
+/// "45s ago", "12m ago", "3h ago" or "2d ago"
+fn format_ago(secs: i64) -> String {
+    let secs = secs.max(0);
+    match secs {
+        0..60 => format!("{}s ago", secs),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_ago() {
+        assert_eq!(format_ago(-3), "0s ago");
+        assert_eq!(format_ago(59), "59s ago");
+        assert_eq!(format_ago(150), "2m ago");
+        assert_eq!(format_ago(7200), "2h ago");
+        assert_eq!(format_ago(200_000), "2d ago");
+    }
+}
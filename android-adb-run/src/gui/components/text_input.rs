@@ -0,0 +1,86 @@
+// gui/components/text_input.rs
+use crate::adb::AdbClient;
+use crate::adb::types::{KEYCODE_BACK, KEYCODE_ENTER, KEYCODE_HOME};
+use crate::game_automation::AutomationCommand;
+use crate::gui::dioxus_app::AppContext;
+use dioxus::prelude::*;
+
+/// What a text-input row button sends to the device
+#[derive(Clone, Copy)]
+enum DeviceInput {
+    Text,
+    Key(u32, &'static str),
+}
+
+/// Text entry plus BACK/HOME/ENTER keys for filling forms on the device
+#[component]
+pub fn TextInput() -> Element {
+    let ctx = use_context::<AppContext>();
+    let shared_adb_client = ctx.shared_adb_client;
+    let automation_command_tx = ctx.automation.command_tx;
+    let mut screenshot_status = ctx.screenshot.status;
+    let mut text = use_signal(String::new);
+    let mut is_sending = use_signal(|| false);
+
+    let mut send = move |input: DeviceInput| {
+        let Some(client) = shared_adb_client.read().clone() else {
+            screenshot_status.set("❌ ADB client not connected".to_string());
+            return;
+        };
+        let value = text.read().clone();
+        if matches!(input, DeviceInput::Text) && value.is_empty() {
+            return;
+        }
+        // Manual input counts as human activity so automation doesn't tap over it
+        if let Some(cmd_tx) = automation_command_tx.read().as_ref() {
+            let _ = cmd_tx.try_send(AutomationCommand::RegisterTouchActivity);
+        }
+        is_sending.set(true);
+        spawn(async move {
+            let client = client.lock().await;
+            let (result, label) = match input {
+                DeviceInput::Text => (
+                    client.send_text(&value).await,
+                    format!("{} character(s)", value.chars().count()),
+                ),
+                DeviceInput::Key(keycode, name) => {
+                    (client.key_event(keycode).await, name.to_string())
+                }
+            };
+            match result {
+                Ok(()) => {
+                    screenshot_status.set(format!("⌨️ Sent {}", label));
+                    if matches!(input, DeviceInput::Text) {
+                        text.set(String::new());
+                    }
+                }
+                Err(e) => screenshot_status.set(format!("❌ Input failed: {}", e)),
+            }
+            is_sending.set(false);
+        });
+    };
+
+    let sending = *is_sending.read();
+    let button_style = "background: var(--control-bg); color: var(--control-text); border:1px solid var(--control-border); padding:4px 10px; border-radius:6px; font-size:0.8em; font-weight:600; cursor:pointer;";
+
+    rsx! {
+        div { class: "panel", style: "padding: 10px 12px; border-radius: 12px; display:flex; align-items:center; gap:6px; flex-wrap:wrap;",
+            span { style: "font-size:0.85em; font-weight:600; color:var(--heading);", "⌨️ Input" }
+            input {
+                style: "flex:1; min-width:140px; background: var(--control-bg); color: var(--control-text); border:1px solid var(--control-border); border-radius:6px; padding:4px 8px; font-size:0.85em;",
+                placeholder: "Text to type on the device",
+                value: "{text}",
+                oninput: move |evt| text.set(evt.value()),
+                onkeydown: move |evt| {
+                    if evt.key() == Key::Enter {
+                        send(DeviceInput::Text);
+                    }
+                },
+            }
+            button { style: button_style, disabled: sending, title: "Type the text into the focused field", onclick: move |_| send(DeviceInput::Text), "Send" }
+            button { style: button_style, disabled: sending, title: "Press ENTER", onclick: move |_| send(DeviceInput::Key(KEYCODE_ENTER, "ENTER")), "⏎" }
+            button { style: button_style, disabled: sending, title: "Press BACK", onclick: move |_| send(DeviceInput::Key(KEYCODE_BACK, "BACK")), "◀ Back" }
+            button { style: button_style, disabled: sending, title: "Press HOME", onclick: move |_| send(DeviceInput::Key(KEYCODE_HOME, "HOME")), "🏠 Home" }
+        }
+    }
+}
@@ -0,0 +1,321 @@
+// gui/components/timed_event_editor.rs
+use crate::game_automation::AutomationCommand;
+use crate::game_automation::coord::Coord;
+use crate::game_automation::types::{
+    MAX_TAP_INTERVAL_SECONDS, MIN_TAP_INTERVAL_SECONDS, TimedEvent, TimedEventType,
+};
+use crate::gui::dioxus_app::AppContext;
+use dioxus::prelude::*;
+
+const INPUT_STYLE: &str = "background: rgba(0,0,0,0.25); color: #eee; border: 1px solid rgba(255,255,255,0.2); border-radius: 4px; padding: 3px 6px; font-size: 0.8em; min-width: 0;";
+const BUTTON_STYLE: &str = "background: rgba(255,255,255,0.08); color: var(--accent); border: 1px solid rgba(135,206,235,0.45); border-radius: 4px; padding: 3px 8px; font-size: 0.75em; cursor: pointer;";
+
+/// Form to add a timed event, or edit the one picked with ✏️ in the list.
+/// Saving an existing id replaces that event.
+#[component]
+pub fn TimedEventEditor() -> Element {
+    let ctx = use_context::<AppContext>();
+    let automation_command_tx = ctx.automation.command_tx;
+    let timed_events_list = ctx.automation.timed_events_list;
+    let mut picking_point = ctx.interaction.picking_point;
+    let picked_point = ctx.interaction.picked_point;
+    let mut editing_event = ctx.interaction.editing_event;
+
+    let mut id = use_signal(String::new);
+    let mut kind = use_signal(|| "tap".to_string());
+    let mut x = use_signal(String::new);
+    let mut y = use_signal(String::new);
+    let mut interval = use_signal(|| "60".to_string());
+    let mut script = use_signal(String::new);
+    let mut template = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+
+    // Screenshot click while picking fills in the coordinates
+    use_effect(move || {
+        if let Some((px, py)) = *picked_point.read() {
+            x.set(px.to_string());
+            y.set(py.to_string());
+        }
+    });
+
+    // ✏️ in the event list loads that event into the form
+    use_effect(move || {
+        let Some(event) = editing_event.read().clone() else {
+            return;
+        };
+        id.set(event.id.clone());
+        interval.set(event.interval.as_secs().to_string());
+        error.set(None);
+        match &event.event_type {
+            TimedEventType::Tap { x: tx, y: ty } => {
+                kind.set("tap".to_string());
+                x.set(tx.to_string());
+                y.set(ty.to_string());
+            }
+            TimedEventType::ConditionalTap {
+                template: name,
+                x: tx,
+                y: ty,
+            } => {
+                kind.set("conditional".to_string());
+                template.set(name.clone());
+                x.set(tx.to_string());
+                y.set(ty.to_string());
+            }
+            TimedEventType::Screenshot => kind.set("screenshot".to_string()),
+            TimedEventType::Script { name } => {
+                kind.set("script".to_string());
+                script.set(name.clone());
+            }
+            TimedEventType::Sequence { name } => {
+                kind.set("sequence".to_string());
+                script.set(name.clone());
+            }
+            // Backups are set up in automation.toml only
+            TimedEventType::CountdownUpdate | TimedEventType::BackupFiles { .. } => {}
+        }
+    });
+
+    let editing = editing_event.read().as_ref().map(|e| e.id.clone());
+    let is_picking = *picking_point.read();
+    let current_kind = kind.read().clone();
+
+    let mut reset_form = move || {
+        id.set(String::new());
+        x.set(String::new());
+        y.set(String::new());
+        script.set(String::new());
+        template.set(String::new());
+        error.set(None);
+        editing_event.set(None);
+    };
+
+    rsx! {
+        div { style: "background: rgba(0,0,0,0.2); border-radius: 8px; padding: 10px 12px; border: 1px solid rgba(255,255,255,0.2); display: flex; flex-direction: column; gap: 6px;",
+            span { style: "font-size: 0.9em; color: var(--accent); font-weight: bold;",
+                if let Some(editing) = &editing { "✏️ Edit '{editing}'" } else { "➕ New Timed Event" }
+            }
+            div { style: "display: flex; gap: 6px; align-items: center; flex-wrap: wrap;",
+                select {
+                    style: INPUT_STYLE,
+                    value: "{current_kind}",
+                    disabled: editing.is_some(),
+                    onchange: move |evt| kind.set(evt.value()),
+                    option { value: "tap", "👆 Tap" }
+                    option { value: "conditional", "🎯 Tap if visible" }
+                    option { value: "screenshot", "📸 Screenshot" }
+                    option { value: "script", "📜 Script" }
+                    option { value: "sequence", "🧩 Sequence" }
+                }
+                if current_kind != "screenshot" {
+                    input {
+                        r#type: "text",
+                        style: "{INPUT_STYLE} width: 110px;",
+                        placeholder: "id",
+                        value: "{id}",
+                        disabled: editing.is_some(),
+                        oninput: move |evt| id.set(evt.value()),
+                    }
+                }
+                input {
+                    r#type: "number",
+                    style: "{INPUT_STYLE} width: 70px;",
+                    title: "Interval in seconds",
+                    value: "{interval}",
+                    oninput: move |evt| interval.set(evt.value()),
+                }
+                span { style: "font-size: 0.75em; color: #ccc;", "s" }
+            }
+            if current_kind == "tap" || current_kind == "conditional" {
+                div { style: "display: flex; gap: 6px; align-items: center;",
+                    input {
+                        r#type: "text",
+                        style: "{INPUT_STYLE} width: 60px;",
+                        placeholder: "x",
+                        title: "Pixels, or a percentage of the screen like 54%",
+                        value: "{x}",
+                        oninput: move |evt| x.set(evt.value()),
+                    }
+                    input {
+                        r#type: "text",
+                        style: "{INPUT_STYLE} width: 60px;",
+                        placeholder: "y",
+                        title: "Pixels, or a percentage of the screen like 54%",
+                        value: "{y}",
+                        oninput: move |evt| y.set(evt.value()),
+                    }
+                    button {
+                        style: if is_picking { "background: #ffc107; color: #222; border: none; border-radius: 4px; padding: 3px 8px; font-size: 0.75em; cursor: pointer;" } else { BUTTON_STYLE },
+                        title: "Click the screenshot to use that point",
+                        onclick: move |_| picking_point.set(!is_picking),
+                        if is_picking { "📍 Click screenshot…" } else { "📍 Pick" }
+                    }
+                }
+            }
+            if current_kind == "conditional" {
+                input {
+                    r#type: "text",
+                    style: INPUT_STYLE,
+                    placeholder: "template that must be on screen (file name without .png)",
+                    value: "{template}",
+                    oninput: move |evt| template.set(evt.value()),
+                }
+            }
+            if current_kind == "sequence" {
+                input {
+                    r#type: "text",
+                    style: INPUT_STYLE,
+                    placeholder: "sequence name ([sequences.<name>] in automation.toml)",
+                    value: "{script}",
+                    oninput: move |evt| script.set(evt.value()),
+                }
+            }
+            if current_kind == "script" {
+                input {
+                    r#type: "text",
+                    style: INPUT_STYLE,
+                    placeholder: "script name (automation_scripts/<name>.rhai)",
+                    value: "{script}",
+                    oninput: move |evt| script.set(evt.value()),
+                }
+            }
+            div { style: "display: flex; gap: 6px; align-items: center;",
+                button {
+                    style: BUTTON_STYLE,
+                    onclick: move |_| {
+                        let editing = editing_event.read().clone();
+                        let existing: Vec<String> = timed_events_list.read().iter().map(|e| e.id.clone()).collect();
+                        let form = EventForm {
+                            id: id.read().clone(),
+                            kind: kind.read().clone(),
+                            x: x.read().clone(),
+                            y: y.read().clone(),
+                            interval: interval.read().clone(),
+                            script: script.read().clone(),
+                            template: template.read().clone(),
+                        };
+                        match form.build(editing.as_ref(), &existing) {
+                            Ok(event) => {
+                                if let Some(tx) = automation_command_tx.read().as_ref() {
+                                    let tx = tx.clone();
+                                    spawn(async move {
+                                        let _ = tx.send(AutomationCommand::AddTimedEvent(event)).await;
+                                    });
+                                }
+                                reset_form();
+                            }
+                            Err(e) => error.set(Some(e)),
+                        }
+                    },
+                    if editing.is_some() { "💾 Update" } else { "➕ Add" }
+                }
+                if editing.is_some() {
+                    button {
+                        style: BUTTON_STYLE,
+                        onclick: move |_| reset_form(),
+                        "Cancel"
+                    }
+                }
+                if let Some(e) = error.read().as_ref() {
+                    span { style: "font-size: 0.75em; color: #ff6b6b;", "{e}" }
+                }
+            }
+        }
+    }
+}
+
+/// Raw form input, validated into a `TimedEvent`
+struct EventForm {
+    id: String,
+    kind: String,
+    x: String,
+    y: String,
+    interval: String,
+    script: String,
+    template: String,
+}
+
+impl EventForm {
+    fn build(
+        &self,
+        editing: Option<&TimedEvent>,
+        existing: &[String],
+    ) -> Result<TimedEvent, String> {
+        let interval_secs: u64 = self
+            .interval
+            .trim()
+            .parse()
+            .map_err(|_| "Interval must be whole seconds".to_string())?;
+
+        let mut event = match self.kind.as_str() {
+            "screenshot" => TimedEvent::new_screenshot(interval_secs.max(1)),
+            kind => {
+                let id = self.id.trim();
+                if id.is_empty() || id.contains(char::is_whitespace) {
+                    return Err("Id must be a single word".to_string());
+                }
+                if id == "screenshot" || id == "countdown_update" {
+                    return Err(format!("'{}' is reserved", id));
+                }
+                if editing.is_none_or(|e| e.id != id) && existing.iter().any(|e| e == id) {
+                    return Err(format!("'{}' already exists", id));
+                }
+
+                if kind == "tap" || kind == "conditional" {
+                    if !(MIN_TAP_INTERVAL_SECONDS..=MAX_TAP_INTERVAL_SECONDS)
+                        .contains(&interval_secs)
+                    {
+                        return Err(format!(
+                            "Tap interval must be {}-{}s",
+                            MIN_TAP_INTERVAL_SECONDS, MAX_TAP_INTERVAL_SECONDS
+                        ));
+                    }
+                    let x: Coord = self
+                        .x
+                        .trim()
+                        .parse()
+                        .map_err(|_| "Pick or enter x (pixels or %)".to_string())?;
+                    let y: Coord = self
+                        .y
+                        .trim()
+                        .parse()
+                        .map_err(|_| "Pick or enter y (pixels or %)".to_string())?;
+                    if kind == "tap" {
+                        TimedEvent::new_tap_seconds(id.to_string(), x, y, interval_secs)
+                    } else {
+                        let template = self.template.trim();
+                        if template.is_empty() {
+                            return Err("Template name required".to_string());
+                        }
+                        TimedEvent::new_conditional_tap(
+                            id.to_string(),
+                            template.to_string(),
+                            x,
+                            y,
+                            std::time::Duration::from_secs(interval_secs),
+                        )
+                    }
+                } else {
+                    let name = self.script.trim().to_string();
+                    let event_type = match kind {
+                        _ if name.is_empty() => return Err(format!("{} name required", kind)),
+                        "sequence" => TimedEventType::Sequence { name },
+                        _ => TimedEventType::Script { name },
+                    };
+                    TimedEvent::new(
+                        id.to_string(),
+                        event_type,
+                        std::time::Duration::from_secs(interval_secs.max(1)),
+                    )
+                }
+            }
+        };
+
+        // Keep on/off state and any wall-clock schedule of the event being edited
+        if let Some(editing) = editing {
+            event.enabled = editing.enabled;
+            event.set_schedule(editing.schedule.clone());
+        }
+        Ok(event)
+    }
+}
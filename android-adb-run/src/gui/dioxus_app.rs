@@ -1,30 +1,57 @@
-use crate::adb::AdbBackend;
+use crate::adb::{AdbBackend, DevicePool, DeviceTelemetry};
 use crate::game_automation::GameState;
+use crate::game_automation::config::active_config;
+use crate::game_automation::counters::CounterReadings;
+use crate::game_automation::sequence::SequenceProgress;
+use crate::game_automation::stats::StatsSummary;
 use crate::game_automation::types::DeviceInfo as AutomationDeviceInfo;
-use crate::game_automation::types::TimedEvent;
+use crate::game_automation::types::{OrientationStatus, RecoveryAttempt, TimedEvent};
+use crate::game_automation::watchdog::StuckDetection;
 use crate::gui::components::{
     actions::Actions,
+    counters_panel::CountersPanel,
     device_info::DeviceInfo,
+    device_tabs::{DeviceTabBar, DeviceTabs, PRIMARY_TAB, TabSummary},
+    history_panel::HistoryPanel,
+    log_viewer::LogViewer,
     screenshot_panel::{TapMarker, screenshot_panel},
+    settings::Settings,
+    shell_console::ShellConsole,
+    stats_panel::StatsPanel,
+    telemetry_panel::TelemetryPanel,
+    templates_panel::TemplatesPanel,
+    text_input::TextInput,
 };
 use crate::gui::hooks::{
     AutomationStateSignals, DeviceSignals, InteractionSignals, ScreenshotSignals, SharedAdbClient,
-    use_automation_loop, use_device_loop, use_runtime_timer,
+    use_automation_loop, use_device_loop, use_mirror_loop, use_runtime_timer,
 };
+use crate::gui::preferences::{
+    GuiPreferences, WindowGeometry, preferences_path, save_window_geometry,
+};
+use crate::gui::status::ConnectionStatus;
+use crate::gui::theme::stylesheet;
 use crate::gui::util::calculate_device_coords;
 use dioxus::html::geometry::ElementPoint;
 use dioxus::prelude::*;
-use std::sync::{Arc, OnceLock};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 use tokio::sync::Mutex;
 
 const APP_VERSION: &str = env!("APP_VERSION_DISPLAY");
 const BUILD_YEAR: &str = env!("APP_BUILD_YEAR");
 
-// Global state to store the debug mode choice
-static DEBUG_MODE: OnceLock<bool> = OnceLock::new();
+// Global state to store the debug mode choice, switchable in ⚙️ Settings
+static DEBUG_MODE: AtomicBool = AtomicBool::new(false);
 
 pub fn is_debug_mode() -> bool {
-    *DEBUG_MODE.get().unwrap_or(&false)
+    DEBUG_MODE.load(Ordering::Relaxed)
+}
+
+pub fn set_debug_mode(enabled: bool) {
+    DEBUG_MODE.store(enabled, Ordering::Relaxed);
 }
 
 /// Grouped application context - reduces signal sprawl
@@ -36,6 +63,7 @@ pub struct AppContext {
     pub interaction: InteractionSignals,
     pub tap_markers: Signal<Vec<TapMarker>>,
     pub shared_adb_client: SharedAdbClient,
+    pub preferences: Signal<GuiPreferences>,
     pub calculate_device_coords: fn(ElementPoint, u32, u32) -> (u32, u32),
 }
 
@@ -61,9 +89,7 @@ fn ensure_gui_environment_inner() -> Result<(), String> {
 }
 
 pub fn run_gui(debug_mode: bool) {
-    DEBUG_MODE
-        .set(debug_mode)
-        .expect("DEBUG_MODE should only be set once");
+    set_debug_mode(debug_mode);
 
     if let Err(message) = ensure_gui_environment() {
         eprintln!("❌ {message}");
@@ -71,20 +97,26 @@ pub fn run_gui(debug_mode: bool) {
         return;
     }
 
-    use dioxus::desktop::{Config, WindowBuilder};
+    use dioxus::desktop::{Config, LogicalPosition, LogicalSize, WindowBuilder};
     let window_title = format!(
         "Android ADB Automation v{} (Build {})",
         APP_VERSION, BUILD_YEAR
     );
-    let config = Config::new()
-        .with_window(
-            WindowBuilder::new()
-                .with_title(window_title)
-                .with_decorations(true)
-                .with_resizable(true)
-                .with_inner_size(dioxus::desktop::LogicalSize::new(1000, 700)),
-        )
-        .with_menu(None);
+    let mut window = WindowBuilder::new()
+        .with_title(window_title)
+        .with_decorations(true)
+        .with_resizable(true)
+        .with_inner_size(LogicalSize::new(1000, 700));
+    // Reopen where the window was last closed
+    if let Some(geometry) = GuiPreferences::load(&preferences_path())
+        .window
+        .and_then(WindowGeometry::usable)
+    {
+        window = window
+            .with_inner_size(LogicalSize::new(geometry.width, geometry.height))
+            .with_position(LogicalPosition::new(geometry.x, geometry.y));
+    }
+    let config = Config::new().with_window(window).with_menu(None);
 
     dioxus::LaunchBuilder::desktop()
         .with_cfg(config)
@@ -93,6 +125,66 @@ pub fn run_gui(debug_mode: bool) {
 
 #[component]
 fn App() -> Element {
+    let preferences = use_signal(|| GuiPreferences::load(&preferences_path()));
+    // One connection per attached device, shared by every tab
+    let pool = use_signal(DevicePool::new);
+    let tabs = DeviceTabs {
+        pinned: use_signal(Vec::new),
+        active: use_signal(|| PRIMARY_TAB.to_string()),
+        summaries: use_signal(BTreeMap::new),
+        available: use_signal(Vec::new),
+        selected: use_signal(|| None::<String>),
+    };
+    use_context_provider(|| tabs);
+
+    dioxus::desktop::use_wry_event_handler(move |event, _| {
+        use dioxus::desktop::tao::event::{Event, WindowEvent};
+        if let Event::WindowEvent {
+            event: WindowEvent::CloseRequested,
+            ..
+        } = event
+        {
+            save_window_geometry(preferences);
+        }
+    });
+
+    let theme = preferences.read().theme.name();
+    let theme_css = stylesheet();
+    let active = tabs.active.read().clone();
+    let pinned = tabs.pinned.read().clone();
+
+    rsx! {
+        div { class: "app", "data-theme": theme,
+            style { dangerous_inner_html: "{theme_css}" }
+            div {
+                style: "flex:1; overflow:auto; padding:8px;",
+                DeviceTabBar {}
+                DeviceSession { key: "{PRIMARY_TAB}", pinned: None, visible: active == PRIMARY_TAB, pool, preferences }
+                for name in pinned {
+                    DeviceSession { key: "{name}", pinned: Some(name.clone()), visible: active == name, pool, preferences }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Props, PartialEq, Clone)]
+struct DeviceSessionProps {
+    /// Device of a tab opened for it; `None` for the first tab, which follows the picker
+    pinned: Option<String>,
+    /// Hidden tabs keep running
+    visible: bool,
+    pool: Signal<DevicePool>,
+    preferences: Signal<GuiPreferences>,
+}
+
+/// Everything for one device: its own connection, automation, signals and panels
+#[component]
+fn DeviceSession(props: DeviceSessionProps) -> Element {
+    let tabs = use_context::<DeviceTabs>();
+    let preferences = props.preferences;
+    let is_primary = props.pinned.is_none();
+
     // Create grouped signals for cleaner organization
     let screenshot = ScreenshotSignals {
         data: use_signal(|| None::<String>),
@@ -101,13 +193,35 @@ fn App() -> Element {
         status_history: use_signal(Vec::new),
         counter: use_signal(|| 0u64),
         is_loading: use_signal(|| false),
-        matched_patch: use_signal(|| None::<String>),
+        matches: use_signal(Vec::new),
+        mirroring: use_signal(|| false),
+        mirror_fps: use_signal(|| None::<f64>),
     };
 
+    // The first tab shares its picker with the tab bar and leaves pinned devices alone
+    let pinned_selection = props.pinned.clone();
+    let own_available = use_signal(Vec::new);
+    let own_selected = use_signal(|| pinned_selection);
+    let no_claims = use_signal(Vec::new);
+    let pinned_device = props.pinned.clone();
     let device = DeviceSignals {
         info: use_signal(|| None::<AutomationDeviceInfo>),
-        status: use_signal(|| "Initializing...".to_string()),
+        status: use_signal(ConnectionStatus::default),
         coords: use_signal(|| None::<(u32, u32)>),
+        available: if is_primary {
+            tabs.available
+        } else {
+            own_available
+        },
+        selected: if is_primary {
+            tabs.selected
+        } else {
+            own_selected
+        },
+        pool: props.pool,
+        telemetry: use_signal(|| None::<DeviceTelemetry>),
+        pinned: use_signal(|| pinned_device),
+        claimed: if is_primary { tabs.pinned } else { no_claims },
     };
 
     let automation = AutomationStateSignals {
@@ -117,6 +231,17 @@ fn App() -> Element {
         touch_timeout_remaining: use_signal(|| None::<u64>),
         timed_tap_countdown: use_signal(|| None::<(String, u64)>),
         timed_events_list: use_signal(Vec::<TimedEvent>::new),
+        health_pause: use_signal(|| None::<String>),
+        recovery: use_signal(|| None::<RecoveryAttempt>),
+        sequence_progress: use_signal(|| None::<SequenceProgress>),
+        stats: use_signal(StatsSummary::default),
+        custom_state: use_signal(|| None::<String>),
+        orientation: use_signal(|| None::<OrientationStatus>),
+        sleeping_until: use_signal(|| None::<String>),
+        stuck: use_signal(|| None::<StuckDetection>),
+        counters: use_signal(CounterReadings::default),
+        template_test: use_signal(|| None),
+        config: use_signal(active_config),
     };
 
     let interaction = InteractionSignals {
@@ -126,9 +251,15 @@ fn App() -> Element {
         is_swiping: use_signal(|| false),
         swipe_start: use_signal(|| None::<(u32, u32)>),
         swipe_end: use_signal(|| None::<(u32, u32)>),
+        swipe_path: use_signal(Vec::<ElementPoint>::new),
+        swipe_started_at: use_signal(|| None::<Instant>),
         selection_start: use_signal(|| None::<ElementPoint>),
         selection_end: use_signal(|| None::<ElementPoint>),
+        selected_region: use_signal(|| None::<(u32, u32, u32, u32)>),
         hover_tap_preview: use_signal(|| None::<(u32, u32)>),
+        picking_point: use_signal(|| false),
+        picked_point: use_signal(|| None::<(u32, u32)>),
+        editing_event: use_signal(|| None::<TimedEvent>),
     };
 
     let shared_adb_client = use_signal(|| None::<Arc<Mutex<AdbBackend>>>);
@@ -139,6 +270,7 @@ fn App() -> Element {
     // Initialize hooks for background tasks with grouped signals
     use_runtime_timer(runtime_days);
     use_device_loop(screenshot, device, shared_adb_client, force_update);
+    use_mirror_loop(screenshot, device.selected, shared_adb_client);
     use_automation_loop(
         is_debug_mode(),
         screenshot,
@@ -154,71 +286,83 @@ fn App() -> Element {
         interaction,
         tap_markers,
         shared_adb_client,
+        preferences,
         calculate_device_coords,
     });
 
+    // Keep this tab's entry in the tab bar current
+    let tab_key = props
+        .pinned
+        .clone()
+        .unwrap_or_else(|| PRIMARY_TAB.to_string());
+    let mut summaries = tabs.summaries;
+    let summary_key = tab_key.clone();
+    use_effect(move || {
+        let summary = TabSummary {
+            device: device.info.read().as_ref().map(|info| info.name.clone()),
+            state: automation.state.read().clone(),
+            connected: device.status.read().is_connected(),
+        };
+        summaries.write().insert(summary_key.clone(), summary);
+    });
+    use_drop(move || {
+        summaries.write().remove(&tab_key);
+    });
+
     let current_status = device.status.read().clone();
     let _update_trigger = force_update.read();
-    let (status_label, status_style) = if current_status.contains("Connected") {
-        (
-            "Connected",
-            "background: #1f5130; color: #48ff9b; border: 1px solid #48ff9b; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; letter-spacing: 0.5px; font-weight: 600;",
-        )
-    } else if current_status.contains("Error") {
-        (
-            "Error",
-            "background: #5a1f1f; color: #ff6262; border: 1px solid #ff6262; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; letter-spacing: 0.5px; font-weight: 600;",
-        )
-    } else {
-        (
-            current_status.as_str(),
-            "background: #5a4b1f; color: #ffd857; border: 1px solid #ffd857; padding: 4px 10px; border-radius: 16px; font-size: 0.8em; letter-spacing: 0.5px; font-weight: 600;",
-        )
-    };
+    let status_label = current_status.badge_label();
+    let status_style = current_status.badge_style();
     let runtime_days_value = *runtime_days.read();
     let screenshot_status = screenshot.status;
+    let layout = preferences.read().layout.name();
+    let display = if props.visible { "" } else { "display:none;" };
 
     rsx! {
-        div {
-            style: "height:97vh; display:flex; flex-direction:column; background:linear-gradient(135deg,#667eea 0%,#764ba2 100%); color:white; box-sizing:border-box;",
-            div {
-                style: "flex:1; overflow:auto; padding:8px;",
-                div { style: "display:flex; gap:14px; align-items:flex-start;",
-                    div { style: "flex:1; min-width:0; display:flex; flex-direction:column; gap:10px;",
-                        if let Some(device_info) = device.info.read().clone() {
-                            DeviceInfo { name: device_info.name, transport_id: device_info.transport_id, screen_x: device_info.screen_x, screen_y: device_info.screen_y, status_style: status_style.to_string(), status_label: status_label.to_string(), runtime_days: runtime_days_value }
-                            Actions {}
-                        } else {
-                            div { style: "background:rgba(255,255,255,0.1); backdrop-filter:blur(10px); padding:20px; border-radius:15px; margin-bottom:20px; border:1px solid rgba(255,255,255,0.2);",
-                                h2 { style: "margin-top:0; color:#ffb347;", "⚠️ No Device Connected" }
-                                div { style: "background:rgba(0,0,0,0.3); padding:15px; border-radius:10px; margin:15px 0;",
-                                    p { style: "font-size:1.2em; margin:0; text-align:center; font-weight:600;", "{current_status}" }
-                                }
-                                if !screenshot_status.read().is_empty() {
-                                    div { style: "background:rgba(0,0,0,0.2); padding:12px; border-radius:8px; margin:10px 0;",
-                                        p { style: "font-size:1em; margin:0; text-align:center; color:#ffd857;", "{screenshot_status.read()}" }
-                                    }
-                                }
-                                p { style: "font-size:0.95em; margin:15px 0; text-align:center; color:rgba(255,255,255,0.7);",
-                                    "Connect your Android device via USB with ADB debugging enabled"
-                                }
-                                button {
-                                    style: "background:linear-gradient(45deg,#dc3545,#e74c3c); color:white; padding:15px 25px; border:none; border-radius:10px; cursor:pointer; font-size:1.1em; font-weight:bold; min-width:150px;",
-                                    onclick: move |_| {
-                                        tokio::spawn(async {
-                                            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                                            std::process::exit(0);
-                                        });
-                                    },
-                                    "🚪 Exit Application"
-                                }
+        div { class: "layout", "data-layout": layout, style: "{display}",
+            div { class: "layout-controls",
+                if let Some(device_info) = device.info.read().clone() {
+                    DeviceInfo { name: device_info.name, transport_id: device_info.transport_id, screen_x: device_info.screen_x, screen_y: device_info.screen_y, status_style: status_style.clone(), status_label: status_label.to_string(), runtime_days: runtime_days_value }
+                    TelemetryPanel {}
+                    TextInput {}
+                    ShellConsole {}
+                    Actions {}
+                    StatsPanel {}
+                    CountersPanel {}
+                    TemplatesPanel {}
+                    Settings {}
+                } else {
+                    div { class: "panel", style: "padding:20px; border-radius:15px; margin-bottom:20px;",
+                        h2 { style: "margin-top:0; color:#ffb347;", "⚠️ No Device Connected" }
+                        div { style: "background:rgba(0,0,0,0.3); padding:15px; border-radius:10px; margin:15px 0;",
+                            p { style: "font-size:1.2em; margin:0; text-align:center; font-weight:600;", "{current_status}" }
+                        }
+                        if !screenshot_status.read().is_empty() {
+                            div { style: "background:rgba(0,0,0,0.2); padding:12px; border-radius:8px; margin:10px 0;",
+                                p { style: "font-size:1em; margin:0; text-align:center; color:#ffd857;", "{screenshot_status.read()}" }
                             }
                         }
-                        div { style: "margin-top:4px; text-align:left; font-size:0.7em; opacity:0.75; letter-spacing:0.5px;", "Built with Rust 🦀 and Dioxus ⚛️" }
+                        p { style: "font-size:0.95em; margin:15px 0; text-align:center; color:rgba(255,255,255,0.7);",
+                            "Connect your Android device via USB with ADB debugging enabled"
+                        }
+                        button {
+                            style: "background:linear-gradient(45deg,#dc3545,#e74c3c); color:white; padding:15px 25px; border:none; border-radius:10px; cursor:pointer; font-size:1.1em; font-weight:bold; min-width:150px;",
+                            onclick: move |_| {
+                                save_window_geometry(preferences);
+                                tokio::spawn(async {
+                                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                                    std::process::exit(0);
+                                });
+                            },
+                            "🚪 Exit Application"
+                        }
                     }
-                    screenshot_panel {}
                 }
+                HistoryPanel {}
+                LogViewer {}
+                div { style: "margin-top:4px; text-align:left; font-size:0.7em; opacity:0.75; letter-spacing:0.5px;", "Built with Rust 🦀 and Dioxus ⚛️" }
             }
+            screenshot_panel {}
         }
     }
 }
@@ -0,0 +1,147 @@
+// gui/heatmap.rs
+// Where automation has tapped this session, binned into grid cells for the
+// screenshot panel overlay. Taps piling up in the wrong place, or off the
+// screen entirely, point at coordinates meant for another resolution or rotation.
+
+use crate::game_automation::history::{HistoryAction, HistoryEntry, Outcome};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Cell edge in device pixels, about a fingertip
+pub const HEATMAP_CELL_PX: u32 = 40;
+
+/// One grid cell with taps in it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeatCell {
+    /// Cell center in device pixels
+    pub x: u32,
+    pub y: u32,
+    pub taps: u32,
+    /// Taps relative to the busiest cell, 0.0 - 1.0
+    pub heat: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TapHeatmap {
+    /// History entries older than this (unix ms) belong to earlier sessions
+    since_ms: u64,
+    cells: HashMap<(u32, u32), u32>,
+    total: u32,
+}
+
+impl Default for TapHeatmap {
+    fn default() -> Self {
+        Self::since(now_ms())
+    }
+}
+
+impl TapHeatmap {
+    /// Count taps recorded from `since_ms` on
+    pub fn since(since_ms: u64) -> Self {
+        Self {
+            since_ms,
+            cells: HashMap::new(),
+            total: 0,
+        }
+    }
+
+    /// Count `entry` if it is a tap automation made this session
+    pub fn record_entry(&mut self, entry: &HistoryEntry) {
+        if entry.action == HistoryAction::Tap
+            && entry.outcome == Outcome::Done
+            && entry.unix_ms >= self.since_ms
+        {
+            self.record(entry.x, entry.y);
+        }
+    }
+
+    pub fn record(&mut self, x: u32, y: u32) {
+        *self
+            .cells
+            .entry((x / HEATMAP_CELL_PX, y / HEATMAP_CELL_PX))
+            .or_default() += 1;
+        self.total += 1;
+    }
+
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Every cell with taps, busiest last so it is drawn on top
+    pub fn cells(&self) -> Vec<HeatCell> {
+        let max = self.cells.values().copied().max().unwrap_or(1) as f32;
+        let mut cells: Vec<HeatCell> = self
+            .cells
+            .iter()
+            .map(|(&(col, row), &taps)| HeatCell {
+                x: col * HEATMAP_CELL_PX + HEATMAP_CELL_PX / 2,
+                y: row * HEATMAP_CELL_PX + HEATMAP_CELL_PX / 2,
+                taps,
+                heat: taps as f32 / max,
+            })
+            .collect();
+        cells.sort_by_key(|cell| (cell.taps, cell.y, cell.x));
+        cells
+    }
+
+    /// Taps in cells starting past the edge of a `width` x `height` screen
+    pub fn outside(&self, width: u32, height: u32) -> u32 {
+        self.cells
+            .iter()
+            .filter(|&(&(col, row), _)| {
+                col * HEATMAP_CELL_PX >= width || row * HEATMAP_CELL_PX >= height
+            })
+            .map(|(_, &taps)| taps)
+            .sum()
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_taps_bin_into_cells_and_flag_offscreen() {
+        let mut heatmap = TapHeatmap::since(0);
+        heatmap.record(350, 628);
+        heatmap.record(355, 630);
+        heatmap.record(120, 1250);
+        // Portrait coordinates on a landscape screen
+        heatmap.record(110, 1900);
+
+        let cells = heatmap.cells();
+        assert_eq!(heatmap.total(), 4);
+        assert_eq!(cells.len(), 3);
+        let hottest = cells.last().unwrap();
+        assert_eq!((hottest.x, hottest.y, hottest.taps), (340, 620, 2));
+        assert_eq!(hottest.heat, 1.0);
+        assert_eq!(cells[0].heat, 0.5);
+        assert_eq!(heatmap.outside(1080, 2400), 0);
+        assert_eq!(heatmap.outside(2400, 1080), 2);
+    }
+
+    #[test]
+    fn test_only_this_sessions_done_taps_count() {
+        let mut heatmap = TapHeatmap::since(1_000);
+        let mut tap = HistoryEntry::new(HistoryAction::Tap, "claim_1d_tap", (350, 628));
+        tap.unix_ms = 2_000;
+        heatmap.record_entry(&tap);
+
+        let mut earlier = tap.clone();
+        earlier.unix_ms = 500;
+        heatmap.record_entry(&earlier);
+        heatmap.record_entry(&tap.clone().blocked("limit"));
+        let mut swipe = tap.clone();
+        swipe.action = HistoryAction::Swipe;
+        heatmap.record_entry(&swipe);
+
+        assert_eq!(heatmap.total(), 1);
+    }
+}
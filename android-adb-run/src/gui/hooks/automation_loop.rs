@@ -1,4 +1,5 @@
-use crate::game_automation::types::AutomationSignals;
+use crate::adb::AdbClient;
+use crate::game_automation::types::{AdbClientHandle, AutomationSignals};
 use crate::game_automation::{AutomationCommand, GameAutomation};
 use crate::gui::hooks::types::*;
 use dioxus::prelude::*;
@@ -23,6 +24,7 @@ pub fn use_automation_loop(
             screenshot_bytes: screenshot.bytes,
             screenshot_status: screenshot.status,
             screenshot_status_history: screenshot.status_history,
+            screenshot_matches: screenshot.matches,
             automation_state: automation.state,
             is_paused_by_touch: automation.is_paused_by_touch,
             touch_timeout_remaining: automation.touch_timeout_remaining,
@@ -31,8 +33,19 @@ pub fn use_automation_loop(
             device_info: device.info,
             status: device.status,
             screenshot_counter: screenshot.counter,
+            device_telemetry: device.telemetry,
+            health_pause: automation.health_pause,
+            recovery: automation.recovery,
+            sequence_progress: automation.sequence_progress,
+            stats: automation.stats,
+            custom_state: automation.custom_state,
+            orientation: automation.orientation,
+            sleeping_until: automation.sleeping_until,
+            stuck: automation.stuck,
+            counters: automation.counters,
+            template_test: automation.template_test,
+            config: automation.config,
         };
-        let mut game_automation = GameAutomation::new(cmd_rx, debug_mode, signals);
 
         // Wait for shared client to be available
         let shared_client = loop {
@@ -42,7 +55,19 @@ pub fn use_automation_loop(
             }
         };
 
-        if let Err(e) = game_automation.set_shared_adb_client(shared_client).await {
+        // The first device decides the `[devices]` profile this tab runs
+        let selected = device.selected.read().clone();
+        let device_key = match selected {
+            Some(key) => key,
+            None => shared_client.lock().await.device_key().to_string(),
+        };
+        let mut game_automation =
+            GameAutomation::for_device(cmd_rx, debug_mode, signals, &device_key);
+
+        if let Err(e) = game_automation
+            .set_shared_adb_client(shared_client.clone())
+            .await
+        {
             log::error!("Failed to set shared automation ADB client: {}", e);
             return; // Don't start automation if client setup fails
         }
@@ -56,5 +81,20 @@ pub fn use_automation_loop(
 
         // Start automation run loop in background (AFTER client is set)
         let _automation_task = spawn(async move { game_automation.run().await });
+
+        // Follow the device picker: hand the newly active connection to the FSM
+        let mut active_client = shared_client;
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            let Some(client) = shared_adb_client.read().clone() else {
+                continue;
+            };
+            if !std::sync::Arc::ptr_eq(&client, &active_client) {
+                active_client = client.clone();
+                let _ = cmd_tx
+                    .send(AutomationCommand::SwitchDevice(AdbClientHandle(client)))
+                    .await;
+            }
+        }
     });
 }
@@ -1,19 +1,52 @@
-use crate::adb::AdbClient;
+use crate::adb::{AdbClient, HotplugEvent, hotplug};
 use crate::gui::hooks::types::SharedAdbClient;
+use crate::gui::status::{ConnectionEvent, ConnectionStatus, DisconnectReason};
 use dioxus::prelude::*;
 
+/// Wait until the active device is lost or the picker selects another device
 pub(super) async fn wait_for_disconnection(
-    monitor_shared_client: SharedAdbClient,
-    mut device_status: Signal<String>,
+    mut monitor_shared_client: SharedAdbClient,
+    mut device_status: Signal<ConnectionStatus>,
+    selected_device: Signal<Option<String>>,
+    device_name: &str,
 ) {
     let mut check_interval = tokio::time::interval(tokio::time::Duration::from_secs(3));
+    let mut hotplug_events = hotplug::subscribe();
+    // Hotplug names devices by `Device::key`, the USB serial when it could be read
+    let client = monitor_shared_client.read().clone();
+    let device_key = match client {
+        Some(client) => client.lock().await.device_key().to_string(),
+        None => device_name.to_string(),
+    };
 
     loop {
-        check_interval.tick().await;
+        tokio::select! {
+            _ = check_interval.tick() => {}
+            event = hotplug::next_event(hotplug_events.as_mut()) => {
+                if event == HotplugEvent::DeviceDetached(device_key.clone()) {
+                    log::info!("🔌 {} unplugged", device_name);
+                    monitor_shared_client.set(None);
+                    device_status.with_mut(|s| {
+                        s.apply(ConnectionEvent::Lost(DisconnectReason::ConnectionLost))
+                    });
+                    break;
+                }
+                continue;
+            }
+        }
+
+        if selected_device.read().as_deref() != Some(device_name) {
+            log::debug!(
+                "Device monitoring: selection changed away from {}",
+                device_name
+            );
+            break;
+        }
 
         if monitor_shared_client.read().is_none() {
             log::debug!("Device monitoring: Client cleared, device disconnected");
-            device_status.set("🔌 Device Disconnected - Searching for device...".to_string());
+            device_status
+                .with_mut(|s| s.apply(ConnectionEvent::Lost(DisconnectReason::ConnectionLost)));
             break;
         }
 
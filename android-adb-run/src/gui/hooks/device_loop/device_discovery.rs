@@ -1,49 +1,82 @@
-use crate::adb::{AdbBackend, AdbClient};
+use crate::adb::{AdbBackend, AdbClient, Device, DevicePool, hotplug};
 use crate::gui::hooks::types::*;
+use crate::gui::status::{ConnectionErrorKind, ConnectionEvent, ConnectionStatus};
 use dioxus::prelude::*;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex as TokioMutex;
 
-pub(super) type ErrorConfig = (Box<dyn Fn(&String) -> String>, &'static str, u32);
-
-pub(super) async fn discover_device_name(device_status: &mut Signal<String>) -> Option<String> {
-    device_status.set("🔍 Looking for devices...".to_string());
-    let devices = match AdbBackend::list_devices().await {
-        Ok(devices) if !devices.is_empty() => devices,
+pub(super) async fn discover_device_name(
+    pool: &DevicePool,
+    device: &mut DeviceSignals,
+) -> Option<String> {
+    let device_status = &mut device.status;
+    device_status.with_mut(|s| s.apply(ConnectionEvent::ScanStarted));
+    // Subscribed before listing, so a device plugged in meanwhile is not missed
+    let mut hotplug_events = hotplug::subscribe();
+    // A pinned tab only sees its own device, the others never see what a tab drives
+    let pinned = device.pinned.read().clone();
+    let claimed = device.claimed.read().clone();
+    let ours = |device: &Device| {
+        let key = device.key();
+        pinned.as_deref().is_none_or(|pinned| pinned == key)
+            && !claimed.iter().any(|claimed| claimed == key)
+    };
+    let devices = match pool.refresh().await {
+        Ok(mut devices) if devices.iter().any(&ours) => {
+            devices.retain(&ours);
+            devices
+        }
         Ok(_) => {
+            device.available.set(Vec::new());
             for seconds in (1..=5).rev() {
-                device_status.set(format!(
-                    "🔌 No Device Connected - Retrying in {}s...",
-                    seconds
-                ));
-                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                device_status
+                    .with_mut(|s| s.apply(ConnectionEvent::NoDeviceFound { retry_in: seconds }));
+                if hotplug::wait_for_attach(hotplug_events.as_mut(), Duration::from_secs(1)).await {
+                    break;
+                }
             }
             return None;
         }
         Err(e) => {
+            let kind = ConnectionErrorKind::Enumeration(e.to_string());
             for seconds in (1..=5).rev() {
-                device_status.set(format!("❌ Error: {} - Retrying in {}s...", e, seconds));
+                device_status.with_mut(|s| {
+                    s.apply(ConnectionEvent::Failed {
+                        kind: kind.clone(),
+                        retry_in: seconds,
+                    })
+                });
                 tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
             }
             return None;
         }
     };
 
-    let first_device = &devices[0];
-    device_status.set(format!("📱 Found device: {}", first_device.name));
-    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-    device_status.set(format!("🔌 Connecting to {}...", first_device.name));
+    // Keep the picker's choice while it is attached, otherwise fall back to the first device
+    let selected = device.selected.read().clone();
+    let chosen = selected
+        .filter(|key| devices.iter().any(|d| d.key() == key))
+        .unwrap_or_else(|| devices[0].key().to_string());
 
-    Some(first_device.name.clone())
+    device.available.set(devices);
+    device.selected.set(Some(chosen.clone()));
+    device
+        .status
+        .with_mut(|s| s.apply(ConnectionEvent::DeviceFound(chosen.clone())));
+
+    Some(chosen)
 }
 
 pub(super) async fn connect_device(
+    pool: &DevicePool,
     device_name: &str,
     device: &mut DeviceSignals,
     force_update: &mut Signal<u32>,
     shared_adb_client: &mut SharedAdbClient,
 ) -> Result<Arc<TokioMutex<AdbBackend>>, crate::adb::AdbError> {
-    let client = AdbBackend::new_with_device(device_name).await?;
+    let shared_client = pool.connect(device_name).await?;
+    let client = shared_client.lock().await;
 
     let (sx, sy) = client.screen_dimensions();
     device.info.set(Some(DeviceInfo {
@@ -52,12 +85,14 @@ pub(super) async fn connect_device(
         screen_x: sx,
         screen_y: sy,
     }));
-    device.status.set("✅ Connected".to_string());
+    device
+        .status
+        .with_mut(|s| s.apply(ConnectionEvent::ConnectSucceeded));
     force_update.with_mut(|v| *v = v.wrapping_add(1));
+    drop(client);
 
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
-    let shared_client = Arc::new(TokioMutex::new(client));
     shared_adb_client.set(Some(shared_client.clone()));
 
     Ok(shared_client)
@@ -65,45 +100,19 @@ pub(super) async fn connect_device(
 
 pub(super) async fn handle_connection_error(
     error: &crate::adb::AdbError,
-    device_status: &mut Signal<String>,
+    device_status: &mut Signal<ConnectionStatus>,
     screenshot_status: &mut Signal<String>,
 ) {
-    let (get_status, tip_msg, retry_secs): ErrorConfig = if error.is_resource_busy() {
-        (
-            Box::new(|_e| {
-                "⚠️ USB Already in Use - Close other ADB apps - Retrying in {}s...".to_string()
-            }),
-            "💡 Close other instances (VS Code, Android Studio, etc.)",
-            10u32,
-        )
-    } else if error.is_permission_denied() {
-        (
-            Box::new(|_e| {
-                "⚠️ Permission Denied - Check USB permissions - Retrying in {}s...".to_string()
-            }),
-            "💡 Run: sudo chmod 666 /dev/bus/usb/*/0*",
-            5u32,
-        )
-    } else if error.is_device_not_found() {
-        (
-            Box::new(|_e| {
-                "⚠️ No Device Found - Reconnect USB cable - Retrying in {}s...".to_string()
-            }),
-            "💡 Unplug and replug the USB cable",
-            5u32,
-        )
-    } else {
-        (
-            Box::new(|e: &String| format!("❌ Connection failed: {} - Retrying in {{}}s...", e)),
-            "⏳ Waiting for USB authorization...",
-            5u32,
-        )
-    };
+    let kind = ConnectionErrorKind::from_adb_error(error);
 
-    for seconds in (1..=retry_secs).rev() {
-        let msg = get_status(&error.to_string());
-        device_status.set(msg.replace("{}", &seconds.to_string()));
-        screenshot_status.set(tip_msg.to_string());
+    for seconds in (1..=kind.retry_seconds()).rev() {
+        device_status.with_mut(|s| {
+            s.apply(ConnectionEvent::Failed {
+                kind: kind.clone(),
+                retry_in: seconds,
+            })
+        });
+        screenshot_status.set(kind.tip().to_string());
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
     }
 }
@@ -1,7 +1,7 @@
 use super::template_matching_pipeline::{decode_screenshot_to_rgb, start_template_matching_phase};
 use crate::adb::AdbClient;
 use crate::gui::hooks::types::ScreenshotSignals;
-use crate::gui::util::base64_encode;
+use crate::gui::util::preview_data_url;
 use dioxus::prelude::WritableExt;
 use std::sync::Arc;
 use tokio::sync::Mutex as TokioMutex;
@@ -9,6 +9,7 @@ use tokio::sync::Mutex as TokioMutex;
 pub(super) fn spawn_initial_screenshot_task(
     mut screenshot: ScreenshotSignals,
     shared_client: Arc<TokioMutex<crate::adb::AdbBackend>>,
+    device_name: String,
 ) {
     dioxus::prelude::spawn(async move {
         screenshot.is_loading.set(true);
@@ -22,10 +23,9 @@ pub(super) fn spawn_initial_screenshot_task(
         match client_lock.screen_capture_bytes().await {
             Ok(bytes) => {
                 let bytes_clone = bytes.clone();
-                let (base64_string, rgb_image) = tokio::task::spawn_blocking(move || {
-                    let b64 = base64_encode(&bytes_clone);
+                let (preview, rgb_image) = tokio::task::spawn_blocking(move || {
                     let rgb = decode_screenshot_to_rgb(&bytes_clone).ok();
-                    (b64, rgb)
+                    (rgb.as_ref().map(preview_data_url).unwrap_or_default(), rgb)
                 })
                 .await
                 .unwrap_or_else(|_| ("Error: encoding failed".to_string(), None));
@@ -36,7 +36,7 @@ pub(super) fn spawn_initial_screenshot_task(
                     *c
                 });
 
-                screenshot.data.set(Some(base64_string));
+                screenshot.data.set(Some(preview));
                 screenshot.bytes.set(Some(bytes.clone()));
                 screenshot.status.set(format!(
                     "✅ Screenshot #{} displayed ({}ms) - Matching...",
@@ -47,11 +47,13 @@ pub(super) fn spawn_initial_screenshot_task(
                 let status_signal = screenshot.status;
                 let status_history_signal = screenshot.status_history;
                 start_template_matching_phase(
+                    &device_name,
                     bytes.clone(),
                     rgb_image,
                     counter_val as u32,
                     status_signal,
                     status_history_signal,
+                    screenshot.matches,
                 );
             }
             Err(e) => {
@@ -6,7 +6,9 @@ mod device_discovery;
 mod initial_screenshot;
 mod template_matching_pipeline;
 
-pub use template_matching_pipeline::{decode_screenshot_to_rgb, start_template_matching_phase};
+pub use template_matching_pipeline::{
+    cancel_matching_run, decode_screenshot_to_rgb, start_template_matching_phase,
+};
 
 pub fn use_device_loop(
     mut screenshot: ScreenshotSignals,
@@ -15,14 +17,17 @@ pub fn use_device_loop(
     mut force_update: Signal<u32>,
 ) {
     use_future(move || async move {
+        // Every attached device keeps its own connection; the picker chooses the active one
+        let pool = device.pool.read().clone();
         loop {
             let Some(device_name) =
-                device_discovery::discover_device_name(&mut device.status).await
+                device_discovery::discover_device_name(&pool, &mut device).await
             else {
                 continue;
             };
 
             match device_discovery::connect_device(
+                &pool,
                 &device_name,
                 &mut device,
                 &mut force_update,
@@ -31,12 +36,24 @@ pub fn use_device_loop(
             .await
             {
                 Ok(shared_client) => {
-                    initial_screenshot::spawn_initial_screenshot_task(screenshot, shared_client);
+                    initial_screenshot::spawn_initial_screenshot_task(
+                        screenshot,
+                        shared_client,
+                        device_name.clone(),
+                    );
 
-                    connection_monitor::wait_for_disconnection(shared_adb_client, device.status)
-                        .await;
+                    connection_monitor::wait_for_disconnection(
+                        shared_adb_client,
+                        device.status,
+                        device.selected,
+                        &device_name,
+                    )
+                    .await;
+                    // Matches still coming in belong to a screen this tab no longer shows
+                    cancel_matching_run(&device_name);
                 }
                 Err(e) => {
+                    pool.remove(&device_name).await;
                     device_discovery::handle_connection_error(
                         &e,
                         &mut device.status,
@@ -1,7 +1,45 @@
-use crate::template_matching::{PatchInfo, TemplateMatcher};
+use crate::game_automation::config::active_config;
+use crate::game_automation::types::MatchOverlay;
+use crate::template_matching::{CancelToken, SearchImage, TemplateMatcher, load_patches};
 use dioxus::prelude::*;
 use image::{ImageReader, RgbImage};
+use std::collections::BTreeMap;
 use std::io::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// How often a "still matching" line is posted while patches are running
+const STILL_MATCHING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Token of the latest matching run per device; a new screenshot cancels the run
+/// before it on the same device, and leaves the other tabs' runs alone
+static CURRENT_RUNS: Mutex<BTreeMap<String, CancelToken>> = Mutex::new(BTreeMap::new());
+
+/// Start a new matching run for `device`, cancelling its run still in flight
+fn supersede_current_run(device: &str) -> CancelToken {
+    let cancel = CancelToken::new();
+    let previous = CURRENT_RUNS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(device.to_string(), cancel.clone());
+    if let Some(previous) = previous {
+        previous.cancel();
+    }
+    cancel
+}
+
+/// Cancel `device`'s run in flight, once its screenshots are no longer shown
+pub fn cancel_matching_run(device: &str) {
+    let previous = CURRENT_RUNS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(device);
+    if let Some(previous) = previous {
+        previous.cancel();
+    }
+}
 
 fn add_history_message(
     history_signal: &mut Signal<Vec<(String, bool)>>,
@@ -37,15 +75,21 @@ fn add_history_message(
     history_signal.set(history);
 }
 
+/// Match the patches against a screenshot of `device` (its `Device::key`)
 pub fn start_template_matching_phase(
+    device: &str,
     bytes: Vec<u8>,
     rgb_image: Option<RgbImage>,
     screenshot_counter: u32,
     mut status_signal: Signal<String>,
     mut status_history_signal: Signal<Vec<(String, bool)>>,
+    mut matches_signal: Signal<Vec<MatchOverlay>>,
 ) {
+    let cancel = supersede_current_run(device);
     spawn(async move {
         log::info!("🚀 PHASE 3 STARTING - Template matching");
+        // Boxes from the previous screenshot no longer line up
+        matches_signal.set(Vec::new());
 
         let (tx, mut rx) = tokio::sync::mpsc::channel::<(String, bool)>(500);
 
@@ -55,9 +99,16 @@ pub fn start_template_matching_phase(
         add_history_message(&mut status_history_signal, init_msg, false, None, false);
 
         log::info!("🧵 Spawning blocking task for match_patches_blocking_with_progress");
+        let run_cancel = cancel.clone();
         let mut result_handle = tokio::task::spawn_blocking(move || {
             log::info!("🔧 Inside spawn_blocking - calling match_patches_blocking_with_progress");
-            match_patches_blocking_with_progress(&bytes, rgb_image, screenshot_counter, tx)
+            match_patches_blocking_with_progress(
+                &bytes,
+                rgb_image,
+                screenshot_counter,
+                &run_cancel,
+                tx,
+            )
         });
 
         let mut result = None;
@@ -119,8 +170,16 @@ pub fn start_template_matching_phase(
             }
         }
 
-        let result = result.unwrap_or(Ok(None));
-        log::info!("✅ PHASE 3 Complete - Final result: {:?}", result);
+        if cancel.is_cancelled() {
+            log::debug!(
+                "⏹️ Dropping matches for superseded screenshot #{}",
+                screenshot_counter
+            );
+            return;
+        }
+        let overlays = result.and_then(Result::ok).unwrap_or_default();
+        log::info!("✅ PHASE 3 Complete - {} patch(es) matched", overlays.len());
+        matches_signal.set(overlays);
     });
 }
 
@@ -128,17 +187,19 @@ fn match_patches_blocking_with_progress(
     screenshot_bytes: &[u8],
     image_rgb: Option<RgbImage>,
     screenshot_counter: u32,
+    cancel: &CancelToken,
     tx: tokio::sync::mpsc::Sender<(String, bool)>,
-) -> Option<String> {
+) -> Vec<MatchOverlay> {
     let image_rgb = match image_rgb {
         Some(img) => img,
         None => match decode_screenshot_to_rgb(screenshot_bytes) {
             Ok(img) => img,
-            Err(_) => return None,
+            Err(_) => return Vec::new(),
         },
     };
 
-    let patch_dir = std::path::Path::new("assets/test_images");
+    let matching = active_config().matching;
+    let patch_dir = std::path::Path::new(&matching.patch_dir);
 
     if !patch_dir.exists() {
         log::debug!("Patch directory not found: {:?}", patch_dir);
@@ -146,74 +207,53 @@ fn match_patches_blocking_with_progress(
             format!("[#{}] ⚠️ Patch directory not found", screenshot_counter),
             false,
         ));
-        return None;
+        return Vec::new();
     }
 
-    let mut matcher = TemplateMatcher::new();
-    let mut patch_count = 0;
+    let mut matcher = TemplateMatcher::from_config(&matching.to_match_config(false));
 
     let _ = tx.blocking_send((
         format!("[#{}] 🔍 Scanning patches...", screenshot_counter),
         false,
     ));
 
-    match std::fs::read_dir(patch_dir) {
-        Ok(entries) => {
-            let entries_vec: Vec<_> = entries.flatten().collect();
-
-            for entry in &entries_vec {
-                let path = entry.path();
-                let filename = match path.file_name() {
-                    Some(name) => match name.to_str() {
-                        Some(s) => s.to_string(),
-                        None => continue,
-                    },
-                    None => continue,
-                };
-
-                if !filename.starts_with("patch-") || !filename.ends_with(".png") {
-                    continue;
-                }
-
-                if let Some((label, x, y, width, height)) = parse_patch_filename(&filename) {
-                    match std::fs::read(&path) {
-                        Ok(pixel_data) => match decode_screenshot_to_rgb(&pixel_data) {
-                            Ok(img) => {
-                                let pixels = img.into_raw();
-                                let patch = PatchInfo::new(label, x, y, width, height, pixels);
-                                matcher.add_patch(patch);
-                                patch_count += 1;
-                            }
-                            Err(_) => continue,
-                        },
-                        Err(_) => continue,
-                    }
-                }
+    let radius = |label: &str| {
+        matching
+            .templates
+            .get(label)
+            .and_then(|behavior| behavior.search_radius)
+    };
+    match load_patches(patch_dir, radius) {
+        Ok(patches) => {
+            for patch in patches {
+                matcher.add_patch(patch);
             }
-
-            if patch_count > 0 {
+            if !matcher.patches().is_empty() {
                 let msg = format!(
                     "[#{}] 📦 Loaded {} patches to match...",
-                    screenshot_counter, patch_count
+                    screenshot_counter,
+                    matcher.patches().len()
                 );
                 let _ = tx.blocking_send((msg, false));
             }
         }
-        Err(_) => {
+        Err(e) => {
+            log::debug!("{}", e);
             let _ = tx.blocking_send((
                 format!("[#{}] ⚠️ Failed to load patches", screenshot_counter),
                 false,
             ));
-            return None;
+            return Vec::new();
         }
     }
+    let patch_count = matcher.patches().len();
 
     if patch_count == 0 {
         let _ = tx.blocking_send((
             format!("[#{}] ⚠️ No patches found", screenshot_counter),
             false,
         ));
-        return None;
+        return Vec::new();
     }
 
     if let Err(e) = tx.blocking_send((
@@ -226,103 +266,144 @@ fn match_patches_blocking_with_progress(
         log::error!("❌ Failed to send 'Matching patches' message: {}", e);
     }
 
-    let threshold = 0.85;
+    let threshold = matching.patch_threshold as f32;
+    let search_image = SearchImage::new(&image_rgb);
+    let patches = matcher.patches();
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(patch_count);
+    // The GUI side closes the channel when it goes away
+    let cancelled = || cancel.is_cancelled() || tx.is_closed();
     let mut best_match: Option<(String, f32)> = None;
-
-    for (idx, patch) in matcher.patches().iter().enumerate() {
-        let patch_name = patch.display_name();
-
-        let progress_pct = ((idx + 1) as f32 / matcher.patches().len() as f32 * 100.0) as u32;
-        let msg = format!(
-            "[#{}] 🔎 Checking {}... ({}%)",
-            screenshot_counter, patch_name, progress_pct
-        );
-        let _ = tx.blocking_send((msg, false));
-
-        let start = std::time::Instant::now();
-        let total_patches = matcher.patches().len();
-        let patches_completed = idx;
-
-        let stop_flag = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
-        let stop_flag_clone = stop_flag.clone();
-
-        let tx_clone = tx.clone();
-        let patch_name_clone = patch_name.clone();
-        let screenshot_counter_clone = screenshot_counter;
-        let progress_handle = std::thread::spawn(move || {
-            let mut counter = 0;
-            loop {
-                std::thread::sleep(std::time::Duration::from_secs(30));
-
-                if stop_flag_clone.load(std::sync::atomic::Ordering::Relaxed) {
-                    break;
+    let mut overlays = Vec::new();
+
+    std::thread::scope(|scope| {
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let next_patch = AtomicUsize::new(0);
+        for _ in 0..workers {
+            let done_tx = done_tx.clone();
+            let (next_patch, matcher, image_rgb, tx) = (&next_patch, &matcher, &search_image, &tx);
+            scope.spawn(move || {
+                loop {
+                    if cancelled() {
+                        break;
+                    }
+                    let idx = next_patch.fetch_add(1, Ordering::Relaxed);
+                    let Some(patch) = patches.get(idx) else {
+                        break;
+                    };
+                    let _ = tx.blocking_send((
+                        format!(
+                            "[#{}] 🔎 Checking {}... ({}/{})",
+                            screenshot_counter,
+                            patch.display_name(),
+                            idx + 1,
+                            patch_count
+                        ),
+                        false,
+                    ));
+                    let start = Instant::now();
+                    let best = matcher
+                        .find_matches_in(
+                            search_image,
+                            idx,
+                            threshold,
+                            1,
+                            matching.patch_search_radius,
+                            cancel,
+                        )
+                        .into_iter()
+                        .next();
+                    if done_tx.send((idx, best, start.elapsed())).is_err() {
+                        break;
+                    }
                 }
+            });
+        }
+        drop(done_tx);
 
-                counter += 1;
-                let elapsed_secs = counter * 30;
-
-                let estimated_secs_per_patch = if patches_completed > 0 {
-                    elapsed_secs / patches_completed as u32
-                } else {
-                    60
-                };
-
-                let remaining_patches = (total_patches - patches_completed - 1) as u32;
-                let estimated_remaining = estimated_secs_per_patch * remaining_patches;
-
-                let msg = if estimated_remaining > 0 {
-                    format!(
-                        "[#{}] ⏳ Still matching {}... ({} sec, ~{} min remaining)",
-                        screenshot_counter_clone,
-                        patch_name_clone,
-                        elapsed_secs,
-                        estimated_remaining / 60
-                    )
-                } else {
-                    format!(
-                        "[#{}] ⏳ Still matching {}... ({} sec)",
-                        screenshot_counter_clone, patch_name_clone, elapsed_secs
-                    )
-                };
-
-                if tx_clone.blocking_send((msg, false)).is_err() {
-                    break;
+        // Workers finish out of order; results are reported in patch order
+        let started = Instant::now();
+        let mut finished = BTreeMap::new();
+        let mut next_report = 0;
+        loop {
+            match done_rx.recv_timeout(STILL_MATCHING_INTERVAL) {
+                Ok((idx, best, elapsed)) => {
+                    finished.insert(idx, (best, elapsed));
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let Some(waiting_on) = patches.get(next_report) else {
+                        continue;
+                    };
+                    if cancelled() {
+                        continue;
+                    }
+                    let _ = tx.blocking_send((
+                        format!(
+                            "[#{}] ⏳ Still matching {}... ({} sec, {}/{} done)",
+                            screenshot_counter,
+                            waiting_on.display_name(),
+                            started.elapsed().as_secs(),
+                            next_report + finished.len(),
+                            patch_count
+                        ),
+                        false,
+                    ));
+                    continue;
                 }
+                Err(RecvTimeoutError::Disconnected) => break,
             }
-        });
-
-        let matches = matcher.find_matches(&image_rgb, idx, threshold, 1, 50);
-        let elapsed = start.elapsed();
-
-        stop_flag.store(true, std::sync::atomic::Ordering::Relaxed);
-
-        let completion_msg = if let Some(m) = matches.first() {
-            let accuracy_pct = (m.correlation * 100.0) as u32;
-            format!(
-                "[#{}] ✓ Matched {} in {:.0}s ({}%)",
-                screenshot_counter,
-                patch_name,
-                elapsed.as_secs_f32(),
-                accuracy_pct
-            )
-        } else {
-            format!(
-                "[#{}] ✗ No match for {} ({:.0}s)",
-                screenshot_counter,
-                patch_name,
-                elapsed.as_secs_f32()
-            )
-        };
-        let _ = tx.blocking_send((completion_msg, true));
 
-        let _ = progress_handle.join();
+            while let Some((best, elapsed)) = finished.remove(&next_report) {
+                let patch = &patches[next_report];
+                let patch_name = patch.display_name();
+                next_report += 1;
+                let completion_msg = match &best {
+                    Some(m) => format!(
+                        "[#{}] ✓ Matched {} in {:.0}s ({}%)",
+                        screenshot_counter,
+                        patch_name,
+                        elapsed.as_secs_f32(),
+                        (m.correlation * 100.0) as u32
+                    ),
+                    None => format!(
+                        "[#{}] ✗ No match for {} ({:.0}s)",
+                        screenshot_counter,
+                        patch_name,
+                        elapsed.as_secs_f32()
+                    ),
+                };
+                if !cancelled() {
+                    let _ = tx.blocking_send((completion_msg, true));
+                }
 
-        if let Some(m) = matches.first() {
-            let patch_name = patch.display_name();
-            if best_match.is_none() || m.correlation > best_match.as_ref().unwrap().1 {
-                best_match = Some((patch_name, m.correlation));
+                if let Some(m) = best {
+                    overlays.push(MatchOverlay {
+                        label: patch_name.clone(),
+                        x: m.x,
+                        y: m.y,
+                        width: patch.width,
+                        height: patch.height,
+                        confidence: m.correlation,
+                        tap: None,
+                    });
+                    if best_match
+                        .as_ref()
+                        .is_none_or(|(_, best)| m.correlation > *best)
+                    {
+                        best_match = Some((patch_name, m.correlation));
+                    }
+                }
             }
         }
+    });
+
+    if cancelled() {
+        log::debug!(
+            "⏹️ Matching for screenshot #{} cancelled",
+            screenshot_counter
+        );
+        return Vec::new();
     }
 
     match &best_match {
@@ -348,7 +429,7 @@ fn match_patches_blocking_with_progress(
         }
     }
 
-    best_match.map(|(name, _)| name)
+    overlays
 }
 
 pub fn decode_screenshot_to_rgb(bytes: &[u8]) -> Result<RgbImage, String> {
@@ -364,43 +445,23 @@ pub fn decode_screenshot_to_rgb(bytes: &[u8]) -> Result<RgbImage, String> {
     Ok(rgb)
 }
 
-fn parse_patch_filename(filename: &str) -> Option<(Option<String>, u32, u32, u32, u32)> {
-    let name = filename.strip_suffix(".png")?;
-
-    if !name.starts_with("patch-") {
-        return None;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_run_only_cancels_its_own_device() {
+        let first_a = supersede_current_run("test-session-a");
+        let first_b = supersede_current_run("test-session-b");
+        let second_a = supersede_current_run("test-session-a");
+        assert!(first_a.is_cancelled());
+        assert!(!first_b.is_cancelled());
+        assert!(!second_a.is_cancelled());
+
+        cancel_matching_run("test-session-b");
+        assert!(first_b.is_cancelled());
+        assert!(!second_a.is_cancelled());
+        cancel_matching_run("test-session-a");
+        assert!(second_a.is_cancelled());
     }
-    let name = &name[6..];
-
-    let bracket_pos = name.rfind('[')?;
-    let label_part = &name[..bracket_pos];
-    let coords_part = &name[bracket_pos..];
-
-    if !coords_part.starts_with('[') || !coords_part.ends_with(']') {
-        return None;
-    }
-
-    let coords_str = &coords_part[1..coords_part.len() - 1];
-    let parts: Vec<&str> = coords_str.split(',').collect();
-    if parts.len() != 4 {
-        return None;
-    }
-
-    let x = parts[0].trim().parse::<u32>().ok()?;
-    let y = parts[1].trim().parse::<u32>().ok()?;
-    let width = parts[2].trim().parse::<u32>().ok()?;
-    let height = parts[3].trim().parse::<u32>().ok()?;
-
-    let label = if label_part.is_empty() {
-        None
-    } else {
-        let label_str = if let Some(stripped) = label_part.strip_suffix('-') {
-            stripped
-        } else {
-            label_part
-        };
-        Some(label_str.to_string())
-    };
-
-    Some((label, x, y, width, height))
 }
@@ -0,0 +1,107 @@
+use crate::adb::AdbClient;
+use crate::game_automation::config::active_config;
+use crate::gui::hooks::device_loop::{decode_screenshot_to_rgb, start_template_matching_phase};
+use crate::gui::hooks::types::{ScreenshotSignals, SharedAdbClient};
+use crate::gui::util::preview_data_url;
+use dioxus::prelude::*;
+use std::time::{Duration, Instant};
+
+/// How often a stopped mirror checks whether it was switched on
+const IDLE_POLL: Duration = Duration::from_millis(200);
+/// Weight of the newest frame in the measured frame rate
+const FPS_SMOOTHING: f64 = 0.2;
+
+/// Live screen mirroring: while `screenshot.mirroring` is on, grab frames back to
+/// back at the `[mirror]` target rate and show them as the current screenshot
+pub fn use_mirror_loop(
+    mut screenshot: ScreenshotSignals,
+    selected: Signal<Option<String>>,
+    shared_adb_client: SharedAdbClient,
+) {
+    use_future(move || async move {
+        let mut active = false;
+        let mut frame: u64 = 0;
+        loop {
+            let client = shared_adb_client.read().clone();
+            let Some(client) = client.filter(|_| *screenshot.mirroring.read()) else {
+                if active {
+                    log::info!("🎥 Live mirroring stopped after {} frames", frame);
+                    active = false;
+                    frame = 0;
+                    screenshot.mirror_fps.set(None);
+                }
+                tokio::time::sleep(IDLE_POLL).await;
+                continue;
+            };
+
+            let mirror = active_config().mirror;
+            if !active {
+                active = true;
+                log::info!(
+                    "🎥 Live mirroring started ({:.1} fps target)",
+                    1.0 / mirror.frame_interval().as_secs_f64()
+                );
+            }
+
+            let started = Instant::now();
+            // Leave the device to manual screenshots in flight
+            if *screenshot.is_loading.read() {
+                tokio::time::sleep(IDLE_POLL).await;
+                continue;
+            }
+            let captured = client.lock().await.screen_capture_bytes().await;
+            match captured {
+                Ok(bytes) => {
+                    frame += 1;
+                    let analyze = mirror.analyzes(frame);
+                    let encode_bytes = bytes.clone();
+                    let (preview, rgb_image) = tokio::task::spawn_blocking(move || {
+                        // One decode feeds both the preview and (when analyzing) matching
+                        let rgb = decode_screenshot_to_rgb(&encode_bytes).ok();
+                        let preview = rgb.as_ref().map(preview_data_url).unwrap_or_default();
+                        (preview, rgb.filter(|_| analyze))
+                    })
+                    .await
+                    .unwrap_or_else(|_| (String::new(), None));
+
+                    let counter = screenshot.counter.with_mut(|c| {
+                        *c += 1;
+                        *c
+                    });
+                    screenshot.data.set(Some(preview));
+                    screenshot.bytes.set(Some(bytes.clone()));
+                    if analyze {
+                        let device = selected.read().clone().unwrap_or_default();
+                        start_template_matching_phase(
+                            &device,
+                            bytes,
+                            rgb_image,
+                            counter as u32,
+                            screenshot.status,
+                            screenshot.status_history,
+                            screenshot.matches,
+                        );
+                    }
+                }
+                Err(e) => {
+                    log::warn!("⚠️ Live frame failed: {}", e);
+                    screenshot
+                        .status
+                        .set(format!("❌ Live frame failed: {}", e));
+                }
+            }
+
+            let budget = mirror.frame_interval();
+            let elapsed = started.elapsed();
+            if elapsed < budget {
+                tokio::time::sleep(budget - elapsed).await;
+            }
+            let fps = 1.0 / started.elapsed().as_secs_f64().max(f64::EPSILON);
+            let smoothed = screenshot
+                .mirror_fps
+                .peek()
+                .map_or(fps, |prev| prev + FPS_SMOOTHING * (fps - prev));
+            screenshot.mirror_fps.set(Some(smoothed));
+        }
+    });
+}
@@ -1,9 +1,11 @@
 pub mod automation_loop;
 pub mod device_loop;
+pub mod mirror_loop;
 pub mod runtime_timer;
 pub mod types;
 
 pub use automation_loop::use_automation_loop;
 pub use device_loop::{start_template_matching_phase, use_device_loop};
+pub use mirror_loop::use_mirror_loop;
 pub use runtime_timer::use_runtime_timer;
 pub use types::*;
@@ -1,8 +1,15 @@
-use crate::adb::AdbBackend;
+use crate::adb::{AdbBackend, Device, DevicePool, DeviceTelemetry};
 use crate::game_automation::AutomationCommand;
 use crate::game_automation::GameState;
+use crate::game_automation::config::AutomationConfig;
+use crate::game_automation::counters::CounterReadings;
+use crate::game_automation::match_image::TemplateTest;
+use crate::game_automation::sequence::SequenceProgress;
+use crate::game_automation::stats::StatsSummary;
 pub use crate::game_automation::types::DeviceInfo;
-use crate::game_automation::types::TimedEvent;
+use crate::game_automation::types::{MatchOverlay, OrientationStatus, RecoveryAttempt, TimedEvent};
+use crate::game_automation::watchdog::StuckDetection;
+use crate::gui::status::ConnectionStatus;
 use dioxus::prelude::Signal;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -20,21 +27,29 @@ pub type CommandTxSignal = Signal<Option<tokio::sync::mpsc::Sender<AutomationCom
 /// Screenshot-related signals grouped together
 #[derive(Clone, Copy)]
 pub struct ScreenshotSignals {
-    pub data: Signal<Option<String>>,   // Base64 encoded for display
+    pub data: Signal<Option<String>>, // Downscaled preview data: URL for display
     pub bytes: Signal<Option<Vec<u8>>>, // Raw bytes for processing
-    pub status: Signal<String>,         // Status message
+    pub status: Signal<String>,       // Status message
     pub status_history: Signal<Vec<(String, bool)>>, // Status message history with flag: (message, is_result)
     pub counter: Signal<u64>,                        // Screenshot counter
     pub is_loading: Signal<bool>,                    // Loading indicator
-    pub matched_patch: Signal<Option<String>>,       // Latest matched patch name
+    pub matches: Signal<Vec<MatchOverlay>>,          // Matches outlined on the screenshot
+    pub mirroring: Signal<bool>,                     // Live mirroring on/off
+    pub mirror_fps: Signal<Option<f64>>,             // Measured live mirroring rate
 }
 
 /// Device connection signals grouped together
 #[derive(Clone, Copy)]
 pub struct DeviceSignals {
     pub info: Signal<Option<DeviceInfo>>,   // Device metadata
-    pub status: Signal<String>,             // Connection status
+    pub status: Signal<ConnectionStatus>,   // Connection status
     pub coords: Signal<Option<(u32, u32)>>, // Current device coordinates
+    pub available: Signal<Vec<Device>>,     // All attached devices (picker options)
+    pub selected: Signal<Option<String>>,   // Device name chosen in the picker
+    pub pool: Signal<DevicePool>,           // Connections to every attached device
+    pub telemetry: Signal<Option<DeviceTelemetry>>, // Latest battery/thermal/memory sample
+    pub pinned: Signal<Option<String>>, // Device this tab is fixed to; the first tab follows the picker
+    pub claimed: Signal<Vec<String>>,   // Devices other tabs drive, never picked here
 }
 
 /// Automation state signals grouped together
@@ -46,6 +61,17 @@ pub struct AutomationStateSignals {
     pub touch_timeout_remaining: Signal<Option<u64>>, // Seconds until resume
     pub timed_tap_countdown: Signal<Option<(String, u64)>>, // Current countdown
     pub timed_events_list: Signal<Vec<TimedEvent>>,   // All timed events
+    pub health_pause: Signal<Option<String>>,         // Guardrail that paused automation
+    pub recovery: Signal<Option<RecoveryAttempt>>,    // Game relaunch in progress
+    pub sequence_progress: Signal<Option<SequenceProgress>>, // Running action sequence
+    pub stats: Signal<StatsSummary>,                  // Taps, match rates, latency, runs
+    pub custom_state: Signal<Option<String>>,         // User-defined game state from [states]
+    pub orientation: Signal<Option<OrientationStatus>>, // Device rotation vs [orientation]
+    pub sleeping_until: Signal<Option<String>>,       // End of [quiet_hours] sleep (HH:MM)
+    pub stuck: Signal<Option<StuckDetection>>,        // Latest [watchdog] stuck detection
+    pub counters: Signal<CounterReadings>,            // [counters] OCR readings over time
+    pub template_test: Signal<Option<TemplateTest>>,  // Latest single-template test
+    pub config: Signal<AutomationConfig>,             // Config the tab's automation runs
 }
 
 /// User interaction signals grouped together
@@ -57,7 +83,13 @@ pub struct InteractionSignals {
     pub is_swiping: Signal<bool>,
     pub swipe_start: Signal<Option<(u32, u32)>>,
     pub swipe_end: Signal<Option<(u32, u32)>>,
+    pub swipe_path: Signal<Vec<dioxus::html::geometry::ElementPoint>>, // Drag path on the screenshot
+    pub swipe_started_at: Signal<Option<std::time::Instant>>,
     pub selection_start: Signal<Option<dioxus::html::geometry::ElementPoint>>,
     pub selection_end: Signal<Option<dioxus::html::geometry::ElementPoint>>,
+    pub selected_region: Signal<Option<(u32, u32, u32, u32)>>, // Last box in device coords (x, y, w, h)
     pub hover_tap_preview: Signal<Option<(u32, u32)>>,
+    pub picking_point: Signal<bool>, // Next screenshot click picks a point instead of tapping
+    pub picked_point: Signal<Option<(u32, u32)>>, // Device coords chosen while picking
+    pub editing_event: Signal<Option<TimedEvent>>, // Timed event loaded into the editor form
 }
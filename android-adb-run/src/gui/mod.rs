@@ -5,11 +5,30 @@ pub mod hooks;
 pub mod util;
 pub mod components {
     pub mod actions;
+    pub mod counters_panel;
     pub mod device_info;
+    pub mod device_picker;
+    pub mod device_tabs;
     pub mod header;
+    pub mod history_panel;
+    pub mod log_viewer;
+    pub mod profile_picker;
     pub mod screenshot_panel; // new panel for interaction status & coords
+    pub mod settings;
+    pub mod shell_console;
+    pub mod stats_panel;
+    pub mod telemetry_panel;
+    pub mod template_editor;
+    pub mod templates_panel;
+    pub mod text_input;
+    pub mod timed_event_editor;
 }
 pub mod dioxus_app; // renamed from dioxus
+pub mod heatmap;
+pub mod preferences;
+pub mod screen_diff;
+pub mod status;
+pub mod theme;
 pub use dioxus_app::run_gui;
 
 // Optionally, re-export common GUI types/functions
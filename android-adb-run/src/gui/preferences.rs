@@ -0,0 +1,175 @@
+// gui/preferences.rs
+// GUI choices kept across restarts: theme, layout, which collapsible panels are
+// open, and the window size and position. Saved as JSON next to automation.toml
+// whenever one changes, and the window geometry when the window closes.
+use crate::game_automation::config::{DEFAULT_PROFILE, profile_path};
+use crate::gui::dioxus_app::AppContext;
+use crate::gui::theme::{Layout, Theme};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+pub const GUI_PREFERENCES_FILE: &str = "gui_preferences.json";
+
+/// Smallest window restored, so a bad save cannot hide the app
+const MIN_WINDOW_SIDE: f64 = 320.0;
+
+/// Window size and position in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: f64,
+    pub height: f64,
+    pub x: f64,
+    pub y: f64,
+}
+
+impl WindowGeometry {
+    /// `None` for sizes too small to use
+    pub fn usable(self) -> Option<Self> {
+        (self.width >= MIN_WINDOW_SIDE && self.height >= MIN_WINDOW_SIDE).then_some(self)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuiPreferences {
+    pub theme: Theme,
+    pub layout: Layout,
+    /// Collapsible panels left open, by panel name
+    pub open_panels: BTreeSet<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowGeometry>,
+}
+
+/// `gui_preferences.json` beside the default profile's config
+pub fn preferences_path() -> PathBuf {
+    profile_path(DEFAULT_PROFILE).with_file_name(GUI_PREFERENCES_FILE)
+}
+
+impl GuiPreferences {
+    /// Saved preferences, defaults when there are none or they cannot be read
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("⚠️ Ignoring {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to encode GUI preferences: {}", e))?;
+        std::fs::write(path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn is_open(&self, panel: &str) -> bool {
+        self.open_panels.contains(panel)
+    }
+
+    pub fn set_open(&mut self, panel: &str, open: bool) {
+        if open {
+            self.open_panels.insert(panel.to_string());
+        } else {
+            self.open_panels.remove(panel);
+        }
+    }
+}
+
+/// Change the GUI preferences and save them right away
+pub fn update_preferences(
+    mut preferences: Signal<GuiPreferences>,
+    change: impl FnOnce(&mut GuiPreferences),
+) {
+    let mut current = preferences.write();
+    change(&mut current);
+    if let Err(e) = current.save(&preferences_path()) {
+        log::warn!("⚠️ GUI preferences not saved: {}", e);
+    }
+}
+
+/// Open/closed state of a collapsible panel, remembered across restarts
+#[derive(Clone, Copy)]
+pub struct PanelOpen {
+    preferences: Signal<GuiPreferences>,
+    panel: &'static str,
+}
+
+impl PanelOpen {
+    pub fn get(&self) -> bool {
+        self.preferences.read().is_open(self.panel)
+    }
+
+    pub fn set(&self, open: bool) {
+        update_preferences(self.preferences, |prefs| prefs.set_open(self.panel, open));
+    }
+}
+
+/// Open state of the collapsible panel named `panel`
+pub fn use_panel_open(panel: &'static str) -> PanelOpen {
+    let preferences = use_context::<AppContext>().preferences;
+    PanelOpen { preferences, panel }
+}
+
+/// Remember where the main window is and how big, for the next start
+pub fn save_window_geometry(preferences: Signal<GuiPreferences>) {
+    let window = dioxus::desktop::window();
+    let scale = window.scale_factor();
+    let size = window.inner_size().to_logical::<f64>(scale);
+    let Ok(position) = window.outer_position() else {
+        return;
+    };
+    let position = position.to_logical::<f64>(scale);
+    update_preferences(preferences, |prefs| {
+        prefs.window = Some(WindowGeometry {
+            width: size.width,
+            height: size.height,
+            x: position.x,
+            y: position.y,
+        })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preferences_round_trip() {
+        let mut prefs = GuiPreferences {
+            theme: Theme::Light,
+            layout: Layout::Stacked,
+            window: Some(WindowGeometry {
+                width: 800.0,
+                height: 600.0,
+                x: 40.0,
+                y: 20.0,
+            }),
+            ..Default::default()
+        };
+        prefs.set_open("logs", true);
+        prefs.set_open("settings", true);
+        prefs.set_open("settings", false);
+
+        let json = serde_json::to_string(&prefs).unwrap();
+        assert!(json.contains(r#""theme":"light""#));
+        let loaded: GuiPreferences = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, prefs);
+        assert!(loaded.is_open("logs") && !loaded.is_open("settings"));
+
+        // Older or partial files fall back per field
+        let partial: GuiPreferences = serde_json::from_str(r#"{"layout":"columns"}"#).unwrap();
+        assert_eq!(
+            (partial.theme, partial.layout),
+            (Theme::Dark, Layout::Columns)
+        );
+        let tiny = WindowGeometry {
+            width: 10.0,
+            ..prefs.window.unwrap()
+        };
+        assert_eq!(tiny.usable(), None);
+    }
+}
@@ -0,0 +1,157 @@
+// gui/screen_diff.rs
+// Difference between a pinned reference screenshot and the live one, for the
+// screenshot panel A/B view: which pixels change when, say, a button turns from
+// disabled to active. Changed pixels are binned into grid cells and touching
+// cells merged into boxes.
+
+use image::RgbImage;
+
+/// Cell edge in device pixels that changed pixels are grouped by
+pub const DIFF_CELL_PX: u32 = 16;
+/// Largest per-channel difference still counted as unchanged (compression noise)
+pub const DIFF_PIXEL_TOLERANCE: u8 = 24;
+/// Cells with fewer changed pixels are left out of the boxes as noise
+pub const DIFF_MIN_CELL_PIXELS: u32 = 4;
+
+/// A box of changed screen in device pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Changed pixels inside the box
+    pub changed: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScreenDiff {
+    pub changed_pixels: u64,
+    pub total_pixels: u64,
+    /// Most changed first
+    pub regions: Vec<DiffRegion>,
+}
+
+impl ScreenDiff {
+    /// Compare two screenshots of the same size
+    pub fn compare(reference: &RgbImage, live: &RgbImage) -> Result<Self, String> {
+        if reference.dimensions() != live.dimensions() {
+            return Err(format!(
+                "Reference is {}x{} but the screenshot is {}x{} - pin a new reference",
+                reference.width(),
+                reference.height(),
+                live.width(),
+                live.height()
+            ));
+        }
+        let (width, height) = live.dimensions();
+        let cols = width.div_ceil(DIFF_CELL_PX);
+        let rows = height.div_ceil(DIFF_CELL_PX);
+        let mut cells = vec![0u32; (cols * rows) as usize];
+        let mut changed_pixels = 0;
+        for ((x, y, before), after) in reference.enumerate_pixels().zip(live.pixels()) {
+            let changed = before
+                .0
+                .iter()
+                .zip(after.0)
+                .any(|(&a, b)| a.abs_diff(b) > DIFF_PIXEL_TOLERANCE);
+            if changed {
+                cells[((y / DIFF_CELL_PX) * cols + x / DIFF_CELL_PX) as usize] += 1;
+                changed_pixels += 1;
+            }
+        }
+
+        // Touching cells, diagonals included, make one box
+        let counts = |cell: usize| cells[cell] >= DIFF_MIN_CELL_PIXELS;
+        let mut seen = vec![false; cells.len()];
+        let mut regions = Vec::new();
+        for start in 0..cells.len() {
+            if seen[start] || !counts(start) {
+                continue;
+            }
+            seen[start] = true;
+            let mut stack = vec![start];
+            let (mut left, mut top, mut right, mut bottom) = (u32::MAX, u32::MAX, 0, 0);
+            let mut changed = 0;
+            while let Some(cell) = stack.pop() {
+                let (col, row) = (cell as u32 % cols, cell as u32 / cols);
+                left = left.min(col);
+                top = top.min(row);
+                right = right.max(col);
+                bottom = bottom.max(row);
+                changed += cells[cell];
+                for next_row in row.saturating_sub(1)..=(row + 1).min(rows - 1) {
+                    for next_col in col.saturating_sub(1)..=(col + 1).min(cols - 1) {
+                        let next = (next_row * cols + next_col) as usize;
+                        if !seen[next] && counts(next) {
+                            seen[next] = true;
+                            stack.push(next);
+                        }
+                    }
+                }
+            }
+            let x = left * DIFF_CELL_PX;
+            let y = top * DIFF_CELL_PX;
+            regions.push(DiffRegion {
+                x,
+                y,
+                width: ((right + 1) * DIFF_CELL_PX).min(width) - x,
+                height: ((bottom + 1) * DIFF_CELL_PX).min(height) - y,
+                changed,
+            });
+        }
+        regions.sort_by(|a, b| b.changed.cmp(&a.changed));
+
+        Ok(Self {
+            changed_pixels,
+            total_pixels: width as u64 * height as u64,
+            regions,
+        })
+    }
+
+    /// Share of pixels (0.0 - 1.0) that changed
+    pub fn changed_fraction(&self) -> f64 {
+        if self.total_pixels == 0 {
+            0.0
+        } else {
+            self.changed_pixels as f64 / self.total_pixels as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_pixels_group_into_boxes() {
+        let reference = RgbImage::from_pixel(100, 60, image::Rgb([40, 40, 40]));
+        let mut live = reference.clone();
+        // A button lighting up across two cells, a single noisy pixel and a faint shift
+        for x in 10..40 {
+            for y in 20..28 {
+                live.put_pixel(x, y, image::Rgb([40, 200, 40]));
+            }
+        }
+        live.put_pixel(90, 50, image::Rgb([255, 255, 255]));
+        live.put_pixel(70, 5, image::Rgb([50, 50, 50]));
+
+        let diff = ScreenDiff::compare(&reference, &live).unwrap();
+        assert_eq!(diff.changed_pixels, 30 * 8 + 1);
+        assert_eq!(
+            diff.regions,
+            vec![DiffRegion {
+                x: 0,
+                y: 16,
+                width: 48,
+                height: 16,
+                changed: 240
+            }]
+        );
+        assert!(diff.changed_fraction() > 0.04 && diff.changed_fraction() < 0.05);
+
+        let unchanged = ScreenDiff::compare(&reference, &reference).unwrap();
+        assert!(unchanged.regions.is_empty());
+        assert!(ScreenDiff::compare(&reference, &RgbImage::new(60, 100)).is_err());
+    }
+}
@@ -0,0 +1,318 @@
+// gui/status.rs
+// Structured connection status shown in the header/device panels.
+//
+// All user-facing status strings are generated here so the device loop and
+// the automation FSM only ever describe *what* happened, never how to word it.
+
+use crate::adb::{AdbError, AdbErrorKind};
+
+const BADGE_BASE_STYLE: &str = "padding: 4px 10px; border-radius: 16px; font-size: 0.8em; letter-spacing: 0.5px; font-weight: 600;";
+
+/// Why a previously connected device is no longer usable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// Connection monitor noticed the shared client was released
+    ConnectionLost,
+    /// Automation hit a USB error and paused itself until reconnection
+    AutomationPaused,
+}
+
+/// Classified connection failure, from `AdbError::kind`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionErrorKind {
+    ResourceBusy,
+    PermissionDenied,
+    DeviceNotFound,
+    /// Listing devices failed before any connection was attempted
+    Enumeration(String),
+    /// The phone shows "Allow USB debugging?" and waits for the user
+    AuthorizationPending,
+    /// Anything else, often an authorization the handshake did not report
+    Other(String),
+}
+
+impl ConnectionErrorKind {
+    pub fn from_adb_error(error: &AdbError) -> Self {
+        match error.kind() {
+            AdbErrorKind::Busy => Self::ResourceBusy,
+            AdbErrorKind::PermissionDenied => Self::PermissionDenied,
+            AdbErrorKind::Disconnected => Self::DeviceNotFound,
+            AdbErrorKind::Unauthorized => Self::AuthorizationPending,
+            _ => Self::Other(error.to_string()),
+        }
+    }
+
+    /// Seconds to wait before the device loop retries
+    pub fn retry_seconds(&self) -> u32 {
+        match self {
+            Self::ResourceBusy => 10,
+            _ => 5,
+        }
+    }
+
+    /// Hint shown below the status while waiting for the retry
+    pub fn tip(&self) -> &'static str {
+        match self {
+            Self::ResourceBusy => "💡 Close other instances (VS Code, Android Studio, etc.)",
+            Self::PermissionDenied => "💡 Run: sudo chmod 666 /dev/bus/usb/*/0*",
+            Self::DeviceNotFound => "💡 Unplug and replug the USB cable",
+            Self::Enumeration(_) => "💡 Check the USB cable and that ADB debugging is enabled",
+            Self::AuthorizationPending => {
+                "📱 Unlock the phone and tap Allow on \"Allow USB debugging?\" (tick Always allow)"
+            }
+            Self::Other(_) => "⏳ Waiting for USB authorization...",
+        }
+    }
+}
+
+/// Connection state driving the header/device panels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// Searching for devices; `retry_in` counts down after an empty scan
+    Discovering {
+        retry_in: Option<u32>,
+    },
+    Connecting {
+        device: String,
+    },
+    Connected {
+        reconnected: bool,
+    },
+    Disconnected(DisconnectReason),
+    /// The automation is retrying a lost device, `attempt` counts from 1
+    Reconnecting {
+        attempt: u32,
+        retry_in: u32,
+    },
+    Error {
+        kind: ConnectionErrorKind,
+        retry_in: u32,
+    },
+}
+
+/// Inputs that move the connection status from one state to the next
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    ScanStarted,
+    NoDeviceFound {
+        retry_in: u32,
+    },
+    DeviceFound(String),
+    ConnectSucceeded,
+    Reconnected,
+    Failed {
+        kind: ConnectionErrorKind,
+        retry_in: u32,
+    },
+    Lost(DisconnectReason),
+    Reconnecting {
+        attempt: u32,
+        retry_in: u32,
+    },
+}
+
+impl Default for ConnectionStatus {
+    fn default() -> Self {
+        Self::Discovering { retry_in: None }
+    }
+}
+
+impl ConnectionStatus {
+    /// Apply an event and return the resulting state.
+    ///
+    /// A device can only be lost once it was connected; a stray `Lost` while
+    /// discovering or connecting keeps the current state.
+    pub fn next(&self, event: ConnectionEvent) -> Self {
+        match (self, event) {
+            (_, ConnectionEvent::ScanStarted) => Self::Discovering { retry_in: None },
+            (_, ConnectionEvent::NoDeviceFound { retry_in }) => Self::Discovering {
+                retry_in: Some(retry_in),
+            },
+            (_, ConnectionEvent::DeviceFound(device)) => Self::Connecting { device },
+            (_, ConnectionEvent::ConnectSucceeded) => Self::Connected { reconnected: false },
+            (_, ConnectionEvent::Reconnected) => Self::Connected { reconnected: true },
+            (_, ConnectionEvent::Failed { kind, retry_in }) => Self::Error { kind, retry_in },
+            (
+                Self::Connected { .. } | Self::Disconnected(_) | Self::Reconnecting { .. },
+                ConnectionEvent::Lost(reason),
+            ) => Self::Disconnected(reason),
+            (current, ConnectionEvent::Lost(_)) => current.clone(),
+            (
+                Self::Disconnected(_) | Self::Reconnecting { .. },
+                ConnectionEvent::Reconnecting { attempt, retry_in },
+            ) => Self::Reconnecting { attempt, retry_in },
+            (current, ConnectionEvent::Reconnecting { .. }) => current.clone(),
+        }
+    }
+
+    /// Apply an event in place
+    pub fn apply(&mut self, event: ConnectionEvent) {
+        *self = self.next(event);
+    }
+
+    pub fn is_connected(&self) -> bool {
+        matches!(self, Self::Connected { .. })
+    }
+
+    /// Full status line shown in the device panel
+    pub fn message(&self) -> String {
+        match self {
+            Self::Discovering { retry_in: None } => "🔍 Looking for devices...".to_string(),
+            Self::Discovering {
+                retry_in: Some(secs),
+            } => format!("🔌 No Device Connected - Retrying in {}s...", secs),
+            Self::Connecting { device } => format!("🔌 Connecting to {}...", device),
+            Self::Connected { reconnected: false } => "✅ Connected".to_string(),
+            Self::Connected { reconnected: true } => "✅ Device Reconnected - Resuming".to_string(),
+            Self::Disconnected(DisconnectReason::ConnectionLost) => {
+                "🔌 Device Disconnected - Searching for device...".to_string()
+            }
+            Self::Disconnected(DisconnectReason::AutomationPaused) => {
+                "🔌 Device Disconnected - Paused".to_string()
+            }
+            Self::Reconnecting { attempt, retry_in } => format!(
+                "🔄 Reconnecting (attempt {}) - Retrying in {}s...",
+                attempt, retry_in
+            ),
+            Self::Error { kind, retry_in } => match kind {
+                ConnectionErrorKind::ResourceBusy => format!(
+                    "⚠️ USB Already in Use - Close other ADB apps - Retrying in {}s...",
+                    retry_in
+                ),
+                ConnectionErrorKind::PermissionDenied => format!(
+                    "⚠️ Permission Denied - Check USB permissions - Retrying in {}s...",
+                    retry_in
+                ),
+                ConnectionErrorKind::DeviceNotFound => format!(
+                    "⚠️ No Device Found - Reconnect USB cable - Retrying in {}s...",
+                    retry_in
+                ),
+                ConnectionErrorKind::AuthorizationPending => format!(
+                    "🔐 Waiting for Authorization - Accept the dialog on the phone - Retrying in {}s...",
+                    retry_in
+                ),
+                ConnectionErrorKind::Enumeration(e) => {
+                    format!("❌ Error: {} - Retrying in {}s...", e, retry_in)
+                }
+                ConnectionErrorKind::Other(e) => {
+                    format!("❌ Connection failed: {} - Retrying in {}s...", e, retry_in)
+                }
+            },
+        }
+    }
+
+    /// Short label for the header badge
+    pub fn badge_label(&self) -> &'static str {
+        match self {
+            Self::Discovering { .. } => "Searching",
+            Self::Connecting { .. } => "Connecting",
+            Self::Connected { .. } => "Connected",
+            Self::Disconnected(_) => "Disconnected",
+            Self::Reconnecting { .. } => "Reconnecting",
+            Self::Error { .. } => "Error",
+        }
+    }
+
+    /// Inline style for the header badge
+    pub fn badge_style(&self) -> String {
+        let colors = match self {
+            Self::Connected { .. } => {
+                "background: #1f5130; color: #48ff9b; border: 1px solid #48ff9b;"
+            }
+            Self::Error { .. } => "background: #5a1f1f; color: #ff6262; border: 1px solid #ff6262;",
+            _ => "background: #5a4b1f; color: #ffd857; border: 1px solid #ffd857;",
+        };
+        format!("{} {}", colors, BADGE_BASE_STYLE)
+    }
+}
+
+impl std::fmt::Display for ConnectionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_to_connected() {
+        let mut status = ConnectionStatus::default();
+        status.apply(ConnectionEvent::DeviceFound("18d1:4ee7".to_string()));
+        assert_eq!(
+            status,
+            ConnectionStatus::Connecting {
+                device: "18d1:4ee7".to_string()
+            }
+        );
+        status.apply(ConnectionEvent::ConnectSucceeded);
+        assert!(status.is_connected());
+        assert_eq!(status.badge_label(), "Connected");
+    }
+
+    #[test]
+    fn test_lost_only_applies_when_connected() {
+        let discovering = ConnectionStatus::default();
+        let next = discovering.next(ConnectionEvent::Lost(DisconnectReason::ConnectionLost));
+        assert_eq!(next, discovering);
+
+        let connected = ConnectionStatus::Connected { reconnected: false };
+        let next = connected.next(ConnectionEvent::Lost(DisconnectReason::AutomationPaused));
+        assert_eq!(
+            next,
+            ConnectionStatus::Disconnected(DisconnectReason::AutomationPaused)
+        );
+        assert_eq!(next.message(), "🔌 Device Disconnected - Paused");
+
+        let retrying = next.next(ConnectionEvent::Reconnecting {
+            attempt: 3,
+            retry_in: 4,
+        });
+        assert_eq!(retrying.badge_label(), "Reconnecting");
+        assert!(retrying.message().contains("attempt 3"));
+        assert!(retrying.next(ConnectionEvent::Reconnected).is_connected());
+        assert_eq!(
+            discovering.next(ConnectionEvent::Reconnecting {
+                attempt: 1,
+                retry_in: 1
+            }),
+            discovering
+        );
+    }
+
+    #[test]
+    fn test_error_messages_include_countdown() {
+        let status = ConnectionStatus::default().next(ConnectionEvent::Failed {
+            kind: ConnectionErrorKind::ResourceBusy,
+            retry_in: 7,
+        });
+        assert_eq!(status.badge_label(), "Error");
+        assert!(status.message().contains("Retrying in 7s"));
+        assert!(status.badge_style().contains("#ff6262"));
+    }
+
+    #[test]
+    fn test_authorization_pending_prompts_on_phone() {
+        let error = AdbError::AuthorizationPending {
+            device: "18d1:4ee7".to_string(),
+        };
+        let kind = ConnectionErrorKind::from_adb_error(&error);
+        assert_eq!(kind, ConnectionErrorKind::AuthorizationPending);
+        assert!(kind.tip().contains("Allow USB debugging?"));
+        let status =
+            ConnectionStatus::default().next(ConnectionEvent::Failed { kind, retry_in: 5 });
+        assert!(status.message().starts_with("🔐 Waiting for Authorization"));
+    }
+
+    #[test]
+    fn test_no_device_countdown() {
+        let status =
+            ConnectionStatus::default().next(ConnectionEvent::NoDeviceFound { retry_in: 3 });
+        assert_eq!(
+            status.message(),
+            "🔌 No Device Connected - Retrying in 3s..."
+        );
+        assert!(!status.is_connected());
+    }
+}
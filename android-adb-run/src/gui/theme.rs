@@ -0,0 +1,160 @@
+// gui/theme.rs
+// GUI colours live here as CSS variables, one set per theme. Components keep
+// their layout inline and take colours from `var(--...)` or the shared classes
+// below, so switching theme only changes `data-theme` on the app root.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 2] = [Theme::Dark, Theme::Light];
+
+    /// Value of the root `data-theme` attribute
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dark => "dark",
+            Self::Light => "light",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Dark => "🌙 Dark",
+            Self::Light => "☀️ Light",
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.name() == s)
+            .ok_or_else(|| format!("Unknown theme '{}'", s))
+    }
+}
+
+/// How the controls column and the screenshot share the window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    /// Side by side, stacked once the window is narrower than `NARROW_WIDTH_PX`
+    #[default]
+    Auto,
+    Columns,
+    Stacked,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 3] = [Layout::Auto, Layout::Columns, Layout::Stacked];
+
+    /// Value of the `data-layout` attribute
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Columns => "columns",
+            Self::Stacked => "stacked",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "↔️ Auto",
+            Self::Columns => "▥ Side by side",
+            Self::Stacked => "▤ Stacked",
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|layout| layout.name() == s)
+            .ok_or_else(|| format!("Unknown layout '{}'", s))
+    }
+}
+
+/// Below this window width the `auto` layout puts the screenshot under the controls
+pub const NARROW_WIDTH_PX: u32 = 900;
+
+/// Theme variables and shared classes, added once at the app root
+pub fn stylesheet() -> String {
+    format!(
+        r#"
+.app[data-theme="dark"] {{
+    --app-bg: linear-gradient(135deg,#667eea 0%,#764ba2 100%);
+    --text: #fff;
+    --panel-bg: rgba(255,255,255,0.1);
+    --panel-border: rgba(255,255,255,0.2);
+    --control-bg: rgba(0,0,0,0.3);
+    --control-text: #fff;
+    --control-border: rgba(255,255,255,0.3);
+    --heading: #90ee90;
+    --accent: #87ceeb;
+    --value: #ffd700;
+}}
+.app[data-theme="light"] {{
+    --app-bg: linear-gradient(135deg,#eef1f8 0%,#d9deec 100%);
+    --text: #1d2333;
+    --panel-bg: rgba(255,255,255,0.85);
+    --panel-border: rgba(29,35,51,0.15);
+    --control-bg: #3a4664;
+    --control-text: #fff;
+    --control-border: rgba(29,35,51,0.35);
+    --heading: #2c7a4b;
+    --accent: #1f6fa8;
+    --value: #9a6700;
+}}
+.app {{
+    height: 97vh;
+    display: flex;
+    flex-direction: column;
+    background: var(--app-bg);
+    color: var(--text);
+    box-sizing: border-box;
+}}
+.panel {{
+    background: var(--panel-bg);
+    backdrop-filter: blur(10px);
+    border: 1px solid var(--panel-border);
+}}
+.device-tabs {{ display: flex; flex-wrap: wrap; align-items: center; gap: 6px; margin-bottom: 8px; }}
+.device-tab {{
+    display: flex; gap: 8px; align-items: center;
+    background: var(--control-bg); color: var(--control-text);
+    border: 1px solid var(--control-border); border-radius: 8px;
+    padding: 4px 10px; font-size: 0.8em; font-weight: 600;
+}}
+.device-tab[data-active="true"] {{ border-color: var(--value); color: var(--value); }}
+.layout {{ display: flex; gap: 14px; align-items: flex-start; }}
+.layout-controls {{ flex: 1; min-width: 0; display: flex; flex-direction: column; gap: 10px; }}
+.screenshot-panel {{ flex: 0 0 400px; }}
+.layout[data-layout="stacked"] {{ flex-direction: column; align-items: stretch; }}
+.layout[data-layout="stacked"] .screenshot-panel {{ flex: none; }}
+@media (max-width: {narrow}px) {{
+    .layout[data-layout="auto"] {{ flex-direction: column; align-items: stretch; }}
+    .layout[data-layout="auto"] .screenshot-panel {{ flex: none; }}
+}}
+"#,
+        narrow = NARROW_WIDTH_PX
+    )
+}
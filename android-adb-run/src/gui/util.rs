@@ -1,49 +1,94 @@
 // gui/util.rs
 // Utility helpers for GUI
 
+use base64::Engine as _;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::{self, FilterType};
+use image::{GenericImageView, Rgb};
+
+/// Largest preview the screenshot panel shows: its 400x600 box at 2x for HiDPI screens
+pub const PREVIEW_MAX_WIDTH: u32 = 800;
+pub const PREVIEW_MAX_HEIGHT: u32 = 1200;
+const PREVIEW_JPEG_QUALITY: u8 = 85;
+
 pub fn base64_encode(data: &[u8]) -> String {
-    const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-    let mut result = String::new();
-    for chunk in data.chunks(3) {
-        let mut buf = [0u8; 3];
-        for (i, &byte) in chunk.iter().enumerate() {
-            buf[i] = byte;
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+/// `data:` URL with a downscaled JPEG of `image` for the screenshot panel `<img>`.
+/// Full-resolution bytes stay in `ScreenshotState::bytes` for matching and saving.
+pub fn preview_data_url<I>(image: &I) -> String
+where
+    I: GenericImageView<Pixel = Rgb<u8>>,
+{
+    scaled_data_url(image, PREVIEW_MAX_WIDTH, PREVIEW_MAX_HEIGHT)
+}
+
+/// `data:` URL with a JPEG of `image` shrunk to fit `max_width`x`max_height`
+pub fn scaled_data_url<I>(image: &I, max_width: u32, max_height: u32) -> String
+where
+    I: GenericImageView<Pixel = Rgb<u8>>,
+{
+    let (width, height) = image.dimensions();
+    let scale =
+        (max_width as f32 / width.max(1) as f32).min(max_height as f32 / height.max(1) as f32);
+
+    let mut jpeg = Vec::new();
+    let mut encoder = JpegEncoder::new_with_quality(&mut jpeg, PREVIEW_JPEG_QUALITY);
+    let encoded = if scale < 1.0 {
+        let preview = imageops::resize(
+            image,
+            ((width as f32 * scale).round() as u32).max(1),
+            ((height as f32 * scale).round() as u32).max(1),
+            FilterType::Triangle,
+        );
+        encoder.encode_image(&preview)
+    } else {
+        encoder.encode_image(image)
+    };
+    match encoded {
+        Ok(()) => format!("data:image/jpeg;base64,{}", base64_encode(&jpeg)),
+        Err(e) => {
+            log::warn!("⚠️ Cannot encode screenshot preview: {}", e);
+            String::new()
         }
-        let b = ((buf[0] as u32) << 16) | ((buf[1] as u32) << 8) | (buf[2] as u32);
-        result.push(CHARS[((b >> 18) & 63) as usize] as char);
-        result.push(CHARS[((b >> 12) & 63) as usize] as char);
-        result.push(if chunk.len() > 1 {
-            CHARS[((b >> 6) & 63) as usize] as char
-        } else {
-            '='
-        });
-        result.push(if chunk.len() > 2 {
-            CHARS[(b & 63) as usize] as char
-        } else {
-            '='
-        });
     }
-    result
 }
 
-pub fn calculate_device_coords(
-    element_rect: dioxus::html::geometry::ElementPoint,
-    screen_x: u32,
-    screen_y: u32,
-) -> (u32, u32) {
+/// Preview `data:` URL for encoded screenshot bytes (falls back to the PNG as-is)
+pub fn preview_data_url_from_bytes(bytes: &[u8]) -> String {
+    match image::load_from_memory(bytes) {
+        Ok(image) => preview_data_url(&image.to_rgb8()),
+        Err(_) => format!("data:image/png;base64,{}", base64_encode(bytes)),
+    }
+}
+
+/// Border drawn around the screenshot `<img>`, in CSS pixels
+pub const SCREENSHOT_BORDER_PX: f32 = 8.0;
+
+/// Size the screenshot is shown at (without border): a `screen_x x screen_y`
+/// device fitted into the panel's 400x600 box
+pub fn display_size(screen_x: u32, screen_y: u32) -> (f32, f32) {
     let max_content_width = 400.0;
     let max_content_height = 600.0;
-    let border_px = 8.0;
 
-    let image_aspect = screen_x as f32 / screen_y as f32;
+    let image_aspect = screen_x as f32 / screen_y.max(1) as f32;
     let container_aspect = max_content_width / max_content_height;
     let (content_w, content_h) = if image_aspect > container_aspect {
         (max_content_width, max_content_width / image_aspect)
     } else {
         (max_content_height * image_aspect, max_content_height)
     };
-    let displayed_w = content_w.max(1.0);
-    let displayed_h = content_h.max(1.0);
+    (content_w.max(1.0), content_h.max(1.0))
+}
+
+pub fn calculate_device_coords(
+    element_rect: dioxus::html::geometry::ElementPoint,
+    screen_x: u32,
+    screen_y: u32,
+) -> (u32, u32) {
+    let border_px = SCREENSHOT_BORDER_PX;
+    let (displayed_w, displayed_h) = display_size(screen_x, screen_y);
 
     let raw_x = element_rect.x as f32 - border_px;
     let raw_y = element_rect.y as f32 - border_px;
@@ -58,3 +103,31 @@ pub fn calculate_device_coords(
 
     (device_x.min(screen_x - 1), device_y.min(screen_y - 1))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine as _;
+
+    #[test]
+    fn test_preview_is_downscaled_jpeg() {
+        let screenshot = image::RgbImage::new(1080, 2400);
+        let url = preview_data_url(&screenshot);
+        let encoded = url.strip_prefix("data:image/jpeg;base64,").unwrap();
+        let jpeg = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .unwrap();
+        let preview = image::load_from_memory(&jpeg).unwrap();
+        assert_eq!(
+            (preview.width(), preview.height()),
+            (540, PREVIEW_MAX_HEIGHT)
+        );
+
+        // Small images are encoded as they are
+        let small = preview_data_url(&image::RgbImage::new(100, 50));
+        let jpeg = base64::engine::general_purpose::STANDARD
+            .decode(small.strip_prefix("data:image/jpeg;base64,").unwrap())
+            .unwrap();
+        assert_eq!(image::load_from_memory(&jpeg).unwrap().width(), 100);
+    }
+}
@@ -1,17 +1,20 @@
-// Macro for debug output
+// Macro for debug output (logged at debug level when `--debug` is on)
 #[macro_export]
 macro_rules! debug_print {
     ($debug_enabled:expr, $($arg:tt)*) => {
         if $debug_enabled {
-            println!($($arg)*);
+            ::log::debug!($($arg)*);
         }
     };
 }
 
 pub mod adb;
 pub mod args;
+pub mod cli;
 pub mod game_automation;
 pub mod gui; // replaced old dioxus root module
+pub mod logging;
+pub mod server;
 pub mod template_matching;
 
 pub use adb::AdbBackend;
@@ -0,0 +1,169 @@
+// Logging - tracing subscriber with per-module levels, a rolling log file and a GUI feed.
+//
+// Levels come from `RUST_LOG` (e.g. `RUST_LOG=info,android_adb_run::adb=debug`),
+// defaulting to `info` (`debug` for this crate with `--debug`). Existing `log::`
+// calls are bridged into tracing, so every message reaches all three outputs:
+//   - stderr, for interactive runs
+//   - `logs/android-adb-run.log.<date>`, rotated daily, for long automation runs
+//   - an in-memory feed the GUI log viewer subscribes to
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::broadcast;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, fmt};
+
+pub const LOG_DIR: &str = "logs";
+pub const LOG_FILE_PREFIX: &str = "android-adb-run.log";
+/// Entries kept for a log viewer that subscribes late
+const LOG_HISTORY: usize = 500;
+
+/// Always silenced: harmless USB transport cleanup errors
+const QUIET_TARGETS: &str = "adb_client::transports::usb_transport=off";
+
+/// One formatted log event, as shown in the GUI log viewer
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// Local wall-clock time, `HH:MM:SS`
+    pub time: String,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+struct LogFeed {
+    history: Mutex<VecDeque<LogEntry>>,
+    sender: broadcast::Sender<LogEntry>,
+}
+
+static LOG_FEED: OnceLock<LogFeed> = OnceLock::new();
+
+fn feed() -> &'static LogFeed {
+    LOG_FEED.get_or_init(|| LogFeed {
+        history: Mutex::new(VecDeque::with_capacity(LOG_HISTORY)),
+        sender: broadcast::channel(LOG_HISTORY).0,
+    })
+}
+
+/// Recent entries plus a receiver for everything logged from now on
+pub fn subscribe() -> (Vec<LogEntry>, broadcast::Receiver<LogEntry>) {
+    let feed = feed();
+    // Hold the history lock so no entry lands between the snapshot and the receiver
+    let history = feed.history.lock().unwrap();
+    (history.iter().cloned().collect(), feed.sender.subscribe())
+}
+
+fn publish(entry: LogEntry) {
+    let feed = feed();
+    let mut history = feed.history.lock().unwrap();
+    if history.len() == LOG_HISTORY {
+        history.pop_front();
+    }
+    history.push_back(entry.clone());
+    // No receivers until the GUI log viewer opens
+    let _ = feed.sender.send(entry);
+}
+
+/// Filter used when `RUST_LOG` is unset
+pub fn default_filter(debug_enabled: bool) -> String {
+    if debug_enabled {
+        format!("info,android_adb_run=debug,{}", QUIET_TARGETS)
+    } else {
+        format!("info,{}", QUIET_TARGETS)
+    }
+}
+
+/// Install the global subscriber; keep the returned guard alive so the log
+/// file is flushed on exit
+pub fn init(debug_enabled: bool) -> Option<WorkerGuard> {
+    let filter = EnvFilter::try_from_default_env()
+        .map(|filter| filter.add_directive(QUIET_TARGETS.parse().expect("valid directive")))
+        .unwrap_or_else(|_| EnvFilter::new(default_filter(debug_enabled)));
+
+    let (file_layer, guard) = match std::fs::create_dir_all(LOG_DIR) {
+        Ok(()) => {
+            let appender = tracing_appender::rolling::daily(LOG_DIR, LOG_FILE_PREFIX);
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (
+                Some(fmt::layer().with_writer(writer).with_ansi(false)),
+                Some(guard),
+            )
+        }
+        Err(e) => {
+            eprintln!("⚠️ Log directory {} unavailable: {}", LOG_DIR, e);
+            (None, None)
+        }
+    };
+
+    let result = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr).with_target(false))
+        .with(file_layer)
+        .with(GuiFeedLayer)
+        .try_init();
+    if let Err(e) = result {
+        eprintln!("⚠️ Logging already initialized: {}", e);
+    }
+    guard
+}
+
+/// Forwards every enabled event to the GUI feed
+struct GuiFeedLayer;
+
+impl<S: Subscriber> Layer<S> for GuiFeedLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        publish(LogEntry {
+            time: wall_clock_time(),
+            level: *metadata.level(),
+            // Events bridged from `log` carry their original target in `log.target`
+            target: visitor
+                .log_target
+                .unwrap_or_else(|| metadata.target().to_string()),
+            message: visitor.message,
+        });
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    log_target: Option<String>,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message.push_str(value),
+            "log.target" => self.log_target = Some(value.to_string()),
+            name if name.starts_with("log.") => {}
+            name => {
+                let _ = write!(self.message, " {}={}", name, value);
+            }
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        match field.name() {
+            "message" => {
+                let _ = write!(self.message, "{:?}", value);
+            }
+            name if name.starts_with("log.") => {}
+            name => {
+                let _ = write!(self.message, " {}={:?}", name, value);
+            }
+        }
+    }
+}
+
+fn wall_clock_time() -> String {
+    let now = time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc());
+    format!("{:02}:{:02}:{:02}", now.hour(), now.minute(), now.second())
+}
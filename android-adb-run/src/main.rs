@@ -1,63 +1,130 @@
-mod args;
-
-use android_adb_run::adb::AdbClient;
+use android_adb_run::adb::{AdbResult, BACKEND_NAME};
+use android_adb_run::args::{Args, Mode};
+use android_adb_run::cli::{self, HeadlessError};
+use android_adb_run::game_automation::config;
+use android_adb_run::game_automation::timelapse::TimelapseOptions;
 use android_adb_run::gui::dioxus_app::run_gui;
-use args::{Args, Mode};
+use android_adb_run::logging;
+use std::path::Path;
 
 fn main() {
-    // Initialize the logger with filter for harmless cleanup errors
-    env_logger::Builder::from_default_env()
-        .filter(
-            Some("adb_client::transports::usb_transport"),
-            log::LevelFilter::Off,
-        )
-        .init();
+    let args = Args::parse();
 
-    let args = match Args::parse() {
-        Some(args) => args,
-        None => return,
-    };
+    // Logging to stderr, logs/ and the GUI log viewer; the guard flushes the file on exit
+    let _log_guard = logging::init(args.debug_mode);
 
+    let device = args.device.clone();
+    let touch_timeout = args.touch_timeout_secs;
     match args.mode {
-        Mode::Screenshot => {
+        Mode::Screenshot {
+            out,
+            region,
+            batch: None,
+        } => {
             println!("📸 CLI screenshot mode...");
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            rt.block_on(async move {
-                match android_adb_run::adb::AdbBackend::list_devices().await {
-                    Ok(devs) if !devs.is_empty() => {
-                        let first = &devs[0];
-                        match android_adb_run::adb::AdbBackend::new_with_device(&first.name).await {
-                            Ok(client) => {
-                                let (sx, sy) = client.screen_dimensions();
-                                println!("📱 Device: {} size: {}x{}", client.device_name(), sx, sy);
-                                match client.screen_capture().await {
-                                    Ok(cap) => {
-                                        if let Err(e) =
-                                            tokio::fs::write("cli-screenshot.png", &cap.bytes)
-                                                .await
-                                        {
-                                            eprintln!("❌ Write failed: {e}");
-                                        } else {
-                                            println!(
-                                                "✅ Screenshot #{} ({}ms) saved to cli-screenshot.png",
-                                                cap.index, cap.duration_ms
-                                            );
-                                        }
-                                    }
-                                    Err(e) => eprintln!("❌ Screenshot failed: {e}"),
-                                }
-                            }
-                            Err(e) => eprintln!("❌ Open device error: {e}"),
-                        }
-                    }
-                    Ok(_) => eprintln!("❌ No devices found"),
-                    Err(e) => eprintln!("❌ List error: {e}"),
-                }
+            run_cli(cli::screenshot(device.as_deref(), &out, region));
+        }
+        Mode::Screenshot {
+            region,
+            batch: Some(batch),
+            ..
+        } => {
+            println!(
+                "📸 CLI batch screenshot mode: {} every {:?} into {}",
+                batch.count, batch.interval, batch.out_dir
+            );
+            run_cli(cli::screenshot_batch(
+                device.as_deref(),
+                region,
+                batch.count,
+                batch.interval,
+                &batch.out_dir,
+            ));
+        }
+        Mode::Devices { json } => run_cli(cli::list_devices(json)),
+        Mode::Tap { x, y } => run_cli(cli::tap(device.as_deref(), x, y)),
+        Mode::Swipe {
+            x1,
+            y1,
+            x2,
+            y2,
+            duration_ms,
+        } => run_cli(cli::swipe(
+            device.as_deref(),
+            (x1, y1),
+            (x2, y2),
+            duration_ms,
+        )),
+        Mode::Record { path } => run_cli(cli::record(device.as_deref(), &path)),
+        Mode::Replay { path } => run_cli(cli::replay(device.as_deref(), &path)),
+        Mode::Pair { address, code } => run_cli(cli::pair(&address, &code)),
+        Mode::Connect { address } => run_cli(cli::connect_wireless(&address)),
+        Mode::AutomationRun { config } => {
+            let result = match config {
+                Some(path) => config::set_config_path(path).map_err(HeadlessError::Failed),
+                None => Ok(()),
+            }
+            .and_then(|()| {
+                override_touch_timeout(touch_timeout);
+                cli::run_automation(device, args.debug_mode)
             });
+            exit_on_error(result);
+        }
+        Mode::Daemon {
+            config,
+            pid_file,
+            serve,
+        } => {
+            let result = match config {
+                Some(path) => config::set_config_path(path).map_err(HeadlessError::Failed),
+                None => Ok(()),
+            }
+            .and_then(|()| {
+                override_touch_timeout(touch_timeout);
+                cli::run_daemon(device, args.debug_mode, serve, Path::new(&pid_file))
+            });
+            exit_on_error(result);
+        }
+        Mode::BenchMatch { screenshot } => {
+            if let Err(e) = cli::bench_match(&screenshot) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+        }
+        Mode::Analyze {
+            image,
+            templates,
+            out,
+            expect,
+        } => {
+            if let Err(e) = cli::analyze(&image, &templates, &out, &expect) {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+        }
+        Mode::ExportVideo {
+            dir,
+            out,
+            fps,
+            width,
+            history,
+        } => {
+            let options = TimelapseOptions { fps, width };
+            if let Err(e) =
+                cli::export_video(&dir, &out, options, history.as_deref(), device.as_deref())
+            {
+                eprintln!("❌ {e}");
+                std::process::exit(1);
+            }
+        }
+        Mode::Serve { addr } => {
+            override_touch_timeout(touch_timeout);
+            exit_on_error(cli::serve_automation(device, args.debug_mode, addr));
         }
         Mode::Gui => {
             println!(
-                "🚀 Launching Android ADB Control GUI{}...",
+                "🚀 Launching Android ADB Control GUI ({} backend){}...",
+                BACKEND_NAME,
                 if args.debug_mode { " [DEBUG MODE]" } else { "" }
             );
             //# timeout set spawn a thread to exit after timeout
@@ -69,8 +136,36 @@ fn main() {
                     std::process::exit(0);
                 });
             }
+            override_touch_timeout(touch_timeout);
             // Run GUI, it will create async runtime and start backend
             run_gui(args.debug_mode);
         }
     }
 }
+
+/// `--touch-timeout` replaces `[touch] pause_timeout_seconds` in the active
+/// config; the file only changes if the config is saved
+fn override_touch_timeout(seconds: Option<u64>) {
+    if let Some(seconds) = seconds {
+        let mut active = config::active_config();
+        active.touch.pause_timeout_seconds = seconds;
+        config::set_active_config(active);
+    }
+}
+
+/// Exit with the error's code when headless automation failed
+fn exit_on_error(result: Result<(), HeadlessError>) {
+    if let Err(e) = result {
+        eprintln!("❌ {e}");
+        std::process::exit(e.exit_code());
+    }
+}
+
+/// Run a one-shot CLI command on a fresh runtime, exiting non-zero on failure
+fn run_cli(command: impl std::future::Future<Output = AdbResult<()>>) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    if let Err(e) = rt.block_on(command) {
+        eprintln!("❌ {e}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,366 @@
+// server/events.rs - automation events for the WebSocket stream at `/ws`.
+//
+// The FSM only publishes through signals, so events are derived by comparing
+// consecutive snapshots taken by `mirror_signals`.
+
+use super::AutomationSnapshot;
+use crate::adb::{DeviceTelemetry, Orientation};
+use crate::game_automation::coord::Coord;
+use crate::game_automation::sequence::SequenceProgress;
+use crate::game_automation::types::TimedEventType;
+use serde::Serialize;
+
+/// Events buffered per WebSocket client before it starts missing some
+pub const EVENT_BUFFER: usize = 64;
+
+/// One change in the automation, sent to WebSocket clients as JSON tagged by `type`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum AutomationEvent {
+    StateChanged {
+        from: String,
+        to: String,
+    },
+    ScreenshotTaken {
+        counter: u64,
+        status: String,
+    },
+    TimedTapExecuted {
+        id: String,
+        x: Coord,
+        y: Coord,
+        execution_count: u64,
+    },
+    /// A screenshot, script or sequence timed event ran
+    TimedEventExecuted {
+        id: String,
+        execution_count: u64,
+    },
+    TouchPause {
+        paused: bool,
+    },
+    ConnectionChanged {
+        status: String,
+    },
+    StatusMessage {
+        message: String,
+    },
+    /// A device health guardrail paused automation
+    HealthPaused {
+        reason: String,
+    },
+    /// The device is back within its health limits
+    HealthResumed,
+    /// `[quiet_hours]` started; automation sleeps until `until` (HH:MM)
+    QuietHoursStarted {
+        until: String,
+    },
+    /// `[quiet_hours]` ended, or automation was stopped while sleeping
+    QuietHoursEnded,
+    /// No template matched and the screen did not change for `[watchdog]
+    /// stuck_after_seconds`; `recovery` is being run
+    StuckDetected {
+        idle_seconds: u64,
+        recovery: String,
+        attempt: u32,
+    },
+    /// The game left the foreground and is being relaunched
+    RecoveryAttempt {
+        package: String,
+        attempt: u32,
+        max_attempts: u32,
+    },
+    /// New battery, thermal and memory sample
+    TelemetryUpdated {
+        telemetry: DeviceTelemetry,
+    },
+    /// An action sequence moved to its next step, finished or failed
+    SequenceProgress {
+        progress: SequenceProgress,
+    },
+    /// A `[states]` transition fired, or a run started in the initial state
+    CustomStateChanged {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// The device turned; `rotated` while it is away from `expected`
+    OrientationChanged {
+        orientation: Orientation,
+        expected: Orientation,
+        rotated: bool,
+    },
+}
+
+/// Events describing how `next` differs from `prev`
+pub fn diff(prev: &AutomationSnapshot, next: &AutomationSnapshot) -> Vec<AutomationEvent> {
+    let mut events = Vec::new();
+
+    if prev.state != next.state {
+        events.push(AutomationEvent::StateChanged {
+            from: format!("{:?}", prev.state),
+            to: format!("{:?}", next.state),
+        });
+    }
+    if prev.connection != next.connection {
+        events.push(AutomationEvent::ConnectionChanged {
+            status: next.connection.clone(),
+        });
+    }
+    if prev.custom_state != next.custom_state {
+        events.push(AutomationEvent::CustomStateChanged {
+            from: prev.custom_state.clone(),
+            to: next.custom_state.clone(),
+        });
+    }
+    // The first reading is only news when the device is already turned
+    if let Some(status) = next.orientation
+        && prev.orientation != next.orientation
+        && (prev.orientation.is_some() || status.rotated())
+    {
+        events.push(AutomationEvent::OrientationChanged {
+            orientation: status.current,
+            expected: status.expected,
+            rotated: status.rotated(),
+        });
+    }
+    if prev.paused_by_touch != next.paused_by_touch {
+        events.push(AutomationEvent::TouchPause {
+            paused: next.paused_by_touch,
+        });
+    }
+
+    for event in &next.timed_events {
+        let previous_count = prev
+            .timed_events
+            .iter()
+            .find(|p| p.id == event.id)
+            .map_or(0, |p| p.execution_count);
+        if event.execution_count <= previous_count {
+            continue;
+        }
+        match event.event_type {
+            TimedEventType::Tap { x, y } | TimedEventType::ConditionalTap { x, y, .. } => events
+                .push(AutomationEvent::TimedTapExecuted {
+                    id: event.id.clone(),
+                    x,
+                    y,
+                    execution_count: event.execution_count,
+                }),
+            // Internal GUI refresh tick
+            TimedEventType::CountdownUpdate => {}
+            TimedEventType::Screenshot
+            | TimedEventType::Script { .. }
+            | TimedEventType::Sequence { .. }
+            | TimedEventType::BackupFiles { .. } => {
+                events.push(AutomationEvent::TimedEventExecuted {
+                    id: event.id.clone(),
+                    execution_count: event.execution_count,
+                })
+            }
+        }
+    }
+
+    if prev.health_pause != next.health_pause {
+        events.push(match &next.health_pause {
+            Some(reason) => AutomationEvent::HealthPaused {
+                reason: reason.clone(),
+            },
+            None => AutomationEvent::HealthResumed,
+        });
+    }
+    if prev.sleeping_until != next.sleeping_until {
+        events.push(match &next.sleeping_until {
+            Some(until) => AutomationEvent::QuietHoursStarted {
+                until: until.clone(),
+            },
+            None => AutomationEvent::QuietHoursEnded,
+        });
+    }
+    if let Some(stuck) = &next.stuck
+        && prev.stuck.as_ref() != Some(stuck)
+    {
+        events.push(AutomationEvent::StuckDetected {
+            idle_seconds: stuck.idle_seconds,
+            recovery: stuck.recovery.clone(),
+            attempt: stuck.attempt,
+        });
+    }
+    if let Some(recovery) = &next.recovery
+        && prev.recovery.as_ref() != Some(recovery)
+    {
+        events.push(AutomationEvent::RecoveryAttempt {
+            package: recovery.package.clone(),
+            attempt: recovery.attempt,
+            max_attempts: recovery.max_attempts,
+        });
+    }
+    if let Some(telemetry) = &next.telemetry
+        && prev.telemetry.as_ref() != Some(telemetry)
+    {
+        events.push(AutomationEvent::TelemetryUpdated {
+            telemetry: telemetry.clone(),
+        });
+    }
+    if let Some(progress) = &next.sequence_progress
+        && prev.sequence_progress.as_ref() != Some(progress)
+    {
+        events.push(AutomationEvent::SequenceProgress {
+            progress: progress.clone(),
+        });
+    }
+
+    // Unchanged screenshots share the same buffer between snapshots
+    let screenshot_changed = next.screenshot_png.as_ref().map(|png| png.as_ptr())
+        != prev.screenshot_png.as_ref().map(|png| png.as_ptr());
+    if screenshot_changed && next.screenshot_png.is_some() {
+        events.push(AutomationEvent::ScreenshotTaken {
+            counter: next.screenshot_counter,
+            status: next.screenshot_status.clone(),
+        });
+    } else if prev.screenshot_status != next.screenshot_status && !next.screenshot_status.is_empty()
+    {
+        events.push(AutomationEvent::StatusMessage {
+            message: next.screenshot_status.clone(),
+        });
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_automation::GameState;
+    use crate::game_automation::types::TimedEvent;
+    use axum::body::Bytes;
+
+    #[test]
+    fn test_diff_reports_state_taps_and_screenshots() {
+        let prev = AutomationSnapshot {
+            timed_events: vec![
+                TimedEvent::new_tap_seconds("claim".into(), 540, 1200, 60),
+                TimedEvent::new_countdown_update(1),
+            ],
+            ..Default::default()
+        };
+        assert!(diff(&prev, &prev.clone()).is_empty());
+
+        let mut next = prev.clone();
+        next.state = GameState::Running;
+        next.custom_state = Some("MainMenu".into());
+        for event in &mut next.timed_events {
+            event.mark_executed();
+        }
+        next.screenshot_counter = 1;
+        next.screenshot_status = "✅ Screenshot #1".into();
+        next.screenshot_png = Some(Bytes::from_static(b"png"));
+
+        assert_eq!(
+            diff(&prev, &next),
+            vec![
+                AutomationEvent::StateChanged {
+                    from: "Idle".into(),
+                    to: "Running".into()
+                },
+                AutomationEvent::CustomStateChanged {
+                    from: None,
+                    to: Some("MainMenu".into())
+                },
+                AutomationEvent::TimedTapExecuted {
+                    id: "claim".into(),
+                    x: Coord::Px(540),
+                    y: Coord::Px(1200),
+                    execution_count: 1
+                },
+                AutomationEvent::ScreenshotTaken {
+                    counter: 1,
+                    status: "✅ Screenshot #1".into()
+                },
+            ]
+        );
+        // The same screenshot carried into the next snapshot is not taken again
+        assert!(diff(&next, &next.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_quiet_hours() {
+        let running = AutomationSnapshot {
+            state: GameState::Running,
+            ..Default::default()
+        };
+        let sleeping = AutomationSnapshot {
+            state: GameState::Sleeping,
+            sleeping_until: Some("07:00".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            diff(&running, &sleeping),
+            vec![
+                AutomationEvent::StateChanged {
+                    from: "Running".into(),
+                    to: "Sleeping".into()
+                },
+                AutomationEvent::QuietHoursStarted {
+                    until: "07:00".into()
+                },
+            ]
+        );
+        assert_eq!(
+            diff(&sleeping, &running)[1],
+            AutomationEvent::QuietHoursEnded
+        );
+    }
+
+    #[test]
+    fn test_diff_reports_stuck() {
+        use crate::game_automation::watchdog::StuckDetection;
+
+        let stuck = AutomationSnapshot {
+            stuck: Some(StuckDetection {
+                idle_seconds: 300,
+                recovery: "press BACK".into(),
+                attempt: 1,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            diff(&AutomationSnapshot::default(), &stuck),
+            vec![AutomationEvent::StuckDetected {
+                idle_seconds: 300,
+                recovery: "press BACK".into(),
+                attempt: 1,
+            }]
+        );
+        // Clearing it after a match is not another detection
+        assert!(diff(&stuck, &AutomationSnapshot::default()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_rotation() {
+        use crate::game_automation::types::OrientationStatus;
+
+        let upright = AutomationSnapshot {
+            orientation: Some(OrientationStatus {
+                current: Orientation::Portrait,
+                expected: Orientation::Portrait,
+            }),
+            ..Default::default()
+        };
+        // Reading the orientation for the first time is not a turn
+        assert!(diff(&AutomationSnapshot::default(), &upright).is_empty());
+
+        let mut turned = upright.clone();
+        turned.orientation = Some(OrientationStatus {
+            current: Orientation::Landscape,
+            expected: Orientation::Portrait,
+        });
+        assert_eq!(
+            diff(&upright, &turned),
+            vec![AutomationEvent::OrientationChanged {
+                orientation: Orientation::Landscape,
+                expected: Orientation::Portrait,
+                rotated: true,
+            }]
+        );
+    }
+}
@@ -0,0 +1,147 @@
+// server/metrics.rs - Prometheus text exposition of the automation snapshot
+// for `GET /metrics`, built with `--features metrics`.
+//
+// Counters restart from zero with the process; Prometheus' rate() handles that.
+use super::AutomationSnapshot;
+use crate::game_automation::GameState;
+use std::fmt::Write;
+
+/// Metric name prefix
+const PREFIX: &str = "adb_automation";
+
+/// Render `snapshot` in the Prometheus text format (version 0.0.4)
+pub fn render(snapshot: &AutomationSnapshot) -> String {
+    let stats = &snapshot.stats;
+    let mut out = String::new();
+
+    metric(
+        &mut out,
+        "screenshots_total",
+        "counter",
+        "Screenshots taken",
+        &[("", snapshot.screenshot_counter as f64)],
+    );
+    metric(
+        &mut out,
+        "taps_total",
+        "counter",
+        "Taps executed, by what triggered them",
+        &[
+            ("source=\"timed_event\"", stats.total_taps() as f64),
+            ("source=\"template\"", stats.total_template_actions() as f64),
+        ],
+    );
+    metric(
+        &mut out,
+        "disconnects_total",
+        "counter",
+        "Times the device connection dropped",
+        &[("", snapshot.disconnects as f64)],
+    );
+    metric(
+        &mut out,
+        "touch_pauses_total",
+        "counter",
+        "Times a human touch paused automation",
+        &[("", snapshot.touch_pauses as f64)],
+    );
+    metric(
+        &mut out,
+        "match_duration_seconds",
+        "summary",
+        "Time spent template matching a screenshot",
+        &[
+            ("_sum", stats.match_time.total_ms as f64 / 1000.0),
+            ("_count", stats.match_time.analyses as f64),
+        ],
+    );
+    let latency = &stats.screenshot_latency;
+    metric(
+        &mut out,
+        "screenshot_latency_seconds",
+        "summary",
+        "Screenshot capture time over the recent captures",
+        &[
+            ("quantile=\"0.5\"", latency.p50_ms as f64 / 1000.0),
+            ("quantile=\"0.9\"", latency.p90_ms as f64 / 1000.0),
+            ("quantile=\"0.99\"", latency.p99_ms as f64 / 1000.0),
+        ],
+    );
+    metric(
+        &mut out,
+        "running",
+        "gauge",
+        "1 while automation is running",
+        &[(
+            "",
+            f64::from(u8::from(snapshot.state == GameState::Running)),
+        )],
+    );
+    metric(
+        &mut out,
+        "paused_by_touch",
+        "gauge",
+        "1 while a human touch holds automation paused",
+        &[("", f64::from(u8::from(snapshot.paused_by_touch)))],
+    );
+    metric(
+        &mut out,
+        "connected",
+        "gauge",
+        "1 while the device is connected",
+        &[("", f64::from(u8::from(snapshot.connected)))],
+    );
+    if let Some(level) = snapshot.telemetry.as_ref().and_then(|t| t.battery_level) {
+        metric(
+            &mut out,
+            "battery_level_percent",
+            "gauge",
+            "Device battery level",
+            &[("", level as f64)],
+        );
+    }
+
+    out
+}
+
+/// One metric family; each sample is a `{labels}` string (or `_sum`/`_count`
+/// suffix for summaries) and its value
+fn metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(&str, f64)]) {
+    let _ = writeln!(out, "# HELP {PREFIX}_{name} {help}");
+    let _ = writeln!(out, "# TYPE {PREFIX}_{name} {kind}");
+    for (labels, value) in samples {
+        let _ = match labels {
+            l if l.is_empty() => writeln!(out, "{PREFIX}_{name} {value}"),
+            l if l.starts_with('_') => writeln!(out, "{PREFIX}_{name}{l} {value}"),
+            l => writeln!(out, "{PREFIX}_{name}{{{l}}} {value}"),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters_and_summary() {
+        let mut snapshot = AutomationSnapshot {
+            screenshot_counter: 7,
+            disconnects: 2,
+            connected: true,
+            ..Default::default()
+        };
+        snapshot.stats.taps.insert("claim".into(), 3);
+        snapshot.stats.match_time.analyses = 4;
+        snapshot.stats.match_time.total_ms = 1500;
+
+        let text = render(&snapshot);
+        assert!(text.contains("# TYPE adb_automation_screenshots_total counter\n"));
+        assert!(text.contains("adb_automation_screenshots_total 7\n"));
+        assert!(text.contains("adb_automation_taps_total{source=\"timed_event\"} 3\n"));
+        assert!(text.contains("adb_automation_disconnects_total 2\n"));
+        assert!(text.contains("adb_automation_match_duration_seconds_sum 1.5\n"));
+        assert!(text.contains("adb_automation_match_duration_seconds_count 4\n"));
+        assert!(text.contains("adb_automation_connected 1\n"));
+        assert!(!text.contains("battery_level"));
+    }
+}
@@ -0,0 +1,176 @@
+// HTTP control API - drives a headless GameAutomation from other machines.
+//
+// `--serve` hosts the FSM exactly like `automation run`, plus an axum server.
+// The FSM publishes through Dioxus signals, which cannot leave the VirtualDom
+// thread, so `mirror_signals` copies them into a shared snapshot that the HTTP
+// handlers read. Commands go straight into the FSM's `AutomationCommand` channel.
+// Differences between consecutive snapshots are broadcast as `AutomationEvent`s
+// to WebSocket clients on `/ws`.
+
+pub mod events;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod routes;
+
+use crate::adb::DeviceTelemetry;
+use crate::game_automation::GameState;
+use crate::game_automation::sequence::SequenceProgress;
+use crate::game_automation::stats::StatsSummary;
+use crate::game_automation::types::{
+    AutomationSignals, DeviceInfo, OrientationStatus, RecoveryAttempt, TimedEvent,
+};
+use crate::game_automation::watchdog::StuckDetection;
+use axum::body::Bytes;
+use dioxus::prelude::ReadableExt;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+pub use events::AutomationEvent;
+pub use routes::{ServerState, router};
+
+/// How often the snapshot is refreshed from the FSM signals
+const MIRROR_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Latest automation state as seen by the HTTP handlers
+#[derive(Debug, Clone)]
+pub struct AutomationSnapshot {
+    pub state: GameState,
+    pub paused_by_touch: bool,
+    pub touch_timeout_remaining: Option<u64>,
+    pub connection: String,
+    pub connected: bool,
+    /// Times the device dropped since the server started
+    pub disconnects: u64,
+    /// Times a human touch paused automation since the server started
+    pub touch_pauses: u64,
+    pub screenshot_status: String,
+    pub screenshot_counter: u64,
+    pub device: Option<DeviceInfo>,
+    pub timed_events: Vec<TimedEvent>,
+    /// PNG bytes of the latest screenshot
+    pub screenshot_png: Option<Bytes>,
+    pub telemetry: Option<DeviceTelemetry>,
+    /// Guardrail currently holding automation paused
+    pub health_pause: Option<String>,
+    pub recovery: Option<RecoveryAttempt>,
+    /// Latest step of the running (or last) action sequence
+    pub sequence_progress: Option<SequenceProgress>,
+    pub stats: StatsSummary,
+    /// `[states]` game state
+    pub custom_state: Option<String>,
+    pub orientation: Option<OrientationStatus>,
+    /// `HH:MM` the `[quiet_hours]` sleep ends
+    pub sleeping_until: Option<String>,
+    /// Latest `[watchdog]` stuck detection, until a template matches again
+    pub stuck: Option<StuckDetection>,
+}
+
+impl Default for AutomationSnapshot {
+    fn default() -> Self {
+        Self {
+            state: GameState::Idle,
+            paused_by_touch: false,
+            touch_timeout_remaining: None,
+            connection: String::new(),
+            connected: false,
+            disconnects: 0,
+            touch_pauses: 0,
+            screenshot_status: String::new(),
+            screenshot_counter: 0,
+            device: None,
+            timed_events: Vec::new(),
+            screenshot_png: None,
+            telemetry: None,
+            health_pause: None,
+            recovery: None,
+            sequence_progress: None,
+            stats: StatsSummary::default(),
+            custom_state: None,
+            orientation: None,
+            sleeping_until: None,
+            stuck: None,
+        }
+    }
+}
+
+pub type SharedSnapshot = Arc<RwLock<AutomationSnapshot>>;
+
+/// Copy the FSM signals into `snapshot` until the VirtualDom drops this task,
+/// broadcasting what changed on `events`
+pub async fn mirror_signals(
+    signals: AutomationSignals,
+    snapshot: SharedSnapshot,
+    events: broadcast::Sender<AutomationEvent>,
+) {
+    // The counter is bumped before the bytes land, so key on both
+    let mut last_screenshot: Option<(u64, usize)> = None;
+    loop {
+        let prev = snapshot.read().unwrap().clone();
+        let counter = *signals.screenshot_counter.peek();
+        let screenshot_png = {
+            let bytes = signals.screenshot_bytes.peek();
+            let key = (counter, bytes.as_ref().map_or(0, Vec::len));
+            if last_screenshot != Some(key) {
+                last_screenshot = Some(key);
+                bytes.as_ref().map(|b| Bytes::from(b.clone()))
+            } else {
+                prev.screenshot_png.clone()
+            }
+        };
+        let connected = signals.status.peek().is_connected();
+        let paused_by_touch = *signals.is_paused_by_touch.peek();
+        let next = AutomationSnapshot {
+            state: signals.automation_state.peek().clone(),
+            paused_by_touch,
+            touch_timeout_remaining: *signals.touch_timeout_remaining.peek(),
+            connection: signals.status.peek().message(),
+            connected,
+            disconnects: prev.disconnects + u64::from(prev.connected && !connected),
+            touch_pauses: prev.touch_pauses + u64::from(!prev.paused_by_touch && paused_by_touch),
+            screenshot_status: signals.screenshot_status.peek().clone(),
+            screenshot_counter: counter,
+            device: signals.device_info.peek().clone(),
+            timed_events: signals.timed_events_list.peek().clone(),
+            screenshot_png,
+            telemetry: signals.device_telemetry.peek().clone(),
+            health_pause: signals.health_pause.peek().clone(),
+            recovery: signals.recovery.peek().clone(),
+            sequence_progress: signals.sequence_progress.peek().clone(),
+            stats: signals.stats.peek().clone(),
+            custom_state: signals.custom_state.peek().clone(),
+            orientation: *signals.orientation.peek(),
+            sleeping_until: signals.sleeping_until.peek().clone(),
+            stuck: signals.stuck.peek().clone(),
+        };
+
+        for event in events::diff(&prev, &next) {
+            // No receivers until a WebSocket client connects
+            let _ = events.send(event);
+        }
+        *snapshot.write().unwrap() = next;
+        tokio::time::sleep(MIRROR_INTERVAL).await;
+    }
+}
+
+/// Bind `addr` and serve the control API on the current tokio runtime
+pub async fn serve(addr: SocketAddr, state: ServerState) -> Result<(), String> {
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("Cannot listen on {}: {}", addr, e))?;
+    if !addr.ip().is_loopback() {
+        log::warn!(
+            "⚠️ Control API on {} has no authentication; anyone on the network can drive the device",
+            addr
+        );
+    }
+    log::info!("🌐 Control API listening on http://{}", addr);
+
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router(state)).await {
+            log::error!("❌ Control API stopped: {}", e);
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,359 @@
+// server/routes.rs - REST endpoints and the `/ws` event stream of the control API
+use super::{AutomationEvent, SharedSnapshot};
+use crate::adb::DeviceTelemetry;
+use crate::game_automation::AutomationCommand;
+use crate::game_automation::coord::Coord;
+use crate::game_automation::sequence::SequenceProgress;
+use crate::game_automation::types::{
+    DeviceInfo, OrientationStatus, RecoveryAttempt, TimedEvent, TimedEventType,
+};
+use crate::game_automation::watchdog::StuckDetection;
+use axum::Json;
+use axum::Router;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc};
+
+const ENDPOINTS: &str = "\
+GET  /state                 automation state, device, status and telemetry
+POST /start                 start automation
+POST /stop                  stop automation
+POST /pause                 pause automation
+POST /resume                resume automation
+GET  /events                list timed events
+POST /events/{id}/trigger   run a timed event now
+GET  /screenshot            latest screenshot (PNG)
+POST /screenshot            take a new screenshot
+GET  /ws                    WebSocket stream of automation events (JSON)
+GET  /metrics               Prometheus metrics (builds with --features metrics)
+";
+
+/// Shared by every handler
+#[derive(Clone)]
+pub struct ServerState {
+    pub snapshot: SharedSnapshot,
+    pub commands: mpsc::Sender<AutomationCommand>,
+    pub events: broadcast::Sender<AutomationEvent>,
+}
+
+pub fn router(state: ServerState) -> Router {
+    let router = Router::new()
+        .route("/", get(|| async { ENDPOINTS }))
+        .route("/state", get(get_state))
+        .route("/start", post(start))
+        .route("/stop", post(stop))
+        .route("/pause", post(pause))
+        .route("/resume", post(resume))
+        .route("/events", get(list_events))
+        .route("/events/{id}/trigger", post(trigger_event))
+        .route("/screenshot", get(get_screenshot).post(take_screenshot))
+        .route("/ws", get(event_stream));
+    #[cfg(feature = "metrics")]
+    let router = router.route("/metrics", get(get_metrics));
+    router.with_state(state)
+}
+
+#[derive(Debug, Serialize)]
+pub struct StateView {
+    pub state: String,
+    pub paused_by_touch: bool,
+    pub touch_timeout_remaining: Option<u64>,
+    pub connection: String,
+    pub screenshot_status: String,
+    pub screenshot_counter: u64,
+    pub device: Option<DeviceView>,
+    pub telemetry: Option<DeviceTelemetry>,
+    /// Why a device health guardrail paused automation
+    pub health_pause: Option<String>,
+    pub recovery: Option<RecoveryAttempt>,
+    pub sequence: Option<SequenceProgress>,
+    /// `[states]` game state
+    pub custom_state: Option<String>,
+    pub orientation: Option<OrientationStatus>,
+    /// `HH:MM` the `[quiet_hours]` sleep ends
+    pub sleeping_until: Option<String>,
+    pub stuck: Option<StuckDetection>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeviceView {
+    pub name: String,
+    pub screen_x: u32,
+    pub screen_y: u32,
+}
+
+impl From<&DeviceInfo> for DeviceView {
+    fn from(info: &DeviceInfo) -> Self {
+        Self {
+            name: info.name.clone(),
+            screen_x: info.screen_x,
+            screen_y: info.screen_y,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct EventView {
+    pub id: String,
+    /// `screenshot`, `tap`, `conditional_tap`, `countdown`, `script`, `sequence` or `backup`
+    pub kind: &'static str,
+    /// Pixels, or a percentage string like `"54%"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<Coord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<Coord>,
+    /// Script or sequence name, or the device path a backup copies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    /// Template that must be on screen for a `conditional_tap`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    pub interval_secs: u64,
+    /// Wall-clock schedule (`"daily at 08:00"`) that replaces the interval
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    pub enabled: bool,
+    pub repeating: bool,
+    pub execution_count: u64,
+    /// Seconds until the next run; absent while disabled
+    pub next_in_secs: Option<u64>,
+}
+
+impl From<&TimedEvent> for EventView {
+    fn from(event: &TimedEvent) -> Self {
+        let (kind, script, template) = match &event.event_type {
+            TimedEventType::Screenshot => ("screenshot", None, None),
+            TimedEventType::Tap { .. } => ("tap", None, None),
+            TimedEventType::ConditionalTap { template, .. } => {
+                ("conditional_tap", None, Some(template.clone()))
+            }
+            TimedEventType::CountdownUpdate => ("countdown", None, None),
+            TimedEventType::Script { name } => ("script", Some(name.clone()), None),
+            TimedEventType::Sequence { name } => ("sequence", Some(name.clone()), None),
+            TimedEventType::BackupFiles { remote_path, .. } => {
+                ("backup", Some(remote_path.clone()), None)
+            }
+        };
+        let (x, y) = event.tap_point().unzip();
+        Self {
+            id: event.id.clone(),
+            kind,
+            x,
+            y,
+            script,
+            template,
+            interval_secs: event.interval.as_secs(),
+            schedule: event.schedule.as_ref().map(|s| s.to_string()),
+            enabled: event.enabled,
+            repeating: event.repeating,
+            execution_count: event.execution_count,
+            next_in_secs: event.time_until_next().map(|d| d.as_secs()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CommandReply {
+    accepted: bool,
+    command: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorReply {
+    error: String,
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (
+        status,
+        Json(ErrorReply {
+            error: message.into(),
+        }),
+    )
+        .into_response()
+}
+
+/// Queue `command` for the FSM; 202 once queued
+async fn send(state: &ServerState, command: AutomationCommand) -> Response {
+    let name = format!("{:?}", command);
+    match state.commands.send(command).await {
+        Ok(()) => (
+            StatusCode::ACCEPTED,
+            Json(CommandReply {
+                accepted: true,
+                command: name,
+            }),
+        )
+            .into_response(),
+        Err(_) => error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Automation is no longer running",
+        ),
+    }
+}
+
+async fn get_state(State(state): State<ServerState>) -> Json<StateView> {
+    let snap = state.snapshot.read().unwrap();
+    Json(StateView {
+        state: format!("{:?}", snap.state),
+        paused_by_touch: snap.paused_by_touch,
+        touch_timeout_remaining: snap.touch_timeout_remaining,
+        connection: snap.connection.clone(),
+        screenshot_status: snap.screenshot_status.clone(),
+        screenshot_counter: snap.screenshot_counter,
+        device: snap.device.as_ref().map(DeviceView::from),
+        telemetry: snap.telemetry.clone(),
+        health_pause: snap.health_pause.clone(),
+        recovery: snap.recovery.clone(),
+        sequence: snap.sequence_progress.clone(),
+        custom_state: snap.custom_state.clone(),
+        orientation: snap.orientation,
+        sleeping_until: snap.sleeping_until.clone(),
+        stuck: snap.stuck.clone(),
+    })
+}
+
+async fn start(State(state): State<ServerState>) -> Response {
+    send(&state, AutomationCommand::Start).await
+}
+
+async fn stop(State(state): State<ServerState>) -> Response {
+    send(&state, AutomationCommand::Stop).await
+}
+
+async fn pause(State(state): State<ServerState>) -> Response {
+    send(&state, AutomationCommand::Pause).await
+}
+
+async fn resume(State(state): State<ServerState>) -> Response {
+    send(&state, AutomationCommand::Resume).await
+}
+
+async fn list_events(State(state): State<ServerState>) -> Json<Vec<EventView>> {
+    let snap = state.snapshot.read().unwrap();
+    let mut events: Vec<EventView> = snap.timed_events.iter().map(EventView::from).collect();
+    events.sort_by(|a, b| a.id.cmp(&b.id));
+    Json(events)
+}
+
+async fn trigger_event(State(state): State<ServerState>, Path(id): Path<String>) -> Response {
+    let known = state
+        .snapshot
+        .read()
+        .unwrap()
+        .timed_events
+        .iter()
+        .any(|event| event.id == id);
+    if !known {
+        return error(
+            StatusCode::NOT_FOUND,
+            format!("Unknown timed event '{}'", id),
+        );
+    }
+    send(&state, AutomationCommand::TriggerTimedEvent(id)).await
+}
+
+async fn get_screenshot(State(state): State<ServerState>) -> Response {
+    let png = state.snapshot.read().unwrap().screenshot_png.clone();
+    match png {
+        Some(png) => ([(header::CONTENT_TYPE, "image/png")], png).into_response(),
+        None => error(StatusCode::NOT_FOUND, "No screenshot taken yet"),
+    }
+}
+
+async fn take_screenshot(State(state): State<ServerState>) -> Response {
+    send(&state, AutomationCommand::TakeScreenshot).await
+}
+
+#[cfg(feature = "metrics")]
+async fn get_metrics(State(state): State<ServerState>) -> Response {
+    let text = super::metrics::render(&state.snapshot.read().unwrap());
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], text).into_response()
+}
+
+async fn event_stream(ws: WebSocketUpgrade, State(state): State<ServerState>) -> Response {
+    let events = state.events.subscribe();
+    ws.on_upgrade(move |socket| forward_events(socket, events))
+}
+
+/// Send every automation event to one WebSocket client until it disconnects
+async fn forward_events(mut socket: WebSocket, mut events: broadcast::Receiver<AutomationEvent>) {
+    log::info!("🔗 Event stream client connected");
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let json = match serde_json::to_string(&event) {
+                        Ok(json) => json,
+                        Err(e) => {
+                            log::warn!("⚠️ Cannot encode {:?}: {}", event, e);
+                            continue;
+                        }
+                    };
+                    if socket.send(Message::Text(json.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    log::warn!("⚠️ Event stream client fell behind, skipped {} events", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                // Clients only listen; anything but a close is ignored
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    log::info!("🔗 Event stream client disconnected");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_event_view_from_timed_event() {
+        let mut tap = TimedEvent::new_tap_seconds("claim".into(), 540, 1200, 60);
+        tap.enabled = false;
+        let view = EventView::from(&tap);
+        assert_eq!(view.kind, "tap");
+        assert_eq!(
+            (view.x, view.y),
+            (Some(Coord::Px(540)), Some(Coord::Px(1200)))
+        );
+        assert_eq!(view.interval_secs, 60);
+        assert_eq!(view.next_in_secs, None);
+
+        let script = TimedEvent::new(
+            "farm".into(),
+            TimedEventType::Script {
+                name: "farm".into(),
+            },
+            Duration::from_secs(300),
+        );
+        let view = EventView::from(&script);
+        assert_eq!(view.kind, "script");
+        assert_eq!(view.script.as_deref(), Some("farm"));
+        assert_eq!(view.next_in_secs, Some(0));
+
+        let conditional = TimedEvent::new_conditional_tap(
+            "claim_if_visible".into(),
+            "claim_button".into(),
+            540,
+            1200,
+            Duration::from_secs(30),
+        );
+        let view = EventView::from(&conditional);
+        assert_eq!(view.kind, "conditional_tap");
+        assert_eq!(view.template.as_deref(), Some("claim_button"));
+        assert_eq!((view.x, view.y), (Some(540), Some(1200)));
+    }
+}
@@ -0,0 +1,249 @@
+/// Timing comparison of the patch matching strategies
+///
+/// Runs every patch against one screenshot with each strategy and tabulates
+/// the time per patch, for `--bench-match` and the criterion benchmarks in
+/// `benches/`, to see which optimization pays off on real screenshots.
+use super::{CancelToken, PatchInfo, SearchImage, TemplateMatcher};
+use image::RgbImage;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// Coarse pass factor for the pyramid strategy when `[matching] pyramid_factor` is off
+pub const DEFAULT_BENCH_PYRAMID_FACTOR: u32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Every position of the whole screenshot, one thread
+    FullScan,
+    /// Only around where each patch was cut (`patch_search_radius`), one thread
+    RegionLimited,
+    /// Whole screenshot, downscaled pass first, one thread
+    Pyramid,
+    /// Whole screenshot, rows of positions spread over all cores
+    MultiThreaded,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [
+        Strategy::FullScan,
+        Strategy::RegionLimited,
+        Strategy::Pyramid,
+        Strategy::MultiThreaded,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::FullScan => "full scan",
+            Strategy::RegionLimited => "region",
+            Strategy::Pyramid => "pyramid",
+            Strategy::MultiThreaded => "threads",
+        }
+    }
+}
+
+/// Matching settings shared by all strategies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchSettings {
+    pub threshold: f32,
+    /// Margin around each patch for the region-limited strategy
+    pub search_margin: u32,
+    pub pyramid_factor: u32,
+}
+
+impl Default for BenchSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 0.85,
+            search_margin: 50,
+            pyramid_factor: DEFAULT_BENCH_PYRAMID_FACTOR,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchTiming {
+    pub patch: String,
+    pub duration: Duration,
+    /// Best correlation at or above the threshold
+    pub correlation: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyTiming {
+    pub strategy: Strategy,
+    pub patches: Vec<PatchTiming>,
+}
+
+impl StrategyTiming {
+    pub fn total(&self) -> Duration {
+        self.patches.iter().map(|timing| timing.duration).sum()
+    }
+}
+
+/// Match every patch in `screenshot` with `strategy`, timing each one
+pub fn run_strategy(
+    strategy: Strategy,
+    screenshot: &RgbImage,
+    patches: &[PatchInfo],
+    settings: &BenchSettings,
+) -> Result<StrategyTiming, String> {
+    // A radius this large makes the search region the whole screenshot
+    let whole_screen = screenshot.width().max(screenshot.height());
+    let mut matcher = TemplateMatcher::new();
+    if strategy == Strategy::Pyramid {
+        matcher.set_pyramid_factor(settings.pyramid_factor);
+    }
+    for patch in patches {
+        let patch = match strategy {
+            Strategy::RegionLimited => patch.clone(),
+            _ => patch.clone().with_search_radius(Some(whole_screen)),
+        };
+        matcher.add_patch(patch);
+    }
+
+    // Grayscale conversion and sum tables are shared by all patches, as in the GUI
+    let image = SearchImage::new(screenshot);
+    let cancel = CancelToken::new();
+    let run = || {
+        matcher
+            .patches()
+            .iter()
+            .enumerate()
+            .map(|(idx, patch)| {
+                let start = Instant::now();
+                let matches = matcher.find_matches_in(
+                    &image,
+                    idx,
+                    settings.threshold,
+                    1,
+                    settings.search_margin,
+                    &cancel,
+                );
+                PatchTiming {
+                    patch: patch.display_name(),
+                    duration: start.elapsed(),
+                    correlation: matches.first().map(|m| m.correlation),
+                }
+            })
+            .collect()
+    };
+    let patches = if strategy == Strategy::MultiThreaded {
+        run()
+    } else {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .map_err(|e| format!("Failed to start a single-threaded pool: {}", e))?
+            .install(run)
+    };
+    Ok(StrategyTiming { strategy, patches })
+}
+
+/// Seconds with millisecond precision, plus `*` when the patch was found
+fn cell(timing: &PatchTiming) -> String {
+    format!(
+        "{:.3}s{}",
+        timing.duration.as_secs_f64(),
+        if timing.correlation.is_some() {
+            "*"
+        } else {
+            ""
+        }
+    )
+}
+
+/// One row per patch and a total row, one column per strategy, with the
+/// speedup over the first strategy underneath
+pub fn format_table(results: &[StrategyTiming]) -> String {
+    let Some(first) = results.first() else {
+        return String::new();
+    };
+    let name_width = first
+        .patches
+        .iter()
+        .map(|timing| timing.patch.len())
+        .chain(["speedup".len()])
+        .max()
+        .unwrap_or(0);
+    let column_width = 12;
+
+    let mut table = String::new();
+    let _ = write!(table, "{:<name_width$}", "patch");
+    for result in results {
+        let _ = write!(table, " {:>column_width$}", result.strategy.name());
+    }
+    table.push('\n');
+    table.push_str(&"-".repeat(name_width + results.len() * (column_width + 1)));
+    table.push('\n');
+
+    for (idx, timing) in first.patches.iter().enumerate() {
+        let _ = write!(table, "{:<name_width$}", timing.patch);
+        for result in results {
+            let value = result.patches.get(idx).map(cell).unwrap_or_default();
+            let _ = write!(table, " {:>column_width$}", value);
+        }
+        table.push('\n');
+    }
+
+    let _ = write!(table, "{:<name_width$}", "total");
+    for result in results {
+        let total = format!("{:.3}s", result.total().as_secs_f64());
+        let _ = write!(table, " {:>column_width$}", total);
+    }
+    table.push('\n');
+    let baseline = first.total().as_secs_f64();
+    let _ = write!(table, "{:<name_width$}", "speedup");
+    for result in results {
+        let total = result.total().as_secs_f64();
+        let speedup = if total > 0.0 {
+            format!("{:.1}x", baseline / total)
+        } else {
+            "-".to_string()
+        };
+        let _ = write!(table, " {:>column_width$}", speedup);
+    }
+    table.push('\n');
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strategies_find_the_patch_and_tabulate() {
+        let screenshot = RgbImage::from_fn(64, 48, |x, y| {
+            image::Rgb([(x * 4) as u8, (y * 5) as u8, (x * y) as u8])
+        });
+        let pixels = image::imageops::crop_imm(&screenshot, 20, 12, 16, 12)
+            .to_image()
+            .into_raw();
+        let patches = [PatchInfo::new(Some("icon".into()), 20, 12, 16, 12, pixels)];
+        let settings = BenchSettings {
+            search_margin: 8,
+            pyramid_factor: 2,
+            ..Default::default()
+        };
+
+        let results: Vec<StrategyTiming> = Strategy::ALL
+            .iter()
+            .map(|&strategy| run_strategy(strategy, &screenshot, &patches, &settings).unwrap())
+            .collect();
+        for result in &results {
+            assert_eq!(result.patches.len(), 1);
+            assert!(
+                result.patches[0].correlation.is_some(),
+                "{} missed the patch",
+                result.strategy.name()
+            );
+        }
+
+        let table = format_table(&results);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].contains("full scan") && lines[0].contains("threads"));
+        assert!(lines[2].starts_with("patch-icon-[20,12,16,12]") && lines[2].contains('*'));
+        assert!(lines[4].starts_with("speedup") && lines[4].contains("1.0x"));
+        assert!(format_table(&[]).is_empty());
+    }
+}
@@ -1,12 +1,205 @@
 /// Template matching implementation
 ///
-/// Optimized correlation-based matching with early exit optimization
-use super::types::{Match, PatchInfo};
-use image::RgbImage;
+/// Screenshots and patches are converted to grayscale once, together with
+/// summed-area tables of their pixels and squared pixels. The squared
+/// difference score is expanded into those sums and the cross-correlation
+/// of patch and screenshot, so most positions are ruled out from the tables
+/// alone and the rest stop as soon as the remaining rows can no longer reach
+/// the threshold. Rows of candidate positions are searched in parallel.
+use super::types::{CancelToken, Match, PatchInfo};
+use crate::game_automation::match_image::MatchConfig;
+use image::{GrayImage, RgbImage};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// Candidate positions refined at full resolution per patch
+pub const DEFAULT_MAX_CANDIDATES: usize = 8;
+/// How much lower the candidate pass threshold is, since downscaling and
+/// skipping positions blur alignment
+pub const DEFAULT_EARLY_EXIT_SLACK: f32 = 0.05;
+/// Largest squared difference of one grayscale pixel
+const MAX_PIXEL_SQ_DIFF: u64 = 255 * 255;
+
+/// Sums of pixel values and of squared pixel values over every rectangle
+/// anchored at the top-left corner, for constant-time window sums
+struct SummedArea {
+    stride: usize,
+    sum: Vec<u64>,
+    sum_sq: Vec<u64>,
+}
+
+impl SummedArea {
+    fn new(gray: &GrayImage) -> Self {
+        let width = gray.width() as usize;
+        let stride = width + 1;
+        let len = stride * (gray.height() as usize + 1);
+        let (mut sum, mut sum_sq) = (vec![0u64; len], vec![0u64; len]);
+        for (y, row) in gray.as_raw().chunks_exact(width.max(1)).enumerate() {
+            let (mut row_sum, mut row_sq) = (0u64, 0u64);
+            for (x, &value) in row.iter().enumerate() {
+                row_sum += value as u64;
+                row_sq += value as u64 * value as u64;
+                let at = (y + 1) * stride + x + 1;
+                sum[at] = sum[at - stride] + row_sum;
+                sum_sq[at] = sum_sq[at - stride] + row_sq;
+            }
+        }
+        Self {
+            stride,
+            sum,
+            sum_sq,
+        }
+    }
+
+    /// Sum and sum of squares of the `width`x`height` window at (`x`, `y`)
+    fn window(&self, x: u32, y: u32, width: u32, height: u32) -> (u64, u64) {
+        let (left, top) = (x as usize, y as usize);
+        let (right, bottom) = (left + width as usize, top + height as usize);
+        let area = |table: &[u64]| {
+            table[bottom * self.stride + right] + table[top * self.stride + left]
+                - table[top * self.stride + right]
+                - table[bottom * self.stride + left]
+        };
+        (area(&self.sum), area(&self.sum_sq))
+    }
+}
+
+/// Screenshot prepared for matching: grayscale with its summed-area tables,
+/// built once and shared by every patch searched in it
+pub struct SearchImage {
+    gray: GrayImage,
+    sums: SummedArea,
+    /// Downscaled copy for the coarse pass, with its factor
+    coarse: OnceLock<(u32, Arc<SearchImage>)>,
+}
+
+impl SearchImage {
+    pub fn new(image: &RgbImage) -> Self {
+        Self::from_gray(image::imageops::grayscale(image))
+    }
+
+    pub fn from_gray(gray: GrayImage) -> Self {
+        let sums = SummedArea::new(&gray);
+        Self {
+            gray,
+            sums,
+            coarse: OnceLock::new(),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.gray.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.gray.height()
+    }
+
+    /// This screenshot downscaled by `factor`, kept for the next patch
+    fn downscaled(&self, factor: u32) -> Arc<SearchImage> {
+        let build = || Arc::new(Self::from_gray(downscale(&self.gray, factor)));
+        let (cached_factor, coarse) = self.coarse.get_or_init(|| (factor, build()));
+        if *cached_factor == factor {
+            coarse.clone()
+        } else {
+            build()
+        }
+    }
+}
+
+/// Pixels of a patch that take part in scoring, with their count
+struct PatchMask {
+    image: GrayImage,
+    compared: u64,
+}
+
+impl PatchMask {
+    fn new(image: GrayImage) -> Self {
+        let compared = image.as_raw().iter().filter(|&&m| m != 0).count() as u64;
+        Self { image, compared }
+    }
+}
+
+/// Patch in grayscale with the sums its score needs, and its coarse copy
+struct PreparedPatch {
+    gray: GrayImage,
+    mask: Option<PatchMask>,
+    /// Sum of the compared pixels
+    sum: u64,
+    /// Sum of squares of the compared pixels in rows `r..` at index `r`
+    rows_sq: Vec<u64>,
+    /// Pyramid factor and the patch downscaled by it, when big enough for it
+    coarse: Option<(u32, Box<PreparedPatch>)>,
+}
+
+impl PreparedPatch {
+    fn new(gray: GrayImage, mask: Option<GrayImage>) -> Self {
+        let mask = mask.map(PatchMask::new);
+        let width = gray.width() as usize;
+        let mut sum = 0;
+        let mut rows_sq = vec![0u64; gray.height() as usize + 1];
+        for (index, &value) in gray.as_raw().iter().enumerate() {
+            if mask.as_ref().is_none_or(|m| m.image.as_raw()[index] != 0) {
+                sum += value as u64;
+                rows_sq[index / width] += value as u64 * value as u64;
+            }
+        }
+        for row in (0..rows_sq.len() - 1).rev() {
+            rows_sq[row] += rows_sq[row + 1];
+        }
+        Self {
+            gray,
+            mask,
+            sum,
+            rows_sq,
+            coarse: None,
+        }
+    }
+
+    fn from_patch(patch: &PatchInfo, factor: u32) -> Option<Self> {
+        let rgb = RgbImage::from_raw(patch.width, patch.height, patch.pixels.clone())?;
+        let gray = image::imageops::grayscale(&rgb);
+        let mask = patch
+            .mask
+            .as_ref()
+            .and_then(|m| GrayImage::from_raw(patch.width, patch.height, m.clone()));
+        let coarse =
+            (factor > 1 && patch.width >= factor * 2 && patch.height >= factor * 2).then(|| {
+                let small_mask = mask.as_ref().map(|m| downscale_mask(m, factor));
+                (
+                    factor,
+                    Box::new(Self::new(downscale(&gray, factor), small_mask)),
+                )
+            });
+        Some(Self {
+            coarse,
+            ..Self::new(gray, mask)
+        })
+    }
+
+    fn compared(&self) -> u64 {
+        match &self.mask {
+            Some(mask) => mask.compared,
+            None => (self.gray.width() * self.gray.height()) as u64,
+        }
+    }
+
+    fn sum_sq(&self) -> u64 {
+        self.rows_sq[0]
+    }
+}
 
 /// Template matcher for finding patches in images
 pub struct TemplateMatcher {
     patches: Vec<PatchInfo>,
+    /// Grayscale copies of `patches`, `None` when the pixels don't fill the patch
+    prepared: Vec<Option<PreparedPatch>>,
+    pyramid_factor: u32,
+    search_step: u32,
+    early_exit_slack: f32,
+    max_candidates: usize,
+    full_screen_fallback: bool,
 }
 
 impl TemplateMatcher {
@@ -14,11 +207,63 @@ impl TemplateMatcher {
     pub fn new() -> Self {
         Self {
             patches: Vec::new(),
+            prepared: Vec::new(),
+            pyramid_factor: 1,
+            search_step: 1,
+            early_exit_slack: DEFAULT_EARLY_EXIT_SLACK,
+            max_candidates: DEFAULT_MAX_CANDIDATES,
+            full_screen_fallback: false,
         }
     }
 
+    /// Create a matcher using the speed/accuracy settings from `config`
+    pub fn from_config(config: &MatchConfig) -> Self {
+        let mut matcher = Self::new();
+        matcher.set_pyramid_factor(config.pyramid_factor);
+        matcher.set_search_step(config.patch_search_step);
+        matcher.set_early_exit_slack(config.patch_early_exit_slack);
+        matcher.set_max_candidates(config.patch_max_candidates);
+        matcher.set_full_screen_fallback(config.patch_full_screen_fallback);
+        matcher
+    }
+
+    /// Search a screenshot downscaled by `factor` first, then refine candidates
+    /// at full resolution. A factor of 1 searches every position at full size.
+    pub fn set_pyramid_factor(&mut self, factor: u32) {
+        self.pyramid_factor = factor.max(1);
+        self.prepared = self
+            .patches
+            .iter()
+            .map(|patch| PreparedPatch::from_patch(patch, self.pyramid_factor))
+            .collect();
+    }
+
+    /// Score only every `step`-th position first, then refine candidates
+    /// around them (1 = every position). Ignored with a pyramid factor.
+    pub fn set_search_step(&mut self, step: u32) {
+        self.search_step = step.max(1);
+    }
+
+    /// How far below the match threshold the coarse or strided pass still keeps
+    /// a position as a candidate; lower values give up on positions sooner
+    pub fn set_early_exit_slack(&mut self, slack: f32) {
+        self.early_exit_slack = slack.clamp(0.0, 1.0);
+    }
+
+    /// Best coarse or strided candidates rescored at full resolution
+    pub fn set_max_candidates(&mut self, max: usize) {
+        self.max_candidates = max.max(1);
+    }
+
+    /// Search the whole screenshot when a patch is not found near its position
+    pub fn set_full_screen_fallback(&mut self, enabled: bool) {
+        self.full_screen_fallback = enabled;
+    }
+
     /// Add a patch to the matcher
     pub fn add_patch(&mut self, patch: PatchInfo) {
+        self.prepared
+            .push(PreparedPatch::from_patch(&patch, self.pyramid_factor));
         self.patches.push(patch);
     }
 
@@ -30,19 +275,21 @@ impl TemplateMatcher {
     /// Clear all patches
     pub fn clear(&mut self) {
         self.patches.clear();
+        self.prepared.clear();
     }
 
     /// Find best matches for a patch in an image
     ///
     /// Uses localized search around the expected patch coordinates for speed.
-    /// Falls back to full image search if no expected location is provided.
+    /// Rows of candidate positions are scored in parallel with rayon.
     ///
     /// # Arguments
     /// * `image_rgb` - The image to search in (RGB format)
     /// * `patch_idx` - Index of the patch to find
     /// * `threshold` - Correlation threshold (0.0-1.0)
     /// * `max_matches` - Maximum number of matches to return
-    /// * `search_margin` - Search region margin around expected position (±N pixels)
+    /// * `search_margin` - Search region margin around expected position (±N pixels),
+    ///   unless the patch sets its own `search_radius`
     ///
     /// # Returns
     /// Vec of matches sorted by correlation (highest first)
@@ -54,140 +301,400 @@ impl TemplateMatcher {
         max_matches: usize,
         search_margin: u32,
     ) -> Vec<Match> {
-        if patch_idx >= self.patches.len() {
-            return Vec::new();
-        }
-
-        let patch = &self.patches[patch_idx];
-        let image_width = image_rgb.width();
-        let image_height = image_rgb.height();
+        self.find_matches_in(
+            &SearchImage::new(image_rgb),
+            patch_idx,
+            threshold,
+            max_matches,
+            search_margin,
+            &CancelToken::new(),
+        )
+    }
 
-        // Convert patch pixels to RgbImage
-        let patch_img = match RgbImage::from_raw(patch.width, patch.height, patch.pixels.clone()) {
-            Some(img) => img,
-            None => return Vec::new(),
+    /// `find_matches` on a screenshot prepared once for all patches, giving up
+    /// once `cancel` is set (checked for every row of candidate positions).
+    /// A cancelled search returns no matches.
+    pub fn find_matches_in(
+        &self,
+        image: &SearchImage,
+        patch_idx: usize,
+        threshold: f32,
+        max_matches: usize,
+        search_margin: u32,
+        cancel: &CancelToken,
+    ) -> Vec<Match> {
+        let (Some(patch), Some(Some(prepared))) =
+            (self.patches.get(patch_idx), self.prepared.get(patch_idx))
+        else {
+            return Vec::new();
         };
+        let image_width = image.width();
+        let image_height = image.height();
+        if patch.width > image_width || patch.height > image_height {
+            return Vec::new();
+        }
 
         // Use localized search around expected position (much faster)
-        let x_min = patch.orig_x.saturating_sub(search_margin);
-        let x_max = (patch.orig_x + patch.width + search_margin)
-            .min(image_width.saturating_sub(patch.width));
-        let y_min = patch.orig_y.saturating_sub(search_margin);
-        let y_max = (patch.orig_y + patch.height + search_margin)
-            .min(image_height.saturating_sub(patch.height));
-
-        let mut matches: Vec<Match> = Vec::new();
-
-        // Calculate total positions for progress reporting
-        let total_y = (y_max - y_min + 1) as usize;
-        let total_x = (x_max - x_min + 1) as usize;
-        let total_positions = total_y * total_x;
-        let report_interval = (total_positions / 10).max(1); // Report every 10%
-        let mut position_count = 0;
-
-        // Search through possible positions in localized region
-        for y in y_min..=y_max {
-            for x in x_min..=x_max {
-                // Extract region from image
-                if let Some(region) =
-                    self.extract_region(image_rgb, x, y, patch.width, patch.height)
-                {
-                    let corr = self.calculate_correlation(&patch_img, &region, threshold);
-
-                    if corr >= threshold {
-                        matches.push(Match {
-                            x,
-                            y,
-                            correlation: corr,
-                        });
-                    }
-                }
+        let radius = patch.search_radius.unwrap_or(search_margin);
+        let region = patch.search_region(radius, image_width, image_height);
+        let x_range = (
+            region.x,
+            region.x + region.width.saturating_sub(patch.width),
+        );
+        let y_range = (
+            region.y,
+            region.y + region.height.saturating_sub(patch.height),
+        );
+        let full_x = (0, image_width - patch.width);
+        let full_y = (0, image_height - patch.height);
 
-                position_count += 1;
-                if position_count % report_interval == 0 {
-                    let progress_pct =
-                        (position_count as f32 / total_positions as f32 * 100.0) as u32;
-                    log::debug!("  ⏳ Localized search: {}%", progress_pct);
-                }
-            }
+        let search = |x_range: (u32, u32), y_range: (u32, u32)| {
+            self.search(image, prepared, x_range, y_range, threshold, cancel)
+        };
+        let fits = region.width >= patch.width && region.height >= patch.height;
+        let mut matches = if fits {
+            search(x_range, y_range)
+        } else {
+            Vec::new()
+        };
+        if matches.is_empty()
+            && self.full_screen_fallback
+            && (x_range, y_range) != (full_x, full_y)
+            && !cancel.is_cancelled()
+        {
+            log::debug!(
+                "🔎 {} not within {}px of its position, searching the whole screenshot",
+                patch.display_name(),
+                radius
+            );
+            matches = search(full_x, full_y);
+        }
+        if cancel.is_cancelled() {
+            log::debug!("⏹️ Search for {} cancelled", patch.display_name());
+            return Vec::new();
         }
 
         // Sort by correlation descending
-        matches.sort_by(|a, b| b.correlation.partial_cmp(&a.correlation).unwrap());
+        matches.sort_by(|a, b| b.correlation.total_cmp(&a.correlation));
         matches.truncate(max_matches);
 
         matches
     }
 
-    /// Extract a region from an image
-    fn extract_region(
+    /// Score every position in the window, or find candidates on a downscaled
+    /// copy (pyramid factor) or every `search_step`-th position first and
+    /// rescore only around the best of them at full resolution
+    fn search(
         &self,
-        image: &RgbImage,
-        x: u32,
-        y: u32,
-        width: u32,
-        height: u32,
-    ) -> Option<RgbImage> {
-        if x + width > image.width() || y + height > image.height() {
-            return None;
-        }
+        image: &SearchImage,
+        patch: &PreparedPatch,
+        (x_min, x_max): (u32, u32),
+        (y_min, y_max): (u32, u32),
+        threshold: f32,
+        cancel: &CancelToken,
+    ) -> Vec<Match> {
+        let candidate_threshold = (threshold - self.early_exit_slack).max(0.0);
+        let (mut candidates, spread) = match &patch.coarse {
+            Some((factor, coarse_patch)) => {
+                let factor = *factor;
+                let small_image = image.downscaled(factor);
+                let coarse_x_max = small_image
+                    .width()
+                    .saturating_sub(coarse_patch.gray.width());
+                let coarse_y_max = small_image
+                    .height()
+                    .saturating_sub(coarse_patch.gray.height());
+                let coarse = search_window(
+                    &small_image,
+                    coarse_patch,
+                    (
+                        (x_min / factor).min(coarse_x_max),
+                        (x_max / factor).min(coarse_x_max),
+                    ),
+                    (
+                        (y_min / factor).min(coarse_y_max),
+                        (y_max / factor).min(coarse_y_max),
+                    ),
+                    1,
+                    candidate_threshold,
+                    cancel,
+                );
+                let candidates = coarse
+                    .into_iter()
+                    .map(|m| Match {
+                        x: m.x * factor,
+                        y: m.y * factor,
+                        ..m
+                    })
+                    .collect();
+                (candidates, factor)
+            }
+            None if self.search_step > 1 => {
+                let step = self.search_step;
+                let candidates = search_window(
+                    image,
+                    patch,
+                    (x_min, x_max),
+                    (y_min, y_max),
+                    step,
+                    candidate_threshold,
+                    cancel,
+                );
+                (candidates, step - 1)
+            }
+            None => {
+                return search_window(
+                    image,
+                    patch,
+                    (x_min, x_max),
+                    (y_min, y_max),
+                    1,
+                    threshold,
+                    cancel,
+                );
+            }
+        };
 
-        let mut region = RgbImage::new(width, height);
-        for dy in 0..height {
-            for dx in 0..width {
-                if let Some(pixel) = image.get_pixel_checked(x + dx, y + dy) {
-                    region.put_pixel(dx, dy, *pixel);
-                } else {
-                    return None;
+        candidates.sort_by(|a, b| b.correlation.total_cmp(&a.correlation));
+        candidates.truncate(self.max_candidates);
+        log::debug!(
+            "  🔬 Candidate pass (±{}px): refining {} candidate(s)",
+            spread,
+            candidates.len()
+        );
+
+        let mut matches: Vec<Match> = candidates
+            .iter()
+            .flat_map(|candidate| {
+                let x_range = (
+                    candidate.x.saturating_sub(spread).max(x_min),
+                    (candidate.x + spread).min(x_max),
+                );
+                let y_range = (
+                    candidate.y.saturating_sub(spread).max(y_min),
+                    (candidate.y + spread).min(y_max),
+                );
+                if x_range.0 > x_range.1 || y_range.0 > y_range.1 {
+                    return Vec::new();
                 }
-            }
-        }
+                search_window(image, patch, x_range, y_range, 1, threshold, cancel)
+            })
+            .collect();
 
-        Some(region)
+        // Refinement windows of neighbouring candidates overlap
+        matches.sort_by_key(|m| (m.y, m.x));
+        matches.dedup_by_key(|m| (m.x, m.y));
+        matches
     }
+}
 
-    /// Calculate normalized correlation between patch and region
-    ///
-    /// Uses sum of squared differences normalized to 0.0-1.0 range
-    fn calculate_correlation(&self, patch: &RgbImage, region: &RgbImage, min_match: f32) -> f32 {
-        if patch.width() != region.width() || patch.height() != region.height() {
-            return 0.0;
-        }
+/// Score every `step`-th position in the inclusive `x_range` x `y_range`
+/// window, rows in parallel
+fn search_window(
+    image: &SearchImage,
+    patch: &PreparedPatch,
+    (x_min, x_max): (u32, u32),
+    (y_min, y_max): (u32, u32),
+    step: u32,
+    threshold: f32,
+    cancel: &CancelToken,
+) -> Vec<Match> {
+    let step = step.max(1) as usize;
+    let rows: Vec<u32> = (y_min..=y_max).step_by(step).collect();
+    // Progress is reported per finished row, every ~10%
+    let total_rows = rows.len();
+    let report_interval = (total_rows / 10).max(1);
+    let rows_done = AtomicUsize::new(0);
+
+    rows.into_par_iter()
+        .flat_map_iter(|y| {
+            if cancel.is_cancelled() {
+                return Vec::new();
+            }
+            let row_matches: Vec<Match> = (x_min..=x_max)
+                .step_by(step)
+                .filter_map(|x| {
+                    let corr = score_at(image, patch, x, y, threshold);
+                    (corr >= threshold).then_some(Match {
+                        x,
+                        y,
+                        correlation: corr,
+                    })
+                })
+                .collect();
+
+            let done = rows_done.fetch_add(1, Ordering::Relaxed) + 1;
+            if done % report_interval == 0 {
+                let progress_pct = (done as f32 / total_rows as f32 * 100.0) as u32;
+                log::debug!("  ⏳ Localized search: {}%", progress_pct);
+            }
+            row_matches
+        })
+        .collect()
+}
 
-        let pixel_count = (patch.width() * patch.height()) as usize;
-        if pixel_count == 0 {
-            return 0.0;
+/// Box-filter downscale by an integer factor (partial edge blocks are dropped)
+fn downscale(image: &GrayImage, factor: u32) -> GrayImage {
+    let width = (image.width() / factor).max(1);
+    let height = (image.height() / factor).max(1);
+    let block = factor.min(image.width()).min(image.height()).max(1);
+    let area = block * block;
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let mut sum = 0u32;
+        for dy in 0..block {
+            for dx in 0..block {
+                sum += image.get_pixel(x * factor + dx, y * factor + dy)[0] as u32;
+            }
         }
+        image::Luma([(sum / area) as u8])
+    })
+}
 
-        // Calculate max possible difference (all pixels completely different)
-        // Each pixel has 3 channels (R,G,B), max difference per channel is 255
-        let max_sq_diff = (pixel_count as f64) * 3.0 * (255.0 * 255.0);
-        let max_allowed_diff = max_sq_diff * (1.0 - min_match as f64);
-
-        let mut sum_sq_diff = 0.0;
-        let mut checked_pixels = 0;
+/// Downscale a mask like `downscale`; a block counts when most of it is compared
+fn downscale_mask(mask: &GrayImage, factor: u32) -> GrayImage {
+    let width = (mask.width() / factor).max(1);
+    let height = (mask.height() / factor).max(1);
+    let block = factor.min(mask.width()).min(mask.height()).max(1);
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let mut on = 0;
+        for dy in 0..block {
+            for dx in 0..block {
+                if mask.get_pixel(x * factor + dx, y * factor + dy)[0] != 0 {
+                    on += 1;
+                }
+            }
+        }
+        image::Luma([if on * 2 >= block * block { 255 } else { 0 }])
+    })
+}
 
-        for (p_pixel, r_pixel) in patch.pixels().zip(region.pixels()) {
-            let p_data = p_pixel.0;
-            let r_data = r_pixel.0;
+/// Correlation of `patch` against `image` with its top-left corner at (`x`, `y`)
+///
+/// 1.0 minus the squared difference relative to its largest possible value,
+/// or 0.0 as soon as it is clear `min_match` can't be reached.
+/// Pixels where the patch mask is 0 are left out of the score.
+fn score_at(image: &SearchImage, patch: &PreparedPatch, x: u32, y: u32, min_match: f32) -> f32 {
+    let (patch_width, patch_height) = patch.gray.dimensions();
+    if x + patch_width > image.width() || y + patch_height > image.height() {
+        return 0.0;
+    }
+    let pixel_count = patch.compared();
+    if pixel_count == 0 {
+        return 0.0;
+    }
 
-            for i in 0..3 {
-                let diff = (p_data[i] as i32) - (r_data[i] as i32);
-                sum_sq_diff += (diff * diff) as f64;
-            }
+    let max_sq_diff = pixel_count * MAX_PIXEL_SQ_DIFF;
+    let max_allowed_diff = max_sq_diff as f64 * (1.0 - min_match.clamp(0.0, 1.0) as f64);
+    let sum_sq_diff = match &patch.mask {
+        Some(mask) => masked_sq_diff(image, patch, mask, x, y, max_allowed_diff),
+        None => table_sq_diff(image, patch, x, y, max_allowed_diff),
+    };
+    match sum_sq_diff {
+        Some(diff) => (1.0 - diff as f64 / max_sq_diff as f64).clamp(0.0, 1.0) as f32,
+        None => 0.0,
+    }
+}
 
-            checked_pixels += 1;
+/// Squared difference as `Σimage² + Σpatch² - 2·Σimage·patch`, the first two
+/// from the tables; `None` once it must exceed `max_allowed`
+fn table_sq_diff(
+    image: &SearchImage,
+    patch: &PreparedPatch,
+    x: u32,
+    y: u32,
+    max_allowed: f64,
+) -> Option<u64> {
+    let (width, height) = patch.gray.dimensions();
+    let (window_sum, window_sq) = image.sums.window(x, y, width, height);
+    let patch_sq = patch.sum_sq();
+
+    // From the sums alone: Σd² >= (Σd)²/n, and Σd² >= (|image| - |patch|)²
+    let mean_bound = (window_sum as f64 - patch.sum as f64).powi(2) / patch.compared() as f64;
+    let norm_bound = ((window_sq as f64).sqrt() - (patch_sq as f64).sqrt()).powi(2);
+    // The bounds are rounded floats; the real difference is an integer
+    let bound_limit = max_allowed + 1.0;
+    if mean_bound.max(norm_bound) > bound_limit {
+        return None;
+    }
 
-            // Early exit optimization: periodically check if threshold can still be met
-            if checked_pixels % 1000 == 0 && sum_sq_diff > max_allowed_diff {
-                return 0.0;
+    let total = window_sq + patch_sq;
+    let row_len = width as usize;
+    let stride = image.width() as usize;
+    let image_raw = image.gray.as_raw();
+    let offset = y as usize * stride + x as usize;
+
+    let mut cross = 0u64;
+    for (row, patch_row) in patch.gray.as_raw().chunks_exact(row_len).enumerate() {
+        let start = offset + row * stride;
+        cross += row_dot(patch_row, &image_raw[start..start + row_len]);
+
+        // Early exit: even a perfect fit of the remaining rows (Cauchy-Schwarz)
+        // would leave too large a difference
+        let done = row as u32 + 1;
+        if done < height {
+            let (_, rest_sq) = image.sums.window(x, y + done, width, height - done);
+            let best_rest = (rest_sq as f64 * patch.rows_sq[done as usize] as f64).sqrt();
+            if total as f64 - 2.0 * (cross as f64 + best_rest) > bound_limit {
+                return None;
             }
         }
+    }
+    let sum_sq_diff = total.saturating_sub(2 * cross);
+    (sum_sq_diff as f64 <= max_allowed).then_some(sum_sq_diff)
+}
 
-        // Convert to 0.0-1.0 correlation score
-        let correlation = 1.0 - (sum_sq_diff / max_sq_diff);
-        correlation.clamp(0.0, 1.0) as f32
+/// Squared difference over the compared pixels only, pixel by pixel, since
+/// window sums would include the ignored ones
+fn masked_sq_diff(
+    image: &SearchImage,
+    patch: &PreparedPatch,
+    mask: &PatchMask,
+    x: u32,
+    y: u32,
+    max_allowed: f64,
+) -> Option<u64> {
+    let row_len = patch.gray.width() as usize;
+    let stride = image.width() as usize;
+    let image_raw = image.gray.as_raw();
+    let offset = y as usize * stride + x as usize;
+
+    let mut sum_sq_diff = 0u64;
+    let rows = patch
+        .gray
+        .as_raw()
+        .chunks_exact(row_len)
+        .zip(mask.image.as_raw().chunks_exact(row_len));
+    for (row, (patch_row, mask_row)) in rows.enumerate() {
+        let start = offset + row * stride;
+        sum_sq_diff += row_sq_diff_masked(patch_row, &image_raw[start..start + row_len], mask_row);
+
+        // Early exit optimization: stop once the threshold can no longer be met
+        if sum_sq_diff as f64 > max_allowed {
+            return None;
+        }
     }
+    Some(sum_sq_diff)
+}
+
+/// Σ a·b over a row; a plain integer loop the compiler vectorizes
+#[inline]
+fn row_dot(a: &[u8], b: &[u8]) -> u64 {
+    a.iter().zip(b).map(|(&p, &r)| p as u64 * r as u64).sum()
+}
+
+/// Sum of squared differences over the pixels whose mask byte is non-zero
+fn row_sq_diff_masked(a: &[u8], b: &[u8], mask: &[u8]) -> u64 {
+    a.iter()
+        .zip(b)
+        .zip(mask)
+        .filter(|(_, m)| **m != 0)
+        .map(|((&p, &r), _)| {
+            let diff = p as i32 - r as i32;
+            (diff * diff) as u64
+        })
+        .sum()
 }
 
 impl Default for TemplateMatcher {
@@ -223,12 +730,12 @@ mod tests {
 
     #[test]
     fn test_correlation_perfect_match() {
-        let matcher = TemplateMatcher::new();
         let pixels = vec![100u8; 300]; // 10x10 RGB = 300 bytes
         let patch = RgbImage::from_raw(10, 10, pixels.clone()).unwrap();
         let region = RgbImage::from_raw(10, 10, pixels).unwrap();
+        let patch = PreparedPatch::new(image::imageops::grayscale(&patch), None);
 
-        let corr = matcher.calculate_correlation(&patch, &region, 0.9);
+        let corr = score_at(&SearchImage::new(&region), &patch, 0, 0, 0.9);
         assert!(
             corr >= 0.99,
             "Perfect match should have correlation >= 0.99"
@@ -236,12 +743,234 @@ mod tests {
     }
 
     #[test]
-    fn test_size_mismatch() {
-        let matcher = TemplateMatcher::new();
+    fn test_patch_past_the_image_edge() {
         let patch = RgbImage::from_raw(10, 10, vec![100u8; 300]).unwrap();
-        let region = RgbImage::from_raw(20, 20, vec![100u8; 1200]).unwrap();
+        let patch = PreparedPatch::new(image::imageops::grayscale(&patch), None);
+        let image = SearchImage::new(&RgbImage::from_raw(20, 20, vec![100u8; 1200]).unwrap());
+
+        assert!(score_at(&image, &patch, 10, 10, 0.9) >= 0.99);
+        assert_eq!(
+            score_at(&image, &patch, 11, 10, 0.9),
+            0.0,
+            "A patch reaching past the image should return 0.0"
+        );
+    }
+
+    #[test]
+    fn test_find_matches_locates_shifted_patch() {
+        // Gradient image so every position has a distinct neighbourhood
+        let image = RgbImage::from_fn(200, 150, |x, y| {
+            image::Rgb([
+                (x % 256) as u8,
+                (y % 256) as u8,
+                ((x * 7 + y * 3) % 256) as u8,
+            ])
+        });
+        let (px, py, size) = (60, 40, 12);
+        let mut pixels = Vec::new();
+        for y in py..py + size {
+            for x in px..px + size {
+                pixels.extend_from_slice(&image.get_pixel(x, y).0);
+            }
+        }
+
+        // Expected position is off by a few pixels; the search margin covers it
+        let mut matcher = TemplateMatcher::new();
+        matcher.add_patch(PatchInfo::new(None, px - 5, py + 3, size, size, pixels));
+        let matches = matcher.find_matches(&image, 0, 0.99, 1, 10);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].x, matches[0].y), (px, py));
+        assert!(matches[0].correlation >= 0.999);
+    }
+
+    #[test]
+    fn test_search_radius_and_full_screen_fallback() {
+        let image = RgbImage::from_fn(200, 150, |x, y| {
+            image::Rgb([
+                (x % 256) as u8,
+                (y % 256) as u8,
+                ((x * 7 + y * 3) % 256) as u8,
+            ])
+        });
+        let (px, py, size) = (150, 100, 12);
+        let mut pixels = Vec::new();
+        for y in py..py + size {
+            for x in px..px + size {
+                pixels.extend_from_slice(&image.get_pixel(x, y).0);
+            }
+        }
+
+        // Cut at (20,20), now 130px away: outside a 10px radius
+        let patch = PatchInfo::new(None, 20, 20, size, size, pixels);
+        let mut matcher = TemplateMatcher::new();
+        matcher.add_patch(patch.clone());
+        assert!(matcher.find_matches(&image, 0, 0.99, 1, 10).is_empty());
+
+        matcher.set_full_screen_fallback(true);
+        let matches = matcher.find_matches(&image, 0, 0.99, 1, 10);
+        assert_eq!((matches[0].x, matches[0].y), (px, py));
+
+        // A per-patch radius wins over the margin passed in
+        let mut matcher = TemplateMatcher::new();
+        matcher.add_patch(patch.with_search_radius(Some(140)));
+        assert_eq!(matcher.find_matches(&image, 0, 0.99, 1, 10).len(), 1);
+    }
+
+    #[test]
+    fn test_coarse_to_fine_matches_full_search() {
+        let image = RgbImage::from_fn(240, 180, |x, y| {
+            image::Rgb([
+                ((x * 3) % 256) as u8,
+                ((y * 5) % 256) as u8,
+                ((x + y) % 256) as u8,
+            ])
+        });
+        let (px, py, size) = (101, 57, 24);
+        let mut pixels = Vec::new();
+        for y in py..py + size {
+            for x in px..px + size {
+                pixels.extend_from_slice(&image.get_pixel(x, y).0);
+            }
+        }
+
+        let mut matcher = TemplateMatcher::new();
+        matcher.add_patch(PatchInfo::new(None, px + 6, py - 7, size, size, pixels));
+        let full = matcher.find_matches(&image, 0, 0.98, 1, 20);
+
+        matcher.set_pyramid_factor(4);
+        let coarse = matcher.find_matches(&image, 0, 0.98, 1, 20);
+
+        assert_eq!((full[0].x, full[0].y), (px, py));
+        assert_eq!((coarse[0].x, coarse[0].y), (px, py));
+        assert_eq!(full[0].correlation, coarse[0].correlation);
+    }
+
+    #[test]
+    fn test_strided_search_refines_to_exact_position() {
+        let image = RgbImage::from_fn(200, 150, |x, y| {
+            image::Rgb([x as u8, (y * 2 % 256) as u8, 60])
+        });
+        let (px, py, size) = (83, 47, 16);
+        let pixels = image::imageops::crop_imm(&image, px, py, size, size)
+            .to_image()
+            .into_raw();
+
+        let mut matcher = TemplateMatcher::new();
+        matcher.add_patch(PatchInfo::new(None, px - 9, py + 4, size, size, pixels));
+        matcher.set_search_step(5);
+        matcher.set_max_candidates(1);
+        let strided = matcher.find_matches(&image, 0, 0.99, 1, 20);
+
+        assert_eq!((strided[0].x, strided[0].y), (px, py));
+        assert!(strided[0].correlation >= 0.999);
+    }
+
+    #[test]
+    fn test_cancelled_search_returns_no_matches() {
+        let image = RgbImage::from_fn(120, 90, |x, y| {
+            image::Rgb([(x * 2 % 256) as u8, (y * 3 % 256) as u8, 40])
+        });
+        let (px, py, size) = (40, 30, 16);
+        let pixels = image::imageops::crop_imm(&image, px, py, size, size)
+            .to_image()
+            .into_raw();
+        let mut matcher = TemplateMatcher::new();
+        matcher.add_patch(PatchInfo::new(None, px, py, size, size, pixels));
+        matcher.set_full_screen_fallback(true);
+
+        let search_image = SearchImage::new(&image);
+        let cancel = CancelToken::new();
+        assert_eq!(
+            matcher
+                .find_matches_in(&search_image, 0, 0.99, 1, 10, &cancel)
+                .len(),
+            1
+        );
+
+        // Cancelling through a clone stops the original's searches too
+        cancel.clone().cancel();
+        assert!(cancel.is_cancelled());
+        assert!(
+            matcher
+                .find_matches_in(&search_image, 0, 0.99, 1, 10, &cancel)
+                .is_empty()
+        );
+        matcher.set_pyramid_factor(4);
+        assert!(
+            matcher
+                .find_matches_in(&search_image, 0, 0.99, 1, 10, &cancel)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_table_score_matches_direct_squared_difference() {
+        let image = RgbImage::from_fn(64, 48, |x, y| {
+            image::Rgb([
+                (x * 13 % 256) as u8,
+                (y * 29 % 256) as u8,
+                ((x ^ y) * 7) as u8,
+            ])
+        });
+        let search_image = SearchImage::new(&image);
+        let patch_rgb = image::imageops::crop_imm(&image, 17, 9, 11, 7).to_image();
+        let patch = PreparedPatch::new(image::imageops::grayscale(&patch_rgb), None);
+
+        for (x, y) in [(17, 9), (0, 0), (30, 20), (53, 41), (18, 9)] {
+            let direct: u64 = (0..7)
+                .flat_map(|dy| (0..11).map(move |dx| (dx, dy)))
+                .map(|(dx, dy)| {
+                    let a = search_image.gray.get_pixel(x + dx, y + dy)[0] as i64;
+                    let b = patch.gray.get_pixel(dx, dy)[0] as i64;
+                    ((a - b) * (a - b)) as u64
+                })
+                .sum();
+            let expected = 1.0 - direct as f64 / (77 * MAX_PIXEL_SQ_DIFF) as f64;
+            let score = score_at(&search_image, &patch, x, y, 0.0);
+            assert!(
+                (score as f64 - expected).abs() < 1e-6,
+                "({}, {}): {} vs {}",
+                x,
+                y,
+                score,
+                expected
+            );
+        }
+        assert_eq!(score_at(&search_image, &patch, 17, 9, 1.0), 1.0);
+        // Positions that can't reach `min_match` score 0.0
+        assert_eq!(score_at(&search_image, &patch, 0, 0, 0.999), 0.0);
+    }
+
+    #[test]
+    fn test_find_matches_ignores_masked_pixels() {
+        let image = RgbImage::from_fn(80, 60, |x, y| {
+            image::Rgb([(x * 3 % 256) as u8, (y * 4 % 256) as u8, 90])
+        });
+        let (px, py, size) = (30, 20, 10);
+        // Patch copied from the image, except for a border painted white
+        let mut pixels = Vec::new();
+        let mut mask = Vec::new();
+        for y in 0..size {
+            for x in 0..size {
+                let border = x == 0 || y == 0 || x == size - 1 || y == size - 1;
+                let pixel = if border {
+                    [255, 255, 255]
+                } else {
+                    image.get_pixel(px + x, py + y).0
+                };
+                pixels.extend_from_slice(&pixel);
+                mask.push(if border { 0 } else { 255 });
+            }
+        }
+
+        let mut matcher = TemplateMatcher::new();
+        matcher.add_patch(PatchInfo::new(None, px, py, size, size, pixels.clone()));
+        matcher.add_patch(PatchInfo::new(None, px, py, size, size, pixels).with_mask(Some(mask)));
 
-        let corr = matcher.calculate_correlation(&patch, &region, 0.9);
-        assert_eq!(corr, 0.0, "Size mismatch should return 0.0");
+        assert!(matcher.find_matches(&image, 0, 0.95, 1, 5).is_empty());
+        let masked = matcher.find_matches(&image, 1, 0.95, 1, 5);
+        assert_eq!((masked[0].x, masked[0].y), (px, py));
+        assert!(masked[0].correlation >= 0.999);
     }
 }
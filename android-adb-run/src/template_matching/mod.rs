@@ -1,12 +1,20 @@
 /// Template matching module for patch detection in screenshots
 ///
 /// This module provides efficient template matching with:
+/// - Grayscale screenshots with summed-area tables, prepared once per screenshot
 /// - Early exit optimization for non-matching patches
+/// - Parallel (rayon) search over candidate rows
+/// - Optional coarse-to-fine pass on a downscaled screenshot
 /// - Localized search around expected positions
 /// - Progress reporting for long operations
+/// - Cancellation of superseded searches (`CancelToken`)
 /// - Correlation-based matching with configurable thresholds
+/// - Timing comparison of the search strategies (`bench`)
+pub mod bench;
 pub mod matcher;
 pub mod types;
 
-pub use matcher::TemplateMatcher;
-pub use types::{Match, PatchInfo};
+pub use matcher::{SearchImage, TemplateMatcher};
+pub use types::{
+    CancelToken, Match, PatchInfo, load_patches, parse_patch_filename, sanitize_label,
+};
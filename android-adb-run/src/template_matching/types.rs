@@ -1,4 +1,9 @@
 /// Template matching data types
+use crate::game_automation::match_image::{SearchRegion, alpha_mask};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 /// Information about a single patch
 #[derive(Clone, Debug)]
 pub struct PatchInfo {
@@ -14,6 +19,10 @@ pub struct PatchInfo {
     pub height: u32,
     /// Raw pixel data (RGB format)
     pub pixels: Vec<u8>,
+    /// One byte per pixel from the PNG alpha channel; 0 = ignored when matching
+    pub mask: Option<Vec<u8>>,
+    /// Pixels around the original position to search, overriding the matcher's margin
+    pub search_radius: Option<u32>,
 }
 
 /// A single match result
@@ -27,6 +36,24 @@ pub struct Match {
     pub correlation: f32,
 }
 
+/// Shared flag that stops an in-flight match; clones cancel together
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
 impl PatchInfo {
     /// Create a new patch from filename and pixel data
     pub fn new(
@@ -44,7 +71,85 @@ impl PatchInfo {
             width,
             height,
             pixels,
+            mask: None,
+            search_radius: None,
+        }
+    }
+
+    /// Search only `radius` pixels around the original position (`None` = matcher default)
+    pub fn with_search_radius(mut self, radius: Option<u32>) -> Self {
+        self.search_radius = radius;
+        self
+    }
+
+    /// Area of a `image_width`x`image_height` screenshot the patch may sit in
+    /// when it moved at most `radius` pixels from where it was cut
+    pub fn search_region(&self, radius: u32, image_width: u32, image_height: u32) -> SearchRegion {
+        let x = self.orig_x.saturating_sub(radius).min(image_width);
+        let y = self.orig_y.saturating_sub(radius).min(image_height);
+        let right = (self.orig_x + self.width + radius).min(image_width);
+        let bottom = (self.orig_y + self.height + radius).min(image_height);
+        SearchRegion::new(
+            x,
+            y,
+            right.saturating_sub(x),
+            bottom.saturating_sub(y),
+            self.display_name(),
+        )
+    }
+
+    /// Ignore pixels where `mask` (one byte per pixel) is 0
+    pub fn with_mask(mut self, mask: Option<Vec<u8>>) -> Self {
+        self.mask = mask.filter(|m| m.len() == (self.width * self.height) as usize);
+        self
+    }
+
+    /// Crop the `width`x`height` region at (`x`,`y`) out of an encoded screenshot
+    pub fn from_screenshot(
+        screenshot: &[u8],
+        label: Option<String>,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, String> {
+        let image = image::load_from_memory(screenshot)
+            .map_err(|e| format!("Failed to decode screenshot: {}", e))?
+            .to_rgb8();
+        if width == 0 || height == 0 || x + width > image.width() || y + height > image.height() {
+            return Err(format!(
+                "Region [{},{},{},{}] is outside the {}x{} screenshot",
+                x,
+                y,
+                width,
+                height,
+                image.width(),
+                image.height()
+            ));
         }
+        let pixels = image::imageops::crop_imm(&image, x, y, width, height)
+            .to_image()
+            .into_raw();
+        Ok(Self::new(label, x, y, width, height, pixels))
+    }
+
+    /// File name the patch loaders recognise, `patch-label-[x,y,w,h].png`
+    pub fn file_name(&self) -> String {
+        format!("{}.png", self.display_name())
+    }
+
+    /// Write the patch as a PNG into `dir`, returning its path
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<PathBuf, String> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let image = image::RgbImage::from_raw(self.width, self.height, self.pixels.clone())
+            .ok_or_else(|| format!("Patch pixels do not fill {}x{}", self.width, self.height))?;
+        let path = dir.join(self.file_name());
+        image
+            .save(&path)
+            .map_err(|e| format!("Failed to save {}: {}", path.display(), e))?;
+        Ok(path)
     }
 
     /// Get the patch name for display
@@ -62,6 +167,95 @@ impl PatchInfo {
     }
 }
 
+/// Load every `patch-label-[x,y,w,h].png` in `dir`; `search_radius` gives the
+/// per-label radius from the config. Unreadable files are skipped.
+pub fn load_patches(
+    dir: &Path,
+    search_radius: impl Fn(&str) -> Option<u32>,
+) -> Result<Vec<PatchInfo>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    let mut patches = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let Some(filename) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some((label, x, y, width, height)) = parse_patch_filename(filename) else {
+            continue;
+        };
+        let Ok(img) = image::open(&path) else {
+            continue;
+        };
+        // Transparent pixels of the patch are not compared
+        let mask = alpha_mask(&img).map(|m| m.into_raw());
+        let pixels = img.to_rgb8().into_raw();
+        let radius = label.as_deref().and_then(&search_radius);
+        patches.push(
+            PatchInfo::new(label, x, y, width, height, pixels)
+                .with_mask(mask)
+                .with_search_radius(radius),
+        );
+    }
+    Ok(patches)
+}
+
+/// Label and `[x,y,w,h]` of a `patch-label-[x,y,w,h].png` file name
+pub fn parse_patch_filename(filename: &str) -> Option<(Option<String>, u32, u32, u32, u32)> {
+    let name = filename.strip_suffix(".png")?;
+
+    if !name.starts_with("patch-") {
+        return None;
+    }
+    let name = &name[6..];
+
+    let bracket_pos = name.rfind('[')?;
+    let label_part = &name[..bracket_pos];
+    let coords_part = &name[bracket_pos..];
+
+    if !coords_part.starts_with('[') || !coords_part.ends_with(']') {
+        return None;
+    }
+
+    let coords_str = &coords_part[1..coords_part.len() - 1];
+    let parts: Vec<&str> = coords_str.split(',').collect();
+    if parts.len() != 4 {
+        return None;
+    }
+
+    let x = parts[0].trim().parse::<u32>().ok()?;
+    let y = parts[1].trim().parse::<u32>().ok()?;
+    let width = parts[2].trim().parse::<u32>().ok()?;
+    let height = parts[3].trim().parse::<u32>().ok()?;
+
+    let label = if label_part.is_empty() {
+        None
+    } else {
+        let label_str = if let Some(stripped) = label_part.strip_suffix('-') {
+            stripped
+        } else {
+            label_part
+        };
+        Some(label_str.to_string())
+    };
+
+    Some((label, x, y, width, height))
+}
+
+/// Turn a user-typed name into a patch label: spaces become `_`, and anything
+/// but letters, digits, `_` and `-` is dropped (brackets would break the file name)
+pub fn sanitize_label(name: &str) -> Option<String> {
+    let label: String = name
+        .trim()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c == '_' || c == '-' => Some(c),
+            c if c.is_whitespace() => Some('_'),
+            _ => None,
+        })
+        .collect();
+    (!label.is_empty()).then_some(label)
+}
+
 impl Match {
     /// Format match as string with correlation percentage
     pub fn to_string(&self, patch: &PatchInfo) -> String {
@@ -73,3 +267,33 @@ impl Match {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_patch_from_screenshot_and_save() {
+        let screenshot = image::RgbImage::from_fn(20, 10, |x, y| image::Rgb([x as u8, y as u8, 7]));
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(screenshot)
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let label = sanitize_label(" Claim button! ");
+        assert_eq!(label.as_deref(), Some("Claim_button"));
+        let patch = PatchInfo::from_screenshot(&png, label, 4, 2, 3, 5).unwrap();
+        assert_eq!(patch.pixels.len(), 3 * 5 * 3);
+        assert_eq!(&patch.pixels[..3], &[4, 2, 7]);
+        assert_eq!(patch.file_name(), "patch-Claim_button-[4,2,3,5].png");
+        assert!(PatchInfo::from_screenshot(&png, None, 18, 0, 3, 5).is_err());
+
+        let dir = std::env::temp_dir().join(format!("patch-save-{}", std::process::id()));
+        let path = patch.save(&dir).unwrap();
+        let saved = image::open(&path).unwrap().to_rgb8();
+        assert_eq!(saved.dimensions(), (3, 5));
+        assert_eq!(saved.into_raw(), patch.pixels);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}